@@ -0,0 +1,52 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum AclError {
+    /// The request could not be authenticated, e.g. missing access key or bad signature.
+    #[error("AuthenticationFailed: {0}")]
+    AuthenticationFailed(String),
+
+    /// The request is authenticated but the account is not allowed to access the resource.
+    #[error("PermissionDenied: {0}")]
+    PermissionDenied(String),
+
+    #[error("InvalidAclConfig: {0}")]
+    InvalidAclConfig(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acl_error_displays_correctly() {
+        assert_eq!(
+            AclError::AuthenticationFailed("bad signature".to_string()).to_string(),
+            "AuthenticationFailed: bad signature"
+        );
+        assert_eq!(
+            AclError::PermissionDenied("no perm".to_string()).to_string(),
+            "PermissionDenied: no perm"
+        );
+        assert_eq!(
+            AclError::InvalidAclConfig("bad perm".to_string()).to_string(),
+            "InvalidAclConfig: bad perm"
+        );
+    }
+}
@@ -0,0 +1,116 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::net::SocketAddr;
+
+use cheetah_string::CheetahString;
+use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use rocketmq_remoting::runtime::RPCHook;
+
+use crate::common::acl_utils;
+use crate::common::session_credentials::SessionCredentials;
+use crate::common::session_credentials::ACCESS_KEY;
+use crate::common::session_credentials::SECURITY_TOKEN;
+use crate::common::session_credentials::SIGNATURE;
+
+/// Signs every outgoing request with the session credentials, the broker validates the signature
+/// with the secret key of the access key.
+pub struct AclClientRPCHook {
+    session_credentials: SessionCredentials,
+}
+
+impl AclClientRPCHook {
+    pub fn new(session_credentials: SessionCredentials) -> Self {
+        Self {
+            session_credentials,
+        }
+    }
+
+    pub fn session_credentials(&self) -> &SessionCredentials {
+        &self.session_credentials
+    }
+
+    /// Adds the access key, security token and signature to the ext fields of `request`.
+    pub fn sign(&self, request: &mut RemotingCommand) {
+        // the custom header is signed as ext fields, the same way the server receives it
+        request.make_custom_header_to_net();
+        request.add_ext_field(ACCESS_KEY, self.session_credentials.access_key.clone());
+        if let Some(security_token) = self.session_credentials.security_token.as_ref() {
+            request.add_ext_field(SECURITY_TOKEN, security_token.clone());
+        }
+        let fields = request
+            .get_ext_fields()
+            .map(|ext_fields| {
+                ext_fields
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let content = acl_utils::combine_request_content(request, &fields);
+        let signature = acl_utils::calculate_signature(
+            content.as_slice(),
+            self.session_credentials.secret_key.as_str(),
+        );
+        request.add_ext_field(SIGNATURE, CheetahString::from_string(signature));
+    }
+}
+
+impl RPCHook for AclClientRPCHook {
+    fn do_before_request(
+        &self,
+        _remote_addr: SocketAddr,
+        request: &mut RemotingCommand,
+    ) -> rocketmq_remoting::Result<()> {
+        self.sign(request);
+        Ok(())
+    }
+
+    fn do_after_response(
+        &self,
+        _remote_addr: SocketAddr,
+        _response: &mut RemotingCommand,
+    ) -> rocketmq_remoting::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn sign_adds_access_key_and_signature() {
+        let hook = AclClientRPCHook::new(SessionCredentials::new("rocketmq", "12345678"));
+        let mut request = RemotingCommand::create_remoting_command(10).set_body("body");
+        request.add_ext_field("topic", "TopicTest");
+        hook.sign(&mut request);
+
+        let ext_fields = request.get_ext_fields().unwrap();
+        assert_eq!(ext_fields.get(ACCESS_KEY).unwrap(), "rocketmq");
+        let fields = ext_fields
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<BTreeMap<_, _>>();
+        let expected = acl_utils::calculate_signature(
+            acl_utils::combine_request_content(&request, &fields).as_slice(),
+            "12345678",
+        );
+        assert_eq!(ext_fields.get(SIGNATURE).unwrap(), expected.as_str());
+    }
+}
@@ -0,0 +1,102 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::BTreeMap;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use cheetah_string::CheetahString;
+use hmac::Hmac;
+use hmac::Mac;
+use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use sha1::Sha1;
+
+use crate::common::session_credentials::SIGNATURE;
+
+type HmacSha1 = Hmac<Sha1>;
+
+/// Calculates the base64 encoded HMAC-SHA1 signature of `data` with `secret_key`.
+pub fn calculate_signature(data: &[u8], secret_key: &str) -> String {
+    let mut mac =
+        HmacSha1::new_from_slice(secret_key.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(data);
+    STANDARD.encode(mac.finalize().into_bytes())
+}
+
+/// Combines the content to sign: the values of `fields` sorted by key, except the signature
+/// itself, followed by the request body.
+pub fn combine_request_content(
+    request: &RemotingCommand,
+    fields: &BTreeMap<CheetahString, CheetahString>,
+) -> Vec<u8> {
+    let mut content = Vec::with_capacity(256);
+    for (key, value) in fields {
+        if key.as_str() != SIGNATURE {
+            content.extend_from_slice(value.as_bytes());
+        }
+    }
+    if let Some(body) = request.get_body() {
+        content.extend_from_slice(body);
+    }
+    content
+}
+
+/// Returns whether two signatures are equal, in time independent of where they differ.
+pub fn signature_equals(expected: &str, actual: &str) -> bool {
+    let expected = expected.as_bytes();
+    let actual = actual.as_bytes();
+    if expected.len() != actual.len() {
+        return false;
+    }
+    expected
+        .iter()
+        .zip(actual)
+        .fold(0u8, |diff, (left, right)| diff | (left ^ right))
+        == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calculate_signature_matches_hmac_sha1() {
+        assert_eq!(
+            calculate_signature(b"rocketmqbody", "12345678"),
+            "V5yRwYNJ9U+rLIld6qBigPxLfmE="
+        );
+    }
+
+    #[test]
+    fn combine_request_content_skips_signature() {
+        let request = RemotingCommand::create_remoting_command(10).set_body("body");
+        let mut fields = BTreeMap::new();
+        fields.insert(CheetahString::from("b"), CheetahString::from("mq"));
+        fields.insert(CheetahString::from("a"), CheetahString::from("rocket"));
+        fields.insert(CheetahString::from(SIGNATURE), CheetahString::from("sig"));
+        assert_eq!(
+            combine_request_content(&request, &fields),
+            b"rocketmqbody".to_vec()
+        );
+    }
+
+    #[test]
+    fn signature_equals_compares_content() {
+        assert!(signature_equals("abc", "abc"));
+        assert!(!signature_equals("abc", "abd"));
+        assert!(!signature_equals("abc", "ab"));
+    }
+}
@@ -0,0 +1,128 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+
+use cheetah_string::CheetahString;
+use rocketmq_remoting::code::request_code::RequestCode;
+
+use crate::acl_error::AclError;
+
+pub const DENY: u8 = 1;
+pub const ANY: u8 = 1 << 1;
+pub const PUB: u8 = 1 << 2;
+pub const SUB: u8 = 1 << 3;
+
+/// Parses a permission string such as `PUB`, `SUB`, `PUB|SUB`, `ANY` or `DENY`. Unknown values
+/// are treated as `DENY`.
+pub fn parse_perm_from_string(perm: &str) -> u8 {
+    match perm.trim().to_uppercase().as_str() {
+        "PUB" => PUB,
+        "SUB" => SUB,
+        "PUB|SUB" | "SUB|PUB" => PUB | SUB,
+        "ANY" => ANY,
+        _ => DENY,
+    }
+}
+
+/// Returns whether the `owned_perm` grants the `needed_perm`.
+pub fn check_permission(needed_perm: u8, owned_perm: u8) -> bool {
+    if owned_perm & DENY > 0 {
+        return false;
+    }
+    if needed_perm & ANY > 0 {
+        return owned_perm & PUB > 0 || owned_perm & SUB > 0;
+    }
+    needed_perm & owned_perm > 0
+}
+
+/// Returns whether requests with `code` may only be issued by admin accounts.
+pub fn need_admin_perm(code: i32) -> bool {
+    matches!(
+        RequestCode::from(code),
+        RequestCode::UpdateAndCreateTopic
+            | RequestCode::UpdateBrokerConfig
+            | RequestCode::DeleteTopicInBroker
+            | RequestCode::UpdateAndCreateSubscriptionGroup
+            | RequestCode::DeleteSubscriptionGroup
+            | RequestCode::UpdateAndCreateAclConfig
+            | RequestCode::DeleteAclConfig
+            | RequestCode::GetBrokerClusterAclInfo
+            | RequestCode::UpdateGlobalWhiteAddrsConfig
+    )
+}
+
+/// Parses resource permissions in the `resource=PERM` form.
+pub fn parse_resource_perms(
+    resources: &[CheetahString],
+) -> crate::Result<HashMap<CheetahString, u8>> {
+    let mut perms = HashMap::with_capacity(resources.len());
+    for resource in resources {
+        let (name, perm) = resource.split_once('=').ok_or_else(|| {
+            AclError::InvalidAclConfig(format!("Parse resource permission failed: {resource}"))
+        })?;
+        perms.insert(
+            CheetahString::from_slice(name.trim()),
+            parse_perm_from_string(perm),
+        );
+    }
+    Ok(perms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_perm_from_string_parses_all_forms() {
+        assert_eq!(parse_perm_from_string("PUB"), PUB);
+        assert_eq!(parse_perm_from_string("sub"), SUB);
+        assert_eq!(parse_perm_from_string("PUB|SUB"), PUB | SUB);
+        assert_eq!(parse_perm_from_string("SUB|PUB"), PUB | SUB);
+        assert_eq!(parse_perm_from_string("ANY"), ANY);
+        assert_eq!(parse_perm_from_string("DENY"), DENY);
+        assert_eq!(parse_perm_from_string("unknown"), DENY);
+    }
+
+    #[test]
+    fn check_permission_respects_deny_and_any() {
+        assert!(check_permission(PUB, PUB));
+        assert!(check_permission(PUB, PUB | SUB));
+        assert!(!check_permission(SUB, PUB));
+        assert!(!check_permission(PUB, DENY));
+        assert!(check_permission(ANY, SUB));
+        assert!(!check_permission(ANY, ANY));
+    }
+
+    #[test]
+    fn need_admin_perm_for_admin_codes() {
+        assert!(need_admin_perm(RequestCode::UpdateAndCreateTopic.to_i32()));
+        assert!(need_admin_perm(RequestCode::DeleteAclConfig.to_i32()));
+        assert!(!need_admin_perm(RequestCode::SendMessage.to_i32()));
+    }
+
+    #[test]
+    fn parse_resource_perms_rejects_malformed() {
+        let perms = parse_resource_perms(&[
+            CheetahString::from("topicA=PUB"),
+            CheetahString::from("topicB=PUB|SUB"),
+        ])
+        .unwrap();
+        assert_eq!(perms.get("topicA"), Some(&PUB));
+        assert_eq!(perms.get("topicB"), Some(&(PUB | SUB)));
+        assert!(parse_resource_perms(&[CheetahString::from("topicA")]).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::mix_all;
+use rocketmq_common::utils::serde_json_utils::SerdeJsonUtils;
+use rocketmq_remoting::code::request_code::RequestCode;
+use rocketmq_remoting::protocol::heartbeat::heartbeat_data::HeartbeatData;
+use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+
+use crate::common::acl_utils;
+use crate::common::permission;
+use crate::common::session_credentials::ACCESS_KEY;
+use crate::common::session_credentials::SECURITY_TOKEN;
+use crate::common::session_credentials::SIGNATURE;
+
+const TOPIC: &str = "topic";
+const TOPIC_V2: &str = "b";
+const GROUP: &str = "group";
+const CONSUMER_GROUP: &str = "consumerGroup";
+
+/// The access information of a single request, the topics and groups it touches are collected
+/// with the permission they need. Groups are keyed by their retry topic.
+#[derive(Debug, Clone, Default)]
+pub struct PlainAccessResource {
+    pub access_key: Option<CheetahString>,
+    pub signature: Option<CheetahString>,
+    pub security_token: Option<CheetahString>,
+    pub remote_addr: String,
+    pub request_code: i32,
+    pub content: Vec<u8>,
+    pub resource_perm_map: HashMap<CheetahString, u8>,
+}
+
+impl PlainAccessResource {
+    /// Parses the access resource of `request` received from `remote_addr`.
+    pub fn parse(request: &RemotingCommand, remote_addr: &str) -> Self {
+        let ext_fields = request.get_ext_fields();
+        let field = |key: &str| ext_fields.and_then(|fields| fields.get(key)).cloned();
+        let mut resource = PlainAccessResource {
+            access_key: field(ACCESS_KEY),
+            signature: field(SIGNATURE),
+            security_token: field(SECURITY_TOKEN),
+            remote_addr: remote_addr.to_string(),
+            request_code: request.code(),
+            ..Default::default()
+        };
+
+        match RequestCode::from(request.code()) {
+            RequestCode::SendMessage => {
+                if let Some(topic) = field(TOPIC) {
+                    resource.add_topic_perm(topic, permission::PUB);
+                }
+            }
+            RequestCode::SendMessageV2 | RequestCode::SendBatchMessage => {
+                if let Some(topic) = field(TOPIC_V2) {
+                    resource.add_topic_perm(topic, permission::PUB);
+                }
+            }
+            RequestCode::ConsumerSendMsgBack => {
+                if let Some(group) = field(GROUP) {
+                    resource.add_group_perm(group.as_str(), permission::SUB);
+                }
+            }
+            RequestCode::PullMessage
+            | RequestCode::LitePullMessage
+            | RequestCode::UpdateConsumerOffset
+            | RequestCode::QueryConsumerOffset
+            | RequestCode::PopMessage
+            | RequestCode::AckMessage
+            | RequestCode::ChangeMessageInvisibleTime => {
+                if let Some(topic) = field(TOPIC) {
+                    resource.add_topic_perm(topic, permission::SUB);
+                }
+                if let Some(group) = field(CONSUMER_GROUP) {
+                    resource.add_group_perm(group.as_str(), permission::SUB);
+                }
+            }
+            RequestCode::QueryMessage => {
+                if let Some(topic) = field(TOPIC) {
+                    resource.add_topic_perm(topic, permission::SUB);
+                }
+            }
+            RequestCode::HeartBeat => {
+                let heartbeat_data = request
+                    .get_body()
+                    .and_then(|body| SerdeJsonUtils::decode::<HeartbeatData>(body).ok());
+                if let Some(heartbeat_data) = heartbeat_data {
+                    for consumer_data in &heartbeat_data.consumer_data_set {
+                        resource.add_group_perm(consumer_data.group_name.as_str(), permission::SUB);
+                        for subscription_data in &consumer_data.subscription_data_set {
+                            resource
+                                .add_topic_perm(subscription_data.topic.clone(), permission::SUB);
+                        }
+                    }
+                }
+            }
+            RequestCode::UnregisterClient | RequestCode::GetConsumerListByGroup => {
+                if let Some(group) = field(CONSUMER_GROUP) {
+                    resource.add_group_perm(group.as_str(), permission::SUB);
+                }
+            }
+            _ => {}
+        }
+
+        let fields = ext_fields
+            .map(|fields| {
+                fields
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect::<BTreeMap<_, _>>()
+            })
+            .unwrap_or_default();
+        resource.content = acl_utils::combine_request_content(request, &fields);
+        resource
+    }
+
+    #[inline]
+    fn add_topic_perm(&mut self, topic: CheetahString, perm: u8) {
+        // sending back to the retry topic is consuming from the group
+        if topic.starts_with(mix_all::RETRY_GROUP_TOPIC_PREFIX) {
+            self.resource_perm_map.insert(topic, permission::SUB);
+        } else {
+            self.resource_perm_map.insert(topic, perm);
+        }
+    }
+
+    #[inline]
+    fn add_group_perm(&mut self, group: &str, perm: u8) {
+        self.resource_perm_map.insert(
+            CheetahString::from_string(mix_all::get_retry_topic(group)),
+            perm,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_pull_message_collects_topic_and_group() {
+        let mut request =
+            RemotingCommand::create_remoting_command(RequestCode::PullMessage.to_i32());
+        request.add_ext_field(TOPIC, "TopicTest");
+        request.add_ext_field(CONSUMER_GROUP, "GroupTest");
+        request.add_ext_field(ACCESS_KEY, "rocketmq");
+        request.add_ext_field(SIGNATURE, "sig");
+
+        let resource = PlainAccessResource::parse(&request, "10.0.0.1");
+        assert_eq!(resource.access_key.as_deref(), Some("rocketmq"));
+        assert_eq!(resource.signature.as_deref(), Some("sig"));
+        assert_eq!(
+            resource.resource_perm_map.get("TopicTest"),
+            Some(&permission::SUB)
+        );
+        assert_eq!(
+            resource.resource_perm_map.get("%RETRY%GroupTest"),
+            Some(&permission::SUB)
+        );
+    }
+
+    #[test]
+    fn parse_send_message_v2_uses_short_topic_key() {
+        let mut request =
+            RemotingCommand::create_remoting_command(RequestCode::SendMessageV2.to_i32());
+        request.add_ext_field(TOPIC_V2, "TopicTest");
+        let resource = PlainAccessResource::parse(&request, "10.0.0.1");
+        assert_eq!(
+            resource.resource_perm_map.get("TopicTest"),
+            Some(&permission::PUB)
+        );
+    }
+}
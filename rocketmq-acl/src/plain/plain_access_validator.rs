@@ -0,0 +1,66 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use rocketmq_remoting::code::response_code::ResponseCode;
+use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use rocketmq_remoting::remoting_error::RemotingError;
+use rocketmq_remoting::runtime::RPCHook;
+use tracing::warn;
+
+use crate::plain::plain_access_resource::PlainAccessResource;
+use crate::plain::plain_permission_manager::PlainPermissionManager;
+
+/// Server side hook rejecting the requests the plain ACL does not allow with
+/// [`ResponseCode::NoPermission`].
+pub struct PlainAccessValidator {
+    permission_manager: Arc<PlainPermissionManager>,
+}
+
+impl PlainAccessValidator {
+    pub fn new(permission_manager: Arc<PlainPermissionManager>) -> Self {
+        Self { permission_manager }
+    }
+}
+
+impl RPCHook for PlainAccessValidator {
+    fn do_before_request(
+        &self,
+        remote_addr: SocketAddr,
+        request: &mut RemotingCommand,
+    ) -> rocketmq_remoting::Result<()> {
+        let resource = PlainAccessResource::parse(request, remote_addr.ip().to_string().as_str());
+        self.permission_manager.validate(&resource).map_err(|e| {
+            warn!(
+                "Reject request code={} from {}: {}",
+                request.code(),
+                remote_addr,
+                e
+            );
+            RemotingError::AbortProcessError(ResponseCode::NoPermission as i32, e.to_string())
+        })
+    }
+
+    fn do_after_response(
+        &self,
+        _remote_addr: SocketAddr,
+        _response: &mut RemotingCommand,
+    ) -> rocketmq_remoting::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,457 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+
+use cheetah_string::CheetahString;
+use parking_lot::RwLock;
+use rocketmq_common::common::base::plain_access_config::PlainAccessConfig;
+use rocketmq_common::common::config_manager::ConfigManager;
+use rocketmq_common::common::mix_all;
+use rocketmq_remoting::protocol::DataVersion;
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::acl_error::AclError;
+use crate::common::acl_utils;
+use crate::common::permission;
+use crate::plain::plain_access_resource::PlainAccessResource;
+use crate::plain::remote_address_strategy::RemoteAddressStrategy;
+
+/// The minimum length of access keys and secret keys.
+const MIN_KEY_LENGTH: usize = 6;
+
+/// The content of the plain ACL file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlainAclConfig {
+    pub global_white_remote_addresses: Vec<CheetahString>,
+    pub accounts: Vec<PlainAccessConfig>,
+    pub data_version: DataVersion,
+}
+
+/// An account with its white list and permissions parsed.
+struct PlainAccount {
+    secret_key: CheetahString,
+    admin: bool,
+    white_remote_address: RemoteAddressStrategy,
+    default_topic_perm: u8,
+    default_group_perm: u8,
+    /// Topic permissions and group permissions keyed by the retry topic of the group.
+    resource_perms: HashMap<CheetahString, u8>,
+}
+
+impl PlainAccount {
+    fn parse(config: &PlainAccessConfig) -> crate::Result<Self> {
+        let access_key = config.access_key.clone().unwrap_or_default();
+        if access_key.len() < MIN_KEY_LENGTH {
+            return Err(AclError::InvalidAclConfig(format!(
+                "The accessKey={} cannot be null and length should longer than {}",
+                access_key, MIN_KEY_LENGTH
+            )));
+        }
+        let secret_key = config.secret_key.clone().unwrap_or_default();
+        if secret_key.len() < MIN_KEY_LENGTH {
+            return Err(AclError::InvalidAclConfig(format!(
+                "The secretKey of accessKey={} cannot be null and length should longer than {}",
+                access_key, MIN_KEY_LENGTH
+            )));
+        }
+        let mut resource_perms = permission::parse_resource_perms(&config.topic_perms)?;
+        for (group, perm) in permission::parse_resource_perms(&config.group_perms)? {
+            resource_perms.insert(
+                CheetahString::from_string(mix_all::get_retry_topic(group.as_str())),
+                perm,
+            );
+        }
+        Ok(PlainAccount {
+            secret_key,
+            admin: config.admin,
+            white_remote_address: RemoteAddressStrategy::parse(
+                config.white_remote_address.as_deref().unwrap_or_default(),
+            )?,
+            default_topic_perm: permission::parse_perm_from_string(
+                config.default_topic_perm.as_deref().unwrap_or_default(),
+            ),
+            default_group_perm: permission::parse_perm_from_string(
+                config.default_group_perm.as_deref().unwrap_or_default(),
+            ),
+            resource_perms,
+        })
+    }
+
+    fn owned_perm(&self, resource: &str) -> u8 {
+        match self.resource_perms.get(resource) {
+            Some(perm) => *perm,
+            None if resource.starts_with(mix_all::RETRY_GROUP_TOPIC_PREFIX) => {
+                self.default_group_perm
+            }
+            None => self.default_topic_perm,
+        }
+    }
+}
+
+#[derive(Default)]
+struct PlainAclState {
+    config: PlainAclConfig,
+    global_white_remote_addresses: Vec<RemoteAddressStrategy>,
+    accounts: HashMap<CheetahString, PlainAccount>,
+}
+
+impl PlainAclState {
+    fn new(config: PlainAclConfig) -> Self {
+        let global_white_remote_addresses = config
+            .global_white_remote_addresses
+            .iter()
+            .filter_map(
+                |address| match RemoteAddressStrategy::parse(address.as_str()) {
+                    Ok(strategy) => Some(strategy),
+                    Err(e) => {
+                        warn!("Ignore global white remote address {}: {}", address, e);
+                        None
+                    }
+                },
+            )
+            .collect();
+        let accounts = config
+            .accounts
+            .iter()
+            .filter_map(|account| match PlainAccount::parse(account) {
+                Ok(parsed) => Some((account.access_key.clone().unwrap_or_default(), parsed)),
+                Err(e) => {
+                    warn!("Ignore acl account {:?}: {}", account.access_key, e);
+                    None
+                }
+            })
+            .collect();
+        PlainAclState {
+            config,
+            global_white_remote_addresses,
+            accounts,
+        }
+    }
+}
+
+/// Manages the accounts of the plain ACL file and validates requests against them.
+pub struct PlainPermissionManager {
+    file_path: String,
+    state: RwLock<PlainAclState>,
+}
+
+impl PlainPermissionManager {
+    pub fn new(file_path: impl Into<String>) -> Self {
+        Self {
+            file_path: file_path.into(),
+            state: RwLock::new(PlainAclState::default()),
+        }
+    }
+
+    /// Validates the request described by `resource`, requests from globally white listed
+    /// addresses are always allowed.
+    pub fn validate(&self, resource: &PlainAccessResource) -> crate::Result<()> {
+        let state = self.state.read();
+        if state
+            .global_white_remote_addresses
+            .iter()
+            .any(|strategy| strategy.matches(resource.remote_addr.as_str()))
+        {
+            return Ok(());
+        }
+        let access_key = resource.access_key.as_ref().ok_or_else(|| {
+            AclError::AuthenticationFailed(format!(
+                "No accessKey is configured, remote address={}",
+                resource.remote_addr
+            ))
+        })?;
+        let account = state.accounts.get(access_key).ok_or_else(|| {
+            AclError::AuthenticationFailed(format!("No acl config for {access_key}"))
+        })?;
+        if account
+            .white_remote_address
+            .matches(resource.remote_addr.as_str())
+        {
+            return Ok(());
+        }
+
+        let signature = acl_utils::calculate_signature(
+            resource.content.as_slice(),
+            account.secret_key.as_str(),
+        );
+        if !resource
+            .signature
+            .as_ref()
+            .is_some_and(|actual| acl_utils::signature_equals(signature.as_str(), actual.as_str()))
+        {
+            return Err(AclError::AuthenticationFailed(format!(
+                "Check signature failed for accessKey={access_key}"
+            )));
+        }
+
+        if permission::need_admin_perm(resource.request_code) && !account.admin {
+            return Err(AclError::PermissionDenied(format!(
+                "Need admin permission for request code={}, but accessKey={} is not admin",
+                resource.request_code, access_key
+            )));
+        }
+        if account.admin {
+            return Ok(());
+        }
+        for (name, needed_perm) in &resource.resource_perm_map {
+            if !permission::check_permission(*needed_perm, account.owned_perm(name.as_str())) {
+                return Err(AclError::PermissionDenied(format!(
+                    "No permission for resource {name} with accessKey={access_key}"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates or replaces the account with the access key of `access_config` and persists the
+    /// ACL file.
+    pub fn update_access_config(&self, access_config: PlainAccessConfig) -> crate::Result<()> {
+        PlainAccount::parse(&access_config)?;
+        let mut config = self.state.read().config.clone();
+        match config
+            .accounts
+            .iter_mut()
+            .find(|account| account.access_key == access_config.access_key)
+        {
+            Some(account) => *account = access_config,
+            None => config.accounts.push(access_config),
+        }
+        self.update_config(config);
+        Ok(())
+    }
+
+    /// Deletes the account with `access_key`, returns whether it existed.
+    pub fn delete_access_config(&self, access_key: &str) -> bool {
+        let mut config = self.state.read().config.clone();
+        let accounts = config.accounts.len();
+        config
+            .accounts
+            .retain(|account| account.access_key.as_deref() != Some(access_key));
+        if accounts == config.accounts.len() {
+            return false;
+        }
+        self.update_config(config);
+        true
+    }
+
+    /// Replaces the global white remote addresses and persists the ACL file.
+    pub fn update_global_white_addrs(
+        &self,
+        global_white_addrs: Vec<CheetahString>,
+    ) -> crate::Result<()> {
+        for address in &global_white_addrs {
+            RemoteAddressStrategy::parse(address.as_str())?;
+        }
+        let mut config = self.state.read().config.clone();
+        config.global_white_remote_addresses = global_white_addrs;
+        self.update_config(config);
+        Ok(())
+    }
+
+    pub fn file_path(&self) -> &str {
+        self.file_path.as_str()
+    }
+
+    pub fn data_version(&self) -> DataVersion {
+        self.state.read().config.data_version.clone()
+    }
+
+    pub fn acl_config(&self) -> PlainAclConfig {
+        self.state.read().config.clone()
+    }
+
+    fn update_config(&self, mut config: PlainAclConfig) {
+        // the cloned version shares its counter, give the new one its own
+        let mut data_version = DataVersion::new();
+        data_version.assign_new_one(&config.data_version);
+        data_version.next_version();
+        config.data_version = data_version;
+        *self.state.write() = PlainAclState::new(config);
+        self.persist();
+    }
+}
+
+impl ConfigManager for PlainPermissionManager {
+    fn config_file_path(&self) -> String {
+        self.file_path.clone()
+    }
+
+    fn encode_pretty(&self, pretty_format: bool) -> String {
+        let state = self.state.read();
+        if pretty_format {
+            serde_json::to_string_pretty(&state.config)
+        } else {
+            serde_json::to_string(&state.config)
+        }
+        .unwrap_or_default()
+    }
+
+    fn decode(&self, json_string: &str) {
+        if json_string.is_empty() {
+            return;
+        }
+        match serde_json::from_str::<PlainAclConfig>(json_string) {
+            Ok(config) => *self.state.write() = PlainAclState::new(config),
+            Err(e) => warn!("Decode plain acl config failed: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocketmq_remoting::code::request_code::RequestCode;
+    use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+    use rocketmq_remoting::runtime::RPCHook;
+
+    use super::*;
+    use crate::common::acl_client_rpc_hook::AclClientRPCHook;
+    use crate::common::session_credentials::SessionCredentials;
+
+    fn account(admin: bool) -> PlainAccessConfig {
+        PlainAccessConfig {
+            access_key: Some("rocketmq".into()),
+            secret_key: Some("12345678".into()),
+            admin,
+            default_topic_perm: Some("DENY".into()),
+            default_group_perm: Some("SUB".into()),
+            topic_perms: vec!["TopicA=PUB".into(), "TopicB=SUB".into()],
+            ..Default::default()
+        }
+    }
+
+    fn signed_resource(code: RequestCode, topic: &str, secret_key: &str) -> PlainAccessResource {
+        let hook = AclClientRPCHook::new(SessionCredentials::new("rocketmq", secret_key));
+        let mut request = RemotingCommand::create_remoting_command(code.to_i32());
+        request.add_ext_field("topic", topic);
+        hook.do_before_request("10.0.0.1:1234".parse().unwrap(), &mut request)
+            .unwrap();
+        PlainAccessResource::parse(&request, "10.0.0.1")
+    }
+
+    #[test]
+    fn validate_checks_signature_and_permissions() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = PlainPermissionManager::new(
+            dir.path()
+                .join("plain_acl.json")
+                .to_string_lossy()
+                .to_string(),
+        );
+        manager.update_access_config(account(false)).unwrap();
+
+        assert!(manager
+            .validate(&signed_resource(
+                RequestCode::SendMessage,
+                "TopicA",
+                "12345678"
+            ))
+            .is_ok());
+        assert!(matches!(
+            manager.validate(&signed_resource(
+                RequestCode::SendMessage,
+                "TopicB",
+                "12345678"
+            )),
+            Err(AclError::PermissionDenied(_))
+        ));
+        assert!(matches!(
+            manager.validate(&signed_resource(
+                RequestCode::SendMessage,
+                "TopicC",
+                "12345678"
+            )),
+            Err(AclError::PermissionDenied(_))
+        ));
+        assert!(matches!(
+            manager.validate(&signed_resource(
+                RequestCode::SendMessage,
+                "TopicA",
+                "87654321"
+            )),
+            Err(AclError::AuthenticationFailed(_))
+        ));
+        assert!(matches!(
+            manager.validate(&signed_resource(
+                RequestCode::UpdateAndCreateTopic,
+                "TopicA",
+                "12345678"
+            )),
+            Err(AclError::PermissionDenied(_))
+        ));
+
+        manager.update_access_config(account(true)).unwrap();
+        assert!(manager
+            .validate(&signed_resource(
+                RequestCode::UpdateAndCreateTopic,
+                "TopicC",
+                "12345678"
+            ))
+            .is_ok());
+    }
+
+    #[test]
+    fn global_white_address_skips_authentication() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = PlainPermissionManager::new(
+            dir.path()
+                .join("plain_acl.json")
+                .to_string_lossy()
+                .to_string(),
+        );
+        let resource = PlainAccessResource {
+            remote_addr: "10.0.0.1".to_string(),
+            ..Default::default()
+        };
+        assert!(manager.validate(&resource).is_err());
+        manager
+            .update_global_white_addrs(vec!["10.0.0.*".into()])
+            .unwrap();
+        assert!(manager.validate(&resource).is_ok());
+    }
+
+    #[test]
+    fn updates_are_persisted_and_loaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir
+            .path()
+            .join("plain_acl.json")
+            .to_string_lossy()
+            .to_string();
+        let manager = PlainPermissionManager::new(file_path.clone());
+        manager.update_access_config(account(false)).unwrap();
+        assert!(manager
+            .update_access_config(PlainAccessConfig {
+                access_key: Some("short".into()),
+                ..account(false)
+            })
+            .is_err());
+        let counter = manager.data_version().get_counter();
+
+        let loaded = PlainPermissionManager::new(file_path);
+        assert!(loaded.load());
+        assert_eq!(loaded.acl_config().accounts, vec![account(false)]);
+        assert_eq!(loaded.data_version().get_counter(), counter);
+
+        assert!(loaded.delete_access_config("rocketmq"));
+        assert!(!loaded.delete_access_config("rocketmq"));
+        assert!(loaded.acl_config().accounts.is_empty());
+        assert_eq!(loaded.data_version().get_counter(), counter + 1);
+    }
+}
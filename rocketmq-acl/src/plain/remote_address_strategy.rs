@@ -0,0 +1,138 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashSet;
+
+use crate::acl_error::AclError;
+
+/// Matches remote addresses against a white list entry, supported forms are `*`, `*.*.*.*`,
+/// an exact address, a prefix such as `192.168.*`, a range such as `192.168.1.1-100` and a
+/// set such as `192.168.1.{1,2,3}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteAddressStrategy {
+    Null,
+    Blank,
+    One(String),
+    Multiple(HashSet<String>),
+    Range { head: String, start: u8, end: u8 },
+    Prefix(String),
+}
+
+impl RemoteAddressStrategy {
+    /// Parses a white list entry, an empty entry matches nothing.
+    pub fn parse(remote_addr: &str) -> crate::Result<Self> {
+        let remote_addr = remote_addr.trim();
+        if remote_addr.is_empty() {
+            return Ok(RemoteAddressStrategy::Blank);
+        }
+        if remote_addr == "*" || remote_addr == "*.*.*.*" {
+            return Ok(RemoteAddressStrategy::Null);
+        }
+        let invalid =
+            || AclError::InvalidAclConfig(format!("Invalid remote address: {remote_addr}"));
+        if let Some(head) = remote_addr.strip_suffix('}') {
+            let (head, values) = head.split_once('{').ok_or_else(invalid)?;
+            let mut addresses = HashSet::new();
+            for value in values.split(',') {
+                let value = value.trim().parse::<u8>().map_err(|_| invalid())?;
+                addresses.insert(format!("{head}{value}"));
+            }
+            return Ok(RemoteAddressStrategy::Multiple(addresses));
+        }
+        if let Some(prefix) = remote_addr.strip_suffix('*') {
+            // "192.168.*.*" matches the same addresses as "192.168.*"
+            let mut prefix = prefix;
+            while let Some(shorter) = prefix.strip_suffix("*.") {
+                prefix = shorter;
+            }
+            if prefix.contains('*') || !prefix.ends_with('.') {
+                return Err(invalid());
+            }
+            return Ok(RemoteAddressStrategy::Prefix(prefix.to_string()));
+        }
+        let (head, last) = match remote_addr.rfind('.') {
+            Some(index) => remote_addr.split_at(index + 1),
+            None => return Ok(RemoteAddressStrategy::One(remote_addr.to_string())),
+        };
+        if let Some((start, end)) = last.split_once('-') {
+            let start = start.trim().parse::<u8>().map_err(|_| invalid())?;
+            let end = end.trim().parse::<u8>().map_err(|_| invalid())?;
+            if start > end {
+                return Err(invalid());
+            }
+            return Ok(RemoteAddressStrategy::Range {
+                head: head.to_string(),
+                start,
+                end,
+            });
+        }
+        Ok(RemoteAddressStrategy::One(remote_addr.to_string()))
+    }
+
+    /// Returns whether `ip` is matched by this strategy.
+    pub fn matches(&self, ip: &str) -> bool {
+        match self {
+            RemoteAddressStrategy::Null => true,
+            RemoteAddressStrategy::Blank => false,
+            RemoteAddressStrategy::One(address) => address == ip,
+            RemoteAddressStrategy::Multiple(addresses) => addresses.contains(ip),
+            RemoteAddressStrategy::Range { head, start, end } => ip
+                .strip_prefix(head.as_str())
+                .and_then(|last| last.parse::<u8>().ok())
+                .is_some_and(|last| (*start..=*end).contains(&last)),
+            RemoteAddressStrategy::Prefix(prefix) => ip.starts_with(prefix.as_str()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_match_all_forms() {
+        let all = RemoteAddressStrategy::parse("*").unwrap();
+        assert!(all.matches("10.0.0.1"));
+        assert!(!RemoteAddressStrategy::parse("")
+            .unwrap()
+            .matches("10.0.0.1"));
+
+        let one = RemoteAddressStrategy::parse("10.0.0.1").unwrap();
+        assert!(one.matches("10.0.0.1"));
+        assert!(!one.matches("10.0.0.2"));
+
+        let multiple = RemoteAddressStrategy::parse("10.0.0.{1,2}").unwrap();
+        assert!(multiple.matches("10.0.0.2"));
+        assert!(!multiple.matches("10.0.0.3"));
+
+        let range = RemoteAddressStrategy::parse("10.0.0.10-20").unwrap();
+        assert!(range.matches("10.0.0.15"));
+        assert!(!range.matches("10.0.0.21"));
+        assert!(!range.matches("10.0.1.15"));
+
+        let prefix = RemoteAddressStrategy::parse("10.0.*.*").unwrap();
+        assert_eq!(prefix, RemoteAddressStrategy::Prefix("10.0.".to_string()));
+        assert!(prefix.matches("10.0.3.4"));
+        assert!(!prefix.matches("10.1.3.4"));
+    }
+
+    #[test]
+    fn parse_rejects_invalid_entries() {
+        assert!(RemoteAddressStrategy::parse("10.0.0.{1,x}").is_err());
+        assert!(RemoteAddressStrategy::parse("10.0.0.20-10").is_err());
+        assert!(RemoteAddressStrategy::parse("10.*.0.1*").is_err());
+    }
+}
@@ -21,6 +21,7 @@ use clap::Parser;
 use rocketmq_broker::command::Args;
 use rocketmq_broker::Builder;
 use rocketmq_common::common::broker::broker_config::BrokerConfig;
+use rocketmq_common::common::server::config::ServerConfig;
 use rocketmq_common::EnvUtils::EnvUtils;
 use rocketmq_common::ParseConfigFile;
 use rocketmq_rust::rocketmq;
@@ -32,10 +33,19 @@ async fn main() -> anyhow::Result<()> {
     // init logger
     rocketmq_common::log::init_logger();
     let (broker_config, message_store_config) = parse_config_file();
+    // the listen address comes from the broker config, the TLS and flow control settings from
+    // its server section
+    let default_server_config = ServerConfig::default();
+    let server_config = ServerConfig {
+        listen_port: default_server_config.listen_port,
+        bind_address: default_server_config.bind_address,
+        ..broker_config.broker_server_config.clone()
+    };
     // boot strap broker
     Builder::new()
         .set_broker_config(broker_config)
         .set_message_store_config(message_store_config)
+        .set_server_config(server_config)
         .build()
         .boot()
         .await;
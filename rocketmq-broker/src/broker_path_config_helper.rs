@@ -125,6 +125,15 @@ pub fn get_message_request_mode_path(root_dir: &str) -> String {
         .into_owned()
 }
 
+// Plain ACL path
+pub fn get_acl_path(root_dir: &str) -> String {
+    PathBuf::from(root_dir)
+        .join("config")
+        .join("plain_acl.json")
+        .to_string_lossy()
+        .into_owned()
+}
+
 #[cfg(test)]
 mod test {
     use std::path::PathBuf;
@@ -23,6 +23,10 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use cheetah_string::CheetahString;
+use rocketmq_acl::common::acl_client_rpc_hook::AclClientRPCHook;
+use rocketmq_acl::common::session_credentials::SessionCredentials;
+use rocketmq_acl::plain::plain_access_validator::PlainAccessValidator;
+use rocketmq_acl::plain::plain_permission_manager::PlainPermissionManager;
 use rocketmq_common::common::broker::broker_config::BrokerConfig;
 use rocketmq_common::common::config::TopicConfig;
 use rocketmq_common::common::config_manager::ConfigManager;
@@ -39,6 +43,7 @@ use rocketmq_remoting::protocol::static_topic::topic_queue_mapping_detail::Topic
 use rocketmq_remoting::protocol::DataVersion;
 use rocketmq_remoting::remoting_server::server::RocketMQServer;
 use rocketmq_remoting::runtime::config::client_config::TokioClientConfig;
+use rocketmq_remoting::runtime::RPCHook;
 use rocketmq_runtime::RocketMQRuntime;
 use rocketmq_rust::ArcMut;
 use rocketmq_store::base::store_enum::StoreType;
@@ -53,6 +58,7 @@ use tracing::warn;
 
 use crate::broker::broker_hook::BrokerShutdownHook;
 use crate::broker::broker_pre_online_service::BrokerPreOnlineService;
+use crate::broker_path_config_helper::get_acl_path;
 use crate::client::consumer_ids_change_listener::ConsumerIdsChangeListener;
 use crate::client::default_consumer_ids_change_listener::DefaultConsumerIdsChangeListener;
 use crate::client::manager::consumer_manager::ConsumerManager;
@@ -175,7 +181,24 @@ impl BrokerRuntime {
             .parse::<SocketAddr>()
             .expect("parse store_host failed");
         let runtime = RocketMQRuntime::new_multi(10, "broker-thread");
-        let broker_outer_api = BrokerOuterAPI::new(Arc::new(TokioClientConfig::default()));
+        let tls_config = &broker_config.broker_server_config.tls_config;
+        let tokio_client_config = Arc::new(TokioClientConfig {
+            use_tls: tls_config.client_enable,
+            tls_config: tls_config.clone(),
+            ..Default::default()
+        });
+        let broker_outer_api = match (
+            broker_config.acl_access_key.as_ref(),
+            broker_config.acl_secret_key.as_ref(),
+        ) {
+            (Some(access_key), Some(secret_key)) => {
+                let rpc_hook: Box<dyn RPCHook> = Box::new(AclClientRPCHook::new(
+                    SessionCredentials::new(access_key.clone(), secret_key.clone()),
+                ));
+                BrokerOuterAPI::new_with_hook(tokio_client_config, Some(Arc::new(rpc_hook)))
+            }
+            _ => BrokerOuterAPI::new(tokio_client_config),
+        };
 
         let topic_queue_mapping_manager =
             TopicQueueMappingManager::new(Arc::new(broker_config.clone()));
@@ -242,6 +265,7 @@ impl BrokerRuntime {
             pop_message_processor: None,
             ack_message_processor: None,
             notification_processor: None,
+            plain_permission_manager: None,
         });
         let mut stats_manager = BrokerStatsManager::new(Arc::new(inner.broker_config.clone()));
         stats_manager.set_producer_state_getter(Arc::new(ProducerStateGetter {
@@ -903,7 +927,23 @@ impl BrokerRuntime {
         self.inner.transaction_metrics_flush_service = Some(TransactionMetricsFlushService);
     }
 
-    fn initial_acl(&mut self) {}
+    fn initial_acl(&mut self) {
+        if !self.inner.broker_config.acl_enable {
+            info!("The broker does not enable acl");
+            return;
+        }
+        let acl_path = get_acl_path(self.inner.broker_config.store_path_root_dir.as_str());
+        let permission_manager = PlainPermissionManager::new(acl_path.as_str());
+        if !permission_manager.load() {
+            warn!(
+                "Load acl config file {} failed, all requests are rejected except from global \
+                 white remote addresses",
+                acl_path
+            );
+        }
+        info!("The broker enables acl, acl config file: {}", acl_path);
+        self.inner.plain_permission_manager = Some(Arc::new(permission_manager));
+    }
 
     fn initial_rpc_hooks(&mut self) {}
 
@@ -928,13 +968,23 @@ impl BrokerRuntime {
         let request_processor = self.init_processor();
         let fast_request_processor = request_processor.clone();
 
-        let server = RocketMQServer::new(Arc::new(self.inner.server_config.clone()));
+        let mut server = RocketMQServer::new(Arc::new(self.inner.server_config.clone()));
+        let mut fast_server_config = self.inner.server_config.clone();
+        fast_server_config.listen_port = self.inner.server_config.listen_port - 2;
+        // the Unix domain socket is served by the main remoting server only
+        fast_server_config.unix_socket_path = None;
+        let mut fast_server = RocketMQServer::new(Arc::new(fast_server_config));
+        if let Some(permission_manager) = self.inner.plain_permission_manager.as_ref() {
+            server.register_rpc_hook(Box::new(PlainAccessValidator::new(
+                permission_manager.clone(),
+            )));
+            fast_server.register_rpc_hook(Box::new(PlainAccessValidator::new(
+                permission_manager.clone(),
+            )));
+        }
         //start nomarl broker remoting_server
         tokio::spawn(async move { server.run(request_processor).await });
         //start fast broker remoting_server
-        let mut fast_server_config = self.inner.server_config.clone();
-        fast_server_config.listen_port = self.inner.server_config.listen_port - 2;
-        let fast_server = RocketMQServer::new(Arc::new(fast_server_config));
         tokio::spawn(async move { fast_server.run(fast_request_processor).await });
 
         if let Some(pop_message_processor) = self.inner.pop_message_processor.as_mut() {
@@ -1437,6 +1487,7 @@ pub(crate) struct BrokerRuntimeInner<MS> {
     pop_message_processor: Option<ArcMut<PopMessageProcessor<MS>>>,
     ack_message_processor: Option<ArcMut<AckMessageProcessor<MS>>>,
     notification_processor: Option<ArcMut<NotificationProcessor>>,
+    plain_permission_manager: Option<Arc<PlainPermissionManager>>,
 }
 
 impl<MS: MessageStore> BrokerRuntimeInner<MS> {
@@ -1626,6 +1677,11 @@ impl<MS: MessageStore> BrokerRuntimeInner<MS> {
         &self.broker_config
     }
 
+    #[inline]
+    pub fn plain_permission_manager(&self) -> Option<&Arc<PlainPermissionManager>> {
+        self.plain_permission_manager.as_ref()
+    }
+
     #[inline]
     pub fn message_store_config(&self) -> &MessageStoreConfig {
         &self.message_store_config
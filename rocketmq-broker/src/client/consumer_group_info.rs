@@ -200,7 +200,12 @@ impl ConsumerGroupInfo {
         let mut topic_set = HashSet::new();
 
         for sub in sub_list.iter() {
-            if let Some(old) = self.subscription_table.get(sub.topic.as_str()) {
+            // The entry guard must be released before inserting into the same map
+            let old = self
+                .subscription_table
+                .get(sub.topic.as_str())
+                .map(|old| old.value().clone());
+            if let Some(old) = old {
                 if sub.sub_version > old.sub_version {
                     if *self.consume_type.read() == ConsumeType::ConsumePassively {
                         info!(
@@ -236,7 +241,7 @@ impl ConsumerGroupInfo {
             }
         });
 
-        *self.last_update_timestamp.lock() = self.get_last_update_timestamp();
+        *self.last_update_timestamp.lock() = get_current_millis();
 
         updated
     }
@@ -407,4 +412,32 @@ mod tests {
 
         assert!(consumer_group_info.update_subscription(&sub_list));
     }
+
+    #[test]
+    fn consumer_group_info_update_subscription_with_newer_version() {
+        let consumer_group_info = ConsumerGroupInfo::with_group_name("test_group".to_string());
+
+        let mut sub_list = HashSet::new();
+        sub_list.insert(SubscriptionData {
+            topic: "topic".into(),
+            sub_string: "*".into(),
+            sub_version: 1,
+            ..Default::default()
+        });
+        assert!(consumer_group_info.update_subscription(&sub_list));
+
+        let mut sub_list = HashSet::new();
+        sub_list.insert(SubscriptionData {
+            topic: "topic".into(),
+            sub_string: "TagA".into(),
+            sub_version: 2,
+            ..Default::default()
+        });
+        assert!(!consumer_group_info.update_subscription(&sub_list));
+        let subscription_data = consumer_group_info
+            .find_subscription_data(&"topic".into())
+            .unwrap();
+        assert_eq!(subscription_data.sub_version, 2);
+        assert_eq!(subscription_data.sub_string.as_str(), "TagA");
+    }
 }
@@ -204,6 +204,50 @@ impl ConsumerOffsetManager {
         }
         topics
     }
+
+    /// Records `offset` as the reset point of a queue and overrides the committed offset, so the
+    /// reset also takes effect for consumers that are offline right now.
+    pub fn assign_reset_offset(
+        &self,
+        topic: &CheetahString,
+        group: &CheetahString,
+        queue_id: i32,
+        offset: i64,
+    ) {
+        let key =
+            CheetahString::from_string(format!("{}{}{}", topic, TOPIC_GROUP_SEPARATOR, group));
+        self.consumer_offset_wrapper
+            .reset_offset_table
+            .write()
+            .entry(key.clone())
+            .or_default()
+            .insert(queue_id, offset);
+        self.consumer_offset_wrapper
+            .offset_table
+            .write()
+            .entry(key)
+            .or_default()
+            .insert(queue_id, offset);
+    }
+
+    pub fn clone_offset(
+        &self,
+        src_group: &CheetahString,
+        dest_group: &CheetahString,
+        topic: &CheetahString,
+    ) {
+        let mut offset_table = self.consumer_offset_wrapper.offset_table.write();
+        let src_key = format!("{}{}{}", topic, TOPIC_GROUP_SEPARATOR, src_group);
+        if let Some(offsets) = offset_table.get(src_key.as_str()).cloned() {
+            offset_table.insert(
+                CheetahString::from_string(format!(
+                    "{}{}{}",
+                    topic, TOPIC_GROUP_SEPARATOR, dest_group
+                )),
+                offsets,
+            );
+        }
+    }
 }
 
 impl ConfigManager for ConsumerOffsetManager {
@@ -435,3 +479,48 @@ impl<'de> Deserialize<'de> for ConsumerOffsetWrapper {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_manager() -> ConsumerOffsetManager {
+        ConsumerOffsetManager::new(Arc::new(BrokerConfig::default()), None)
+    }
+
+    #[test]
+    fn assign_reset_offset_overrides_committed_offset() {
+        let manager = new_manager();
+        let topic = CheetahString::from_static_str("test_topic");
+        let group = CheetahString::from_static_str("test_group");
+        manager.commit_offset("127.0.0.1".into(), &group, &topic, 0, 100);
+
+        manager.assign_reset_offset(&topic, &group, 0, 10);
+
+        assert_eq!(manager.query_offset(&group, &topic, 0), 10);
+        assert!(manager.has_offset_reset(&group, &topic, 0));
+        assert_eq!(
+            manager.query_then_erase_reset_offset(&topic, &group, 0),
+            Some(10)
+        );
+        assert!(!manager.has_offset_reset(&group, &topic, 0));
+    }
+
+    #[test]
+    fn clone_offset_copies_all_queues() {
+        let manager = new_manager();
+        let topic = CheetahString::from_static_str("test_topic");
+        let src_group = CheetahString::from_static_str("src_group");
+        let dest_group = CheetahString::from_static_str("dest_group");
+        manager.commit_offset("127.0.0.1".into(), &src_group, &topic, 0, 5);
+        manager.commit_offset("127.0.0.1".into(), &src_group, &topic, 1, 7);
+
+        manager.clone_offset(&src_group, &dest_group, &topic);
+
+        assert_eq!(manager.query_offset(&dest_group, &topic, 0), 5);
+        assert_eq!(manager.query_offset(&dest_group, &topic, 1), 7);
+        assert!(manager
+            .which_topic_by_consumer(&dest_group)
+            .contains(&topic));
+    }
+}
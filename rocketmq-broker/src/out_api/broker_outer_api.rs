@@ -202,7 +202,7 @@ impl BrokerOuterAPI {
                 cluster_name,
                 ha_server_addr,
                 enable_acting_master: Some(enable_acting_master),
-                compressed: false,
+                compressed,
                 heartbeat_timeout_millis,
                 body_crc32: 0,
             };
@@ -151,7 +151,9 @@ where
                     .await
             }
 
-            RequestCode::QueryMessage | RequestCode::ViewMessageById => {
+            RequestCode::QueryMessage
+            | RequestCode::ViewMessageById
+            | RequestCode::QueryMessageByCondition => {
                 self.query_message_processor
                     .process_request(channel, ctx, request_code, request)
                     .await
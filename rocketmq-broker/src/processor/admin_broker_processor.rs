@@ -25,12 +25,14 @@ use rocketmq_store::log_file::MessageStore;
 use tracing::warn;
 
 use crate::broker_runtime::BrokerRuntimeInner;
+use crate::processor::admin_broker_processor::acl_request_handler::AclRequestHandler;
 use crate::processor::admin_broker_processor::batch_mq_handler::BatchMqHandler;
 use crate::processor::admin_broker_processor::broker_config_request_handler::BrokerConfigRequestHandler;
 use crate::processor::admin_broker_processor::consumer_request_handler::ConsumerRequestHandler;
 use crate::processor::admin_broker_processor::offset_request_handler::OffsetRequestHandler;
 use crate::processor::admin_broker_processor::topic_request_handler::TopicRequestHandler;
 
+mod acl_request_handler;
 mod batch_mq_handler;
 mod broker_config_request_handler;
 mod consumer_request_handler;
@@ -43,6 +45,7 @@ pub struct AdminBrokerProcessor<MS> {
     consumer_request_handler: ConsumerRequestHandler<MS>,
     offset_request_handler: OffsetRequestHandler<MS>,
     batch_mq_handler: BatchMqHandler<MS>,
+    acl_request_handler: AclRequestHandler<MS>,
     broker_runtime_inner: ArcMut<BrokerRuntimeInner<MS>>,
 }
 
@@ -71,12 +74,14 @@ impl<MS: MessageStore> AdminBrokerProcessor<MS> {
         let consumer_request_handler = ConsumerRequestHandler::new(broker_runtime_inner.clone());
         let offset_request_handler = OffsetRequestHandler::new(broker_runtime_inner.clone());
         let batch_mq_handler = BatchMqHandler::new(broker_runtime_inner.clone());
+        let acl_request_handler = AclRequestHandler::new(broker_runtime_inner.clone());
         AdminBrokerProcessor {
             topic_request_handler,
             broker_config_request_handler,
             consumer_request_handler,
             offset_request_handler,
             batch_mq_handler,
+            acl_request_handler,
             broker_runtime_inner,
         }
     }
@@ -121,6 +126,11 @@ impl<MS: MessageStore> AdminBrokerProcessor<MS> {
                     .get_broker_config(channel, ctx, request_code, request)
                     .await
             }
+            RequestCode::DeleteExpiredCommitlog => {
+                self.broker_config_request_handler
+                    .delete_expired_commit_log(channel, ctx, request_code, request)
+                    .await
+            }
             RequestCode::GetSystemTopicListFromBroker => {
                 self.topic_request_handler
                     .get_system_topic_list_from_broker(channel, ctx, request_code, request)
@@ -141,6 +151,26 @@ impl<MS: MessageStore> AdminBrokerProcessor<MS> {
                     .get_consume_stats(channel, ctx, request_code, request)
                     .await
             }
+            RequestCode::GetConsumerRunningInfo => {
+                self.consumer_request_handler
+                    .get_consumer_running_info(channel, ctx, request_code, request)
+                    .await
+            }
+            RequestCode::ConsumeMessageDirectly => {
+                self.consumer_request_handler
+                    .consume_message_directly(channel, ctx, request_code, request)
+                    .await
+            }
+            RequestCode::InvokeBrokerToResetOffset => {
+                self.consumer_request_handler
+                    .reset_offset(channel, ctx, request_code, request)
+                    .await
+            }
+            RequestCode::CloneGroupOffset => {
+                self.consumer_request_handler
+                    .clone_group_offset(channel, ctx, request_code, request)
+                    .await
+            }
             RequestCode::GetAllConsumerOffset => {
                 self.consumer_request_handler
                     .get_all_consumer_offset(channel, ctx, request_code, request)
@@ -188,6 +218,26 @@ impl<MS: MessageStore> AdminBrokerProcessor<MS> {
                     .unlock_batch_mq(channel, ctx, request_code, request)
                     .await
             }
+            RequestCode::UpdateAndCreateAclConfig => {
+                self.acl_request_handler
+                    .update_and_create_acl_config(channel, ctx, request_code, request)
+                    .await
+            }
+            RequestCode::DeleteAclConfig => {
+                self.acl_request_handler
+                    .delete_acl_config(channel, ctx, request_code, request)
+                    .await
+            }
+            RequestCode::GetBrokerClusterAclInfo => {
+                self.acl_request_handler
+                    .get_broker_cluster_acl_info(channel, ctx, request_code, request)
+                    .await
+            }
+            RequestCode::UpdateGlobalWhiteAddrsConfig => {
+                self.acl_request_handler
+                    .update_global_white_addrs_config(channel, ctx, request_code, request)
+                    .await
+            }
             _ => Some(get_unknown_cmd_response(request_code)),
         }
     }
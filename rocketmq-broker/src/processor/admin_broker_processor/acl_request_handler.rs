@@ -0,0 +1,242 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::base::plain_access_config::PlainAccessConfig;
+use rocketmq_remoting::code::request_code::RequestCode;
+use rocketmq_remoting::code::response_code::ResponseCode;
+use rocketmq_remoting::net::channel::Channel;
+use rocketmq_remoting::protocol::header::create_access_config_request_header::CreateAccessConfigRequestHeader;
+use rocketmq_remoting::protocol::header::delete_access_config_request_header::DeleteAccessConfigRequestHeader;
+use rocketmq_remoting::protocol::header::get_broker_acl_config_response_header::GetBrokerAclConfigResponseHeader;
+use rocketmq_remoting::protocol::header::update_global_white_addrs_config_request_header::UpdateGlobalWhiteAddrsConfigRequestHeader;
+use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use rocketmq_remoting::runtime::connection_handler_context::ConnectionHandlerContext;
+use rocketmq_rust::ArcMut;
+use rocketmq_store::log_file::MessageStore;
+use tracing::info;
+use tracing::warn;
+
+use crate::broker_runtime::BrokerRuntimeInner;
+
+const ACL_NOT_ENABLED: &str = "The broker does not enable acl";
+
+#[derive(Clone)]
+pub(super) struct AclRequestHandler<MS> {
+    broker_runtime_inner: ArcMut<BrokerRuntimeInner<MS>>,
+}
+
+impl<MS> AclRequestHandler<MS> {
+    pub fn new(broker_runtime_inner: ArcMut<BrokerRuntimeInner<MS>>) -> Self {
+        Self {
+            broker_runtime_inner,
+        }
+    }
+}
+
+impl<MS: MessageStore> AclRequestHandler<MS> {
+    pub async fn update_and_create_acl_config(
+        &mut self,
+        _channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        _request_code: RequestCode,
+        request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        let response = RemotingCommand::create_response_command();
+        let Some(permission_manager) = self.broker_runtime_inner.plain_permission_manager() else {
+            return Some(
+                response
+                    .set_code(ResponseCode::SystemError)
+                    .set_remark(ACL_NOT_ENABLED),
+            );
+        };
+        let request_header =
+            match request.decode_command_custom_header::<CreateAccessConfigRequestHeader>() {
+                Ok(header) => header,
+                Err(e) => {
+                    return Some(
+                        response
+                            .set_code(ResponseCode::SystemError)
+                            .set_remark(e.to_string()),
+                    )
+                }
+            };
+        let access_config = PlainAccessConfig {
+            access_key: Some(request_header.access_key.clone()),
+            secret_key: request_header.secret_key,
+            white_remote_address: request_header.white_remote_address,
+            admin: request_header.admin,
+            default_topic_perm: request_header.default_topic_perm,
+            default_group_perm: request_header.default_group_perm,
+            topic_perms: split_comma(request_header.topic_perms.as_deref()),
+            group_perms: split_comma(request_header.group_perms.as_deref()),
+        };
+        match permission_manager.update_access_config(access_config) {
+            Ok(_) => {
+                info!(
+                    "Update or create acl config of accessKey={} success",
+                    request_header.access_key
+                );
+                Some(response.set_code(ResponseCode::Success))
+            }
+            Err(e) => {
+                warn!(
+                    "Update or create acl config of accessKey={} failed: {}",
+                    request_header.access_key, e
+                );
+                Some(
+                    response
+                        .set_code(ResponseCode::SystemError)
+                        .set_remark(e.to_string()),
+                )
+            }
+        }
+    }
+
+    pub async fn delete_acl_config(
+        &mut self,
+        _channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        _request_code: RequestCode,
+        request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        let response = RemotingCommand::create_response_command();
+        let Some(permission_manager) = self.broker_runtime_inner.plain_permission_manager() else {
+            return Some(
+                response
+                    .set_code(ResponseCode::SystemError)
+                    .set_remark(ACL_NOT_ENABLED),
+            );
+        };
+        let request_header =
+            match request.decode_command_custom_header::<DeleteAccessConfigRequestHeader>() {
+                Ok(header) => header,
+                Err(e) => {
+                    return Some(
+                        response
+                            .set_code(ResponseCode::SystemError)
+                            .set_remark(e.to_string()),
+                    )
+                }
+            };
+        if permission_manager.delete_access_config(request_header.access_key.as_str()) {
+            info!(
+                "Delete acl config of accessKey={} success",
+                request_header.access_key
+            );
+            Some(response.set_code(ResponseCode::Success))
+        } else {
+            Some(
+                response
+                    .set_code(ResponseCode::SystemError)
+                    .set_remark(format!(
+                        "No acl config for accessKey={}",
+                        request_header.access_key
+                    )),
+            )
+        }
+    }
+
+    pub async fn update_global_white_addrs_config(
+        &mut self,
+        _channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        _request_code: RequestCode,
+        request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        let response = RemotingCommand::create_response_command();
+        let Some(permission_manager) = self.broker_runtime_inner.plain_permission_manager() else {
+            return Some(
+                response
+                    .set_code(ResponseCode::SystemError)
+                    .set_remark(ACL_NOT_ENABLED),
+            );
+        };
+        let request_header = match request
+            .decode_command_custom_header::<UpdateGlobalWhiteAddrsConfigRequestHeader>()
+        {
+            Ok(header) => header,
+            Err(e) => {
+                return Some(
+                    response
+                        .set_code(ResponseCode::SystemError)
+                        .set_remark(e.to_string()),
+                )
+            }
+        };
+        let global_white_addrs = split_comma(Some(request_header.global_white_addrs.as_str()));
+        match permission_manager.update_global_white_addrs(global_white_addrs) {
+            Ok(_) => Some(response.set_code(ResponseCode::Success)),
+            Err(e) => Some(
+                response
+                    .set_code(ResponseCode::SystemError)
+                    .set_remark(e.to_string()),
+            ),
+        }
+    }
+
+    pub async fn get_broker_cluster_acl_info(
+        &mut self,
+        _channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        _request_code: RequestCode,
+        _request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        let Some(permission_manager) = self.broker_runtime_inner.plain_permission_manager() else {
+            return Some(
+                RemotingCommand::create_response_command()
+                    .set_code(ResponseCode::SystemError)
+                    .set_remark(ACL_NOT_ENABLED),
+            );
+        };
+        let data_version = permission_manager.data_version();
+        let mut all_acl_file_version = HashMap::with_capacity(1);
+        all_acl_file_version.insert(
+            permission_manager.file_path().to_string(),
+            data_version.clone(),
+        );
+        let broker_config = self.broker_runtime_inner.broker_config();
+        let response_header = GetBrokerAclConfigResponseHeader {
+            version: CheetahString::from_string(
+                serde_json::to_string(&data_version).unwrap_or_default(),
+            ),
+            all_acl_file_version: serde_json::to_string(&all_acl_file_version)
+                .ok()
+                .map(CheetahString::from_string),
+            broker_name: broker_config.broker_name.clone(),
+            broker_addr: CheetahString::from_string(broker_config.get_broker_addr()),
+            cluster_name: broker_config.broker_identity.broker_cluster_name.clone(),
+        };
+        Some(RemotingCommand::create_response_command_with_header(
+            response_header,
+        ))
+    }
+}
+
+fn split_comma(value: Option<&str>) -> Vec<CheetahString> {
+    value
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(CheetahString::from_slice)
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -28,6 +28,7 @@ use rocketmq_remoting::runtime::connection_handler_context::ConnectionHandlerCon
 use rocketmq_rust::ArcMut;
 use rocketmq_store::log_file::MessageStore;
 use sysinfo::Disks;
+use tracing::warn;
 
 use crate::broker_runtime::BrokerRuntimeInner;
 
@@ -80,7 +81,7 @@ impl<MS: MessageStore> BrokerConfigRequestHandler<MS> {
             .collect::<HashMap<_, _>>();
         let mut body = String::new();
         for (key, value) in combine_map {
-            body.push_str(&format!("{}:{}\n", key, value));
+            body.push_str(&format!("{}={}\n", key, value));
         }
         if !body.is_empty() {
             response.set_body_mut_ref(body);
@@ -88,6 +89,21 @@ impl<MS: MessageStore> BrokerConfigRequestHandler<MS> {
         Some(response)
     }
 
+    pub async fn delete_expired_commit_log(
+        &mut self,
+        _channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        _request_code: RequestCode,
+        _request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        warn!("invoke deleteExpiredCommitLog start.");
+        if let Some(message_store) = self.broker_runtime_inner.message_store() {
+            message_store.execute_delete_files_manually();
+        }
+        warn!("invoke deleteExpiredCommitLog end.");
+        Some(RemotingCommand::create_response_command())
+    }
+
     pub async fn get_broker_runtime_info(
         &mut self,
         _channel: Channel,
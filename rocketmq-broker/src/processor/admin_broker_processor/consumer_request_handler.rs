@@ -15,9 +15,13 @@
  * limitations under the License.
  */
 
+use std::collections::BTreeMap;
 use std::collections::HashSet;
 
+use cheetah_string::CheetahString;
+use rocketmq_common::common::broker::broker_role::BrokerRole;
 use rocketmq_common::common::config_manager::ConfigManager;
+use rocketmq_common::common::message::message_decoder;
 use rocketmq_common::common::message::message_queue::MessageQueue;
 use rocketmq_remoting::code::request_code::RequestCode;
 use rocketmq_remoting::code::response_code::ResponseCode;
@@ -26,16 +30,25 @@ use rocketmq_remoting::protocol::admin::consume_stats::ConsumeStats;
 use rocketmq_remoting::protocol::admin::offset_wrapper::OffsetWrapper;
 use rocketmq_remoting::protocol::body::connection::Connection;
 use rocketmq_remoting::protocol::body::consumer_connection::ConsumerConnection;
+use rocketmq_remoting::protocol::body::reset_offset_body::ResetOffsetBody;
+use rocketmq_remoting::protocol::header::clone_group_offset_request_header::CloneGroupOffsetRequestHeader;
+use rocketmq_remoting::protocol::header::consume_message_directly_result_request_header::ConsumeMessageDirectlyResultRequestHeader;
 use rocketmq_remoting::protocol::header::get_consume_stats_request_header::GetConsumeStatsRequestHeader;
 use rocketmq_remoting::protocol::header::get_consumer_connection_list_request_header::GetConsumerConnectionListRequestHeader;
+use rocketmq_remoting::protocol::header::get_consumer_running_info_request_header::GetConsumerRunningInfoRequestHeader;
+use rocketmq_remoting::protocol::header::reset_offset_request_header::ResetOffsetRequestHeader;
 use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
 use rocketmq_remoting::protocol::RemotingSerializable;
 use rocketmq_remoting::runtime::connection_handler_context::ConnectionHandlerContext;
 use rocketmq_rust::ArcMut;
 use rocketmq_store::log_file::MessageStore;
+use tracing::info;
 use tracing::warn;
 
 use crate::broker_runtime::BrokerRuntimeInner;
+use crate::client::net::broker_to_client::Broker2Client;
+
+const CALL_CLIENT_TIMEOUT_MILLIS: u64 = 10_000;
 
 #[derive(Clone)]
 pub(super) struct ConsumerRequestHandler<MS> {
@@ -78,6 +91,7 @@ impl<MS: MessageStore> ConsumerRequestHandler<MS> {
                     subscription_table.insert(key_value.key().clone(), key_value.clone());
                 }
 
+                let mut connection_set = HashSet::new();
                 for channel_info in consumer_group_info.get_channel_info_table().iter() {
                     let mut connection = Connection::new();
                     connection.set_client_id(channel_info.client_id().clone());
@@ -85,8 +99,9 @@ impl<MS: MessageStore> ConsumerRequestHandler<MS> {
                     connection.set_version(channel_info.version());
                     connection
                         .set_client_addr(channel_info.key().remote_address().to_string().into());
-                    body_data.get_connection_set().insert(connection);
+                    connection_set.insert(connection);
                 }
+                body_data.set_connection_set(connection_set);
                 let body = body_data
                     .encode()
                     .expect("consumer connection list encode failed");
@@ -218,7 +233,9 @@ impl<MS: MessageStore> ConsumerRequestHandler<MS> {
                     }
                 }
 
-                consume_stats.get_offset_table().insert(mq, offset_wrapper);
+                consume_stats
+                    .get_offset_table_mut()
+                    .insert(mq, offset_wrapper);
             }
 
             let consume_tps = self
@@ -257,4 +274,329 @@ impl<MS: MessageStore> ConsumerRequestHandler<MS> {
             )
         }
     }
+
+    pub async fn get_consumer_running_info(
+        &mut self,
+        _channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        request_code: RequestCode,
+        request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        let request_header = request
+            .decode_command_custom_header::<GetConsumerRunningInfoRequestHeader>()
+            .unwrap();
+        Some(
+            self.call_consumer(
+                request_code,
+                request,
+                &request_header.consumer_group,
+                &request_header.client_id,
+            )
+            .await,
+        )
+    }
+
+    /// Looks up the message by its offset message id in the local commit log and pushes it to
+    /// the given client, which consumes it with the listener of the group.
+    pub async fn consume_message_directly(
+        &mut self,
+        _channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        request_code: RequestCode,
+        mut request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        let request_header = request
+            .decode_command_custom_header::<ConsumeMessageDirectlyResultRequestHeader>()
+            .unwrap();
+        let msg_id = request_header.msg_id.unwrap_or_default();
+        let Some(message_id) = message_decoder::try_decode_message_id(msg_id.as_str()) else {
+            return Some(
+                RemotingCommand::create_response_command_with_code(ResponseCode::SystemError)
+                    .set_remark(format!("invalid offset message id: {}", msg_id)),
+            );
+        };
+        let Some(message) = self
+            .broker_runtime_inner
+            .message_store()
+            .as_ref()
+            .unwrap()
+            .select_one_message_by_offset(message_id.offset)
+            .await
+            .and_then(|result| result.get_bytes())
+        else {
+            return Some(
+                RemotingCommand::create_response_command_with_code(ResponseCode::SystemError)
+                    .set_remark(format!(
+                        "can not find message by offset: {}",
+                        message_id.offset
+                    )),
+            );
+        };
+        request.add_ext_field(
+            "brokerName",
+            self.broker_runtime_inner
+                .broker_config()
+                .broker_name
+                .clone(),
+        );
+        let request = request.set_body(message);
+        Some(
+            self.call_consumer(
+                request_code,
+                request,
+                &request_header.consumer_group,
+                request_header.client_id.unwrap_or_default().as_str(),
+            )
+            .await,
+        )
+    }
+
+    /// Forwards an admin request to one online client of a consumer group and returns the
+    /// response of the client.
+    async fn call_consumer(
+        &self,
+        request_code: RequestCode,
+        request: RemotingCommand,
+        consumer_group: &CheetahString,
+        client_id: &str,
+    ) -> RemotingCommand {
+        let client_channel_info = self
+            .broker_runtime_inner
+            .consumer_manager()
+            .get_consumer_group_info(consumer_group)
+            .and_then(|group_info| group_info.find_channel_by_client_id(client_id));
+        let Some(client_channel_info) = client_channel_info else {
+            return RemotingCommand::create_response_command_with_code(ResponseCode::SystemError)
+                .set_remark(format!(
+                    "The Consumer <{}> <{}> not online",
+                    consumer_group, client_id
+                ));
+        };
+
+        let mut new_request = RemotingCommand::create_remoting_command(request_code)
+            .set_ext_fields(request.ext_fields().cloned().unwrap_or_default());
+        if let Some(body) = request.body() {
+            new_request = new_request.set_body(body.clone());
+        }
+        let mut client_channel = client_channel_info.channel().clone();
+        match Broker2Client
+            .call_client(&mut client_channel, new_request, CALL_CLIENT_TIMEOUT_MILLIS)
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => RemotingCommand::create_response_command_with_code(ResponseCode::SystemError)
+                .set_remark(format!(
+                    "invoke consumer <{}> <{}> Exception: {}",
+                    consumer_group, client_id, e
+                )),
+        }
+    }
+
+    /// Resets the consume offsets of a group on this broker. The new offsets are written to the
+    /// offset table and handed out to online consumers on their next pull, clients are not
+    /// asked to reset their local offsets.
+    pub async fn reset_offset(
+        &mut self,
+        channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        _request_code: RequestCode,
+        request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        let request_header = request
+            .decode_command_custom_header::<ResetOffsetRequestHeader>()
+            .unwrap();
+        info!(
+            "[reset-offset] reset offset started by {}. topic={}, group={}, timestamp={}, \
+             queueId={}, offset={:?}",
+            channel.remote_address(),
+            request_header.topic,
+            request_header.group,
+            request_header.timestamp,
+            request_header.queue_id,
+            request_header.offset
+        );
+        let response = RemotingCommand::create_response_command();
+        if self.broker_runtime_inner.message_store_config().broker_role == BrokerRole::Slave {
+            return Some(
+                response
+                    .set_code(ResponseCode::SystemError)
+                    .set_remark("Can not reset offset in slave broker"),
+            );
+        }
+        let topic = &request_header.topic;
+        let group = &request_header.group;
+        let Some(topic_config) = self
+            .broker_runtime_inner
+            .topic_config_manager()
+            .select_topic_config(topic)
+        else {
+            return Some(
+                response
+                    .set_code(ResponseCode::TopicNotExist)
+                    .set_remark(format!("Topic {} does not exist", topic)),
+            );
+        };
+        if !self
+            .broker_runtime_inner
+            .subscription_group_manager()
+            .contains_subscription_group(group)
+        {
+            return Some(
+                response
+                    .set_code(ResponseCode::SubscriptionGroupNotExist)
+                    .set_remark(format!("Group {} does not exist", group)),
+            );
+        }
+
+        let message_store = self.broker_runtime_inner.message_store().as_ref().unwrap();
+        let mut queue_offset_map = BTreeMap::new();
+        if request_header.queue_id >= 0 {
+            let queue_id = request_header.queue_id;
+            let offset = match request_header.offset {
+                Some(offset) if offset != -1 => {
+                    let min = message_store.get_min_offset_in_queue(topic, queue_id);
+                    let max = message_store.get_max_offset_in_queue(topic, queue_id);
+                    if (min >= 0 && offset < min) || offset > max + 1 {
+                        return Some(response.set_code(ResponseCode::SystemError).set_remark(
+                            format!(
+                                "Target offset {} not in consume queue range [{}-{}]",
+                                offset, min, max
+                            ),
+                        ));
+                    }
+                    offset
+                }
+                _ => search_offset(
+                    message_store.as_ref(),
+                    topic,
+                    queue_id,
+                    request_header.timestamp,
+                ),
+            };
+            queue_offset_map.insert(queue_id, offset);
+        } else {
+            for queue_id in 0..topic_config.read_queue_nums as i32 {
+                queue_offset_map.insert(
+                    queue_id,
+                    search_offset(
+                        message_store.as_ref(),
+                        topic,
+                        queue_id,
+                        request_header.timestamp,
+                    ),
+                );
+            }
+        }
+        if queue_offset_map.is_empty() {
+            return Some(
+                response
+                    .set_code(ResponseCode::SystemError)
+                    .set_remark("No queues to reset."),
+            );
+        }
+
+        let broker_name = self
+            .broker_runtime_inner
+            .broker_config()
+            .broker_name
+            .clone();
+        let mut body = ResetOffsetBody::default();
+        for (queue_id, offset) in queue_offset_map {
+            self.broker_runtime_inner
+                .consumer_offset_manager()
+                .assign_reset_offset(topic, group, queue_id, offset);
+            self.broker_runtime_inner
+                .pop_inflight_message_counter()
+                .clear_in_flight_message_num(topic, group, queue_id);
+            body.offset_table.insert(
+                MessageQueue::from_parts(topic.clone(), broker_name.clone(), queue_id),
+                offset,
+            );
+        }
+        let body = body.encode().expect("reset offset body encode failed");
+        Some(response.set_body(body))
+    }
+
+    pub async fn clone_group_offset(
+        &mut self,
+        _channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        _request_code: RequestCode,
+        request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        let request_header = request
+            .decode_command_custom_header::<CloneGroupOffsetRequestHeader>()
+            .unwrap();
+        let topics = match request_header
+            .topic
+            .as_ref()
+            .filter(|topic| !topic.is_empty())
+        {
+            Some(topic) => HashSet::from([topic.clone()]),
+            None => self
+                .broker_runtime_inner
+                .consumer_offset_manager()
+                .which_topic_by_consumer(&request_header.src_group),
+        };
+        for topic in topics.iter() {
+            if self
+                .broker_runtime_inner
+                .topic_config_manager()
+                .select_topic_config(topic)
+                .is_none()
+            {
+                warn!("[cloneGroupOffset], topic config not exist, {}", topic);
+                continue;
+            }
+            if !request_header.offline {
+                let find_subscription_data = self
+                    .broker_runtime_inner
+                    .consumer_manager()
+                    .find_subscription_data(&request_header.src_group, topic);
+                if find_subscription_data.is_none()
+                    && self
+                        .broker_runtime_inner
+                        .consumer_manager()
+                        .find_subscription_data_count(&request_header.src_group)
+                        > 0
+                {
+                    warn!(
+                        "AdminBrokerProcessor#cloneGroupOffset: topic does not exist in consumer \
+                         group's subscription, topic={}, consumer group={}",
+                        topic, request_header.src_group
+                    );
+                    continue;
+                }
+            }
+            self.broker_runtime_inner
+                .consumer_offset_manager()
+                .clone_offset(&request_header.src_group, &request_header.dest_group, topic);
+        }
+        Some(RemotingCommand::create_response_command())
+    }
+}
+
+/// Returns the offset of the first message stored at or after `timestamp`, the max offset when
+/// `timestamp` is -1 or later than every stored message.
+fn search_offset<MS: MessageStore>(
+    message_store: &MS,
+    topic: &CheetahString,
+    queue_id: i32,
+    timestamp: i64,
+) -> i64 {
+    let min_offset = message_store.get_min_offset_in_queue(topic, queue_id);
+    let max_offset = message_store.get_max_offset_in_queue(topic, queue_id);
+    if timestamp == -1 {
+        return max_offset;
+    }
+    let (mut low, mut high) = (min_offset.max(0), max_offset);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if message_store.get_message_store_timestamp(topic, queue_id, mid) < timestamp {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    low
 }
@@ -19,12 +19,16 @@ use rocketmq_common::common::mix_all::UNIQUE_MSG_QUERY_FLAG;
 use rocketmq_remoting::code::request_code::RequestCode;
 use rocketmq_remoting::code::response_code::ResponseCode;
 use rocketmq_remoting::net::channel::Channel;
+use rocketmq_remoting::protocol::body::query_message_by_condition_request_body::QueryMessageByConditionRequestBody;
+use rocketmq_remoting::protocol::header::query_message_by_condition_response_header::QueryMessageByConditionResponseHeader;
 use rocketmq_remoting::protocol::header::query_message_request_header::QueryMessageRequestHeader;
 use rocketmq_remoting::protocol::header::query_message_response_header::QueryMessageResponseHeader;
 use rocketmq_remoting::protocol::header::view_message_request_header::ViewMessageRequestHeader;
 use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use rocketmq_remoting::protocol::RemotingDeserializable;
 use rocketmq_remoting::runtime::connection_handler_context::ConnectionHandlerContext;
 use rocketmq_rust::ArcMut;
+use rocketmq_store::base::query_message_condition::QueryMessageCondition;
 use rocketmq_store::log_file::MessageStore;
 
 use crate::broker_runtime::BrokerRuntimeInner;
@@ -62,6 +66,9 @@ where
         match request_code {
             RequestCode::QueryMessage => self.query_message(channel, ctx, request).await,
             RequestCode::ViewMessageById => self.view_message_by_id(channel, ctx, request).await,
+            RequestCode::QueryMessageByCondition => {
+                self.query_message_by_condition(channel, ctx, request).await
+            }
             _ => None,
         }
     }
@@ -122,6 +129,82 @@ where
         )
     }
 
+    async fn query_message_by_condition(
+        &mut self,
+        _channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        let mut response = RemotingCommand::create_response_command_with_header(
+            QueryMessageByConditionResponseHeader::default(),
+        );
+        let request_body = match request
+            .get_body()
+            .and_then(|body| QueryMessageByConditionRequestBody::decode(body).ok())
+        {
+            Some(request_body) => request_body,
+            None => {
+                return Some(
+                    response
+                        .set_code(ResponseCode::SystemError)
+                        .set_remark("decode QueryMessageByConditionRequestBody failed"),
+                )
+            }
+        };
+        let keys = request_body.index_keys();
+        if keys.is_empty() {
+            return Some(
+                response
+                    .set_code(ResponseCode::SystemError)
+                    .set_remark("at least one key or the unique key is required"),
+            );
+        }
+        let max_num = if request_body.max_num > 0 {
+            request_body.max_num
+        } else {
+            self.broker_runtime_inner
+                .message_store_config()
+                .default_query_max_num as i32
+        };
+        let condition = QueryMessageCondition {
+            topic: request_body.topic,
+            keys,
+            tags: request_body.tags,
+            begin_timestamp: request_body.begin_timestamp,
+            end_timestamp: request_body.end_timestamp,
+            max_num,
+            cursor: request_body.cursor,
+        };
+        let query_message_result = self
+            .broker_runtime_inner
+            .message_store()
+            .as_ref()
+            .unwrap()
+            .query_message_by_condition(&condition)
+            .await?;
+
+        let response_header = response
+            .read_custom_header_mut::<QueryMessageByConditionResponseHeader>()
+            .unwrap();
+        response_header.index_last_update_phyoffset =
+            query_message_result.index_last_update_phyoffset;
+        response_header.index_last_update_timestamp =
+            query_message_result.index_last_update_timestamp;
+        response_header.next_cursor = query_message_result.next_cursor;
+
+        if query_message_result.buffer_total_size > 0 {
+            if let Some(body) = query_message_result.get_message_data() {
+                response.set_body_mut_ref(body);
+            }
+            return Some(response);
+        }
+        Some(
+            response
+                .set_code(ResponseCode::QueryNotFound)
+                .set_remark("can not find message, maybe time range not correct"),
+        )
+    }
+
     async fn view_message_by_id(
         &mut self,
         _channel: Channel,
@@ -238,8 +238,7 @@ where
     fn decode_msg_list(get_message_result: &GetMessageResult) -> Vec<MessageExt> {
         let mut found_list = Vec::new();
         for bb in get_message_result.message_mapped_list() {
-            let data = &bb.mapped_file.as_ref().unwrap().get_mapped_file()
-                [bb.start_offset as usize..(bb.start_offset + bb.size as u64) as usize];
+            let data = bb.get_buffer();
             let mut bytes = Bytes::copy_from_slice(data);
             let msg_ext = message_decoder::decode(&mut bytes, true, false, false, false, false);
             if let Some(msg_ext) = msg_ext {
@@ -395,9 +395,13 @@ mod tests {
     use std::collections::HashMap;
     use std::sync::Arc;
 
+    use cheetah_string::CheetahString;
+    use rocketmq_common::common::broker::broker_config::BrokerConfig;
     use rocketmq_common::common::config::TopicConfig;
     use rocketmq_common::common::message::message_ext::MessageExt;
+    use rocketmq_common::common::message::MessageTrait;
     use rocketmq_store::base::message_status_enum::PutMessageStatus;
+    use rocketmq_store::message_store::default_message_store::DefaultMessageStore;
 
     use super::*;
 
@@ -418,4 +422,37 @@ mod tests {
             PutMessageStatus::MessageIllegal
         );
     }
+
+    #[test]
+    fn check_before_put_message_rejects_messages_while_disk_is_full() {
+        let dir = tempfile::tempdir().unwrap();
+        let message_store_config = Arc::new(MessageStoreConfig {
+            store_path_root_dir: CheetahString::from(dir.path().to_string_lossy().to_string()),
+            ..MessageStoreConfig::default()
+        });
+        let message_store = DefaultMessageStore::new(
+            message_store_config.clone(),
+            Arc::new(BrokerConfig::default()),
+            Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            None,
+            false,
+        );
+        let mut msg = MessageExt::default();
+        msg.message.topic = "test_topic".into();
+        msg.set_body(bytes::Bytes::from_static(b"hello"));
+
+        message_store.get_running_flags().get_and_make_disk_full();
+        let result =
+            HookUtils::check_before_put_message(&message_store, &message_store_config, &msg);
+        assert_eq!(
+            result.unwrap().put_message_status(),
+            PutMessageStatus::ServiceNotAvailable
+        );
+
+        message_store.get_running_flags().get_and_make_disk_ok();
+        assert!(
+            HookUtils::check_before_put_message(&message_store, &message_store_config, &msg)
+                .is_none()
+        );
+    }
 }
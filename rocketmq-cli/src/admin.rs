@@ -0,0 +1,151 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+pub mod cluster;
+pub mod group;
+pub mod message;
+pub mod topic;
+
+use std::collections::BTreeSet;
+
+use cheetah_string::CheetahString;
+use clap::Args;
+use rocketmq_common::common::mix_all;
+use rocketmq_tools::admin::default_mq_admin_ext::DefaultMQAdminExt;
+use rocketmq_tools::admin::mq_admin_ext_async::MQAdminExt;
+use rocketmq_tools::tools_error::ToolsError;
+use serde::Serialize;
+use tabled::Table;
+use tabled::Tabled;
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, AdminCommandError>;
+
+#[derive(Debug, Error)]
+pub enum AdminCommandError {
+    #[error("{0}")]
+    Tools(Box<ToolsError>),
+    #[error("Serialize output to json failed. {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl From<ToolsError> for AdminCommandError {
+    fn from(e: ToolsError) -> Self {
+        AdminCommandError::Tools(Box::new(e))
+    }
+}
+
+/// Options shared by every admin subcommand.
+#[derive(Args, Debug, Clone)]
+pub struct AdminArgs {
+    #[arg(
+        short = 'n',
+        long,
+        global = true,
+        value_name = "NAMESRV",
+        help = "name server address list, eg: '192.168.0.1:9876;192.168.0.2:9876', defaults to \
+                the NAMESRV_ADDR environment variable"
+    )]
+    pub namesrv: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        default_value_t = false,
+        help = "print the result as json instead of a table"
+    )]
+    pub json: bool,
+}
+
+/// Creates and starts an admin client for `args`. Callers are expected to shut it down.
+pub async fn start_admin(args: &AdminArgs) -> Result<DefaultMQAdminExt> {
+    let mut admin = DefaultMQAdminExt::new();
+    if let Some(namesrv) = args.namesrv.as_deref() {
+        admin.set_namesrv_addr(namesrv);
+    }
+    admin.set_instance_name(format!("rocketmq-cli-{}", std::process::id()));
+    MQAdminExt::start(&mut admin).await?;
+    Ok(admin)
+}
+
+/// Prints `rows` as a table, or as a json array when `--json` is given.
+pub fn print_rows<T>(args: &AdminArgs, rows: &[T]) -> Result<()>
+where
+    T: Tabled + Serialize,
+{
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(rows)?);
+    } else {
+        println!("{}", Table::new(rows));
+    }
+    Ok(())
+}
+
+/// Prints `value` as json when `--json` is given, otherwise prints `text`.
+pub fn print_message<T: Serialize>(args: &AdminArgs, value: &T, text: &str) -> Result<()> {
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(value)?);
+    } else {
+        println!("{}", text);
+    }
+    Ok(())
+}
+
+/// Returns the master broker addresses of `cluster_name`, failing if the cluster is unknown.
+pub async fn fetch_master_addrs_by_cluster(
+    admin: &DefaultMQAdminExt,
+    cluster_name: &str,
+) -> Result<BTreeSet<CheetahString>> {
+    let cluster_info = admin.examine_broker_cluster_info().await?;
+    let broker_names = cluster_info
+        .cluster_addr_table
+        .as_ref()
+        .and_then(|table| table.get(cluster_name))
+        .ok_or_else(|| {
+            AdminCommandError::InvalidArgument(format!("cluster [{}] not found", cluster_name))
+        })?;
+    let master_addrs = broker_names
+        .iter()
+        .filter_map(|broker_name| {
+            cluster_info
+                .broker_addr_table
+                .as_ref()
+                .and_then(|table| table.get(broker_name))
+        })
+        .filter_map(|broker_data| broker_data.broker_addrs().get(&mix_all::MASTER_ID))
+        .cloned()
+        .collect();
+    Ok(master_addrs)
+}
+
+/// Resolves the brokers an update applies to: `broker` itself, or every master of `cluster`.
+pub async fn target_broker_addrs(
+    admin: &DefaultMQAdminExt,
+    cluster: Option<&str>,
+    broker: Option<&str>,
+) -> Result<BTreeSet<CheetahString>> {
+    match (broker, cluster) {
+        (Some(broker), _) => Ok(BTreeSet::from([CheetahString::from(broker)])),
+        (None, Some(cluster)) => fetch_master_addrs_by_cluster(admin, cluster).await,
+        (None, None) => Err(AdminCommandError::InvalidArgument(
+            "either --cluster or --broker is required".to_string(),
+        )),
+    }
+}
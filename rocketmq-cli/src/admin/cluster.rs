@@ -0,0 +1,640 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use cheetah_string::CheetahString;
+use clap::Args;
+use clap::Subcommand;
+use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_common::UtilAll::time_millis_to_human_string2;
+use rocketmq_tools::admin::default_mq_admin_ext::DefaultMQAdminExt;
+use rocketmq_tools::admin::mq_admin_ext_async::MQAdminExt;
+use serde::Serialize;
+use tabled::Tabled;
+
+use crate::admin::print_message;
+use crate::admin::print_rows;
+use crate::admin::start_admin;
+use crate::admin::target_broker_addrs;
+use crate::admin::AdminArgs;
+use crate::admin::Result;
+
+/// Clears the terminal and moves the cursor home before a watch refresh.
+const CLEAR_SCREEN: &str = "\x1B[2J\x1B[H";
+
+#[derive(Debug, Subcommand)]
+pub enum ClusterCommands {
+    #[command(about = "list the brokers of every cluster with their load")]
+    List {
+        #[arg(
+            short = 'c',
+            long,
+            value_name = "CLUSTER",
+            help = "only list the brokers of this cluster"
+        )]
+        cluster: Option<String>,
+
+        #[arg(
+            short = 'w',
+            long,
+            value_name = "SECONDS",
+            value_parser = clap::value_parser!(u64).range(1..),
+            help = "refresh the list every SECONDS seconds until interrupted"
+        )]
+        watch: Option<u64>,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        about = "print the runtime info of a broker or of every master of a cluster"
+    )]
+    BrokerStatus(BrokerTargetArgs),
+
+    #[command(
+        arg_required_else_help = true,
+        subcommand,
+        about = "read or update the config of brokers"
+    )]
+    BrokerConfig(BrokerConfigCommands),
+
+    #[command(
+        arg_required_else_help = true,
+        about = "revoke the write permission of a broker on every name server"
+    )]
+    WipeWritePerm {
+        #[arg(short = 'b', long, value_name = "BROKER_NAME", help = "broker name")]
+        broker_name: String,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        about = "restore the write permission of a broker on every name server"
+    )]
+    AddWritePerm {
+        #[arg(short = 'b', long, value_name = "BROKER_NAME", help = "broker name")]
+        broker_name: String,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        subcommand,
+        about = "read or update the kv config of the name servers"
+    )]
+    Kv(KvCommands),
+
+    #[command(
+        arg_required_else_help = true,
+        subcommand,
+        about = "read or update the config of the name servers"
+    )]
+    NamesrvConfig(NamesrvConfigCommands),
+}
+
+#[derive(Args, Debug)]
+pub struct BrokerTargetArgs {
+    #[arg(
+        short = 'b',
+        long,
+        value_name = "BROKER_ADDR",
+        required_unless_present = "cluster",
+        conflicts_with = "cluster",
+        help = "broker address"
+    )]
+    pub broker: Option<String>,
+
+    #[arg(
+        short = 'c',
+        long,
+        value_name = "CLUSTER",
+        help = "every master broker of this cluster"
+    )]
+    pub cluster: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BrokerConfigCommands {
+    #[command(arg_required_else_help = true, about = "print the config of brokers")]
+    Get {
+        #[command(flatten)]
+        target: BrokerTargetArgs,
+
+        #[arg(short = 'k', long, value_name = "KEY", help = "only print this key")]
+        key: Option<String>,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        about = "update one config key of brokers"
+    )]
+    Set {
+        #[command(flatten)]
+        target: BrokerTargetArgs,
+
+        #[arg(short = 'k', long, value_name = "KEY", help = "config key")]
+        key: String,
+
+        #[arg(short = 'v', long, value_name = "VALUE", help = "config value")]
+        value: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum KvCommands {
+    #[command(
+        arg_required_else_help = true,
+        about = "print one key, or the whole namespace when no key is given"
+    )]
+    Get {
+        #[arg(short = 's', long, value_name = "NAMESPACE", help = "kv namespace")]
+        namespace: String,
+
+        #[arg(short = 'k', long, value_name = "KEY", help = "kv key")]
+        key: Option<String>,
+    },
+
+    #[command(arg_required_else_help = true, about = "create or update a kv config")]
+    Put {
+        #[arg(short = 's', long, value_name = "NAMESPACE", help = "kv namespace")]
+        namespace: String,
+
+        #[arg(short = 'k', long, value_name = "KEY", help = "kv key")]
+        key: String,
+
+        #[arg(short = 'v', long, value_name = "VALUE", help = "kv value")]
+        value: String,
+    },
+
+    #[command(arg_required_else_help = true, about = "delete a kv config")]
+    Delete {
+        #[arg(short = 's', long, value_name = "NAMESPACE", help = "kv namespace")]
+        namespace: String,
+
+        #[arg(short = 'k', long, value_name = "KEY", help = "kv key")]
+        key: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum NamesrvConfigCommands {
+    #[command(about = "print the config of every name server")]
+    Get,
+
+    #[command(
+        arg_required_else_help = true,
+        about = "update one config key on every name server"
+    )]
+    Set {
+        #[arg(short = 'k', long, value_name = "KEY", help = "config key")]
+        key: String,
+
+        #[arg(short = 'v', long, value_name = "VALUE", help = "config value")]
+        value: String,
+    },
+}
+
+#[derive(Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BrokerRow {
+    #[tabled(rename = "Cluster")]
+    cluster: String,
+    #[tabled(rename = "Broker Name")]
+    broker_name: String,
+    #[tabled(rename = "BID")]
+    broker_id: u64,
+    #[tabled(rename = "Addr")]
+    addr: String,
+    #[tabled(rename = "Version")]
+    version: String,
+    #[tabled(rename = "InTPS")]
+    put_tps: String,
+    #[tabled(rename = "OutTPS")]
+    get_tps: String,
+    #[tabled(rename = "Put Today")]
+    put_today: String,
+    #[tabled(rename = "Get Today")]
+    get_today: String,
+    #[tabled(rename = "Activated")]
+    activated: String,
+}
+
+#[derive(Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct KeyValueRow {
+    #[tabled(rename = "Source")]
+    source: String,
+    #[tabled(rename = "Key")]
+    key: String,
+    #[tabled(rename = "Value")]
+    value: String,
+}
+
+#[derive(Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WritePermRow {
+    #[tabled(rename = "Name Server")]
+    namesrv: String,
+    #[tabled(rename = "Broker Name")]
+    broker_name: String,
+    #[tabled(rename = "Affected Topics")]
+    affected_topics: i32,
+}
+
+pub async fn execute_cluster_command(args: AdminArgs, command: ClusterCommands) -> Result<()> {
+    let mut admin = start_admin(&args).await?;
+    let result = run(&admin, &args, command).await;
+    MQAdminExt::shutdown(&mut admin).await;
+    result
+}
+
+async fn run(admin: &DefaultMQAdminExt, args: &AdminArgs, command: ClusterCommands) -> Result<()> {
+    match command {
+        ClusterCommands::List { cluster, watch } => match watch {
+            Some(interval) => watch_cluster(admin, args, cluster.as_deref(), interval).await,
+            None => print_rows(args, &list_brokers(admin, cluster.as_deref()).await?),
+        },
+        ClusterCommands::BrokerStatus(target) => {
+            let mut rows = Vec::new();
+            for broker_addr in target_addrs(admin, &target).await? {
+                let runtime_info = admin
+                    .fetch_broker_runtime_stats(broker_addr.clone())
+                    .await?;
+                rows.extend(key_value_rows(&broker_addr, runtime_info.table, None));
+            }
+            print_rows(args, &rows)
+        }
+        ClusterCommands::BrokerConfig(BrokerConfigCommands::Get { target, key }) => {
+            let mut rows = Vec::new();
+            for broker_addr in target_addrs(admin, &target).await? {
+                let config = admin.get_broker_config(broker_addr.clone()).await?;
+                rows.extend(key_value_rows(&broker_addr, config, key.as_deref()));
+            }
+            print_rows(args, &rows)
+        }
+        ClusterCommands::BrokerConfig(BrokerConfigCommands::Set { target, key, value }) => {
+            let properties = HashMap::from([(
+                CheetahString::from(key.as_str()),
+                CheetahString::from(value.as_str()),
+            )]);
+            let mut rows = Vec::new();
+            for broker_addr in target_addrs(admin, &target).await? {
+                admin
+                    .update_broker_config(broker_addr.clone(), properties.clone())
+                    .await?;
+                rows.push(KeyValueRow {
+                    source: broker_addr.to_string(),
+                    key: key.clone(),
+                    value: value.clone(),
+                });
+            }
+            print_rows(args, &rows)
+        }
+        ClusterCommands::WipeWritePerm { broker_name } => {
+            update_write_perm(admin, args, broker_name, false).await
+        }
+        ClusterCommands::AddWritePerm { broker_name } => {
+            update_write_perm(admin, args, broker_name, true).await
+        }
+        ClusterCommands::Kv(KvCommands::Get {
+            namespace,
+            key: Some(key),
+        }) => {
+            let value = admin
+                .get_kv_config(namespace.as_str().into(), key.as_str().into())
+                .await?;
+            print_rows(
+                args,
+                &[KeyValueRow {
+                    source: namespace,
+                    key,
+                    value: value.to_string(),
+                }],
+            )
+        }
+        ClusterCommands::Kv(KvCommands::Get {
+            namespace,
+            key: None,
+        }) => {
+            let kv_table = admin
+                .get_kv_list_by_namespace(namespace.as_str().into())
+                .await?;
+            print_rows(args, &key_value_rows(&namespace, kv_table.table, None))
+        }
+        ClusterCommands::Kv(KvCommands::Put {
+            namespace,
+            key,
+            value,
+        }) => {
+            admin
+                .create_and_update_kv_config(
+                    namespace.as_str().into(),
+                    key.as_str().into(),
+                    value.as_str().into(),
+                )
+                .await?;
+            print_message(
+                args,
+                &serde_json::json!({ "namespace": namespace, "key": key, "value": value }),
+                &format!("put kv config success. [{}] {}={}", namespace, key, value),
+            )
+        }
+        ClusterCommands::Kv(KvCommands::Delete { namespace, key }) => {
+            admin
+                .delete_kv_config(namespace.as_str().into(), key.as_str().into())
+                .await?;
+            print_message(
+                args,
+                &serde_json::json!({ "namespace": namespace, "key": key, "deleted": true }),
+                &format!("delete kv config success. [{}] {}", namespace, key),
+            )
+        }
+        ClusterCommands::NamesrvConfig(NamesrvConfigCommands::Get) => {
+            let configs = admin.get_name_server_config(Vec::new()).await?;
+            let configs: BTreeMap<_, _> = configs.into_iter().collect();
+            let rows: Vec<_> = configs
+                .into_iter()
+                .flat_map(|(namesrv, config)| key_value_rows(&namesrv, config, None))
+                .collect();
+            print_rows(args, &rows)
+        }
+        ClusterCommands::NamesrvConfig(NamesrvConfigCommands::Set { key, value }) => {
+            let properties = HashMap::from([(
+                CheetahString::from(key.as_str()),
+                CheetahString::from(value.as_str()),
+            )]);
+            admin
+                .update_name_server_config(properties, Vec::new())
+                .await?;
+            let rows: Vec<_> = admin
+                .get_name_server_address_list()
+                .await
+                .into_iter()
+                .map(|namesrv| KeyValueRow {
+                    source: namesrv.to_string(),
+                    key: key.clone(),
+                    value: value.clone(),
+                })
+                .collect();
+            print_rows(args, &rows)
+        }
+    }
+}
+
+async fn target_addrs(
+    admin: &DefaultMQAdminExt,
+    target: &BrokerTargetArgs,
+) -> Result<Vec<CheetahString>> {
+    Ok(
+        target_broker_addrs(admin, target.cluster.as_deref(), target.broker.as_deref())
+            .await?
+            .into_iter()
+            .collect(),
+    )
+}
+
+/// Collects one row per broker, the load columns stay empty for brokers that did not answer.
+async fn list_brokers(admin: &DefaultMQAdminExt, cluster: Option<&str>) -> Result<Vec<BrokerRow>> {
+    let cluster_info = admin.examine_broker_cluster_info().await?;
+    let cluster_addr_table: BTreeMap<_, _> = cluster_info
+        .cluster_addr_table
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(cluster_name, _)| cluster.map_or(true, |cluster| cluster_name == cluster))
+        .collect();
+    let broker_addr_table = cluster_info.broker_addr_table.unwrap_or_default();
+
+    let mut rows = Vec::new();
+    for (cluster_name, broker_names) in cluster_addr_table {
+        let mut broker_names: Vec<_> = broker_names.into_iter().collect();
+        broker_names.sort();
+        for broker_name in broker_names {
+            let Some(broker_data) = broker_addr_table.get(&broker_name) else {
+                continue;
+            };
+            let broker_addrs: BTreeMap<_, _> = broker_data.broker_addrs().iter().collect();
+            for (broker_id, addr) in broker_addrs {
+                let runtime_info = admin
+                    .fetch_broker_runtime_stats(addr.clone())
+                    .await
+                    .map(|kv_table| kv_table.table)
+                    .unwrap_or_default();
+                let value = |key: &str| {
+                    runtime_info
+                        .get(key)
+                        .map(|value| value.to_string())
+                        .unwrap_or_default()
+                };
+                rows.push(BrokerRow {
+                    cluster: cluster_name.to_string(),
+                    broker_name: broker_name.to_string(),
+                    broker_id: *broker_id,
+                    addr: addr.to_string(),
+                    version: value("brokerVersionDesc"),
+                    put_tps: format_tps(runtime_info.get("putTps")),
+                    // Java brokers spell the key without the second `r`.
+                    get_tps: format_tps(
+                        runtime_info
+                            .get("getTransferredTps")
+                            .or_else(|| runtime_info.get("getTransferedTps")),
+                    ),
+                    put_today: daily_total(
+                        &runtime_info,
+                        "msgPutTotalTodayNow",
+                        "msgPutTotalTodayMorning",
+                    ),
+                    get_today: daily_total(
+                        &runtime_info,
+                        "msgGetTotalTodayNow",
+                        "msgGetTotalTodayMorning",
+                    ),
+                    activated: value("brokerActive"),
+                });
+            }
+        }
+    }
+    Ok(rows)
+}
+
+async fn watch_cluster(
+    admin: &DefaultMQAdminExt,
+    args: &AdminArgs,
+    cluster: Option<&str>,
+    interval: u64,
+) -> Result<()> {
+    let mut ticker = tokio::time::interval(Duration::from_secs(interval));
+    loop {
+        ticker.tick().await;
+        let rows = list_brokers(admin, cluster).await;
+        if !args.json {
+            print!("{}", CLEAR_SCREEN);
+            println!(
+                "{}  refresh every {}s, press Ctrl+C to quit",
+                time_millis_to_human_string2(get_current_millis() as i64),
+                interval
+            );
+        }
+        // A name server that is briefly unreachable should not end the watch.
+        match rows {
+            Ok(rows) => print_rows(args, &rows)?,
+            Err(e) => eprintln!("fetch cluster info failed, retry in {}s: {}", interval, e),
+        }
+    }
+}
+
+async fn update_write_perm(
+    admin: &DefaultMQAdminExt,
+    args: &AdminArgs,
+    broker_name: String,
+    writable: bool,
+) -> Result<()> {
+    let mut rows = Vec::new();
+    for namesrv in admin.get_name_server_address_list().await {
+        let affected_topics = if writable {
+            admin
+                .add_write_perm_of_broker(namesrv.clone(), broker_name.as_str().into())
+                .await?
+        } else {
+            admin
+                .wipe_write_perm_of_broker(namesrv.clone(), broker_name.as_str().into())
+                .await?
+        };
+        rows.push(WritePermRow {
+            namesrv: namesrv.to_string(),
+            broker_name: broker_name.clone(),
+            affected_topics,
+        });
+    }
+    print_rows(args, &rows)
+}
+
+fn key_value_rows(
+    source: &str,
+    table: HashMap<CheetahString, CheetahString>,
+    only_key: Option<&str>,
+) -> Vec<KeyValueRow> {
+    let table: BTreeMap<_, _> = table
+        .into_iter()
+        .filter(|(key, _)| only_key.map_or(true, |only_key| key == only_key))
+        .collect();
+    table
+        .into_iter()
+        .map(|(key, value)| KeyValueRow {
+            source: source.to_string(),
+            key: key.to_string(),
+            value: value.to_string(),
+        })
+        .collect()
+}
+
+/// Runtime TPS values hold the averages of the last 10s, 1m and 10m, the first one is shown.
+fn format_tps(value: Option<&CheetahString>) -> String {
+    value
+        .and_then(|value| value.split_whitespace().next())
+        .and_then(|tps| tps.parse::<f64>().ok())
+        .map(|tps| format!("{:.2}", tps))
+        .unwrap_or_default()
+}
+
+fn daily_total(
+    runtime_info: &HashMap<CheetahString, CheetahString>,
+    now_key: &str,
+    morning_key: &str,
+) -> String {
+    let total = |key: &str| {
+        runtime_info
+            .get(key)
+            .and_then(|value| value.parse::<i64>().ok())
+    };
+    match (total(now_key), total(morning_key)) {
+        (Some(now), Some(morning)) => (now - morning).to_string(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(entries: &[(&'static str, &'static str)]) -> HashMap<CheetahString, CheetahString> {
+        entries
+            .iter()
+            .map(|(key, value)| {
+                (
+                    CheetahString::from_static_str(key),
+                    CheetahString::from_static_str(value),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn formats_the_latest_tps_average() {
+        let tps = CheetahString::from_static_str("12.3456 10.0 8.5");
+        assert_eq!(format_tps(Some(&tps)), "12.35");
+        assert_eq!(
+            format_tps(Some(&CheetahString::from_static_str("0"))),
+            "0.00"
+        );
+        assert_eq!(format_tps(Some(&CheetahString::from_static_str("n/a"))), "");
+        assert_eq!(format_tps(Some(&CheetahString::new())), "");
+        assert_eq!(format_tps(None), "");
+    }
+
+    #[test]
+    fn computes_daily_totals_from_the_morning_snapshot() {
+        let runtime_info = table(&[
+            ("msgPutTotalTodayNow", "150"),
+            ("msgPutTotalTodayMorning", "100"),
+            ("msgGetTotalTodayNow", "bad"),
+            ("msgGetTotalTodayMorning", "100"),
+        ]);
+        assert_eq!(
+            daily_total(
+                &runtime_info,
+                "msgPutTotalTodayNow",
+                "msgPutTotalTodayMorning"
+            ),
+            "50"
+        );
+        assert_eq!(
+            daily_total(
+                &runtime_info,
+                "msgGetTotalTodayNow",
+                "msgGetTotalTodayMorning"
+            ),
+            ""
+        );
+        assert_eq!(
+            daily_total(&runtime_info, "missing", "msgPutTotalTodayMorning"),
+            ""
+        );
+    }
+
+    #[test]
+    fn key_value_rows_are_sorted_and_filtered() {
+        let config = table(&[("listenPort", "10911"), ("brokerName", "broker-a")]);
+        let rows = key_value_rows("127.0.0.1:10911", config.clone(), None);
+        let keys: Vec<_> = rows.iter().map(|row| row.key.as_str()).collect();
+        assert_eq!(keys, ["brokerName", "listenPort"]);
+        assert!(rows.iter().all(|row| row.source == "127.0.0.1:10911"));
+
+        let rows = key_value_rows("127.0.0.1:10911", config, Some("listenPort"));
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].value, "10911");
+    }
+}
@@ -0,0 +1,998 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use cheetah_string::CheetahString;
+use clap::Args;
+use clap::Subcommand;
+use rocketmq_client_rust::client_error::MQClientError;
+use rocketmq_common::common::message::message_queue::MessageQueue;
+use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_common::UtilAll::time_millis_to_human_string2;
+use rocketmq_remoting::code::response_code::ResponseCode;
+use rocketmq_remoting::protocol::body::broker_body::cluster_info::ClusterInfo;
+use rocketmq_remoting::protocol::body::consumer_running_info::ConsumerRunningInfo;
+use rocketmq_remoting::protocol::subscription::subscription_group_config::SubscriptionGroupConfig;
+use rocketmq_tools::admin::default_mq_admin_ext::DefaultMQAdminExt;
+use rocketmq_tools::admin::mq_admin_ext_async::MQAdminExt;
+use rocketmq_tools::tools_error::ToolsError;
+use serde::Serialize;
+use tabled::Table;
+use tabled::Tabled;
+
+use crate::admin::print_message;
+use crate::admin::print_rows;
+use crate::admin::start_admin;
+use crate::admin::target_broker_addrs;
+use crate::admin::AdminArgs;
+use crate::admin::AdminCommandError;
+use crate::admin::Result;
+
+#[derive(Debug, Subcommand)]
+pub enum GroupCommands {
+    #[command(
+        arg_required_else_help = true,
+        about = "examine the consume progress and lag of a group per message queue"
+    )]
+    Progress {
+        #[arg(short = 'g', long, value_name = "GROUP", help = "consumer group name")]
+        group: String,
+
+        #[arg(
+            short = 't',
+            long,
+            value_name = "TOPIC",
+            help = "only examine this topic"
+        )]
+        topic: Option<String>,
+
+        #[arg(
+            short = 'c',
+            long,
+            value_name = "CLUSTER",
+            help = "only examine brokers of this cluster"
+        )]
+        cluster: Option<String>,
+
+        #[arg(
+            short = 'b',
+            long,
+            value_name = "BROKER_ADDR",
+            help = "only examine this broker"
+        )]
+        broker: Option<String>,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "sort the queues by lag, the largest first"
+        )]
+        sort_by_lag: bool,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        about = "list the online clients and subscriptions of a group"
+    )]
+    Connection {
+        #[arg(short = 'g', long, value_name = "GROUP", help = "consumer group name")]
+        group: String,
+
+        #[arg(
+            short = 'b',
+            long,
+            value_name = "BROKER_ADDR",
+            help = "ask this broker instead of the brokers of the retry topic"
+        )]
+        broker: Option<String>,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        about = "examine the running info of a group inside one client"
+    )]
+    RunningInfo {
+        #[arg(short = 'g', long, value_name = "GROUP", help = "consumer group name")]
+        group: String,
+
+        #[arg(
+            short = 'i',
+            long,
+            value_name = "CLIENT_ID",
+            help = "client id, see `group connection`"
+        )]
+        client_id: String,
+
+        #[arg(long, default_value_t = false, help = "also ask for the thread stack")]
+        jstack: bool,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        about = "reset the consume offset of a group by timestamp, or of one queue to an offset"
+    )]
+    ResetOffset(ResetOffsetArgs),
+
+    #[command(
+        arg_required_else_help = true,
+        about = "copy the consume offsets of a topic from one group to another"
+    )]
+    CloneOffset {
+        #[arg(
+            short = 's',
+            long,
+            value_name = "GROUP",
+            help = "source consumer group"
+        )]
+        src_group: String,
+
+        #[arg(
+            short = 'd',
+            long,
+            value_name = "GROUP",
+            help = "destination consumer group"
+        )]
+        dest_group: String,
+
+        #[arg(short = 't', long, value_name = "TOPIC", help = "topic name")]
+        topic: String,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "clone even if the source group does not subscribe the topic any more"
+        )]
+        offline: bool,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        about = "delete the subscription group config from a broker or a cluster"
+    )]
+    Delete {
+        #[arg(short = 'g', long, value_name = "GROUP", help = "consumer group name")]
+        group: String,
+
+        #[arg(
+            short = 'c',
+            long,
+            value_name = "CLUSTER",
+            required_unless_present = "broker",
+            conflicts_with = "broker",
+            help = "delete from every master broker of this cluster"
+        )]
+        cluster: Option<String>,
+
+        #[arg(
+            short = 'b',
+            long,
+            value_name = "BROKER_ADDR",
+            help = "delete from this broker only"
+        )]
+        broker: Option<String>,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "also remove the consume offsets of the group"
+        )]
+        remove_offset: bool,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        about = "create or update the subscription group config on a broker or a cluster"
+    )]
+    Update(GroupConfigArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ResetOffsetArgs {
+    #[arg(short = 'g', long, value_name = "GROUP", help = "consumer group name")]
+    pub group: String,
+
+    #[arg(short = 't', long, value_name = "TOPIC", help = "topic name")]
+    pub topic: String,
+
+    #[arg(
+        long,
+        value_name = "TIMESTAMP",
+        required_unless_present = "offset",
+        conflicts_with = "offset",
+        help = "reset every queue to the first message stored at or after this time, in \
+                milliseconds, or `now` to skip all stored messages"
+    )]
+    pub timestamp: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "OFFSET",
+        requires_all = ["broker", "queue_id"],
+        help = "reset a single queue, given by --broker and --queue-id, to this offset"
+    )]
+    pub offset: Option<u64>,
+
+    #[arg(
+        short = 'b',
+        long,
+        value_name = "BROKER_ADDR",
+        help = "broker of the queue"
+    )]
+    pub broker: Option<String>,
+
+    #[arg(short = 'q', long, value_name = "QUEUE_ID", help = "queue id")]
+    pub queue_id: Option<i32>,
+
+    #[arg(
+        short = 'c',
+        long,
+        value_name = "CLUSTER",
+        help = "only reset queues on brokers of this cluster"
+    )]
+    pub cluster: Option<String>,
+
+    #[arg(
+        long,
+        default_value_t = true,
+        action = clap::ArgAction::Set,
+        help = "also move offsets forward, not only rewind them"
+    )]
+    pub force: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct GroupConfigArgs {
+    #[arg(short = 'g', long, value_name = "GROUP", help = "consumer group name")]
+    pub group: String,
+
+    #[arg(
+        short = 'c',
+        long,
+        value_name = "CLUSTER",
+        required_unless_present = "broker",
+        conflicts_with = "broker",
+        help = "apply to every master broker of this cluster"
+    )]
+    pub cluster: Option<String>,
+
+    #[arg(
+        short = 'b',
+        long,
+        value_name = "BROKER_ADDR",
+        help = "apply to this broker only"
+    )]
+    pub broker: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "BOOL",
+        help = "whether the group is allowed to consume"
+    )]
+    pub consume_enable: Option<bool>,
+
+    #[arg(
+        long,
+        value_name = "BOOL",
+        help = "whether the group consumes from the min offset"
+    )]
+    pub consume_from_min_enable: Option<bool>,
+
+    #[arg(
+        long,
+        value_name = "BOOL",
+        help = "whether the group consumes in broadcast mode"
+    )]
+    pub consume_broadcast_enable: Option<bool>,
+
+    #[arg(long, value_name = "BOOL", help = "whether the group consumes orderly")]
+    pub consume_message_orderly: Option<bool>,
+
+    #[arg(long, value_name = "NUMS", help = "queue nums of the retry topic")]
+    pub retry_queue_nums: Option<i32>,
+
+    #[arg(long, value_name = "TIMES", help = "max reconsume times")]
+    pub retry_max_times: Option<i32>,
+
+    #[arg(long, value_name = "ID", help = "broker id to consume from")]
+    pub broker_id: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "ID",
+        help = "broker id to consume from when consuming slowly"
+    )]
+    pub which_broker_when_consume_slowly: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "BOOL",
+        help = "whether clients are notified when the consumer ids change"
+    )]
+    pub notify_consumer_ids_changed_enable: Option<bool>,
+}
+
+#[derive(Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProgressRow {
+    #[tabled(rename = "Topic")]
+    topic: String,
+    #[tabled(rename = "Broker Name")]
+    broker_name: String,
+    #[tabled(rename = "Queue Id")]
+    queue_id: i32,
+    #[tabled(rename = "Broker Offset")]
+    broker_offset: i64,
+    #[tabled(rename = "Consumer Offset")]
+    consumer_offset: i64,
+    #[tabled(rename = "Diff")]
+    diff: i64,
+    #[tabled(rename = "Last Consumed")]
+    last_timestamp: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProgressSummary<'a> {
+    group: &'a str,
+    consume_tps: f64,
+    diff_total: i64,
+    queues: &'a [ProgressRow],
+}
+
+#[derive(Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConnectionRow {
+    #[tabled(rename = "Client Id")]
+    client_id: String,
+    #[tabled(rename = "Client Address")]
+    client_addr: String,
+    #[tabled(rename = "Language")]
+    language: String,
+    #[tabled(rename = "Version")]
+    version: i32,
+}
+
+#[derive(Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SubscriptionRow {
+    #[tabled(rename = "Topic")]
+    topic: String,
+    #[tabled(rename = "Sub Expression")]
+    sub_expression: String,
+}
+
+#[derive(Tabled)]
+struct PropertyRow {
+    #[tabled(rename = "Property")]
+    key: String,
+    #[tabled(rename = "Value")]
+    value: String,
+}
+
+#[derive(Tabled)]
+struct ProcessQueueRow {
+    #[tabled(rename = "Topic")]
+    topic: String,
+    #[tabled(rename = "Broker Name")]
+    broker_name: String,
+    #[tabled(rename = "Queue Id")]
+    queue_id: i32,
+    #[tabled(rename = "Commit Offset")]
+    commit_offset: u64,
+    #[tabled(rename = "Cached Msgs")]
+    cached_msg_count: u32,
+    #[tabled(rename = "Cached Min")]
+    cached_msg_min_offset: u64,
+    #[tabled(rename = "Cached Max")]
+    cached_msg_max_offset: u64,
+    #[tabled(rename = "Locked")]
+    locked: bool,
+    #[tabled(rename = "Dropped")]
+    dropped: bool,
+    #[tabled(rename = "Last Pull")]
+    last_pull_timestamp: String,
+}
+
+#[derive(Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResetOffsetRow {
+    #[tabled(rename = "Topic")]
+    topic: String,
+    #[tabled(rename = "Broker Name")]
+    broker_name: String,
+    #[tabled(rename = "Queue Id")]
+    queue_id: i32,
+    #[tabled(rename = "Offset")]
+    offset: u64,
+}
+
+#[derive(Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GroupConfigRow {
+    #[tabled(rename = "Broker")]
+    broker_addr: String,
+    #[tabled(rename = "Group")]
+    group: String,
+    #[tabled(rename = "Consume Enable")]
+    consume_enable: bool,
+    #[tabled(rename = "Broadcast")]
+    consume_broadcast_enable: bool,
+    #[tabled(rename = "Orderly")]
+    consume_message_orderly: bool,
+    #[tabled(rename = "Retry Queues")]
+    retry_queue_nums: i32,
+    #[tabled(rename = "Retry Max Times")]
+    retry_max_times: i32,
+    #[tabled(rename = "Broker Id")]
+    broker_id: u64,
+}
+
+pub async fn execute_group_command(args: AdminArgs, command: GroupCommands) -> Result<()> {
+    let mut admin = start_admin(&args).await?;
+    let result = run(&admin, &args, command).await;
+    MQAdminExt::shutdown(&mut admin).await;
+    result
+}
+
+async fn run(admin: &DefaultMQAdminExt, args: &AdminArgs, command: GroupCommands) -> Result<()> {
+    match command {
+        GroupCommands::Progress {
+            group,
+            topic,
+            cluster,
+            broker,
+            sort_by_lag,
+        } => examine_progress(admin, args, group, topic, cluster, broker, sort_by_lag).await,
+        GroupCommands::Connection { group, broker } => {
+            examine_connection(admin, args, group, broker).await
+        }
+        GroupCommands::RunningInfo {
+            group,
+            client_id,
+            jstack,
+        } => examine_running_info(admin, args, group, client_id, jstack).await,
+        GroupCommands::ResetOffset(reset_args) => reset_offset(admin, args, reset_args).await,
+        GroupCommands::CloneOffset {
+            src_group,
+            dest_group,
+            topic,
+            offline,
+        } => {
+            admin
+                .clone_group_offset(
+                    src_group.as_str().into(),
+                    dest_group.as_str().into(),
+                    topic.as_str().into(),
+                    offline,
+                )
+                .await?;
+            print_message(
+                args,
+                &serde_json::json!({
+                    "srcGroup": src_group,
+                    "destGroup": dest_group,
+                    "topic": topic,
+                    "cloned": true,
+                }),
+                &format!(
+                    "clone group offset success. srcGroup[{}], destGroup[{}], topic[{}]",
+                    src_group, dest_group, topic
+                ),
+            )
+        }
+        GroupCommands::Delete {
+            group,
+            cluster,
+            broker,
+            remove_offset,
+        } => {
+            let broker_addrs =
+                target_broker_addrs(admin, cluster.as_deref(), broker.as_deref()).await?;
+            for broker_addr in &broker_addrs {
+                admin
+                    .delete_subscription_group(
+                        broker_addr.clone(),
+                        group.as_str().into(),
+                        Some(remove_offset),
+                    )
+                    .await?;
+            }
+            let broker_addrs: Vec<_> = broker_addrs.iter().map(|addr| addr.to_string()).collect();
+            print_message(
+                args,
+                &serde_json::json!({ "group": group, "brokers": broker_addrs, "deleted": true }),
+                &format!(
+                    "delete subscription group [{}] from [{}] success.",
+                    group,
+                    broker_addrs.join(", ")
+                ),
+            )
+        }
+        GroupCommands::Update(config_args) => update_group(admin, args, config_args).await,
+    }
+}
+
+async fn examine_progress(
+    admin: &DefaultMQAdminExt,
+    args: &AdminArgs,
+    group: String,
+    topic: Option<String>,
+    cluster: Option<String>,
+    broker: Option<String>,
+    sort_by_lag: bool,
+) -> Result<()> {
+    let consume_stats = admin
+        .examine_consume_stats(
+            group.as_str().into(),
+            topic.map(CheetahString::from),
+            cluster.map(CheetahString::from),
+            broker.map(CheetahString::from),
+            None,
+        )
+        .await?;
+    let mut rows: Vec<_> = consume_stats
+        .get_offset_table()
+        .into_iter()
+        .map(|(mq, offset)| ProgressRow {
+            topic: mq.get_topic().to_string(),
+            broker_name: mq.get_broker_name().to_string(),
+            queue_id: mq.get_queue_id(),
+            broker_offset: offset.get_broker_offset(),
+            consumer_offset: offset.get_consumer_offset(),
+            diff: offset.get_broker_offset() - offset.get_consumer_offset(),
+            last_timestamp: format_timestamp(offset.get_last_timestamp()),
+        })
+        .collect();
+    sort_progress_rows(&mut rows, sort_by_lag);
+
+    let diff_total = consume_stats.compute_total_diff();
+    if args.json {
+        let summary = ProgressSummary {
+            group: &group,
+            consume_tps: consume_stats.get_consume_tps(),
+            diff_total,
+            queues: &rows,
+        };
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+    } else {
+        println!("{}", Table::new(&rows));
+        println!(
+            "Consume TPS: {:.2}\nDiff Total: {}",
+            consume_stats.get_consume_tps(),
+            diff_total
+        );
+    }
+    Ok(())
+}
+
+/// Orders the rows by queue, or by lag with the largest first and ties kept in queue order.
+fn sort_progress_rows(rows: &mut [ProgressRow], sort_by_lag: bool) {
+    rows.sort_by(|a, b| {
+        (&a.topic, &a.broker_name, a.queue_id).cmp(&(&b.topic, &b.broker_name, b.queue_id))
+    });
+    if sort_by_lag {
+        rows.sort_by_key(|row| Reverse(row.diff));
+    }
+}
+
+async fn examine_connection(
+    admin: &DefaultMQAdminExt,
+    args: &AdminArgs,
+    group: String,
+    broker: Option<String>,
+) -> Result<()> {
+    let consumer_connection = admin
+        .examine_consumer_connection_info(group.into(), broker.map(CheetahString::from))
+        .await?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&consumer_connection)?);
+        return Ok(());
+    }
+
+    let mut connection_rows: Vec<_> = consumer_connection
+        .get_connection_set()
+        .into_iter()
+        .map(|connection| ConnectionRow {
+            client_id: connection.get_client_id().to_string(),
+            client_addr: connection.get_client_addr().to_string(),
+            language: format!("{:?}", connection.get_language()),
+            version: connection.get_version(),
+        })
+        .collect();
+    connection_rows.sort_by(|a, b| a.client_id.cmp(&b.client_id));
+    let mut subscription_rows: Vec<_> = consumer_connection
+        .get_subscription_table()
+        .iter()
+        .map(|entry| SubscriptionRow {
+            topic: entry.key().to_string(),
+            sub_expression: entry.value().sub_string.to_string(),
+        })
+        .collect();
+    subscription_rows.sort_by(|a, b| a.topic.cmp(&b.topic));
+
+    println!("{}", Table::new(connection_rows));
+    println!("{}", Table::new(subscription_rows));
+    println!(
+        "ConsumeType: {}\nMessageModel: {}\nConsumeFromWhere: {:?}",
+        consumer_connection.get_consume_type().name(),
+        consumer_connection.get_message_model(),
+        consumer_connection.get_consume_from_where()
+    );
+    Ok(())
+}
+
+async fn examine_running_info(
+    admin: &DefaultMQAdminExt,
+    args: &AdminArgs,
+    group: String,
+    client_id: String,
+    jstack: bool,
+) -> Result<()> {
+    let running_info = admin
+        .get_consumer_running_info(group.into(), client_id.into(), jstack, None)
+        .await?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&running_info)?);
+        return Ok(());
+    }
+
+    let properties: BTreeMap<_, _> = running_info.properties.iter().collect();
+    let property_rows: Vec<_> = properties
+        .into_iter()
+        .map(|(key, value)| PropertyRow {
+            key: key.to_string(),
+            value: value.to_string(),
+        })
+        .collect();
+    let mut subscription_rows: Vec<_> = running_info
+        .subscription_set
+        .iter()
+        .map(|subscription| SubscriptionRow {
+            topic: subscription.topic.to_string(),
+            sub_expression: subscription.sub_string.to_string(),
+        })
+        .collect();
+    subscription_rows.sort_by(|a, b| a.topic.cmp(&b.topic));
+
+    println!("{}", Table::new(property_rows));
+    println!("{}", Table::new(subscription_rows));
+    println!("{}", Table::new(process_queue_rows(&running_info)));
+    if let Some(jstack) = running_info
+        .jstack
+        .as_ref()
+        .filter(|jstack| !jstack.is_empty())
+    {
+        println!("{}", jstack);
+    }
+    Ok(())
+}
+
+fn process_queue_rows(running_info: &ConsumerRunningInfo) -> Vec<ProcessQueueRow> {
+    let mut mq_table: Vec<(&MessageQueue, _)> = running_info.mq_table.iter().collect();
+    mq_table.sort_by_key(|(mq, _)| *mq);
+    mq_table
+        .into_iter()
+        .map(|(mq, info)| ProcessQueueRow {
+            topic: mq.get_topic().to_string(),
+            broker_name: mq.get_broker_name().to_string(),
+            queue_id: mq.get_queue_id(),
+            commit_offset: info.commit_offset,
+            cached_msg_count: info.cached_msg_count,
+            cached_msg_min_offset: info.cached_msg_min_offset,
+            cached_msg_max_offset: info.cached_msg_max_offset,
+            locked: info.locked,
+            dropped: info.droped,
+            last_pull_timestamp: format_timestamp(info.last_pull_timestamp as i64),
+        })
+        .collect()
+}
+
+async fn reset_offset(
+    admin: &DefaultMQAdminExt,
+    args: &AdminArgs,
+    reset_args: ResetOffsetArgs,
+) -> Result<()> {
+    let topic = CheetahString::from(reset_args.topic.as_str());
+    let group = CheetahString::from(reset_args.group.as_str());
+    let rows = match (reset_args.offset, reset_args.timestamp.as_deref()) {
+        (Some(offset), _) => {
+            let (Some(broker), Some(queue_id)) = (reset_args.broker, reset_args.queue_id) else {
+                return Err(AdminCommandError::InvalidArgument(
+                    "--offset requires --broker and --queue-id".to_string(),
+                ));
+            };
+            let cluster_info = admin.examine_broker_cluster_info().await?;
+            let broker_name = broker_name_of(&cluster_info, &broker).ok_or_else(|| {
+                AdminCommandError::InvalidArgument(format!(
+                    "broker {} is not found in the cluster",
+                    broker
+                ))
+            })?;
+            admin
+                .reset_offset_by_queue_id(
+                    broker.as_str().into(),
+                    group,
+                    topic.clone(),
+                    queue_id,
+                    offset,
+                )
+                .await?;
+            vec![ResetOffsetRow {
+                topic: topic.to_string(),
+                broker_name,
+                queue_id,
+                offset,
+            }]
+        }
+        (None, Some(timestamp)) => {
+            let timestamp = parse_timestamp(timestamp)?;
+            let offset_table = admin
+                .reset_offset_by_timestamp(
+                    reset_args.cluster.map(CheetahString::from),
+                    topic,
+                    group,
+                    timestamp,
+                    reset_args.force,
+                )
+                .await?;
+            let mut offset_table: Vec<_> = offset_table.into_iter().collect();
+            offset_table.sort_by(|(a, _), (b, _)| a.cmp(b));
+            offset_table
+                .into_iter()
+                .map(|(mq, offset)| ResetOffsetRow {
+                    topic: mq.get_topic().to_string(),
+                    broker_name: mq.get_broker_name().to_string(),
+                    queue_id: mq.get_queue_id(),
+                    offset,
+                })
+                .collect()
+        }
+        (None, None) => {
+            return Err(AdminCommandError::InvalidArgument(
+                "either --timestamp or --offset is required".to_string(),
+            ))
+        }
+    };
+    print_rows(args, &rows)
+}
+
+async fn update_group(
+    admin: &DefaultMQAdminExt,
+    args: &AdminArgs,
+    config_args: GroupConfigArgs,
+) -> Result<()> {
+    let group = CheetahString::from(config_args.group.as_str());
+    let broker_addrs = target_broker_addrs(
+        admin,
+        config_args.cluster.as_deref(),
+        config_args.broker.as_deref(),
+    )
+    .await?;
+    let mut rows = Vec::with_capacity(broker_addrs.len());
+    for broker_addr in broker_addrs {
+        let mut config = match admin
+            .examine_subscription_group_config(broker_addr.clone(), group.clone())
+            .await
+        {
+            Ok(config) => config,
+            // Groups that do not exist on the broker yet start from the default config.
+            Err(error) if is_group_not_exist(&error) => SubscriptionGroupConfig::new(group.clone()),
+            Err(error) => return Err(error.into()),
+        };
+        apply_group_config(&mut config, &config_args);
+        admin
+            .create_and_update_subscription_group_config(broker_addr.clone(), config.clone())
+            .await?;
+        rows.push(GroupConfigRow {
+            broker_addr: broker_addr.to_string(),
+            group: config.group_name().to_string(),
+            consume_enable: config.consume_enable(),
+            consume_broadcast_enable: config.consume_broadcast_enable(),
+            consume_message_orderly: config.consume_message_orderly(),
+            retry_queue_nums: config.retry_queue_nums(),
+            retry_max_times: config.retry_max_times(),
+            broker_id: config.broker_id(),
+        });
+    }
+    print_rows(args, &rows)
+}
+
+fn is_group_not_exist(error: &ToolsError) -> bool {
+    matches!(
+        error,
+        ToolsError::MQClientError(MQClientError::MQClientBrokerError(err))
+            if err.response_code() == ResponseCode::SubscriptionGroupNotExist as i32
+    )
+}
+
+/// Finds the name of the broker serving `broker_addr`.
+fn broker_name_of(cluster_info: &ClusterInfo, broker_addr: &str) -> Option<String> {
+    cluster_info
+        .broker_addr_table
+        .as_ref()?
+        .values()
+        .find(|broker_data| {
+            broker_data
+                .broker_addrs()
+                .values()
+                .any(|addr| addr == broker_addr)
+        })
+        .map(|broker_data| broker_data.broker_name().to_string())
+}
+
+fn apply_group_config(config: &mut SubscriptionGroupConfig, config_args: &GroupConfigArgs) {
+    if let Some(value) = config_args.consume_enable {
+        config.set_consume_enable(value);
+    }
+    if let Some(value) = config_args.consume_from_min_enable {
+        config.set_consume_from_min_enable(value);
+    }
+    if let Some(value) = config_args.consume_broadcast_enable {
+        config.set_consume_broadcast_enable(value);
+    }
+    if let Some(value) = config_args.consume_message_orderly {
+        config.set_consume_message_orderly(value);
+    }
+    if let Some(value) = config_args.retry_queue_nums {
+        config.set_retry_queue_nums(value);
+    }
+    if let Some(value) = config_args.retry_max_times {
+        config.set_retry_max_times(value);
+    }
+    if let Some(value) = config_args.broker_id {
+        config.set_broker_id(value);
+    }
+    if let Some(value) = config_args.which_broker_when_consume_slowly {
+        config.set_which_broker_when_consume_slowly(value);
+    }
+    if let Some(value) = config_args.notify_consumer_ids_changed_enable {
+        config.set_notify_consumer_ids_changed_enable(value);
+    }
+}
+
+/// Parses a reset timestamp, either milliseconds since the epoch or `now`.
+fn parse_timestamp(timestamp: &str) -> Result<u64> {
+    if timestamp.eq_ignore_ascii_case("now") {
+        return Ok(get_current_millis());
+    }
+    timestamp.parse().map_err(|_| {
+        AdminCommandError::InvalidArgument(format!(
+            "timestamp must be milliseconds or `now`, but got {}",
+            timestamp
+        ))
+    })
+}
+
+fn format_timestamp(timestamp: i64) -> String {
+    if timestamp > 0 {
+        time_millis_to_human_string2(timestamp)
+    } else {
+        String::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rocketmq_client_rust::client_error::MQBrokerErr;
+    use rocketmq_remoting::protocol::route::route_data_view::BrokerData;
+
+    use super::*;
+
+    fn progress_row(broker_name: &str, queue_id: i32, diff: i64) -> ProgressRow {
+        ProgressRow {
+            topic: String::from("TopicTest"),
+            broker_name: broker_name.to_string(),
+            queue_id,
+            broker_offset: diff,
+            consumer_offset: 0,
+            diff,
+            last_timestamp: String::new(),
+        }
+    }
+
+    fn queues(rows: &[ProgressRow]) -> Vec<(&str, i32)> {
+        rows.iter()
+            .map(|row| (row.broker_name.as_str(), row.queue_id))
+            .collect()
+    }
+
+    #[test]
+    fn sorts_progress_rows_by_queue_or_lag() {
+        let mut rows = vec![
+            progress_row("broker-b", 0, 5),
+            progress_row("broker-a", 1, 10),
+            progress_row("broker-a", 0, 5),
+        ];
+        sort_progress_rows(&mut rows, false);
+        assert_eq!(
+            queues(&rows),
+            [("broker-a", 0), ("broker-a", 1), ("broker-b", 0)]
+        );
+
+        sort_progress_rows(&mut rows, true);
+        assert_eq!(
+            queues(&rows),
+            [("broker-a", 1), ("broker-a", 0), ("broker-b", 0)]
+        );
+    }
+
+    #[test]
+    fn only_missing_groups_fall_back_to_defaults() {
+        let broker_error = |code: ResponseCode| {
+            ToolsError::from(MQClientError::MQClientBrokerError(MQBrokerErr::new(
+                code as i32,
+                "error",
+            )))
+        };
+        assert!(is_group_not_exist(&broker_error(
+            ResponseCode::SubscriptionGroupNotExist
+        )));
+        assert!(!is_group_not_exist(&broker_error(
+            ResponseCode::NoPermission
+        )));
+        assert!(!is_group_not_exist(&ToolsError::NotStarted));
+    }
+
+    #[test]
+    fn finds_broker_name_by_address() {
+        let broker_data = BrokerData::new(
+            CheetahString::from_static_str("DefaultCluster"),
+            CheetahString::from_static_str("broker-a"),
+            HashMap::from([
+                (0, CheetahString::from_static_str("127.0.0.1:10911")),
+                (1, CheetahString::from_static_str("127.0.0.1:10921")),
+            ]),
+            None,
+        );
+        let cluster_info = ClusterInfo::new(
+            Some(HashMap::from([(
+                CheetahString::from_static_str("broker-a"),
+                broker_data,
+            )])),
+            None,
+        );
+        assert_eq!(
+            broker_name_of(&cluster_info, "127.0.0.1:10921").as_deref(),
+            Some("broker-a")
+        );
+        assert_eq!(broker_name_of(&cluster_info, "127.0.0.1:10931"), None);
+        assert_eq!(
+            broker_name_of(&ClusterInfo::default(), "127.0.0.1:10911"),
+            None
+        );
+    }
+
+    #[test]
+    fn parses_reset_timestamps() {
+        assert_eq!(parse_timestamp("1700000000000").unwrap(), 1_700_000_000_000);
+        assert!(parse_timestamp("NOW").unwrap() > 0);
+        assert!(matches!(
+            parse_timestamp("yesterday"),
+            Err(AdminCommandError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn formats_unset_timestamps_as_empty() {
+        assert_eq!(format_timestamp(0), "");
+        assert_eq!(format_timestamp(-1), "");
+        assert!(!format_timestamp(1_700_000_000_000).is_empty());
+    }
+}
@@ -0,0 +1,820 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+mod trace;
+mod transfer;
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use cheetah_string::CheetahString;
+use clap::Subcommand;
+use rocketmq_common::common::message::message_client_id_setter::MessageClientIDSetter;
+use rocketmq_common::common::message::message_ext::MessageExt;
+use rocketmq_common::common::message::message_queue::MessageQueue;
+use rocketmq_common::common::message::MessageTrait;
+use rocketmq_common::common::topic::TopicValidator;
+use rocketmq_common::UtilAll::bytes_to_string;
+use rocketmq_common::UtilAll::time_millis_to_human_string2;
+use rocketmq_tools::admin::default_mq_admin_ext::DefaultMQAdminExt;
+use rocketmq_tools::admin::mq_admin_ext_async::MQAdminExt;
+use serde::Serialize;
+use tabled::Table;
+use tabled::Tabled;
+
+use crate::admin::message::transfer::ExportFormat;
+use crate::admin::message::transfer::ExportOptions;
+use crate::admin::message::transfer::ImportOptions;
+use crate::admin::print_rows;
+use crate::admin::start_admin;
+use crate::admin::AdminArgs;
+use crate::admin::AdminCommandError;
+use crate::admin::Result;
+
+/// Messages pulled per request by `print-queue`.
+const PULL_BATCH_SIZE: i32 = 32;
+
+#[derive(Debug, Subcommand)]
+pub enum MessageCommands {
+    #[command(
+        arg_required_else_help = true,
+        about = "query a message by its offset message id or unique message id"
+    )]
+    ById {
+        #[arg(short = 't', long, value_name = "TOPIC", help = "topic name")]
+        topic: String,
+
+        #[arg(
+            short = 'i',
+            long,
+            value_name = "MSG_ID",
+            help = "offset or unique message id"
+        )]
+        msg_id: String,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        about = "query messages by key from the index of every broker of the topic"
+    )]
+    ByKey {
+        #[arg(short = 't', long, value_name = "TOPIC", help = "topic name")]
+        topic: String,
+
+        #[arg(short = 'k', long, value_name = "KEY", help = "message key")]
+        key: String,
+
+        #[arg(
+            long,
+            value_name = "MILLIS",
+            default_value_t = 0,
+            help = "only messages stored at or after this time"
+        )]
+        begin: i64,
+
+        #[arg(
+            long,
+            value_name = "MILLIS",
+            default_value_t = i64::MAX,
+            help = "only messages stored before this time"
+        )]
+        end: i64,
+
+        #[arg(
+            long,
+            value_name = "NUM",
+            default_value_t = 64,
+            help = "max messages returned by each broker"
+        )]
+        max_num: i32,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        about = "query the message at an offset of a queue"
+    )]
+    ByOffset {
+        #[arg(short = 't', long, value_name = "TOPIC", help = "topic name")]
+        topic: String,
+
+        #[arg(short = 'b', long, value_name = "BROKER_NAME", help = "broker name")]
+        broker_name: String,
+
+        #[arg(short = 'q', long, value_name = "QUEUE_ID", help = "queue id")]
+        queue_id: i32,
+
+        #[arg(short = 'o', long, value_name = "OFFSET", help = "queue offset")]
+        offset: i64,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        about = "print the messages of the queues of a topic"
+    )]
+    PrintQueue {
+        #[arg(short = 't', long, value_name = "TOPIC", help = "topic name")]
+        topic: String,
+
+        #[arg(
+            short = 'b',
+            long,
+            value_name = "BROKER_NAME",
+            help = "only print queues of this broker"
+        )]
+        broker_name: Option<String>,
+
+        #[arg(
+            short = 'q',
+            long,
+            value_name = "QUEUE_ID",
+            help = "only print this queue"
+        )]
+        queue_id: Option<i32>,
+
+        #[arg(
+            long,
+            value_name = "OFFSET",
+            help = "start at this queue offset instead of the min offset of each queue"
+        )]
+        from_offset: Option<i64>,
+
+        #[arg(
+            long,
+            value_name = "COUNT",
+            default_value_t = 32,
+            help = "max messages printed per queue"
+        )]
+        max_count: i64,
+
+        #[arg(long, default_value_t = false, help = "also print the message bodies")]
+        print_body: bool,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        about = "push a stored message to one client of a consumer group and show how it was \
+                 consumed"
+    )]
+    ResendToConsumer {
+        #[arg(short = 't', long, value_name = "TOPIC", help = "topic name")]
+        topic: String,
+
+        #[arg(
+            short = 'i',
+            long,
+            value_name = "MSG_ID",
+            help = "offset or unique message id"
+        )]
+        msg_id: String,
+
+        #[arg(short = 'g', long, value_name = "GROUP", help = "consumer group name")]
+        group: String,
+
+        #[arg(
+            short = 'c',
+            long,
+            value_name = "CLIENT_ID",
+            help = "client id, see `group connection`"
+        )]
+        client_id: String,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        about = "export the messages of a topic, or of some queues, to a self-describing file"
+    )]
+    Export {
+        #[arg(short = 't', long, value_name = "TOPIC", help = "topic name")]
+        topic: String,
+
+        #[arg(
+            short = 'f',
+            long,
+            value_name = "FILE",
+            help = "file the messages are written to"
+        )]
+        file: PathBuf,
+
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = ExportFormat::Json,
+            help = "json writes JSON Lines, binary keeps the store encoding of every message"
+        )]
+        format: ExportFormat,
+
+        #[arg(
+            short = 'b',
+            long,
+            value_name = "BROKER_NAME",
+            help = "only export queues of this broker"
+        )]
+        broker_name: Option<String>,
+
+        #[arg(
+            short = 'q',
+            long,
+            value_name = "QUEUE_ID",
+            value_delimiter = ',',
+            help = "only export these queues, eg: 0,1,3"
+        )]
+        queue_ids: Vec<i32>,
+
+        #[arg(
+            long,
+            value_name = "OFFSET",
+            help = "first queue offset exported, defaults to the min offset of each queue"
+        )]
+        begin_offset: Option<i64>,
+
+        #[arg(
+            long,
+            value_name = "OFFSET",
+            help = "queue offset the export stops before, defaults to the max offset of each queue"
+        )]
+        end_offset: Option<i64>,
+
+        #[arg(
+            long,
+            value_name = "MILLIS",
+            help = "only messages stored at or after this time"
+        )]
+        begin_timestamp: Option<i64>,
+
+        #[arg(
+            long,
+            value_name = "MILLIS",
+            help = "only messages stored before this time"
+        )]
+        end_timestamp: Option<i64>,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        about = "replay the messages of an export file into a topic"
+    )]
+    Import {
+        #[arg(
+            short = 'f',
+            long,
+            value_name = "FILE",
+            help = "file written by `message export`"
+        )]
+        file: PathBuf,
+
+        #[arg(
+            short = 't',
+            long,
+            value_name = "TOPIC",
+            help = "topic the messages are sent to, defaults to the exported topic"
+        )]
+        topic: Option<String>,
+
+        #[arg(
+            short = 'g',
+            long,
+            value_name = "GROUP",
+            default_value = "TOOLS_IMPORT_PRODUCER_GROUP",
+            help = "producer group used to send the messages"
+        )]
+        producer_group: String,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "keep the original born and store timestamps in the BORN_TIMESTAMP and \
+                    ORIGIN_STORE_TIMESTAMP properties"
+        )]
+        keep_timestamps: bool,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        about = "show the send, consume and transaction trace of a message and whether every \
+                 consumer group consumed it"
+    )]
+    Trace {
+        #[arg(
+            short = 'i',
+            long,
+            value_name = "MSG_ID",
+            help = "unique message id, or offset message id when the topic is given"
+        )]
+        msg_id: String,
+
+        #[arg(
+            short = 't',
+            long,
+            value_name = "TOPIC",
+            help = "topic of the message, defaults to the topic of its send trace"
+        )]
+        topic: Option<String>,
+
+        #[arg(
+            long,
+            value_name = "TOPIC",
+            default_value = TopicValidator::RMQ_SYS_TRACE_TOPIC,
+            help = "topic the trace records are stored in"
+        )]
+        trace_topic: String,
+    },
+}
+
+/// Everything worth knowing about a stored message, in the order `by-id` prints it.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageView {
+    offset_msg_id: String,
+    unique_msg_id: Option<String>,
+    topic: String,
+    tags: Option<String>,
+    keys: Option<String>,
+    queue_id: i32,
+    queue_offset: i64,
+    commit_log_offset: i64,
+    reconsume_times: i32,
+    born_timestamp: String,
+    store_timestamp: String,
+    born_host: String,
+    store_host: String,
+    sys_flag: i32,
+    body_crc: u32,
+    properties: BTreeMap<String, String>,
+    body_encoding: &'static str,
+    body: String,
+    trace_topic: &'static str,
+    trace_key: String,
+}
+
+impl MessageView {
+    fn new(message: &MessageExt) -> Self {
+        let unique_msg_id = MessageClientIDSetter::get_uniq_id(message).map(|id| id.to_string());
+        let (body_encoding, body) = render_body(message.get_body().map(|body| body.as_ref()));
+        // Trace records are indexed in the trace topic by the unique id of the message.
+        let trace_key = unique_msg_id
+            .clone()
+            .unwrap_or_else(|| message.msg_id().to_string());
+        MessageView {
+            offset_msg_id: message.msg_id().to_string(),
+            unique_msg_id,
+            topic: message.get_topic().to_string(),
+            tags: message.get_tags().map(|tags| tags.to_string()),
+            keys: message.get_keys().map(|keys| keys.to_string()),
+            queue_id: message.queue_id(),
+            queue_offset: message.queue_offset(),
+            commit_log_offset: message.commit_log_offset(),
+            reconsume_times: message.reconsume_times(),
+            born_timestamp: time_millis_to_human_string2(message.born_timestamp()),
+            store_timestamp: time_millis_to_human_string2(message.store_timestamp()),
+            born_host: message.born_host().to_string(),
+            store_host: message.store_host().to_string(),
+            sys_flag: message.sys_flag(),
+            body_crc: message.body_crc(),
+            properties: message
+                .get_properties()
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            body_encoding,
+            body,
+            trace_topic: TopicValidator::RMQ_SYS_TRACE_TOPIC,
+            trace_key,
+        }
+    }
+
+    fn print(&self) {
+        let fields = [
+            ("OffsetID", self.offset_msg_id.clone()),
+            ("UniqueID", self.unique_msg_id.clone().unwrap_or_default()),
+            ("Topic", self.topic.clone()),
+            ("Tags", self.tags.clone().unwrap_or_default()),
+            ("Keys", self.keys.clone().unwrap_or_default()),
+            ("Queue ID", self.queue_id.to_string()),
+            ("Queue Offset", self.queue_offset.to_string()),
+            ("CommitLog Offset", self.commit_log_offset.to_string()),
+            ("Reconsume Times", self.reconsume_times.to_string()),
+            ("Born Timestamp", self.born_timestamp.clone()),
+            ("Store Timestamp", self.store_timestamp.clone()),
+            ("Born Host", self.born_host.clone()),
+            ("Store Host", self.store_host.clone()),
+            ("System Flag", self.sys_flag.to_string()),
+            ("Body CRC", self.body_crc.to_string()),
+            (
+                "Trace",
+                format!(
+                    "message trace -i {} --trace-topic {}",
+                    self.trace_key, self.trace_topic
+                ),
+            ),
+        ];
+        let rows: Vec<_> = fields
+            .into_iter()
+            .map(|(name, value)| FieldRow {
+                name: name.to_string(),
+                value,
+            })
+            .chain(self.properties.iter().map(|(key, value)| FieldRow {
+                name: format!("Property {}", key),
+                value: value.clone(),
+            }))
+            .collect();
+        println!("{}", Table::new(rows));
+        println!("Message Body ({}):\n{}", self.body_encoding, self.body);
+    }
+}
+
+#[derive(Tabled)]
+struct FieldRow {
+    #[tabled(rename = "Field")]
+    name: String,
+    #[tabled(rename = "Value")]
+    value: String,
+}
+
+#[derive(Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageRow {
+    #[tabled(rename = "Broker")]
+    broker: String,
+    #[tabled(rename = "Queue Id")]
+    queue_id: i32,
+    #[tabled(rename = "Queue Offset")]
+    queue_offset: i64,
+    #[tabled(rename = "Offset Msg Id")]
+    offset_msg_id: String,
+    #[tabled(rename = "Unique Msg Id")]
+    unique_msg_id: String,
+    #[tabled(rename = "Tags")]
+    tags: String,
+    #[tabled(rename = "Keys")]
+    keys: String,
+    #[tabled(rename = "Store Timestamp")]
+    store_timestamp: String,
+    #[tabled(rename = "Body")]
+    body: String,
+}
+
+impl MessageRow {
+    fn new(broker: &str, message: &MessageExt, with_body: bool) -> Self {
+        let body = message.get_body().map(|body| body.as_ref());
+        MessageRow {
+            broker: broker.to_string(),
+            queue_id: message.queue_id(),
+            queue_offset: message.queue_offset(),
+            offset_msg_id: message.msg_id().to_string(),
+            unique_msg_id: MessageClientIDSetter::get_uniq_id(message)
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+            tags: message
+                .get_tags()
+                .map(|tags| tags.to_string())
+                .unwrap_or_default(),
+            keys: message
+                .get_keys()
+                .map(|keys| keys.to_string())
+                .unwrap_or_default(),
+            store_timestamp: time_millis_to_human_string2(message.store_timestamp()),
+            body: if with_body {
+                render_body(body).1
+            } else {
+                format!("{} bytes", body.map_or(0, |body| body.len()))
+            },
+        }
+    }
+}
+
+#[derive(Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConsumeResultRow {
+    #[tabled(rename = "Consume Result")]
+    consume_result: String,
+    #[tabled(rename = "Remark")]
+    remark: String,
+    #[tabled(rename = "Spent Millis")]
+    spent_time_mills: u64,
+    #[tabled(rename = "Orderly")]
+    order: bool,
+    #[tabled(rename = "Auto Commit")]
+    auto_commit: bool,
+}
+
+pub async fn execute_message_command(args: AdminArgs, command: MessageCommands) -> Result<()> {
+    // Imports only need a producer, an admin client would stay unused.
+    if let MessageCommands::Import {
+        file,
+        topic,
+        producer_group,
+        keep_timestamps,
+    } = command
+    {
+        let options = ImportOptions {
+            topic,
+            producer_group,
+            keep_timestamps,
+        };
+        return transfer::import_messages(&args, options, &file).await;
+    }
+    let mut admin = start_admin(&args).await?;
+    let result = run(&admin, &args, command).await;
+    MQAdminExt::shutdown(&mut admin).await;
+    result
+}
+
+async fn run(admin: &DefaultMQAdminExt, args: &AdminArgs, command: MessageCommands) -> Result<()> {
+    match command {
+        MessageCommands::ById { topic, msg_id } => {
+            let message = admin.view_message(topic.into(), msg_id.into()).await?;
+            print_message_view(args, &MessageView::new(&message))
+        }
+        MessageCommands::ByKey {
+            topic,
+            key,
+            begin,
+            end,
+            max_num,
+        } => {
+            let mut messages = admin
+                .query_message_by_key(topic.into(), key.into(), max_num, begin, end)
+                .await?;
+            messages.sort_by_key(|message| message.store_timestamp());
+            if args.json {
+                let views: Vec<_> = messages.iter().map(MessageView::new).collect();
+                println!("{}", serde_json::to_string_pretty(&views)?);
+                return Ok(());
+            }
+            let rows: Vec<_> = messages
+                .iter()
+                .map(|message| MessageRow::new(&message.store_host().to_string(), message, false))
+                .collect();
+            print_rows(args, &rows)
+        }
+        MessageCommands::ByOffset {
+            topic,
+            broker_name,
+            queue_id,
+            offset,
+        } => {
+            let mq = MessageQueue::from_parts(topic, broker_name, queue_id);
+            let message = pull_messages(admin, &mq, offset, 1)
+                .await?
+                .into_iter()
+                .next()
+                .ok_or_else(|| {
+                    AdminCommandError::InvalidArgument(format!(
+                        "no message at offset {} of {}",
+                        offset, mq
+                    ))
+                })?;
+            print_message_view(args, &MessageView::new(&message))
+        }
+        MessageCommands::PrintQueue {
+            topic,
+            broker_name,
+            queue_id,
+            from_offset,
+            max_count,
+            print_body,
+        } => {
+            print_queue(
+                admin,
+                args,
+                topic,
+                broker_name,
+                queue_id,
+                from_offset,
+                max_count,
+                print_body,
+            )
+            .await
+        }
+        MessageCommands::ResendToConsumer {
+            topic,
+            msg_id,
+            group,
+            client_id,
+        } => {
+            let result = admin
+                .consume_message_directly(
+                    group.into(),
+                    client_id.into(),
+                    topic.into(),
+                    msg_id.into(),
+                )
+                .await?;
+            let row = ConsumeResultRow {
+                consume_result: result
+                    .consume_result()
+                    .map(|consume_result| consume_result.to_string())
+                    .unwrap_or_default(),
+                remark: result
+                    .remark()
+                    .as_ref()
+                    .map(|remark| remark.to_string())
+                    .unwrap_or_default(),
+                spent_time_mills: result.spent_time_mills(),
+                order: result.order(),
+                auto_commit: result.auto_commit(),
+            };
+            print_rows(args, &[row])
+        }
+        MessageCommands::Export {
+            topic,
+            file,
+            format,
+            broker_name,
+            queue_ids,
+            begin_offset,
+            end_offset,
+            begin_timestamp,
+            end_timestamp,
+        } => {
+            let options = ExportOptions {
+                topic,
+                broker_name,
+                queue_ids,
+                begin_offset,
+                end_offset,
+                begin_timestamp,
+                end_timestamp,
+                format,
+            };
+            transfer::export_messages(admin, args, options, &file).await
+        }
+        MessageCommands::Trace {
+            msg_id,
+            topic,
+            trace_topic,
+        } => trace::trace_message(admin, args, msg_id, topic, trace_topic).await,
+        MessageCommands::Import { .. } => unreachable!("imports are run without an admin client"),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn print_queue(
+    admin: &DefaultMQAdminExt,
+    args: &AdminArgs,
+    topic: String,
+    broker_name: Option<String>,
+    queue_id: Option<i32>,
+    from_offset: Option<i64>,
+    max_count: i64,
+    print_body: bool,
+) -> Result<()> {
+    let topic_stats = admin
+        .examine_topic_stats(CheetahString::from(topic.as_str()), None)
+        .await?;
+    let mut queues: Vec<_> = topic_stats
+        .get_offset_table()
+        .into_iter()
+        .filter(|(mq, _)| {
+            broker_name
+                .as_deref()
+                .map_or(true, |broker_name| mq.get_broker_name() == broker_name)
+                && queue_id.map_or(true, |queue_id| mq.get_queue_id() == queue_id)
+        })
+        .collect();
+    queues.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut messages = Vec::new();
+    for (mq, topic_offset) in queues {
+        let mut offset = from_offset
+            .unwrap_or(topic_offset.get_min_offset())
+            .max(topic_offset.get_min_offset());
+        let end_offset = topic_offset
+            .get_max_offset()
+            .min(offset.saturating_add(max_count));
+        while offset < end_offset {
+            let batch_size = (end_offset - offset).min(PULL_BATCH_SIZE as i64) as i32;
+            let pulled = pull_messages(admin, &mq, offset, batch_size).await?;
+            let Some(last) = pulled.last() else {
+                break;
+            };
+            offset = last.queue_offset() + 1;
+            messages.extend(
+                pulled
+                    .into_iter()
+                    .filter(|message| message.queue_offset() < end_offset)
+                    .map(|message| (mq.get_broker_name().clone(), message)),
+            );
+        }
+    }
+
+    if args.json {
+        let views: Vec<_> = messages
+            .iter()
+            .map(|(_, message)| MessageView::new(message))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&views)?);
+        return Ok(());
+    }
+    let rows: Vec<_> = messages
+        .iter()
+        .map(|(broker_name, message)| MessageRow::new(broker_name, message, print_body))
+        .collect();
+    print_rows(args, &rows)
+}
+
+async fn pull_messages(
+    admin: &DefaultMQAdminExt,
+    mq: &MessageQueue,
+    offset: i64,
+    max_nums: i32,
+) -> Result<Vec<MessageExt>> {
+    let pull_result = admin
+        .pull_message_by_offset(mq.clone(), offset, max_nums)
+        .await?;
+    Ok(pull_result
+        .msg_found_list
+        .unwrap_or_default()
+        .into_iter()
+        .map(|message| message.as_ref().clone())
+        .collect())
+}
+
+fn print_message_view(args: &AdminArgs, view: &MessageView) -> Result<()> {
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(view)?);
+    } else {
+        view.print();
+    }
+    Ok(())
+}
+
+/// Renders a message body as text when it is valid UTF-8, as hex otherwise.
+fn render_body(body: Option<&[u8]>) -> (&'static str, String) {
+    let body = body.unwrap_or_default();
+    match std::str::from_utf8(body) {
+        Ok(text) => ("utf-8", text.to_string()),
+        Err(_) => ("hex", bytes_to_string(body)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use rocketmq_common::common::message::MessageConst;
+
+    use super::*;
+
+    const OFFSET_MSG_ID: &str = "7F00000100002A9F0000000000000000";
+    const UNIQUE_MSG_ID: &str = "7F0000010D6C18B4AAC2764F2D6C0000";
+
+    fn message(body: &[u8]) -> MessageExt {
+        let mut message = MessageExt::default();
+        message.set_msg_id(CheetahString::from_static_str(OFFSET_MSG_ID));
+        message.set_body(Bytes::copy_from_slice(body));
+        message
+    }
+
+    #[test]
+    fn renders_text_bodies_as_utf8_and_others_as_hex() {
+        assert_eq!(
+            render_body(Some("hello".as_bytes())),
+            ("utf-8", String::from("hello"))
+        );
+        assert_eq!(
+            render_body(Some(&[0xff, 0x00, 0x1a])),
+            ("hex", String::from("FF001A"))
+        );
+        assert_eq!(render_body(None), ("utf-8", String::new()));
+    }
+
+    #[test]
+    fn traces_by_unique_id_and_falls_back_to_offset_id() {
+        let mut message = message(b"hello");
+        let view = MessageView::new(&message);
+        assert_eq!(view.offset_msg_id, OFFSET_MSG_ID);
+        assert_eq!(view.unique_msg_id, None);
+        assert_eq!(view.trace_key, OFFSET_MSG_ID);
+
+        message.put_property(
+            CheetahString::from_static_str(MessageConst::PROPERTY_UNIQ_CLIENT_MESSAGE_ID_KEYIDX),
+            CheetahString::from_static_str(UNIQUE_MSG_ID),
+        );
+        let view = MessageView::new(&message);
+        assert_eq!(view.unique_msg_id.as_deref(), Some(UNIQUE_MSG_ID));
+        assert_eq!(view.trace_key, UNIQUE_MSG_ID);
+        assert_eq!(view.body_encoding, "utf-8");
+        assert_eq!(view.body, "hello");
+    }
+
+    #[test]
+    fn message_rows_show_the_body_size_unless_asked_for_the_body() {
+        let message = message(b"hello");
+        assert_eq!(MessageRow::new("broker-a", &message, false).body, "5 bytes");
+        assert_eq!(MessageRow::new("broker-a", &message, true).body, "hello");
+    }
+}
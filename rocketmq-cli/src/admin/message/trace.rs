@@ -0,0 +1,214 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+//! Message trace queries, the records clients sent to the trace topic about a message, grouped
+//! into the producer timeline and one timeline per consumer group, next to the consume
+//! progress the brokers report for every group subscribed to the topic.
+
+use std::collections::BTreeMap;
+
+use cheetah_string::CheetahString;
+use rocketmq_client_rust::trace::trace_type::TraceType;
+use rocketmq_client_rust::trace::trace_view::TraceView;
+use rocketmq_common::common::message::message_client_id_setter::MessageClientIDSetter;
+use rocketmq_common::UtilAll::time_millis_to_human_string2;
+use rocketmq_tools::admin::default_mq_admin_ext::DefaultMQAdminExt;
+use rocketmq_tools::admin::mq_admin_ext_async::MQAdminExt;
+use serde::Serialize;
+use tabled::Table;
+use tabled::Tabled;
+
+use crate::admin::AdminArgs;
+use crate::admin::Result;
+
+/// Everything known about the path of a message, `--json` prints it as is.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageTrace {
+    msg_id: String,
+    topic: Option<String>,
+    producer: Vec<TraceRow>,
+    consumer_groups: Vec<GroupTrace>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GroupTrace {
+    consumer_group: String,
+    track_type: Option<String>,
+    exception_desc: Option<String>,
+    records: Vec<TraceRow>,
+}
+
+#[derive(Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TraceRow {
+    #[tabled(rename = "Type")]
+    trace_type: String,
+    #[tabled(rename = "Group")]
+    group: String,
+    #[tabled(rename = "Client Host")]
+    client_host: String,
+    #[tabled(rename = "Time")]
+    time: String,
+    #[tabled(rename = "Retry Times")]
+    retry_times: i32,
+    #[tabled(rename = "Cost Millis")]
+    cost_time: i64,
+    #[tabled(rename = "Status")]
+    status: String,
+}
+
+impl TraceRow {
+    fn new(view: &TraceView) -> Self {
+        TraceRow {
+            trace_type: view
+                .trace_type
+                .map(|trace_type| trace_type.to_string())
+                .unwrap_or_default(),
+            group: view.group_name.to_string(),
+            client_host: view.client_host.to_string(),
+            time: time_millis_to_human_string2(view.time_stamp),
+            retry_times: view.retry_times,
+            cost_time: view.cost_time,
+            status: view.status.to_string(),
+        }
+    }
+}
+
+/// Prints the trace of the message `msg_id`. Without `topic` the topic is taken from the send
+/// record, the consume progress is skipped when the message cannot be found.
+pub(super) async fn trace_message(
+    admin: &DefaultMQAdminExt,
+    args: &AdminArgs,
+    msg_id: String,
+    topic: Option<String>,
+    trace_topic: String,
+) -> Result<()> {
+    let trace_topic = CheetahString::from_string(trace_topic);
+    // Trace records are keyed by the unique id, an offset id is only resolvable with a topic.
+    let mut message = None;
+    let mut trace_key = CheetahString::from_string(msg_id);
+    if let Some(topic) = &topic {
+        let found = admin
+            .view_message(topic.as_str().into(), trace_key.clone())
+            .await?;
+        if let Some(uniq_id) = MessageClientIDSetter::get_uniq_id(&found) {
+            trace_key = uniq_id;
+        }
+        message = Some(found);
+    }
+
+    let views = admin
+        .query_message_trace_by_id(trace_topic, trace_key.clone())
+        .await?;
+    let topic = topic.or_else(|| {
+        views
+            .iter()
+            .find(|view| view.trace_type == Some(TraceType::Pub) && !view.topic.is_empty())
+            .map(|view| view.topic.to_string())
+    });
+    if message.is_none() {
+        if let Some(topic) = &topic {
+            match admin
+                .view_message(topic.as_str().into(), trace_key.clone())
+                .await
+            {
+                Ok(found) => message = Some(found),
+                Err(e) => eprintln!(
+                    "Failed to look message {} up in topic {}, consume progress is not reported: \
+                     {}",
+                    trace_key, topic, e
+                ),
+            }
+        }
+    }
+
+    let mut groups: BTreeMap<String, GroupTrace> = BTreeMap::new();
+    if let Some(message) = message {
+        for track in admin.message_track_detail(message).await? {
+            let consumer_group = track.consumer_group.to_string();
+            groups.insert(
+                consumer_group.clone(),
+                GroupTrace {
+                    consumer_group,
+                    track_type: Some(track.track_type.to_string()),
+                    exception_desc: track.exception_desc.map(|desc| desc.to_string()),
+                    records: Vec::new(),
+                },
+            );
+        }
+    }
+    let mut producer = Vec::new();
+    for view in &views {
+        match view.trace_type {
+            Some(TraceType::SubBefore) | Some(TraceType::SubAfter) => {
+                let consumer_group = view.group_name.to_string();
+                groups
+                    .entry(consumer_group.clone())
+                    .or_insert_with(|| GroupTrace {
+                        consumer_group,
+                        track_type: None,
+                        exception_desc: None,
+                        records: Vec::new(),
+                    })
+                    .records
+                    .push(TraceRow::new(view));
+            }
+            _ => producer.push(TraceRow::new(view)),
+        }
+    }
+    let trace = MessageTrace {
+        msg_id: trace_key.to_string(),
+        topic,
+        producer,
+        consumer_groups: groups.into_values().collect(),
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&trace)?);
+    } else {
+        print_trace(&trace);
+    }
+    Ok(())
+}
+
+fn print_trace(trace: &MessageTrace) {
+    println!(
+        "Message {} of topic {}",
+        trace.msg_id,
+        trace.topic.as_deref().unwrap_or("<unknown>")
+    );
+    if trace.producer.is_empty() {
+        println!("\nProducer: no trace records");
+    } else {
+        println!("\nProducer:\n{}", Table::new(&trace.producer));
+    }
+    for group in &trace.consumer_groups {
+        let mut title = format!("\nConsumer group {}", group.consumer_group);
+        if let Some(track_type) = &group.track_type {
+            title.push_str(&format!(" [{}]", track_type));
+        }
+        if let Some(exception_desc) = &group.exception_desc {
+            title.push_str(&format!(" {}", exception_desc));
+        }
+        if group.records.is_empty() {
+            println!("{}: no trace records", title);
+        } else {
+            println!("{}:\n{}", title, Table::new(&group.records));
+        }
+    }
+}
@@ -0,0 +1,920 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+//! Export of topic messages to portable files and their import into another topic.
+//!
+//! An export file starts with an [`ExportHeader`] describing the source topic and the exported
+//! offset ranges. JSON exports are JSON Lines: the header line is followed by one
+//! [`ExportedMessage`] per line. Binary exports start with [`BINARY_MAGIC`] and the length
+//! prefixed header, followed by the messages in the store encoding of `MessageExt`, every record
+//! starting with its total size like in a commit log.
+//!
+//! Imports keep keys, tags and user properties, broker assigned properties and the unique
+//! message id are dropped.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+
+use bytes::Buf;
+use bytes::BufMut;
+use bytes::Bytes;
+use bytes::BytesMut;
+use cheetah_string::CheetahString;
+use clap::ValueEnum;
+use rocketmq_client_rust::producer::default_mq_producer::DefaultMQProducer;
+use rocketmq_client_rust::producer::mq_producer::MQProducer;
+use rocketmq_common::common::message::message_decoder;
+use rocketmq_common::common::message::message_ext::MessageExt;
+use rocketmq_common::common::message::message_queue::MessageQueue;
+use rocketmq_common::common::message::message_single::Message;
+use rocketmq_common::common::message::MessageConst;
+use rocketmq_common::common::message::MessageTrait;
+use rocketmq_common::common::message::MessageVersion;
+use rocketmq_common::common::sys_flag::message_sys_flag::MessageSysFlag;
+use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_common::UtilAll::string_to_bytes;
+use rocketmq_tools::admin::default_mq_admin_ext::DefaultMQAdminExt;
+use rocketmq_tools::admin::mq_admin_ext_async::MQAdminExt;
+use rocketmq_tools::tools_error::ToolsError;
+use serde::Deserialize;
+use serde::Serialize;
+use tabled::Tabled;
+
+use super::pull_messages;
+use super::render_body;
+use super::PULL_BATCH_SIZE;
+use crate::admin::print_rows;
+use crate::admin::AdminArgs;
+use crate::admin::AdminCommandError;
+use crate::admin::Result;
+
+/// First bytes of a binary export, JSON exports start with the header line instead.
+const BINARY_MAGIC: &[u8; 8] = b"RMQMSGEX";
+const FORMAT_NAME: &str = "rocketmq-message-export";
+const FORMAT_VERSION: u32 = 1;
+
+/// Largest binary header or record read, the default max message size of brokers and producers
+/// plus room for the fixed fields, the topic and the properties.
+const MAX_RECORD_SIZE: usize = 1024 * 1024 * 4 + 1024 * 128;
+/// Smallest binary record, a message with IPv4 hosts and no body, topic or properties.
+const MIN_RECORD_SIZE: usize =
+    message_decoder::SYSFLAG_POSITION + 4 + 8 + 8 + 8 + 8 + 4 + 8 + 4 + 1 + 2;
+
+/// Holds the original store timestamp of an imported message when timestamps are kept, the born
+/// timestamp goes to [`MessageConst::PROPERTY_BORN_TIMESTAMP`].
+const PROPERTY_ORIGIN_STORE_TIMESTAMP: &str = "ORIGIN_STORE_TIMESTAMP";
+
+/// Properties assigned by brokers and consumers, replaying them would redirect, delay or hide
+/// the imported messages. Imported messages are new messages of the target topic, so they also
+/// get new unique ids from the producer.
+const NON_REPLAYABLE_PROPERTIES: &[&str] = &[
+    MessageConst::PROPERTY_UNIQ_CLIENT_MESSAGE_ID_KEYIDX,
+    MessageConst::PROPERTY_MIN_OFFSET,
+    MessageConst::PROPERTY_MAX_OFFSET,
+    MessageConst::PROPERTY_CLUSTER,
+    MessageConst::PROPERTY_MSG_REGION,
+    MessageConst::PROPERTY_TRACE_SWITCH,
+    MessageConst::PROPERTY_REAL_TOPIC,
+    MessageConst::PROPERTY_REAL_QUEUE_ID,
+    MessageConst::PROPERTY_RETRY_TOPIC,
+    MessageConst::PROPERTY_CONSUME_START_TIMESTAMP,
+    MessageConst::PROPERTY_DELAY_TIME_LEVEL,
+    MessageConst::PROPERTY_TIMER_DELAY_LEVEL,
+    MessageConst::PROPERTY_TIMER_DELAY_MS,
+    MessageConst::PROPERTY_TIMER_DELAY_SEC,
+    MessageConst::PROPERTY_TIMER_DELIVER_MS,
+    MessageConst::PROPERTY_TIMER_ENQUEUE_MS,
+    MessageConst::PROPERTY_TIMER_DEQUEUE_MS,
+    MessageConst::PROPERTY_TIMER_OUT_MS,
+    MessageConst::PROPERTY_TIMER_ROLL_TIMES,
+    MessageConst::PROPERTY_TRANSACTION_PREPARED,
+    MessageConst::PROPERTY_TRANSACTION_PREPARED_QUEUE_OFFSET,
+    MessageConst::PROPERTY_TRANSACTION_CHECK_TIMES,
+    MessageConst::PROPERTY_PRODUCER_GROUP,
+    MessageConst::PROPERTY_POP_CK,
+    MessageConst::PROPERTY_POP_CK_OFFSET,
+    MessageConst::PROPERTY_FIRST_POP_TIME,
+    MessageConst::PROPERTY_INNER_MULTI_DISPATCH,
+    MessageConst::PROPERTY_INNER_MULTI_QUEUE_OFFSET,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// JSON Lines, readable and editable, bodies are kept as text or hex.
+    Json,
+    /// The store encoding of every message, re-encoded from the pulled messages with their
+    /// properties and body as the broker returned them.
+    Binary,
+}
+
+/// Which messages of a topic are exported.
+#[derive(Debug)]
+pub struct ExportOptions {
+    pub topic: String,
+    pub broker_name: Option<String>,
+    pub queue_ids: Vec<i32>,
+    pub begin_offset: Option<i64>,
+    pub end_offset: Option<i64>,
+    pub begin_timestamp: Option<i64>,
+    pub end_timestamp: Option<i64>,
+    pub format: ExportFormat,
+}
+
+/// How exported messages are replayed.
+#[derive(Debug)]
+pub struct ImportOptions {
+    pub topic: Option<String>,
+    pub producer_group: String,
+    pub keep_timestamps: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportHeader {
+    format: String,
+    version: u32,
+    encoding: ExportFormat,
+    topic: String,
+    exported_at: u64,
+    begin_timestamp: Option<i64>,
+    end_timestamp: Option<i64>,
+    queues: Vec<QueueRange>,
+}
+
+/// The offsets `[begin_offset, end_offset)` exported from a queue.
+#[derive(Debug, Clone, Serialize, Deserialize, Tabled)]
+#[serde(rename_all = "camelCase")]
+struct QueueRange {
+    #[tabled(rename = "Broker")]
+    broker_name: String,
+    #[tabled(rename = "Queue Id")]
+    queue_id: i32,
+    #[tabled(rename = "Begin Offset")]
+    begin_offset: i64,
+    #[tabled(rename = "End Offset")]
+    end_offset: i64,
+}
+
+/// A message of a JSON export, tags and keys are part of the properties.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedMessage {
+    topic: String,
+    broker_name: String,
+    queue_id: i32,
+    queue_offset: i64,
+    commit_log_offset: i64,
+    offset_msg_id: String,
+    flag: i32,
+    sys_flag: i32,
+    born_timestamp: i64,
+    born_host: String,
+    store_timestamp: i64,
+    store_host: String,
+    reconsume_times: i32,
+    body_crc: u32,
+    properties: BTreeMap<String, String>,
+    body_encoding: String,
+    body: String,
+}
+
+impl ExportedMessage {
+    fn new(broker_name: &str, message: &MessageExt) -> Self {
+        let (body_encoding, body) = render_body(message.get_body().map(|body| body.as_ref()));
+        ExportedMessage {
+            topic: message.get_topic().to_string(),
+            broker_name: broker_name.to_string(),
+            queue_id: message.queue_id(),
+            queue_offset: message.queue_offset(),
+            commit_log_offset: message.commit_log_offset(),
+            offset_msg_id: message.msg_id().to_string(),
+            flag: message.get_flag(),
+            sys_flag: message.sys_flag(),
+            born_timestamp: message.born_timestamp(),
+            born_host: message.born_host().to_string(),
+            store_timestamp: message.store_timestamp(),
+            store_host: message.store_host().to_string(),
+            reconsume_times: message.reconsume_times(),
+            body_crc: message.body_crc(),
+            properties: message
+                .get_properties()
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            body_encoding: body_encoding.to_string(),
+            body,
+        }
+    }
+
+    fn body(&self) -> Result<Vec<u8>> {
+        match self.body_encoding.as_str() {
+            "utf-8" => Ok(self.body.clone().into_bytes()),
+            // string_to_bytes reads anything that is not a hex digit as zero
+            "hex" => string_to_bytes(self.body.as_str())
+                .filter(|_| {
+                    self.body.len() % 2 == 0 && self.body.bytes().all(|b| b.is_ascii_hexdigit())
+                })
+                .ok_or_else(|| {
+                    invalid_file(format!(
+                        "invalid hex body of message {}",
+                        self.offset_msg_id
+                    ))
+                }),
+            encoding => Err(invalid_file(format!(
+                "unknown body encoding {} of message {}",
+                encoding, self.offset_msg_id
+            ))),
+        }
+    }
+}
+
+/// Writes the messages of the selected queues to `path`.
+pub(super) async fn export_messages(
+    admin: &DefaultMQAdminExt,
+    args: &AdminArgs,
+    options: ExportOptions,
+    path: &Path,
+) -> Result<()> {
+    let ranges = queue_ranges(admin, &options).await?;
+    let header = ExportHeader {
+        format: FORMAT_NAME.to_string(),
+        version: FORMAT_VERSION,
+        encoding: options.format,
+        topic: options.topic.clone(),
+        exported_at: get_current_millis(),
+        begin_timestamp: options.begin_timestamp,
+        end_timestamp: options.end_timestamp,
+        queues: ranges.clone(),
+    };
+    let mut writer = BufWriter::new(File::create(path)?);
+    let header_bytes = serde_json::to_vec(&header)?;
+    match options.format {
+        ExportFormat::Json => {
+            writer.write_all(&header_bytes)?;
+            writer.write_all(b"\n")?;
+        }
+        ExportFormat::Binary => {
+            writer.write_all(BINARY_MAGIC)?;
+            writer.write_all(&(header_bytes.len() as u32).to_be_bytes())?;
+            writer.write_all(&header_bytes)?;
+        }
+    }
+
+    let mut rows = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        let mq = MessageQueue::from_parts(
+            options.topic.as_str(),
+            range.broker_name.as_str(),
+            range.queue_id,
+        );
+        let mut exported = 0;
+        let mut offset = range.begin_offset;
+        while offset < range.end_offset {
+            let batch_size = (range.end_offset - offset).min(PULL_BATCH_SIZE as i64) as i32;
+            let pulled = pull_messages(admin, &mq, offset, batch_size).await?;
+            let Some(last) = pulled.last() else {
+                break;
+            };
+            offset = last.queue_offset() + 1;
+            for mut message in pulled {
+                if message.queue_offset() >= range.end_offset
+                    || !in_time_range(&options, message.store_timestamp())
+                {
+                    continue;
+                }
+                match options.format {
+                    ExportFormat::Json => {
+                        serde_json::to_writer(
+                            &mut writer,
+                            &ExportedMessage::new(&range.broker_name, &message),
+                        )?;
+                        writer.write_all(b"\n")?;
+                    }
+                    ExportFormat::Binary => {
+                        writer.write_all(&encode_record(&mut message)?)?;
+                    }
+                }
+                exported += 1;
+            }
+        }
+        rows.push(ExportRow { range, exported });
+    }
+    writer.flush()?;
+
+    print_rows(args, &rows)?;
+    if !args.json {
+        let total: u64 = rows.iter().map(|row| row.exported).sum();
+        println!(
+            "Exported {} messages of topic {} to {}",
+            total,
+            options.topic,
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Replays the messages of an export file into a topic, in the order of the file.
+pub(super) async fn import_messages(
+    args: &AdminArgs,
+    options: ImportOptions,
+    path: &Path,
+) -> Result<()> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let binary = reader.fill_buf()?.starts_with(BINARY_MAGIC);
+    let header = if binary {
+        read_binary_header(&mut reader)?
+    } else {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        serde_json::from_str::<ExportHeader>(&line)
+            .map_err(|e| invalid_file(format!("invalid header line: {}", e)))?
+    };
+    if header.format != FORMAT_NAME || header.version > FORMAT_VERSION {
+        return Err(invalid_file(format!(
+            "unsupported format {} version {}",
+            header.format, header.version
+        )));
+    }
+    let topic = options.topic.clone().unwrap_or(header.topic.clone());
+
+    let mut builder = DefaultMQProducer::builder().producer_group(options.producer_group.as_str());
+    if let Some(namesrv) = args.namesrv.as_deref() {
+        builder = builder.name_server_addr(namesrv);
+    }
+    let mut producer = builder.build();
+    producer.start().await.map_err(ToolsError::from)?;
+    let result = replay(&mut producer, &mut reader, binary, &topic, &options).await;
+    producer.shutdown().await;
+    let row = result?;
+
+    let (sent, failed) = (row.sent, row.failed);
+    print_rows(args, &[row])?;
+    if failed > 0 {
+        return Err(AdminCommandError::InvalidArgument(format!(
+            "{} of {} messages could not be sent to topic {}",
+            failed,
+            sent + failed,
+            topic
+        )));
+    }
+    Ok(())
+}
+
+async fn replay(
+    producer: &mut DefaultMQProducer,
+    reader: &mut impl BufRead,
+    binary: bool,
+    topic: &str,
+    options: &ImportOptions,
+) -> Result<ImportRow> {
+    let mut row = ImportRow {
+        topic: topic.to_string(),
+        sent: 0,
+        failed: 0,
+    };
+    let mut line = String::new();
+    loop {
+        let message = if binary {
+            let Some(message) = read_binary_record(reader)? else {
+                break;
+            };
+            replayable_message(
+                topic,
+                message.get_flag(),
+                message.get_properties(),
+                message.get_body().cloned().unwrap_or_default(),
+                (message.born_timestamp(), message.store_timestamp()),
+                options.keep_timestamps,
+            )
+        } else {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                break;
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            let exported: ExportedMessage = serde_json::from_str(&line)
+                .map_err(|e| invalid_file(format!("invalid message line: {}", e)))?;
+            let properties = exported
+                .properties
+                .iter()
+                .map(|(key, value)| (key.as_str().into(), value.as_str().into()))
+                .collect();
+            replayable_message(
+                topic,
+                exported.flag,
+                &properties,
+                Bytes::from(exported.body()?),
+                (exported.born_timestamp, exported.store_timestamp),
+                options.keep_timestamps,
+            )
+        };
+        match producer.send(message).await {
+            Ok(_) => row.sent += 1,
+            Err(e) => {
+                row.failed += 1;
+                eprintln!("send message failed: {}", e);
+            }
+        }
+    }
+    Ok(row)
+}
+
+/// Builds the message sent for an exported one, keeping its flag, body, keys, tags and user
+/// properties but none of the properties the source cluster assigned.
+fn replayable_message(
+    topic: &str,
+    flag: i32,
+    properties: &HashMap<CheetahString, CheetahString>,
+    body: Bytes,
+    (born_timestamp, store_timestamp): (i64, i64),
+    keep_timestamps: bool,
+) -> Message {
+    let mut message = Message::new(topic, &[]);
+    message.set_body(body);
+    message.set_flag(flag);
+    let mut properties: HashMap<_, _> = properties
+        .iter()
+        .filter(|(key, _)| !NON_REPLAYABLE_PROPERTIES.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    if keep_timestamps {
+        properties.insert(
+            MessageConst::PROPERTY_BORN_TIMESTAMP.into(),
+            born_timestamp.to_string().into(),
+        );
+        properties.insert(
+            PROPERTY_ORIGIN_STORE_TIMESTAMP.into(),
+            store_timestamp.to_string().into(),
+        );
+    }
+    message.set_properties(properties);
+    message
+}
+
+/// Resolves the offset range of every selected queue, timestamps are searched in the queues.
+async fn queue_ranges(
+    admin: &DefaultMQAdminExt,
+    options: &ExportOptions,
+) -> Result<Vec<QueueRange>> {
+    let topic_stats = admin
+        .examine_topic_stats(CheetahString::from(options.topic.as_str()), None)
+        .await?;
+    let mut queues: Vec<_> = topic_stats
+        .get_offset_table()
+        .into_iter()
+        .filter(|(mq, _)| {
+            options
+                .broker_name
+                .as_deref()
+                .map_or(true, |broker_name| mq.get_broker_name() == broker_name)
+                && (options.queue_ids.is_empty() || options.queue_ids.contains(&mq.get_queue_id()))
+        })
+        .collect();
+    if queues.is_empty() {
+        return Err(AdminCommandError::InvalidArgument(format!(
+            "no queue of topic {} matches the selection",
+            options.topic
+        )));
+    }
+    queues.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut ranges = Vec::with_capacity(queues.len());
+    for (mq, topic_offset) in queues {
+        let (min_offset, max_offset) =
+            (topic_offset.get_min_offset(), topic_offset.get_max_offset());
+        let mut begin_offset = options.begin_offset.unwrap_or(min_offset).max(min_offset);
+        let mut end_offset = options.end_offset.unwrap_or(max_offset).min(max_offset);
+        if let Some(timestamp) = options.begin_timestamp {
+            begin_offset = begin_offset.max(
+                search_offset_by_timestamp(admin, &mq, begin_offset, end_offset, timestamp).await?,
+            );
+        }
+        if let Some(timestamp) = options.end_timestamp {
+            end_offset = end_offset.min(
+                search_offset_by_timestamp(admin, &mq, begin_offset, end_offset, timestamp).await?,
+            );
+        }
+        ranges.push(QueueRange {
+            broker_name: mq.get_broker_name().to_string(),
+            queue_id: mq.get_queue_id(),
+            begin_offset,
+            end_offset: end_offset.max(begin_offset),
+        });
+    }
+    Ok(ranges)
+}
+
+/// Finds the first offset in `[low, high)` of a message stored at or after `timestamp`, `high`
+/// if there is none. Store timestamps only grow within a queue, so a binary search pulling one
+/// message per step is enough.
+async fn search_offset_by_timestamp(
+    admin: &DefaultMQAdminExt,
+    mq: &MessageQueue,
+    mut low: i64,
+    mut high: i64,
+    timestamp: i64,
+) -> Result<i64> {
+    while low < high {
+        let middle = low + (high - low) / 2;
+        match pull_messages(admin, mq, middle, 1).await?.first() {
+            Some(message) if message.store_timestamp() < timestamp => {
+                low = (message.queue_offset() + 1).clamp(middle + 1, high);
+            }
+            _ => high = middle,
+        }
+    }
+    Ok(low)
+}
+
+fn in_time_range(options: &ExportOptions, store_timestamp: i64) -> bool {
+    options
+        .begin_timestamp
+        .map_or(true, |begin| store_timestamp >= begin)
+        && options
+            .end_timestamp
+            .map_or(true, |end| store_timestamp < end)
+}
+
+/// Encodes a message like the store does, its leading total size matching the record length.
+fn encode_record(message: &mut MessageExt) -> Result<Bytes> {
+    if message.get_body().is_none() {
+        message.set_body(Bytes::new());
+    }
+    let encoded =
+        message_decoder::encode(message, true).map_err(|e| invalid_file(e.to_string()))?;
+    let mut record = BytesMut::from(encoded.as_ref());
+    let total_size = record.len() as i32;
+    (&mut record[..4]).put_i32(total_size);
+    Ok(record.freeze())
+}
+
+fn read_binary_header(reader: &mut impl Read) -> Result<ExportHeader> {
+    let mut magic = [0u8; 8];
+    read_exact(reader, &mut magic, "header")?;
+    let mut length = [0u8; 4];
+    read_exact(reader, &mut length, "header")?;
+    let length = u32::from_be_bytes(length) as usize;
+    if length > MAX_RECORD_SIZE {
+        return Err(invalid_file(format!("invalid header size {}", length)));
+    }
+    let mut header = vec![0u8; length];
+    read_exact(reader, &mut header, "header")?;
+    serde_json::from_slice(&header).map_err(|e| invalid_file(format!("invalid header: {}", e)))
+}
+
+fn read_binary_record(reader: &mut impl BufRead) -> Result<Option<MessageExt>> {
+    if reader.fill_buf()?.is_empty() {
+        return Ok(None);
+    }
+    let mut size = [0u8; 4];
+    read_exact(reader, &mut size, "record")?;
+    let total_size = i32::from_be_bytes(size);
+    if total_size < MIN_RECORD_SIZE as i32 || total_size as usize > MAX_RECORD_SIZE {
+        return Err(invalid_file(format!("invalid record size {}", total_size)));
+    }
+    let mut record = BytesMut::with_capacity(total_size as usize);
+    record.put_slice(&size);
+    record.resize(total_size as usize, 0);
+    read_exact(reader, &mut record[4..], "record")?;
+    let sys_flag = check_record(&record)?;
+    let mut record = record.freeze();
+    let mut message = message_decoder::decode(&mut record, true, false, false, false, false)
+        .ok_or_else(|| invalid_file("undecodable message record".to_string()))?;
+    if sys_flag & MessageSysFlag::COMPRESSED_FLAG == MessageSysFlag::COMPRESSED_FLAG {
+        let body = message.get_body().cloned().unwrap_or_default();
+        let body = MessageSysFlag::get_compression_type(sys_flag)
+            .try_decompression(&body)
+            .map_err(|e| invalid_file(format!("invalid compressed body: {}", e)))?;
+        message.set_body(body);
+    }
+    Ok(Some(message))
+}
+
+/// Checks that the fields of a record are within it, [`message_decoder::decode`] panics on
+/// malformed records. Returns the system flag of the message.
+fn check_record(record: &[u8]) -> Result<i32> {
+    let field = |position: usize, size: usize| {
+        record
+            .get(position..position + size)
+            .ok_or_else(|| invalid_file("record ends inside a message field".to_string()))
+    };
+    let magic_code = field(message_decoder::MESSAGE_MAGIC_CODE_POSITION, 4)?.get_i32();
+    let version = MessageVersion::value_of_magic_code(magic_code)
+        .map_err(|_| invalid_file(format!("invalid record magic code {}", magic_code)))?;
+    let sys_flag = field(message_decoder::SYSFLAG_POSITION, 4)?.get_i32();
+    if (sys_flag & MessageSysFlag::COMPRESSED_FLAG) == MessageSysFlag::COMPRESSED_FLAG
+        && (sys_flag & MessageSysFlag::COMPRESSION_TYPE_COMPARATOR) >> 8 > 3
+    {
+        return Err(invalid_file(format!(
+            "unknown compression of record with system flag {}",
+            sys_flag
+        )));
+    }
+    let host_size = |v6_flag: i32| if sys_flag & v6_flag == 0 { 8 } else { 20 };
+    // born timestamp, born host, store timestamp, store host, reconsume times and prepared
+    // transaction offset come before the body
+    let mut position = message_decoder::SYSFLAG_POSITION
+        + 4
+        + 8
+        + host_size(MessageSysFlag::BORNHOST_V6_FLAG)
+        + 8
+        + host_size(MessageSysFlag::STOREHOSTADDRESS_V6_FLAG)
+        + 4
+        + 8;
+    let body_length = field(position, 4)?.get_i32();
+    if body_length < 0 {
+        return Err(invalid_file(format!("invalid body size {}", body_length)));
+    }
+    position += 4 + body_length as usize;
+    let topic_length = match version {
+        MessageVersion::V1 => field(position, 1)?.get_u8() as i32,
+        MessageVersion::V2 => field(position, 2)?.get_i16() as i32,
+    };
+    if topic_length < 0 {
+        return Err(invalid_file(format!("invalid topic size {}", topic_length)));
+    }
+    position += version.get_topic_length_size();
+    std::str::from_utf8(field(position, topic_length as usize)?)
+        .map_err(|_| invalid_file("topic is not utf-8".to_string()))?;
+    position += topic_length as usize;
+    let properties_length = field(position, 2)?.get_i16();
+    if properties_length < 0 {
+        return Err(invalid_file(format!(
+            "invalid properties size {}",
+            properties_length
+        )));
+    }
+    field(position + 2, properties_length as usize)?;
+    Ok(sys_flag)
+}
+
+fn read_exact(reader: &mut impl Read, buf: &mut [u8], what: &str) -> Result<()> {
+    reader.read_exact(buf).map_err(|e| match e.kind() {
+        ErrorKind::UnexpectedEof => invalid_file(format!("truncated {}", what)),
+        _ => AdminCommandError::Io(e),
+    })
+}
+
+fn invalid_file(reason: String) -> AdminCommandError {
+    AdminCommandError::InvalidArgument(format!("invalid export file, {}", reason))
+}
+
+#[derive(Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportRow {
+    #[tabled(inline)]
+    #[serde(flatten)]
+    range: QueueRange,
+    #[tabled(rename = "Exported")]
+    exported: u64,
+}
+
+#[derive(Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportRow {
+    #[tabled(rename = "Topic")]
+    topic: String,
+    #[tabled(rename = "Sent")]
+    sent: u64,
+    #[tabled(rename = "Failed")]
+    failed: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::net::SocketAddr;
+
+    use super::*;
+
+    fn message(queue_offset: i64, body: &[u8]) -> MessageExt {
+        let mut message = MessageExt::default();
+        message.set_topic(CheetahString::from_static_str("TopicTest"));
+        message.set_body(Bytes::copy_from_slice(body));
+        message.set_tags(CheetahString::from_static_str("TagA"));
+        message.put_property(
+            CheetahString::from_static_str("region"),
+            CheetahString::from_static_str("east"),
+        );
+        message.put_property(
+            CheetahString::from_static_str(MessageConst::PROPERTY_UNIQ_CLIENT_MESSAGE_ID_KEYIDX),
+            CheetahString::from_static_str("7F0000010D6C18B4AAC2764F2D6C0000"),
+        );
+        message.set_queue_id(1);
+        message.set_queue_offset(queue_offset);
+        message.set_born_timestamp(1_700_000_000_000);
+        message.set_store_timestamp(1_700_000_000_100);
+        message.set_born_host("127.0.0.1:50000".parse::<SocketAddr>().unwrap());
+        message.set_store_host("127.0.0.1:10911".parse::<SocketAddr>().unwrap());
+        message
+    }
+
+    fn binary_export(messages: &mut [MessageExt]) -> Vec<u8> {
+        let header = serde_json::to_vec(&ExportHeader {
+            format: FORMAT_NAME.to_string(),
+            version: FORMAT_VERSION,
+            encoding: ExportFormat::Binary,
+            topic: "TopicTest".to_string(),
+            exported_at: 1_700_000_001_000,
+            begin_timestamp: None,
+            end_timestamp: None,
+            queues: vec![],
+        })
+        .unwrap();
+        let mut export = BINARY_MAGIC.to_vec();
+        export.extend_from_slice(&(header.len() as u32).to_be_bytes());
+        export.extend_from_slice(&header);
+        for message in messages {
+            export.extend_from_slice(&encode_record(message).unwrap());
+        }
+        export
+    }
+
+    fn read_invalid_record(record: Vec<u8>) -> String {
+        match read_binary_record(&mut Cursor::new(record)) {
+            Err(AdminCommandError::InvalidArgument(reason)) => reason,
+            other => panic!("unexpected result {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn json_messages_round_trip() {
+        for body in [&b"hello"[..], &[0xff, 0x00, 0x1a][..]] {
+            let line = serde_json::to_string(&ExportedMessage::new("broker-a", &message(7, body)))
+                .unwrap();
+            let exported: ExportedMessage = serde_json::from_str(&line).unwrap();
+            assert_eq!(exported.body().unwrap(), body);
+            assert_eq!(exported.topic, "TopicTest");
+            assert_eq!(exported.broker_name, "broker-a");
+            assert_eq!(exported.queue_offset, 7);
+            assert_eq!(exported.properties["TAGS"], "TagA");
+            assert_eq!(exported.properties["region"], "east");
+        }
+    }
+
+    #[test]
+    fn json_messages_with_unknown_body_encoding_are_rejected() {
+        let mut exported = ExportedMessage::new("broker-a", &message(0, b"hello"));
+        exported.body_encoding = "base64".to_string();
+        assert!(exported.body().is_err());
+        exported.body_encoding = "hex".to_string();
+        exported.body = "not hex".to_string();
+        assert!(exported.body().is_err());
+    }
+
+    #[test]
+    fn binary_exports_round_trip() {
+        let export = binary_export(&mut [message(3, b"first"), message(4, &[0xff, 0x00])]);
+        let mut reader = Cursor::new(export);
+        assert!(reader.fill_buf().unwrap().starts_with(BINARY_MAGIC));
+
+        let header = read_binary_header(&mut reader).unwrap();
+        assert_eq!(header.format, FORMAT_NAME);
+        assert_eq!(header.encoding, ExportFormat::Binary);
+        assert_eq!(header.topic, "TopicTest");
+
+        let first = read_binary_record(&mut reader).unwrap().unwrap();
+        assert_eq!(first.get_topic(), "TopicTest");
+        assert_eq!(first.queue_offset(), 3);
+        assert_eq!(first.get_body().unwrap().as_ref(), b"first");
+        assert_eq!(first.get_tags().unwrap(), "TagA");
+        assert_eq!(
+            first.get_property(&CheetahString::from_static_str("region")),
+            Some(CheetahString::from_static_str("east"))
+        );
+        assert_eq!(first.store_timestamp(), 1_700_000_000_100);
+
+        let second = read_binary_record(&mut reader).unwrap().unwrap();
+        assert_eq!(second.queue_offset(), 4);
+        assert_eq!(second.get_body().unwrap().as_ref(), &[0xff, 0x00]);
+        assert!(read_binary_record(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn replayed_messages_drop_broker_assigned_properties() {
+        let mut properties = message(0, b"hello").get_properties().clone();
+        properties.insert(
+            MessageConst::PROPERTY_REAL_TOPIC.into(),
+            "%RETRY%group".into(),
+        );
+        properties.insert(MessageConst::PROPERTY_DELAY_TIME_LEVEL.into(), "3".into());
+        let replayed = replayable_message(
+            "TargetTopic",
+            8,
+            &properties,
+            Bytes::from_static(b"hello"),
+            (1, 2),
+            false,
+        );
+        assert_eq!(replayed.get_topic(), "TargetTopic");
+        assert_eq!(replayed.get_flag(), 8);
+        assert_eq!(replayed.get_body().unwrap().as_ref(), b"hello");
+        let mut keys: Vec<_> = replayed
+            .get_properties()
+            .keys()
+            .map(|key| key.as_str())
+            .collect();
+        keys.sort_unstable();
+        assert_eq!(keys, ["TAGS", "region"]);
+    }
+
+    #[test]
+    fn replayed_messages_keep_timestamps_on_request() {
+        let replayed = replayable_message(
+            "TargetTopic",
+            0,
+            &HashMap::new(),
+            Bytes::new(),
+            (1_700_000_000_000, 1_700_000_000_100),
+            true,
+        );
+        let properties = replayed.get_properties();
+        assert_eq!(
+            properties[MessageConst::PROPERTY_BORN_TIMESTAMP],
+            "1700000000000"
+        );
+        assert_eq!(properties[PROPERTY_ORIGIN_STORE_TIMESTAMP], "1700000000100");
+    }
+
+    #[test]
+    fn truncated_exports_are_rejected() {
+        let record = encode_record(&mut message(0, b"hello")).unwrap();
+        let reason = read_invalid_record(record[..record.len() - 1].to_vec());
+        assert!(reason.contains("truncated record"), "{}", reason);
+        let reason = read_invalid_record(record[..2].to_vec());
+        assert!(reason.contains("truncated record"), "{}", reason);
+
+        let export = binary_export(&mut []);
+        assert!(read_binary_header(&mut Cursor::new(&export[..export.len() - 1])).is_err());
+    }
+
+    #[test]
+    fn records_with_invalid_sizes_are_rejected() {
+        for size in [
+            -1,
+            0,
+            4,
+            MIN_RECORD_SIZE as i32 - 1,
+            MAX_RECORD_SIZE as i32 + 1,
+        ] {
+            let reason = read_invalid_record(size.to_be_bytes().to_vec());
+            assert!(reason.contains("invalid record size"), "{}", reason);
+        }
+
+        let mut export = BINARY_MAGIC.to_vec();
+        export.extend_from_slice(&u32::MAX.to_be_bytes());
+        assert!(read_binary_header(&mut Cursor::new(export)).is_err());
+    }
+
+    #[test]
+    fn corrupt_records_are_rejected() {
+        let record = encode_record(&mut message(0, b"hello")).unwrap().to_vec();
+
+        let mut bad_magic = record.clone();
+        bad_magic[message_decoder::MESSAGE_MAGIC_CODE_POSITION] ^= 0xff;
+        let reason = read_invalid_record(bad_magic);
+        assert!(reason.contains("magic code"), "{}", reason);
+
+        let body_position = message_decoder::SYSFLAG_POSITION + 4 + 8 + 8 + 8 + 8 + 4 + 8;
+        let mut long_body = record.clone();
+        long_body[body_position..body_position + 4].copy_from_slice(&1024i32.to_be_bytes());
+        let reason = read_invalid_record(long_body);
+        assert!(reason.contains("ends inside"), "{}", reason);
+
+        let mut negative_body = record.clone();
+        negative_body[body_position..body_position + 4].copy_from_slice(&(-1i32).to_be_bytes());
+        let reason = read_invalid_record(negative_body);
+        assert!(reason.contains("invalid body size"), "{}", reason);
+
+        let mut v6_hosts = record;
+        let sys_flag = MessageSysFlag::BORNHOST_V6_FLAG | MessageSysFlag::STOREHOSTADDRESS_V6_FLAG;
+        v6_hosts[message_decoder::SYSFLAG_POSITION..message_decoder::SYSFLAG_POSITION + 4]
+            .copy_from_slice(&sys_flag.to_be_bytes());
+        assert!(read_binary_record(&mut Cursor::new(v6_hosts)).is_err());
+    }
+}
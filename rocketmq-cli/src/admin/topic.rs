@@ -0,0 +1,602 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+
+use cheetah_string::CheetahString;
+use clap::Args;
+use clap::Subcommand;
+use rocketmq_common::common::attribute::attribute_enum::EnumAttribute;
+use rocketmq_common::common::config::TopicConfig;
+use rocketmq_common::common::constant::PermName;
+use rocketmq_common::TopicAttributes;
+use rocketmq_common::UtilAll::time_millis_to_human_string2;
+use rocketmq_tools::admin::default_mq_admin_ext::DefaultMQAdminExt;
+use rocketmq_tools::admin::mq_admin_ext_async::MQAdminExt;
+use serde::Serialize;
+use tabled::Table;
+use tabled::Tabled;
+
+use crate::admin::print_message;
+use crate::admin::print_rows;
+use crate::admin::start_admin;
+use crate::admin::target_broker_addrs;
+use crate::admin::AdminArgs;
+use crate::admin::AdminCommandError;
+use crate::admin::Result;
+
+const DEFAULT_QUEUE_NUMS: u32 = 8;
+const DEFAULT_PERM: u32 = PermName::PERM_READ | PermName::PERM_WRITE;
+
+#[derive(Debug, Subcommand)]
+pub enum TopicCommands {
+    #[command(about = "list all topics, or the topics of a cluster")]
+    List {
+        #[arg(
+            short = 'c',
+            long,
+            value_name = "CLUSTER",
+            help = "only list topics of this cluster"
+        )]
+        cluster: Option<String>,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        about = "examine the route info of a topic"
+    )]
+    Route {
+        #[arg(short = 't', long, value_name = "TOPIC", help = "topic name")]
+        topic: String,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        about = "examine the queue offsets of a topic"
+    )]
+    Status {
+        #[arg(short = 't', long, value_name = "TOPIC", help = "topic name")]
+        topic: String,
+
+        #[arg(
+            short = 'b',
+            long,
+            value_name = "BROKER_ADDR",
+            help = "only examine the queues on this broker"
+        )]
+        broker: Option<String>,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        about = "create a topic on a broker or on every master of a cluster"
+    )]
+    Create(TopicConfigArgs),
+
+    #[command(
+        arg_required_else_help = true,
+        about = "update the queue numbers, perm or attributes of an existing topic"
+    )]
+    Update(TopicConfigArgs),
+
+    #[command(
+        arg_required_else_help = true,
+        about = "delete a topic from the brokers and name servers of a cluster"
+    )]
+    Delete {
+        #[arg(short = 't', long, value_name = "TOPIC", help = "topic name")]
+        topic: String,
+
+        #[arg(short = 'c', long, value_name = "CLUSTER", help = "cluster name")]
+        cluster: String,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        about = "list the clusters serving a topic"
+    )]
+    Cluster {
+        #[arg(short = 't', long, value_name = "TOPIC", help = "topic name")]
+        topic: String,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct TopicConfigArgs {
+    #[arg(short = 't', long, value_name = "TOPIC", help = "topic name")]
+    pub topic: String,
+
+    #[arg(
+        short = 'c',
+        long,
+        value_name = "CLUSTER",
+        required_unless_present = "broker",
+        conflicts_with = "broker",
+        help = "apply to every master broker of this cluster"
+    )]
+    pub cluster: Option<String>,
+
+    #[arg(
+        short = 'b',
+        long,
+        value_name = "BROKER_ADDR",
+        help = "apply to this broker only"
+    )]
+    pub broker: Option<String>,
+
+    #[arg(
+        short = 'r',
+        long,
+        value_name = "NUMS",
+        help = "read queue nums, defaults to 8 on create"
+    )]
+    pub read_queue_nums: Option<u32>,
+
+    #[arg(
+        short = 'w',
+        long,
+        value_name = "NUMS",
+        help = "write queue nums, defaults to 8 on create"
+    )]
+    pub write_queue_nums: Option<u32>,
+
+    #[arg(
+        short = 'p',
+        long,
+        value_name = "PERM",
+        help = "topic perm, 2: read only, 4: write only, 6: read and write, defaults to 6 on \
+                create"
+    )]
+    pub perm: Option<u32>,
+
+    #[arg(
+        short = 'o',
+        long,
+        value_name = "ORDER",
+        help = "whether the topic is ordered"
+    )]
+    pub order: Option<bool>,
+
+    #[arg(
+        long,
+        value_name = "TYPE",
+        help = "message type: NORMAL, FIFO, DELAY, TRANSACTION or MIXED"
+    )]
+    pub message_type: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "POLICY",
+        help = "cleanup policy: DELETE or COMPACTION"
+    )]
+    pub cleanup_policy: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "TYPE",
+        help = "consume queue type: SimpleCQ or BatchCQ, can not be changed once created"
+    )]
+    pub queue_type: Option<String>,
+}
+
+#[derive(Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TopicRow {
+    #[tabled(rename = "Topic")]
+    topic: String,
+}
+
+#[derive(Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ClusterRow {
+    #[tabled(rename = "Cluster")]
+    cluster: String,
+}
+
+#[derive(Tabled)]
+struct RouteBrokerRow {
+    #[tabled(rename = "Cluster")]
+    cluster: String,
+    #[tabled(rename = "Broker Name")]
+    broker_name: String,
+    #[tabled(rename = "Broker Id")]
+    broker_id: u64,
+    #[tabled(rename = "Address")]
+    addr: String,
+}
+
+#[derive(Tabled)]
+struct RouteQueueRow {
+    #[tabled(rename = "Broker Name")]
+    broker_name: String,
+    #[tabled(rename = "Read Queues")]
+    read_queue_nums: u32,
+    #[tabled(rename = "Write Queues")]
+    write_queue_nums: u32,
+    #[tabled(rename = "Perm")]
+    perm: String,
+    #[tabled(rename = "Sys Flag")]
+    topic_sys_flag: u32,
+}
+
+#[derive(Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TopicStatusRow {
+    #[tabled(rename = "Broker Name")]
+    broker_name: String,
+    #[tabled(rename = "Queue Id")]
+    queue_id: i32,
+    #[tabled(rename = "Min Offset")]
+    min_offset: i64,
+    #[tabled(rename = "Max Offset")]
+    max_offset: i64,
+    #[tabled(rename = "Last Updated")]
+    last_update_time: String,
+}
+
+#[derive(Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TopicConfigRow {
+    #[tabled(rename = "Broker")]
+    broker_addr: String,
+    #[tabled(rename = "Topic")]
+    topic: String,
+    #[tabled(rename = "Read Queues")]
+    read_queue_nums: u32,
+    #[tabled(rename = "Write Queues")]
+    write_queue_nums: u32,
+    #[tabled(rename = "Perm")]
+    perm: String,
+    #[tabled(rename = "Order")]
+    order: bool,
+    #[tabled(rename = "Attributes")]
+    attributes: String,
+}
+
+pub async fn execute_topic_command(args: AdminArgs, command: TopicCommands) -> Result<()> {
+    let mut admin = start_admin(&args).await?;
+    let result = run(&admin, &args, command).await;
+    MQAdminExt::shutdown(&mut admin).await;
+    result
+}
+
+async fn run(admin: &DefaultMQAdminExt, args: &AdminArgs, command: TopicCommands) -> Result<()> {
+    match command {
+        TopicCommands::List { cluster } => list_topics(admin, args, cluster).await,
+        TopicCommands::Route { topic } => examine_route(admin, args, topic).await,
+        TopicCommands::Status { topic, broker } => examine_status(admin, args, topic, broker).await,
+        TopicCommands::Create(config_args) => update_topic(admin, args, config_args, true).await,
+        TopicCommands::Update(config_args) => update_topic(admin, args, config_args, false).await,
+        TopicCommands::Delete { topic, cluster } => {
+            admin
+                .delete_topic(topic.as_str().into(), cluster.as_str().into())
+                .await?;
+            print_message(
+                args,
+                &serde_json::json!({ "topic": topic, "cluster": cluster, "deleted": true }),
+                &format!(
+                    "delete topic [{}] from cluster [{}] success.",
+                    topic, cluster
+                ),
+            )
+        }
+        TopicCommands::Cluster { topic } => {
+            let clusters: BTreeSet<_> = admin
+                .get_topic_cluster_list(topic)
+                .await?
+                .into_iter()
+                .collect();
+            let rows: Vec<_> = clusters
+                .into_iter()
+                .map(|cluster| ClusterRow {
+                    cluster: cluster.to_string(),
+                })
+                .collect();
+            print_rows(args, &rows)
+        }
+    }
+}
+
+async fn list_topics(
+    admin: &DefaultMQAdminExt,
+    args: &AdminArgs,
+    cluster: Option<String>,
+) -> Result<()> {
+    let topic_list = match cluster {
+        Some(cluster) => admin.fetch_topics_by_cluster(cluster.into()).await?,
+        None => admin.fetch_all_topic_list().await?,
+    };
+    let topics: BTreeSet<_> = topic_list.topic_list.into_iter().collect();
+    let rows: Vec<_> = topics
+        .into_iter()
+        .map(|topic| TopicRow {
+            topic: topic.to_string(),
+        })
+        .collect();
+    print_rows(args, &rows)
+}
+
+async fn examine_route(admin: &DefaultMQAdminExt, args: &AdminArgs, topic: String) -> Result<()> {
+    let route = admin.examine_topic_route_info(topic.into()).await?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&route)?);
+        return Ok(());
+    }
+
+    let mut broker_rows = Vec::new();
+    for broker_data in &route.broker_datas {
+        for (broker_id, addr) in broker_data.broker_addrs() {
+            broker_rows.push(RouteBrokerRow {
+                cluster: broker_data.cluster().to_string(),
+                broker_name: broker_data.broker_name().to_string(),
+                broker_id: *broker_id,
+                addr: addr.to_string(),
+            });
+        }
+    }
+    broker_rows.sort_by(|a, b| (&a.broker_name, a.broker_id).cmp(&(&b.broker_name, b.broker_id)));
+
+    let mut queue_datas = route.queue_datas;
+    queue_datas.sort();
+    let queue_rows: Vec<_> = queue_datas
+        .into_iter()
+        .map(|queue_data| RouteQueueRow {
+            broker_name: queue_data.broker_name.to_string(),
+            read_queue_nums: queue_data.read_queue_nums,
+            write_queue_nums: queue_data.write_queue_nums,
+            perm: PermName::perm2string(queue_data.perm),
+            topic_sys_flag: queue_data.topic_sys_flag,
+        })
+        .collect();
+
+    println!("{}", Table::new(broker_rows));
+    println!("{}", Table::new(queue_rows));
+    Ok(())
+}
+
+async fn examine_status(
+    admin: &DefaultMQAdminExt,
+    args: &AdminArgs,
+    topic: String,
+    broker: Option<String>,
+) -> Result<()> {
+    let stats = admin
+        .examine_topic_stats(topic.into(), broker.map(CheetahString::from))
+        .await?;
+    let mut offset_table: Vec<_> = stats.get_offset_table().into_iter().collect();
+    offset_table.sort_by(|(a, _), (b, _)| {
+        (a.get_broker_name(), a.get_queue_id()).cmp(&(b.get_broker_name(), b.get_queue_id()))
+    });
+    let rows: Vec<_> = offset_table
+        .into_iter()
+        .map(|(mq, offset)| TopicStatusRow {
+            broker_name: mq.get_broker_name().to_string(),
+            queue_id: mq.get_queue_id(),
+            min_offset: offset.get_min_offset(),
+            max_offset: offset.get_max_offset(),
+            last_update_time: if offset.get_last_update_timestamp() > 0 {
+                time_millis_to_human_string2(offset.get_last_update_timestamp())
+            } else {
+                String::new()
+            },
+        })
+        .collect();
+    print_rows(args, &rows)
+}
+
+async fn update_topic(
+    admin: &DefaultMQAdminExt,
+    args: &AdminArgs,
+    config_args: TopicConfigArgs,
+    create: bool,
+) -> Result<()> {
+    if let Some(perm) = config_args.perm {
+        if !PermName::is_valid(perm) {
+            return Err(AdminCommandError::InvalidArgument(format!(
+                "perm {} is not valid",
+                perm
+            )));
+        }
+    }
+    let attributes = topic_attributes(&config_args)?;
+    let broker_addrs = target_broker_addrs(
+        admin,
+        config_args.cluster.as_deref(),
+        config_args.broker.as_deref(),
+    )
+    .await?;
+
+    let topic = CheetahString::from(config_args.topic.as_str());
+    let mut rows = Vec::with_capacity(broker_addrs.len());
+    for broker_addr in broker_addrs {
+        let mut topic_config = if create {
+            TopicConfig::with_perm(
+                topic.clone(),
+                DEFAULT_QUEUE_NUMS,
+                DEFAULT_QUEUE_NUMS,
+                DEFAULT_PERM,
+            )
+        } else {
+            admin
+                .examine_topic_config(broker_addr.clone(), topic.clone())
+                .await?
+        };
+        if let Some(read_queue_nums) = config_args.read_queue_nums {
+            topic_config.read_queue_nums = read_queue_nums;
+        }
+        if let Some(write_queue_nums) = config_args.write_queue_nums {
+            topic_config.write_queue_nums = write_queue_nums;
+        }
+        if let Some(perm) = config_args.perm {
+            topic_config.perm = perm;
+        }
+        if let Some(order) = config_args.order {
+            topic_config.order = order;
+        }
+        // The broker merges these changes into the attributes it already has.
+        topic_config.attributes = attributes.clone();
+
+        admin
+            .create_and_update_topic_config(broker_addr.clone(), topic_config.clone())
+            .await?;
+        rows.push(TopicConfigRow {
+            broker_addr: broker_addr.to_string(),
+            topic: topic.to_string(),
+            read_queue_nums: topic_config.read_queue_nums,
+            write_queue_nums: topic_config.write_queue_nums,
+            perm: PermName::perm2string(topic_config.perm),
+            order: topic_config.order,
+            attributes: format_attributes(&topic_config.attributes),
+        });
+    }
+    print_rows(args, &rows)
+}
+
+/// Builds the `+key=value` attribute changes understood by the broker from the command options.
+fn topic_attributes(
+    config_args: &TopicConfigArgs,
+) -> Result<HashMap<CheetahString, CheetahString>> {
+    let options = [
+        (
+            &*TopicAttributes::TOPIC_MESSAGE_TYPE_ATTRIBUTE,
+            config_args.message_type.as_deref(),
+        ),
+        (
+            &*TopicAttributes::CLEANUP_POLICY_ATTRIBUTE,
+            config_args.cleanup_policy.as_deref(),
+        ),
+        (
+            &*TopicAttributes::QUEUE_TYPE_ATTRIBUTE,
+            config_args.queue_type.as_deref(),
+        ),
+    ];
+    let mut attributes = HashMap::new();
+    for (attribute, value) in options {
+        let Some(value) = value else {
+            continue;
+        };
+        let value = normalize_attribute_value(attribute, value)?;
+        attributes.insert(
+            CheetahString::from(format!("+{}", attribute.get_name())),
+            CheetahString::from(value),
+        );
+    }
+    Ok(attributes)
+}
+
+/// Matches `value` case-insensitively against the allowed values of `attribute`.
+fn normalize_attribute_value(attribute: &EnumAttribute, value: &str) -> Result<String> {
+    attribute
+        .get_universe()
+        .iter()
+        .find(|allowed| allowed.eq_ignore_ascii_case(value))
+        .cloned()
+        .ok_or_else(|| {
+            let mut allowed: Vec<_> = attribute.get_universe().iter().cloned().collect();
+            allowed.sort();
+            AdminCommandError::InvalidArgument(format!(
+                "{} must be one of [{}], but got {}",
+                attribute.get_name(),
+                allowed.join(", "),
+                value
+            ))
+        })
+}
+
+fn format_attributes(attributes: &HashMap<CheetahString, CheetahString>) -> String {
+    let mut attributes: Vec<_> = attributes
+        .iter()
+        .map(|(key, value)| format!("{}={}", key.trim_start_matches('+'), value))
+        .collect();
+    attributes.sort();
+    attributes.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_args() -> TopicConfigArgs {
+        TopicConfigArgs {
+            topic: String::from("TopicTest"),
+            cluster: Some(String::from("DefaultCluster")),
+            broker: None,
+            read_queue_nums: None,
+            write_queue_nums: None,
+            perm: None,
+            order: None,
+            message_type: None,
+            cleanup_policy: None,
+            queue_type: None,
+        }
+    }
+
+    #[test]
+    fn builds_attribute_changes_from_options() {
+        assert!(topic_attributes(&config_args()).unwrap().is_empty());
+
+        let mut config_args = config_args();
+        config_args.message_type = Some(String::from("fifo"));
+        config_args.cleanup_policy = Some(String::from("Compaction"));
+        let attributes = topic_attributes(&config_args).unwrap();
+        assert_eq!(attributes.len(), 2);
+        assert_eq!(
+            attributes.get(&CheetahString::from_static_str("+message.type")),
+            Some(&CheetahString::from_static_str("FIFO"))
+        );
+        assert_eq!(
+            attributes.get(&CheetahString::from_static_str("+cleanup.policy")),
+            Some(&CheetahString::from_static_str("COMPACTION"))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_attribute_values() {
+        let error = normalize_attribute_value(&TopicAttributes::QUEUE_TYPE_ATTRIBUTE, "LightCQ")
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            AdminCommandError::InvalidArgument(String::from(
+                "queue.type must be one of [BatchCQ, SimpleCQ], but got LightCQ"
+            ))
+            .to_string()
+        );
+        assert_eq!(
+            normalize_attribute_value(&TopicAttributes::QUEUE_TYPE_ATTRIBUTE, "batchcq").unwrap(),
+            "BatchCQ"
+        );
+    }
+
+    #[test]
+    fn formats_attributes_sorted_without_prefix() {
+        let attributes = HashMap::from([
+            (
+                CheetahString::from_static_str("+queue.type"),
+                CheetahString::from_static_str("SimpleCQ"),
+            ),
+            (
+                CheetahString::from_static_str("cleanup.policy"),
+                CheetahString::from_static_str("DELETE"),
+            ),
+        ]);
+        assert_eq!(
+            format_attributes(&attributes),
+            "cleanup.policy=DELETE,queue.type=SimpleCQ"
+        );
+        assert_eq!(format_attributes(&HashMap::new()), "");
+    }
+}
@@ -14,10 +14,22 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::future::Future;
+
 use clap::Parser;
+use rocketmq_cli::admin::cluster::execute_cluster_command;
+use rocketmq_cli::admin::group::execute_group_command;
+use rocketmq_cli::admin::message::execute_message_command;
+use rocketmq_cli::admin::topic::execute_topic_command;
 use rocketmq_cli::command_line::Commands;
 use rocketmq_cli::command_line::RootCli;
+use rocketmq_cli::commit_log_check::check_commit_log;
+use rocketmq_cli::content_show::print_checkpoint;
+use rocketmq_cli::content_show::print_config;
+use rocketmq_cli::content_show::print_consume_queue;
 use rocketmq_cli::content_show::print_content;
+use rocketmq_cli::content_show::print_index_file;
+use rocketmq_cli::logic_files_rebuild::rebuild_logic_files;
 
 fn main() {
     let cli = RootCli::parse();
@@ -25,5 +37,66 @@ fn main() {
         Commands::ReadMessageLog { config, from, to } => {
             print_content(from, to, config);
         }
+        Commands::ReadConsumeQueue {
+            path,
+            from,
+            to,
+            commit_log,
+            mapped_file_size,
+        } => {
+            print_consume_queue(path, from, to, commit_log, mapped_file_size);
+        }
+        Commands::ReadIndexFile {
+            path,
+            hash_slot_num,
+            key,
+            limit,
+        } => {
+            print_index_file(path, hash_slot_num, key, limit);
+        }
+        Commands::ReadCheckpoint { path } => {
+            print_checkpoint(path);
+        }
+        Commands::ReadConfig { path } => {
+            print_config(path);
+        }
+        Commands::CheckCommitLog {
+            store_path,
+            mapped_file_size,
+            repair,
+        } => {
+            check_commit_log(store_path, mapped_file_size, repair);
+        }
+        Commands::RebuildLogicFiles {
+            store_path,
+            mapped_file_size,
+            from,
+            to,
+        } => {
+            rebuild_logic_files(store_path, mapped_file_size, from, to);
+        }
+        Commands::Topic { admin, command } => {
+            run_admin_command(execute_topic_command(admin, command));
+        }
+        Commands::Group { admin, command } => {
+            run_admin_command(execute_group_command(admin, command));
+        }
+        Commands::Message { admin, command } => {
+            run_admin_command(execute_message_command(admin, command));
+        }
+        Commands::Cluster { admin, command } => {
+            run_admin_command(execute_cluster_command(admin, command));
+        }
+    }
+}
+
+fn run_admin_command(command: impl Future<Output = rocketmq_cli::admin::Result<()>>) {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("failed to build tokio runtime");
+    if let Err(e) = runtime.block_on(command) {
+        eprintln!("{}", e);
+        std::process::exit(1);
     }
 }
@@ -16,9 +16,16 @@
  */
 use std::path::PathBuf;
 
+use clap::builder::RangedU64ValueParser;
 use clap::Parser;
 use clap::Subcommand;
 
+use crate::admin::cluster::ClusterCommands;
+use crate::admin::group::GroupCommands;
+use crate::admin::message::MessageCommands;
+use crate::admin::topic::TopicCommands;
+use crate::admin::AdminArgs;
+
 #[derive(Parser, Debug)]
 #[command(author = "mxsm", version = "0.2.0", about = "RocketMQ CLI(Rust)")]
 pub struct RootCli {
@@ -64,4 +71,242 @@ pub enum Commands {
         )]
         to: Option<u32>,
     },
+
+    #[command(
+        arg_required_else_help = true,
+        author = "mxsm",
+        version = "0.2.0",
+        about = "read consume queue file and check it against the commit log"
+    )]
+    ReadConsumeQueue {
+        #[arg(
+            short,
+            long,
+            value_name = "FILE",
+            help = "consume queue file path, like store/consumequeue/<topic>/<queueId>/<file>"
+        )]
+        path: PathBuf,
+
+        #[arg(
+            short = 'f',
+            long,
+            value_name = "FROM",
+            help = "index of the first unit to read, defaults to the first unit of the file"
+        )]
+        from: Option<u32>,
+
+        #[arg(
+            short = 't',
+            long,
+            value_name = "TO",
+            help = "index of the unit to stop reading at, defaults to the end of the file"
+        )]
+        to: Option<u32>,
+
+        #[arg(
+            short = 'c',
+            long,
+            value_name = "COMMIT_LOG_DIR",
+            help = "commit log dir, when set every unit is checked against its message"
+        )]
+        commit_log: Option<PathBuf>,
+
+        #[arg(
+            long,
+            value_name = "SIZE",
+            default_value_t = 1024 * 1024 * 1024,
+            value_parser = clap::value_parser!(u64).range(1..),
+            help = "mapped file size of the commit log"
+        )]
+        mapped_file_size: u64,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        author = "mxsm",
+        version = "0.2.0",
+        about = "read index file header, hash slots and index chains"
+    )]
+    ReadIndexFile {
+        #[arg(short, long, value_name = "FILE", help = "index file path")]
+        path: PathBuf,
+
+        #[arg(
+            long,
+            value_name = "NUM",
+            default_value_t = 5000000,
+            value_parser = RangedU64ValueParser::<usize>::new().range(1..),
+            help = "hash slot number of the index file"
+        )]
+        hash_slot_num: usize,
+
+        #[arg(
+            short,
+            long,
+            value_name = "KEY",
+            help = "dump the index chain of the key, formatted as topic#key"
+        )]
+        key: Option<String>,
+
+        #[arg(
+            short,
+            long,
+            value_name = "LIMIT",
+            default_value_t = 100,
+            help = "max number of slots or chain items to print"
+        )]
+        limit: usize,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        author = "mxsm",
+        version = "0.2.0",
+        about = "read store checkpoint file"
+    )]
+    ReadCheckpoint {
+        #[arg(short, long, value_name = "FILE", help = "checkpoint file path")]
+        path: PathBuf,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        author = "mxsm",
+        version = "0.2.0",
+        about = "read broker config file, like topics.json or consumerOffset.json"
+    )]
+    ReadConfig {
+        #[arg(short, long, value_name = "FILE", help = "config file path")]
+        path: PathBuf,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        author = "mxsm",
+        version = "0.2.0",
+        about = "verify commit log CRC and optionally repair the corrupted store"
+    )]
+    CheckCommitLog {
+        #[arg(
+            short = 's',
+            long,
+            value_name = "STORE_PATH",
+            help = "store root dir of the broker, the broker must be stopped"
+        )]
+        store_path: PathBuf,
+
+        #[arg(
+            long,
+            value_name = "SIZE",
+            default_value_t = 1024 * 1024 * 1024,
+            help = "mapped file size of the commit log"
+        )]
+        mapped_file_size: usize,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "truncate commit log, consume queues and index to the last good position"
+        )]
+        repair: bool,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        author = "mxsm",
+        version = "0.2.0",
+        about = "rebuild consume queues and index files from the commit log"
+    )]
+    RebuildLogicFiles {
+        #[arg(
+            short = 's',
+            long,
+            value_name = "STORE_PATH",
+            help = "store root dir of the broker, the broker must be stopped"
+        )]
+        store_path: PathBuf,
+
+        #[arg(
+            long,
+            value_name = "SIZE",
+            default_value_t = 1024 * 1024 * 1024,
+            help = "mapped file size of the commit log"
+        )]
+        mapped_file_size: usize,
+
+        #[arg(
+            short = 'f',
+            long,
+            value_name = "FROM",
+            default_value_t = 0,
+            help = "physical offset of the commit log to rebuild from"
+        )]
+        from: i64,
+
+        #[arg(
+            short = 't',
+            long,
+            value_name = "TO",
+            default_value_t = i64::MAX,
+            help = "physical offset of the commit log to rebuild to, defaults to the end of the \
+                    commit log"
+        )]
+        to: i64,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        author = "mxsm",
+        version = "0.2.0",
+        about = "topic management, like the topic commands of mqadmin"
+    )]
+    Topic {
+        #[command(flatten)]
+        admin: AdminArgs,
+
+        #[command(subcommand)]
+        command: TopicCommands,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        author = "mxsm",
+        version = "0.2.0",
+        about = "consumer group management: progress, connections, offsets and configs"
+    )]
+    Group {
+        #[command(flatten)]
+        admin: AdminArgs,
+
+        #[command(subcommand)]
+        command: GroupCommands,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        author = "mxsm",
+        version = "0.2.0",
+        about = "message inspection: query by id, key or offset, print queues and resend"
+    )]
+    Message {
+        #[command(flatten)]
+        admin: AdminArgs,
+
+        #[command(subcommand)]
+        command: MessageCommands,
+    },
+
+    #[command(
+        arg_required_else_help = true,
+        author = "mxsm",
+        version = "0.2.0",
+        about = "cluster operations: brokers, broker configs, write permission and name server kv"
+    )]
+    Cluster {
+        #[command(flatten)]
+        admin: AdminArgs,
+
+        #[command(subcommand)]
+        command: ClusterCommands,
+    },
 }
@@ -0,0 +1,82 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::broker::broker_config::BrokerConfig;
+use rocketmq_store::config::message_store_config::MessageStoreConfig;
+use rocketmq_store::log_file::commit_log_verifier::CommitLogVerifier;
+use tabled::Table;
+use tabled::Tabled;
+
+pub fn check_commit_log(store_path: PathBuf, mapped_file_size: usize, repair: bool) {
+    let message_store_config = Arc::new(MessageStoreConfig {
+        store_path_root_dir: CheetahString::from(store_path.to_string_lossy().to_string()),
+        mapped_file_size_commit_log: mapped_file_size,
+        ..MessageStoreConfig::default()
+    });
+    let verifier = CommitLogVerifier::new(message_store_config);
+    let report = verifier.verify();
+    let table = report
+        .files
+        .iter()
+        .map(|file| CommitLogFilePrint {
+            file_name: file.file_name.clone(),
+            messages: file.message_count,
+            last_good_offset: file.last_good_offset,
+            first_corrupted_offset: file
+                .first_corrupted_offset
+                .map_or_else(|| "-".to_string(), |offset| offset.to_string()),
+            reason: file
+                .corruption
+                .map_or_else(|| "OK".to_string(), |kind| kind.to_string()),
+        })
+        .collect::<Vec<_>>();
+    println!("{}", Table::new(table));
+
+    match report.first_corrupted_offset() {
+        None => println!("commit log is clean"),
+        Some(offset) if repair => {
+            let repaired = verifier.repair(
+                Arc::new(BrokerConfig::default()),
+                Arc::new(parking_lot::Mutex::new(HashMap::new())),
+                &report,
+            );
+            println!(
+                "repair commit log to offset {}: {}",
+                offset,
+                if repaired { "OK" } else { "Failed" }
+            );
+        }
+        Some(offset) => println!(
+            "commit log is corrupted at offset {}, run with --repair to truncate it",
+            offset
+        ),
+    }
+}
+
+#[derive(Tabled)]
+struct CommitLogFilePrint {
+    file_name: String,
+    messages: u64,
+    last_good_offset: i64,
+    first_corrupted_offset: String,
+    reason: String,
+}
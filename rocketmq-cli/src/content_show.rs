@@ -14,30 +14,50 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
-
+use std::collections::HashMap;
 use std::fs;
+use std::fs::File;
+use std::path::Path;
 use std::path::PathBuf;
 
 use bytes::Buf;
+use bytes::Bytes;
 use cheetah_string::CheetahString;
+use memmap2::Mmap;
+use rocketmq_common::common::config::TopicConfig;
+use rocketmq_common::common::constant::PermName;
+use rocketmq_common::common::hasher::string_hasher::JavaStringHasher;
 use rocketmq_common::common::message::message_decoder;
-use rocketmq_store::log_file::mapped_file::default_mapped_file_impl::DefaultMappedFile;
-use rocketmq_store::log_file::mapped_file::MappedFile;
+use rocketmq_common::common::message::message_ext::MessageExt;
+use rocketmq_common::CRC32Utils::crc32;
+use rocketmq_common::UtilAll::time_millis_to_human_string2;
+use rocketmq_remoting::protocol::body::topic_info_wrapper::topic_config_wrapper::TopicConfigSerializeWrapper;
+use rocketmq_remoting::protocol::subscription::subscription_group_config::SubscriptionGroupConfig;
+use rocketmq_remoting::protocol::DataVersion;
+use rocketmq_store::index::index_header::INDEX_HEADER_SIZE;
+use rocketmq_store::queue::single_consume_queue::CQ_STORE_UNIT_SIZE;
+use serde::Deserialize;
 use tabled::Table;
 use tabled::Tabled;
 
+const HASH_SLOT_SIZE: usize = 4;
+const INDEX_SIZE: usize = 20;
+const CHECKPOINT_SIZE: usize = 40;
+
 pub fn print_content(from: Option<u32>, to: Option<u32>, path: Option<PathBuf>) {
     if path.is_none() {
         println!("path is none");
         return;
     }
-    let path_buf = path.unwrap().into_os_string();
-    let file_metadata = fs::metadata(path_buf.clone()).unwrap();
-    println!("file size: {}B", file_metadata.len());
-    let mapped_file = DefaultMappedFile::new(
-        CheetahString::from(path_buf.to_string_lossy().to_string()),
-        file_metadata.len(),
-    );
+    let path = path.unwrap();
+    let mapped_file = match map_file(&path) {
+        Ok(mapped_file) => mapped_file,
+        Err(e) => {
+            println!("read commit log file {} failed: {}", path.display(), e);
+            return;
+        }
+    };
+    println!("file size: {}B", mapped_file.len());
     // read message number
     let mut counter = 0;
     let form = from.unwrap_or_default();
@@ -51,7 +71,7 @@ pub fn print_content(from: Option<u32>, to: Option<u32>, path: Option<PathBuf>)
         if counter >= to {
             break;
         }
-        let bytes = mapped_file.get_bytes(current_pos, 4);
+        let bytes = get_bytes(&mapped_file, current_pos, 4);
         if bytes.is_none() {
             break;
         }
@@ -65,27 +85,814 @@ pub fn print_content(from: Option<u32>, to: Option<u32>, path: Option<PathBuf>)
             current_pos += size as usize;
             continue;
         }
-        let mut msg_bytes = mapped_file.get_bytes(current_pos, size as usize);
+        let mut msg_bytes =
+            get_bytes(&mapped_file, current_pos, size as usize).map(Bytes::copy_from_slice);
         current_pos += size as usize;
         if msg_bytes.is_none() {
             break;
         }
-        let message =
-            message_decoder::decode(msg_bytes.as_mut().unwrap(), true, false, false, false, true);
+        // decode without the CRC check so that a corrupted body is still printed and flagged
+        let message = message_decoder::decode(
+            msg_bytes.as_mut().unwrap(),
+            true,
+            false,
+            false,
+            false,
+            false,
+        );
         //parse message bytes and print it
         match message {
             None => {}
             Some(value) => {
-                table.push(MessagePrint {
-                    message_id: value.msg_id.to_string(),
-                });
+                table.push(MessagePrint::from(&value));
+            }
+        }
+    }
+    println!("{}", Table::new(table));
+}
+
+/// Prints the units of a consume queue file. When `commit_log_dir` is given, every unit is
+/// checked against the message it points to in the commit log.
+pub fn print_consume_queue(
+    path: PathBuf,
+    from: Option<u32>,
+    to: Option<u32>,
+    commit_log_dir: Option<PathBuf>,
+    mapped_file_size: u64,
+) {
+    let mapped_file = match map_file(&path) {
+        Ok(mapped_file) => mapped_file,
+        Err(e) => {
+            println!("read consume queue file {} failed: {}", path.display(), e);
+            return;
+        }
+    };
+    println!("file size: {}B", mapped_file.len());
+    // consume queue files live in <store>/consumequeue/<topic>/<queueId>/<fileFromOffset>
+    let file_from_offset = file_name_offset(&path).unwrap_or_default();
+    let queue_dir = path.parent();
+    let queue_id = queue_dir
+        .and_then(|dir| dir.file_name())
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.parse::<i32>().ok());
+    let topic = queue_dir
+        .and_then(|dir| dir.parent())
+        .and_then(|dir| dir.file_name())
+        .map(|name| name.to_string_lossy().to_string());
+
+    let mut commit_log = commit_log_dir.map(|dir| CommitLogReader::new(dir, mapped_file_size));
+    let unit_size = CQ_STORE_UNIT_SIZE as usize;
+    let from = from.unwrap_or_default() as usize;
+    let to = to.map_or(usize::MAX, |value| value as usize);
+    let mut table = vec![];
+    let mut inconsistent = 0;
+    for index in from..to {
+        let mut unit = match get_bytes(&mapped_file, index * unit_size, unit_size) {
+            Some(unit) => unit,
+            None => break,
+        };
+        let phy_offset = unit.get_i64();
+        let size = unit.get_i32();
+        let tags_code = unit.get_i64();
+        if phy_offset == 0 && size == 0 && tags_code == 0 {
+            break;
+        }
+        let queue_offset = file_from_offset / CQ_STORE_UNIT_SIZE as i64 + index as i64;
+        let check = match commit_log.as_mut() {
+            None => "-".to_string(),
+            Some(reader) => {
+                let result = match reader.read(phy_offset, size) {
+                    Err(reason) => reason,
+                    Ok(message) => check_consume_queue_unit(
+                        &message,
+                        size,
+                        topic.as_deref(),
+                        queue_id,
+                        queue_offset,
+                    ),
+                };
+                if result != "OK" {
+                    inconsistent += 1;
+                }
+                result
             }
+        };
+        table.push(ConsumeQueueUnitPrint {
+            index,
+            queue_offset,
+            phy_offset,
+            size,
+            tags_code,
+            check,
+        });
+    }
+    let units = table.len();
+    println!("{}", Table::new(table));
+    if commit_log.is_some() {
+        println!(
+            "{} units checked against the commit log, {} inconsistent",
+            units, inconsistent
+        );
+    }
+}
+
+fn check_consume_queue_unit(
+    message: &MessageExt,
+    size: i32,
+    topic: Option<&str>,
+    queue_id: Option<i32>,
+    queue_offset: i64,
+) -> String {
+    if message.store_size() != size {
+        return format!("size mismatch, commit log {}", message.store_size());
+    }
+    if topic.is_some_and(|topic| topic != message.topic().as_str()) {
+        return format!("topic mismatch, commit log {}", message.topic());
+    }
+    if queue_id.is_some_and(|queue_id| queue_id != message.queue_id()) {
+        return format!("queue id mismatch, commit log {}", message.queue_id());
+    }
+    if message.queue_offset() != queue_offset {
+        return format!(
+            "queue offset mismatch, commit log {}",
+            message.queue_offset()
+        );
+    }
+    "OK".to_string()
+}
+
+/// Prints the header of an index file and its used hash slots. With `key` (`topic#key`, as it
+/// is written by the index service) the index chain of the key's slot is dumped instead.
+pub fn print_index_file(path: PathBuf, hash_slot_num: usize, key: Option<String>, limit: usize) {
+    let mapped_file = match map_file(&path) {
+        Ok(mapped_file) => mapped_file,
+        Err(e) => {
+            println!("read index file {} failed: {}", path.display(), e);
+            return;
+        }
+    };
+    let slots_end = INDEX_HEADER_SIZE + hash_slot_num * HASH_SLOT_SIZE;
+    if mapped_file.len() < slots_end {
+        println!(
+            "file size {}B is smaller than the header and {} hash slots",
+            mapped_file.len(),
+            hash_slot_num
+        );
+        return;
+    }
+    let header = IndexHeaderPrint::new(&mapped_file[..INDEX_HEADER_SIZE]);
+    let reader = IndexChainReader {
+        data: &mapped_file,
+        slots_end,
+        index_count: header.index_count,
+        begin_timestamp: header.begin_timestamp,
+    };
+    println!("{}", Table::new(vec![header]));
+
+    if let Some(key) = key {
+        let key_hash = index_key_hash(&key);
+        let slot = key_hash as usize % hash_slot_num;
+        let head = reader.slot_value(slot);
+        println!(
+            "key hash: {}, slot: {}, head index: {}",
+            key_hash, slot, head
+        );
+        let table = reader
+            .chain(head)
+            .into_iter()
+            .take(limit)
+            .map(|item| IndexItemPrint {
+                matched: item.key_hash == key_hash,
+                ..item
+            })
+            .collect::<Vec<_>>();
+        println!("{}", Table::new(table));
+        return;
+    }
+
+    let slot_bytes = &mapped_file[INDEX_HEADER_SIZE..slots_end];
+    let mut used_slots = 0;
+    let mut table = vec![];
+    for (slot, mut value) in slot_bytes.chunks_exact(HASH_SLOT_SIZE).enumerate() {
+        let head = value.get_i32();
+        if head <= 0 {
+            continue;
+        }
+        used_slots += 1;
+        if table.len() < limit {
+            table.push(IndexSlotPrint {
+                slot,
+                head_index: head,
+                chain_length: reader.chain(head).len(),
+            });
         }
     }
     println!("{}", Table::new(table));
+    println!(
+        "{} of {} hash slots are used, {} shown",
+        used_slots,
+        hash_slot_num,
+        used_slots.min(limit)
+    );
+}
+
+/// Prints the flushed timestamps and offsets recorded in the store checkpoint file.
+pub fn print_checkpoint(path: PathBuf) {
+    let content = match fs::read(&path) {
+        Ok(content) => content,
+        Err(e) => {
+            println!("read checkpoint file {} failed: {}", path.display(), e);
+            return;
+        }
+    };
+    if content.len() < CHECKPOINT_SIZE {
+        println!(
+            "file size {}B is smaller than the {}B of a checkpoint",
+            content.len(),
+            CHECKPOINT_SIZE
+        );
+        return;
+    }
+    let mut buffer = &content[..CHECKPOINT_SIZE];
+    let physic_msg_timestamp = buffer.get_u64();
+    let logics_msg_timestamp = buffer.get_u64();
+    let index_msg_timestamp = buffer.get_u64();
+    let master_flushed_offset = buffer.get_u64();
+    let confirm_phy_offset = buffer.get_u64();
+    // same as StoreCheckpoint::get_min_timestamp and get_min_timestamp_index
+    let min_timestamp = (physic_msg_timestamp.min(logics_msg_timestamp) as i64 - 1000 * 3).max(0);
+    let min_timestamp_index = (min_timestamp as u64).min(index_msg_timestamp);
+    let timestamp_row = |name: &str, value: u64| CheckpointPrint {
+        name: name.to_string(),
+        value: value.to_string(),
+        human: human_time(value as i64),
+    };
+    let offset_row = |name: &str, value: u64| CheckpointPrint {
+        name: name.to_string(),
+        value: value.to_string(),
+        human: "-".to_string(),
+    };
+    let table = vec![
+        timestamp_row("physicMsgTimestamp", physic_msg_timestamp),
+        timestamp_row("logicsMsgTimestamp", logics_msg_timestamp),
+        timestamp_row("indexMsgTimestamp", index_msg_timestamp),
+        offset_row("masterFlushedOffset", master_flushed_offset),
+        offset_row("confirmPhyOffset", confirm_phy_offset),
+        timestamp_row("minTimestamp", min_timestamp as u64),
+        timestamp_row("minTimestampIndex", min_timestamp_index),
+    ];
+    println!("{}", Table::new(table));
+}
+
+/// Prints a broker config file. The tables of `topics.json`, `subscriptionGroup.json`,
+/// `consumerOffset.json` and `delayOffset.json` are decoded, other files are pretty printed.
+pub fn print_config(path: PathBuf) {
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) => {
+            println!("read config file {} failed: {}", path.display(), e);
+            return;
+        }
+    };
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let result = match file_name.as_str() {
+        "topics.json" => print_topic_config(&content),
+        "subscriptionGroup.json" => print_subscription_group_config(&content),
+        "consumerOffset.json" | "lmqConsumerOffset.json" => print_consumer_offset(&content),
+        "delayOffset.json" => print_delay_offset(&content),
+        _ => serde_json::from_str::<serde_json::Value>(&content)
+            .and_then(|value| serde_json::to_string_pretty(&value))
+            .map(|pretty| println!("{}", pretty)),
+    };
+    if let Err(e) = result {
+        println!("decode config file {} failed: {}", path.display(), e);
+    }
+}
+
+fn print_topic_config(content: &str) -> serde_json::Result<()> {
+    let wrapper = serde_json::from_str::<TopicConfigSerializeWrapper>(content)?;
+    print_data_version(wrapper.data_version());
+    let mut topics = wrapper.topic_config_table().iter().collect::<Vec<_>>();
+    topics.sort_by_key(|(topic, _)| *topic);
+    let table = topics
+        .into_iter()
+        .map(|(topic, config)| TopicConfigPrint::new(topic, config))
+        .collect::<Vec<_>>();
+    println!("{}", Table::new(table));
+    Ok(())
+}
+
+fn print_subscription_group_config(content: &str) -> serde_json::Result<()> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct SubscriptionGroupFile {
+        #[serde(default)]
+        subscription_group_table: HashMap<CheetahString, SubscriptionGroupConfig>,
+        data_version: Option<DataVersion>,
+    }
+
+    let file = serde_json::from_str::<SubscriptionGroupFile>(content)?;
+    if let Some(data_version) = file.data_version.as_ref() {
+        print_data_version(data_version);
+    }
+    let mut groups = file.subscription_group_table.values().collect::<Vec<_>>();
+    groups.sort_by_key(|config| config.group_name());
+    let table = groups
+        .into_iter()
+        .map(|config| SubscriptionGroupPrint {
+            group: config.group_name().to_string(),
+            consume_enable: config.consume_enable(),
+            broadcast: config.consume_broadcast_enable(),
+            orderly: config.consume_message_orderly(),
+            retry_queue_nums: config.retry_queue_nums(),
+            retry_max_times: config.retry_max_times(),
+            broker_id: config.broker_id(),
+            which_broker_when_consume_slowly: config.which_broker_when_consume_slowly(),
+        })
+        .collect::<Vec<_>>();
+    println!("{}", Table::new(table));
+    Ok(())
+}
+
+fn print_consumer_offset(content: &str) -> serde_json::Result<()> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ConsumerOffsetFile {
+        #[serde(default)]
+        offset_table: HashMap<String /* topic@group */, HashMap<i32, i64>>,
+        data_version: Option<DataVersion>,
+    }
+
+    let file = serde_json::from_str::<ConsumerOffsetFile>(content)?;
+    if let Some(data_version) = file.data_version.as_ref() {
+        print_data_version(data_version);
+    }
+    let mut table = vec![];
+    for (topic_at_group, offsets) in file.offset_table {
+        let (topic, group) = topic_at_group
+            .split_once('@')
+            .unwrap_or((topic_at_group.as_str(), ""));
+        for (queue_id, offset) in offsets {
+            table.push(ConsumerOffsetPrint {
+                topic: topic.to_string(),
+                group: group.to_string(),
+                queue_id,
+                offset,
+            });
+        }
+    }
+    table.sort_by(|a, b| {
+        (a.topic.as_str(), a.group.as_str(), a.queue_id).cmp(&(
+            b.topic.as_str(),
+            b.group.as_str(),
+            b.queue_id,
+        ))
+    });
+    println!("{}", Table::new(table));
+    Ok(())
+}
+
+fn print_delay_offset(content: &str) -> serde_json::Result<()> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct DelayOffsetFile {
+        #[serde(default)]
+        offset_table: HashMap<i32 /* level */, i64>,
+        data_version: Option<DataVersion>,
+    }
+
+    let file = serde_json::from_str::<DelayOffsetFile>(content)?;
+    if let Some(data_version) = file.data_version.as_ref() {
+        print_data_version(data_version);
+    }
+    let mut table = file
+        .offset_table
+        .into_iter()
+        .map(|(level, offset)| DelayOffsetPrint { level, offset })
+        .collect::<Vec<_>>();
+    table.sort_by_key(|row| row.level);
+    println!("{}", Table::new(table));
+    Ok(())
+}
+
+fn print_data_version(data_version: &DataVersion) {
+    println!(
+        "data version: stateVersion={}, timestamp={}, counter={}",
+        data_version.get_state_version(),
+        human_time(data_version.get_timestamp()),
+        data_version.get_counter()
+    );
+}
+
+/// Opens commit log files lazily by their start offset to look up the message of a consume
+/// queue unit.
+struct CommitLogReader {
+    dir: PathBuf,
+    mapped_file_size: u64,
+    files: HashMap<i64, Mmap>,
+}
+
+impl CommitLogReader {
+    fn new(dir: PathBuf, mapped_file_size: u64) -> Self {
+        Self {
+            dir,
+            mapped_file_size,
+            files: HashMap::new(),
+        }
+    }
+
+    fn read(&mut self, phy_offset: i64, size: i32) -> Result<MessageExt, String> {
+        if phy_offset < 0 || size <= 0 {
+            return Err("invalid unit".to_string());
+        }
+        let file_from_offset = phy_offset - phy_offset % self.mapped_file_size as i64;
+        if !self.files.contains_key(&file_from_offset) {
+            let path = self.dir.join(format!("{:020}", file_from_offset));
+            let mapped_file = map_file(&path)
+                .map_err(|_| format!("commit log file {:020} not found", file_from_offset))?;
+            self.files.insert(file_from_offset, mapped_file);
+        }
+        let mapped_file = &self.files[&file_from_offset];
+        let mut bytes = get_bytes(
+            mapped_file,
+            (phy_offset - file_from_offset) as usize,
+            size as usize,
+        )
+        .map(Bytes::copy_from_slice)
+        .ok_or_else(|| "out of commit log file".to_string())?;
+        message_decoder::decode(&mut bytes, true, false, false, false, true)
+            .ok_or_else(|| "message undecodable or CRC check failed".to_string())
+    }
+}
+
+struct IndexChainReader<'a> {
+    data: &'a [u8],
+    slots_end: usize,
+    index_count: i32,
+    begin_timestamp: i64,
+}
+
+impl IndexChainReader<'_> {
+    fn slot_value(&self, slot: usize) -> i32 {
+        get_bytes(
+            self.data,
+            INDEX_HEADER_SIZE + slot * HASH_SLOT_SIZE,
+            HASH_SLOT_SIZE,
+        )
+        .map_or(0, |mut bytes| bytes.get_i32())
+    }
+
+    /// Walks an index chain from its head with the same guards as the index file lookup.
+    fn chain(&self, head: i32) -> Vec<IndexItemPrint> {
+        let mut items = vec![];
+        let mut next = head;
+        while next > 0 && next <= self.index_count && items.len() < self.index_count as usize {
+            let mut bytes = match get_bytes(
+                self.data,
+                self.slots_end + next as usize * INDEX_SIZE,
+                INDEX_SIZE,
+            ) {
+                Some(bytes) => bytes,
+                None => break,
+            };
+            let key_hash = bytes.get_i32();
+            let phy_offset = bytes.get_i64();
+            let time_diff = bytes.get_i32();
+            let prev_index = bytes.get_i32();
+            items.push(IndexItemPrint {
+                index: next,
+                key_hash,
+                phy_offset,
+                store_timestamp: human_time(self.begin_timestamp + time_diff as i64 * 1000),
+                prev_index,
+                matched: false,
+            });
+            if time_diff < 0 || prev_index == next {
+                break;
+            }
+            next = prev_index;
+        }
+        items
+    }
+}
+
+/// Maps a store file read-only, so inspecting the files of a broker never changes them.
+fn map_file(path: &Path) -> std::io::Result<Mmap> {
+    let file = File::open(path)?;
+    unsafe { Mmap::map(&file) }
+}
+
+fn get_bytes(data: &[u8], pos: usize, size: usize) -> Option<&[u8]> {
+    data.get(pos..pos.checked_add(size)?)
+}
+
+/// Same hash as the index file uses for its keys.
+fn index_key_hash(key: &str) -> i32 {
+    JavaStringHasher::new()
+        .hash_str(key)
+        .checked_abs()
+        .unwrap_or(0)
+}
+
+fn file_name_offset(path: &Path) -> Option<i64> {
+    path.file_name()?.to_str()?.parse::<i64>().ok()
+}
+
+fn display_time(timestamp: &i64) -> String {
+    human_time(*timestamp)
+}
+
+fn human_time(timestamp: i64) -> String {
+    if timestamp <= 0 {
+        return "-".to_string();
+    }
+    time_millis_to_human_string2(timestamp)
 }
 
 #[derive(Tabled)]
 struct MessagePrint {
     message_id: String,
+    topic: String,
+    queue_id: i32,
+    queue_offset: i64,
+    phy_offset: i64,
+    store_size: i32,
+    sys_flag: i32,
+    body_crc: String,
+    born_timestamp: String,
+    store_timestamp: String,
+    properties: String,
+}
+
+impl From<&MessageExt> for MessagePrint {
+    fn from(message: &MessageExt) -> Self {
+        let crc_matched = message
+            .body()
+            .map_or(true, |body| crc32(&body) == message.body_crc());
+        let mut properties = message
+            .properties()
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>();
+        properties.sort();
+        MessagePrint {
+            message_id: message.msg_id().to_string(),
+            topic: message.topic().to_string(),
+            queue_id: message.queue_id(),
+            queue_offset: message.queue_offset(),
+            phy_offset: message.commit_log_offset(),
+            store_size: message.store_size(),
+            sys_flag: message.sys_flag(),
+            body_crc: format!(
+                "{}({})",
+                message.body_crc(),
+                if crc_matched { "OK" } else { "MISMATCH" }
+            ),
+            born_timestamp: human_time(message.born_timestamp()),
+            store_timestamp: human_time(message.store_timestamp()),
+            properties: properties.join("\n"),
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct ConsumeQueueUnitPrint {
+    index: usize,
+    queue_offset: i64,
+    phy_offset: i64,
+    size: i32,
+    tags_code: i64,
+    check: String,
+}
+
+#[derive(Tabled)]
+struct IndexHeaderPrint {
+    #[tabled(display_with = "display_time")]
+    begin_timestamp: i64,
+    #[tabled(display_with = "display_time")]
+    end_timestamp: i64,
+    begin_phy_offset: i64,
+    end_phy_offset: i64,
+    hash_slot_count: i32,
+    index_count: i32,
+}
+
+impl IndexHeaderPrint {
+    /// Decodes the header like IndexHeader::load, which treats an index count below one as one.
+    fn new(mut header: &[u8]) -> Self {
+        IndexHeaderPrint {
+            begin_timestamp: header.get_i64(),
+            end_timestamp: header.get_i64(),
+            begin_phy_offset: header.get_i64(),
+            end_phy_offset: header.get_i64(),
+            hash_slot_count: header.get_i32(),
+            index_count: header.get_i32().max(1),
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct IndexSlotPrint {
+    slot: usize,
+    head_index: i32,
+    chain_length: usize,
+}
+
+#[derive(Tabled)]
+struct IndexItemPrint {
+    index: i32,
+    key_hash: i32,
+    phy_offset: i64,
+    store_timestamp: String,
+    prev_index: i32,
+    matched: bool,
+}
+
+#[derive(Tabled)]
+struct CheckpointPrint {
+    name: String,
+    value: String,
+    human: String,
+}
+
+#[derive(Tabled)]
+struct TopicConfigPrint {
+    topic: String,
+    read_queue_nums: u32,
+    write_queue_nums: u32,
+    perm: String,
+    order: bool,
+    topic_sys_flag: u32,
+    attributes: String,
+}
+
+impl TopicConfigPrint {
+    fn new(topic: &CheetahString, config: &TopicConfig) -> Self {
+        let mut attributes = config
+            .attributes
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>();
+        attributes.sort();
+        TopicConfigPrint {
+            topic: topic.to_string(),
+            read_queue_nums: config.read_queue_nums,
+            write_queue_nums: config.write_queue_nums,
+            perm: PermName::perm2string(config.perm),
+            order: config.order,
+            topic_sys_flag: config.topic_sys_flag,
+            attributes: attributes.join(","),
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct SubscriptionGroupPrint {
+    group: String,
+    consume_enable: bool,
+    broadcast: bool,
+    orderly: bool,
+    retry_queue_nums: i32,
+    retry_max_times: i32,
+    broker_id: u64,
+    which_broker_when_consume_slowly: u64,
+}
+
+#[derive(Tabled)]
+struct ConsumerOffsetPrint {
+    topic: String,
+    group: String,
+    queue_id: i32,
+    offset: i64,
+}
+
+#[derive(Tabled)]
+struct DelayOffsetPrint {
+    level: i32,
+    offset: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BufMut;
+    use rocketmq_common::common::message::MessageTrait;
+
+    use super::*;
+
+    const HASH_SLOT_NUM: usize = 4;
+
+    fn message(store_size: i32, topic: &str, queue_id: i32, queue_offset: i64) -> MessageExt {
+        let mut message = MessageExt::default();
+        message.set_store_size(store_size);
+        message.set_topic(CheetahString::from(topic));
+        message.set_queue_id(queue_id);
+        message.set_queue_offset(queue_offset);
+        message
+    }
+
+    #[test]
+    fn checks_consume_queue_unit_against_message() {
+        let message = message(100, "TopicTest", 1, 7);
+        let check = |size, topic, queue_id, queue_offset| {
+            check_consume_queue_unit(&message, size, topic, queue_id, queue_offset)
+        };
+        assert_eq!(check(100, Some("TopicTest"), Some(1), 7), "OK");
+        assert_eq!(check(100, None, None, 7), "OK");
+        assert_eq!(
+            check(90, Some("TopicTest"), Some(1), 7),
+            "size mismatch, commit log 100"
+        );
+        assert_eq!(
+            check(100, Some("Other"), Some(1), 7),
+            "topic mismatch, commit log TopicTest"
+        );
+        assert_eq!(
+            check(100, Some("TopicTest"), Some(2), 7),
+            "queue id mismatch, commit log 1"
+        );
+        assert_eq!(
+            check(100, Some("TopicTest"), Some(1), 8),
+            "queue offset mismatch, commit log 7"
+        );
+    }
+
+    /// Builds an index file whose items, given as `(key_hash, time_diff, prev_index)`, start at
+    /// index 1.
+    fn index_file(begin_timestamp: i64, items: &[(i32, i32, i32)]) -> Vec<u8> {
+        let mut data = vec![];
+        data.put_i64(begin_timestamp);
+        data.put_i64(0);
+        data.put_i64(0);
+        data.put_i64(0);
+        data.put_i32(HASH_SLOT_NUM as i32);
+        data.put_i32(items.len() as i32 + 1);
+        data.resize(
+            INDEX_HEADER_SIZE + HASH_SLOT_NUM * HASH_SLOT_SIZE + INDEX_SIZE,
+            0,
+        );
+        for (index, (key_hash, time_diff, prev_index)) in items.iter().enumerate() {
+            data.put_i32(*key_hash);
+            data.put_i64(index as i64 * 100);
+            data.put_i32(*time_diff);
+            data.put_i32(*prev_index);
+        }
+        data
+    }
+
+    fn chain_indexes(data: &[u8], head: i32) -> Vec<i32> {
+        let header = IndexHeaderPrint::new(&data[..INDEX_HEADER_SIZE]);
+        let reader = IndexChainReader {
+            data,
+            slots_end: INDEX_HEADER_SIZE + HASH_SLOT_NUM * HASH_SLOT_SIZE,
+            index_count: header.index_count,
+            begin_timestamp: header.begin_timestamp,
+        };
+        reader.chain(head).iter().map(|item| item.index).collect()
+    }
+
+    #[test]
+    fn walks_index_chain_from_head_to_tail() {
+        let data = index_file(1_700_000_000_000, &[(11, 0, 0), (22, 1, 1), (33, 2, 2)]);
+        assert_eq!(chain_indexes(&data, 3), [3, 2, 1]);
+        assert_eq!(chain_indexes(&data, 1), [1]);
+        assert!(chain_indexes(&data, 0).is_empty());
+        // heads past the written items are ignored
+        assert!(chain_indexes(&data, 4).is_empty());
+    }
+
+    #[test]
+    fn stops_walking_corrupted_index_chains() {
+        // an item pointing at itself
+        let data = index_file(0, &[(11, 0, 0), (22, 0, 2)]);
+        assert_eq!(chain_indexes(&data, 2), [2]);
+        // a negative time diff
+        let data = index_file(0, &[(11, 0, 0), (22, -1, 1)]);
+        assert_eq!(chain_indexes(&data, 2), [2]);
+        // a cycle is cut after index count items
+        let data = index_file(0, &[(11, 0, 2), (22, 0, 1)]);
+        assert_eq!(chain_indexes(&data, 2), [2, 1, 2]);
+        // a file truncated in the middle of the last item
+        let data = index_file(0, &[(11, 0, 0), (22, 0, 1)]);
+        assert!(chain_indexes(&data[..data.len() - 1], 2).is_empty());
+        assert_eq!(chain_indexes(&data[..data.len() - 1], 1), [1]);
+    }
+
+    #[test]
+    fn decodes_index_header() {
+        let mut data = index_file(1_700_000_000_000, &[]);
+        let header = IndexHeaderPrint::new(&data[..INDEX_HEADER_SIZE]);
+        assert_eq!(header.begin_timestamp, 1_700_000_000_000);
+        assert_eq!(header.hash_slot_count, HASH_SLOT_NUM as i32);
+        assert_eq!(header.index_count, 1);
+
+        // an index count that was never written reads as one, like IndexHeader::load
+        data[36..40].copy_from_slice(&0i32.to_be_bytes());
+        assert_eq!(
+            IndexHeaderPrint::new(&data[..INDEX_HEADER_SIZE]).index_count,
+            1
+        );
+    }
 }
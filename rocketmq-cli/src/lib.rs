@@ -15,5 +15,8 @@
  * limitations under the License.
  */
 
+pub mod admin;
 pub mod command_line;
+pub mod commit_log_check;
 pub mod content_show;
+pub mod logic_files_rebuild;
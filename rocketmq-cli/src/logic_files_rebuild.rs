@@ -0,0 +1,59 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::broker::broker_config::BrokerConfig;
+use rocketmq_store::config::message_store_config::MessageStoreConfig;
+use rocketmq_store::log_file::MessageStore;
+use rocketmq_store::message_store::default_message_store::DefaultMessageStore;
+
+pub fn rebuild_logic_files(store_path: PathBuf, mapped_file_size: usize, from: i64, to: i64) {
+    let message_store_config = Arc::new(MessageStoreConfig {
+        store_path_root_dir: CheetahString::from(store_path.to_string_lossy().to_string()),
+        mapped_file_size_commit_log: mapped_file_size,
+        ..MessageStoreConfig::default()
+    });
+    // index files are flushed in the background when they roll over
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    runtime.block_on(async move {
+        let mut message_store = DefaultMessageStore::new(
+            message_store_config,
+            Arc::new(BrokerConfig::default()),
+            Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            None,
+            false,
+        );
+        if !message_store.load_offline() {
+            println!("load message store from {} failed", store_path.display());
+            return;
+        }
+        println!(
+            "rebuild logic files from commit log range [{}, {}), max physical offset is {}",
+            from,
+            to,
+            message_store.get_max_phy_offset()
+        );
+        let dispatched = message_store.rebuild_logic_files(from, to);
+        println!("rebuild logic files OK, {} messages dispatched", dispatched);
+    });
+}
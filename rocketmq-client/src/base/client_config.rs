@@ -22,6 +22,7 @@ use std::time::Duration;
 
 use cheetah_string::CheetahString;
 use rocketmq_common::common::message::message_queue::MessageQueue;
+use rocketmq_common::common::server::tls_config::TLS_ENABLE;
 use rocketmq_common::utils::name_server_address_utils::NameServerAddressUtils;
 use rocketmq_common::utils::name_server_address_utils::NAMESRV_ENDPOINT_PATTERN;
 use rocketmq_common::utils::network_util::NetworkUtil;
@@ -117,7 +118,10 @@ impl ClientConfig {
                 .unwrap_or_else(|_| "false".to_string())
                 .parse::<bool>()
                 .unwrap_or(false),
-            use_tls: false,
+            use_tls: env::var(TLS_ENABLE)
+                .unwrap_or_else(|_| "false".to_string())
+                .parse::<bool>()
+                .unwrap_or(false),
             socks_proxy_config: env::var(SOCKS_PROXY_CONFIG)
                 .unwrap_or_else(|_| "{}".to_string())
                 .into(),
@@ -40,6 +40,8 @@ use rocketmq_common::MessageAccessor::MessageAccessor;
 use rocketmq_common::TimeUtils::get_current_millis;
 use rocketmq_remoting::protocol::body::consume_message_directly_result::ConsumeMessageDirectlyResult;
 use rocketmq_remoting::protocol::body::consumer_running_info::ConsumerRunningInfo;
+use rocketmq_remoting::protocol::body::pop_process_queue_info::PopProcessQueueInfo;
+use rocketmq_remoting::protocol::body::process_queue_info::ProcessQueueInfo;
 use rocketmq_remoting::protocol::filter::filter_api::FilterAPI;
 use rocketmq_remoting::protocol::header::ack_message_request_header::AckMessageRequestHeader;
 use rocketmq_remoting::protocol::header::change_invisible_time_request_header::ChangeInvisibleTimeRequestHeader;
@@ -1662,7 +1664,79 @@ impl MQConsumerInner for DefaultMQPushConsumerImpl {
         self.consumer_config.unit_mode
     }
 
-    fn consumer_running_info(&self) -> ConsumerRunningInfo {
-        todo!()
+    async fn consumer_running_info(&self) -> ConsumerRunningInfo {
+        let mut info = ConsumerRunningInfo::default();
+        let consumer_config = &self.consumer_config;
+        let properties = [
+            ("consumerGroup", consumer_config.consumer_group.to_string()),
+            ("messageModel", consumer_config.message_model.to_string()),
+            (
+                "consumeFromWhere",
+                format!("{:?}", consumer_config.consume_from_where),
+            ),
+            (
+                "consumeThreadMin",
+                consumer_config.consume_thread_min.to_string(),
+            ),
+            (
+                "consumeThreadMax",
+                consumer_config.consume_thread_max.to_string(),
+            ),
+            ("pullBatchSize", consumer_config.pull_batch_size.to_string()),
+            (
+                "consumeMessageBatchMaxSize",
+                consumer_config.consume_message_batch_max_size.to_string(),
+            ),
+            (
+                "maxReconsumeTimes",
+                consumer_config.max_reconsume_times.to_string(),
+            ),
+            (
+                ConsumerRunningInfo::PROP_CONSUME_ORDERLY,
+                self.consume_orderly.to_string(),
+            ),
+            (
+                ConsumerRunningInfo::PROP_THREADPOOL_CORE_SIZE,
+                consumer_config.consume_thread_min.to_string(),
+            ),
+        ];
+        for (key, value) in properties {
+            info.properties.insert(key.into(), value.into());
+        }
+
+        let subscription_inner = self.rebalance_impl.get_subscription_inner();
+        info.subscription_set = subscription_inner.read().await.values().cloned().collect();
+
+        let process_queue_table = self
+            .rebalance_impl
+            .rebalance_impl_inner
+            .process_queue_table
+            .read()
+            .await;
+        for (mq, pq) in process_queue_table.iter() {
+            let mut pq_info = ProcessQueueInfo::default();
+            if let Some(offset_store) = self.offset_store.as_ref() {
+                pq_info.commit_offset = offset_store
+                    .read_offset(mq, ReadOffsetType::MemoryFirstThenStore)
+                    .await
+                    .max(0) as u64;
+            }
+            pq.fill_process_queue_info(&mut pq_info).await;
+            info.mq_table.insert(mq.clone(), pq_info);
+        }
+        drop(process_queue_table);
+
+        let pop_process_queue_table = self
+            .rebalance_impl
+            .rebalance_impl_inner
+            .pop_process_queue_table
+            .read()
+            .await;
+        for (mq, pq) in pop_process_queue_table.iter() {
+            let mut pq_info = PopProcessQueueInfo::default();
+            pq.fill_pop_process_queue_info(&mut pq_info);
+            info.mq_pop_table.insert(mq.clone(), pq_info);
+        }
+        info
     }
 }
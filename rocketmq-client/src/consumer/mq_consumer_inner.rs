@@ -87,7 +87,7 @@ pub trait MQConsumerInnerLocal: MQConsumerInnerAny + Sync + 'static {
     fn is_unit_mode(&self) -> bool;
 
     /// Returns the running information of the consumer.
-    fn consumer_running_info(&self) -> ConsumerRunningInfo;
+    async fn consumer_running_info(&self) -> ConsumerRunningInfo;
 }
 
 pub trait MQConsumerInnerAny: std::any::Any {
@@ -205,7 +205,7 @@ impl MQConsumerInner for MQConsumerInnerImpl {
     }
 
     #[inline]
-    fn consumer_running_info(&self) -> ConsumerRunningInfo {
-        MQConsumerInner::consumer_running_info(self.default_mqpush_consumer_impl.as_ref())
+    async fn consumer_running_info(&self) -> ConsumerRunningInfo {
+        MQConsumerInner::consumer_running_info(self.default_mqpush_consumer_impl.as_ref()).await
     }
 }
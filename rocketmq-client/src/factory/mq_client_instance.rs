@@ -34,6 +34,7 @@ use rocketmq_common::common::mix_all;
 use rocketmq_common::TimeUtils::get_current_millis;
 use rocketmq_remoting::base::connection_net_event::ConnectionNetEvent;
 use rocketmq_remoting::protocol::body::consume_message_directly_result::ConsumeMessageDirectlyResult;
+use rocketmq_remoting::protocol::body::consumer_running_info::ConsumerRunningInfo;
 use rocketmq_remoting::protocol::heartbeat::consumer_data::ConsumerData;
 use rocketmq_remoting::protocol::heartbeat::heartbeat_data::HeartbeatData;
 use rocketmq_remoting::protocol::heartbeat::message_model::MessageModel;
@@ -230,7 +231,11 @@ impl MQClientInstance {
         let (tx, mut rx) = tokio::sync::broadcast::channel::<ConnectionNetEvent>(16);
 
         let mq_client_api_impl = ArcMut::new(MQClientAPIImpl::new(
-            Arc::new(TokioClientConfig::default()),
+            Arc::new(TokioClientConfig {
+                use_tls: client_config.use_tls,
+                socks_proxy_config: client_config.socks_proxy_config.to_string(),
+                ..Default::default()
+            }),
             ClientRemotingProcessor::new(instance.clone()),
             rpc_hook,
             client_config.clone(),
@@ -305,13 +310,20 @@ impl MQClientInstance {
                         .expect("mq_client_api_impl is None")
                         .fetch_name_server_addr()
                         .await;
+                } else if let Some(namesrv_addr) = self.client_config.namesrv_addr.as_deref() {
+                    // Make sure the address list is applied before the first request is sent
+                    self.mq_client_api_impl
+                        .as_mut()
+                        .expect("mq_client_api_impl is None")
+                        .update_name_server_address_list(namesrv_addr)
+                        .await;
                 }
                 // Start request-response channel
                 self.mq_client_api_impl
                     .as_mut()
                     .expect("mq_client_api_impl is None")
                     .start()
-                    .await;
+                    .await?;
                 // Start various schedule tasks
                 self.start_scheduled_task(this.clone());
                 // Start pull service
@@ -1210,6 +1222,29 @@ impl MQClientInstance {
         }
     }
 
+    pub async fn consumer_running_info(
+        &self,
+        consumer_group: &CheetahString,
+    ) -> Option<ConsumerRunningInfo> {
+        let consumer = self.select_consumer(consumer_group).await?;
+        let mut info = consumer.consumer_running_info().await;
+        if let Some(namesrv_addr) = self.client_config.get_namesrv_addr() {
+            info.properties.insert(
+                ConsumerRunningInfo::PROP_NAMESERVER_ADDR.into(),
+                namesrv_addr,
+            );
+        }
+        info.properties.insert(
+            ConsumerRunningInfo::PROP_CONSUME_TYPE.into(),
+            consumer.consume_type().name().into(),
+        );
+        info.properties.insert(
+            ConsumerRunningInfo::PROP_CLIENT_VERSION.into(),
+            env!("CARGO_PKG_VERSION").into(),
+        );
+        Some(info)
+    }
+
     pub async fn consume_message_directly(
         &self,
         message: MessageExt,
@@ -1336,3 +1371,37 @@ pub fn topic_route_data2topic_subscribe_info(
     }
     mq_list
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn start_applies_configured_name_server_address() {
+        let mut instance =
+            MQClientInstance::new_arc(ClientConfig::default(), 0, "namesrv_addr_test", None);
+        // configured after construction, so only start can apply it
+        instance.client_config = ArcMut::new(ClientConfig {
+            namesrv_addr: Some("127.0.0.1:9876;127.0.0.2:9876".into()),
+            ..ClientConfig::default()
+        });
+        assert!(instance
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .get_name_server_address_list()
+            .is_empty());
+
+        let this = instance.clone();
+        instance.start(this).await.unwrap();
+
+        assert_eq!(
+            instance
+                .mq_client_api_impl
+                .as_ref()
+                .unwrap()
+                .get_name_server_address_list(),
+            ["127.0.0.1:9876", "127.0.0.2:9876"]
+        );
+    }
+}
@@ -31,6 +31,7 @@ use rocketmq_remoting::code::response_code::ResponseCode;
 use rocketmq_remoting::net::channel::Channel;
 use rocketmq_remoting::protocol::header::check_transaction_state_request_header::CheckTransactionStateRequestHeader;
 use rocketmq_remoting::protocol::header::consume_message_directly_result_request_header::ConsumeMessageDirectlyResultRequestHeader;
+use rocketmq_remoting::protocol::header::get_consumer_running_info_request_header::GetConsumerRunningInfoRequestHeader;
 use rocketmq_remoting::protocol::header::notify_consumer_ids_changed_request_header::NotifyConsumerIdsChangedRequestHeader;
 use rocketmq_remoting::protocol::header::reply_message_request_header::ReplyMessageRequestHeader;
 use rocketmq_remoting::protocol::namespace_util::NamespaceUtil;
@@ -79,7 +80,7 @@ impl RequestProcessor for ClientRemotingProcessor {
                 unimplemented!("GetConsumerStatusFromClient")
             }
             RequestCode::GetConsumerRunningInfo => {
-                unimplemented!("GetConsumerRunningInfo")
+                self.get_consumer_running_info(channel, ctx, request).await
             }
             RequestCode::ConsumeMessageDirectly => {
                 self.consume_message_directly(channel, ctx, request).await
@@ -273,6 +274,41 @@ impl ClientRemotingProcessor {
         Ok(None)
     }
 
+    async fn get_consumer_running_info(
+        &mut self,
+        _channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        request: RemotingCommand,
+    ) -> Result<Option<RemotingCommand>> {
+        let request_header =
+            request.decode_command_custom_header::<GetConsumerRunningInfoRequestHeader>()?;
+        let running_info = self
+            .client_instance
+            .consumer_running_info(&request_header.consumer_group)
+            .await;
+        match running_info {
+            Some(mut running_info) => {
+                if request_header.jstack_enable {
+                    // There is no thread stack to report for a tokio based client.
+                    running_info.jstack = Some(CheetahString::empty());
+                }
+                let body = running_info.encode().map_err(|_| {
+                    RemotingCommandError("encode consumer running info failed".to_string())
+                })?;
+                Ok(Some(
+                    RemotingCommand::create_response_command().set_body(body),
+                ))
+            }
+            None => Ok(Some(
+                RemotingCommand::create_response_command_with_code(ResponseCode::SystemError)
+                    .set_remark(format!(
+                        "The Consumer Group <{}> not exist in this consumer",
+                        request_header.consumer_group
+                    )),
+            )),
+        }
+    }
+
     async fn consume_message_directly(
         &mut self,
         channel: Channel,
@@ -20,8 +20,13 @@ use std::sync::atomic::AtomicU32;
 use std::sync::Arc;
 use std::time::Instant;
 
+use bytes::Bytes;
 use cheetah_string::CheetahString;
 use lazy_static::lazy_static;
+use rocketmq_common::common::attribute::attribute_parser::AttributeParser;
+use rocketmq_common::common::base::plain_access_config::PlainAccessConfig;
+use rocketmq_common::common::config::TopicConfig;
+use rocketmq_common::common::filter::expression_type::ExpressionType;
 use rocketmq_common::common::message::message_batch::MessageBatch;
 use rocketmq_common::common::message::message_client_id_setter::MessageClientIDSetter;
 use rocketmq_common::common::message::message_enum::MessageRequestMode;
@@ -42,45 +47,93 @@ use rocketmq_remoting::clients::rocketmq_default_impl::RocketmqDefaultClient;
 use rocketmq_remoting::clients::RemotingClient;
 use rocketmq_remoting::code::request_code::RequestCode;
 use rocketmq_remoting::code::response_code::ResponseCode;
+use rocketmq_remoting::protocol::admin::consume_stats::ConsumeStats;
+use rocketmq_remoting::protocol::admin::topic_stats_table::TopicStatsTable;
 use rocketmq_remoting::protocol::body::batch_ack_message_request_body::BatchAckMessageRequestBody;
+use rocketmq_remoting::protocol::body::broker_body::cluster_info::ClusterInfo;
 use rocketmq_remoting::protocol::body::check_client_request_body::CheckClientRequestBody;
+use rocketmq_remoting::protocol::body::cluster_acl_version_info::ClusterAclVersionInfo;
+use rocketmq_remoting::protocol::body::consume_message_directly_result::ConsumeMessageDirectlyResult;
+use rocketmq_remoting::protocol::body::consumer_connection::ConsumerConnection;
+use rocketmq_remoting::protocol::body::consumer_running_info::ConsumerRunningInfo;
+use rocketmq_remoting::protocol::body::create_topic_list_request_body::CreateTopicListRequestBody;
 use rocketmq_remoting::protocol::body::get_consumer_listby_group_response_body::GetConsumerListByGroupResponseBody;
+use rocketmq_remoting::protocol::body::group_list::GroupList;
+use rocketmq_remoting::protocol::body::kv_table::KVTable;
 use rocketmq_remoting::protocol::body::query_assignment_request_body::QueryAssignmentRequestBody;
 use rocketmq_remoting::protocol::body::query_assignment_response_body::QueryAssignmentResponseBody;
+use rocketmq_remoting::protocol::body::query_message_by_condition_request_body::QueryMessageByConditionRequestBody;
 use rocketmq_remoting::protocol::body::request::lock_batch_request_body::LockBatchRequestBody;
+use rocketmq_remoting::protocol::body::reset_offset_body::ResetOffsetBody;
 use rocketmq_remoting::protocol::body::response::lock_batch_response_body::LockBatchResponseBody;
 use rocketmq_remoting::protocol::body::set_message_request_mode_request_body::SetMessageRequestModeRequestBody;
+use rocketmq_remoting::protocol::body::topic::topic_list::TopicList;
+use rocketmq_remoting::protocol::body::topic_info_wrapper::TopicConfigSerializeWrapper;
 use rocketmq_remoting::protocol::body::unlock_batch_request_body::UnlockBatchRequestBody;
 use rocketmq_remoting::protocol::header::ack_message_request_header::AckMessageRequestHeader;
 use rocketmq_remoting::protocol::header::change_invisible_time_request_header::ChangeInvisibleTimeRequestHeader;
 use rocketmq_remoting::protocol::header::change_invisible_time_response_header::ChangeInvisibleTimeResponseHeader;
 use rocketmq_remoting::protocol::header::client_request_header::GetRouteInfoRequestHeader;
+use rocketmq_remoting::protocol::header::clone_group_offset_request_header::CloneGroupOffsetRequestHeader;
+use rocketmq_remoting::protocol::header::consume_message_directly_result_request_header::ConsumeMessageDirectlyResultRequestHeader;
 use rocketmq_remoting::protocol::header::consumer_send_msg_back_request_header::ConsumerSendMsgBackRequestHeader;
+use rocketmq_remoting::protocol::header::create_access_config_request_header::CreateAccessConfigRequestHeader;
+use rocketmq_remoting::protocol::header::create_topic_request_header::CreateTopicRequestHeader;
+use rocketmq_remoting::protocol::header::delete_access_config_request_header::DeleteAccessConfigRequestHeader;
+use rocketmq_remoting::protocol::header::delete_subscription_group_request_header::DeleteSubscriptionGroupRequestHeader;
+use rocketmq_remoting::protocol::header::delete_topic_request_header::DeleteTopicRequestHeader;
 use rocketmq_remoting::protocol::header::end_transaction_request_header::EndTransactionRequestHeader;
 use rocketmq_remoting::protocol::header::extra_info_util::ExtraInfoUtil;
+use rocketmq_remoting::protocol::header::get_broker_acl_config_response_header::GetBrokerAclConfigResponseHeader;
+use rocketmq_remoting::protocol::header::get_consume_stats_request_header::GetConsumeStatsRequestHeader;
+use rocketmq_remoting::protocol::header::get_consumer_connection_list_request_header::GetConsumerConnectionListRequestHeader;
 use rocketmq_remoting::protocol::header::get_consumer_listby_group_request_header::GetConsumerListByGroupRequestHeader;
+use rocketmq_remoting::protocol::header::get_consumer_running_info_request_header::GetConsumerRunningInfoRequestHeader;
 use rocketmq_remoting::protocol::header::get_max_offset_request_header::GetMaxOffsetRequestHeader;
 use rocketmq_remoting::protocol::header::get_max_offset_response_header::GetMaxOffsetResponseHeader;
+use rocketmq_remoting::protocol::header::get_subscription_group_config_request_header::GetSubscriptionGroupConfigRequestHeader;
+use rocketmq_remoting::protocol::header::get_topic_config_request_header::GetTopicConfigRequestHeader;
+use rocketmq_remoting::protocol::header::get_topic_stats_info_request_header::GetTopicStatsInfoRequestHeader;
 use rocketmq_remoting::protocol::header::heartbeat_request_header::HeartbeatRequestHeader;
 use rocketmq_remoting::protocol::header::lock_batch_mq_request_header::LockBatchMqRequestHeader;
 use rocketmq_remoting::protocol::header::message_operation_header::send_message_request_header::SendMessageRequestHeader;
 use rocketmq_remoting::protocol::header::message_operation_header::send_message_request_header_v2::SendMessageRequestHeaderV2;
 use rocketmq_remoting::protocol::header::message_operation_header::send_message_response_header::SendMessageResponseHeader;
+use rocketmq_remoting::protocol::header::namesrv::kv_config_header::DeleteKVConfigRequestHeader;
+use rocketmq_remoting::protocol::header::namesrv::kv_config_header::GetKVConfigRequestHeader;
+use rocketmq_remoting::protocol::header::namesrv::kv_config_header::GetKVConfigResponseHeader;
+use rocketmq_remoting::protocol::header::namesrv::kv_config_header::GetKVListByNamespaceRequestHeader;
+use rocketmq_remoting::protocol::header::namesrv::kv_config_header::PutKVConfigRequestHeader;
+use rocketmq_remoting::protocol::header::namesrv::perm_broker_header::AddWritePermOfBrokerRequestHeader;
+use rocketmq_remoting::protocol::header::namesrv::perm_broker_header::AddWritePermOfBrokerResponseHeader;
+use rocketmq_remoting::protocol::header::namesrv::perm_broker_header::WipeWritePermOfBrokerRequestHeader;
+use rocketmq_remoting::protocol::header::namesrv::perm_broker_header::WipeWritePermOfBrokerResponseHeader;
+use rocketmq_remoting::protocol::header::namesrv::topic_operation_header::DeleteTopicFromNamesrvRequestHeader;
+use rocketmq_remoting::protocol::header::namesrv::topic_operation_header::GetTopicsByClusterRequestHeader;
 use rocketmq_remoting::protocol::header::pop_message_request_header::PopMessageRequestHeader;
 use rocketmq_remoting::protocol::header::pop_message_response_header::PopMessageResponseHeader;
 use rocketmq_remoting::protocol::header::pull_message_request_header::PullMessageRequestHeader;
 use rocketmq_remoting::protocol::header::pull_message_response_header::PullMessageResponseHeader;
 use rocketmq_remoting::protocol::header::query_consumer_offset_request_header::QueryConsumerOffsetRequestHeader;
 use rocketmq_remoting::protocol::header::query_consumer_offset_response_header::QueryConsumerOffsetResponseHeader;
+use rocketmq_remoting::protocol::header::query_message_by_condition_response_header::QueryMessageByConditionResponseHeader;
+use rocketmq_remoting::protocol::header::query_message_request_header::QueryMessageRequestHeader;
+use rocketmq_remoting::protocol::header::query_topic_consume_by_who_request_header::QueryTopicConsumeByWhoRequestHeader;
+use rocketmq_remoting::protocol::header::query_topics_by_consumer_request_header::QueryTopicsByConsumerRequestHeader;
+use rocketmq_remoting::protocol::header::reset_offset_request_header::ResetOffsetRequestHeader;
 use rocketmq_remoting::protocol::header::unlock_batch_mq_request_header::UnlockBatchMqRequestHeader;
 use rocketmq_remoting::protocol::header::unregister_client_request_header::UnregisterClientRequestHeader;
 use rocketmq_remoting::protocol::header::update_consumer_offset_header::UpdateConsumerOffsetRequestHeader;
+use rocketmq_remoting::protocol::header::update_global_white_addrs_config_request_header::UpdateGlobalWhiteAddrsConfigRequestHeader;
+use rocketmq_remoting::protocol::header::view_message_request_header::ViewMessageRequestHeader;
 use rocketmq_remoting::protocol::heartbeat::heartbeat_data::HeartbeatData;
 use rocketmq_remoting::protocol::heartbeat::message_model::MessageModel;
 use rocketmq_remoting::protocol::heartbeat::subscription_data::SubscriptionData;
 use rocketmq_remoting::protocol::namespace_util::NamespaceUtil;
 use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
 use rocketmq_remoting::protocol::route::topic_route_data::TopicRouteData;
+use rocketmq_remoting::protocol::static_topic::topic_config_and_queue_mapping::TopicConfigAndQueueMapping;
+use rocketmq_remoting::protocol::subscription::subscription_group_config::SubscriptionGroupConfig;
 use rocketmq_remoting::protocol::RemotingDeserializable;
 use rocketmq_remoting::protocol::RemotingSerializable;
 use rocketmq_remoting::remoting::RemotingService;
@@ -89,11 +142,11 @@ use rocketmq_remoting::rpc::topic_request_header::TopicRequestHeader;
 use rocketmq_remoting::runtime::config::client_config::TokioClientConfig;
 use rocketmq_remoting::runtime::RPCHook;
 use rocketmq_rust::ArcMut;
-use tracing::error;
 use tracing::warn;
 
 use crate::base::client_config::ClientConfig;
 use crate::client_broker_err;
+use crate::client_error::ClientErr;
 use crate::client_error::MQBrokerErr;
 use crate::client_error::MQClientError;
 use crate::client_error::MQClientError::MQClientBrokerError;
@@ -167,9 +220,11 @@ impl MQClientAPIImpl {
         }
     }
 
-    pub async fn start(&self) {
+    pub async fn start(&self) -> Result<()> {
+        self.remoting_client.check_tls_config()?;
         let client = ArcMut::downgrade(&self.remoting_client);
         self.remoting_client.start(client).await;
+        Ok(())
     }
 
     pub async fn fetch_name_server_addr(&mut self) -> Option<String> {
@@ -437,6 +492,33 @@ impl MQClientAPIImpl {
         .await
     }
 
+    /// Sends a message synchronously without going through a producer, for callers that pick
+    /// the target queue themselves (e.g. the proxy).
+    pub async fn send_message_directly<T>(
+        &mut self,
+        addr: &CheetahString,
+        broker_name: &CheetahString,
+        msg: &T,
+        request_header: SendMessageRequestHeader,
+        timeout_millis: u64,
+    ) -> Result<SendResult>
+    where
+        T: MessageTrait,
+    {
+        let mut request = if *sendSmartMsg {
+            let request_header_v2 =
+                SendMessageRequestHeaderV2::create_send_message_request_header_v2(&request_header);
+            RemotingCommand::create_request_command(RequestCode::SendMessageV2, request_header_v2)
+        } else {
+            RemotingCommand::create_request_command(RequestCode::SendMessage, request_header)
+        };
+        if let Some(body) = msg.get_body() {
+            request.set_body_mut_ref(body.clone());
+        }
+        self.send_message_sync(addr, broker_name, msg, timeout_millis, request)
+            .await
+    }
+
     async fn send_message_sync<T>(
         &mut self,
         addr: &CheetahString,
@@ -515,7 +597,7 @@ impl MQClientAPIImpl {
                         Box::pin(self.on_exception_impl(
                             broker_name,
                             msg,
-                            duration,
+                            timeout_millis.saturating_sub(duration),
                             request,
                             send_callback,
                             topic_publish_info,
@@ -532,7 +614,26 @@ impl MQClientAPIImpl {
                 }
             }
             Err(err) => {
-                error!("send message async error: {:?}", err);
+                let duration = (Instant::now() - begin_start_time).as_millis() as u64;
+                producer
+                    .update_fault_item(broker_name.clone(), duration, true, true)
+                    .await;
+                Box::pin(self.on_exception_impl(
+                    broker_name,
+                    msg,
+                    timeout_millis.saturating_sub(duration),
+                    request,
+                    send_callback,
+                    topic_publish_info,
+                    instance,
+                    retry_times_when_send_failed,
+                    times,
+                    MQClientError::RemotingError(err),
+                    context,
+                    true,
+                    producer,
+                ))
+                .await;
             }
         }
     }
@@ -645,12 +746,23 @@ impl MQClientAPIImpl {
                     .get_broker_name_from_message_queue(mq_chosen.as_ref().unwrap())
                     .await;
             }
-            let addr = instance
-                .as_ref()
-                .unwrap()
-                .find_broker_address_in_publish(retry_broker_name.as_ref())
-                .await
-                .unwrap();
+            let addr = match instance.as_ref() {
+                Some(instance) => {
+                    instance
+                        .find_broker_address_in_publish(retry_broker_name.as_ref())
+                        .await
+                }
+                None => None,
+            };
+            let Some(addr) = addr else {
+                // The broker went away, there is nothing left to retry against
+                let e = MQClientError::MQClientErr(ClientErr::new(format!(
+                    "The broker[{}] not exist, send failed: {}",
+                    retry_broker_name, e
+                )));
+                Self::on_send_failed(send_callback, e, context, producer);
+                return;
+            };
             warn!(
                 "async send msg by retry {} times. topic={}, brokerAddr={}, brokerName={}",
                 tmp,
@@ -674,7 +786,21 @@ impl MQClientAPIImpl {
                 producer,
             ))
             .await;
-        } else if context.is_some() {
+        } else {
+            Self::on_send_failed(send_callback, e, context, producer);
+        }
+    }
+
+    fn on_send_failed(
+        send_callback: Option<SendMessageCallback>,
+        e: MQClientError,
+        context: &mut Option<SendMessageContext<'_>>,
+        producer: &DefaultMQProducerImpl,
+    ) {
+        if let Some(send_callback) = send_callback.as_ref() {
+            send_callback(None, Some(&e));
+        }
+        if context.is_some() {
             let inner = context.as_mut().unwrap();
             inner.exception = Some(Arc::new(Box::new(e)));
             producer.execute_send_message_hook_after(context);
@@ -1254,215 +1380,1119 @@ impl MQClientAPIImpl {
         )
     }
 
-    pub async fn set_message_request_mode(
+    /// Queries one page of messages from the index of a broker, the messages are returned
+    /// together with the response header that carries the cursor of the next page.
+    pub async fn query_message_by_condition(
         &mut self,
-        broker_addr: &CheetahString,
-        topic: &CheetahString,
-        consumer_group: &CheetahString,
-        mode: MessageRequestMode,
-        pop_share_queue_num: i32,
+        addr: &CheetahString,
+        request_body: &QueryMessageByConditionRequestBody,
         timeout_millis: u64,
-    ) -> Result<()> {
-        let body = SetMessageRequestModeRequestBody {
-            topic: topic.clone(),
-            consumer_group: consumer_group.clone(),
-            mode,
-            pop_share_queue_num,
-        };
-        let request = RemotingCommand::create_remoting_command(RequestCode::SetMessageRequestMode)
-            .set_body(
-                body.encode()
-                    .expect("encode SetMessageRequestModeRequestBody failed"),
-            );
+    ) -> Result<(QueryMessageByConditionResponseHeader, Vec<MessageExt>)> {
+        let request =
+            RemotingCommand::create_remoting_command(RequestCode::QueryMessageByCondition)
+                .set_body(request_body.encode().map_err(MQClientError::CommonError)?);
         let response = self
             .remoting_client
-            .invoke_async(
-                Some(&mix_all::broker_vip_channel(
-                    self.client_config.vip_channel_enabled,
-                    broker_addr,
-                )),
-                request,
-                timeout_millis,
-            )
+            .invoke_async(Some(addr), request, timeout_millis)
             .await?;
-        if ResponseCode::from(response.code()) != ResponseCode::Success {
-            return mq_client_err!(
+        match ResponseCode::from(response.code()) {
+            ResponseCode::Success => {
+                let response_header = response
+                    .decode_command_custom_header::<QueryMessageByConditionResponseHeader>()
+                    .map_err(MQClientError::RemotingError)?;
+                let messages = match response.body() {
+                    Some(body) => MessageDecoder::decodes_batch(&mut body.clone(), true, true),
+                    None => Vec::new(),
+                };
+                Ok((response_header, messages))
+            }
+            ResponseCode::QueryNotFound => {
+                Ok((QueryMessageByConditionResponseHeader::default(), Vec::new()))
+            }
+            _ => client_broker_err!(
                 response.code(),
-                response.remark().cloned().unwrap_or_default().to_string()
-            );
+                response.remark().map_or("".to_string(), |s| s.to_string()),
+                addr.to_string()
+            ),
         }
-        Ok(())
     }
 
-    pub async fn query_assignment(
+    pub async fn create_plain_access_config(
         &mut self,
         addr: &CheetahString,
-        topic: CheetahString,
-        consumer_group: CheetahString,
-        client_id: CheetahString,
-        strategy_name: CheetahString,
-        message_model: MessageModel,
-        timeout: u64,
-    ) -> Result<Option<HashSet<MessageQueueAssignment>>> {
-        let request_body = QueryAssignmentRequestBody {
-            topic,
-            consumer_group,
-            client_id,
-            strategy_name,
-            message_model,
+        plain_access_config: &PlainAccessConfig,
+        timeout_millis: u64,
+    ) -> Result<()> {
+        let join = |perms: &[CheetahString]| {
+            (!perms.is_empty()).then(|| {
+                CheetahString::from_string(
+                    perms
+                        .iter()
+                        .map(|perm| perm.as_str())
+                        .collect::<Vec<_>>()
+                        .join(","),
+                )
+            })
         };
-        let request = RemotingCommand::new_request(
-            RequestCode::QueryAssignment,
-            request_body
-                .encode()
-                .expect("encode QueryAssignmentRequestBody failed"),
+        let request_header = CreateAccessConfigRequestHeader {
+            access_key: plain_access_config.access_key.clone().unwrap_or_default(),
+            secret_key: plain_access_config.secret_key.clone(),
+            white_remote_address: plain_access_config.white_remote_address.clone(),
+            admin: plain_access_config.admin,
+            default_topic_perm: plain_access_config.default_topic_perm.clone(),
+            default_group_perm: plain_access_config.default_group_perm.clone(),
+            topic_perms: join(&plain_access_config.topic_perms),
+            group_perms: join(&plain_access_config.group_perms),
+        };
+        let request = RemotingCommand::create_request_command(
+            RequestCode::UpdateAndCreateAclConfig,
+            request_header,
         );
-        let response = self
-            .remoting_client
-            .invoke_async(
-                Some(&mix_all::broker_vip_channel(
-                    self.client_config.vip_channel_enabled,
-                    addr,
-                )),
-                request,
-                timeout,
-            )
-            .await?;
-
-        if ResponseCode::from(response.code()) == ResponseCode::Success {
-            let body = response.body();
-            if let Some(body) = body {
-                let assignment = QueryAssignmentResponseBody::decode(body.as_ref());
-                if let Ok(assignment) = assignment {
-                    return Ok(Some(assignment.message_queue_assignments));
-                }
-            }
-            return Ok(None);
-        }
+        self.invoke_broker_admin(addr, request, timeout_millis)
+            .await
+            .map(|_| ())
+    }
 
-        client_broker_err!(
-            response.code(),
-            response.remark().map_or("".to_string(), |s| s.to_string()),
-            addr.to_string()
-        )
+    pub async fn delete_access_config(
+        &mut self,
+        addr: &CheetahString,
+        access_key: CheetahString,
+        timeout_millis: u64,
+    ) -> Result<()> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::DeleteAclConfig,
+            DeleteAccessConfigRequestHeader { access_key },
+        );
+        self.invoke_broker_admin(addr, request, timeout_millis)
+            .await
+            .map(|_| ())
     }
 
-    pub async fn change_invisible_time_async(
-        &self,
-        broker_name: &CheetahString,
+    pub async fn update_global_white_addrs_config(
+        &mut self,
         addr: &CheetahString,
-        request_header: ChangeInvisibleTimeRequestHeader,
+        global_white_addrs: CheetahString,
+        acl_file_full_path: Option<CheetahString>,
         timeout_millis: u64,
-        ack_callback: impl AckCallback,
-    ) -> crate::Result<()> {
-        let offset = request_header.offset;
-        let topic = request_header.topic.clone();
-        let queue_id = request_header.queue_id;
+    ) -> Result<()> {
         let request = RemotingCommand::create_request_command(
-            RequestCode::ChangeMessageInvisibleTime,
-            request_header,
+            RequestCode::UpdateGlobalWhiteAddrsConfig,
+            UpdateGlobalWhiteAddrsConfigRequestHeader {
+                global_white_addrs,
+                acl_file_full_path,
+            },
         );
-        match self
-            .remoting_client
-            .invoke_async(Some(addr), request, timeout_millis)
+        self.invoke_broker_admin(addr, request, timeout_millis)
             .await
-        {
-            Ok(response) => {
-                let response_header = response
-                    .decode_command_custom_header::<ChangeInvisibleTimeResponseHeader>()
-                    .map_err(RemotingError)?;
-                let ack_result = if ResponseCode::from(response.code()) == ResponseCode::Success {
-                    AckResult {
-                        status: AckStatus::Ok,
-                        pop_time: response_header.pop_time as i64,
-                        extra_info: CheetahString::from_string(format!(
-                            "{}{}{}",
-                            ExtraInfoUtil::build_extra_info(
-                                offset,
-                                response_header.pop_time as i64,
-                                response_header.invisible_time,
-                                response_header.revive_qid,
-                                &topic,
-                                broker_name,
-                                queue_id,
-                            ),
-                            MessageConst::KEY_SEPARATOR,
-                            offset
-                        )),
-                    }
-                } else {
-                    AckResult {
-                        status: AckStatus::NotExist,
-                        ..Default::default()
-                    }
-                };
-                ack_callback.on_success(ack_result);
-            }
-            Err(e) => {
-                ack_callback.on_exception(Box::new(e));
-            }
-        };
-        Ok(())
+            .map(|_| ())
     }
 
-    pub async fn pop_message_async<PC>(
-        &self,
-        broker_name: &CheetahString,
+    pub async fn get_broker_cluster_acl_info(
+        &mut self,
         addr: &CheetahString,
-        request_header: PopMessageRequestHeader,
         timeout_millis: u64,
-        mut pop_callback: PC,
-    ) -> Result<()>
-    where
-        PC: PopCallback + 'static,
-    {
-        let topic = request_header.topic.clone();
-        let order = request_header.order.unwrap_or_default();
+    ) -> Result<ClusterAclVersionInfo> {
         let request =
-            RemotingCommand::create_request_command(RequestCode::PopMessage, request_header);
-        match self
-            .remoting_client
-            .invoke_async(Some(addr), request, timeout_millis)
-            .await
-        {
-            Ok(response) => {
-                let result = self.process_pop_response(broker_name, response, &topic, order);
-                match result {
-                    Ok(pop_result) => {
-                        pop_callback.on_success(pop_result).await;
-                    }
-                    Err(e) => {
-                        pop_callback.on_error(Box::new(e));
-                    }
-                }
-            }
-            Err(e) => {
-                pop_callback.on_error(Box::new(e));
-            }
-        }
-        Ok(())
+            RemotingCommand::create_remoting_command(RequestCode::GetBrokerClusterAclInfo);
+        let response = self
+            .invoke_broker_admin(addr, request, timeout_millis)
+            .await?;
+        let response_header = response
+            .decode_command_custom_header::<GetBrokerAclConfigResponseHeader>()
+            .expect("decode error");
+        Ok(ClusterAclVersionInfo {
+            broker_name: response_header.broker_name,
+            broker_addr: response_header.broker_addr,
+            acl_config_data_version: serde_json::from_str(response_header.version.as_str()).ok(),
+            all_acl_config_data_version: response_header
+                .all_acl_file_version
+                .and_then(|versions| serde_json::from_str(versions.as_str()).ok())
+                .unwrap_or_default(),
+            cluster_name: response_header.cluster_name,
+        })
     }
 
-    fn process_pop_response(
-        &self,
-        broker_name: &CheetahString,
-        mut response: RemotingCommand,
-        topic: &CheetahString,
-        is_order: bool,
-    ) -> Result<PopResult> {
-        let response_code = ResponseCode::from(response.code());
-        let (pop_status, msg_found_list) = match response_code {
-            ResponseCode::Success => {
-                let messages = MessageDecoder::decodes_batch(
-                    response.get_body_mut().unwrap(),
-                    self.client_config.decode_read_body,
-                    self.client_config.decode_decompress_body,
-                );
-                (PopStatus::Found, messages)
-            }
-            ResponseCode::PollingFull => (PopStatus::PollingFull, vec![]),
-            ResponseCode::PollingTimeout | ResponseCode::PullNotFound => {
-                (PopStatus::PollingNotFound, vec![])
+    pub async fn get_broker_cluster_info(&mut self, timeout_millis: u64) -> Result<ClusterInfo> {
+        let request = RemotingCommand::create_remoting_command(RequestCode::GetBrokerClusterInfo);
+        let response = self
+            .invoke_name_server_admin(None, request, timeout_millis)
+            .await?;
+        Ok(decode_response_body(&response)?)
+    }
+
+    pub async fn get_topic_list_from_name_server(
+        &mut self,
+        timeout_millis: u64,
+    ) -> Result<TopicList> {
+        let request =
+            RemotingCommand::create_remoting_command(RequestCode::GetAllTopicListFromNameserver);
+        let response = self
+            .invoke_name_server_admin(None, request, timeout_millis)
+            .await?;
+        Ok(decode_response_body(&response)?)
+    }
+
+    pub async fn get_topics_by_cluster(
+        &mut self,
+        cluster: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<TopicList> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::GetTopicsByCluster,
+            GetTopicsByClusterRequestHeader::new(cluster.clone()),
+        );
+        let response = self
+            .invoke_name_server_admin(None, request, timeout_millis)
+            .await?;
+        Ok(decode_response_body(&response)?)
+    }
+
+    pub async fn delete_topic_in_name_server(
+        &mut self,
+        addr: &CheetahString,
+        cluster_name: Option<CheetahString>,
+        topic: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<()> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::DeleteTopicInNamesrv,
+            DeleteTopicFromNamesrvRequestHeader::new(topic.clone(), cluster_name),
+        );
+        self.invoke_name_server_admin(Some(addr), request, timeout_millis)
+            .await
+            .map(|_| ())
+    }
+
+    /// Puts a KV config on every name server, the first failure is returned.
+    pub async fn put_kv_config_value(
+        &mut self,
+        namespace: &CheetahString,
+        key: &CheetahString,
+        value: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<()> {
+        let name_server_list = self.get_name_server_address_list().to_vec();
+        for addr in name_server_list {
+            let request = RemotingCommand::create_request_command(
+                RequestCode::PutKvConfig,
+                PutKVConfigRequestHeader::new(namespace.clone(), key.clone(), value.clone()),
+            );
+            self.invoke_name_server_admin(Some(&addr), request, timeout_millis)
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_kv_config_value(
+        &mut self,
+        namespace: &CheetahString,
+        key: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<CheetahString> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::GetKvConfig,
+            GetKVConfigRequestHeader::new(namespace.clone(), key.clone()),
+        );
+        let response = self
+            .invoke_name_server_admin(None, request, timeout_millis)
+            .await?;
+        let response_header =
+            response.decode_command_custom_header::<GetKVConfigResponseHeader>()?;
+        Ok(response_header.value.unwrap_or_default())
+    }
+
+    pub async fn get_kv_list_by_namespace(
+        &mut self,
+        namespace: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<KVTable> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::GetKvlistByNamespace,
+            GetKVListByNamespaceRequestHeader::new(namespace.clone()),
+        );
+        let response = self
+            .invoke_name_server_admin(None, request, timeout_millis)
+            .await?;
+        Ok(decode_response_body(&response)?)
+    }
+
+    /// Deletes a KV config from every name server, the first failure is returned.
+    pub async fn delete_kv_config_value(
+        &mut self,
+        namespace: &CheetahString,
+        key: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<()> {
+        let name_server_list = self.get_name_server_address_list().to_vec();
+        for addr in name_server_list {
+            let request = RemotingCommand::create_request_command(
+                RequestCode::DeleteKvConfig,
+                DeleteKVConfigRequestHeader::new(namespace.clone(), key.clone()),
+            );
+            self.invoke_name_server_admin(Some(&addr), request, timeout_millis)
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn wipe_write_perm_of_broker(
+        &mut self,
+        namesrv_addr: &CheetahString,
+        broker_name: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<i32> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::WipeWritePermOfBroker,
+            WipeWritePermOfBrokerRequestHeader::new(broker_name.clone()),
+        );
+        let response = self
+            .invoke_name_server_admin(Some(namesrv_addr), request, timeout_millis)
+            .await?;
+        let response_header =
+            response.decode_command_custom_header::<WipeWritePermOfBrokerResponseHeader>()?;
+        Ok(response_header.wipe_topic_count)
+    }
+
+    pub async fn add_write_perm_of_broker(
+        &mut self,
+        namesrv_addr: &CheetahString,
+        broker_name: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<i32> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::AddWritePermOfBroker,
+            AddWritePermOfBrokerRequestHeader::new(broker_name.clone()),
+        );
+        let response = self
+            .invoke_name_server_admin(Some(namesrv_addr), request, timeout_millis)
+            .await?;
+        let response_header =
+            response.decode_command_custom_header::<AddWritePermOfBrokerResponseHeader>()?;
+        Ok(response_header.add_topic_count)
+    }
+
+    pub async fn get_name_server_config(
+        &mut self,
+        namesrv_addr: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<HashMap<CheetahString, CheetahString>> {
+        let request = RemotingCommand::create_remoting_command(RequestCode::GetNamesrvConfig);
+        let response = self
+            .invoke_name_server_admin(Some(namesrv_addr), request, timeout_millis)
+            .await?;
+        Ok(parse_config_properties(response.body().as_ref()))
+    }
+
+    pub async fn update_name_server_config(
+        &mut self,
+        namesrv_addr: &CheetahString,
+        properties: &HashMap<CheetahString, CheetahString>,
+        timeout_millis: u64,
+    ) -> Result<()> {
+        let request = RemotingCommand::create_remoting_command(RequestCode::UpdateNamesrvConfig)
+            .set_body(properties_to_string(properties));
+        self.invoke_name_server_admin(Some(namesrv_addr), request, timeout_millis)
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn create_topic(
+        &mut self,
+        addr: &CheetahString,
+        default_topic: &CheetahString,
+        topic_config: &TopicConfig,
+        timeout_millis: u64,
+    ) -> Result<()> {
+        let attributes = topic_config
+            .attributes
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect::<HashMap<String, String>>();
+        let request_header = CreateTopicRequestHeader {
+            topic: topic_config.topic_name.clone().unwrap_or_default(),
+            default_topic: default_topic.clone(),
+            read_queue_nums: topic_config.read_queue_nums as i32,
+            write_queue_nums: topic_config.write_queue_nums as i32,
+            perm: topic_config.perm as i32,
+            topic_filter_type: CheetahString::from_string(
+                topic_config.topic_filter_type.to_string(),
+            ),
+            topic_sys_flag: Some(topic_config.topic_sys_flag as i32),
+            order: topic_config.order,
+            attributes: Some(CheetahString::from_string(
+                AttributeParser::parse_to_string(&attributes),
+            )),
+            force: None,
+            topic_request_header: None,
+        };
+        let request = RemotingCommand::create_request_command(
+            RequestCode::UpdateAndCreateTopic,
+            request_header,
+        );
+        self.invoke_broker_admin(addr, request, timeout_millis)
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn create_topic_list(
+        &mut self,
+        addr: &CheetahString,
+        topic_config_list: Vec<TopicConfig>,
+        timeout_millis: u64,
+    ) -> Result<()> {
+        let body = CreateTopicListRequestBody { topic_config_list };
+        let request =
+            RemotingCommand::create_remoting_command(RequestCode::UpdateAndCreateTopicList)
+                .set_body(body.encode()?);
+        self.invoke_broker_admin(addr, request, timeout_millis)
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn delete_topic_in_broker(
+        &mut self,
+        addr: &CheetahString,
+        topic: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<()> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::DeleteTopicInBroker,
+            DeleteTopicRequestHeader {
+                topic: topic.clone(),
+                topic_request_header: None,
+            },
+        );
+        self.invoke_broker_admin(addr, request, timeout_millis)
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn get_topic_config(
+        &mut self,
+        addr: &CheetahString,
+        topic: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<TopicConfig> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::GetTopicConfig,
+            GetTopicConfigRequestHeader {
+                topic: topic.clone(),
+                topic_request_header: None,
+            },
+        );
+        let response = self
+            .invoke_broker_admin(addr, request, timeout_millis)
+            .await?;
+        // Rust brokers nest the config next to the queue mapping, Java brokers flatten it.
+        match decode_response_body::<TopicConfigAndQueueMapping>(&response) {
+            Ok(topic_config_and_queue_mapping) => Ok(topic_config_and_queue_mapping.topic_config),
+            Err(_) => Ok(decode_response_body(&response)?),
+        }
+    }
+
+    pub async fn get_all_topic_config(
+        &mut self,
+        addr: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<TopicConfigSerializeWrapper> {
+        let request = RemotingCommand::create_remoting_command(RequestCode::GetAllTopicConfig);
+        let response = self
+            .invoke_broker_admin(addr, request, timeout_millis)
+            .await?;
+        Ok(decode_response_body(&response)?)
+    }
+
+    pub async fn get_topic_stats_info(
+        &mut self,
+        addr: &CheetahString,
+        topic: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<TopicStatsTable> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::GetTopicStatsInfo,
+            GetTopicStatsInfoRequestHeader {
+                topic: topic.clone(),
+                topic_request_header: None,
+            },
+        );
+        let response = self
+            .invoke_broker_admin(addr, request, timeout_millis)
+            .await?;
+        Ok(decode_response_body(&response)?)
+    }
+
+    pub async fn get_consume_stats(
+        &mut self,
+        addr: &CheetahString,
+        consumer_group: &CheetahString,
+        topic: Option<&CheetahString>,
+        timeout_millis: u64,
+    ) -> Result<ConsumeStats> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::GetConsumeStats,
+            GetConsumeStatsRequestHeader {
+                consumer_group: consumer_group.clone(),
+                topic: topic.cloned().unwrap_or_default(),
+                topic_request_header: None,
+            },
+        );
+        let response = self
+            .invoke_broker_admin(addr, request, timeout_millis)
+            .await?;
+        Ok(decode_response_body(&response)?)
+    }
+
+    pub async fn get_consumer_connection_list(
+        &mut self,
+        addr: &CheetahString,
+        consumer_group: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<ConsumerConnection> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::GetConsumerConnectionList,
+            GetConsumerConnectionListRequestHeader {
+                consumer_group: consumer_group.clone(),
+                rpc_request_header: None,
+            },
+        );
+        let response = self
+            .invoke_broker_admin(addr, request, timeout_millis)
+            .await?;
+        Ok(decode_response_body(&response)?)
+    }
+
+    pub async fn get_consumer_running_info(
+        &mut self,
+        addr: &CheetahString,
+        consumer_group: &CheetahString,
+        client_id: &CheetahString,
+        jstack: bool,
+        timeout_millis: u64,
+    ) -> Result<ConsumerRunningInfo> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::GetConsumerRunningInfo,
+            GetConsumerRunningInfoRequestHeader {
+                consumer_group: consumer_group.clone(),
+                client_id: client_id.clone(),
+                jstack_enable: jstack,
+                rpc_request_header: None,
+            },
+        );
+        let response = self
+            .invoke_broker_admin(addr, request, timeout_millis)
+            .await?;
+        Ok(decode_response_body(&response)?)
+    }
+
+    /// Asks the broker to reset the offsets of `group` on `topic`. All queues are reset to the
+    /// first message stored at or after `timestamp` when `queue_id` is negative, otherwise only
+    /// that queue is reset, to `offset` if given.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn invoke_broker_to_reset_offset(
+        &mut self,
+        addr: &CheetahString,
+        topic: &CheetahString,
+        group: &CheetahString,
+        timestamp: i64,
+        is_force: bool,
+        queue_id: i32,
+        offset: Option<i64>,
+        timeout_millis: u64,
+    ) -> Result<HashMap<MessageQueue, i64>> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::InvokeBrokerToResetOffset,
+            ResetOffsetRequestHeader {
+                topic: topic.clone(),
+                group: group.clone(),
+                queue_id,
+                offset,
+                timestamp,
+                is_force,
+                topic_request_header: None,
+            },
+        );
+        let response = self
+            .invoke_broker_admin(addr, request, timeout_millis)
+            .await?;
+        if response.body().is_none() {
+            return Ok(HashMap::new());
+        }
+        let body: ResetOffsetBody = decode_response_body(&response)?;
+        Ok(body.offset_table)
+    }
+
+    pub async fn clone_group_offset(
+        &mut self,
+        addr: &CheetahString,
+        src_group: &CheetahString,
+        dest_group: &CheetahString,
+        topic: Option<&CheetahString>,
+        is_offline: bool,
+        timeout_millis: u64,
+    ) -> Result<()> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::CloneGroupOffset,
+            CloneGroupOffsetRequestHeader {
+                src_group: src_group.clone(),
+                dest_group: dest_group.clone(),
+                topic: topic.cloned(),
+                offline: is_offline,
+                rpc_request_header: None,
+            },
+        );
+        self.invoke_broker_admin(addr, request, timeout_millis)
+            .await
+            .map(|_| ())
+    }
+
+    /// Reads the message stored at commit log offset `phy_offset` of a broker, the body is
+    /// decompressed.
+    pub async fn view_message(
+        &mut self,
+        addr: &CheetahString,
+        topic: &CheetahString,
+        phy_offset: i64,
+        timeout_millis: u64,
+    ) -> Result<MessageExt> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::ViewMessageById,
+            ViewMessageRequestHeader {
+                topic: topic.clone(),
+                offset: phy_offset,
+            },
+        );
+        let response = self
+            .invoke_broker_admin(addr, request, timeout_millis)
+            .await?;
+        let mut body = response.body().clone().unwrap_or_default();
+        if body.is_empty() {
+            return mq_client_err!(format!(
+                "broker {} returned no message at offset {}",
+                addr, phy_offset
+            ));
+        }
+        match MessageDecoder::decode(&mut body, true, true, false, false, false) {
+            Some(message) => Ok(message),
+            None => mq_client_err!(format!(
+                "decode message at offset {} from broker {} failed",
+                phy_offset, addr
+            )),
+        }
+    }
+
+    /// Looks messages up in the index of a broker by key, or by unique message id when
+    /// `unique_key` is set. A key that is not in the index yields an empty list.
+    pub async fn query_message(
+        &mut self,
+        addr: &CheetahString,
+        request_header: QueryMessageRequestHeader,
+        unique_key: bool,
+        timeout_millis: u64,
+    ) -> Result<Vec<MessageExt>> {
+        let mut request =
+            RemotingCommand::create_request_command(RequestCode::QueryMessage, request_header);
+        request.add_ext_field(mix_all::UNIQUE_MSG_QUERY_FLAG, unique_key.to_string());
+        let response = self
+            .remoting_client
+            .invoke_async(Some(addr), request, timeout_millis)
+            .await?;
+        match ResponseCode::from(response.code()) {
+            ResponseCode::Success => Ok(match response.body() {
+                Some(body) => MessageDecoder::decodes_batch(&mut body.clone(), true, true),
+                None => Vec::new(),
+            }),
+            ResponseCode::QueryNotFound => Ok(Vec::new()),
+            _ => client_broker_err!(
+                response.code(),
+                response.remark().map_or("".to_string(), |s| s.to_string()),
+                addr.to_string()
+            ),
+        }
+    }
+
+    /// Pulls at most `max_msg_nums` messages of a queue starting at `offset`, outside of any
+    /// consumer. Nothing is committed and the messages are decoded with decompressed bodies.
+    pub async fn pull_message_from_offset(
+        &mut self,
+        addr: &CheetahString,
+        consumer_group: &CheetahString,
+        mq: &MessageQueue,
+        offset: i64,
+        max_msg_nums: i32,
+        timeout_millis: u64,
+    ) -> Result<PullResult> {
+        let request_header = PullMessageRequestHeader {
+            consumer_group: consumer_group.clone(),
+            topic: mq.get_topic_cs().clone(),
+            queue_id: mq.get_queue_id(),
+            queue_offset: offset,
+            max_msg_nums,
+            sys_flag: PullSysFlag::build_sys_flag(false, false, true, false) as i32,
+            commit_offset: 0,
+            suspend_timeout_millis: 0,
+            subscription: Some(CheetahString::from_static_str("*")),
+            sub_version: 0,
+            expression_type: Some(CheetahString::from_static_str(ExpressionType::TAG)),
+            max_msg_bytes: None,
+            request_source: None,
+            proxy_forward_client_id: None,
+            topic_request: None,
+        };
+        let request =
+            RemotingCommand::create_request_command(RequestCode::PullMessage, request_header);
+        let mut pull_result_ext = self
+            .pull_message_sync(addr, request, timeout_millis)
+            .await?;
+        if pull_result_ext.pull_result.pull_status == PullStatus::Found {
+            let mut message_binary = pull_result_ext.message_binary.take().unwrap_or_default();
+            let messages = MessageDecoder::decodes_batch(&mut message_binary, true, true);
+            pull_result_ext.pull_result.msg_found_list =
+                Some(messages.into_iter().map(ArcMut::new).collect());
+        }
+        Ok(pull_result_ext.pull_result)
+    }
+
+    /// Asks the broker to push the message `msg_id` to one client of `consumer_group`, which
+    /// consumes it with its listener and reports the outcome.
+    pub async fn consume_message_directly(
+        &mut self,
+        addr: &CheetahString,
+        consumer_group: &CheetahString,
+        client_id: &CheetahString,
+        topic: &CheetahString,
+        msg_id: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<ConsumeMessageDirectlyResult> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::ConsumeMessageDirectly,
+            ConsumeMessageDirectlyResultRequestHeader {
+                consumer_group: consumer_group.clone(),
+                client_id: Some(client_id.clone()),
+                msg_id: Some(msg_id.clone()),
+                topic: Some(topic.clone()),
+                ..Default::default()
+            },
+        );
+        let response = self
+            .invoke_broker_admin(addr, request, timeout_millis)
+            .await?;
+        Ok(decode_response_body(&response)?)
+    }
+
+    pub async fn get_broker_runtime_info(
+        &mut self,
+        addr: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<KVTable> {
+        let request = RemotingCommand::create_remoting_command(RequestCode::GetBrokerRuntimeInfo);
+        let response = self
+            .invoke_broker_admin(addr, request, timeout_millis)
+            .await?;
+        Ok(decode_response_body(&response)?)
+    }
+
+    pub async fn get_broker_config(
+        &mut self,
+        addr: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<HashMap<CheetahString, CheetahString>> {
+        let request = RemotingCommand::create_remoting_command(RequestCode::GetBrokerConfig);
+        let response = self
+            .invoke_broker_admin(addr, request, timeout_millis)
+            .await?;
+        Ok(parse_config_properties(response.body().as_ref()))
+    }
+
+    pub async fn update_broker_config(
+        &mut self,
+        addr: &CheetahString,
+        properties: &HashMap<CheetahString, CheetahString>,
+        timeout_millis: u64,
+    ) -> Result<()> {
+        let request = RemotingCommand::create_remoting_command(RequestCode::UpdateBrokerConfig)
+            .set_body(properties_to_string(properties));
+        self.invoke_broker_admin(addr, request, timeout_millis)
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn query_topic_consume_by_who(
+        &mut self,
+        addr: &CheetahString,
+        topic: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<GroupList> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::QueryTopicConsumeByWho,
+            QueryTopicConsumeByWhoRequestHeader {
+                topic: topic.clone(),
+                topic_request_header: None,
+            },
+        );
+        let response = self
+            .invoke_broker_admin(addr, request, timeout_millis)
+            .await?;
+        Ok(decode_response_body(&response)?)
+    }
+
+    pub async fn query_topics_by_consumer(
+        &mut self,
+        addr: &CheetahString,
+        group: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<TopicList> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::QueryTopicsByConsumer,
+            QueryTopicsByConsumerRequestHeader {
+                group: group.clone(),
+                rpc_request_header: None,
+            },
+        );
+        let response = self
+            .invoke_broker_admin(addr, request, timeout_millis)
+            .await?;
+        Ok(decode_response_body(&response)?)
+    }
+
+    pub async fn create_subscription_group(
+        &mut self,
+        addr: &CheetahString,
+        config: &SubscriptionGroupConfig,
+        timeout_millis: u64,
+    ) -> Result<()> {
+        let request =
+            RemotingCommand::create_remoting_command(RequestCode::UpdateAndCreateSubscriptionGroup)
+                .set_body(config.encode()?);
+        self.invoke_broker_admin(addr, request, timeout_millis)
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn delete_subscription_group(
+        &mut self,
+        addr: &CheetahString,
+        group_name: &CheetahString,
+        clean_offset: bool,
+        timeout_millis: u64,
+    ) -> Result<()> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::DeleteSubscriptionGroup,
+            DeleteSubscriptionGroupRequestHeader {
+                group_name: group_name.clone(),
+                clean_offset,
+                rpc_request_header: None,
+            },
+        );
+        self.invoke_broker_admin(addr, request, timeout_millis)
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn get_subscription_group_config(
+        &mut self,
+        addr: &CheetahString,
+        group: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<SubscriptionGroupConfig> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::GetSubscriptionGroupConfig,
+            GetSubscriptionGroupConfigRequestHeader {
+                group: group.clone(),
+                rpc_request_header: None,
+            },
+        );
+        let response = self
+            .invoke_broker_admin(addr, request, timeout_millis)
+            .await?;
+        Ok(decode_response_body(&response)?)
+    }
+
+    pub async fn delete_expired_commit_log(
+        &mut self,
+        addr: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<bool> {
+        let request = RemotingCommand::create_remoting_command(RequestCode::DeleteExpiredCommitlog);
+        self.invoke_broker_admin(addr, request, timeout_millis)
+            .await
+            .map(|_| true)
+    }
+
+    pub async fn clean_expired_consume_queue(
+        &mut self,
+        addr: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<bool> {
+        let request =
+            RemotingCommand::create_remoting_command(RequestCode::CleanExpiredConsumequeue);
+        self.invoke_broker_admin(addr, request, timeout_millis)
+            .await
+            .map(|_| true)
+    }
+
+    pub async fn clean_unused_topic(
+        &mut self,
+        addr: &CheetahString,
+        timeout_millis: u64,
+    ) -> Result<bool> {
+        let request = RemotingCommand::create_remoting_command(RequestCode::CleanUnusedTopic);
+        self.invoke_broker_admin(addr, request, timeout_millis)
+            .await
+            .map(|_| true)
+    }
+
+    /// Invokes an admin request on a broker, responses other than success are turned into
+    /// broker errors.
+    async fn invoke_broker_admin(
+        &mut self,
+        addr: &CheetahString,
+        request: RemotingCommand,
+        timeout_millis: u64,
+    ) -> Result<RemotingCommand> {
+        let response = self
+            .remoting_client
+            .invoke_async(Some(addr), request, timeout_millis)
+            .await?;
+        if ResponseCode::from(response.code()) != ResponseCode::Success {
+            return client_broker_err!(
+                response.code(),
+                response.remark().map_or("".to_string(), |s| s.to_string()),
+                addr.to_string()
+            );
+        }
+        Ok(response)
+    }
+
+    /// Invokes an admin request on a name server, a random one is picked when `addr` is
+    /// `None`. Responses other than success are turned into client errors.
+    async fn invoke_name_server_admin(
+        &mut self,
+        addr: Option<&CheetahString>,
+        request: RemotingCommand,
+        timeout_millis: u64,
+    ) -> Result<RemotingCommand> {
+        let response = self
+            .remoting_client
+            .invoke_async(addr, request, timeout_millis)
+            .await?;
+        if ResponseCode::from(response.code()) != ResponseCode::Success {
+            return mq_client_err!(
+                response.code(),
+                response.remark().cloned().unwrap_or_default().to_string()
+            );
+        }
+        Ok(response)
+    }
+
+    pub async fn set_message_request_mode(
+        &mut self,
+        broker_addr: &CheetahString,
+        topic: &CheetahString,
+        consumer_group: &CheetahString,
+        mode: MessageRequestMode,
+        pop_share_queue_num: i32,
+        timeout_millis: u64,
+    ) -> Result<()> {
+        let body = SetMessageRequestModeRequestBody {
+            topic: topic.clone(),
+            consumer_group: consumer_group.clone(),
+            mode,
+            pop_share_queue_num,
+        };
+        let request = RemotingCommand::create_remoting_command(RequestCode::SetMessageRequestMode)
+            .set_body(
+                body.encode()
+                    .expect("encode SetMessageRequestModeRequestBody failed"),
+            );
+        let response = self
+            .remoting_client
+            .invoke_async(
+                Some(&mix_all::broker_vip_channel(
+                    self.client_config.vip_channel_enabled,
+                    broker_addr,
+                )),
+                request,
+                timeout_millis,
+            )
+            .await?;
+        if ResponseCode::from(response.code()) != ResponseCode::Success {
+            return mq_client_err!(
+                response.code(),
+                response.remark().cloned().unwrap_or_default().to_string()
+            );
+        }
+        Ok(())
+    }
+
+    pub async fn query_assignment(
+        &mut self,
+        addr: &CheetahString,
+        topic: CheetahString,
+        consumer_group: CheetahString,
+        client_id: CheetahString,
+        strategy_name: CheetahString,
+        message_model: MessageModel,
+        timeout: u64,
+    ) -> Result<Option<HashSet<MessageQueueAssignment>>> {
+        let request_body = QueryAssignmentRequestBody {
+            topic,
+            consumer_group,
+            client_id,
+            strategy_name,
+            message_model,
+        };
+        let request = RemotingCommand::new_request(
+            RequestCode::QueryAssignment,
+            request_body
+                .encode()
+                .expect("encode QueryAssignmentRequestBody failed"),
+        );
+        let response = self
+            .remoting_client
+            .invoke_async(
+                Some(&mix_all::broker_vip_channel(
+                    self.client_config.vip_channel_enabled,
+                    addr,
+                )),
+                request,
+                timeout,
+            )
+            .await?;
+
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            let body = response.body();
+            if let Some(body) = body {
+                let assignment = QueryAssignmentResponseBody::decode(body.as_ref());
+                if let Ok(assignment) = assignment {
+                    return Ok(Some(assignment.message_queue_assignments));
+                }
+            }
+            return Ok(None);
+        }
+
+        client_broker_err!(
+            response.code(),
+            response.remark().map_or("".to_string(), |s| s.to_string()),
+            addr.to_string()
+        )
+    }
+
+    pub async fn change_invisible_time_async(
+        &self,
+        broker_name: &CheetahString,
+        addr: &CheetahString,
+        request_header: ChangeInvisibleTimeRequestHeader,
+        timeout_millis: u64,
+        ack_callback: impl AckCallback,
+    ) -> crate::Result<()> {
+        let offset = request_header.offset;
+        let topic = request_header.topic.clone();
+        let queue_id = request_header.queue_id;
+        let request = RemotingCommand::create_request_command(
+            RequestCode::ChangeMessageInvisibleTime,
+            request_header,
+        );
+        match self
+            .remoting_client
+            .invoke_async(Some(addr), request, timeout_millis)
+            .await
+        {
+            Ok(response) => {
+                let response_header = response
+                    .decode_command_custom_header::<ChangeInvisibleTimeResponseHeader>()
+                    .map_err(RemotingError)?;
+                let ack_result = if ResponseCode::from(response.code()) == ResponseCode::Success {
+                    AckResult {
+                        status: AckStatus::Ok,
+                        pop_time: response_header.pop_time as i64,
+                        extra_info: CheetahString::from_string(format!(
+                            "{}{}{}",
+                            ExtraInfoUtil::build_extra_info(
+                                offset,
+                                response_header.pop_time as i64,
+                                response_header.invisible_time,
+                                response_header.revive_qid,
+                                &topic,
+                                broker_name,
+                                queue_id,
+                            ),
+                            MessageConst::KEY_SEPARATOR,
+                            offset
+                        )),
+                    }
+                } else {
+                    AckResult {
+                        status: AckStatus::NotExist,
+                        ..Default::default()
+                    }
+                };
+                ack_callback.on_success(ack_result);
+            }
+            Err(e) => {
+                ack_callback.on_exception(Box::new(e));
+            }
+        };
+        Ok(())
+    }
+
+    pub async fn pop_message_async<PC>(
+        &self,
+        broker_name: &CheetahString,
+        addr: &CheetahString,
+        request_header: PopMessageRequestHeader,
+        timeout_millis: u64,
+        mut pop_callback: PC,
+    ) -> Result<()>
+    where
+        PC: PopCallback + 'static,
+    {
+        let topic = request_header.topic.clone();
+        let order = request_header.order.unwrap_or_default();
+        let request =
+            RemotingCommand::create_request_command(RequestCode::PopMessage, request_header);
+        match self
+            .remoting_client
+            .invoke_async(Some(addr), request, timeout_millis)
+            .await
+        {
+            Ok(response) => {
+                let result = self.process_pop_response(broker_name, response, &topic, order);
+                match result {
+                    Ok(pop_result) => {
+                        pop_callback.on_success(pop_result).await;
+                    }
+                    Err(e) => {
+                        pop_callback.on_error(Box::new(e));
+                    }
+                }
+            }
+            Err(e) => {
+                pop_callback.on_error(Box::new(e));
+            }
+        }
+        Ok(())
+    }
+
+    fn process_pop_response(
+        &self,
+        broker_name: &CheetahString,
+        mut response: RemotingCommand,
+        topic: &CheetahString,
+        is_order: bool,
+    ) -> Result<PopResult> {
+        let response_code = ResponseCode::from(response.code());
+        let (pop_status, msg_found_list) = match response_code {
+            ResponseCode::Success => {
+                let messages = MessageDecoder::decodes_batch(
+                    response.get_body_mut().unwrap(),
+                    self.client_config.decode_read_body,
+                    self.client_config.decode_decompress_body,
+                );
+                (PopStatus::Found, messages)
+            }
+            ResponseCode::PollingFull => (PopStatus::PollingFull, vec![]),
+            ResponseCode::PollingTimeout | ResponseCode::PullNotFound => {
+                (PopStatus::PollingNotFound, vec![])
             }
             _ => {
                 return client_broker_err!(
@@ -1753,6 +2783,54 @@ impl MQClientAPIImpl {
     }
 }
 
+fn decode_response_body<T>(
+    response: &RemotingCommand,
+) -> std::result::Result<T, rocketmq_common::error::Error>
+where
+    T: RemotingDeserializable<Output = T>,
+{
+    T::decode(response.body().as_deref().unwrap_or_default())
+}
+
+/// Parses a config dump returned by a broker or name server. Java servers send
+/// `key=value` lines, the Rust name server sends a flat JSON object.
+fn parse_config_properties(body: Option<&Bytes>) -> HashMap<CheetahString, CheetahString> {
+    let content = body
+        .map(|body| String::from_utf8_lossy(body).into_owned())
+        .unwrap_or_default();
+    if content.trim_start().starts_with('{') {
+        if let Ok(map) = serde_json::from_str::<HashMap<String, serde_json::Value>>(&content) {
+            return map
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        serde_json::Value::String(value) => value,
+                        value => value.to_string(),
+                    };
+                    (
+                        CheetahString::from_string(key),
+                        CheetahString::from_string(value),
+                    )
+                })
+                .collect();
+        }
+    }
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().into(), value.trim().into()))
+        .collect()
+}
+
+fn properties_to_string(properties: &HashMap<CheetahString, CheetahString>) -> String {
+    properties
+        .iter()
+        .map(|(key, value)| format!("{}={}\n", key, value))
+        .collect()
+}
+
 fn build_queue_offset_sorted_map(
     topic: &str,
     msg_found_list: &[MessageExt],
@@ -1812,3 +2890,83 @@ fn build_queue_offset_sorted_map(
     }
     sort_map
 }
+
+#[cfg(test)]
+mod tests {
+    use rocketmq_common::common::message::message_single::Message;
+
+    use super::*;
+    use crate::producer::default_mq_producer::ProducerConfig;
+
+    #[test]
+    fn parses_properties_config_dump() {
+        let body = Bytes::from_static(b"# comment\nbrokerName=broker-a\nlistenPort = 10911\n");
+        let properties = parse_config_properties(Some(&body));
+        assert_eq!(properties.len(), 2);
+        assert_eq!(properties.get("brokerName").unwrap(), "broker-a");
+        assert_eq!(properties.get("listenPort").unwrap(), "10911");
+    }
+
+    #[test]
+    fn parses_json_config_dump() {
+        let body = Bytes::from_static(br#"{"kvConfigPath":"/tmp/kv.json","clusterTest":false}"#);
+        let properties = parse_config_properties(Some(&body));
+        assert_eq!(properties.get("kvConfigPath").unwrap(), "/tmp/kv.json");
+        assert_eq!(properties.get("clusterTest").unwrap(), "false");
+        assert!(parse_config_properties(None).is_empty());
+    }
+
+    #[test]
+    fn properties_round_trip() {
+        let properties = HashMap::from([(
+            CheetahString::from_static_str("flushDiskType"),
+            CheetahString::from_static_str("SYNC_FLUSH"),
+        )]);
+        let body = Bytes::from(properties_to_string(&properties));
+        assert_eq!(parse_config_properties(Some(&body)), properties);
+    }
+
+    #[test]
+    fn send_message_async_calls_back_once_when_broker_is_gone() {
+        // the producer owns a runtime, so it has to be dropped outside of the async context
+        let producer =
+            DefaultMQProducerImpl::new(ClientConfig::default(), ProducerConfig::default(), None);
+        let failures = Arc::new(AtomicU32::new(0));
+        let callback_failures = failures.clone();
+        let send_callback: SendMessageCallback = Arc::new(move |result, error| {
+            assert!(result.is_none());
+            assert!(error.unwrap().to_string().contains("broker-gone"));
+            callback_failures.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        });
+        let msg = Message::new("send_async_topic", b"body");
+        let times = AtomicU32::new(0);
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let instance =
+                MQClientInstance::new_arc(ClientConfig::default(), 0, "send_async_test", None);
+            let mut mq_client_api_impl = instance.mq_client_api_impl.clone().unwrap();
+            // nothing listens on the address and the broker is not in the route table, so the
+            // retry finds no address and reports the failure instead of panicking
+            mq_client_api_impl
+                .send_message_async(
+                    &CheetahString::from_static_str("127.0.0.1:1"),
+                    &CheetahString::from_static_str("broker-gone"),
+                    &msg,
+                    3000,
+                    RemotingCommand::create_remoting_command(RequestCode::SendMessage),
+                    Some(send_callback),
+                    None,
+                    Some(instance),
+                    2,
+                    &times,
+                    &mut None,
+                    &producer,
+                )
+                .await;
+        });
+
+        assert_eq!(failures.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(times.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+}
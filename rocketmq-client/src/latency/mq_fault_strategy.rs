@@ -81,7 +81,7 @@ impl MQFaultStrategy {
     }
 
     pub fn is_start_detector_enable(&self) -> bool {
-        unimplemented!("not implemented")
+        self.start_detector_enable.load(Ordering::Relaxed)
     }
 
     pub fn select_one_message_queue(
@@ -220,3 +220,23 @@ impl QueueFilter for AvailableFilter {
         flag
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_start_detector_enable_follows_client_config() {
+        let client_config = ClientConfig {
+            start_detector_enable: true,
+            ..ClientConfig::default()
+        };
+        assert!(MQFaultStrategy::new(&client_config).is_start_detector_enable());
+
+        let client_config = ClientConfig {
+            start_detector_enable: false,
+            ..ClientConfig::default()
+        };
+        assert!(!MQFaultStrategy::new(&client_config).is_start_detector_enable());
+    }
+}
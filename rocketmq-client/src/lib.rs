@@ -32,9 +32,11 @@ mod hook;
 mod implementation;
 mod latency;
 pub mod producer;
-mod trace;
+pub mod trace;
 pub mod utils;
 
 pub use crate::consumer::consumer_impl::pull_request_ext::PullResultExt;
+pub use crate::implementation::mq_client_api_impl::MQClientAPIImpl;
+pub use crate::implementation::mq_client_manager::MQClientManager;
 
 pub type Result<T> = std::result::Result<T, MQClientError>;
@@ -0,0 +1,436 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::str::FromStr;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::message::message_enum::MessageType;
+use rocketmq_common::common::message::MessageConst;
+
+use crate::producer::local_transaction_state::LocalTransactionState;
+use crate::trace::trace_bean::TraceBean;
+use crate::trace::trace_constants::TraceConstants;
+use crate::trace::trace_context::TraceContext;
+use crate::trace::trace_transfer_bean::TraceTransferBean;
+use crate::trace::trace_type::TraceType;
+
+/// Encodes trace contexts to the body of trace messages and decodes them back, in the format
+/// the Java client writes to the trace topic.
+pub struct TraceDataEncoder;
+
+impl TraceDataEncoder {
+    /// Decodes the trace records of a trace message body. Records written by older clients
+    /// carry fewer fields, records that cannot be parsed are skipped.
+    pub fn decoder_from_trace_data_string(trace_data: &str) -> Vec<TraceContext> {
+        trace_data
+            .split(TraceConstants::FIELD_SPLITOR)
+            .filter(|record| !record.trim().is_empty())
+            .filter_map(decode_record)
+            .collect()
+    }
+
+    /// Encodes a trace context to the data of a trace message and the keys it is indexed by.
+    pub fn encoder_from_context_bean(ctx: &TraceContext) -> Option<TraceTransferBean> {
+        let trace_type = ctx.trace_type?;
+        let beans = ctx.trace_beans.as_deref().unwrap_or_default();
+        let records: Vec<Vec<String>> = match trace_type {
+            TraceType::Pub => beans
+                .first()
+                .map(|bean| {
+                    vec![
+                        trace_type.to_string(),
+                        ctx.time_stamp.to_string(),
+                        ctx.region_id.to_string(),
+                        ctx.group_name.to_string(),
+                        bean.topic.to_string(),
+                        bean.msg_id.to_string(),
+                        bean.tags.to_string(),
+                        bean.keys.to_string(),
+                        bean.store_host.to_string(),
+                        bean.body_length.to_string(),
+                        ctx.cost_time.to_string(),
+                        message_type_ordinal(bean.msg_type).to_string(),
+                        bean.offset_msg_id.to_string(),
+                        ctx.is_success.to_string(),
+                        bean.client_host.to_string(),
+                    ]
+                })
+                .into_iter()
+                .collect(),
+            TraceType::SubBefore => beans
+                .iter()
+                .map(|bean| {
+                    vec![
+                        trace_type.to_string(),
+                        ctx.time_stamp.to_string(),
+                        ctx.region_id.to_string(),
+                        ctx.group_name.to_string(),
+                        ctx.request_id.to_string(),
+                        bean.msg_id.to_string(),
+                        bean.retry_times.to_string(),
+                        bean.keys.to_string(),
+                    ]
+                })
+                .collect(),
+            TraceType::SubAfter => beans
+                .iter()
+                .map(|bean| {
+                    vec![
+                        trace_type.to_string(),
+                        ctx.request_id.to_string(),
+                        bean.msg_id.to_string(),
+                        ctx.cost_time.to_string(),
+                        ctx.is_success.to_string(),
+                        bean.keys.to_string(),
+                        ctx.context_code.to_string(),
+                        ctx.time_stamp.to_string(),
+                        ctx.group_name.to_string(),
+                    ]
+                })
+                .collect(),
+            TraceType::EndTransaction => beans
+                .first()
+                .map(|bean| {
+                    vec![
+                        trace_type.to_string(),
+                        ctx.time_stamp.to_string(),
+                        ctx.region_id.to_string(),
+                        ctx.group_name.to_string(),
+                        bean.topic.to_string(),
+                        bean.msg_id.to_string(),
+                        bean.tags.to_string(),
+                        bean.keys.to_string(),
+                        bean.store_host.to_string(),
+                        message_type_ordinal(bean.msg_type).to_string(),
+                        bean.transaction_id
+                            .as_ref()
+                            .map(|transaction_id| transaction_id.to_string())
+                            .unwrap_or_default(),
+                        bean.transaction_state
+                            .map(|transaction_state| transaction_state.to_string())
+                            .unwrap_or_default(),
+                        bean.from_transaction_check.to_string(),
+                    ]
+                })
+                .into_iter()
+                .collect(),
+        };
+
+        let content_splitor = TraceConstants::CONTENT_SPLITOR.to_string();
+        let mut trans_data = String::with_capacity(256);
+        for fields in records {
+            trans_data.push_str(&fields.join(&content_splitor));
+            trans_data.push(TraceConstants::FIELD_SPLITOR);
+        }
+        let mut transfer_bean = TraceTransferBean {
+            trans_data: CheetahString::from_string(trans_data),
+            ..Default::default()
+        };
+        for bean in beans {
+            transfer_bean.trans_key.insert(bean.msg_id.clone());
+            transfer_bean.trans_key.extend(
+                bean.keys
+                    .split(MessageConst::KEY_SEPARATOR)
+                    .filter(|key| !key.is_empty())
+                    .map(CheetahString::from),
+            );
+        }
+        Some(transfer_bean)
+    }
+}
+
+fn decode_record(record: &str) -> Option<TraceContext> {
+    let line: Vec<&str> = record.split(TraceConstants::CONTENT_SPLITOR).collect();
+    let text = |index: usize| CheetahString::from(line.get(index).copied().unwrap_or_default());
+    let trace_type = TraceType::from_name(line[0])?;
+    let mut context = TraceContext {
+        trace_type: Some(trace_type),
+        is_success: true,
+        ..Default::default()
+    };
+    let mut bean = TraceBean {
+        client_host: CheetahString::default(),
+        store_host: CheetahString::default(),
+        ..Default::default()
+    };
+    match trace_type {
+        TraceType::Pub => {
+            if line.len() < 13 {
+                return None;
+            }
+            context.time_stamp = parse(&line, 1)?;
+            context.region_id = text(2);
+            context.group_name = text(3);
+            bean.topic = text(4);
+            bean.msg_id = text(5);
+            bean.tags = text(6);
+            bean.keys = text(7);
+            bean.store_host = text(8);
+            bean.body_length = parse(&line, 9)?;
+            context.cost_time = parse(&line, 10)?;
+            bean.msg_type = message_type_from_ordinal(parse(&line, 11)?);
+            if line.len() == 13 {
+                context.is_success = parse_bool(&line, 12);
+            } else {
+                bean.offset_msg_id = text(12);
+                context.is_success = parse_bool(&line, 13);
+                bean.client_host = text(14);
+            }
+        }
+        TraceType::SubBefore => {
+            if line.len() < 7 {
+                return None;
+            }
+            context.time_stamp = parse(&line, 1)?;
+            context.region_id = text(2);
+            context.group_name = text(3);
+            context.request_id = text(4);
+            bean.msg_id = text(5);
+            bean.retry_times = parse(&line, 6)?;
+            bean.keys = text(7);
+        }
+        TraceType::SubAfter => {
+            if line.len() < 5 {
+                return None;
+            }
+            context.request_id = text(1);
+            bean.msg_id = text(2);
+            context.cost_time = parse(&line, 3)?;
+            context.is_success = parse_bool(&line, 4);
+            bean.keys = text(5);
+            if line.len() >= 7 {
+                context.context_code = parse(&line, 6)?;
+            }
+            // Older clients leave the consume time and group to the matching SubBefore record.
+            if line.len() >= 9 {
+                context.time_stamp = parse(&line, 7)?;
+                context.group_name = text(8);
+            }
+        }
+        TraceType::EndTransaction => {
+            if line.len() < 13 {
+                return None;
+            }
+            context.time_stamp = parse(&line, 1)?;
+            context.region_id = text(2);
+            context.group_name = text(3);
+            bean.topic = text(4);
+            bean.msg_id = text(5);
+            bean.tags = text(6);
+            bean.keys = text(7);
+            bean.store_host = text(8);
+            bean.msg_type = message_type_from_ordinal(parse(&line, 9)?);
+            bean.transaction_id = Some(text(10));
+            bean.transaction_state = transaction_state_from_name(line[11]);
+            bean.from_transaction_check = parse_bool(&line, 12);
+        }
+    }
+    context.trace_beans = Some(vec![bean]);
+    Some(context)
+}
+
+fn parse<T: FromStr>(line: &[&str], index: usize) -> Option<T> {
+    line.get(index)?.trim().parse().ok()
+}
+
+/// Parses booleans like Java does, anything but `true` is false.
+fn parse_bool(line: &[&str], index: usize) -> bool {
+    line.get(index)
+        .is_some_and(|value| value.trim().eq_ignore_ascii_case("true"))
+}
+
+/// Trace records carry the ordinal of the Java `MessageType` enum, whose order this enum keeps.
+fn message_type_ordinal(msg_type: Option<MessageType>) -> i32 {
+    msg_type.unwrap_or_default() as i32
+}
+
+fn message_type_from_ordinal(ordinal: i32) -> Option<MessageType> {
+    match ordinal {
+        0 => Some(MessageType::NormalMsg),
+        1 => Some(MessageType::TransMsgHalf),
+        2 => Some(MessageType::TransMsgCommit),
+        3 => Some(MessageType::DelayMsg),
+        4 => Some(MessageType::OrderMsg),
+        _ => None,
+    }
+}
+
+fn transaction_state_from_name(name: &str) -> Option<LocalTransactionState> {
+    match name {
+        "COMMIT_MESSAGE" => Some(LocalTransactionState::CommitMessage),
+        "ROLLBACK_MESSAGE" => Some(LocalTransactionState::RollbackMessage),
+        "UNKNOW" | "UNKNOWN" => Some(LocalTransactionState::Unknown),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bean(msg_id: &str, keys: &str) -> TraceBean {
+        TraceBean {
+            topic: CheetahString::from("TopicTest"),
+            msg_id: CheetahString::from(msg_id),
+            offset_msg_id: CheetahString::from("7F00000100002A9F0000000000000100"),
+            tags: CheetahString::from("TagA"),
+            keys: CheetahString::from(keys),
+            store_host: CheetahString::from("127.0.0.1:10911"),
+            client_host: CheetahString::from("127.0.0.2"),
+            body_length: 11,
+            retry_times: 2,
+            msg_type: Some(MessageType::DelayMsg),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pub_context_round_trips() {
+        let context = TraceContext {
+            trace_type: Some(TraceType::Pub),
+            time_stamp: 1734784743000,
+            region_id: CheetahString::from("DefaultRegion"),
+            group_name: CheetahString::from("producer_group"),
+            cost_time: 3,
+            is_success: true,
+            trace_beans: Some(vec![bean("C0A8000100002A9F0000000000000001", "k1 k2")]),
+            ..Default::default()
+        };
+        let transfer_bean = TraceDataEncoder::encoder_from_context_bean(&context).unwrap();
+        assert_eq!(transfer_bean.trans_key.len(), 3);
+        assert!(transfer_bean.trans_key.contains("k2"));
+
+        let decoded = TraceDataEncoder::decoder_from_trace_data_string(&transfer_bean.trans_data);
+        assert_eq!(decoded.len(), 1);
+        let decoded = &decoded[0];
+        assert_eq!(decoded.trace_type, Some(TraceType::Pub));
+        assert_eq!(decoded.time_stamp, 1734784743000);
+        assert_eq!(decoded.group_name, "producer_group");
+        assert_eq!(decoded.cost_time, 3);
+        assert!(decoded.is_success);
+        let decoded_bean = &decoded.trace_beans.as_ref().unwrap()[0];
+        assert_eq!(decoded_bean.msg_id, "C0A8000100002A9F0000000000000001");
+        assert_eq!(decoded_bean.keys, "k1 k2");
+        assert_eq!(decoded_bean.body_length, 11);
+        assert_eq!(decoded_bean.msg_type, Some(MessageType::DelayMsg));
+        assert_eq!(
+            decoded_bean.offset_msg_id,
+            "7F00000100002A9F0000000000000100"
+        );
+        assert_eq!(decoded_bean.client_host, "127.0.0.2");
+    }
+
+    #[test]
+    fn consume_contexts_round_trip_every_bean() {
+        let beans = vec![bean("id-1", ""), bean("id-2", "")];
+        let before = TraceContext {
+            trace_type: Some(TraceType::SubBefore),
+            time_stamp: 100,
+            group_name: CheetahString::from("consumer_group"),
+            request_id: CheetahString::from("request-1"),
+            trace_beans: Some(beans.clone()),
+            ..Default::default()
+        };
+        let after = TraceContext {
+            trace_type: Some(TraceType::SubAfter),
+            time_stamp: 150,
+            group_name: CheetahString::from("consumer_group"),
+            request_id: CheetahString::from("request-1"),
+            cost_time: 50,
+            is_success: false,
+            context_code: 1,
+            trace_beans: Some(beans),
+            ..Default::default()
+        };
+        let trace_data = format!(
+            "{}{}",
+            TraceDataEncoder::encoder_from_context_bean(&before)
+                .unwrap()
+                .trans_data,
+            TraceDataEncoder::encoder_from_context_bean(&after)
+                .unwrap()
+                .trans_data
+        );
+
+        let decoded = TraceDataEncoder::decoder_from_trace_data_string(&trace_data);
+        assert_eq!(decoded.len(), 4);
+        assert_eq!(decoded[1].trace_type, Some(TraceType::SubBefore));
+        assert_eq!(decoded[1].request_id, "request-1");
+        assert_eq!(decoded[1].trace_beans.as_ref().unwrap()[0].msg_id, "id-2");
+        assert_eq!(decoded[1].trace_beans.as_ref().unwrap()[0].retry_times, 2);
+        assert_eq!(decoded[2].trace_type, Some(TraceType::SubAfter));
+        assert_eq!(decoded[2].time_stamp, 150);
+        assert_eq!(decoded[2].group_name, "consumer_group");
+        assert_eq!(decoded[2].cost_time, 50);
+        assert_eq!(decoded[2].context_code, 1);
+        assert!(!decoded[2].is_success);
+    }
+
+    #[test]
+    fn end_transaction_context_round_trips() {
+        let mut transaction_bean = bean("id-1", "");
+        transaction_bean.msg_type = Some(MessageType::TransMsgHalf);
+        transaction_bean.transaction_id = Some(CheetahString::from("transaction-1"));
+        transaction_bean.transaction_state = Some(LocalTransactionState::RollbackMessage);
+        transaction_bean.from_transaction_check = true;
+        let context = TraceContext {
+            trace_type: Some(TraceType::EndTransaction),
+            time_stamp: 200,
+            group_name: CheetahString::from("producer_group"),
+            trace_beans: Some(vec![transaction_bean]),
+            ..Default::default()
+        };
+        let transfer_bean = TraceDataEncoder::encoder_from_context_bean(&context).unwrap();
+
+        let decoded = TraceDataEncoder::decoder_from_trace_data_string(&transfer_bean.trans_data);
+        let decoded_bean = &decoded[0].trace_beans.as_ref().unwrap()[0];
+        assert_eq!(decoded[0].trace_type, Some(TraceType::EndTransaction));
+        assert_eq!(decoded_bean.msg_type, Some(MessageType::TransMsgHalf));
+        assert_eq!(
+            decoded_bean.transaction_id,
+            Some(CheetahString::from("transaction-1"))
+        );
+        assert_eq!(
+            decoded_bean.transaction_state,
+            Some(LocalTransactionState::RollbackMessage)
+        );
+        assert!(decoded_bean.from_transaction_check);
+    }
+
+    #[test]
+    fn decodes_records_of_older_clients_and_skips_malformed_ones() {
+        let trace_data = [
+            "Pub\u{1}100\u{1}region\u{1}group\u{1}TopicTest\u{1}id-1\u{1}TagA\u{1}k1\u{1}127.0.0.\
+             1:10911\u{1}11\u{1}2\u{1}0\u{1}true",
+            "SubAfter\u{1}request-1\u{1}id-1\u{1}7\u{1}true\u{1}k1",
+            "SubBefore\u{1}not-a-number\u{1}region\u{1}group\u{1}request-1\u{1}id-1\u{1}0",
+            "Unknown\u{1}1",
+        ]
+        .join("\u{2}");
+
+        let decoded = TraceDataEncoder::decoder_from_trace_data_string(&trace_data);
+        assert_eq!(decoded.len(), 2);
+        assert!(decoded[0].is_success);
+        assert_eq!(
+            decoded[0].trace_beans.as_ref().unwrap()[0].msg_type,
+            Some(MessageType::NormalMsg)
+        );
+        assert_eq!(decoded[1].trace_type, Some(TraceType::SubAfter));
+        assert_eq!(decoded[1].cost_time, 7);
+        assert_eq!(decoded[1].time_stamp, 0);
+        assert!(decoded[1].group_name.is_empty());
+    }
+}
@@ -25,6 +25,19 @@ pub enum TraceType {
     EndTransaction,
 }
 
+impl TraceType {
+    /// Parses the name a trace record starts with, the inverse of the `Display` impl.
+    pub fn from_name(name: &str) -> Option<TraceType> {
+        match name {
+            "Pub" => Some(TraceType::Pub),
+            "SubBefore" => Some(TraceType::SubBefore),
+            "SubAfter" => Some(TraceType::SubAfter),
+            "EndTransaction" => Some(TraceType::EndTransaction),
+            _ => None,
+        }
+    }
+}
+
 impl Display for TraceType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -72,6 +85,16 @@ mod tests {
         assert_eq!(output, "SubAfter");
     }
 
+    #[test]
+    fn trace_type_from_name() {
+        assert_eq!(TraceType::from_name("SubAfter"), Some(TraceType::SubAfter));
+        assert_eq!(
+            TraceType::from_name(&TraceType::EndTransaction.to_string()),
+            Some(TraceType::EndTransaction)
+        );
+        assert_eq!(TraceType::from_name("Sub"), None);
+    }
+
     #[test]
     fn trace_type_display_end_transaction() {
         let trace_type = TraceType::EndTransaction;
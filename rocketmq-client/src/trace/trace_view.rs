@@ -18,8 +18,13 @@
 use cheetah_string::CheetahString;
 use lazy_static::lazy_static;
 use rocketmq_common::common::message::message_enum::MessageType;
+use rocketmq_common::common::message::message_ext::MessageExt;
+use rocketmq_common::common::message::MessageTrait;
 use rocketmq_common::utils::util_all;
 
+use crate::trace::trace_data_encoder::TraceDataEncoder;
+use crate::trace::trace_type::TraceType;
+
 lazy_static! {
     static ref LOCAL_ADDRESS: CheetahString = util_all::get_ip_str();
 }
@@ -39,6 +44,9 @@ pub struct TraceView {
     pub topic: CheetahString,
     pub group_name: CheetahString,
     pub status: CheetahString,
+    pub trace_type: Option<TraceType>,
+    pub request_id: CheetahString,
+    pub retry_times: i32,
 }
 
 impl Default for TraceView {
@@ -57,7 +65,62 @@ impl Default for TraceView {
             topic: CheetahString::default(),
             group_name: CheetahString::default(),
             status: CheetahString::default(),
+            trace_type: None,
+            request_id: CheetahString::default(),
+            retry_times: 0,
+        }
+    }
+}
+
+impl TraceView {
+    /// Decodes the records of `message`, a message of the trace topic, that trace the message
+    /// whose unique id is `key`.
+    pub fn decode_from_trace_trans_data(key: &str, message: &MessageExt) -> Vec<TraceView> {
+        let Some(body) = message.get_body().filter(|body| !body.is_empty()) else {
+            return Vec::new();
+        };
+        let trace_data = String::from_utf8_lossy(body);
+        // Records without a client host were sent by the traced client itself.
+        let born_host = CheetahString::from_string(message.born_host().ip().to_string());
+        let mut views = Vec::new();
+        for context in TraceDataEncoder::decoder_from_trace_data_string(&trace_data) {
+            // A consume record traces a whole batch, every message of it is a bean.
+            for bean in context.trace_beans.iter().flatten() {
+                if bean.msg_id != key {
+                    continue;
+                }
+                let status = match (context.trace_type, bean.transaction_state) {
+                    (Some(TraceType::EndTransaction), Some(transaction_state)) => {
+                        transaction_state.to_string()
+                    }
+                    _ if context.is_success => "success".to_string(),
+                    _ => "failed".to_string(),
+                };
+                views.push(TraceView {
+                    msg_id: bean.msg_id.clone(),
+                    tags: bean.tags.clone(),
+                    keys: bean.keys.clone(),
+                    store_host: bean.store_host.clone(),
+                    client_host: if bean.client_host.is_empty() {
+                        born_host.clone()
+                    } else {
+                        bean.client_host.clone()
+                    },
+                    cost_time: context.cost_time as i64,
+                    msg_type: bean.msg_type,
+                    offset_msg_id: bean.offset_msg_id.clone(),
+                    time_stamp: context.time_stamp as i64,
+                    born_time: 0,
+                    topic: bean.topic.clone(),
+                    group_name: context.group_name.clone(),
+                    status: CheetahString::from_string(status),
+                    trace_type: context.trace_type,
+                    request_id: context.request_id.clone(),
+                    retry_times: bean.retry_times,
+                });
+            }
         }
+        views
     }
 }
 
@@ -83,6 +146,9 @@ mod tests {
         assert_eq!(trace_view.topic, CheetahString::default());
         assert_eq!(trace_view.group_name, CheetahString::default());
         assert_eq!(trace_view.status, CheetahString::default());
+        assert_eq!(trace_view.trace_type, None);
+        assert_eq!(trace_view.request_id, CheetahString::default());
+        assert_eq!(trace_view.retry_times, 0);
     }
 
     #[test]
@@ -101,6 +167,9 @@ mod tests {
             topic: CheetahString::from("topic"),
             group_name: CheetahString::from("group"),
             status: CheetahString::from("status"),
+            trace_type: Some(TraceType::Pub),
+            request_id: CheetahString::from("request_id"),
+            retry_times: 1,
         };
         assert_eq!(trace_view.msg_id, CheetahString::from("msg_id"));
         assert_eq!(trace_view.tags, CheetahString::from("tags"));
@@ -118,5 +187,85 @@ mod tests {
         assert_eq!(trace_view.topic, CheetahString::from("topic"));
         assert_eq!(trace_view.group_name, CheetahString::from("group"));
         assert_eq!(trace_view.status, CheetahString::from("status"));
+        assert_eq!(trace_view.trace_type, Some(TraceType::Pub));
+        assert_eq!(trace_view.request_id, CheetahString::from("request_id"));
+        assert_eq!(trace_view.retry_times, 1);
+    }
+
+    #[test]
+    fn decodes_the_records_of_the_traced_message() {
+        let records: [&[&str]; 4] = [
+            &[
+                "Pub",
+                "100",
+                "region",
+                "producer_group",
+                "TopicTest",
+                "id-1",
+                "TagA",
+                "k1",
+                "127.0.0.1:10911",
+                "11",
+                "2",
+                "0",
+                "offset-id",
+                "true",
+            ],
+            &[
+                "SubBefore",
+                "200",
+                "region",
+                "consumer_group",
+                "request-1",
+                "id-0",
+                "0",
+                "",
+            ],
+            &[
+                "SubBefore",
+                "200",
+                "region",
+                "consumer_group",
+                "request-1",
+                "id-1",
+                "1",
+                "",
+            ],
+            &[
+                "SubAfter",
+                "request-1",
+                "id-1",
+                "7",
+                "false",
+                "k1",
+                "0",
+                "207",
+                "consumer_group",
+            ],
+        ];
+        let trace_data = records
+            .iter()
+            .map(|record| record.join("\u{1}"))
+            .collect::<Vec<_>>()
+            .join("\u{2}");
+        let mut message = MessageExt::default();
+        message.set_body(bytes::Bytes::from(trace_data));
+
+        let views = TraceView::decode_from_trace_trans_data("id-1", &message);
+        assert_eq!(views.len(), 3);
+        assert_eq!(views[0].trace_type, Some(TraceType::Pub));
+        assert_eq!(views[0].group_name, CheetahString::from("producer_group"));
+        assert_eq!(views[0].offset_msg_id, CheetahString::from("offset-id"));
+        assert_eq!(views[0].status, CheetahString::from("success"));
+        assert_eq!(
+            views[0].client_host,
+            CheetahString::from_string(message.born_host().ip().to_string())
+        );
+        assert_eq!(views[1].trace_type, Some(TraceType::SubBefore));
+        assert_eq!(views[1].retry_times, 1);
+        assert_eq!(views[2].request_id, CheetahString::from("request-1"));
+        assert_eq!(views[2].cost_time, 7);
+        assert_eq!(views[2].status, CheetahString::from("failed"));
+        assert!(TraceView::decode_from_trace_trans_data("id-2", &message).is_empty());
     }
 }
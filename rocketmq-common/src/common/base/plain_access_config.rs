@@ -26,10 +26,13 @@ pub struct PlainAccessConfig {
     pub access_key: Option<CheetahString>,
     pub secret_key: Option<CheetahString>,
     pub white_remote_address: Option<CheetahString>,
+    #[serde(default)]
     pub admin: bool,
     pub default_topic_perm: Option<CheetahString>,
     pub default_group_perm: Option<CheetahString>,
+    #[serde(default)]
     pub topic_perms: Vec<CheetahString>,
+    #[serde(default)]
     pub group_perms: Vec<CheetahString>,
 }
 
@@ -205,6 +205,13 @@ pub struct BrokerConfig {
     pub pop_polling_size: usize,
     pub enable_pop_message_threshold: bool,
     pub pop_inflight_message_threshold: i64,
+    #[serde(default)]
+    pub acl_enable: bool,
+    /// The access key the broker signs its requests to the name server with.
+    #[serde(default)]
+    pub acl_access_key: Option<CheetahString>,
+    #[serde(default)]
+    pub acl_secret_key: Option<CheetahString>,
 }
 
 impl Default for BrokerConfig {
@@ -315,6 +322,9 @@ impl Default for BrokerConfig {
             pop_polling_size: 1024,
             enable_pop_message_threshold: false,
             pop_inflight_message_threshold: 10000,
+            acl_enable: false,
+            acl_access_key: None,
+            acl_secret_key: None,
         }
     }
 }
@@ -514,6 +524,7 @@ impl BrokerConfig {
             "namesrvAddr".into(),
             self.namesrv_addr.clone().unwrap_or_default(),
         );
+        properties.insert("aclEnable".into(), self.acl_enable.to_string().into());
         properties.insert(
             "fetchNameSrvAddrByDnsLookup".into(),
             self.fetch_name_srv_addr_by_dns_lookup.to_string().into(),
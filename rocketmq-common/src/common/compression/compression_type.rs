@@ -32,6 +32,24 @@ impl CompressionType {
         }
     }
 
+    /// Same as [`CompressionType::of`], but returns `None` for an unsupported name.
+    pub fn try_of(name: &str) -> Option<Self> {
+        match name.trim().to_uppercase().as_str() {
+            "LZ4" => Some(Self::LZ4),
+            "ZSTD" => Some(Self::Zstd),
+            "ZLIB" => Some(Self::Zlib),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::LZ4 => "LZ4",
+            Self::Zstd => "ZSTD",
+            Self::Zlib => "ZLIB",
+        }
+    }
+
     pub fn find_by_value(value: i32) -> Self {
         match value {
             1 => Self::LZ4,
@@ -86,6 +104,22 @@ impl CompressionType {
             }
         }
     }
+
+    /// Same as [`CompressionType::decompression`], but reports corrupted data as an error
+    /// instead of panicking, for data received from the network.
+    pub fn try_decompression(&self, data: &[u8]) -> io::Result<Bytes> {
+        match self {
+            CompressionType::LZ4 => decompress_size_prepended(data)
+                .map(Bytes::from)
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string())),
+            CompressionType::Zstd => zstd::decode_all(data.reader()).map(Bytes::from),
+            CompressionType::Zlib => {
+                let mut decompressed_data = Vec::new();
+                ZlibDecoder::new(data.reader()).read_to_end(&mut decompressed_data)?;
+                Ok(Bytes::from(decompressed_data))
+            }
+        }
+    }
 }
 
 struct BytesWriter {
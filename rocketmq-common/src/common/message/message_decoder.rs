@@ -537,6 +537,15 @@ pub fn decode_message(buffer: &mut Bytes) -> Message {
     }
 }
 
+/// Decodes an offset message id like [`decode_message_id`], but returns `None` instead of
+/// panicking when the id is not the hex encoding of an IPv4 or IPv6 store host and offset.
+pub fn try_decode_message_id(msg_id: &str) -> Option<MessageId> {
+    if !matches!(msg_id.len(), 32 | 56) || !msg_id.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(decode_message_id(msg_id))
+}
+
 pub fn decode_message_id(msg_id: &str) -> MessageId {
     let bytes = util_all::string_to_bytes(msg_id).unwrap();
     let mut buffer = Bytes::from(bytes);
@@ -675,7 +684,8 @@ pub fn encode(message_ext: &MessageExt, need_compress: bool) -> Result<Bytes> {
     }
 
     // 16 TOPIC
-    byte_buffer.put_i16(topic_len as i16);
+    // The magic code is V1, whose topic length takes one byte
+    byte_buffer.put_u8(topic_len as u8);
     byte_buffer.put_slice(topics);
 
     // 17 properties
@@ -782,7 +792,8 @@ pub fn encode_uniquely(message_ext: &MessageExt, need_compress: bool) -> Result<
     }
 
     // 14 TOPIC
-    byte_buffer.put_i16(topic_len as i16);
+    // The magic code is V1, whose topic length takes one byte
+    byte_buffer.put_u8(topic_len as u8);
     byte_buffer.put_slice(topics);
 
     // 15 properties
@@ -853,6 +864,19 @@ mod tests {
         assert_eq!(message_id.offset, 860316681131967304);
     }
 
+    #[test]
+    fn try_decode_message_id_rejects_malformed_ids() {
+        assert_eq!(
+            try_decode_message_id("7F0000010007D8260BF075769D36C348"),
+            Some(decode_message_id("7F0000010007D8260BF075769D36C348"))
+        );
+        assert_eq!(try_decode_message_id("7F0000010007D826"), None);
+        assert_eq!(
+            try_decode_message_id("ZZ0000010007D8260BF075769D36C348"),
+            None
+        );
+    }
+
     #[test]
     fn encode_with_compression() {
         let mut message_ext = MessageExt::default();
@@ -873,6 +897,28 @@ mod tests {
         assert!(!bytes.is_empty());
     }
 
+    #[test]
+    fn encode_then_decode_round_trips() {
+        let mut message_ext = MessageExt::default();
+        message_ext.set_topic(CheetahString::from_static_str("round_trip_topic"));
+        message_ext.set_body(Bytes::from("Hello, World!"));
+        message_ext.put_property(
+            CheetahString::from_static_str(MessageConst::PROPERTY_KEYS),
+            CheetahString::from_static_str("k1"),
+        );
+        message_ext.set_queue_offset(7);
+        message_ext.set_store_timestamp(1_700_000_000_000);
+
+        let mut bytes = encode(&message_ext, false).unwrap();
+        let decoded = decode(&mut bytes, true, false, false, false, false).unwrap();
+        assert_eq!(decoded.get_topic(), "round_trip_topic");
+        assert_eq!(decoded.get_body().unwrap().as_ref(), b"Hello, World!");
+        assert_eq!(decoded.get_keys().unwrap(), "k1");
+        assert_eq!(decoded.queue_offset(), 7);
+        assert_eq!(decoded.store_timestamp(), 1_700_000_000_000);
+        assert!(!bytes.has_remaining());
+    }
+
     #[test]
     fn encode_with_empty_body() {
         let mut message_ext = MessageExt::default();
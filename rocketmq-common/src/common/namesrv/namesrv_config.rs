@@ -24,6 +24,7 @@ use serde_json::Value;
 
 use crate::common::mix_all::ROCKETMQ_HOME_ENV;
 use crate::common::mix_all::ROCKETMQ_HOME_PROPERTY;
+use crate::common::server::tls_config::TlsConfig;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct NamesrvConfig {
@@ -92,6 +93,12 @@ pub struct NamesrvConfig {
 
     #[serde(alias = "configBlackList")]
     pub config_black_list: String,
+
+    #[serde(alias = "tlsConfig", default)]
+    pub tls_config: TlsConfig,
+
+    #[serde(alias = "aclEnable", default)]
+    pub acl_enable: bool,
 }
 
 impl Default for NamesrvConfig {
@@ -139,6 +146,8 @@ impl Default for NamesrvConfig {
             wait_seconds_for_service: 45,
             delete_topic_with_broker_registration: false,
             config_black_list: "configBlackList;configStorePath;kvConfigPath".to_string(),
+            tls_config: TlsConfig::default(),
+            acl_enable: false,
         }
     }
 }
@@ -17,11 +17,80 @@
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::common::server::tls_config::TlsConfig;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ServerConfig {
     pub listen_port: u32,
     pub bind_address: String,
+    #[serde(default)]
+    pub tls_config: TlsConfig,
+    /// Max oneway requests processed concurrently by the server.
+    #[serde(default = "default_server_oneway_semaphore_value")]
+    pub server_oneway_semaphore_value: usize,
+    /// Max request-response requests processed concurrently by the server.
+    #[serde(default = "default_server_async_semaphore_value")]
+    pub server_async_semaphore_value: usize,
+    /// Max time to wait for a processing permit before the request is rejected as overloaded.
+    #[serde(default = "default_server_permit_timeout_millis")]
+    pub server_permit_timeout_millis: u64,
+    /// Channels without any read or write for this long are closed, `0` never closes them.
+    #[serde(default = "default_server_channel_max_idle_time_seconds")]
+    pub server_channel_max_idle_time_seconds: u64,
+    /// Reading from a channel pauses once its pending outbound bytes exceed this mark, `0`
+    /// disables write buffer flow control.
+    #[serde(default)]
+    pub write_buffer_high_water_mark: usize,
+    /// Reading from a paused channel resumes once its pending outbound bytes drop to this mark.
+    #[serde(default)]
+    pub write_buffer_low_water_mark: usize,
+    /// Announces GoAway to the connected clients on shutdown and waits for them to drain.
+    #[serde(default)]
+    pub enable_shutdown_gracefully: bool,
+    /// Max time to wait for the clients to drain on a graceful shutdown.
+    #[serde(default = "default_shutdown_wait_time_seconds")]
+    pub shutdown_wait_time_seconds: u64,
+    /// Bodies at least this large are compressed for peers accepting compressed bodies, `0`
+    /// disables remoting body compression.
+    #[serde(default = "default_body_compress_threshold")]
+    pub body_compress_threshold: usize,
+    /// Compression type of the remoting bodies, one of `LZ4`, `ZSTD` or `ZLIB`.
+    #[serde(default = "default_body_compress_type")]
+    pub body_compress_type: String,
+    /// Path of a Unix domain socket the server additionally listens on, for clients running on
+    /// the same host. `None` only listens on TCP. Unix domain socket connections are plaintext,
+    /// so the server refuses to start with one in TLS enforcing mode.
+    #[serde(default)]
+    pub unix_socket_path: Option<String>,
+}
+
+fn default_server_oneway_semaphore_value() -> usize {
+    256
+}
+
+fn default_server_async_semaphore_value() -> usize {
+    64
+}
+
+fn default_server_permit_timeout_millis() -> u64 {
+    3000
+}
+
+fn default_server_channel_max_idle_time_seconds() -> u64 {
+    120
+}
+
+fn default_shutdown_wait_time_seconds() -> u64 {
+    30
+}
+
+fn default_body_compress_threshold() -> usize {
+    4 * 1024
+}
+
+fn default_body_compress_type() -> String {
+    "LZ4".to_string()
 }
 
 impl Default for ServerConfig {
@@ -29,6 +98,18 @@ impl Default for ServerConfig {
         ServerConfig {
             listen_port: 10911,
             bind_address: "0.0.0.0".to_string(),
+            tls_config: TlsConfig::default(),
+            server_oneway_semaphore_value: default_server_oneway_semaphore_value(),
+            server_async_semaphore_value: default_server_async_semaphore_value(),
+            server_permit_timeout_millis: default_server_permit_timeout_millis(),
+            server_channel_max_idle_time_seconds: default_server_channel_max_idle_time_seconds(),
+            write_buffer_high_water_mark: 0,
+            write_buffer_low_water_mark: 0,
+            enable_shutdown_gracefully: false,
+            shutdown_wait_time_seconds: default_shutdown_wait_time_seconds(),
+            body_compress_threshold: default_body_compress_threshold(),
+            body_compress_type: default_body_compress_type(),
+            unix_socket_path: None,
         }
     }
 }
@@ -41,4 +122,8 @@ impl ServerConfig {
     pub fn listen_port(&self) -> u32 {
         self.listen_port
     }
+
+    pub fn tls_config(&self) -> &TlsConfig {
+        &self.tls_config
+    }
 }
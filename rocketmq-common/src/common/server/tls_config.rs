@@ -0,0 +1,135 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::env;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+pub const TLS_SERVER_MODE: &str = "tls.server.mode";
+pub const TLS_SERVER_NEED_CLIENT_AUTH: &str = "tls.server.need.client.auth";
+pub const TLS_SERVER_CERT_PATH: &str = "tls.server.certPath";
+pub const TLS_SERVER_KEY_PATH: &str = "tls.server.keyPath";
+pub const TLS_SERVER_TRUST_CERT_PATH: &str = "tls.server.trustCertPath";
+pub const TLS_ENABLE: &str = "tls.enable";
+pub const TLS_CLIENT_CERT_PATH: &str = "tls.client.certPath";
+pub const TLS_CLIENT_KEY_PATH: &str = "tls.client.keyPath";
+pub const TLS_CLIENT_TRUST_CERT_PATH: &str = "tls.client.trustCertPath";
+pub const TLS_TEST_MODE_ENABLE: &str = "tls.test.mode.enable";
+
+/// How the server listener treats TLS.
+///
+/// * `Disabled`: only plaintext connections are accepted.
+/// * `Permissive`: both plaintext and TLS connections are accepted, which allows clients to be
+///   migrated one by one.
+/// * `Enforcing`: only TLS connections are accepted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TlsMode {
+    #[default]
+    Disabled,
+    Permissive,
+    Enforcing,
+}
+
+impl TlsMode {
+    pub fn parse(mode: &str) -> Option<TlsMode> {
+        match mode.trim().to_ascii_lowercase().as_str() {
+            "disabled" => Some(TlsMode::Disabled),
+            "permissive" => Some(TlsMode::Permissive),
+            "enforcing" => Some(TlsMode::Enforcing),
+            _ => None,
+        }
+    }
+}
+
+/// TLS settings of the remoting layer, the server side is used by the listener and the client
+/// side by the outgoing connections. Every field falls back to the system property of the same
+/// name as the Java implementation, so it can be set from the environment as well.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TlsConfig {
+    pub server_mode: TlsMode,
+    /// Whether the server requires the client to present a certificate signed by
+    /// `server_trust_cert_path`.
+    pub server_need_client_auth: bool,
+    pub server_cert_path: Option<String>,
+    pub server_key_path: Option<String>,
+    pub server_trust_cert_path: Option<String>,
+    /// Whether the outgoing connections use TLS.
+    pub client_enable: bool,
+    pub client_cert_path: Option<String>,
+    pub client_key_path: Option<String>,
+    pub client_trust_cert_path: Option<String>,
+    /// Skips the verification of the server certificate, only for testing.
+    pub test_mode_enable: bool,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        TlsConfig {
+            server_mode: env::var(TLS_SERVER_MODE)
+                .ok()
+                .and_then(|mode| TlsMode::parse(mode.as_str()))
+                .unwrap_or_default(),
+            server_need_client_auth: env::var(TLS_SERVER_NEED_CLIENT_AUTH)
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            server_cert_path: env::var(TLS_SERVER_CERT_PATH).ok(),
+            server_key_path: env::var(TLS_SERVER_KEY_PATH).ok(),
+            server_trust_cert_path: env::var(TLS_SERVER_TRUST_CERT_PATH).ok(),
+            client_enable: env::var(TLS_ENABLE)
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            client_cert_path: env::var(TLS_CLIENT_CERT_PATH).ok(),
+            client_key_path: env::var(TLS_CLIENT_KEY_PATH).ok(),
+            client_trust_cert_path: env::var(TLS_CLIENT_TRUST_CERT_PATH).ok(),
+            test_mode_enable: env::var(TLS_TEST_MODE_ENABLE)
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tls_mode_parse_ignores_case() {
+        assert_eq!(TlsMode::parse("Permissive"), Some(TlsMode::Permissive));
+        assert_eq!(TlsMode::parse(" enforcing "), Some(TlsMode::Enforcing));
+        assert_eq!(TlsMode::parse("disabled"), Some(TlsMode::Disabled));
+        assert_eq!(TlsMode::parse("strict"), None);
+    }
+
+    #[test]
+    fn tls_config_deserializes_partial_json() {
+        let config: TlsConfig = serde_json::from_str(
+            r#"{"serverMode":"permissive","serverCertPath":"/etc/rocketmq/server.pem"}"#,
+        )
+        .unwrap();
+        assert_eq!(config.server_mode, TlsMode::Permissive);
+        assert_eq!(
+            config.server_cert_path.as_deref(),
+            Some("/etc/rocketmq/server.pem")
+        );
+        assert!(!config.client_enable);
+    }
+}
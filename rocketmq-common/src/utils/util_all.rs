@@ -38,6 +38,7 @@ use chrono::Timelike;
 use chrono::Utc;
 use local_ip_address::Error;
 use once_cell::sync::Lazy;
+use sysinfo::Disks;
 use tracing::error;
 use tracing::info;
 
@@ -126,34 +127,8 @@ pub fn get_disk_partition_space_used_percent(path: &str) -> f64 {
         return -1.0;
     }
 
-    match fs::metadata(path) {
-        Ok(metadata) => {
-            let total_space = metadata.len();
-            if total_space > 0 {
-                match (fs::metadata(path), fs::metadata(path)) {
-                    (Ok(metadata1), Ok(metadata2)) => {
-                        let free_space = metadata1.len();
-                        let usable_space = metadata2.len();
-                        let used_space = total_space.saturating_sub(free_space);
-                        let entire_space = used_space + usable_space;
-                        let round_num = if used_space * 100 % entire_space != 0 {
-                            1
-                        } else {
-                            0
-                        };
-                        let result = used_space * 100 / entire_space + round_num;
-                        return result as f64 / 100.0;
-                    }
-                    (Err(e), _) | (_, Err(e)) => {
-                        error!(
-                            "Error when measuring disk space usage, got exception: {:?}",
-                            e
-                        );
-                        return -1.0;
-                    }
-                }
-            }
-        }
+    let path = match path.canonicalize() {
+        Ok(path) => path,
         Err(e) => {
             error!(
                 "Error when measuring disk space usage, got exception: {:?}",
@@ -161,9 +136,33 @@ pub fn get_disk_partition_space_used_percent(path: &str) -> f64 {
             );
             return -1.0;
         }
+    };
+    // the partition holding the path is the one with the longest matching mount point
+    let disks = Disks::new_with_refreshed_list();
+    let disk = disks
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len());
+    match disk {
+        Some(disk) if disk.total_space() > 0 => {
+            let entire_space = disk.total_space();
+            let used_space = entire_space.saturating_sub(disk.available_space());
+            let round_num = if used_space * 100 % entire_space != 0 {
+                1
+            } else {
+                0
+            };
+            let result = used_space * 100 / entire_space + round_num;
+            result as f64 / 100.0
+        }
+        _ => {
+            error!(
+                "Error when measuring disk space usage, no disk partition found for path: {}",
+                path.to_string_lossy()
+            );
+            -1.0
+        }
     }
-
-    -1.0
 }
 
 pub fn bytes_to_string(src: &[u8]) -> String {
@@ -355,6 +354,22 @@ mod tests {
         assert_eq!(time_millis_to_human_string(timestamp), "20210701120000000");
     }
 
+    #[test]
+    fn get_disk_partition_space_used_percent_returns_ratio_for_existing_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let ratio = get_disk_partition_space_used_percent(dir.path().to_str().unwrap());
+        assert!(ratio <= 1.0);
+    }
+
+    #[test]
+    fn get_disk_partition_space_used_percent_returns_negative_for_invalid_path() {
+        assert_eq!(get_disk_partition_space_used_percent(""), -1.0);
+        assert_eq!(
+            get_disk_partition_space_used_percent("/non/existing/path/for/rocketmq"),
+            -1.0
+        );
+    }
+
     #[test]
     fn is_path_exists_returns_true_for_existing_path() {
         assert_eq!(is_path_exists("."), true);
@@ -0,0 +1,331 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashSet;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use cheetah_string::CheetahString;
+use clap::Args;
+use clap::ValueEnum;
+use rocketmq_client_rust::consumer::default_mq_push_consumer::DefaultMQPushConsumer;
+use rocketmq_client_rust::consumer::listener::consume_concurrently_context::ConsumeConcurrentlyContext;
+use rocketmq_client_rust::consumer::listener::consume_concurrently_status::ConsumeConcurrentlyStatus;
+use rocketmq_client_rust::consumer::listener::message_listener_concurrently::MessageListenerConcurrently;
+use rocketmq_client_rust::consumer::mq_push_consumer::MQPushConsumer;
+use rocketmq_client_rust::consumer::pull_status::PullStatus;
+use rocketmq_client_rust::factory::mq_client_instance::topic_route_data2topic_subscribe_info;
+use rocketmq_common::common::message::message_enum::MessageRequestMode;
+use rocketmq_common::common::message::message_ext::MessageExt;
+use rocketmq_common::common::message::message_queue::MessageQueue;
+use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_common::UtilAll::time_millis_to_human_string2;
+use rocketmq_tools::admin::default_mq_admin_ext::DefaultMQAdminExt;
+use rocketmq_tools::admin::mq_admin_ext_async::MQAdminExt;
+use tokio::task::JoinSet;
+
+use crate::stats::tps;
+use crate::stats::LatencyHistogram;
+use crate::stats::LatencySnapshot;
+use crate::BenchResult;
+
+const PULL_BATCH_SIZE: i32 = 32;
+const PULL_MIN_IDLE_BACKOFF: Duration = Duration::from_millis(100);
+const PULL_MAX_IDLE_BACKOFF: Duration = Duration::from_secs(1);
+const POP_SHARE_QUEUE_NUM: i32 = 8;
+
+#[derive(Args, Debug, Clone)]
+pub struct ConsumerArgs {
+    #[arg(
+        short,
+        long,
+        default_value = "127.0.0.1:9876",
+        help = "name server address list"
+    )]
+    pub namesrv: String,
+
+    #[arg(
+        short,
+        long,
+        default_value = "BenchmarkTest",
+        help = "topic to consume"
+    )]
+    pub topic: String,
+
+    #[arg(
+        short,
+        long,
+        default_value = "benchmark_consumer",
+        help = "consumer group"
+    )]
+    pub group: String,
+
+    #[arg(short, long, value_enum, default_value_t = ConsumeMode::Push, help = "consume mode")]
+    pub mode: ConsumeMode,
+
+    #[arg(
+        short,
+        long,
+        default_value = "*",
+        help = "tag expression of the subscription, ignored by the pull mode"
+    )]
+    pub expression: String,
+
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..), help = "report interval in seconds")]
+    pub report_interval: u64,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsumeMode {
+    /// push consumer with client side rebalance
+    Push,
+    /// push consumer switched to pop mode on the brokers
+    Pop,
+    /// pulls every queue from its max offset, without a consumer group offset
+    Pull,
+}
+
+#[derive(Default)]
+struct ConsumerStats {
+    received: AtomicU64,
+    /// born timestamp to consume
+    born_to_consume: LatencyHistogram,
+    /// store timestamp to consume
+    store_to_consume: LatencyHistogram,
+}
+
+impl ConsumerStats {
+    fn on_message(&self, msg: &MessageExt) {
+        let now = get_current_millis() as i64;
+        self.received.fetch_add(1, Ordering::Relaxed);
+        self.born_to_consume
+            .record_millis(now - msg.born_timestamp());
+        self.store_to_consume
+            .record_millis(now - msg.store_timestamp());
+    }
+}
+
+/// Prints the consumer statistics of every interval and keeps the totals for the summary.
+struct ConsumerReporter {
+    stats: Arc<ConsumerStats>,
+    begin: Instant,
+    last_report: Instant,
+    last_received: u64,
+    total_b2c: LatencySnapshot,
+    total_s2c: LatencySnapshot,
+}
+
+impl ConsumerReporter {
+    fn new(stats: Arc<ConsumerStats>) -> Self {
+        Self {
+            stats,
+            begin: Instant::now(),
+            last_report: Instant::now(),
+            last_received: 0,
+            total_b2c: LatencySnapshot::default(),
+            total_s2c: LatencySnapshot::default(),
+        }
+    }
+
+    fn report(&mut self) {
+        let received = self.stats.received.load(Ordering::Relaxed);
+        let b2c = self.stats.born_to_consume.drain();
+        let s2c = self.stats.store_to_consume.drain();
+        self.total_b2c.merge(&b2c);
+        self.total_s2c.merge(&s2c);
+        println!(
+            "Current Time: {} | Consume TPS: {} | B2C RT(ms) {} | S2C RT(ms) {}",
+            time_millis_to_human_string2(get_current_millis() as i64),
+            tps(received - self.last_received, self.last_report.elapsed()),
+            b2c.describe(),
+            s2c.describe(),
+        );
+        self.last_received = received;
+        self.last_report = Instant::now();
+    }
+
+    fn summary(&mut self) {
+        self.report();
+        let received = self.stats.received.load(Ordering::Relaxed);
+        println!(
+            "Summary: Consumed {} messages in {:.1}s | Average TPS: {} | B2C RT(ms) {} | S2C \
+             RT(ms) {}",
+            received,
+            self.begin.elapsed().as_secs_f64(),
+            tps(received, self.begin.elapsed()),
+            self.total_b2c.describe(),
+            self.total_s2c.describe(),
+        );
+    }
+}
+
+struct BenchmarkListener {
+    stats: Arc<ConsumerStats>,
+}
+
+impl MessageListenerConcurrently for BenchmarkListener {
+    fn consume_message(
+        &self,
+        msgs: &[&MessageExt],
+        _context: &ConsumeConcurrentlyContext,
+    ) -> rocketmq_client_rust::Result<ConsumeConcurrentlyStatus> {
+        for msg in msgs {
+            self.stats.on_message(msg);
+        }
+        Ok(ConsumeConcurrentlyStatus::ConsumeSuccess)
+    }
+}
+
+pub async fn run_consumer(args: ConsumerArgs) -> BenchResult<()> {
+    let stats = Arc::new(ConsumerStats::default());
+    let mut admin = DefaultMQAdminExt::new();
+    admin.set_namesrv_addr(args.namesrv.as_str());
+    admin.set_instance_name(format!("rocketmq-bench-{}", std::process::id()));
+    MQAdminExt::start(&mut admin).await?;
+    let admin = Arc::new(admin);
+
+    let stopped = Arc::new(AtomicBool::new(false));
+    let mut workers = JoinSet::new();
+    // DefaultMQPushConsumer can not be shut down yet, it is kept alive until the process exits
+    let mut _push_consumer = None;
+    match args.mode {
+        ConsumeMode::Push | ConsumeMode::Pop => {
+            if args.mode == ConsumeMode::Pop {
+                switch_request_mode(&admin, &args, MessageRequestMode::Pop).await?;
+            }
+            let mut consumer = DefaultMQPushConsumer::builder()
+                .consumer_group(args.group.as_str())
+                .name_server_addr(args.namesrv.as_str())
+                .client_rebalance(args.mode == ConsumeMode::Push)
+                .build();
+            consumer.subscribe(args.topic.as_str(), args.expression.as_str())?;
+            consumer.register_message_listener_concurrently(BenchmarkListener {
+                stats: stats.clone(),
+            });
+            consumer.start().await?;
+            _push_consumer = Some(consumer);
+        }
+        ConsumeMode::Pull => {
+            let route = admin
+                .examine_topic_route_info(CheetahString::from(args.topic.as_str()))
+                .await?;
+            for mq in topic_route_data2topic_subscribe_info(args.topic.as_str(), &route) {
+                // An offset beyond the queue end is corrected to the max offset by the broker,
+                // so pulling starts from the newest messages like a fresh consumer group.
+                workers.spawn(pull_loop(
+                    admin.clone(),
+                    mq,
+                    i64::MAX,
+                    stats.clone(),
+                    stopped.clone(),
+                ));
+            }
+        }
+    }
+    println!(
+        "Consumer started, topic: {}, group: {}, mode: {:?}",
+        args.topic, args.group, args.mode
+    );
+
+    let mut reporter = ConsumerReporter::new(stats);
+    let period = Duration::from_secs(args.report_interval);
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+    let signal = rocketmq_rust::wait_for_signal();
+    tokio::pin!(signal);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => reporter.report(),
+            _ = &mut signal => break,
+        }
+    }
+    stopped.store(true, Ordering::Release);
+    while workers.join_next().await.is_some() {}
+    // the pull tasks are done, so this is the last reference to the admin
+    if let Ok(mut admin) = Arc::try_unwrap(admin) {
+        MQAdminExt::shutdown(&mut admin).await;
+    }
+    reporter.summary();
+    Ok(())
+}
+
+/// Pop consumption is decided by the brokers, switch the group on every broker of the topic.
+async fn switch_request_mode(
+    admin: &DefaultMQAdminExt,
+    args: &ConsumerArgs,
+    mode: MessageRequestMode,
+) -> BenchResult<()> {
+    let route = admin
+        .examine_topic_route_info(CheetahString::from(args.topic.as_str()))
+        .await?;
+    let broker_addrs = route
+        .broker_datas
+        .iter()
+        .flat_map(|broker_data| broker_data.broker_addrs().values().cloned())
+        .collect::<HashSet<_>>();
+    for broker_addr in broker_addrs {
+        admin
+            .set_message_request_mode(
+                broker_addr,
+                CheetahString::from(args.topic.as_str()),
+                CheetahString::from(args.group.as_str()),
+                mode,
+                POP_SHARE_QUEUE_NUM,
+                3_000,
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+async fn pull_loop(
+    admin: Arc<DefaultMQAdminExt>,
+    mq: MessageQueue,
+    mut offset: i64,
+    stats: Arc<ConsumerStats>,
+    stopped: Arc<AtomicBool>,
+) {
+    let mut idle_backoff = PULL_MIN_IDLE_BACKOFF;
+    while !stopped.load(Ordering::Acquire) {
+        let result = match admin
+            .pull_message_by_offset(mq.clone(), offset, PULL_BATCH_SIZE)
+            .await
+        {
+            Ok(result) => result,
+            Err(_) => {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                continue;
+            }
+        };
+        if let Some(msgs) = result.msg_found_list() {
+            for msg in msgs {
+                stats.on_message(msg);
+            }
+        }
+        offset = result.next_begin_offset() as i64;
+        if *result.pull_status() == PullStatus::Found {
+            idle_backoff = PULL_MIN_IDLE_BACKOFF;
+        } else {
+            // Back off on idle queues so auto created topics with many queues do not flood the
+            // broker with empty pulls
+            tokio::time::sleep(idle_backoff).await;
+            idle_backoff = (idle_backoff * 2).min(PULL_MAX_IDLE_BACKOFF);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+mod consumer;
+mod producer;
+mod stats;
+
+use clap::Parser;
+use clap::Subcommand;
+
+use crate::consumer::run_consumer;
+use crate::consumer::ConsumerArgs;
+use crate::producer::run_producer;
+use crate::producer::ProducerArgs;
+
+pub type BenchResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+#[derive(Parser, Debug)]
+#[command(author = "mxsm", version = "0.2.0", about = "RocketMQ Benchmark(Rust)")]
+struct BenchCli {
+    #[command(subcommand)]
+    command: BenchCommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum BenchCommands {
+    #[command(about = "send messages and report send TPS, RT and failures")]
+    Producer(ProducerArgs),
+
+    #[command(about = "consume messages and report consume TPS and end-to-end latency")]
+    Consumer(ConsumerArgs),
+}
+
+#[rocketmq_rust::main]
+async fn main() -> BenchResult<()> {
+    // client logs are only wanted when asked for, they would bury the reports
+    if std::env::var("RUST_LOG").is_ok() {
+        rocketmq_common::log::init_logger();
+    }
+    match BenchCli::parse().command {
+        BenchCommands::Producer(args) => run_producer(args).await,
+        BenchCommands::Consumer(args) => run_consumer(args).await,
+    }
+}
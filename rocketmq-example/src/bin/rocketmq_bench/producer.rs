@@ -0,0 +1,472 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::any::Any;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use clap::Args;
+use clap::ValueEnum;
+use rocketmq_client_rust::producer::default_mq_producer::DefaultMQProducer;
+use rocketmq_client_rust::producer::local_transaction_state::LocalTransactionState;
+use rocketmq_client_rust::producer::mq_producer::MQProducer;
+use rocketmq_client_rust::producer::send_result::SendResult;
+use rocketmq_client_rust::producer::send_status::SendStatus;
+use rocketmq_client_rust::producer::transaction_listener::TransactionListener;
+use rocketmq_client_rust::producer::transaction_mq_producer::TransactionMQProducer;
+use rocketmq_common::common::message::message_ext::MessageExt;
+use rocketmq_common::common::message::message_single::Message;
+use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_common::UtilAll::time_millis_to_human_string2;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::stats::tps;
+use crate::stats::LatencyHistogram;
+use crate::stats::LatencySnapshot;
+use crate::BenchResult;
+
+#[derive(Args, Debug, Clone)]
+pub struct ProducerArgs {
+    #[arg(
+        short,
+        long,
+        default_value = "127.0.0.1:9876",
+        help = "name server address list"
+    )]
+    pub namesrv: String,
+
+    #[arg(
+        short,
+        long,
+        default_value = "BenchmarkTest",
+        help = "topic to send to"
+    )]
+    pub topic: String,
+
+    #[arg(
+        short,
+        long,
+        default_value = "benchmark_producer",
+        help = "producer group"
+    )]
+    pub group: String,
+
+    #[arg(
+        short = 'w',
+        long,
+        default_value_t = 64,
+        help = "number of sending tasks"
+    )]
+    pub threads: usize,
+
+    #[arg(
+        short = 's',
+        long,
+        default_value_t = 128,
+        help = "message body size in bytes"
+    )]
+    pub message_size: usize,
+
+    #[arg(
+        short = 'b',
+        long,
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "number of messages per send request, more than 1 sends batches"
+    )]
+    pub batch_size: u32,
+
+    #[arg(short, long, value_enum, default_value_t = SendMode::Sync, help = "send mode")]
+    pub mode: SendMode,
+
+    #[arg(
+        short = 'c',
+        long,
+        default_value_t = 0,
+        help = "number of distinct tags, 0 sends no tag"
+    )]
+    pub tag_count: u64,
+
+    #[arg(
+        short,
+        long,
+        default_value_t = false,
+        help = "set a unique key on every message"
+    )]
+    pub keys: bool,
+
+    #[arg(
+        short,
+        long,
+        default_value_t = 0,
+        help = "delay level of the messages, 0 disables delay"
+    )]
+    pub delay_level: i32,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["batch_size", "mode", "delay_level"],
+        help = "send transactional messages, the local transaction always commits"
+    )]
+    pub transaction: bool,
+
+    #[arg(
+        short = 'q',
+        long,
+        default_value_t = 0,
+        help = "number of messages to send, 0 sends until interrupted"
+    )]
+    pub message_num: u64,
+
+    #[arg(
+        long,
+        default_value_t = 1024,
+        help = "max in flight requests in async mode"
+    )]
+    pub max_in_flight: usize,
+
+    #[arg(long, default_value_t = 3000, help = "send timeout in milliseconds")]
+    pub send_timeout: u64,
+
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..), help = "report interval in seconds")]
+    pub report_interval: u64,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendMode {
+    Sync,
+    Async,
+    Oneway,
+}
+
+#[derive(Default)]
+struct ProducerStats {
+    /// messages acknowledged with `SEND_OK`
+    send_success: AtomicU64,
+    /// requests that failed with an error
+    send_failed: AtomicU64,
+    /// responses with a status other than `SEND_OK`
+    response_failed: AtomicU64,
+    rt: LatencyHistogram,
+}
+
+impl ProducerStats {
+    fn on_response(&self, elapsed: Duration, messages: u64, status: &SendStatus) {
+        self.rt.record(elapsed);
+        if *status == SendStatus::SendOk {
+            self.send_success.fetch_add(messages, Ordering::Relaxed);
+        } else {
+            self.response_failed.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn on_error(&self) {
+        self.send_failed.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Prints the producer statistics of every interval and keeps the totals for the summary.
+struct ProducerReporter {
+    stats: Arc<ProducerStats>,
+    begin: Instant,
+    last_report: Instant,
+    last_success: u64,
+    total_rt: LatencySnapshot,
+}
+
+impl ProducerReporter {
+    fn new(stats: Arc<ProducerStats>) -> Self {
+        Self {
+            stats,
+            begin: Instant::now(),
+            last_report: Instant::now(),
+            last_success: 0,
+            total_rt: LatencySnapshot::default(),
+        }
+    }
+
+    fn report(&mut self) {
+        let success = self.stats.send_success.load(Ordering::Relaxed);
+        let rt = self.stats.rt.drain();
+        self.total_rt.merge(&rt);
+        println!(
+            "Current Time: {} | Send TPS: {} | RT(ms) {} | Send Failed: {} | Response Failed: {}",
+            time_millis_to_human_string2(get_current_millis() as i64),
+            tps(success - self.last_success, self.last_report.elapsed()),
+            rt.describe(),
+            self.stats.send_failed.load(Ordering::Relaxed),
+            self.stats.response_failed.load(Ordering::Relaxed),
+        );
+        self.last_success = success;
+        self.last_report = Instant::now();
+    }
+
+    fn summary(&mut self) {
+        self.report();
+        let success = self.stats.send_success.load(Ordering::Relaxed);
+        println!(
+            "Summary: Sent {} messages in {:.1}s | Average TPS: {} | RT(ms) {} | Send Failed: {} \
+             | Response Failed: {}",
+            success,
+            self.begin.elapsed().as_secs_f64(),
+            tps(success, self.begin.elapsed()),
+            self.total_rt.describe(),
+            self.stats.send_failed.load(Ordering::Relaxed),
+            self.stats.response_failed.load(Ordering::Relaxed),
+        );
+    }
+}
+
+#[derive(Clone)]
+enum BenchProducer {
+    Default(DefaultMQProducer),
+    Transaction(TransactionMQProducer),
+}
+
+impl BenchProducer {
+    async fn start(&mut self) -> BenchResult<()> {
+        match self {
+            BenchProducer::Default(producer) => producer.start().await?,
+            BenchProducer::Transaction(producer) => producer.start().await?,
+        }
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) {
+        match self {
+            BenchProducer::Default(producer) => producer.shutdown().await,
+            BenchProducer::Transaction(producer) => producer.shutdown().await,
+        }
+    }
+}
+
+struct CommitTransactionListener;
+
+impl TransactionListener for CommitTransactionListener {
+    fn execute_local_transaction(
+        &self,
+        _msg: &Message,
+        _arg: Option<&(dyn Any + Send + Sync)>,
+    ) -> LocalTransactionState {
+        LocalTransactionState::CommitMessage
+    }
+
+    fn check_local_transaction(&self, _msg: &MessageExt) -> LocalTransactionState {
+        LocalTransactionState::CommitMessage
+    }
+}
+
+pub async fn run_producer(args: ProducerArgs) -> BenchResult<()> {
+    if args.mode == SendMode::Oneway && args.batch_size > 1 {
+        return Err("oneway mode does not support batch send".into());
+    }
+    let mut producer = if args.transaction {
+        BenchProducer::Transaction(
+            TransactionMQProducer::builder()
+                .producer_group(args.group.as_str())
+                .name_server_addr(args.namesrv.as_str())
+                .send_msg_timeout(args.send_timeout as u32)
+                .topics(vec![args.topic.as_str()])
+                .transaction_listener(CommitTransactionListener)
+                .build(),
+        )
+    } else {
+        BenchProducer::Default(
+            DefaultMQProducer::builder()
+                .producer_group(args.group.as_str())
+                .name_server_addr(args.namesrv.as_str())
+                .send_msg_timeout(args.send_timeout as u32)
+                .build(),
+        )
+    };
+    producer.start().await?;
+    println!(
+        "Producer started, topic: {}, tasks: {}, message size: {}B, batch size: {}, mode: {:?}, \
+         transaction: {}",
+        args.topic, args.threads, args.message_size, args.batch_size, args.mode, args.transaction
+    );
+
+    let args = Arc::new(args);
+    let stats = Arc::new(ProducerStats::default());
+    let stopped = Arc::new(AtomicBool::new(false));
+    let issued = Arc::new(AtomicU64::new(0));
+    let in_flight = Arc::new(Semaphore::new(args.max_in_flight));
+    let body = message_body(args.message_size);
+    let mut workers = JoinSet::new();
+    for _ in 0..args.threads {
+        workers.spawn(send_loop(
+            producer.clone(),
+            args.clone(),
+            body.clone(),
+            stats.clone(),
+            stopped.clone(),
+            issued.clone(),
+            in_flight.clone(),
+        ));
+    }
+
+    let mut reporter = ProducerReporter::new(stats);
+    let period = Duration::from_secs(args.report_interval);
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+    let signal = rocketmq_rust::wait_for_signal();
+    tokio::pin!(signal);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => reporter.report(),
+            _ = &mut signal => break,
+            finished = workers.join_next() => {
+                if finished.is_none() {
+                    break;
+                }
+            }
+        }
+    }
+    stopped.store(true, Ordering::Release);
+    while workers.join_next().await.is_some() {}
+    // wait for the callbacks of the async requests still in flight, they time out by themselves
+    let _ = tokio::time::timeout(
+        Duration::from_millis(args.send_timeout * 2),
+        in_flight.acquire_many(args.max_in_flight as u32),
+    )
+    .await;
+    producer.shutdown().await;
+    reporter.summary();
+    Ok(())
+}
+
+async fn send_loop(
+    mut producer: BenchProducer,
+    args: Arc<ProducerArgs>,
+    body: Vec<u8>,
+    stats: Arc<ProducerStats>,
+    stopped: Arc<AtomicBool>,
+    issued: Arc<AtomicU64>,
+    in_flight: Arc<Semaphore>,
+) {
+    let batch_size = args.batch_size as u64;
+    while !stopped.load(Ordering::Acquire) {
+        let first = issued.fetch_add(batch_size, Ordering::Relaxed);
+        if args.message_num > 0 && first >= args.message_num {
+            break;
+        }
+        let last = if args.message_num > 0 {
+            (first + batch_size).min(args.message_num)
+        } else {
+            first + batch_size
+        };
+        let mut msgs = (first..last)
+            .map(|sequence| build_message(&args, &body, sequence))
+            .collect::<Vec<_>>();
+        let count = msgs.len() as u64;
+        let begin = Instant::now();
+        match &mut producer {
+            BenchProducer::Transaction(producer) => {
+                match producer
+                    .send_message_in_transaction::<()>(msgs.pop().unwrap(), None)
+                    .await
+                {
+                    Ok(result) => match result.send_result {
+                        Some(send_result) => {
+                            stats.on_response(begin.elapsed(), count, &send_result.send_status)
+                        }
+                        None => stats.on_error(),
+                    },
+                    Err(_) => stats.on_error(),
+                }
+            }
+            BenchProducer::Default(producer) => match args.mode {
+                SendMode::Sync => {
+                    let result = if count > 1 {
+                        producer.send_batch(msgs).await
+                    } else {
+                        producer.send(msgs.pop().unwrap()).await
+                    };
+                    match result {
+                        Ok(send_result) => {
+                            stats.on_response(begin.elapsed(), count, &send_result.send_status)
+                        }
+                        Err(_) => stats.on_error(),
+                    }
+                }
+                SendMode::Oneway => match producer.send_oneway(msgs.pop().unwrap()).await {
+                    Ok(()) => stats.on_response(begin.elapsed(), count, &SendStatus::SendOk),
+                    Err(_) => stats.on_error(),
+                },
+                SendMode::Async => {
+                    let permit = match in_flight.clone().acquire_owned().await {
+                        Ok(permit) => Mutex::new(Some(permit)),
+                        Err(_) => break,
+                    };
+                    let callback_stats = stats.clone();
+                    let callback =
+                        move |result: Option<&SendResult>,
+                              error: Option<&dyn std::error::Error>| {
+                            match (result, error) {
+                                (Some(send_result), None) => callback_stats.on_response(
+                                    begin.elapsed(),
+                                    count,
+                                    &send_result.send_status,
+                                ),
+                                _ => callback_stats.on_error(),
+                            }
+                            // the callback runs once, release the in flight slot
+                            permit.lock().unwrap().take();
+                        };
+                    let result = if count > 1 {
+                        producer.send_batch_with_callback(msgs, callback).await
+                    } else {
+                        producer
+                            .send_with_callback(msgs.pop().unwrap(), callback)
+                            .await
+                    };
+                    if result.is_err() {
+                        stats.on_error();
+                    }
+                }
+            },
+        }
+    }
+}
+
+fn build_message(args: &ProducerArgs, body: &[u8], sequence: u64) -> Message {
+    let mut msg = Message::new(args.topic.as_str(), body);
+    if args.tag_count > 0 {
+        msg.set_tags(format!("tag{}", sequence % args.tag_count).into());
+    }
+    if args.keys {
+        msg.set_keys(format!("bench-{}-{}", std::process::id(), sequence).into());
+    }
+    if args.delay_level > 0 {
+        msg.set_delay_time_level(args.delay_level);
+    }
+    msg
+}
+
+fn message_body(size: usize) -> Vec<u8> {
+    b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789"
+        .iter()
+        .cycle()
+        .take(size)
+        .copied()
+        .collect()
+}
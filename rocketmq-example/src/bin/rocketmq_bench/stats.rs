@@ -0,0 +1,211 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+/// Latencies above this are all counted in the last bucket.
+const MAX_TRACKED_LATENCY_MS: usize = 10_000;
+
+/// A lock free latency histogram with 1ms buckets. The reporter drains it every interval, so
+/// each report only covers the latencies recorded since the previous one.
+pub struct LatencyHistogram {
+    buckets: Box<[AtomicU64]>,
+    total_micros: AtomicU64,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: (0..=MAX_TRACKED_LATENCY_MS)
+                .map(|_| AtomicU64::new(0))
+                .collect(),
+            total_micros: AtomicU64::new(0),
+        }
+    }
+}
+
+impl LatencyHistogram {
+    pub fn record(&self, latency: Duration) {
+        self.record_micros(latency.as_micros() as u64);
+    }
+
+    pub fn record_millis(&self, latency_ms: i64) {
+        self.record_micros(latency_ms.max(0) as u64 * 1000);
+    }
+
+    fn record_micros(&self, latency_micros: u64) {
+        let bucket = ((latency_micros / 1000) as usize).min(MAX_TRACKED_LATENCY_MS);
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.total_micros
+            .fetch_add(latency_micros, Ordering::Relaxed);
+    }
+
+    /// Moves the recorded latencies out of the histogram.
+    pub fn drain(&self) -> LatencySnapshot {
+        LatencySnapshot {
+            buckets: self
+                .buckets
+                .iter()
+                .map(|bucket| bucket.swap(0, Ordering::Relaxed))
+                .collect(),
+            total_micros: self.total_micros.swap(0, Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct LatencySnapshot {
+    buckets: Vec<u64>,
+    total_micros: u64,
+}
+
+impl Default for LatencySnapshot {
+    fn default() -> Self {
+        Self {
+            buckets: vec![0; MAX_TRACKED_LATENCY_MS + 1],
+            total_micros: 0,
+        }
+    }
+}
+
+impl LatencySnapshot {
+    pub fn merge(&mut self, other: &LatencySnapshot) {
+        for (bucket, count) in self.buckets.iter_mut().zip(other.buckets.iter()) {
+            *bucket += count;
+        }
+        self.total_micros += other.total_micros;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.buckets.iter().sum()
+    }
+
+    pub fn average_millis(&self) -> f64 {
+        match self.count() {
+            0 => 0.0,
+            count => self.total_micros as f64 / count as f64 / 1000.0,
+        }
+    }
+
+    pub fn max_millis(&self) -> u64 {
+        self.buckets
+            .iter()
+            .rposition(|count| *count > 0)
+            .unwrap_or_default() as u64
+    }
+
+    /// The smallest latency in ms that `quantile` of the samples do not exceed.
+    pub fn percentile_millis(&self, quantile: f64) -> u64 {
+        let count = self.count();
+        if count == 0 {
+            return 0;
+        }
+        let rank = ((count as f64 * quantile).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (latency, bucket) in self.buckets.iter().enumerate() {
+            seen += bucket;
+            if seen >= rank {
+                return latency as u64;
+            }
+        }
+        MAX_TRACKED_LATENCY_MS as u64
+    }
+
+    pub fn describe(&self) -> String {
+        format!(
+            "AVG: {:.3} | P50: {} | P90: {} | P99: {} | P999: {} | MAX: {}",
+            self.average_millis(),
+            self.percentile_millis(0.5),
+            self.percentile_millis(0.9),
+            self.percentile_millis(0.99),
+            self.percentile_millis(0.999),
+            self.max_millis()
+        )
+    }
+}
+
+/// Messages per second over `elapsed`.
+pub fn tps(count: u64, elapsed: Duration) -> u64 {
+    match elapsed.as_millis() {
+        0 => count,
+        millis => (count as u128 * 1000 / millis) as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot_of(latencies_ms: &[i64]) -> LatencySnapshot {
+        let histogram = LatencyHistogram::default();
+        for latency in latencies_ms {
+            histogram.record_millis(*latency);
+        }
+        histogram.drain()
+    }
+
+    #[test]
+    fn percentiles_of_recorded_latencies() {
+        let snapshot = snapshot_of(&(1..=100).collect::<Vec<_>>());
+        assert_eq!(snapshot.count(), 100);
+        assert_eq!(snapshot.percentile_millis(0.5), 50);
+        assert_eq!(snapshot.percentile_millis(0.9), 90);
+        assert_eq!(snapshot.percentile_millis(0.99), 99);
+        assert_eq!(snapshot.percentile_millis(0.999), 100);
+        assert_eq!(snapshot.max_millis(), 100);
+        assert_eq!(snapshot.average_millis(), 50.5);
+    }
+
+    #[test]
+    fn empty_snapshot_reports_zero() {
+        let snapshot = LatencySnapshot::default();
+        assert_eq!(snapshot.count(), 0);
+        assert_eq!(snapshot.percentile_millis(0.99), 0);
+        assert_eq!(snapshot.average_millis(), 0.0);
+        assert_eq!(snapshot.max_millis(), 0);
+    }
+
+    #[test]
+    fn latencies_out_of_range_are_clamped() {
+        let snapshot = snapshot_of(&[-5, 60_000]);
+        assert_eq!(snapshot.percentile_millis(0.5), 0);
+        assert_eq!(snapshot.max_millis(), MAX_TRACKED_LATENCY_MS as u64);
+    }
+
+    #[test]
+    fn drain_resets_and_merge_aggregates() {
+        let histogram = LatencyHistogram::default();
+        histogram.record(Duration::from_millis(3));
+        histogram.record(Duration::from_millis(7));
+        let first = histogram.drain();
+        assert_eq!(histogram.drain().count(), 0);
+
+        let mut total = LatencySnapshot::default();
+        total.merge(&first);
+        total.merge(&snapshot_of(&[20]));
+        assert_eq!(total.count(), 3);
+        assert_eq!(total.average_millis(), 10.0);
+        assert_eq!(total.max_millis(), 20);
+    }
+
+    #[test]
+    fn tps_over_elapsed_time() {
+        assert_eq!(tps(500, Duration::from_millis(250)), 2000);
+        assert_eq!(tps(7, Duration::ZERO), 7);
+    }
+}
@@ -39,12 +39,16 @@ async fn main() -> anyhow::Result<()> {
     );
     let config_file = PathBuf::from(home).join("conf").join("namesrv.toml");
     let namesrv_config = ParseConfigFile::parse_config_file::<NamesrvConfig>(config_file.clone())?;
+    let server_config = ServerConfig {
+        listen_port: args.port,
+        bind_address: args.ip,
+        tls_config: namesrv_config.tls_config.clone(),
+        unix_socket_path: args.unix_socket_path,
+        ..Default::default()
+    };
     Builder::new()
         .set_name_server_config(namesrv_config)
-        .set_server_config(ServerConfig {
-            listen_port: args.port,
-            bind_address: args.ip,
-        })
+        .set_server_config(server_config)
         .build()
         .boot()
         .await;
@@ -82,4 +86,7 @@ struct Args {
     /// rocketmq name remoting_server config file
     #[arg(short, long, value_name = "FILE", default_missing_value = "None")]
     config: Option<PathBuf>,
+    /// Unix domain socket the name remoting_server additionally listens on
+    #[arg(long, value_name = "PATH", required = false)]
+    unix_socket_path: Option<String>,
 }
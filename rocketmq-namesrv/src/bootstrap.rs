@@ -15,10 +15,14 @@
  * limitations under the License.
  */
 use std::net::SocketAddr;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
 use cheetah_string::CheetahString;
+use rocketmq_acl::plain::plain_access_validator::PlainAccessValidator;
+use rocketmq_acl::plain::plain_permission_manager::PlainPermissionManager;
+use rocketmq_common::common::config_manager::ConfigManager;
 use rocketmq_common::common::namesrv::namesrv_config::NamesrvConfig;
 use rocketmq_common::common::server::config::ServerConfig;
 use rocketmq_common::utils::network_util::NetworkUtil;
@@ -33,6 +37,7 @@ use rocketmq_rust::wait_for_signal;
 use rocketmq_rust::ArcMut;
 use tokio::sync::broadcast;
 use tracing::info;
+use tracing::warn;
 
 use crate::processor::ClientRequestProcessor;
 use crate::processor::NameServerRequestProcessor;
@@ -82,9 +87,15 @@ impl NameServerRuntime {
         let (notify_conn_disconnect, _) = broadcast::channel::<SocketAddr>(100);
         let receiver = notify_conn_disconnect.subscribe();
         let request_processor = self.init_processors(receiver);
-        let server = RocketMQServer::new(Arc::new(self.inner.server_config.clone()));
+        let mut server = RocketMQServer::new(Arc::new(self.inner.server_config.clone()));
+        if self.inner.name_server_config.acl_enable {
+            server.register_rpc_hook(Box::new(PlainAccessValidator::new(Arc::new(
+                self.load_plain_permission_manager(),
+            ))));
+        }
         tokio::spawn(async move {
-            server.run(request_processor).await;
+            // failures to start are logged by the server
+            let _ = server.run(request_processor).await;
         });
         let namesrv = CheetahString::from_string(format!(
             "{}:{}",
@@ -107,6 +118,24 @@ impl NameServerRuntime {
         }
     }
 
+    /// Loads the plain ACL file stored next to the kv config file.
+    fn load_plain_permission_manager(&self) -> PlainPermissionManager {
+        let acl_path = Path::new(self.inner.name_server_config.kv_config_path.as_str())
+            .with_file_name("plain_acl.json")
+            .to_string_lossy()
+            .into_owned();
+        let permission_manager = PlainPermissionManager::new(acl_path.as_str());
+        if !permission_manager.load() {
+            warn!(
+                "Load acl config file {} failed, all requests are rejected except from global \
+                 white remote addresses",
+                acl_path
+            );
+        }
+        info!("The name server enables acl, acl config file: {}", acl_path);
+        permission_manager
+    }
+
     #[inline]
     fn shutdown(&mut self) {
         if let Some(runtime) = self.name_server_runtime.take() {
@@ -193,7 +222,11 @@ impl Builder {
     pub fn build(self) -> NameServerBootstrap {
         let name_server_config = self.name_server_config.unwrap_or_default();
         let runtime = RocketMQRuntime::new_multi(10, "namesrv-thread");
-        let tokio_client_config = TokioClientConfig::default();
+        let tokio_client_config = TokioClientConfig {
+            use_tls: name_server_config.tls_config.client_enable,
+            tls_config: name_server_config.tls_config.clone(),
+            ..Default::default()
+        };
         let remoting_client = ArcMut::new(RocketmqDefaultClient::new(
             Arc::new(tokio_client_config.clone()),
             DefaultRemotingRequestProcessor,
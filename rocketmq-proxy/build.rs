@@ -0,0 +1,116 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Generates the `apache.rocketmq.v2.MessagingService` server without `protoc`, the messages
+//! are defined by hand in `src/proto.rs`.
+
+use tonic_build::manual::Method;
+use tonic_build::manual::Service;
+
+const CODEC: &str = "tonic::codec::ProstCodec";
+
+fn method(name: &str, route_name: &str, input_type: &str, output_type: &str) -> Method {
+    Method::builder()
+        .name(name)
+        .route_name(route_name)
+        .input_type(format!("super::{}", input_type))
+        .output_type(format!("super::{}", output_type))
+        .codec_path(CODEC)
+        .build()
+}
+
+fn streaming_method(
+    name: &str,
+    route_name: &str,
+    input_type: &str,
+    output_type: &str,
+    client_streaming: bool,
+) -> Method {
+    let builder = Method::builder()
+        .name(name)
+        .route_name(route_name)
+        .input_type(format!("super::{}", input_type))
+        .output_type(format!("super::{}", output_type))
+        .codec_path(CODEC)
+        .server_streaming();
+    if client_streaming {
+        builder.client_streaming().build()
+    } else {
+        builder.build()
+    }
+}
+
+fn main() {
+    let service = Service::builder()
+        .name("MessagingService")
+        .package("apache.rocketmq.v2")
+        .method(method(
+            "query_route",
+            "QueryRoute",
+            "QueryRouteRequest",
+            "QueryRouteResponse",
+        ))
+        .method(method(
+            "heartbeat",
+            "Heartbeat",
+            "HeartbeatRequest",
+            "HeartbeatResponse",
+        ))
+        .method(method(
+            "send_message",
+            "SendMessage",
+            "SendMessageRequest",
+            "SendMessageResponse",
+        ))
+        .method(method(
+            "query_assignment",
+            "QueryAssignment",
+            "QueryAssignmentRequest",
+            "QueryAssignmentResponse",
+        ))
+        .method(streaming_method(
+            "receive_message",
+            "ReceiveMessage",
+            "ReceiveMessageRequest",
+            "ReceiveMessageResponse",
+            false,
+        ))
+        .method(method(
+            "ack_message",
+            "AckMessage",
+            "AckMessageRequest",
+            "AckMessageResponse",
+        ))
+        .method(streaming_method(
+            "telemetry",
+            "Telemetry",
+            "TelemetryCommand",
+            "TelemetryCommand",
+            true,
+        ))
+        .method(method(
+            "change_invisible_duration",
+            "ChangeInvisibleDuration",
+            "ChangeInvisibleDurationRequest",
+            "ChangeInvisibleDurationResponse",
+        ))
+        .build();
+
+    tonic_build::manual::Builder::new()
+        .build_client(false)
+        .compile(&[service]);
+}
@@ -0,0 +1,67 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use rocketmq_common::ParseConfigFile;
+use rocketmq_proxy::bootstrap::Builder;
+use rocketmq_proxy::config::ProxyConfig;
+use rocketmq_rust::rocketmq;
+
+#[rocketmq::main]
+async fn main() -> anyhow::Result<()> {
+    rocketmq_common::log::init_logger();
+    let args = Args::parse();
+
+    let mut proxy_config = match args.config {
+        Some(config_file) => ParseConfigFile::parse_config_file::<ProxyConfig>(config_file)?,
+        None => ProxyConfig::default(),
+    };
+    if let Some(namesrv_addr) = args.namesrv_addr {
+        proxy_config.namesrv_addr = namesrv_addr;
+    }
+    if let Some(port) = args.port {
+        proxy_config.grpc_server_port = port;
+    }
+
+    Builder::new()
+        .set_proxy_config(proxy_config)
+        .build()
+        .boot()
+        .await
+}
+
+#[derive(Parser, Debug)]
+#[command(
+    author = "mxsm",
+    version = "0.1.0",
+    about = "RocketMQ gRPC Proxy(Rust)"
+)]
+struct Args {
+    /// Name server address list, eg: '192.168.0.1:9876;192.168.0.2:9876'
+    #[arg(short, long, value_name = "NAMESRV_ADDR", required = false)]
+    namesrv_addr: Option<String>,
+
+    /// gRPC port the proxy listens on
+    #[arg(short, long, value_name = "PORT", required = false)]
+    port: Option<u16>,
+
+    /// Proxy config file
+    #[arg(short, long, value_name = "FILE")]
+    config: Option<PathBuf>,
+}
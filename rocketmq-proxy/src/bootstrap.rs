@@ -0,0 +1,84 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use rocketmq_rust::wait_for_signal;
+use tracing::info;
+
+use crate::config::ProxyConfig;
+use crate::grpc_service::GrpcMessagingService;
+use crate::processor::MessagingProcessor;
+use crate::proto::v2::messaging_service_server::MessagingServiceServer;
+
+/// Room left on top of the largest message body for the rest of a gRPC message.
+const GRPC_MESSAGE_OVERHEAD: usize = 1024 * 1024;
+
+pub struct ProxyBootstrap {
+    proxy_config: Arc<ProxyConfig>,
+}
+
+impl ProxyBootstrap {
+    /// Serves the gRPC messaging service until a shutdown signal is received.
+    pub async fn boot(self) -> anyhow::Result<()> {
+        let addr: SocketAddr = format!(
+            "{}:{}",
+            self.proxy_config.bind_address, self.proxy_config.grpc_server_port
+        )
+        .parse()?;
+        let processor = Arc::new(MessagingProcessor::new(self.proxy_config.clone()).await?);
+        let service = MessagingServiceServer::new(GrpcMessagingService::new(processor))
+            .max_decoding_message_size(self.proxy_config.max_message_size + GRPC_MESSAGE_OVERHEAD);
+
+        info!(
+            "Rocketmq Proxy(Rust) serving gRPC on {}, namesrv: {}",
+            addr, self.proxy_config.namesrv_addr
+        );
+        tonic::transport::Server::builder()
+            .add_service(service)
+            .serve_with_shutdown(addr, async {
+                wait_for_signal().await;
+                info!("Received signal, initiating shutdown...");
+            })
+            .await?;
+        info!("Rocketmq Proxy(Rust) gracefully shutdown completed");
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct Builder {
+    proxy_config: Option<ProxyConfig>,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Builder::default()
+    }
+
+    pub fn set_proxy_config(mut self, proxy_config: ProxyConfig) -> Self {
+        self.proxy_config = Some(proxy_config);
+        self
+    }
+
+    pub fn build(self) -> ProxyBootstrap {
+        ProxyBootstrap {
+            proxy_config: Arc::new(self.proxy_config.unwrap_or_default()),
+        }
+    }
+}
@@ -0,0 +1,44 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::HashMap;
+
+use parking_lot::RwLock;
+
+use crate::proto::v2::Settings;
+
+/// Keeps the settings each gRPC client reported through `Telemetry`, keyed by client id.
+#[derive(Default)]
+pub struct ClientSettingsManager {
+    settings: RwLock<HashMap<String, Settings>>,
+}
+
+impl ClientSettingsManager {
+    pub fn update(&self, client_id: &str, settings: Settings) {
+        self.settings
+            .write()
+            .insert(client_id.to_string(), settings);
+    }
+
+    pub fn get(&self, client_id: &str) -> Option<Settings> {
+        self.settings.read().get(client_id).cloned()
+    }
+
+    pub fn remove(&self, client_id: &str) -> Option<Settings> {
+        self.settings.write().remove(client_id)
+    }
+}
@@ -0,0 +1,133 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use serde::Deserialize;
+
+/// Runtime configuration of the gRPC proxy.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyConfig {
+    #[serde(default = "ProxyConfig::default_grpc_server_port")]
+    pub grpc_server_port: u16,
+
+    #[serde(default = "ProxyConfig::default_bind_address")]
+    pub bind_address: String,
+
+    #[serde(default = "ProxyConfig::default_namesrv_addr")]
+    pub namesrv_addr: String,
+
+    /// Timeout of the remoting requests issued to namesrv and brokers.
+    #[serde(default = "ProxyConfig::default_remoting_timeout_millis")]
+    pub remoting_timeout_millis: u64,
+
+    /// How long a topic route fetched from namesrv is served from the cache.
+    #[serde(default = "ProxyConfig::default_topic_route_cache_expired_millis")]
+    pub topic_route_cache_expired_millis: u64,
+
+    /// Upper bound of a single message body accepted by `SendMessage`.
+    #[serde(default = "ProxyConfig::default_max_message_size")]
+    pub max_message_size: usize,
+
+    /// Upper bound of the messages returned by a single `ReceiveMessage` call.
+    #[serde(default = "ProxyConfig::default_max_receive_batch_size")]
+    pub max_receive_batch_size: u32,
+
+    /// Invisible duration used when `ReceiveMessage` does not carry one.
+    #[serde(default = "ProxyConfig::default_invisible_time_millis")]
+    pub default_invisible_time_millis: u64,
+
+    /// Long polling time used when `ReceiveMessage` does not carry one.
+    #[serde(default = "ProxyConfig::default_long_polling_timeout_millis")]
+    pub default_long_polling_timeout_millis: u64,
+
+    /// Upper bound of the long polling time requested by clients.
+    #[serde(default = "ProxyConfig::default_max_long_polling_timeout_millis")]
+    pub max_long_polling_timeout_millis: u64,
+}
+
+impl ProxyConfig {
+    fn default_grpc_server_port() -> u16 {
+        8081
+    }
+
+    fn default_bind_address() -> String {
+        "0.0.0.0".to_string()
+    }
+
+    fn default_namesrv_addr() -> String {
+        "127.0.0.1:9876".to_string()
+    }
+
+    fn default_remoting_timeout_millis() -> u64 {
+        3000
+    }
+
+    fn default_topic_route_cache_expired_millis() -> u64 {
+        30_000
+    }
+
+    fn default_max_message_size() -> usize {
+        4 * 1024 * 1024
+    }
+
+    fn default_max_receive_batch_size() -> u32 {
+        32
+    }
+
+    fn default_invisible_time_millis() -> u64 {
+        60_000
+    }
+
+    fn default_long_polling_timeout_millis() -> u64 {
+        20_000
+    }
+
+    fn default_max_long_polling_timeout_millis() -> u64 {
+        30_000
+    }
+}
+
+impl Default for ProxyConfig {
+    fn default() -> Self {
+        ProxyConfig {
+            grpc_server_port: Self::default_grpc_server_port(),
+            bind_address: Self::default_bind_address(),
+            namesrv_addr: Self::default_namesrv_addr(),
+            remoting_timeout_millis: Self::default_remoting_timeout_millis(),
+            topic_route_cache_expired_millis: Self::default_topic_route_cache_expired_millis(),
+            max_message_size: Self::default_max_message_size(),
+            max_receive_batch_size: Self::default_max_receive_batch_size(),
+            default_invisible_time_millis: Self::default_invisible_time_millis(),
+            default_long_polling_timeout_millis: Self::default_long_polling_timeout_millis(),
+            max_long_polling_timeout_millis: Self::default_max_long_polling_timeout_millis(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_fields_fall_back_to_defaults() {
+        let config: ProxyConfig = serde_json::from_str(r#"{"grpcServerPort": 18081}"#).unwrap();
+        assert_eq!(config.grpc_server_port, 18081);
+        assert_eq!(config.namesrv_addr, "127.0.0.1:9876");
+        assert_eq!(config.max_message_size, 4 * 1024 * 1024);
+        assert_eq!(config.default_invisible_time_millis, 60_000);
+    }
+}
@@ -0,0 +1,502 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Conversions between the gRPC protocol and the remoting protocol spoken by brokers.
+
+use std::collections::HashMap;
+
+use cheetah_string::CheetahString;
+use prost_types::Duration;
+use prost_types::Timestamp;
+use rocketmq_common::common::constant::PermName;
+use rocketmq_common::common::message::message_ext::MessageExt;
+use rocketmq_common::common::message::message_single::Message;
+use rocketmq_common::common::message::MessageConst;
+use rocketmq_common::common::message::MessageTrait;
+use rocketmq_common::common::message::STRING_HASH_SET;
+use rocketmq_common::common::mix_all;
+use rocketmq_common::common::sys_flag::message_sys_flag::MessageSysFlag;
+use rocketmq_common::CRC32Utils::crc32;
+use rocketmq_remoting::protocol::route::route_data_view::QueueData;
+use rocketmq_remoting::protocol::route::topic_route_data::TopicRouteData;
+
+use crate::error::ProxyError;
+use crate::proto::v2;
+use crate::proto::v2::Code;
+use crate::proto::v2::DigestType;
+use crate::proto::v2::Encoding;
+use crate::proto::v2::MessageType;
+use crate::proto::v2::Permission;
+
+const ACCEPT_MESSAGE_TYPES: [MessageType; 4] = [
+    MessageType::Normal,
+    MessageType::Fifo,
+    MessageType::Delay,
+    MessageType::Transaction,
+];
+
+/// Builds the message queues of a topic the way the Java proxy does: every broker exposes its
+/// read-only queues first, then its write-only queues and finally its read-write queues. The
+/// broker endpoints are the proxy endpoints the client connected to.
+pub fn build_message_queues(
+    topic: &str,
+    route: &TopicRouteData,
+    endpoints: Option<&v2::Endpoints>,
+) -> Vec<v2::MessageQueue> {
+    let mut message_queues = Vec::new();
+    for queue_data in &route.queue_datas {
+        if !has_master(route, queue_data.broker_name()) {
+            continue;
+        }
+        let (read, write, read_write) = split_queue_nums(queue_data);
+        let permissions = std::iter::repeat(Permission::Read)
+            .take(read as usize)
+            .chain(std::iter::repeat(Permission::Write).take(write as usize))
+            .chain(std::iter::repeat(Permission::ReadWrite).take(read_write as usize));
+        for (id, permission) in permissions.enumerate() {
+            message_queues.push(message_queue(
+                topic,
+                queue_data.broker_name(),
+                id as i32,
+                permission,
+                endpoints,
+            ));
+        }
+    }
+    message_queues
+}
+
+/// Builds one assignment per readable broker. The queue id is `-1` so that the broker picks the
+/// queue to pop from.
+pub fn build_assignments(
+    topic: &str,
+    route: &TopicRouteData,
+    endpoints: Option<&v2::Endpoints>,
+) -> Vec<v2::Assignment> {
+    route
+        .queue_datas
+        .iter()
+        .filter(|queue_data| {
+            PermName::is_readable(queue_data.perm()) && queue_data.read_queue_nums() > 0
+        })
+        .filter(|queue_data| has_master(route, queue_data.broker_name()))
+        .map(|queue_data| v2::Assignment {
+            message_queue: Some(message_queue(
+                topic,
+                queue_data.broker_name(),
+                -1,
+                Permission::ReadWrite,
+                endpoints,
+            )),
+        })
+        .collect()
+}
+
+fn split_queue_nums(queue_data: &QueueData) -> (u32, u32, u32) {
+    let perm = queue_data.perm();
+    let readable = PermName::is_readable(perm);
+    let writeable = PermName::is_writeable(perm);
+    match (readable, writeable) {
+        (true, true) => {
+            let read_write = queue_data
+                .read_queue_nums()
+                .min(queue_data.write_queue_nums());
+            (
+                queue_data.read_queue_nums() - read_write,
+                queue_data.write_queue_nums() - read_write,
+                read_write,
+            )
+        }
+        (true, false) => (queue_data.read_queue_nums(), 0, 0),
+        (false, true) => (0, queue_data.write_queue_nums(), 0),
+        (false, false) => (0, 0, 0),
+    }
+}
+
+fn has_master(route: &TopicRouteData, broker_name: &str) -> bool {
+    master_addr(route, broker_name).is_some()
+}
+
+/// Returns the address of the master of `broker_name` in `route`.
+pub fn master_addr(route: &TopicRouteData, broker_name: &str) -> Option<CheetahString> {
+    route
+        .broker_datas
+        .iter()
+        .find(|broker_data| broker_data.broker_name() == broker_name)
+        .and_then(|broker_data| broker_data.broker_addrs().get(&mix_all::MASTER_ID))
+        .cloned()
+}
+
+fn message_queue(
+    topic: &str,
+    broker_name: &str,
+    id: i32,
+    permission: Permission,
+    endpoints: Option<&v2::Endpoints>,
+) -> v2::MessageQueue {
+    v2::MessageQueue {
+        topic: Some(v2::Resource {
+            resource_namespace: String::new(),
+            name: topic.to_string(),
+        }),
+        id,
+        permission: permission as i32,
+        broker: Some(v2::Broker {
+            name: broker_name.to_string(),
+            id: mix_all::MASTER_ID as i32,
+            endpoints: endpoints.cloned(),
+        }),
+        accept_message_types: ACCEPT_MESSAGE_TYPES.iter().map(|t| *t as i32).collect(),
+    }
+}
+
+/// Converts a message published through `SendMessage` into a remoting message.
+pub fn to_remoting_message(message: &v2::Message, max_body_size: usize) -> crate::Result<Message> {
+    let topic = message
+        .topic
+        .as_ref()
+        .map(|topic| topic.name.as_str())
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| ProxyError::bad_request(Code::IllegalTopic, "topic is required"))?;
+    if message.body.len() > max_body_size {
+        return Err(ProxyError::bad_request(
+            Code::MessageBodyTooLarge,
+            format!(
+                "message body size {} exceeds the limit {}",
+                message.body.len(),
+                max_body_size
+            ),
+        ));
+    }
+    let system_properties = message.system_properties.clone().unwrap_or_default();
+    match system_properties.body_encoding() {
+        Encoding::Unspecified | Encoding::Identity => {}
+        encoding => {
+            return Err(ProxyError::bad_request(
+                Code::Unsupported,
+                format!("body encoding {:?} is not supported", encoding),
+            ));
+        }
+    }
+    if system_properties.message_type() == MessageType::Transaction {
+        return Err(ProxyError::bad_request(
+            Code::Unsupported,
+            "transaction messages are not supported by this proxy",
+        ));
+    }
+
+    let mut msg = Message::new(topic, &message.body);
+    for (key, value) in &message.user_properties {
+        if key.is_empty() || value.is_empty() || STRING_HASH_SET.contains(key.as_str()) {
+            return Err(ProxyError::bad_request(
+                Code::IllegalMessagePropertyKey,
+                format!("property {} is reserved or blank", key),
+            ));
+        }
+        msg.put_property(
+            CheetahString::from_string(key.clone()),
+            CheetahString::from_string(value.clone()),
+        );
+    }
+    if let Some(tag) = system_properties.tag.filter(|tag| !tag.is_empty()) {
+        msg.set_tags(CheetahString::from_string(tag));
+    }
+    if !system_properties.keys.is_empty() {
+        msg.set_keys(CheetahString::from_string(
+            system_properties.keys.join(MessageConst::KEY_SEPARATOR),
+        ));
+    }
+    if !system_properties.message_id.is_empty() {
+        put_property(
+            &mut msg,
+            MessageConst::PROPERTY_UNIQ_CLIENT_MESSAGE_ID_KEYIDX,
+            system_properties.message_id,
+        );
+    }
+    if let Some(message_group) = system_properties.message_group {
+        put_property(&mut msg, MessageConst::PROPERTY_SHARDING_KEY, message_group);
+    }
+    if let Some(delivery_timestamp) = system_properties.delivery_timestamp {
+        put_property(
+            &mut msg,
+            MessageConst::PROPERTY_TIMER_DELIVER_MS,
+            timestamp_to_millis(&delivery_timestamp).to_string(),
+        );
+    }
+    if let Some(trace_context) = system_properties.trace_context {
+        put_property(
+            &mut msg,
+            MessageConst::PROPERTY_TRACE_CONTEXT,
+            trace_context,
+        );
+    }
+    Ok(msg)
+}
+
+fn put_property(msg: &mut Message, key: &'static str, value: String) {
+    msg.put_property(
+        CheetahString::from_static_str(key),
+        CheetahString::from_string(value),
+    );
+}
+
+/// Converts a message popped from a broker into the message delivered by `ReceiveMessage`.
+pub fn to_grpc_message(message: &MessageExt) -> v2::Message {
+    let properties = message.properties();
+    let property = |key: &str| properties.get(key).map(|value| value.to_string());
+
+    let body = message.body().map(|body| body.to_vec()).unwrap_or_default();
+    let delivery_timestamp = property(MessageConst::PROPERTY_TIMER_DELIVER_MS)
+        .and_then(|value| value.parse::<i64>().ok())
+        .map(timestamp_from_millis);
+    let message_group = property(MessageConst::PROPERTY_SHARDING_KEY);
+    let message_type = if property(MessageConst::PROPERTY_TRANSACTION_PREPARED)
+        .is_some_and(|value| value == "true")
+        || MessageSysFlag::get_transaction_value(message.sys_flag())
+            == MessageSysFlag::TRANSACTION_PREPARED_TYPE
+    {
+        MessageType::Transaction
+    } else if message_group.is_some() {
+        MessageType::Fifo
+    } else if delivery_timestamp.is_some() {
+        MessageType::Delay
+    } else {
+        MessageType::Normal
+    };
+
+    let system_properties = v2::SystemProperties {
+        tag: property(MessageConst::PROPERTY_TAGS),
+        keys: property(MessageConst::PROPERTY_KEYS)
+            .map(|keys| {
+                keys.split(MessageConst::KEY_SEPARATOR)
+                    .filter(|key| !key.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        message_id: property(MessageConst::PROPERTY_UNIQ_CLIENT_MESSAGE_ID_KEYIDX)
+            .unwrap_or_else(|| message.msg_id().to_string()),
+        body_digest: Some(v2::Digest {
+            r#type: DigestType::Crc32 as i32,
+            checksum: format!("{:08X}", crc32(&body)),
+        }),
+        body_encoding: Encoding::Identity as i32,
+        message_type: message_type as i32,
+        born_timestamp: Some(timestamp_from_millis(message.born_timestamp())),
+        born_host: message.born_host().ip().to_string(),
+        store_timestamp: Some(timestamp_from_millis(message.store_timestamp())),
+        store_host: message.store_host().to_string(),
+        delivery_timestamp,
+        receipt_handle: property(MessageConst::PROPERTY_POP_CK),
+        queue_id: message.queue_id(),
+        queue_offset: Some(message.queue_offset()),
+        delivery_attempt: Some(message.reconsume_times() + 1),
+        message_group,
+        trace_context: property(MessageConst::PROPERTY_TRACE_CONTEXT),
+        ..Default::default()
+    };
+
+    let user_properties = properties
+        .iter()
+        .filter(|(key, _)| !STRING_HASH_SET.contains(key.as_str()))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect::<HashMap<_, _>>();
+
+    v2::Message {
+        topic: Some(v2::Resource {
+            resource_namespace: String::new(),
+            name: message.topic().to_string(),
+        }),
+        user_properties,
+        system_properties: Some(system_properties),
+        body,
+    }
+}
+
+pub fn timestamp_from_millis(millis: i64) -> Timestamp {
+    Timestamp {
+        seconds: millis.div_euclid(1000),
+        nanos: (millis.rem_euclid(1000) * 1_000_000) as i32,
+    }
+}
+
+pub fn timestamp_to_millis(timestamp: &Timestamp) -> i64 {
+    timestamp.seconds * 1000 + i64::from(timestamp.nanos) / 1_000_000
+}
+
+pub fn duration_to_millis(duration: &Duration) -> u64 {
+    (duration.seconds.max(0) as u64) * 1000 + (duration.nanos.max(0) as u64) / 1_000_000
+}
+
+pub fn duration_from_millis(millis: u64) -> Duration {
+    Duration {
+        seconds: (millis / 1000) as i64,
+        nanos: ((millis % 1000) * 1_000_000) as i32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rocketmq_remoting::protocol::route::route_data_view::BrokerData;
+
+    use super::*;
+
+    fn route(perm: u32, read: u32, write: u32) -> TopicRouteData {
+        let mut broker_addrs = HashMap::new();
+        broker_addrs.insert(mix_all::MASTER_ID, CheetahString::from("127.0.0.1:10911"));
+        TopicRouteData {
+            queue_datas: vec![QueueData::new(
+                CheetahString::from("broker-a"),
+                read,
+                write,
+                perm,
+                0,
+            )],
+            broker_datas: vec![BrokerData::new(
+                CheetahString::from("DefaultCluster"),
+                CheetahString::from("broker-a"),
+                broker_addrs,
+                None,
+            )],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn message_queues_split_read_write_permissions() {
+        let route = route(PermName::PERM_READ | PermName::PERM_WRITE, 4, 2);
+        let queues = build_message_queues("TopicTest", &route, None);
+        let permissions = queues.iter().map(|q| q.permission()).collect::<Vec<_>>();
+        assert_eq!(
+            permissions,
+            vec![
+                Permission::Read,
+                Permission::Read,
+                Permission::ReadWrite,
+                Permission::ReadWrite
+            ]
+        );
+        assert_eq!(
+            queues.iter().map(|q| q.id).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+        assert_eq!(queues[0].broker.as_ref().unwrap().name, "broker-a");
+    }
+
+    #[test]
+    fn assignments_skip_unreadable_brokers() {
+        let readable = route(PermName::PERM_READ | PermName::PERM_WRITE, 4, 4);
+        let assignments = build_assignments("TopicTest", &readable, None);
+        assert_eq!(assignments.len(), 1);
+        assert_eq!(assignments[0].message_queue.as_ref().unwrap().id, -1);
+
+        let write_only = route(PermName::PERM_WRITE, 4, 4);
+        assert!(build_assignments("TopicTest", &write_only, None).is_empty());
+    }
+
+    #[test]
+    fn remoting_message_carries_system_properties() {
+        let message = v2::Message {
+            topic: Some(v2::Resource {
+                resource_namespace: String::new(),
+                name: "TopicTest".to_string(),
+            }),
+            user_properties: HashMap::from([("color".to_string(), "red".to_string())]),
+            system_properties: Some(v2::SystemProperties {
+                tag: Some("TagA".to_string()),
+                keys: vec!["k1".to_string(), "k2".to_string()],
+                message_id: "01AB".to_string(),
+                message_group: Some("group-1".to_string()),
+                ..Default::default()
+            }),
+            body: b"hello".to_vec(),
+        };
+        let msg = to_remoting_message(&message, 1024).unwrap();
+        let property = |key: &'static str| {
+            msg.get_property(&CheetahString::from_static_str(key))
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(property(MessageConst::PROPERTY_TAGS), "TagA");
+        assert_eq!(property(MessageConst::PROPERTY_KEYS), "k1 k2");
+        assert_eq!(
+            property(MessageConst::PROPERTY_UNIQ_CLIENT_MESSAGE_ID_KEYIDX),
+            "01AB"
+        );
+        assert_eq!(property(MessageConst::PROPERTY_SHARDING_KEY), "group-1");
+        assert_eq!(property("color"), "red");
+    }
+
+    #[test]
+    fn remoting_message_rejects_reserved_properties_and_large_bodies() {
+        let mut message = v2::Message {
+            topic: Some(v2::Resource {
+                resource_namespace: String::new(),
+                name: "TopicTest".to_string(),
+            }),
+            body: vec![0; 16],
+            ..Default::default()
+        };
+        let error = to_remoting_message(&message, 8).unwrap_err();
+        assert_eq!(error.code(), Code::MessageBodyTooLarge);
+
+        message
+            .user_properties
+            .insert(MessageConst::PROPERTY_TAGS.to_string(), "TagA".to_string());
+        let error = to_remoting_message(&message, 1024).unwrap_err();
+        assert_eq!(error.code(), Code::IllegalMessagePropertyKey);
+    }
+
+    #[test]
+    fn grpc_message_exposes_receipt_handle_and_user_properties() {
+        let mut message = MessageExt::default();
+        message.set_message_inner(Message::new("TopicTest", b"hello"));
+        message.put_property(
+            CheetahString::from_static_str(MessageConst::PROPERTY_POP_CK),
+            CheetahString::from("0 1 2 3 4 broker-a 0 5"),
+        );
+        message.put_property(
+            CheetahString::from_static_str("color"),
+            CheetahString::from_static_str("red"),
+        );
+        message.set_reconsume_times(2);
+
+        let grpc_message = to_grpc_message(&message);
+        let system_properties = grpc_message.system_properties.unwrap();
+        assert_eq!(
+            system_properties.receipt_handle.as_deref(),
+            Some("0 1 2 3 4 broker-a 0 5")
+        );
+        assert_eq!(system_properties.delivery_attempt, Some(3));
+        assert_eq!(
+            system_properties.body_digest.unwrap().checksum,
+            format!("{:08X}", crc32(b"hello"))
+        );
+        assert_eq!(grpc_message.user_properties.len(), 1);
+        assert_eq!(grpc_message.user_properties["color"], "red");
+    }
+
+    #[test]
+    fn timestamps_round_trip() {
+        let timestamp = timestamp_from_millis(1_700_000_000_123);
+        assert_eq!(timestamp.seconds, 1_700_000_000);
+        assert_eq!(timestamp_to_millis(&timestamp), 1_700_000_000_123);
+        assert_eq!(duration_to_millis(&duration_from_millis(2500)), 2500);
+    }
+}
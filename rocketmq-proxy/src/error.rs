@@ -0,0 +1,124 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use rocketmq_client_rust::client_error::MQClientError;
+use rocketmq_remoting::code::response_code::ResponseCode;
+use rocketmq_remoting::remoting_error::RemotingError;
+use thiserror::Error;
+
+use crate::proto::v2::Code;
+use crate::proto::v2::Status;
+
+#[derive(Debug, Error)]
+pub enum ProxyError {
+    #[error("{1}")]
+    InvalidArgument(Code, String),
+
+    #[error("{0}")]
+    ClientError(Box<MQClientError>),
+
+    #[error("Topic route of {0} not found")]
+    TopicRouteNotFound(String),
+
+    #[error("Broker {0} not found")]
+    BrokerNotFound(String),
+
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl From<MQClientError> for ProxyError {
+    fn from(error: MQClientError) -> Self {
+        ProxyError::ClientError(Box::new(error))
+    }
+}
+
+impl ProxyError {
+    pub fn bad_request(code: Code, message: impl Into<String>) -> Self {
+        ProxyError::InvalidArgument(code, message.into())
+    }
+
+    /// Maps the error onto the status code understood by the gRPC SDKs.
+    pub fn code(&self) -> Code {
+        match self {
+            ProxyError::InvalidArgument(code, _) => *code,
+            ProxyError::TopicRouteNotFound(_) => Code::TopicNotFound,
+            ProxyError::BrokerNotFound(_) => Code::NotFound,
+            ProxyError::Internal(_) => Code::InternalServerError,
+            ProxyError::ClientError(error) => match error.as_ref() {
+                MQClientError::MQClientBrokerError(error) => {
+                    match ResponseCode::from(error.response_code()) {
+                        ResponseCode::TopicNotExist => Code::TopicNotFound,
+                        ResponseCode::SubscriptionGroupNotExist => Code::ConsumerGroupNotFound,
+                        ResponseCode::NoPermission => Code::Forbidden,
+                        ResponseCode::MessageIllegal => Code::BadRequest,
+                        ResponseCode::SystemBusy
+                        | ResponseCode::FlowControl
+                        | ResponseCode::PollingFull => Code::TooManyRequests,
+                        ResponseCode::NoMessage => Code::MessageNotFound,
+                        _ => Code::InternalServerError,
+                    }
+                }
+                MQClientError::RequestTimeoutError(_)
+                | MQClientError::RemotingError(RemotingError::RemotingTimeoutError(_, _)) => {
+                    Code::ProxyTimeout
+                }
+                MQClientError::RemotingTooMuchRequestError(_) => Code::TooManyRequests,
+                MQClientError::IllegalArgumentError(_) => Code::BadRequest,
+                _ => Code::InternalServerError,
+            },
+        }
+    }
+
+    pub fn to_status(&self) -> Status {
+        Status {
+            code: self.code() as i32,
+            message: self.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocketmq_client_rust::client_error::MQBrokerErr;
+
+    use super::*;
+
+    #[test]
+    fn broker_response_codes_map_to_grpc_codes() {
+        let error = ProxyError::from(MQClientError::MQClientBrokerError(MQBrokerErr::new(
+            ResponseCode::TopicNotExist as i32,
+            "topic not exist",
+        )));
+        assert_eq!(error.code(), Code::TopicNotFound);
+
+        let error = ProxyError::from(MQClientError::MQClientBrokerError(MQBrokerErr::new(
+            ResponseCode::FlowControl as i32,
+            "flow control",
+        )));
+        assert_eq!(error.code(), Code::TooManyRequests);
+    }
+
+    #[test]
+    fn remoting_timeout_maps_to_proxy_timeout() {
+        let error = ProxyError::from(MQClientError::RemotingError(
+            RemotingError::RemotingTimeoutError("127.0.0.1:10911".to_string(), 3000),
+        ));
+        assert_eq!(error.code(), Code::ProxyTimeout);
+        assert_eq!(error.to_status().code, Code::ProxyTimeout as i32);
+    }
+}
@@ -0,0 +1,464 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::pin::Pin;
+use std::sync::Arc;
+
+use rocketmq_client_rust::consumer::pop_status::PopStatus;
+use rocketmq_client_rust::producer::send_status::SendStatus;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::Stream;
+use tokio_stream::StreamExt;
+use tonic::Request;
+use tonic::Response;
+use tonic::Streaming;
+use tracing::info;
+use tracing::warn;
+
+use crate::client_manager::ClientSettingsManager;
+use crate::config::ProxyConfig;
+use crate::converter;
+use crate::processor::MessagingProcessor;
+use crate::proto::v2;
+use crate::proto::v2::messaging_service_server::MessagingService;
+use crate::proto::v2::receive_message_response::Content;
+use crate::proto::v2::settings::PubSub;
+use crate::proto::v2::telemetry_command::Command;
+use crate::proto::v2::ClientType;
+use crate::proto::v2::Code;
+
+/// Metadata key the SDKs put their client id under.
+const CLIENT_ID_KEY: &str = "x-mq-client-id";
+
+type ResponseStream<T> = Pin<Box<dyn Stream<Item = Result<T, tonic::Status>> + Send>>;
+
+/// The `apache.rocketmq.v2.MessagingService` served by the proxy.
+pub struct GrpcMessagingService {
+    processor: Arc<MessagingProcessor>,
+    client_settings: Arc<ClientSettingsManager>,
+}
+
+impl GrpcMessagingService {
+    pub fn new(processor: Arc<MessagingProcessor>) -> Self {
+        GrpcMessagingService {
+            processor,
+            client_settings: Arc::new(ClientSettingsManager::default()),
+        }
+    }
+
+    fn is_fifo_consumer(&self, client_id: Option<&str>) -> bool {
+        client_id
+            .and_then(|client_id| self.client_settings.get(client_id))
+            .and_then(|settings| match settings.pub_sub {
+                Some(PubSub::Subscription(subscription)) => subscription.fifo,
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Completes the settings reported by a client with the values enforced by the proxy.
+fn adjust_settings(config: &ProxyConfig, mut settings: v2::Settings) -> v2::Settings {
+    match settings.pub_sub.as_mut() {
+        Some(PubSub::Publishing(publishing)) => {
+            publishing.max_body_size = config.max_message_size as i32;
+        }
+        Some(PubSub::Subscription(subscription)) => {
+            let batch_size = subscription
+                .receive_batch_size
+                .unwrap_or(config.max_receive_batch_size as i32);
+            subscription.receive_batch_size =
+                Some(batch_size.clamp(1, config.max_receive_batch_size as i32));
+            subscription.fifo = Some(subscription.fifo.unwrap_or_default());
+            subscription.long_polling_timeout =
+                Some(subscription.long_polling_timeout.unwrap_or_else(|| {
+                    converter::duration_from_millis(config.default_long_polling_timeout_millis)
+                }));
+        }
+        None => {}
+    }
+    settings
+}
+
+fn client_id<T>(request: &Request<T>) -> Option<String> {
+    request
+        .metadata()
+        .get(CLIENT_ID_KEY)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn status(code: Code, message: impl Into<String>) -> v2::Status {
+    v2::Status {
+        code: code as i32,
+        message: message.into(),
+    }
+}
+
+fn ok_status() -> v2::Status {
+    status(Code::Ok, "OK")
+}
+
+fn resource_name(resource: Option<&v2::Resource>) -> Option<&str> {
+    resource
+        .map(|resource| resource.name.as_str())
+        .filter(|name| !name.is_empty())
+}
+
+/// The status of a batch is OK when every entry succeeded, otherwise the first failure.
+fn batch_status<'a>(statuses: impl IntoIterator<Item = Option<&'a v2::Status>>) -> v2::Status {
+    statuses
+        .into_iter()
+        .flatten()
+        .find(|status| status.code != Code::Ok as i32)
+        .cloned()
+        .unwrap_or_else(ok_status)
+}
+
+fn send_status(send_status: SendStatus) -> v2::Status {
+    match send_status {
+        SendStatus::SendOk => ok_status(),
+        SendStatus::FlushDiskTimeout => {
+            status(Code::MasterPersistenceTimeout, "flush disk timeout")
+        }
+        SendStatus::FlushSlaveTimeout => {
+            status(Code::SlavePersistenceTimeout, "flush slave timeout")
+        }
+        SendStatus::SlaveNotAvailable => status(Code::HaNotAvailable, "slave not available"),
+    }
+}
+
+#[tonic::async_trait]
+impl MessagingService for GrpcMessagingService {
+    async fn query_route(
+        &self,
+        request: Request<v2::QueryRouteRequest>,
+    ) -> Result<Response<v2::QueryRouteResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let Some(topic) = resource_name(request.topic.as_ref()) else {
+            return Ok(Response::new(v2::QueryRouteResponse {
+                status: Some(status(Code::IllegalTopic, "topic is required")),
+                message_queues: vec![],
+            }));
+        };
+        let response = match self.processor.query_route(topic).await {
+            Ok(route) => v2::QueryRouteResponse {
+                status: Some(ok_status()),
+                message_queues: converter::build_message_queues(
+                    topic,
+                    &route,
+                    request.endpoints.as_ref(),
+                ),
+            },
+            Err(error) => v2::QueryRouteResponse {
+                status: Some(error.to_status()),
+                message_queues: vec![],
+            },
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn heartbeat(
+        &self,
+        request: Request<v2::HeartbeatRequest>,
+    ) -> Result<Response<v2::HeartbeatResponse>, tonic::Status> {
+        let Some(client_id) = client_id(&request) else {
+            return Ok(Response::new(v2::HeartbeatResponse {
+                status: Some(status(Code::ClientIdRequired, "client id is required")),
+            }));
+        };
+        let request = request.into_inner();
+        let consumer = matches!(
+            request.client_type(),
+            ClientType::PushConsumer | ClientType::SimpleConsumer | ClientType::PullConsumer
+        );
+        let status = match resource_name(request.group.as_ref()) {
+            Some(group) if consumer => {
+                let subscriptions = match self.client_settings.get(&client_id) {
+                    Some(v2::Settings {
+                        pub_sub: Some(PubSub::Subscription(subscription)),
+                        ..
+                    }) => subscription.subscriptions,
+                    _ => vec![],
+                };
+                match self
+                    .processor
+                    .heartbeat(&client_id, group, &subscriptions)
+                    .await
+                {
+                    Ok(()) => ok_status(),
+                    Err(error) => error.to_status(),
+                }
+            }
+            _ => ok_status(),
+        };
+        Ok(Response::new(v2::HeartbeatResponse {
+            status: Some(status),
+        }))
+    }
+
+    async fn send_message(
+        &self,
+        request: Request<v2::SendMessageRequest>,
+    ) -> Result<Response<v2::SendMessageResponse>, tonic::Status> {
+        let request = request.into_inner();
+        if request.messages.is_empty() {
+            return Ok(Response::new(v2::SendMessageResponse {
+                status: Some(status(Code::BadRequest, "no message to send")),
+                entries: vec![],
+            }));
+        }
+        let mut entries = Vec::with_capacity(request.messages.len());
+        for message in &request.messages {
+            let message_id = message
+                .system_properties
+                .as_ref()
+                .map(|properties| properties.message_id.clone())
+                .unwrap_or_default();
+            let entry = match self.processor.send_message(message).await {
+                Ok(send_result) => v2::SendResultEntry {
+                    status: Some(send_status(send_result.send_status)),
+                    message_id: send_result
+                        .msg_id
+                        .map(|msg_id| msg_id.to_string())
+                        .unwrap_or(message_id),
+                    transaction_id: send_result.transaction_id.unwrap_or_default(),
+                    offset: send_result.queue_offset as i64,
+                },
+                Err(error) => {
+                    warn!("send message {} failed: {}", message_id, error);
+                    v2::SendResultEntry {
+                        status: Some(error.to_status()),
+                        message_id,
+                        ..Default::default()
+                    }
+                }
+            };
+            entries.push(entry);
+        }
+        Ok(Response::new(v2::SendMessageResponse {
+            status: Some(batch_status(
+                entries.iter().map(|entry| entry.status.as_ref()),
+            )),
+            entries,
+        }))
+    }
+
+    async fn query_assignment(
+        &self,
+        request: Request<v2::QueryAssignmentRequest>,
+    ) -> Result<Response<v2::QueryAssignmentResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let Some(topic) = resource_name(request.topic.as_ref()) else {
+            return Ok(Response::new(v2::QueryAssignmentResponse {
+                status: Some(status(Code::IllegalTopic, "topic is required")),
+                assignments: vec![],
+            }));
+        };
+        let response = match self.processor.query_route(topic).await {
+            Ok(route) => v2::QueryAssignmentResponse {
+                status: Some(ok_status()),
+                assignments: converter::build_assignments(
+                    topic,
+                    &route,
+                    request.endpoints.as_ref(),
+                ),
+            },
+            Err(error) => v2::QueryAssignmentResponse {
+                status: Some(error.to_status()),
+                assignments: vec![],
+            },
+        };
+        Ok(Response::new(response))
+    }
+
+    type ReceiveMessageStream = ResponseStream<v2::ReceiveMessageResponse>;
+
+    async fn receive_message(
+        &self,
+        request: Request<v2::ReceiveMessageRequest>,
+    ) -> Result<Response<Self::ReceiveMessageStream>, tonic::Status> {
+        let fifo = self.is_fifo_consumer(client_id(&request).as_deref());
+        let request = request.into_inner();
+        let mut responses = Vec::new();
+        let final_status = match self.processor.receive_message(&request, fifo).await {
+            Ok(pop_result) => match pop_result.pop_status {
+                PopStatus::Found => {
+                    let messages = pop_result.msg_found_list.unwrap_or_default();
+                    if messages.is_empty() {
+                        status(Code::MessageNotFound, "no new message")
+                    } else {
+                        responses.extend(messages.iter().map(|message| {
+                            v2::ReceiveMessageResponse {
+                                content: Some(Content::Message(converter::to_grpc_message(
+                                    message,
+                                ))),
+                            }
+                        }));
+                        ok_status()
+                    }
+                }
+                PopStatus::NoNewMsg | PopStatus::PollingNotFound => {
+                    status(Code::MessageNotFound, "no new message")
+                }
+                PopStatus::PollingFull => status(Code::TooManyRequests, "polling full"),
+            },
+            Err(error) => error.to_status(),
+        };
+        responses.push(v2::ReceiveMessageResponse {
+            content: Some(Content::Status(final_status)),
+        });
+        let stream = tokio_stream::iter(responses.into_iter().map(Ok));
+        Ok(Response::new(Box::pin(stream) as Self::ReceiveMessageStream))
+    }
+
+    async fn ack_message(
+        &self,
+        request: Request<v2::AckMessageRequest>,
+    ) -> Result<Response<v2::AckMessageResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let (Some(group), Some(topic)) = (
+            resource_name(request.group.as_ref()),
+            resource_name(request.topic.as_ref()),
+        ) else {
+            return Ok(Response::new(v2::AckMessageResponse {
+                status: Some(status(Code::BadRequest, "group and topic are required")),
+                entries: vec![],
+            }));
+        };
+        let mut entries = Vec::with_capacity(request.entries.len());
+        for entry in request.entries {
+            let status = match self
+                .processor
+                .ack_message(group, topic, &entry.receipt_handle)
+                .await
+            {
+                Ok(()) => ok_status(),
+                Err(error) => error.to_status(),
+            };
+            entries.push(v2::AckMessageResultEntry {
+                message_id: entry.message_id,
+                receipt_handle: entry.receipt_handle,
+                status: Some(status),
+            });
+        }
+        Ok(Response::new(v2::AckMessageResponse {
+            status: Some(batch_status(
+                entries.iter().map(|entry| entry.status.as_ref()),
+            )),
+            entries,
+        }))
+    }
+
+    type TelemetryStream = ResponseStream<v2::TelemetryCommand>;
+
+    async fn telemetry(
+        &self,
+        request: Request<Streaming<v2::TelemetryCommand>>,
+    ) -> Result<Response<Self::TelemetryStream>, tonic::Status> {
+        let Some(client_id) = client_id(&request) else {
+            return Err(tonic::Status::invalid_argument("client id is required"));
+        };
+        let mut inbound = request.into_inner();
+        let (tx, rx) = mpsc::channel(16);
+        let client_settings = self.client_settings.clone();
+        let processor = self.processor.clone();
+        tokio::spawn(async move {
+            while let Some(command) = inbound.next().await {
+                let command = match command {
+                    Ok(command) => command,
+                    Err(error) => {
+                        warn!("telemetry stream of {} broken: {}", client_id, error);
+                        break;
+                    }
+                };
+                let Some(Command::Settings(settings)) = command.command else {
+                    continue;
+                };
+                let settings = adjust_settings(processor.config(), settings);
+                client_settings.update(&client_id, settings.clone());
+                let response = v2::TelemetryCommand {
+                    status: Some(ok_status()),
+                    command: Some(Command::Settings(settings)),
+                };
+                if tx.send(Ok(response)).await.is_err() {
+                    break;
+                }
+            }
+            client_settings.remove(&client_id);
+            info!("telemetry stream of {} closed", client_id);
+        });
+        Ok(Response::new(
+            Box::pin(ReceiverStream::new(rx)) as Self::TelemetryStream
+        ))
+    }
+
+    async fn change_invisible_duration(
+        &self,
+        request: Request<v2::ChangeInvisibleDurationRequest>,
+    ) -> Result<Response<v2::ChangeInvisibleDurationResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let (Some(group), Some(topic)) = (
+            resource_name(request.group.as_ref()),
+            resource_name(request.topic.as_ref()),
+        ) else {
+            return Ok(Response::new(v2::ChangeInvisibleDurationResponse {
+                status: Some(status(Code::BadRequest, "group and topic are required")),
+                receipt_handle: String::new(),
+            }));
+        };
+        let invisible_millis = request
+            .invisible_duration
+            .as_ref()
+            .map(converter::duration_to_millis)
+            .unwrap_or(self.processor.config().default_invisible_time_millis);
+        let response = match self
+            .processor
+            .change_invisible_duration(group, topic, &request.receipt_handle, invisible_millis)
+            .await
+        {
+            Ok(receipt_handle) => v2::ChangeInvisibleDurationResponse {
+                status: Some(ok_status()),
+                receipt_handle,
+            },
+            Err(error) => v2::ChangeInvisibleDurationResponse {
+                status: Some(error.to_status()),
+                receipt_handle: String::new(),
+            },
+        };
+        Ok(Response::new(response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_status_reports_first_failure() {
+        let ok = ok_status();
+        let not_found = status(Code::TopicNotFound, "topic not found");
+        let timeout = status(Code::ProxyTimeout, "timeout");
+        assert_eq!(
+            batch_status([Some(&ok), Some(&not_found), Some(&timeout)]).code,
+            Code::TopicNotFound as i32
+        );
+        assert_eq!(batch_status([Some(&ok), None]).code, Code::Ok as i32);
+    }
+}
@@ -0,0 +1,32 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! A proxy serving the RocketMQ 5.x gRPC API (`apache.rocketmq.v2.MessagingService`) in front of
+//! a remoting cluster, so that the gRPC SDKs can talk to brokers written against the remoting
+//! protocol.
+
+pub mod bootstrap;
+mod client_manager;
+pub mod config;
+mod converter;
+pub mod error;
+mod grpc_service;
+mod processor;
+pub mod proto;
+mod route;
+
+pub type Result<T> = std::result::Result<T, error::ProxyError>;
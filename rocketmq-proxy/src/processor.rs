@@ -0,0 +1,831 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use cheetah_string::CheetahString;
+use parking_lot::Mutex;
+use rocketmq_client_rust::base::client_config::ClientConfig;
+use rocketmq_client_rust::client_error::MQBrokerErr;
+use rocketmq_client_rust::client_error::MQClientError;
+use rocketmq_client_rust::consumer::ack_callback::AckCallback;
+use rocketmq_client_rust::consumer::ack_result::AckResult;
+use rocketmq_client_rust::consumer::ack_status::AckStatus;
+use rocketmq_client_rust::consumer::pop_callback::PopCallback;
+use rocketmq_client_rust::consumer::pop_result::PopResult;
+use rocketmq_client_rust::factory::mq_client_instance::MQClientInstance;
+use rocketmq_client_rust::producer::send_result::SendResult;
+use rocketmq_client_rust::MQClientAPIImpl;
+use rocketmq_common::common::constant::consume_init_mode::ConsumeInitMode;
+use rocketmq_common::common::constant::PermName;
+use rocketmq_common::common::consumer::consume_from_where::ConsumeFromWhere;
+use rocketmq_common::common::filter::expression_type::ExpressionType;
+use rocketmq_common::common::message::message_single::Message;
+use rocketmq_common::common::message::MessageConst;
+use rocketmq_common::common::message::MessageTrait;
+use rocketmq_common::common::mix_all;
+use rocketmq_common::common::sys_flag::message_sys_flag::MessageSysFlag;
+use rocketmq_common::common::topic::TopicValidator;
+use rocketmq_common::CRC32Utils::crc32;
+use rocketmq_common::MessageDecoder;
+use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_remoting::protocol::filter::filter_api::FilterAPI;
+use rocketmq_remoting::protocol::header::ack_message_request_header::AckMessageRequestHeader;
+use rocketmq_remoting::protocol::header::change_invisible_time_request_header::ChangeInvisibleTimeRequestHeader;
+use rocketmq_remoting::protocol::header::extra_info_util::ExtraInfoUtil;
+use rocketmq_remoting::protocol::header::message_operation_header::send_message_request_header::SendMessageRequestHeader;
+use rocketmq_remoting::protocol::header::namesrv::topic_operation_header::TopicRequestHeader as PopTopicRequestHeader;
+use rocketmq_remoting::protocol::header::pop_message_request_header::PopMessageRequestHeader;
+use rocketmq_remoting::protocol::heartbeat::consume_type::ConsumeType;
+use rocketmq_remoting::protocol::heartbeat::consumer_data::ConsumerData;
+use rocketmq_remoting::protocol::heartbeat::heartbeat_data::HeartbeatData;
+use rocketmq_remoting::protocol::heartbeat::message_model::MessageModel;
+use rocketmq_remoting::protocol::route::topic_route_data::TopicRouteData;
+use rocketmq_remoting::remoting_error::RemotingError;
+use rocketmq_remoting::rpc::rpc_request_header::RpcRequestHeader;
+use rocketmq_remoting::rpc::topic_request_header::TopicRequestHeader;
+use rocketmq_rust::ArcMut;
+use tokio::sync::oneshot;
+use tracing::warn;
+
+use crate::config::ProxyConfig;
+use crate::converter;
+use crate::error::ProxyError;
+use crate::proto::v2;
+use crate::proto::v2::Code;
+use crate::proto::v2::FilterType;
+use crate::route::TopicRouteService;
+
+/// Producer group stamped on the messages sent through the proxy.
+const PROXY_PRODUCER_GROUP: &str = "PROXY_SEND_MESSAGE_GROUP";
+
+/// Translates the gRPC calls into remoting requests issued through [`MQClientAPIImpl`].
+pub struct MessagingProcessor {
+    config: Arc<ProxyConfig>,
+    api: ArcMut<MQClientAPIImpl>,
+    topic_route_service: TopicRouteService,
+    send_queue_index: AtomicUsize,
+}
+
+impl MessagingProcessor {
+    /// Creates the processor and starts the remoting client it talks to namesrv and brokers
+    /// with. Must be called from within a tokio runtime.
+    pub async fn new(config: Arc<ProxyConfig>) -> crate::Result<Self> {
+        let client_config = ClientConfig {
+            namesrv_addr: Some(CheetahString::from_string(config.namesrv_addr.clone())),
+            instance_name: CheetahString::from_static_str("PROXY"),
+            ..ClientConfig::default()
+        };
+        let client_id = client_config.build_mq_client_id();
+        let client_instance = MQClientInstance::new_arc(client_config, 0, client_id, None);
+        let api = client_instance.get_mq_client_api_impl();
+        api.start().await?;
+        api.update_name_server_address_list(config.namesrv_addr.as_str())
+            .await;
+        let topic_route_service = TopicRouteService::new(
+            api.clone(),
+            config.remoting_timeout_millis,
+            config.topic_route_cache_expired_millis,
+        );
+        Ok(MessagingProcessor {
+            config,
+            api,
+            topic_route_service,
+            send_queue_index: AtomicUsize::new(0),
+        })
+    }
+
+    pub fn config(&self) -> &ProxyConfig {
+        &self.config
+    }
+
+    pub async fn query_route(&self, topic: &str) -> crate::Result<Arc<TopicRouteData>> {
+        self.topic_route_service.get_route(topic).await
+    }
+
+    /// Sends `message` to a writable queue of its topic. FIFO messages of the same message group
+    /// always land on the same queue.
+    pub async fn send_message(&self, message: &v2::Message) -> crate::Result<SendResult> {
+        let msg = converter::to_remoting_message(message, self.config.max_message_size)?;
+        let topic = msg.get_topic().clone();
+        let route = self.topic_route_service.get_route(&topic).await?;
+        let queues = writable_queues(&route);
+        if queues.is_empty() {
+            return Err(ProxyError::bad_request(
+                Code::Forbidden,
+                format!("topic {} has no writable queue", topic),
+            ));
+        }
+        let index = match message
+            .system_properties
+            .as_ref()
+            .and_then(|properties| properties.message_group.as_ref())
+        {
+            Some(message_group) => crc32(message_group.as_bytes()) as usize,
+            None => self.send_queue_index.fetch_add(1, Ordering::Relaxed),
+        };
+        let (broker_name, queue_id, addr) = queues[index % queues.len()].clone();
+        let request_header = send_message_request_header(&msg, &broker_name, queue_id);
+        let mut api = self.api.clone();
+        let send_result = api
+            .send_message_directly(
+                &addr,
+                &broker_name,
+                &msg,
+                request_header,
+                self.config.remoting_timeout_millis,
+            )
+            .await?;
+        Ok(send_result)
+    }
+
+    /// Pops messages for `group` from the broker named by the requested message queue.
+    pub async fn receive_message(
+        &self,
+        request: &v2::ReceiveMessageRequest,
+        fifo: bool,
+    ) -> crate::Result<PopResult> {
+        let (broker_name, request_header) =
+            pop_message_request_header(&self.config, request, fifo)?;
+        let addr = self
+            .topic_route_service
+            .find_master_addr(request_header.topic.as_str(), broker_name.as_str())
+            .await?;
+        let timeout_millis = request_header.poll_time + self.config.remoting_timeout_millis;
+        let (tx, rx) = oneshot::channel();
+        self.api
+            .pop_message_async(
+                &broker_name,
+                &addr,
+                request_header,
+                timeout_millis,
+                PopResultSender(Some(tx)),
+            )
+            .await?;
+        rx.await
+            .map_err(|_| ProxyError::Internal("pop callback dropped".to_string()))?
+    }
+
+    /// Acknowledges the message identified by `receipt_handle`.
+    pub async fn ack_message(
+        &self,
+        group: &str,
+        topic: &str,
+        receipt_handle: &str,
+    ) -> crate::Result<()> {
+        let handle = ReceiptHandle::parse(receipt_handle, topic, group)?;
+        let addr = self
+            .topic_route_service
+            .find_master_addr(topic, &handle.broker_name)
+            .await?;
+        let request_header = AckMessageRequestHeader {
+            consumer_group: CheetahString::from_slice(group),
+            topic: CheetahString::from_string(handle.real_topic),
+            queue_id: handle.queue_id,
+            extra_info: CheetahString::from_slice(receipt_handle),
+            offset: handle.queue_offset,
+            topic_request_header: Some(topic_request_header(&handle.broker_name)),
+        };
+        let (callback, rx) = AckResultSender::new();
+        self.api
+            .ack_message_async(
+                &addr,
+                request_header,
+                self.config.remoting_timeout_millis,
+                callback,
+            )
+            .await?;
+        let ack_result = rx
+            .await
+            .map_err(|_| ProxyError::Internal("ack callback dropped".to_string()))??;
+        match ack_result.status() {
+            AckStatus::Ok => Ok(()),
+            AckStatus::NotExist => Err(ProxyError::bad_request(
+                Code::InvalidReceiptHandle,
+                "receipt handle is expired or the message does not exist",
+            )),
+        }
+    }
+
+    /// Changes the invisible time of the message identified by `receipt_handle` and returns the
+    /// receipt handle to use from now on.
+    pub async fn change_invisible_duration(
+        &self,
+        group: &str,
+        topic: &str,
+        receipt_handle: &str,
+        invisible_millis: u64,
+    ) -> crate::Result<String> {
+        let handle = ReceiptHandle::parse(receipt_handle, topic, group)?;
+        let addr = self
+            .topic_route_service
+            .find_master_addr(topic, &handle.broker_name)
+            .await?;
+        let broker_name = CheetahString::from_slice(&handle.broker_name);
+        let request_header = ChangeInvisibleTimeRequestHeader {
+            consumer_group: CheetahString::from_slice(group),
+            topic: CheetahString::from_string(handle.real_topic),
+            queue_id: handle.queue_id,
+            extra_info: CheetahString::from_slice(receipt_handle),
+            offset: handle.queue_offset,
+            invisible_time: invisible_millis as i64,
+            topic_request_header: Some(topic_request_header(&handle.broker_name)),
+        };
+        let (callback, rx) = AckResultSender::new();
+        self.api
+            .change_invisible_time_async(
+                &broker_name,
+                &addr,
+                request_header,
+                self.config.remoting_timeout_millis,
+                callback,
+            )
+            .await?;
+        let ack_result = rx
+            .await
+            .map_err(|_| ProxyError::Internal("ack callback dropped".to_string()))??;
+        match ack_result.status() {
+            AckStatus::Ok => Ok(ack_result.extra_info().to_string()),
+            AckStatus::NotExist => Err(ProxyError::bad_request(
+                Code::InvalidReceiptHandle,
+                "receipt handle is expired or the message does not exist",
+            )),
+        }
+    }
+
+    /// Registers the consumer `group` of `client_id` on the masters serving `topics`, so that
+    /// brokers know the subscriptions of the gRPC consumers.
+    pub async fn heartbeat(
+        &self,
+        client_id: &str,
+        group: &str,
+        subscriptions: &[v2::SubscriptionEntry],
+    ) -> crate::Result<()> {
+        let mut subscription_data_set = HashSet::new();
+        let mut addrs = HashSet::new();
+        for entry in subscriptions {
+            let Some(topic) = resource_name(entry.topic.as_ref()) else {
+                continue;
+            };
+            let (exp_type, exp) = filter_expression(entry.expression.as_ref());
+            let topic = CheetahString::from_string(topic);
+            let subscription_data = FilterAPI::build(
+                &topic,
+                &CheetahString::from_string(exp),
+                Some(CheetahString::from_static_str(exp_type)),
+            )
+            .map_err(|error| ProxyError::bad_request(Code::IllegalFilterExpression, error))?;
+            subscription_data_set.insert(subscription_data);
+
+            let route = self.topic_route_service.get_route(&topic).await?;
+            addrs.extend(route.broker_datas.iter().filter_map(|broker_data| {
+                broker_data.broker_addrs().get(&mix_all::MASTER_ID).cloned()
+            }));
+        }
+
+        let mut consumer_data_set = HashSet::new();
+        consumer_data_set.insert(ConsumerData {
+            group_name: CheetahString::from_slice(group),
+            consume_type: ConsumeType::ConsumePop,
+            message_model: MessageModel::Clustering,
+            consume_from_where: ConsumeFromWhere::ConsumeFromLastOffset,
+            subscription_data_set,
+            unit_mode: false,
+        });
+        let heartbeat_data = HeartbeatData {
+            client_id: CheetahString::from_slice(client_id),
+            consumer_data_set,
+            ..Default::default()
+        };
+        let mut api = self.api.clone();
+        for addr in addrs {
+            if let Err(error) = api
+                .send_heartbeat(&addr, &heartbeat_data, self.config.remoting_timeout_millis)
+                .await
+            {
+                warn!(
+                    "send heartbeat of {} to {} failed: {}",
+                    client_id, addr, error
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writable queues of a topic as `(broker name, queue id, master address)`.
+fn writable_queues(route: &TopicRouteData) -> Vec<(CheetahString, i32, CheetahString)> {
+    let mut queues = Vec::new();
+    for queue_data in &route.queue_datas {
+        if !PermName::is_writeable(queue_data.perm()) {
+            continue;
+        }
+        let Some(addr) = converter::master_addr(route, queue_data.broker_name()) else {
+            continue;
+        };
+        for queue_id in 0..queue_data.write_queue_nums() {
+            queues.push((
+                queue_data.broker_name().clone(),
+                queue_id as i32,
+                addr.clone(),
+            ));
+        }
+    }
+    queues
+}
+
+/// Builds the request sending `msg` to queue `queue_id` of `broker_name`.
+fn send_message_request_header(
+    msg: &Message,
+    broker_name: &CheetahString,
+    queue_id: i32,
+) -> SendMessageRequestHeader {
+    let mut sys_flag = 0;
+    if msg.get_tags().is_some_and(|tags| tags.contains("||")) {
+        sys_flag |= MessageSysFlag::MULTI_TAGS_FLAG;
+    }
+    SendMessageRequestHeader {
+        producer_group: CheetahString::from_static_str(PROXY_PRODUCER_GROUP),
+        topic: msg.get_topic().clone(),
+        default_topic: CheetahString::from_static_str(TopicValidator::AUTO_CREATE_TOPIC_KEY_TOPIC),
+        default_topic_queue_nums: 4,
+        queue_id,
+        sys_flag,
+        born_timestamp: get_current_millis() as i64,
+        flag: msg.get_flag(),
+        properties: Some(MessageDecoder::message_properties_to_string(
+            msg.get_properties(),
+        )),
+        reconsume_times: Some(0),
+        unit_mode: Some(false),
+        batch: Some(false),
+        topic_request_header: Some(TopicRequestHeader {
+            rpc_request_header: Some(RpcRequestHeader {
+                broker_name: Some(broker_name.clone()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Builds the pop request of a receive request, returns it with the name of the broker to pop
+/// from. The batch size and the long polling timeout are capped by `config`.
+fn pop_message_request_header(
+    config: &ProxyConfig,
+    request: &v2::ReceiveMessageRequest,
+    fifo: bool,
+) -> crate::Result<(CheetahString, PopMessageRequestHeader)> {
+    let group = resource_name(request.group.as_ref())
+        .ok_or_else(|| ProxyError::bad_request(Code::IllegalConsumerGroup, "group is required"))?;
+    let message_queue = request
+        .message_queue
+        .as_ref()
+        .ok_or_else(|| ProxyError::bad_request(Code::BadRequest, "message queue is required"))?;
+    let topic = resource_name(message_queue.topic.as_ref())
+        .ok_or_else(|| ProxyError::bad_request(Code::IllegalTopic, "topic is required"))?;
+    let broker_name = message_queue
+        .broker
+        .as_ref()
+        .map(|broker| CheetahString::from_slice(&broker.name))
+        .ok_or_else(|| ProxyError::bad_request(Code::BadRequest, "broker is required"))?;
+    let (exp_type, exp) = filter_expression(request.filter_expression.as_ref());
+    let invisible_time = request
+        .invisible_duration
+        .as_ref()
+        .map(converter::duration_to_millis)
+        .unwrap_or(config.default_invisible_time_millis);
+    let poll_time = request
+        .long_polling_timeout
+        .as_ref()
+        .map(converter::duration_to_millis)
+        .unwrap_or(config.default_long_polling_timeout_millis)
+        .min(config.max_long_polling_timeout_millis);
+    let max_msg_nums = (request.batch_size.max(1) as u32).min(config.max_receive_batch_size);
+    let request_header = PopMessageRequestHeader {
+        consumer_group: CheetahString::from_string(group),
+        topic: CheetahString::from_string(topic),
+        queue_id: message_queue.id,
+        max_msg_nums,
+        invisible_time,
+        poll_time,
+        born_time: get_current_millis(),
+        init_mode: ConsumeInitMode::MAX,
+        exp_type: Some(CheetahString::from_static_str(exp_type)),
+        exp: Some(CheetahString::from_string(exp)),
+        order: Some(fifo),
+        attempt_id: request.attempt_id.clone().map(CheetahString::from_string),
+        topic_request_header: Some(PopTopicRequestHeader {
+            lo: None,
+            rpc: Some(RpcRequestHeader {
+                broker_name: Some(broker_name.clone()),
+                ..Default::default()
+            }),
+        }),
+    };
+    Ok((broker_name, request_header))
+}
+
+/// The expression type and expression of a gRPC filter, all tags are subscribed without one.
+fn filter_expression(filter: Option<&v2::FilterExpression>) -> (&'static str, String) {
+    match filter {
+        Some(filter) if filter.r#type() == FilterType::Sql => {
+            (ExpressionType::SQL92, filter.expression.clone())
+        }
+        Some(filter) if !filter.expression.is_empty() => {
+            (ExpressionType::TAG, filter.expression.clone())
+        }
+        _ => (ExpressionType::TAG, "*".to_string()),
+    }
+}
+
+fn resource_name(resource: Option<&v2::Resource>) -> Option<String> {
+    resource
+        .map(|resource| resource.name.clone())
+        .filter(|name| !name.is_empty())
+}
+
+fn topic_request_header(broker_name: &str) -> TopicRequestHeader {
+    TopicRequestHeader {
+        rpc_request_header: Some(RpcRequestHeader {
+            broker_name: Some(CheetahString::from_slice(broker_name)),
+            ..Default::default()
+        }),
+        lo: None,
+    }
+}
+
+/// The parts of a receipt handle (the `POP_CK` of a popped message) needed to ack it.
+#[derive(Debug)]
+struct ReceiptHandle {
+    real_topic: String,
+    broker_name: String,
+    queue_id: i32,
+    queue_offset: i64,
+}
+
+impl ReceiptHandle {
+    fn parse(receipt_handle: &str, topic: &str, group: &str) -> crate::Result<Self> {
+        let invalid = |error: RemotingError| {
+            ProxyError::bad_request(Code::InvalidReceiptHandle, error.to_string())
+        };
+        if receipt_handle.is_empty() {
+            return Err(ProxyError::bad_request(
+                Code::InvalidReceiptHandle,
+                "receipt handle is empty",
+            ));
+        }
+        // brokers join the parts with the key separator, see
+        // `ExtraInfoUtil::build_extra_info_with_msg_queue_offset`
+        let strs = receipt_handle
+            .split(MessageConst::KEY_SEPARATOR)
+            .map(String::from)
+            .collect::<Vec<_>>();
+        Ok(ReceiptHandle {
+            real_topic: ExtraInfoUtil::get_real_topic(&strs, topic, group).map_err(invalid)?,
+            broker_name: ExtraInfoUtil::get_broker_name(&strs).map_err(invalid)?,
+            queue_id: ExtraInfoUtil::get_queue_id(&strs).map_err(invalid)?,
+            queue_offset: ExtraInfoUtil::get_queue_offset(&strs).map_err(invalid)?,
+        })
+    }
+}
+
+struct PopResultSender(Option<oneshot::Sender<crate::Result<PopResult>>>);
+
+impl PopCallback for PopResultSender {
+    async fn on_success(&mut self, pop_result: PopResult) {
+        if let Some(tx) = self.0.take() {
+            let _ = tx.send(Ok(pop_result));
+        }
+    }
+
+    fn on_error(&mut self, e: Box<dyn Error + Send>) {
+        if let Some(tx) = self.0.take() {
+            let _ = tx.send(Err(client_error(e.as_ref())));
+        }
+    }
+}
+
+struct AckResultSender(Mutex<Option<oneshot::Sender<crate::Result<AckResult>>>>);
+
+impl AckResultSender {
+    fn new() -> (Self, oneshot::Receiver<crate::Result<AckResult>>) {
+        let (tx, rx) = oneshot::channel();
+        (AckResultSender(Mutex::new(Some(tx))), rx)
+    }
+}
+
+impl AckCallback for AckResultSender {
+    fn on_success(&self, ack_result: AckResult) {
+        if let Some(tx) = self.0.lock().take() {
+            let _ = tx.send(Ok(ack_result));
+        }
+    }
+
+    fn on_exception(&self, e: Box<dyn Error>) {
+        if let Some(tx) = self.0.lock().take() {
+            let _ = tx.send(Err(client_error(e.as_ref())));
+        }
+    }
+}
+
+/// Recovers the client error handed to a callback as a trait object, so that its code survives.
+fn client_error(error: &(dyn Error + 'static)) -> ProxyError {
+    if let Some(RemotingError::RemotingTimeoutError(addr, timeout)) =
+        error.downcast_ref::<RemotingError>()
+    {
+        return ProxyError::from(MQClientError::RemotingError(
+            RemotingError::RemotingTimeoutError(addr.clone(), *timeout),
+        ));
+    }
+    if let Some(MQClientError::MQClientBrokerError(error)) = error.downcast_ref::<MQClientError>() {
+        return ProxyError::from(MQClientError::MQClientBrokerError(MQBrokerErr::new(
+            error.response_code(),
+            error.error_message().cloned().unwrap_or_default(),
+        )));
+    }
+    ProxyError::Internal(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rocketmq_common::common::key_builder::KeyBuilder;
+    use rocketmq_remoting::protocol::route::route_data_view::BrokerData;
+    use rocketmq_remoting::protocol::route::route_data_view::QueueData;
+
+    use super::*;
+
+    fn broker_data(broker_name: &str, addr: &str) -> BrokerData {
+        let mut broker_addrs = HashMap::new();
+        broker_addrs.insert(mix_all::MASTER_ID, CheetahString::from(addr));
+        BrokerData::new(
+            CheetahString::from("DefaultCluster"),
+            CheetahString::from(broker_name),
+            broker_addrs,
+            None,
+        )
+    }
+
+    fn resource(name: &str) -> Option<v2::Resource> {
+        Some(v2::Resource {
+            resource_namespace: String::new(),
+            name: name.to_string(),
+        })
+    }
+
+    fn receive_request() -> v2::ReceiveMessageRequest {
+        v2::ReceiveMessageRequest {
+            group: resource("GroupTest"),
+            message_queue: Some(v2::MessageQueue {
+                topic: resource("TopicTest"),
+                id: 3,
+                broker: Some(v2::Broker {
+                    name: "broker-a".to_string(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            batch_size: 16,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn writable_queues_skip_brokers_without_write_permission_or_master() {
+        let route = TopicRouteData {
+            queue_datas: vec![
+                QueueData::new(
+                    CheetahString::from("broker-a"),
+                    4,
+                    2,
+                    PermName::PERM_READ | PermName::PERM_WRITE,
+                    0,
+                ),
+                QueueData::new(
+                    CheetahString::from("broker-b"),
+                    4,
+                    4,
+                    PermName::PERM_READ,
+                    0,
+                ),
+                QueueData::new(
+                    CheetahString::from("broker-c"),
+                    4,
+                    4,
+                    PermName::PERM_READ | PermName::PERM_WRITE,
+                    0,
+                ),
+            ],
+            // broker-c has no master to send to
+            broker_datas: vec![
+                broker_data("broker-a", "127.0.0.1:10911"),
+                broker_data("broker-b", "127.0.0.1:10921"),
+            ],
+            ..Default::default()
+        };
+        let queues = writable_queues(&route);
+        assert_eq!(
+            queues,
+            vec![
+                (
+                    CheetahString::from("broker-a"),
+                    0,
+                    CheetahString::from("127.0.0.1:10911")
+                ),
+                (
+                    CheetahString::from("broker-a"),
+                    1,
+                    CheetahString::from("127.0.0.1:10911")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn receipt_handle_round_trip() {
+        let pop_ck = ExtraInfoUtil::build_extra_info_with_msg_queue_offset(
+            7,
+            1_700_000_000_000,
+            60_000,
+            1,
+            "TopicTest",
+            "broker-a",
+            3,
+            42,
+        );
+        let handle = ReceiptHandle::parse(&pop_ck, "TopicTest", "GroupTest").unwrap();
+        assert_eq!(handle.real_topic, "TopicTest");
+        assert_eq!(handle.broker_name, "broker-a");
+        assert_eq!(handle.queue_id, 3);
+        assert_eq!(handle.queue_offset, 42);
+
+        // messages popped from the retry topic are acked on it
+        let retry_topic = KeyBuilder::build_pop_retry_topic_v1("TopicTest", "GroupTest");
+        let pop_ck = ExtraInfoUtil::build_extra_info_with_msg_queue_offset(
+            7,
+            1_700_000_000_000,
+            60_000,
+            1,
+            &retry_topic,
+            "broker-a",
+            0,
+            5,
+        );
+        let handle = ReceiptHandle::parse(&pop_ck, "TopicTest", "GroupTest").unwrap();
+        assert_eq!(handle.real_topic, retry_topic);
+        assert_eq!(handle.queue_offset, 5);
+    }
+
+    #[test]
+    fn receipt_handle_rejects_malformed_handles() {
+        for receipt_handle in [
+            "",
+            "0 1 2 3 0",
+            "0 1 2 3 0 broker-a",
+            "0 1 2 3 0 broker-a x 42",
+            "0 1 2 3 0 broker-a 3 x",
+            "0|1|2|3|0|broker-a|3|42",
+        ] {
+            let error = ReceiptHandle::parse(receipt_handle, "TopicTest", "GroupTest").unwrap_err();
+            assert_eq!(
+                error.code(),
+                Code::InvalidReceiptHandle,
+                "{}",
+                receipt_handle
+            );
+        }
+    }
+
+    #[test]
+    fn pop_request_header_is_built_from_receive_request() {
+        let config = ProxyConfig::default();
+        let mut request = receive_request();
+        request.filter_expression = Some(v2::FilterExpression {
+            r#type: FilterType::Tag as i32,
+            expression: "TagA || TagB".to_string(),
+        });
+        request.invisible_duration = Some(converter::duration_from_millis(30_000));
+        request.attempt_id = Some("attempt-1".to_string());
+
+        let (broker_name, header) = pop_message_request_header(&config, &request, true).unwrap();
+        assert_eq!(broker_name, "broker-a");
+        assert_eq!(header.consumer_group, "GroupTest");
+        assert_eq!(header.topic, "TopicTest");
+        assert_eq!(header.queue_id, 3);
+        assert_eq!(header.max_msg_nums, 16);
+        assert_eq!(header.invisible_time, 30_000);
+        assert_eq!(header.poll_time, config.default_long_polling_timeout_millis);
+        assert_eq!(header.exp_type.as_deref(), Some(ExpressionType::TAG));
+        assert_eq!(header.exp.as_deref(), Some("TagA || TagB"));
+        assert_eq!(header.order, Some(true));
+        assert_eq!(header.attempt_id.as_deref(), Some("attempt-1"));
+        assert_eq!(
+            header
+                .topic_request_header
+                .and_then(|header| header.rpc)
+                .and_then(|rpc| rpc.broker_name)
+                .as_deref(),
+            Some("broker-a")
+        );
+    }
+
+    #[test]
+    fn pop_request_header_caps_batch_size_and_polling_timeout() {
+        let config = ProxyConfig::default();
+        let mut request = receive_request();
+        request.batch_size = 1024;
+        request.long_polling_timeout = Some(converter::duration_from_millis(120_000));
+        request.filter_expression = Some(v2::FilterExpression {
+            r#type: FilterType::Sql as i32,
+            expression: "a > 1".to_string(),
+        });
+
+        let (_, header) = pop_message_request_header(&config, &request, false).unwrap();
+        assert_eq!(header.max_msg_nums, config.max_receive_batch_size);
+        assert_eq!(header.poll_time, config.max_long_polling_timeout_millis);
+        assert_eq!(header.invisible_time, config.default_invisible_time_millis);
+        assert_eq!(header.exp_type.as_deref(), Some(ExpressionType::SQL92));
+        assert_eq!(header.exp.as_deref(), Some("a > 1"));
+
+        // without a filter all tags are received
+        request.filter_expression = None;
+        request.batch_size = 0;
+        let (_, header) = pop_message_request_header(&config, &request, false).unwrap();
+        assert_eq!(header.max_msg_nums, 1);
+        assert_eq!(header.exp.as_deref(), Some("*"));
+    }
+
+    #[test]
+    fn pop_request_header_requires_group_topic_and_broker() {
+        let config = ProxyConfig::default();
+        let mut request = receive_request();
+        request.group = None;
+        let error = pop_message_request_header(&config, &request, false).unwrap_err();
+        assert_eq!(error.code(), Code::IllegalConsumerGroup);
+
+        let mut request = receive_request();
+        request.message_queue.as_mut().unwrap().topic = resource("");
+        let error = pop_message_request_header(&config, &request, false).unwrap_err();
+        assert_eq!(error.code(), Code::IllegalTopic);
+
+        let mut request = receive_request();
+        request.message_queue.as_mut().unwrap().broker = None;
+        let error = pop_message_request_header(&config, &request, false).unwrap_err();
+        assert_eq!(error.code(), Code::BadRequest);
+    }
+
+    #[test]
+    fn send_request_header_is_built_from_message() {
+        let message = v2::Message {
+            topic: resource("TopicTest"),
+            system_properties: Some(v2::SystemProperties {
+                tag: Some("TagA||TagB".to_string()),
+                ..Default::default()
+            }),
+            body: b"hello".to_vec(),
+            ..Default::default()
+        };
+        let msg = converter::to_remoting_message(&message, 1024).unwrap();
+        let header = send_message_request_header(&msg, &CheetahString::from("broker-a"), 2);
+        assert_eq!(header.producer_group, PROXY_PRODUCER_GROUP);
+        assert_eq!(header.topic, "TopicTest");
+        assert_eq!(header.queue_id, 2);
+        assert_eq!(
+            header.sys_flag & MessageSysFlag::MULTI_TAGS_FLAG,
+            MessageSysFlag::MULTI_TAGS_FLAG
+        );
+        assert!(header
+            .properties
+            .unwrap()
+            .contains(&format!("{}\u{1}TagA||TagB", MessageConst::PROPERTY_TAGS)));
+        assert_eq!(
+            header
+                .topic_request_header
+                .and_then(|header| header.rpc_request_header)
+                .and_then(|rpc| rpc.broker_name)
+                .as_deref(),
+            Some("broker-a")
+        );
+    }
+}
@@ -0,0 +1,657 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Messages of the `apache.rocketmq.v2` gRPC protocol spoken by the RocketMQ 5.x SDKs.
+//!
+//! The definitions follow `definition.proto` and `service.proto` of apache/rocketmq-apis with
+//! the same field tags, only the parts served by the proxy are defined.
+
+pub mod v2 {
+    use std::collections::HashMap;
+
+    use prost_types::Duration;
+    use prost_types::Timestamp;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Permission {
+        Unspecified = 0,
+        None = 1,
+        Read = 2,
+        Write = 3,
+        ReadWrite = 4,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum FilterType {
+        Unspecified = 0,
+        Tag = 1,
+        Sql = 2,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum AddressScheme {
+        Unspecified = 0,
+        IPv4 = 1,
+        IPv6 = 2,
+        DomainName = 3,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum MessageType {
+        Unspecified = 0,
+        Normal = 1,
+        Fifo = 2,
+        Delay = 3,
+        Transaction = 4,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum DigestType {
+        Unspecified = 0,
+        Crc32 = 1,
+        Md5 = 2,
+        Sha1 = 3,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum ClientType {
+        Unspecified = 0,
+        Producer = 1,
+        PushConsumer = 2,
+        SimpleConsumer = 3,
+        PullConsumer = 4,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Encoding {
+        Unspecified = 0,
+        Identity = 1,
+        Gzip = 2,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Language {
+        Unspecified = 0,
+        Java = 1,
+        Cpp = 2,
+        DotNet = 3,
+        Golang = 4,
+        Rust = 5,
+        Python = 6,
+        Php = 7,
+        NodeJs = 8,
+        Ruby = 9,
+        ObjectiveC = 10,
+        Dart = 11,
+        Kotlin = 12,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Code {
+        Unspecified = 0,
+        Ok = 20000,
+        MultipleResults = 30000,
+        BadRequest = 40000,
+        IllegalAccessPoint = 40001,
+        IllegalTopic = 40002,
+        IllegalConsumerGroup = 40003,
+        IllegalMessageTag = 40004,
+        IllegalMessageKey = 40005,
+        IllegalMessageGroup = 40006,
+        IllegalMessagePropertyKey = 40007,
+        InvalidTransactionId = 40008,
+        IllegalMessageId = 40009,
+        IllegalFilterExpression = 40010,
+        IllegalInvisibleTime = 40011,
+        IllegalDeliveryTime = 40012,
+        InvalidReceiptHandle = 40013,
+        MessagePropertyConflictWithType = 40014,
+        UnrecognizedClientType = 40015,
+        MessageCorrupted = 40016,
+        ClientIdRequired = 40017,
+        IllegalPollingTime = 40018,
+        Unauthorized = 40100,
+        PaymentRequired = 40200,
+        Forbidden = 40300,
+        NotFound = 40400,
+        MessageNotFound = 40401,
+        TopicNotFound = 40402,
+        ConsumerGroupNotFound = 40403,
+        RequestTimeout = 40800,
+        PayloadTooLarge = 41300,
+        MessageBodyTooLarge = 41301,
+        PreconditionFailed = 42800,
+        TooManyRequests = 42900,
+        RequestHeaderFieldsTooLarge = 43100,
+        MessagePropertiesTooLarge = 43101,
+        InternalError = 50000,
+        InternalServerError = 50001,
+        HaNotAvailable = 50002,
+        NotImplemented = 50100,
+        ProxyTimeout = 50400,
+        MasterPersistenceTimeout = 50401,
+        SlavePersistenceTimeout = 50402,
+        Unsupported = 50500,
+        VersionUnsupported = 50501,
+        VerifyFifoMessageUnsupported = 50502,
+        FailedToConsumeMessage = 60000,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Resource {
+        #[prost(string, tag = "1")]
+        pub resource_namespace: String,
+        #[prost(string, tag = "2")]
+        pub name: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct FilterExpression {
+        #[prost(enumeration = "FilterType", tag = "1")]
+        pub r#type: i32,
+        #[prost(string, tag = "2")]
+        pub expression: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct RetryPolicy {
+        #[prost(int32, tag = "1")]
+        pub max_attempts: i32,
+        #[prost(oneof = "retry_policy::Strategy", tags = "2, 3")]
+        pub strategy: Option<retry_policy::Strategy>,
+    }
+
+    pub mod retry_policy {
+        #[derive(Clone, PartialEq, prost::Oneof)]
+        pub enum Strategy {
+            #[prost(message, tag = "2")]
+            ExponentialBackoff(super::ExponentialBackoff),
+            #[prost(message, tag = "3")]
+            CustomizedBackoff(super::CustomizedBackoff),
+        }
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ExponentialBackoff {
+        #[prost(message, optional, tag = "1")]
+        pub initial: Option<Duration>,
+        #[prost(message, optional, tag = "2")]
+        pub max: Option<Duration>,
+        #[prost(float, tag = "3")]
+        pub multiplier: f32,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct CustomizedBackoff {
+        #[prost(message, repeated, tag = "1")]
+        pub next: Vec<Duration>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SubscriptionEntry {
+        #[prost(message, optional, tag = "1")]
+        pub topic: Option<Resource>,
+        #[prost(message, optional, tag = "2")]
+        pub expression: Option<FilterExpression>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Address {
+        #[prost(string, tag = "1")]
+        pub host: String,
+        #[prost(int32, tag = "2")]
+        pub port: i32,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Endpoints {
+        #[prost(enumeration = "AddressScheme", tag = "1")]
+        pub scheme: i32,
+        #[prost(message, repeated, tag = "2")]
+        pub addresses: Vec<Address>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Broker {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(int32, tag = "2")]
+        pub id: i32,
+        #[prost(message, optional, tag = "3")]
+        pub endpoints: Option<Endpoints>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MessageQueue {
+        #[prost(message, optional, tag = "1")]
+        pub topic: Option<Resource>,
+        #[prost(int32, tag = "2")]
+        pub id: i32,
+        #[prost(enumeration = "Permission", tag = "3")]
+        pub permission: i32,
+        #[prost(message, optional, tag = "4")]
+        pub broker: Option<Broker>,
+        #[prost(enumeration = "MessageType", repeated, tag = "5")]
+        pub accept_message_types: Vec<i32>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Digest {
+        #[prost(enumeration = "DigestType", tag = "1")]
+        pub r#type: i32,
+        #[prost(string, tag = "2")]
+        pub checksum: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SystemProperties {
+        #[prost(string, optional, tag = "1")]
+        pub tag: Option<String>,
+        #[prost(string, repeated, tag = "2")]
+        pub keys: Vec<String>,
+        #[prost(string, tag = "3")]
+        pub message_id: String,
+        #[prost(message, optional, tag = "4")]
+        pub body_digest: Option<Digest>,
+        #[prost(enumeration = "Encoding", tag = "5")]
+        pub body_encoding: i32,
+        #[prost(enumeration = "MessageType", tag = "6")]
+        pub message_type: i32,
+        #[prost(message, optional, tag = "7")]
+        pub born_timestamp: Option<Timestamp>,
+        #[prost(string, tag = "8")]
+        pub born_host: String,
+        #[prost(message, optional, tag = "9")]
+        pub store_timestamp: Option<Timestamp>,
+        #[prost(string, tag = "10")]
+        pub store_host: String,
+        #[prost(message, optional, tag = "11")]
+        pub delivery_timestamp: Option<Timestamp>,
+        #[prost(string, optional, tag = "12")]
+        pub receipt_handle: Option<String>,
+        #[prost(int32, tag = "13")]
+        pub queue_id: i32,
+        #[prost(int64, optional, tag = "14")]
+        pub queue_offset: Option<i64>,
+        #[prost(message, optional, tag = "15")]
+        pub invisible_duration: Option<Duration>,
+        #[prost(int32, optional, tag = "16")]
+        pub delivery_attempt: Option<i32>,
+        #[prost(string, optional, tag = "17")]
+        pub message_group: Option<String>,
+        #[prost(string, optional, tag = "18")]
+        pub trace_context: Option<String>,
+        #[prost(message, optional, tag = "19")]
+        pub orphaned_transaction_recovery_duration: Option<Duration>,
+        #[prost(message, optional, tag = "20")]
+        pub dead_letter_queue: Option<DeadLetterQueue>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct DeadLetterQueue {
+        #[prost(string, tag = "1")]
+        pub topic: String,
+        #[prost(string, tag = "2")]
+        pub message_id: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Message {
+        #[prost(message, optional, tag = "1")]
+        pub topic: Option<Resource>,
+        #[prost(map = "string, string", tag = "2")]
+        pub user_properties: HashMap<String, String>,
+        #[prost(message, optional, tag = "3")]
+        pub system_properties: Option<SystemProperties>,
+        #[prost(bytes = "vec", tag = "4")]
+        pub body: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Assignment {
+        #[prost(message, optional, tag = "1")]
+        pub message_queue: Option<MessageQueue>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Status {
+        #[prost(enumeration = "Code", tag = "1")]
+        pub code: i32,
+        #[prost(string, tag = "2")]
+        pub message: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Ua {
+        #[prost(enumeration = "Language", tag = "1")]
+        pub language: i32,
+        #[prost(string, tag = "2")]
+        pub version: String,
+        #[prost(string, tag = "3")]
+        pub platform: String,
+        #[prost(string, tag = "4")]
+        pub hostname: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Settings {
+        #[prost(enumeration = "ClientType", optional, tag = "1")]
+        pub client_type: Option<i32>,
+        #[prost(message, optional, tag = "2")]
+        pub access_point: Option<Endpoints>,
+        #[prost(message, optional, tag = "3")]
+        pub backoff_policy: Option<RetryPolicy>,
+        #[prost(message, optional, tag = "4")]
+        pub request_timeout: Option<Duration>,
+        #[prost(oneof = "settings::PubSub", tags = "5, 6")]
+        pub pub_sub: Option<settings::PubSub>,
+        #[prost(message, optional, tag = "7")]
+        pub user_agent: Option<Ua>,
+        #[prost(message, optional, tag = "8")]
+        pub metric: Option<Metric>,
+    }
+
+    pub mod settings {
+        #[derive(Clone, PartialEq, prost::Oneof)]
+        pub enum PubSub {
+            #[prost(message, tag = "5")]
+            Publishing(super::Publishing),
+            #[prost(message, tag = "6")]
+            Subscription(super::Subscription),
+        }
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Publishing {
+        #[prost(message, repeated, tag = "1")]
+        pub topics: Vec<Resource>,
+        #[prost(int32, tag = "2")]
+        pub max_body_size: i32,
+        #[prost(bool, tag = "3")]
+        pub validate_message_type: bool,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Subscription {
+        #[prost(message, optional, tag = "1")]
+        pub group: Option<Resource>,
+        #[prost(message, repeated, tag = "2")]
+        pub subscriptions: Vec<SubscriptionEntry>,
+        #[prost(bool, optional, tag = "3")]
+        pub fifo: Option<bool>,
+        #[prost(int32, optional, tag = "4")]
+        pub receive_batch_size: Option<i32>,
+        #[prost(message, optional, tag = "5")]
+        pub long_polling_timeout: Option<Duration>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Metric {
+        #[prost(bool, tag = "1")]
+        pub on: bool,
+        #[prost(message, optional, tag = "2")]
+        pub endpoints: Option<Endpoints>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct QueryRouteRequest {
+        #[prost(message, optional, tag = "1")]
+        pub topic: Option<Resource>,
+        #[prost(message, optional, tag = "2")]
+        pub endpoints: Option<Endpoints>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct QueryRouteResponse {
+        #[prost(message, optional, tag = "1")]
+        pub status: Option<Status>,
+        #[prost(message, repeated, tag = "2")]
+        pub message_queues: Vec<MessageQueue>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SendMessageRequest {
+        #[prost(message, repeated, tag = "1")]
+        pub messages: Vec<Message>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SendResultEntry {
+        #[prost(message, optional, tag = "1")]
+        pub status: Option<Status>,
+        #[prost(string, tag = "2")]
+        pub message_id: String,
+        #[prost(string, tag = "3")]
+        pub transaction_id: String,
+        #[prost(int64, tag = "4")]
+        pub offset: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SendMessageResponse {
+        #[prost(message, optional, tag = "1")]
+        pub status: Option<Status>,
+        #[prost(message, repeated, tag = "2")]
+        pub entries: Vec<SendResultEntry>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct QueryAssignmentRequest {
+        #[prost(message, optional, tag = "1")]
+        pub topic: Option<Resource>,
+        #[prost(message, optional, tag = "2")]
+        pub group: Option<Resource>,
+        #[prost(message, optional, tag = "3")]
+        pub endpoints: Option<Endpoints>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct QueryAssignmentResponse {
+        #[prost(message, optional, tag = "1")]
+        pub status: Option<Status>,
+        #[prost(message, repeated, tag = "2")]
+        pub assignments: Vec<Assignment>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ReceiveMessageRequest {
+        #[prost(message, optional, tag = "1")]
+        pub group: Option<Resource>,
+        #[prost(message, optional, tag = "2")]
+        pub message_queue: Option<MessageQueue>,
+        #[prost(message, optional, tag = "3")]
+        pub filter_expression: Option<FilterExpression>,
+        #[prost(int32, tag = "4")]
+        pub batch_size: i32,
+        #[prost(message, optional, tag = "5")]
+        pub invisible_duration: Option<Duration>,
+        #[prost(bool, tag = "6")]
+        pub auto_renew: bool,
+        #[prost(message, optional, tag = "7")]
+        pub long_polling_timeout: Option<Duration>,
+        #[prost(string, optional, tag = "8")]
+        pub attempt_id: Option<String>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ReceiveMessageResponse {
+        #[prost(oneof = "receive_message_response::Content", tags = "1, 2, 3")]
+        pub content: Option<receive_message_response::Content>,
+    }
+
+    pub mod receive_message_response {
+        #[allow(clippy::large_enum_variant)]
+        #[derive(Clone, PartialEq, prost::Oneof)]
+        pub enum Content {
+            #[prost(message, tag = "1")]
+            Status(super::Status),
+            #[prost(message, tag = "2")]
+            Message(super::Message),
+            #[prost(message, tag = "3")]
+            DeliveryTimestamp(prost_types::Timestamp),
+        }
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct AckMessageEntry {
+        #[prost(string, tag = "1")]
+        pub message_id: String,
+        #[prost(string, tag = "2")]
+        pub receipt_handle: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct AckMessageRequest {
+        #[prost(message, optional, tag = "1")]
+        pub group: Option<Resource>,
+        #[prost(message, optional, tag = "2")]
+        pub topic: Option<Resource>,
+        #[prost(message, repeated, tag = "3")]
+        pub entries: Vec<AckMessageEntry>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct AckMessageResultEntry {
+        #[prost(string, tag = "1")]
+        pub message_id: String,
+        #[prost(string, tag = "2")]
+        pub receipt_handle: String,
+        #[prost(message, optional, tag = "3")]
+        pub status: Option<Status>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct AckMessageResponse {
+        #[prost(message, optional, tag = "1")]
+        pub status: Option<Status>,
+        #[prost(message, repeated, tag = "2")]
+        pub entries: Vec<AckMessageResultEntry>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct HeartbeatRequest {
+        #[prost(message, optional, tag = "1")]
+        pub group: Option<Resource>,
+        #[prost(enumeration = "ClientType", tag = "2")]
+        pub client_type: i32,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct HeartbeatResponse {
+        #[prost(message, optional, tag = "1")]
+        pub status: Option<Status>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ChangeInvisibleDurationRequest {
+        #[prost(message, optional, tag = "1")]
+        pub group: Option<Resource>,
+        #[prost(message, optional, tag = "2")]
+        pub topic: Option<Resource>,
+        #[prost(string, tag = "3")]
+        pub receipt_handle: String,
+        #[prost(message, optional, tag = "4")]
+        pub invisible_duration: Option<Duration>,
+        #[prost(string, tag = "5")]
+        pub message_id: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ChangeInvisibleDurationResponse {
+        #[prost(message, optional, tag = "1")]
+        pub status: Option<Status>,
+        #[prost(string, tag = "2")]
+        pub receipt_handle: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct PrintThreadStackTraceCommand {
+        #[prost(string, tag = "1")]
+        pub nonce: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ThreadStackTrace {
+        #[prost(string, tag = "1")]
+        pub nonce: String,
+        #[prost(string, optional, tag = "2")]
+        pub thread_stack_trace: Option<String>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct VerifyMessageCommand {
+        #[prost(string, tag = "1")]
+        pub nonce: String,
+        #[prost(message, optional, tag = "2")]
+        pub message: Option<Message>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct VerifyMessageResult {
+        #[prost(string, tag = "1")]
+        pub nonce: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct RecoverOrphanedTransactionCommand {
+        #[prost(message, optional, tag = "1")]
+        pub message: Option<Message>,
+        #[prost(string, tag = "2")]
+        pub transaction_id: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct TelemetryCommand {
+        #[prost(message, optional, tag = "1")]
+        pub status: Option<Status>,
+        #[prost(oneof = "telemetry_command::Command", tags = "2, 3, 4, 5, 6, 7")]
+        pub command: Option<telemetry_command::Command>,
+    }
+
+    pub mod telemetry_command {
+        #[allow(clippy::large_enum_variant)]
+        #[derive(Clone, PartialEq, prost::Oneof)]
+        pub enum Command {
+            #[prost(message, tag = "2")]
+            Settings(super::Settings),
+            #[prost(message, tag = "3")]
+            ThreadStackTrace(super::ThreadStackTrace),
+            #[prost(message, tag = "4")]
+            VerifyMessageResult(super::VerifyMessageResult),
+            #[prost(message, tag = "5")]
+            RecoverOrphanedTransactionCommand(super::RecoverOrphanedTransactionCommand),
+            #[prost(message, tag = "6")]
+            PrintThreadStackTraceCommand(super::PrintThreadStackTraceCommand),
+            #[prost(message, tag = "7")]
+            VerifyMessageCommand(super::VerifyMessageCommand),
+        }
+    }
+
+    include!(concat!(
+        env!("OUT_DIR"),
+        "/apache.rocketmq.v2.MessagingService.rs"
+    ));
+}
@@ -0,0 +1,96 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use cheetah_string::CheetahString;
+use parking_lot::RwLock;
+use rocketmq_client_rust::MQClientAPIImpl;
+use rocketmq_remoting::protocol::route::topic_route_data::TopicRouteData;
+use rocketmq_rust::ArcMut;
+
+use crate::converter;
+use crate::error::ProxyError;
+
+struct CachedRoute {
+    route: Arc<TopicRouteData>,
+    fetched_at: Instant,
+}
+
+/// Caches the topic routes fetched from namesrv for `expired` before fetching them again.
+pub struct TopicRouteService {
+    api: ArcMut<MQClientAPIImpl>,
+    timeout_millis: u64,
+    expired: Duration,
+    routes: RwLock<HashMap<CheetahString, CachedRoute>>,
+}
+
+impl TopicRouteService {
+    pub fn new(api: ArcMut<MQClientAPIImpl>, timeout_millis: u64, expired_millis: u64) -> Self {
+        TopicRouteService {
+            api,
+            timeout_millis,
+            expired: Duration::from_millis(expired_millis),
+            routes: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub async fn get_route(&self, topic: &str) -> crate::Result<Arc<TopicRouteData>> {
+        if let Some(cached) = self.routes.read().get(topic) {
+            if cached.fetched_at.elapsed() < self.expired {
+                return Ok(cached.route.clone());
+            }
+        }
+        self.refresh(topic).await
+    }
+
+    /// Returns the master address of `broker_name`, refreshing the route once when the broker is
+    /// unknown to the cached route.
+    pub async fn find_master_addr(
+        &self,
+        topic: &str,
+        broker_name: &str,
+    ) -> crate::Result<CheetahString> {
+        let route = self.get_route(topic).await?;
+        if let Some(addr) = converter::master_addr(&route, broker_name) {
+            return Ok(addr);
+        }
+        let route = self.refresh(topic).await?;
+        converter::master_addr(&route, broker_name)
+            .ok_or_else(|| ProxyError::BrokerNotFound(broker_name.to_string()))
+    }
+
+    async fn refresh(&self, topic: &str) -> crate::Result<Arc<TopicRouteData>> {
+        let route = self
+            .api
+            .get_topic_route_info_from_name_server(topic, self.timeout_millis)
+            .await?
+            .ok_or_else(|| ProxyError::TopicRouteNotFound(topic.to_string()))?;
+        let route = Arc::new(route);
+        self.routes.write().insert(
+            CheetahString::from_slice(topic),
+            CachedRoute {
+                route: route.clone(),
+                fetched_at: Instant::now(),
+            },
+        );
+        Ok(route)
+    }
+}
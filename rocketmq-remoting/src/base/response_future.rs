@@ -14,16 +14,34 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::collections::HashMap;
 use std::hash::Hash;
 use std::hash::Hasher;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use rocketmq_common::TimeUtils::get_current_millis;
+use tracing::warn;
 
 use crate::protocol::remoting_command::RemotingCommand;
+use crate::remoting_error::RemotingError::ConnectionInvalid;
+use crate::remoting_error::RemotingError::RemotingTimeoutError;
 use crate::Result;
 
+/// Grace period before a request past its timeout is swept from a response table, so that the
+/// caller waiting for it times out first.
+const SCAN_GRACE_MILLIS: u64 = 1000;
+
+/// The requests of a channel waiting for their response, by opaque. It is shared by the tasks
+/// sending requests, reading responses and sweeping timed out requests, so it is only accessed
+/// through its lock, which is never held across an `.await`.
+pub type ResponseTable = Arc<Mutex<HashMap<i32, ResponseFuture>>>;
+
 pub struct ResponseFuture {
     pub(crate) opaque: i32,
     pub(crate) timeout_millis: u64,
     pub(crate) send_request_ok: bool,
+    pub(crate) begin_timestamp: u64,
     //pub(crate) response_command: Option<RemotingCommand>,
     pub(crate) tx: tokio::sync::oneshot::Sender<Result<RemotingCommand>>,
 }
@@ -58,8 +76,149 @@ impl ResponseFuture {
             opaque,
             timeout_millis,
             send_request_ok,
+            begin_timestamp: get_current_millis(),
             // response_command,
             tx,
         }
     }
+
+    #[inline]
+    pub fn opaque(&self) -> i32 {
+        self.opaque
+    }
+
+    #[inline]
+    pub fn timeout_millis(&self) -> u64 {
+        self.timeout_millis
+    }
+
+    #[inline]
+    pub fn begin_timestamp(&self) -> u64 {
+        self.begin_timestamp
+    }
+
+    /// Whether the request waited longer than its timeout at `now`, requests without a timeout
+    /// never expire.
+    pub fn is_timeout(&self, now: u64) -> bool {
+        self.timeout_millis > 0 && now.saturating_sub(self.begin_timestamp) > self.timeout_millis
+    }
+
+    /// Completes the request with `result`, the waiting caller may already be gone.
+    pub fn put_response(self, result: Result<RemotingCommand>) {
+        let _ = self.tx.send(result);
+    }
+}
+
+/// Removes the requests of `response_table` that are past their timeout and fails them, returns
+/// their opaques.
+pub(crate) fn scan_response_table(
+    response_table: &mut HashMap<i32, ResponseFuture>,
+    remote_address: &str,
+) -> Vec<i32> {
+    let now = get_current_millis();
+    let expired = response_table
+        .values()
+        .filter(|future| future.is_timeout(now.saturating_sub(SCAN_GRACE_MILLIS)))
+        .map(ResponseFuture::opaque)
+        .collect::<Vec<_>>();
+    for opaque in &expired {
+        if let Some(future) = response_table.remove(opaque) {
+            warn!(
+                "remove timeout request, opaque={}, timeout={}ms, address={}",
+                opaque, future.timeout_millis, remote_address
+            );
+            let timeout_millis = future.timeout_millis;
+            future.put_response(Err(RemotingTimeoutError(
+                remote_address.to_string(),
+                timeout_millis,
+            )));
+        }
+    }
+    expired
+}
+
+/// Fails every request of `response_table` as the channel to `remote_address` is closed, returns
+/// the number of requests failed.
+pub(crate) fn fail_response_table(
+    response_table: &mut HashMap<i32, ResponseFuture>,
+    remote_address: &str,
+) -> usize {
+    let pending = response_table.len();
+    for (_, future) in response_table.drain() {
+        future.put_response(Err(ConnectionInvalid(format!(
+            "the channel to {} is closed",
+            remote_address
+        ))));
+    }
+    pending
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::oneshot;
+
+    use super::*;
+    use crate::remoting_error::RemotingError;
+
+    fn pending(
+        opaque: i32,
+        timeout_millis: u64,
+        age_millis: u64,
+    ) -> (ResponseFuture, oneshot::Receiver<Result<RemotingCommand>>) {
+        let (tx, rx) = oneshot::channel();
+        let mut future = ResponseFuture::new(opaque, timeout_millis, true, tx);
+        future.begin_timestamp -= age_millis;
+        (future, rx)
+    }
+
+    #[test]
+    fn requests_without_timeout_never_expire() {
+        let (future, _rx) = pending(1, 0, 60_000);
+        assert!(!future.is_timeout(get_current_millis()));
+        let (future, _rx) = pending(2, 3000, 3001);
+        assert!(future.is_timeout(get_current_millis()));
+    }
+
+    #[test]
+    fn scan_fails_expired_requests_only() {
+        let mut response_table = HashMap::new();
+        let (expired, mut expired_rx) = pending(1, 100, 2000);
+        let (waiting, mut waiting_rx) = pending(2, 3000, 0);
+        response_table.insert(1, expired);
+        response_table.insert(2, waiting);
+
+        assert_eq!(
+            scan_response_table(&mut response_table, "127.0.0.1:10911"),
+            vec![1]
+        );
+        assert!(matches!(
+            expired_rx.try_recv().unwrap(),
+            Err(RemotingError::RemotingTimeoutError(_, 100))
+        ));
+        assert!(waiting_rx.try_recv().is_err());
+        assert!(response_table.contains_key(&2));
+    }
+
+    #[test]
+    fn closed_channel_fails_all_requests() {
+        let mut response_table = HashMap::new();
+        let (first, mut first_rx) = pending(1, 3000, 0);
+        let (second, mut second_rx) = pending(2, 3000, 0);
+        response_table.insert(1, first);
+        response_table.insert(2, second);
+
+        assert_eq!(
+            fail_response_table(&mut response_table, "127.0.0.1:10911"),
+            2
+        );
+        assert!(response_table.is_empty());
+        assert!(matches!(
+            first_rx.try_recv().unwrap(),
+            Err(RemotingError::ConnectionInvalid(_))
+        ));
+        assert!(matches!(
+            second_rx.try_recv().unwrap(),
+            Err(RemotingError::ConnectionInvalid(_))
+        ));
+    }
 }
@@ -39,6 +39,9 @@ pub mod rocketmq_default_impl;
 /// clients connected to specific addresses.
 #[allow(async_fn_in_trait)]
 pub trait RemotingClient: RemotingService {
+    /// The processor of the requests servers send to this client.
+    type Processor: RequestProcessor;
+
     /// Updates the list of name remoting_server addresses.
     ///
     /// # Arguments
@@ -86,19 +89,27 @@ pub trait RemotingClient: RemotingService {
         timeout_millis: u64,
     );
 
-    /// Checks if a specified address is reachable.
+    /// Checks if a specified address is reachable, connecting to it if needed.
     ///
     /// # Arguments
     /// * `addr` - The address to check for reachability.
-    fn is_address_reachable(&mut self, addr: &CheetahString);
+    ///
+    /// # Returns
+    /// `true` if a healthy connection to the address is available.
+    async fn is_address_reachable(&self, addr: &CheetahString) -> bool;
 
     /// Closes clients connected to the specified addresses.
     ///
     /// # Arguments
     /// * `addrs` - A list of addresses whose clients should be closed.
-    fn close_clients(&mut self, addrs: Vec<String>);
+    async fn close_clients(&self, addrs: Vec<CheetahString>);
 
-    fn register_processor(&mut self, processor: impl RequestProcessor + Sync);
+    /// Registers the processor of the requests servers send to this client, it replaces the
+    /// current processor for the connections created afterwards.
+    ///
+    /// # Arguments
+    /// * `processor` - The processor to register.
+    fn register_processor(&mut self, processor: Self::Processor);
 }
 
 impl<T> InvokeCallback for T
@@ -15,25 +15,45 @@
  * limitations under the License.
  */
 use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
 
+use parking_lot::Mutex;
 use rocketmq_rust::ArcMut;
 use tokio::sync::mpsc::Receiver;
+use tokio_rustls::TlsConnector;
 use tracing::error;
+use tracing::info;
 use tracing::warn;
 
 use crate::base::connection_net_event::ConnectionNetEvent;
+use crate::base::response_future::fail_response_table;
+use crate::base::response_future::scan_response_table;
 use crate::base::response_future::ResponseFuture;
+use crate::base::response_future::ResponseTable;
 use crate::code::response_code::ResponseCode;
+use crate::connection::BodyCompression;
 use crate::connection::Connection;
 use crate::net::channel::Channel;
+use crate::net::socks5;
+use crate::net::socks5::SocksProxyConfig;
+use crate::net::tls;
+use crate::net::transport;
 use crate::protocol::remoting_command::RemotingCommand;
 use crate::protocol::RemotingCommandType;
+use crate::remoting_error::RemotingError;
 use crate::remoting_error::RemotingError::ConnectionInvalid;
 use crate::remoting_error::RemotingError::Io;
 use crate::remoting_error::RemotingError::RemoteError;
+use crate::remoting_error::RemotingError::RemotingTimeoutError;
 use crate::runtime::connection_handler_context::ConnectionHandlerContext;
 use crate::runtime::connection_handler_context::ConnectionHandlerContextWrapper;
 use crate::runtime::processor::RequestProcessor;
+use crate::runtime::RPCHook;
 use crate::Result;
 
 #[derive(Clone)]
@@ -51,10 +71,18 @@ pub struct Client {
 }
 
 struct ClientInner {
-    response_table: ArcMut<HashMap<i32, ResponseFuture>>,
+    /// The address this client dialed.
+    addr: String,
+    /// Set once the server announced GoAway, the client should be replaced by a new one.
+    going_away: AtomicBool,
+    response_table: ResponseTable,
     channel: Channel,
     ctx: ConnectionHandlerContext,
     tx: tokio::sync::mpsc::Sender<SendMessage>,
+    /// Hooks run around the requests the server sends to this client.
+    rpc_hooks: Arc<Vec<Arc<Box<dyn RPCHook>>>>,
+    /// Number of requests on this channel that were not answered within their timeout.
+    timed_out_requests: AtomicU64,
 }
 
 type SendMessage = (
@@ -76,12 +104,30 @@ async fn run_recv<PR: RequestProcessor>(mut client: ArcMut<ClientInner>, mut pro
                 // handle request
                 RemotingCommandType::REQUEST => {
                     let opaque = msg.opaque();
+                    let oneway_rpc = msg.is_oneway_rpc();
+                    let remote_address = client.channel.remote_address();
+                    let mut msg = msg;
+                    if let Err(err) = client.do_before_rpc_hooks(remote_address, &mut msg) {
+                        if !oneway_rpc {
+                            let command = hook_error_response(err).set_opaque(opaque);
+                            let _ = client.tx.send((command, None, None)).await;
+                        }
+                        continue;
+                    }
                     let process_result = processor
                         .process_request(client.channel.clone(), client.ctx.clone(), msg)
                         .await;
                     match process_result {
                         Ok(response) => {
-                            if let Some(response) = response {
+                            if let Some(mut response) = response {
+                                if let Err(err) =
+                                    client.do_after_rpc_hooks(remote_address, &mut response)
+                                {
+                                    if oneway_rpc {
+                                        continue;
+                                    }
+                                    response = hook_error_response(err);
+                                }
                                 let _ = client
                                     .tx
                                     .send((response.set_opaque(opaque), None, None))
@@ -101,9 +147,20 @@ async fn run_recv<PR: RequestProcessor>(mut client: ArcMut<ClientInner>, mut pro
                 // handle response
                 RemotingCommandType::RESPONSE => {
                     let opaque = msg.opaque();
-                    if let Some(response_future) = client.response_table.remove(&opaque) {
+                    if msg.code() == ResponseCode::GoAway as i32
+                        && !client.going_away.load(Ordering::Acquire)
+                    {
+                        info!(
+                            "receive go away from {}, the channel will be replaced",
+                            client.addr
+                        );
+                        client.going_away.store(true, Ordering::Release);
+                    }
+                    let response_future = client.response_table.lock().remove(&opaque);
+                    if let Some(response_future) = response_future {
                         let _ = response_future.tx.send(Ok(msg));
-                    } else {
+                    } else if msg.code() != ResponseCode::GoAway as i32 {
+                        // a GoAway may be announced without a request to answer
                         warn!(
                             "receive response, cmd={}, but not matched any request, address={}",
                             msg,
@@ -114,9 +171,8 @@ async fn run_recv<PR: RequestProcessor>(mut client: ArcMut<ClientInner>, mut pro
             },
             Err(error) => match error {
                 Io(value) => {
-                    client.ctx.channel.connection.ok = false;
                     error!("error: {:?}", value);
-                    return;
+                    break;
                 }
                 _ => {
                     error!("error: {:?}", error);
@@ -124,27 +180,77 @@ async fn run_recv<PR: RequestProcessor>(mut client: ArcMut<ClientInner>, mut pro
             },
         }
     }
+    // the channel is closed, the requests waiting for their response fail right away
+    client.ctx.channel.connection.ok = false;
+    client.fail_in_flight_requests();
+}
+
+/// The response of a request aborted by an RPC hook, the same as the server answers.
+fn hook_error_response(error: RemotingError) -> RemotingCommand {
+    match error {
+        RemotingError::AbortProcessError(code, message) => {
+            RemotingCommand::create_response_command_with_code_remark(code, message)
+        }
+        error => RemotingCommand::create_response_command_with_code_remark(
+            ResponseCode::SystemError,
+            error.to_string(),
+        ),
+    }
 }
 
 impl ClientInner {
-    pub async fn connect<T, PR>(
-        addr: T,
+    pub async fn connect<PR>(
+        addr: &str,
+        tls_connector: Option<&TlsConnector>,
+        socks_proxy: Option<&SocksProxyConfig>,
+        body_compression: Option<BodyCompression>,
+        rpc_hooks: Arc<Vec<Arc<Box<dyn RPCHook>>>>,
         processor: PR,
         tx: Option<&tokio::sync::broadcast::Sender<ConnectionNetEvent>>,
     ) -> Result<(tokio::sync::mpsc::Sender<SendMessage>, ArcMut<ClientInner>)>
     where
-        T: tokio::net::ToSocketAddrs,
         PR: RequestProcessor + 'static,
     {
-        let tcp_stream = tokio::net::TcpStream::connect(addr).await;
-        if tcp_stream.is_err() {
-            return Err(Io(tcp_stream.err().unwrap()));
-        }
-        let stream = tcp_stream?;
-        let local_addr = stream.local_addr()?;
-        let remote_address = stream.peer_addr()?;
-        let connection = Connection::new(stream);
-        let response_table = ArcMut::new(HashMap::with_capacity(128));
+        let (connection, local_addr, remote_address) = match transport::unix_socket_path(addr) {
+            // Unix domain sockets stay on the host, neither proxied nor encrypted
+            Some(path) if tls_connector.is_some() => {
+                return Err(RemotingError::TlsError(format!(
+                    "Unix domain socket {} cannot be dialed with TLS enabled",
+                    path
+                )));
+            }
+            Some(path) => {
+                let (stream, local_addr, remote_address) = transport::connect_unix(path).await?;
+                (Connection::new(stream), local_addr, remote_address)
+            }
+            None => {
+                let stream = match socks_proxy {
+                    Some(socks_proxy) => socks5::connect(socks_proxy, addr).await?,
+                    None => {
+                        let tcp_stream = tokio::net::TcpStream::connect(addr).await;
+                        if tcp_stream.is_err() {
+                            return Err(Io(tcp_stream.err().unwrap()));
+                        }
+                        tcp_stream?
+                    }
+                };
+                let local_addr = stream.local_addr()?;
+                // Behind a proxy the peer is the proxy itself, prefer the address being dialed.
+                let remote_address = match addr.parse::<SocketAddr>() {
+                    Ok(remote_address) => remote_address,
+                    Err(_) => stream.peer_addr()?,
+                };
+                let connection = match tls_connector {
+                    Some(tls_connector) => {
+                        Connection::new(tls::connect(tls_connector, addr, stream).await?)
+                    }
+                    None => Connection::new(stream),
+                };
+                (connection, local_addr, remote_address)
+            }
+        };
+        let connection = connection.with_body_compression(body_compression);
+        let response_table: ResponseTable = Arc::new(Mutex::new(HashMap::with_capacity(128)));
         let channel = Channel::new(
             local_addr,
             remote_address,
@@ -154,6 +260,8 @@ impl ClientInner {
 
         let (tx_, rx) = tokio::sync::mpsc::channel(1024);
         let client = ClientInner {
+            addr: addr.to_string(),
+            going_away: AtomicBool::new(false),
             ctx: ArcMut::new(ConnectionHandlerContextWrapper::new(
                 //connection,
                 channel.clone(),
@@ -161,6 +269,8 @@ impl ClientInner {
             response_table,
             channel,
             tx: tx_.clone(),
+            rpc_hooks,
+            timed_out_requests: AtomicU64::new(0),
         };
         let client = ArcMut::new(client);
 
@@ -175,6 +285,49 @@ impl ClientInner {
         Ok((tx_, client))
     }
 
+    fn do_before_rpc_hooks(
+        &self,
+        remote_address: SocketAddr,
+        request: &mut RemotingCommand,
+    ) -> Result<()> {
+        for hook in self.rpc_hooks.iter() {
+            hook.do_before_request(remote_address, request)?;
+        }
+        Ok(())
+    }
+
+    fn do_after_rpc_hooks(
+        &self,
+        remote_address: SocketAddr,
+        response: &mut RemotingCommand,
+    ) -> Result<()> {
+        for hook in self.rpc_hooks.iter() {
+            hook.do_after_response(remote_address, response)?;
+        }
+        Ok(())
+    }
+
+    /// Fails all the requests waiting for their response as the channel is closed.
+    fn fail_in_flight_requests(&self) {
+        let failed = fail_response_table(&mut self.response_table.lock(), &self.addr);
+        if failed > 0 {
+            warn!(
+                "the channel to {} is closed, {} in-flight requests failed",
+                self.addr, failed
+            );
+        }
+    }
+
+    /// Counts a request that was not answered within its timeout, returns whether it was still
+    /// waiting.
+    fn on_request_timeout(&self, opaque: i32) -> bool {
+        let waiting = self.response_table.lock().remove(&opaque).is_some();
+        if waiting {
+            self.timed_out_requests.fetch_add(1, Ordering::Relaxed);
+        }
+        waiting
+    }
+
     pub async fn send(
         &mut self,
         request: RemotingCommand,
@@ -183,7 +336,7 @@ impl ClientInner {
     ) -> Result<()> {
         let opaque = request.opaque();
         if let Some(tx) = tx {
-            self.response_table.insert(
+            self.response_table.lock().insert(
                 opaque,
                 ResponseFuture::new(opaque, timeout_millis.unwrap_or(0), true, tx),
             );
@@ -192,12 +345,12 @@ impl ClientInner {
             Ok(_) => Ok(()),
             Err(error) => match error {
                 Io(value) => {
-                    self.response_table.remove(&opaque);
                     self.ctx.channel.connection.ok = false;
+                    self.fail_in_flight_requests();
                     Err(ConnectionInvalid(value.to_string()))
                 }
                 _ => {
-                    self.response_table.remove(&opaque);
+                    self.response_table.lock().remove(&opaque);
                     Err(error)
                 }
             },
@@ -211,17 +364,24 @@ impl Client {
     /// # Arguments
     ///
     /// * `addr` - The address to connect to.
+    /// * `tls_connector` - The connector used for the TLS handshake, `None` for plaintext.
+    /// * `socks_proxy` - The SOCKS5 proxy to dial through, `None` to connect directly.
+    /// * `body_compression` - The compression of the request bodies, `None` to send them as is.
+    /// * `rpc_hooks` - The hooks run around the requests the server sends to this client.
     ///
     /// # Returns
     ///
     /// A new `Client` instance wrapped in a `Result`. Returns an error if the connection fails.
-    pub async fn connect<T, PR>(
-        addr: T,
+    pub async fn connect<PR>(
+        addr: &str,
+        tls_connector: Option<&TlsConnector>,
+        socks_proxy: Option<&SocksProxyConfig>,
+        body_compression: Option<BodyCompression>,
+        rpc_hooks: Arc<Vec<Arc<Box<dyn RPCHook>>>>,
         processor: PR,
         tx: Option<&tokio::sync::broadcast::Sender<ConnectionNetEvent>>,
     ) -> Result<Client>
     where
-        T: tokio::net::ToSocketAddrs,
         PR: RequestProcessor + 'static,
     {
         /*let tcp_stream = tokio::net::TcpStream::connect(addr).await;
@@ -231,7 +391,16 @@ impl Client {
         Ok(Client {
             connection: Connection::new(tcp_stream?),
         })*/
-        let (tx, inner) = ClientInner::connect(addr, processor, tx).await?;
+        let (tx, inner) = ClientInner::connect(
+            addr,
+            tls_connector,
+            socks_proxy,
+            body_compression,
+            rpc_hooks,
+            processor,
+            tx,
+        )
+        .await?;
         Ok(Client {
             //connection: inner.connection.clone(),
             inner,
@@ -248,7 +417,8 @@ impl Client {
     /// # Returns
     ///
     /// The `RemotingCommand` representing the response, wrapped in a `Result`. Returns an error if
-    /// the invocation fails.
+    /// the invocation fails, `RemotingTimeoutError` if no response arrived within
+    /// `timeout_millis`.
     pub async fn send_read(
         &mut self,
         request: RemotingCommand,
@@ -259,6 +429,7 @@ impl Client {
         Ok(response)*/
 
         let (tx, rx) = tokio::sync::oneshot::channel::<Result<RemotingCommand>>();
+        let opaque = request.opaque();
 
         if let Err(err) = self
             .tx
@@ -267,9 +438,21 @@ impl Client {
         {
             return Err(RemoteError(err.to_string()));
         }
-        match rx.await {
-            Ok(value) => value,
-            Err(error) => Err(RemoteError(error.to_string())),
+        match tokio::time::timeout(Duration::from_millis(timeout_millis), rx).await {
+            Ok(Ok(value)) => value,
+            Ok(Err(error)) => Err(RemoteError(error.to_string())),
+            Err(_) => {
+                if self.inner.on_request_timeout(opaque) {
+                    warn!(
+                        "wait response from {} timeout, opaque={}, timeout={}ms",
+                        self.inner.addr, opaque, timeout_millis
+                    );
+                }
+                Err(RemotingTimeoutError(
+                    self.inner.addr.clone(),
+                    timeout_millis,
+                ))
+            }
         }
     }
 
@@ -343,6 +526,49 @@ impl Client {
         self.inner.ctx.channel.connection_ref()
     }
 
+    /// Returns the address of the remote peer.
+    pub fn remote_address(&self) -> SocketAddr {
+        self.inner.channel.remote_address()
+    }
+
+    /// The address this client dialed.
+    pub fn addr(&self) -> &str {
+        self.inner.addr.as_str()
+    }
+
+    /// Whether the server announced GoAway on this client's channel.
+    pub fn is_going_away(&self) -> bool {
+        self.inner.going_away.load(Ordering::Acquire)
+    }
+
+    /// Number of requests waiting for their response.
+    pub fn in_flight_requests(&self) -> usize {
+        self.inner.response_table.lock().len()
+    }
+
+    /// Number of requests on this channel that were not answered within their timeout.
+    pub fn timed_out_requests(&self) -> u64 {
+        self.inner.timed_out_requests.load(Ordering::Relaxed)
+    }
+
+    /// Fails the requests whose callers stopped waiting without cleaning up after them, returns
+    /// how many were removed.
+    pub fn scan_response_table(&self) -> usize {
+        let expired =
+            scan_response_table(&mut self.inner.response_table.lock(), &self.inner.addr).len();
+        self.inner
+            .timed_out_requests
+            .fetch_add(expired as u64, Ordering::Relaxed);
+        expired
+    }
+
+    /// Closes the channel, requests still waiting for their response fail.
+    pub async fn close(&mut self) -> Result<()> {
+        let result = self.inner.ctx.channel.connection.close().await;
+        self.inner.fail_in_flight_requests();
+        result
+    }
+
     pub fn connection_mut(&mut self) -> &mut Connection {
         self.inner.ctx.channel.connection_mut()
     }
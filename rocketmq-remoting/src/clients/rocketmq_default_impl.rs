@@ -16,18 +16,21 @@
  */
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::net::SocketAddr;
 use std::sync::atomic::AtomicI32;
 use std::sync::Arc;
 use std::time::Duration;
 
 use cheetah_string::CheetahString;
 use rand::Rng;
+use rocketmq_common::TimeUtils::get_current_millis;
 use rocketmq_runtime::RocketMQRuntime;
 use rocketmq_rust::ArcMut;
 use rocketmq_rust::WeakArcMut;
 use tokio::runtime::Handle;
 use tokio::sync::Mutex;
 use tokio::time;
+use tokio_rustls::TlsConnector;
 use tracing::debug;
 use tracing::error;
 use tracing::info;
@@ -36,6 +39,10 @@ use tracing::warn;
 use crate::base::connection_net_event::ConnectionNetEvent;
 use crate::clients::Client;
 use crate::clients::RemotingClient;
+use crate::code::response_code::ResponseCode;
+use crate::connection::BodyCompression;
+use crate::net::socks5::SocksProxyRules;
+use crate::net::tls;
 use crate::protocol::remoting_command::RemotingCommand;
 use crate::remoting::RemotingService;
 use crate::remoting_error::RemotingError;
@@ -47,6 +54,17 @@ use crate::Result;
 
 const LOCK_TIMEOUT_MILLIS: u64 = 3000;
 
+/// Max time a channel replaced after GoAway is kept open for its in-flight requests.
+const GO_AWAY_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Delay of the first scan of the response tables and the interval between the scans.
+const SCAN_RESPONSE_TABLE_DELAY: Duration = Duration::from_secs(3);
+const SCAN_RESPONSE_TABLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Requests with a shorter timeout do not close their channel when they time out, the channel is
+/// more likely healthy than the timeout reasonable.
+const MIN_CLOSE_TIMEOUT_MILLIS: u64 = 100;
+
 pub type ArcSyncClient = Arc<Mutex<Client>>;
 
 pub struct RocketmqDefaultClient<PR = DefaultRemotingRequestProcessor> {
@@ -60,6 +78,16 @@ pub struct RocketmqDefaultClient<PR = DefaultRemotingRequestProcessor> {
     client_runtime: Option<RocketMQRuntime>,
     processor: PR,
     tx: Option<tokio::sync::broadcast::Sender<ConnectionNetEvent>>,
+    /// `Err` holds why the TLS context could not be created, the client refuses to connect
+    /// then rather than falling back to plaintext.
+    tls_connector: std::result::Result<Option<TlsConnector>, CheetahString>,
+    socks_proxy_rules: SocksProxyRules,
+    body_compression: Option<BodyCompression>,
+    /// Last time a channel was replaced after GoAway, by address.
+    last_reconnect_timestamps: Arc<parking_lot::Mutex<HashMap<CheetahString, u64>>>,
+    /// Hooks run around the requests sent to servers and the requests received from them, the
+    /// connections take a snapshot of the hooks when created.
+    rpc_hooks: Arc<Vec<Arc<Box<dyn RPCHook>>>>,
 }
 impl<PR: RequestProcessor + Sync + Clone + 'static> RocketmqDefaultClient<PR> {
     pub fn new(tokio_client_config: Arc<TokioClientConfig>, processor: PR) -> Self {
@@ -71,6 +99,38 @@ impl<PR: RequestProcessor + Sync + Clone + 'static> RocketmqDefaultClient<PR> {
         processor: PR,
         tx: Option<tokio::sync::broadcast::Sender<ConnectionNetEvent>>,
     ) -> Self {
+        let tls_connector = if tokio_client_config.use_tls {
+            match tls::build_client_connector(&tokio_client_config.tls_config) {
+                Ok(tls_connector) => {
+                    info!("TLS enabled for the remoting client");
+                    Ok(Some(tls_connector))
+                }
+                Err(err) => {
+                    error!(
+                        "Failed to create TLS context for the remoting client: {}",
+                        err
+                    );
+                    Err(CheetahString::from_string(err.to_string()))
+                }
+            }
+        } else {
+            Ok(None)
+        };
+        let socks_proxy_rules = SocksProxyRules::parse(&tokio_client_config.socks_proxy_config)
+            .unwrap_or_else(|err| {
+                error!(
+                    "Ignore the invalid socks proxy config {}: {}",
+                    tokio_client_config.socks_proxy_config, err
+                );
+                SocksProxyRules::default()
+            });
+        if !socks_proxy_rules.is_empty() {
+            info!("SOCKS5 proxy enabled for the remoting client");
+        }
+        let body_compression = BodyCompression::new(
+            tokio_client_config.body_compress_threshold,
+            &tokio_client_config.body_compress_type,
+        );
         Self {
             tokio_client_config,
             connection_tables: Arc::new(Mutex::new(Default::default())),
@@ -81,18 +141,32 @@ impl<PR: RequestProcessor + Sync + Clone + 'static> RocketmqDefaultClient<PR> {
             client_runtime: Some(RocketMQRuntime::new_multi(10, "client-thread")),
             processor,
             tx,
+            tls_connector,
+            socks_proxy_rules,
+            body_compression,
+            last_reconnect_timestamps: Default::default(),
+            rpc_hooks: Default::default(),
         }
     }
 }
 
 impl<PR: RequestProcessor + Sync + Clone + 'static> RocketmqDefaultClient<PR> {
+    /// Fails with the configuration error if the TLS context of the client could not be
+    /// created, no connection can be made then.
+    pub fn check_tls_config(&self) -> Result<()> {
+        match &self.tls_connector {
+            Ok(_) => Ok(()),
+            Err(err) => Err(RemotingError::TlsError(err.to_string())),
+        }
+    }
+
     async fn get_and_create_nameserver_client(&self) -> Option<Client> {
         let mut addr = self.namesrv_addr_choosed.as_ref().clone();
         if let Some(ref addr) = addr {
             let guard = self.connection_tables.lock().await;
             let ct = guard.get(addr);
             if let Some(ct) = ct {
-                let conn_status = ct.connection().ok;
+                let conn_status = ct.connection().ok && !ct.is_going_away();
                 //let conn_status = ct.lock().await.connection().ok;
                 if conn_status {
                     return Some(ct.clone());
@@ -105,7 +179,7 @@ impl<PR: RequestProcessor + Sync + Clone + 'static> RocketmqDefaultClient<PR> {
         if let Some(addr) = addr.as_ref() {
             let ct = connection_tables.get(addr);
             if let Some(ct) = ct {
-                let conn_status = ct.connection().ok;
+                let conn_status = ct.connection().ok && !ct.is_going_away();
                 //let conn_status = ct.lock().await.connection().ok;
                 if conn_status {
                     return Some(ct.clone());
@@ -148,7 +222,10 @@ impl<PR: RequestProcessor + Sync + Clone + 'static> RocketmqDefaultClient<PR> {
                 }
                 let client = self.connection_tables.lock().await.get(addr).cloned();
                 // if client.is_some() && client.as_ref()?.lock().await.connection().ok {
-                if client.is_some() && client.as_ref()?.connection().ok {
+                if client.is_some()
+                    && client.as_ref()?.connection().ok
+                    && !client.as_ref()?.is_going_away()
+                {
                     return client;
                 }
                 self.create_client(
@@ -162,28 +239,40 @@ impl<PR: RequestProcessor + Sync + Clone + 'static> RocketmqDefaultClient<PR> {
 
     async fn create_client(&self, addr: &CheetahString, duration: Duration) -> Option<Client> {
         let mut connection_tables = self.connection_tables.lock().await;
-        let cw = connection_tables.get(addr);
-        if let Some(cw) = cw {
-            // if cw.lock().await.connection().ok {
+        let mut going_away_client = None;
+        if let Some(cw) = connection_tables.get(addr) {
             if cw.connection().ok {
-                return Some(cw.clone());
+                if !cw.is_going_away() || !self.try_reconnect_for_go_away(addr) {
+                    return Some(cw.clone());
+                }
+                info!("the channel to {} is going away, reconnect", addr);
+                going_away_client = Some(cw.clone());
             }
         }
 
-        let cw = connection_tables.get(addr.as_str());
-        if let Some(cw) = cw {
-            if cw.connection().ok {
-                // if cw.lock().await.connection().ok {
-                return Some(cw.clone());
+        let tls_connector = match &self.tls_connector {
+            Ok(tls_connector) => tls_connector.as_ref(),
+            Err(err) => {
+                error!(
+                    "getAndCreateClient connect to {} failed, invalid TLS config: {}",
+                    addr, err
+                );
+                return going_away_client;
             }
-        } else {
-            let _ = connection_tables.remove(addr.as_str());
-        }
-
+        };
         let addr_inner = addr.to_string();
 
         match time::timeout(duration, async {
-            Client::connect(addr_inner, self.processor.clone(), self.tx.as_ref()).await
+            Client::connect(
+                addr_inner.as_str(),
+                tls_connector,
+                self.socks_proxy_rules.find(addr_inner.as_str()),
+                self.body_compression,
+                self.rpc_hooks.clone(),
+                self.processor.clone(),
+                self.tx.as_ref(),
+            )
+            .await
         })
         .await
         {
@@ -192,20 +281,130 @@ impl<PR: RequestProcessor + Sync + Clone + 'static> RocketmqDefaultClient<PR> {
                     //let client = Arc::new(Mutex::new(client_r));
                     let client = client_r;
                     connection_tables.insert(addr.clone(), client.clone());
+                    if let Some(going_away_client) = going_away_client {
+                        tokio::spawn(close_after_drained(going_away_client));
+                    }
                     Some(client)
                 }
                 Err(_) => {
                     error!("getAndCreateClient connect to {} failed", addr);
-                    None
+                    going_away_client
                 }
             },
             Err(_) => {
                 error!("getAndCreateClient connect to {} failed", addr);
-                None
+                going_away_client
+            }
+        }
+    }
+
+    /// Whether the channel to `addr` may be replaced after GoAway, a channel is replaced at most
+    /// once per `max_reconnect_interval_time_seconds`.
+    fn try_reconnect_for_go_away(&self, addr: &CheetahString) -> bool {
+        if !self.tokio_client_config.enable_reconnect_for_go_away {
+            return false;
+        }
+        let now = get_current_millis();
+        let interval_millis = self
+            .tokio_client_config
+            .max_reconnect_interval_time_seconds
+            .max(0) as u64
+            * 1000;
+        let mut last_reconnect_timestamps = self.last_reconnect_timestamps.lock();
+        match last_reconnect_timestamps.get(addr) {
+            Some(last) if now.saturating_sub(*last) < interval_millis => false,
+            _ => {
+                last_reconnect_timestamps.insert(addr.clone(), now);
+                true
+            }
+        }
+    }
+
+    async fn send_request(
+        &self,
+        mut client: Client,
+        request: RemotingCommand,
+        timeout_millis: u64,
+    ) -> Result<RemotingCommand> {
+        match self
+            .client_runtime
+            .as_ref()
+            .unwrap()
+            .get_handle()
+            .spawn(async move { client.send_read(request, timeout_millis).await })
+            .await
+        {
+            Ok(result) => result,
+            Err(err) => Err(RemotingError::RemoteError(err.to_string())),
+        }
+    }
+
+    /// Sends `request` on `client`, a channel whose request timed out is closed when
+    /// `client_close_socket_if_timeout` is set.
+    async fn send_request_or_close(
+        &self,
+        client: Client,
+        request: RemotingCommand,
+        timeout_millis: u64,
+    ) -> Result<RemotingCommand> {
+        let addr = CheetahString::from(client.addr());
+        let result = self.send_request(client, request, timeout_millis).await;
+        if matches!(result, Err(RemotingError::RemotingTimeoutError(_, _)))
+            && self.tokio_client_config.client_close_socket_if_timeout
+            && timeout_millis >= MIN_CLOSE_TIMEOUT_MILLIS
+        {
+            warn!("close the channel to {} after a request timed out", addr);
+            self.close_clients(vec![addr]).await;
+        }
+        result
+    }
+
+    /// Fails the requests left in the response tables of the channels past their timeout.
+    async fn scan_response_table(&self) {
+        let connection_tables = self.connection_tables.lock().await;
+        for (addr, client) in connection_tables.iter() {
+            let expired = client.scan_response_table();
+            if expired > 0 {
+                warn!(
+                    "removed {} timeout requests of the channel to {}",
+                    expired, addr
+                );
             }
         }
     }
 
+    /// Number of requests that timed out on each open channel, by address.
+    pub async fn timed_out_requests(&self) -> HashMap<CheetahString, u64> {
+        self.connection_tables
+            .lock()
+            .await
+            .iter()
+            .map(|(addr, client)| (addr.clone(), client.timed_out_requests()))
+            .collect()
+    }
+
+    fn do_before_rpc_hooks(
+        &self,
+        remote_address: SocketAddr,
+        request: &mut RemotingCommand,
+    ) -> Result<()> {
+        for hook in self.rpc_hooks.iter() {
+            hook.do_before_request(remote_address, request)?;
+        }
+        Ok(())
+    }
+
+    fn do_after_rpc_hooks(
+        &self,
+        remote_address: SocketAddr,
+        response: &mut RemotingCommand,
+    ) -> Result<()> {
+        for hook in self.rpc_hooks.iter() {
+            hook.do_after_response(remote_address, response)?;
+        }
+        Ok(())
+    }
+
     async fn scan_available_name_srv(&self) {
         if self.namesrv_addr_list.as_ref().is_empty() {
             debug!("scanAvailableNameSrv addresses of name remoting_server is null!");
@@ -242,6 +441,7 @@ impl<PR: RequestProcessor + Sync + Clone + 'static> RemotingService for Rocketmq
     async fn start(&self, this: WeakArcMut<Self>) {
         if let Some(client) = this.upgrade() {
             let connect_timeout_millis = self.tokio_client_config.connect_timeout_millis as u64;
+            let scanner = client.clone();
             self.client_runtime
                 .as_ref()
                 .unwrap()
@@ -252,6 +452,17 @@ impl<PR: RequestProcessor + Sync + Clone + 'static> RemotingService for Rocketmq
                         time::sleep(Duration::from_millis(connect_timeout_millis)).await;
                     }
                 });
+            self.client_runtime
+                .as_ref()
+                .unwrap()
+                .get_handle()
+                .spawn(async move {
+                    time::sleep(SCAN_RESPONSE_TABLE_DELAY).await;
+                    loop {
+                        scanner.scan_response_table().await;
+                        time::sleep(SCAN_RESPONSE_TABLE_INTERVAL).await;
+                    }
+                });
         }
     }
 
@@ -272,16 +483,18 @@ impl<PR: RequestProcessor + Sync + Clone + 'static> RemotingService for Rocketmq
     }
 
     fn register_rpc_hook(&mut self, hook: Arc<Box<dyn RPCHook>>) {
-        todo!()
+        Arc::make_mut(&mut self.rpc_hooks).push(hook);
     }
 
     fn clear_rpc_hook(&mut self) {
-        todo!()
+        self.rpc_hooks = Default::default();
     }
 }
 
 #[allow(unused_variables)]
 impl<PR: RequestProcessor + Sync + Clone + 'static> RemotingClient for RocketmqDefaultClient<PR> {
+    type Processor = PR;
+
     async fn update_name_server_address_list(&self, addrs: Vec<CheetahString>) {
         let old = self.namesrv_addr_list.mut_from_ref();
         let mut update = false;
@@ -351,29 +564,37 @@ impl<PR: RequestProcessor + Sync + Clone + 'static> RemotingClient for RocketmqD
         let client = self.get_and_create_client(addr).await;
         match client {
             None => Err(RemotingError::RemoteError("get client failed".to_string())),
-            Some(mut client) => {
-                match self
-                    .client_runtime
-                    .as_ref()
-                    .unwrap()
-                    .get_handle()
-                    .spawn(async move {
-                        time::timeout(Duration::from_millis(timeout_millis), async move {
-                            client.send_read(request, timeout_millis).await
-                        })
+            Some(client) => {
+                let remote_address = client.remote_address();
+                let mut request = request;
+                self.do_before_rpc_hooks(remote_address, &mut request)?;
+                let retry_request = (self.tokio_client_config.enable_reconnect_for_go_away
+                    && self.tokio_client_config.enable_transparent_retry)
+                    .then(|| request.clone());
+                let mut response = self
+                    .send_request_or_close(client, request, timeout_millis)
+                    .await?;
+                // GoAway only answers requests the server did not process, retrying them on a new
+                // channel is safe.
+                let go_away = response.code() == ResponseCode::GoAway as i32;
+                if let Some(retry_request) = retry_request.filter(|_| go_away) {
+                    if let Some(client) = self
+                        .get_and_create_client(addr)
                         .await
-                    })
-                    .await
-                {
-                    Ok(result) => match result {
-                        Ok(response) => match response {
-                            Ok(value) => Ok(value),
-                            Err(e) => Err(RemotingError::RemoteError(e.to_string())),
-                        },
-                        Err(err) => Err(RemotingError::RemoteError(err.to_string())),
-                    },
-                    Err(err) => Err(RemotingError::RemoteError(err.to_string())),
+                        .filter(|client| !client.is_going_away())
+                    {
+                        info!(
+                            "retry request[code={}] on the new channel to {}",
+                            retry_request.code(),
+                            client.addr()
+                        );
+                        response = self
+                            .send_request_or_close(client, retry_request, timeout_millis)
+                            .await?;
+                    }
                 }
+                self.do_after_rpc_hooks(remote_address, &mut response)?;
+                Ok(response)
             }
         }
     }
@@ -390,6 +611,11 @@ impl<PR: RequestProcessor + Sync + Clone + 'static> RemotingClient for RocketmqD
                 error!("get client failed");
             }
             Some(mut client) => {
+                let mut request = request;
+                if let Err(err) = self.do_before_rpc_hooks(client.remote_address(), &mut request) {
+                    error!("invoke oneway to {} rejected by rpc hook: {}", addr, err);
+                    return;
+                }
                 self.client_runtime
                     .as_ref()
                     .unwrap()
@@ -410,16 +636,49 @@ impl<PR: RequestProcessor + Sync + Clone + 'static> RemotingClient for RocketmqD
         }
     }
 
-    fn is_address_reachable(&mut self, addr: &CheetahString) {
-        todo!()
+    async fn is_address_reachable(&self, addr: &CheetahString) -> bool {
+        if addr.is_empty() {
+            return false;
+        }
+        match self.get_and_create_client(Some(addr)).await {
+            Some(client) => client.connection().ok,
+            None => {
+                warn!("address {} is not reachable", addr);
+                false
+            }
+        }
+    }
+
+    async fn close_clients(&self, addrs: Vec<CheetahString>) {
+        let mut connection_tables = self.connection_tables.lock().await;
+        for addr in addrs {
+            if let Some(mut client) = connection_tables.remove(&addr) {
+                match client.close().await {
+                    Ok(_) => info!("close the channel to {}", addr),
+                    Err(err) => warn!("close the channel to {} failed: {}", addr, err),
+                }
+            }
+        }
     }
 
-    fn close_clients(&mut self, addrs: Vec<String>) {
-        todo!()
+    fn register_processor(&mut self, processor: PR) {
+        self.processor = processor;
     }
+}
 
-    fn register_processor(&mut self, processor: impl RequestProcessor + Sync) {
-        todo!()
+/// Closes a channel replaced after GoAway once its in-flight requests are answered.
+async fn close_after_drained(mut client: Client) {
+    let deadline = time::Instant::now() + GO_AWAY_DRAIN_TIMEOUT;
+    while client.in_flight_requests() > 0 && time::Instant::now() < deadline {
+        time::sleep(Duration::from_millis(100)).await;
+    }
+    match client.close().await {
+        Ok(_) => info!("close the drained channel to {}", client.addr()),
+        Err(err) => warn!(
+            "close the drained channel to {} failed: {}",
+            client.addr(),
+            err
+        ),
     }
 }
 
@@ -427,3 +686,322 @@ fn init_value_index() -> i32 {
     let mut rng = rand::thread_rng();
     rng.gen_range(0..999)
 }
+
+#[cfg(test)]
+mod tests {
+    use rocketmq_common::common::server::tls_config::TlsConfig;
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::connection::Connection;
+
+    /// Serves one connection per code, each connection answers every request with its code.
+    async fn serve(codes: Vec<ResponseCode>) -> CheetahString {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            for code in codes {
+                let (stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let mut connection = Connection::new(stream);
+                    while let Some(Ok(request)) = connection.receive_command().await {
+                        let response = RemotingCommand::create_response_command_with_code(code)
+                            .set_opaque(request.opaque());
+                        if connection.send_command(response).await.is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+        addr.into()
+    }
+
+    /// Rejects the requests the server sends to the client, tags the requests sent to servers.
+    struct RejectingHook;
+
+    impl RPCHook for RejectingHook {
+        fn do_before_request(
+            &self,
+            _remote_addr: SocketAddr,
+            request: &mut RemotingCommand,
+        ) -> Result<()> {
+            if request.code() == 20 {
+                return Err(RemotingError::AbortProcessError(
+                    ResponseCode::NoPermission as i32,
+                    "rejected by hook".to_string(),
+                ));
+            }
+            request.add_ext_field("hooked", "true");
+            Ok(())
+        }
+
+        fn do_after_response(
+            &self,
+            _remote_addr: SocketAddr,
+            _response: &mut RemotingCommand,
+        ) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn reachability_and_close_clients() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let client = RocketmqDefaultClient::new(
+            Arc::new(TokioClientConfig::default()),
+            DefaultRemotingRequestProcessor,
+        );
+        runtime.block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = CheetahString::from(listener.local_addr().unwrap().to_string());
+            let server = tokio::spawn(async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut connection = Connection::new(stream);
+                // returns once the client closed the channel
+                connection.receive_command().await.is_none()
+            });
+            assert!(client.is_address_reachable(&addr).await);
+            assert!(!client.is_address_reachable(&CheetahString::new()).await);
+
+            client.close_clients(vec![addr.clone()]).await;
+            assert!(client.connection_tables.lock().await.is_empty());
+            assert!(time::timeout(Duration::from_secs(3), server)
+                .await
+                .unwrap()
+                .unwrap());
+        });
+    }
+
+    #[test]
+    fn rpc_hooks_run_around_server_requests() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mut client = RocketmqDefaultClient::new(
+            Arc::new(TokioClientConfig::default()),
+            DefaultRemotingRequestProcessor,
+        );
+        client.register_rpc_hook(Arc::new(Box::new(RejectingHook)));
+        runtime.block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = CheetahString::from(listener.local_addr().unwrap().to_string());
+            let server = tokio::spawn(async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut connection = Connection::new(stream);
+                let request = connection.receive_command().await.unwrap().unwrap();
+                let hooked = request
+                    .ext_fields()
+                    .and_then(|fields| fields.get("hooked").cloned());
+                connection
+                    .send_command(
+                        RemotingCommand::create_response_command().set_opaque(request.opaque()),
+                    )
+                    .await
+                    .unwrap();
+                connection
+                    .send_command(RemotingCommand::create_remoting_command(20).set_opaque(99))
+                    .await
+                    .unwrap();
+                let response = connection.receive_command().await.unwrap().unwrap();
+                (hooked, response)
+            });
+            client
+                .invoke_async(
+                    Some(&addr),
+                    RemotingCommand::create_remoting_command(10),
+                    3000,
+                )
+                .await
+                .unwrap();
+            let (hooked, response) = server.await.unwrap();
+            assert_eq!(hooked.unwrap().as_str(), "true");
+            assert_eq!(response.opaque(), 99);
+            assert_eq!(response.code(), ResponseCode::NoPermission as i32);
+            assert_eq!(response.remark().unwrap().as_str(), "rejected by hook");
+        });
+    }
+
+    #[test]
+    fn invalid_tls_config_fails_requests_instead_of_panicking() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let client = RocketmqDefaultClient::new(
+            Arc::new(TokioClientConfig {
+                use_tls: true,
+                tls_config: TlsConfig {
+                    test_mode_enable: false,
+                    client_trust_cert_path: Some("/nonexistent/ca.pem".to_string()),
+                    ..TlsConfig::default()
+                },
+                ..TokioClientConfig::default()
+            }),
+            DefaultRemotingRequestProcessor,
+        );
+        assert!(matches!(
+            client.check_tls_config(),
+            Err(RemotingError::TlsError(_))
+        ));
+        runtime.block_on(async {
+            let addr = serve(vec![ResponseCode::Success]).await;
+            // the client never falls back to plaintext
+            assert!(client
+                .invoke_async(
+                    Some(&addr),
+                    RemotingCommand::create_remoting_command(10),
+                    3000,
+                )
+                .await
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn retries_request_on_new_channel_after_go_away() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let client = RocketmqDefaultClient::new(
+            Arc::new(TokioClientConfig::default()),
+            DefaultRemotingRequestProcessor,
+        );
+        runtime.block_on(async {
+            let addr = serve(vec![ResponseCode::GoAway, ResponseCode::Success]).await;
+            let response = client
+                .invoke_async(
+                    Some(&addr),
+                    RemotingCommand::create_remoting_command(10),
+                    3000,
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.code(), ResponseCode::Success as i32);
+        });
+    }
+
+    #[test]
+    fn reconnects_after_go_away_without_retry() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let client = RocketmqDefaultClient::new(
+            Arc::new(TokioClientConfig {
+                enable_transparent_retry: false,
+                ..Default::default()
+            }),
+            DefaultRemotingRequestProcessor,
+        );
+        runtime.block_on(async {
+            let addr = serve(vec![ResponseCode::GoAway, ResponseCode::Success]).await;
+            let response = client
+                .invoke_async(
+                    Some(&addr),
+                    RemotingCommand::create_remoting_command(10),
+                    3000,
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.code(), ResponseCode::GoAway as i32);
+            let response = client
+                .invoke_async(
+                    Some(&addr),
+                    RemotingCommand::create_remoting_command(10),
+                    3000,
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.code(), ResponseCode::Success as i32);
+        });
+    }
+    /// Accepts one connection and reads the requests without answering them, the connection is
+    /// dropped after the first request when `hang_up` is set.
+    async fn serve_silently(hang_up: bool) -> CheetahString {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut connection = Connection::new(stream);
+            while let Some(Ok(_)) = connection.receive_command().await {
+                if hang_up {
+                    return;
+                }
+            }
+        });
+        addr.into()
+    }
+
+    #[test]
+    fn counts_timed_out_requests() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let client = RocketmqDefaultClient::new(
+            Arc::new(TokioClientConfig {
+                client_close_socket_if_timeout: false,
+                ..Default::default()
+            }),
+            DefaultRemotingRequestProcessor,
+        );
+        runtime.block_on(async {
+            let addr = serve_silently(false).await;
+            let result = client
+                .invoke_async(
+                    Some(&addr),
+                    RemotingCommand::create_remoting_command(10),
+                    200,
+                )
+                .await;
+            assert!(matches!(
+                result,
+                Err(RemotingError::RemotingTimeoutError(_, 200))
+            ));
+            assert_eq!(client.timed_out_requests().await.get(&addr), Some(&1));
+            let connection_tables = client.connection_tables.lock().await;
+            assert_eq!(
+                connection_tables.get(&addr).unwrap().in_flight_requests(),
+                0
+            );
+        });
+    }
+
+    #[test]
+    fn closes_channel_after_timeout() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let client = RocketmqDefaultClient::new(
+            Arc::new(TokioClientConfig {
+                client_close_socket_if_timeout: true,
+                ..Default::default()
+            }),
+            DefaultRemotingRequestProcessor,
+        );
+        runtime.block_on(async {
+            let addr = serve_silently(false).await;
+            let result = client
+                .invoke_async(
+                    Some(&addr),
+                    RemotingCommand::create_remoting_command(10),
+                    200,
+                )
+                .await;
+            assert!(matches!(
+                result,
+                Err(RemotingError::RemotingTimeoutError(_, 200))
+            ));
+            assert!(client.connection_tables.lock().await.is_empty());
+        });
+    }
+
+    #[test]
+    fn fails_in_flight_requests_when_channel_closes() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let client = RocketmqDefaultClient::new(
+            Arc::new(TokioClientConfig::default()),
+            DefaultRemotingRequestProcessor,
+        );
+        runtime.block_on(async {
+            let addr = serve_silently(true).await;
+            let result = time::timeout(
+                Duration::from_secs(1),
+                client.invoke_async(
+                    Some(&addr),
+                    RemotingCommand::create_remoting_command(10),
+                    10_000,
+                ),
+            )
+            .await
+            .unwrap();
+            assert!(matches!(result, Err(RemotingError::ConnectionInvalid(_))));
+        });
+    }
+}
@@ -148,6 +148,7 @@ pub enum RequestCode {
     RemoveColdDataFlowCtrConfig = 2002,
     GetColdDataFlowCtrInfo = 2003,
     SetCommitlogReadMode = 2004,
+    QueryMessageByCondition = 2005,
     Unknown = -9999999,
 }
 
@@ -290,6 +291,7 @@ impl From<i32> for RequestCode {
             2002 => RequestCode::RemoveColdDataFlowCtrConfig,
             2003 => RequestCode::GetColdDataFlowCtrInfo,
             2004 => RequestCode::SetCommitlogReadMode,
+            2005 => RequestCode::QueryMessageByCondition,
             _ => RequestCode::Unknown,
         }
     }
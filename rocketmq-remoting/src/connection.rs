@@ -16,6 +16,15 @@
  */
 use std::hash::Hash;
 use std::hash::Hasher;
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
 
 use bytes::BufMut;
 use bytes::BytesMut;
@@ -23,13 +32,239 @@ use futures_util::stream::SplitSink;
 use futures_util::stream::SplitStream;
 use futures_util::SinkExt;
 use futures_util::StreamExt;
+use rocketmq_common::common::compression::compression_type::CompressionType;
+use rocketmq_common::TimeUtils::get_current_millis;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::io::ReadBuf;
 use tokio::net::TcpStream;
+use tokio::sync::Notify;
+use tokio_rustls::TlsStream;
 use tokio_util::codec::Framed;
+use tracing::warn;
 
 use crate::codec::remoting_command_codec::CompositeCodec;
+use crate::net::transport::TransportStream;
 use crate::protocol::remoting_command::RemotingCommand;
+use crate::protocol::LanguageCode;
 use crate::remoting_error::RemotingError;
 
+/// The byte stream underneath a `Connection`, either a plain TCP stream, a TLS session on top
+/// of it or the stream of another transport, such as a Unix domain socket.
+pub enum ConnectionStream {
+    Tcp(TcpStream),
+    Tls(Box<TlsStream<TcpStream>>),
+    Transport(Box<dyn TransportStream>),
+}
+
+impl ConnectionStream {
+    #[inline]
+    pub fn is_tls(&self) -> bool {
+        matches!(self, ConnectionStream::Tls(_))
+    }
+}
+
+impl From<TcpStream> for ConnectionStream {
+    fn from(stream: TcpStream) -> Self {
+        ConnectionStream::Tcp(stream)
+    }
+}
+
+impl From<TlsStream<TcpStream>> for ConnectionStream {
+    fn from(stream: TlsStream<TcpStream>) -> Self {
+        ConnectionStream::Tls(Box::new(stream))
+    }
+}
+
+impl From<Box<dyn TransportStream>> for ConnectionStream {
+    fn from(stream: Box<dyn TransportStream>) -> Self {
+        ConnectionStream::Transport(stream)
+    }
+}
+
+impl AsyncRead for ConnectionStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            ConnectionStream::Tcp(stream) => Pin::new(stream).poll_read(cx, buf),
+            ConnectionStream::Transport(stream) => Pin::new(stream.as_mut()).poll_read(cx, buf),
+            ConnectionStream::Tls(stream) => Pin::new(stream.as_mut()).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for ConnectionStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            ConnectionStream::Tcp(stream) => Pin::new(stream).poll_write(cx, buf),
+            ConnectionStream::Transport(stream) => Pin::new(stream.as_mut()).poll_write(cx, buf),
+            ConnectionStream::Tls(stream) => Pin::new(stream.as_mut()).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            ConnectionStream::Tcp(stream) => Pin::new(stream).poll_flush(cx),
+            ConnectionStream::Transport(stream) => Pin::new(stream.as_mut()).poll_flush(cx),
+            ConnectionStream::Tls(stream) => Pin::new(stream.as_mut()).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            ConnectionStream::Tcp(stream) => Pin::new(stream).poll_shutdown(cx),
+            ConnectionStream::Transport(stream) => Pin::new(stream.as_mut()).poll_shutdown(cx),
+            ConnectionStream::Tls(stream) => Pin::new(stream.as_mut()).poll_shutdown(cx),
+        }
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            ConnectionStream::Tcp(stream) => Pin::new(stream).poll_write_vectored(cx, bufs),
+            ConnectionStream::Transport(stream) => {
+                Pin::new(stream.as_mut()).poll_write_vectored(cx, bufs)
+            }
+            ConnectionStream::Tls(stream) => {
+                Pin::new(stream.as_mut()).poll_write_vectored(cx, bufs)
+            }
+        }
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        match self {
+            ConnectionStream::Tcp(stream) => stream.is_write_vectored(),
+            ConnectionStream::Tls(stream) => stream.is_write_vectored(),
+            ConnectionStream::Transport(stream) => stream.is_write_vectored(),
+        }
+    }
+}
+
+/// Tracks the bytes being written to a connection, so that readers can pause while the peer does
+/// not drain its socket.
+///
+/// The connection turns unwritable once the pending bytes exceed the high water mark and turns
+/// writable again once they drop to the low water mark. A high water mark of `0` disables the
+/// check.
+#[derive(Debug)]
+pub struct WriteBuffer {
+    high_water_mark: usize,
+    low_water_mark: usize,
+    pending_bytes: AtomicUsize,
+    writable: AtomicBool,
+    writable_notify: Notify,
+    last_write_timestamp: AtomicU64,
+}
+
+impl WriteBuffer {
+    pub fn new(low_water_mark: usize, high_water_mark: usize) -> Self {
+        Self {
+            high_water_mark,
+            low_water_mark: low_water_mark.min(high_water_mark),
+            pending_bytes: AtomicUsize::new(0),
+            writable: AtomicBool::new(true),
+            writable_notify: Notify::new(),
+            last_write_timestamp: AtomicU64::new(get_current_millis()),
+        }
+    }
+
+    #[inline]
+    pub fn pending_bytes(&self) -> usize {
+        self.pending_bytes.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    pub fn is_writable(&self) -> bool {
+        self.writable.load(Ordering::Acquire)
+    }
+
+    /// The time of the last completed write, in milliseconds.
+    #[inline]
+    pub fn last_write_timestamp(&self) -> u64 {
+        self.last_write_timestamp.load(Ordering::Acquire)
+    }
+
+    /// Waits until the connection turns writable.
+    pub async fn wait_writable(&self) {
+        loop {
+            let notified = self.writable_notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if self.is_writable() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    fn begin_write(&self, bytes: usize) {
+        let pending = self.pending_bytes.fetch_add(bytes, Ordering::AcqRel) + bytes;
+        if self.high_water_mark > 0 && pending > self.high_water_mark {
+            self.writable.store(false, Ordering::Release);
+        }
+    }
+
+    fn end_write(&self, bytes: usize) {
+        let pending = self.pending_bytes.fetch_sub(bytes, Ordering::AcqRel) - bytes;
+        self.last_write_timestamp
+            .store(get_current_millis(), Ordering::Release);
+        if pending <= self.low_water_mark && !self.is_writable() {
+            self.writable.store(true, Ordering::Release);
+            self.writable_notify.notify_waiters();
+        }
+    }
+}
+
+impl Default for WriteBuffer {
+    fn default() -> Self {
+        WriteBuffer::new(0, 0)
+    }
+}
+
+/// Compression of the bodies sent over a connection.
+///
+/// Every command sent advertises that compressed bodies are accepted, bodies are only compressed
+/// once the peer has advertised the same, so peers without the feature keep receiving plain
+/// bodies.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BodyCompression {
+    /// Bodies at least this large are compressed.
+    pub threshold: usize,
+    pub compression_type: CompressionType,
+}
+
+impl BodyCompression {
+    /// Returns `None` when the threshold is `0` or the compression type is not supported.
+    pub fn new(threshold: usize, compression_type: &str) -> Option<Self> {
+        if threshold == 0 {
+            return None;
+        }
+        match CompressionType::try_of(compression_type) {
+            Some(compression_type) => Some(Self {
+                threshold,
+                compression_type,
+            }),
+            None => {
+                warn!(
+                    "Unsupported body compress type: {}, body compression is disabled",
+                    compression_type
+                );
+                None
+            }
+        }
+    }
+}
+
 /// Send and receive `Frame` values from a remote peer.
 ///
 /// When implementing networking protocols, a message on that protocol is
@@ -46,8 +281,8 @@ pub struct Connection {
     /// The `Framed` instance used for reading from and writing to the TCP stream.
     /// It leverages the `RemotingCommandCodec` for encoding and decoding frames.
     //pub(crate) framed: Framed<TcpStream, RemotingCommandCodec>,
-    writer: SplitSink<Framed<TcpStream, CompositeCodec>, BytesMut>,
-    reader: SplitStream<Framed<TcpStream, CompositeCodec>>,
+    writer: SplitSink<Framed<ConnectionStream, CompositeCodec>, BytesMut>,
+    reader: SplitStream<Framed<ConnectionStream, CompositeCodec>>,
 
     /// A boolean flag indicating the current state of the connection.
     /// `true` means the connection is in a good state, while `false` indicates
@@ -55,6 +290,13 @@ pub struct Connection {
     pub(crate) ok: bool,
 
     buf: BytesMut,
+
+    write_buffer: Arc<WriteBuffer>,
+
+    body_compression: Option<BodyCompression>,
+
+    /// Whether the peer advertised that it accepts compressed bodies.
+    peer_accepts_compressed_body: bool,
 }
 
 impl Hash for Connection {
@@ -64,10 +306,10 @@ impl Hash for Connection {
 
         // Use the addr: *const _ess of writer and reader to hash them (they serve as a unique
         // identifier for these components)
-        let writer_addr: *const SplitSink<Framed<TcpStream, CompositeCodec>, BytesMut> =
-            &self.writer as *const SplitSink<Framed<TcpStream, CompositeCodec>, BytesMut>;
-        let reader_addr: *const SplitStream<Framed<TcpStream, CompositeCodec>> =
-            &self.reader as *const SplitStream<Framed<TcpStream, CompositeCodec>>;
+        let writer_addr: *const SplitSink<Framed<ConnectionStream, CompositeCodec>, BytesMut> =
+            &self.writer as *const SplitSink<Framed<ConnectionStream, CompositeCodec>, BytesMut>;
+        let reader_addr: *const SplitStream<Framed<ConnectionStream, CompositeCodec>> =
+            &self.reader as *const SplitStream<Framed<ConnectionStream, CompositeCodec>>;
 
         writer_addr.hash(state);
         reader_addr.hash(state);
@@ -92,29 +334,57 @@ impl Connection {
     ///
     /// # Arguments
     ///
-    /// * `tcp_stream` - The `TcpStream` associated with the connection.
+    /// * `stream` - The stream associated with the connection, a `TcpStream` or a TLS session.
     ///
     /// # Returns
     ///
     /// A new `Connection` instance.
-    pub fn new(tcp_stream: TcpStream) -> Connection {
-        let framed = Framed::with_capacity(tcp_stream, CompositeCodec::new(), 1024 * 4);
+    pub fn new(stream: impl Into<ConnectionStream>) -> Connection {
+        let framed = Framed::with_capacity(stream.into(), CompositeCodec::new(), 1024 * 4);
         let (writer, reader) = framed.split();
         Self {
             writer,
             reader,
             ok: true,
             buf: BytesMut::with_capacity(4096),
+            write_buffer: Arc::new(WriteBuffer::default()),
+            body_compression: None,
+            peer_accepts_compressed_body: false,
         }
     }
 
+    /// Sets the compression of the bodies sent to a peer accepting them, see
+    /// [`BodyCompression`].
+    pub fn with_body_compression(
+        mut self,
+        body_compression: Option<BodyCompression>,
+    ) -> Connection {
+        self.body_compression = body_compression;
+        self
+    }
+
+    /// Sets the water marks used for write buffer flow control, see [`WriteBuffer`].
+    pub fn with_write_buffer_water_mark(
+        mut self,
+        low_water_mark: usize,
+        high_water_mark: usize,
+    ) -> Connection {
+        self.write_buffer = Arc::new(WriteBuffer::new(low_water_mark, high_water_mark));
+        self
+    }
+
     #[inline]
-    pub fn reader(&self) -> &SplitStream<Framed<TcpStream, CompositeCodec>> {
+    pub fn write_buffer(&self) -> Arc<WriteBuffer> {
+        self.write_buffer.clone()
+    }
+
+    #[inline]
+    pub fn reader(&self) -> &SplitStream<Framed<ConnectionStream, CompositeCodec>> {
         &self.reader
     }
 
     #[inline]
-    pub fn writer(&self) -> &SplitSink<Framed<TcpStream, CompositeCodec>, BytesMut> {
+    pub fn writer(&self) -> &SplitSink<Framed<ConnectionStream, CompositeCodec>, BytesMut> {
         &self.writer
     }
 
@@ -124,7 +394,20 @@ impl Connection {
     ///
     /// A result containing the received command or an error.
     pub async fn receive_command(&mut self) -> Option<Result<RemotingCommand, RemotingError>> {
-        self.reader.next().await
+        let command = self.reader.next().await;
+        if let Some(Ok(command)) = &command {
+            // only the peers of this implementation know the flag
+            if command.language() == LanguageCode::RUST && command.accepts_compressed_body() {
+                self.peer_accepts_compressed_body = true;
+            }
+        }
+        command
+    }
+
+    /// Closes the write half of the connection, the peer closes the connection in turn.
+    pub async fn close(&mut self) -> Result<(), RemotingError> {
+        self.ok = false;
+        self.writer.close().await
     }
 
     /// Sends a `RemotingCommand` over the connection.
@@ -141,11 +424,127 @@ impl Connection {
         mut command: RemotingCommand,
     ) -> Result<(), RemotingError> {
         self.buf.clear();
+        command.mark_accept_compressed_body_ref();
+        if let Some(body_compression) = self.body_compression {
+            if self.peer_accepts_compressed_body {
+                command.compress_body(
+                    body_compression.threshold,
+                    body_compression.compression_type,
+                );
+            }
+        }
         command.fast_header_encode(&mut self.buf);
         if let Some(body_inner) = command.take_body() {
             self.buf.put(body_inner);
         }
-        self.writer.send(self.buf.clone()).await?;
+        let bytes = self.buf.len();
+        self.write_buffer.begin_write(bytes);
+        let result = self.writer.send(self.buf.clone()).await;
+        self.write_buffer.end_write(bytes);
+        result?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
+
+    use super::*;
+
+    #[test]
+    fn write_buffer_without_water_mark_is_always_writable() {
+        let write_buffer = WriteBuffer::default();
+        write_buffer.begin_write(1024 * 1024);
+        assert!(write_buffer.is_writable());
+        write_buffer.end_write(1024 * 1024);
+        assert_eq!(write_buffer.pending_bytes(), 0);
+    }
+
+    #[test]
+    fn write_buffer_turns_unwritable_above_high_water_mark() {
+        let write_buffer = WriteBuffer::new(100, 200);
+        write_buffer.begin_write(150);
+        assert!(write_buffer.is_writable());
+        write_buffer.begin_write(100);
+        assert!(!write_buffer.is_writable());
+        write_buffer.end_write(100);
+        // 150 pending bytes are still above the low water mark
+        assert!(!write_buffer.is_writable());
+        write_buffer.end_write(150);
+        assert!(write_buffer.is_writable());
+    }
+
+    #[tokio::test]
+    async fn wait_writable_wakes_up_below_low_water_mark() {
+        let write_buffer = Arc::new(WriteBuffer::new(0, 10));
+        write_buffer.begin_write(20);
+        let waiter = tokio::spawn({
+            let write_buffer = write_buffer.clone();
+            async move { write_buffer.wait_writable().await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+        write_buffer.end_write(20);
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    /// Reads one raw frame sent by a `Connection`.
+    async fn read_frame(stream: &mut TcpStream) -> BytesMut {
+        let total_size = stream.read_i32().await.unwrap();
+        let mut frame = BytesMut::zeroed(total_size as usize + 4);
+        frame[..4].copy_from_slice(&total_size.to_be_bytes());
+        stream.read_exact(&mut frame[4..]).await.unwrap();
+        frame
+    }
+
+    #[tokio::test]
+    async fn compresses_bodies_once_the_peer_accepts_them() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut peer = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let mut connection = Connection::new(listener.accept().await.unwrap().0)
+            .with_body_compression(BodyCompression::new(1024, "LZ4"));
+        let body = bytes::Bytes::from("consume-stats ".repeat(1024));
+
+        connection
+            .send_command(RemotingCommand::new_request(1, body.clone()))
+            .await
+            .unwrap();
+        let plain = read_frame(&mut peer).await;
+        assert!(plain.len() > body.len());
+
+        let mut request = RemotingCommand::new_request(2, bytes::Bytes::new());
+        request.mark_accept_compressed_body_ref();
+        let mut buf = BytesMut::new();
+        request.fast_header_encode(&mut buf);
+        peer.write_all(&buf).await.unwrap();
+        connection.receive_command().await.unwrap().unwrap();
+
+        connection
+            .send_command(RemotingCommand::new_request(1, body.clone()))
+            .await
+            .unwrap();
+        let mut compressed = read_frame(&mut peer).await;
+        assert!(compressed.len() < body.len());
+        let decoded = RemotingCommand::decode(&mut compressed).unwrap().unwrap();
+        assert_eq!(decoded.body().as_ref().unwrap(), &body);
+    }
+
+    #[test]
+    fn body_compression_requires_threshold_and_known_type() {
+        assert!(BodyCompression::new(0, "LZ4").is_none());
+        assert!(BodyCompression::new(1024, "SNAPPY").is_none());
+        assert_eq!(
+            BodyCompression::new(1024, "zstd").unwrap().compression_type,
+            CompressionType::Zstd
+        );
+    }
+}
@@ -16,3 +16,6 @@
  */
 
 pub mod channel;
+pub mod socks5;
+pub mod tls;
+pub mod transport;
@@ -14,7 +14,6 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
-use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fmt::Display;
 use std::hash::Hash;
@@ -29,7 +28,9 @@ use tokio::time::timeout;
 use tracing::error;
 use uuid::Uuid;
 
+use crate::base::response_future::fail_response_table;
 use crate::base::response_future::ResponseFuture;
+use crate::base::response_future::ResponseTable;
 use crate::connection::Connection;
 use crate::protocol::remoting_command::RemotingCommand;
 use crate::remoting_error::RemotingError;
@@ -44,7 +45,7 @@ pub struct Channel {
     channel_id: String,
     tx: tokio::sync::mpsc::Sender<ChannelMessage>,
     pub(crate) connection: ArcMut<Connection>,
-    pub(crate) response_table: ArcMut<HashMap<i32, ResponseFuture>>,
+    pub(crate) response_table: ResponseTable,
 }
 
 type ChannelMessage = (
@@ -56,12 +57,13 @@ type ChannelMessage = (
 pub(crate) async fn run_send(
     mut connection: ArcMut<Connection>,
     mut rx: Receiver<ChannelMessage>,
-    mut response_table: ArcMut<HashMap<i32, ResponseFuture>>,
+    response_table: ResponseTable,
+    remote_address: SocketAddr,
 ) {
     while let Some((request, tx, timeout_millis)) = rx.recv().await {
         let opaque = request.opaque();
         if let Some(tx) = tx {
-            response_table.insert(
+            response_table.lock().insert(
                 opaque,
                 ResponseFuture::new(opaque, timeout_millis.unwrap_or(0), true, tx),
             );
@@ -71,12 +73,14 @@ pub(crate) async fn run_send(
             Err(error) => match error {
                 Io(error) => {
                     error!("send request failed: {}", error);
-                    response_table.remove(&opaque);
                     connection.ok = false;
+                    // nothing is written to a broken channel anymore, fail the other requests
+                    // instead of leaving them to their timeout
+                    fail_response_table(&mut response_table.lock(), &remote_address.to_string());
                     return;
                 }
                 _ => {
-                    response_table.remove(&opaque);
+                    response_table.lock().remove(&opaque);
                 }
             },
         };
@@ -89,10 +93,7 @@ impl PartialEq for Channel {
             && self.remote_address == other.remote_address
             && self.channel_id == other.channel_id
             && Arc::ptr_eq(self.connection.get_inner(), other.connection.get_inner())
-            && Arc::ptr_eq(
-                self.response_table.get_inner(),
-                other.response_table.get_inner(),
-            )
+            && Arc::ptr_eq(&self.response_table, &other.response_table)
     }
 }
 
@@ -104,7 +105,7 @@ impl Hash for Channel {
         self.remote_address.hash(state);
         self.channel_id.hash(state);
         Arc::as_ptr(self.connection.get_inner()).hash(state);
-        Arc::as_ptr(self.response_table.get_inner()).hash(state);
+        Arc::as_ptr(&self.response_table).hash(state);
     }
 }
 
@@ -133,13 +134,18 @@ impl Channel {
         local_address: SocketAddr,
         remote_address: SocketAddr,
         connection: Connection,
-        response_table: ArcMut<HashMap<i32, ResponseFuture>>,
+        response_table: ResponseTable,
     ) -> Self {
         let channel_id = Uuid::new_v4().to_string();
         let (tx, rx) = tokio::sync::mpsc::channel(1024);
         //let response_table = ArcMut::new(HashMap::with_capacity(32));
         let connection = ArcMut::new(connection);
-        tokio::spawn(run_send(connection.clone(), rx, response_table.clone()));
+        tokio::spawn(run_send(
+            connection.clone(),
+            rx,
+            response_table.clone(),
+            remote_address,
+        ));
         Self {
             local_address,
             remote_address,
@@ -221,12 +227,12 @@ impl Channel {
             Ok(result) => match result {
                 Ok(response) => response,
                 Err(e) => {
-                    self.response_table.remove(&opaque);
+                    self.response_table.lock().remove(&opaque);
                     Err(RemotingError::ChannelRecvRequestFailed(e.to_string()))
                 }
             },
             Err(e) => {
-                self.response_table.remove(&opaque);
+                self.response_table.lock().remove(&opaque);
                 Err(RemotingError::ChannelRecvRequestFailed(e.to_string()))
             }
         }
@@ -0,0 +1,467 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+//! SOCKS5 proxy support of the remoting client.
+//!
+//! Proxy rules use the same JSON layout as the Java client, keyed by the CIDR of the target
+//! addresses they apply to:
+//!
+//! ```json
+//! {"0.0.0.0/0": {"addr": "127.0.0.1:1080", "username": "user", "password": "pass"}}
+//! ```
+//!
+//! `0.0.0.0/0` matches every target, including host names. A bare IP matches that address only.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+use crate::remoting_error::RemotingError::SocksProxyError;
+use crate::Result;
+
+const SOCKS_VERSION: u8 = 0x05;
+const AUTH_VERSION: u8 = 0x01;
+const METHOD_NO_AUTH: u8 = 0x00;
+const METHOD_USERNAME_PASSWORD: u8 = 0x02;
+const METHOD_NOT_ACCEPTABLE: u8 = 0xFF;
+const CMD_CONNECT: u8 = 0x01;
+const ATYP_IPV4: u8 = 0x01;
+const ATYP_DOMAIN: u8 = 0x03;
+const ATYP_IPV6: u8 = 0x04;
+const REPLY_SUCCEEDED: u8 = 0x00;
+
+/// The CIDR which matches every target address.
+pub const MATCH_ALL_CIDR: &str = "0.0.0.0/0";
+
+/// A SOCKS5 proxy server and the optional credentials used to authenticate against it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SocksProxyConfig {
+    pub addr: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+impl SocksProxyConfig {
+    /// Returns the credentials when a non-empty username is configured.
+    fn credentials(&self) -> Option<(&str, &str)> {
+        match self.username.as_deref() {
+            Some(username) if !username.is_empty() => {
+                Some((username, self.password.as_deref().unwrap_or_default()))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Cidr {
+    Any,
+    Net { network: IpAddr, prefix_len: u8 },
+}
+
+impl Cidr {
+    fn parse(cidr: &str) -> Result<Cidr> {
+        let cidr = cidr.trim();
+        if cidr == MATCH_ALL_CIDR {
+            return Ok(Cidr::Any);
+        }
+        let invalid = || SocksProxyError(format!("invalid proxy CIDR: {cidr}"));
+        let (ip, prefix_len) = match cidr.split_once('/') {
+            Some((ip, prefix_len)) => (
+                ip.parse::<IpAddr>().map_err(|_| invalid())?,
+                Some(prefix_len.parse::<u8>().map_err(|_| invalid())?),
+            ),
+            None => (cidr.parse::<IpAddr>().map_err(|_| invalid())?, None),
+        };
+        let max_len = if ip.is_ipv4() { 32 } else { 128 };
+        let prefix_len = prefix_len.unwrap_or(max_len);
+        if prefix_len > max_len {
+            return Err(invalid());
+        }
+        Ok(Cidr::Net {
+            network: ip,
+            prefix_len,
+        })
+    }
+
+    fn prefix_len(&self) -> u8 {
+        match self {
+            Cidr::Any => 0,
+            Cidr::Net { prefix_len, .. } => *prefix_len,
+        }
+    }
+
+    fn contains(&self, host: &str) -> bool {
+        let network = match self {
+            Cidr::Any => return true,
+            Cidr::Net { network, .. } => network,
+        };
+        let prefix_len = self.prefix_len() as u32;
+        match (network, host.parse::<IpAddr>()) {
+            (IpAddr::V4(network), Ok(IpAddr::V4(ip))) => {
+                let mask = u32::MAX.checked_shl(32 - prefix_len).unwrap_or(0);
+                u32::from(*network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), Ok(IpAddr::V6(ip))) => {
+                let mask = u128::MAX.checked_shl(128 - prefix_len).unwrap_or(0);
+                u128::from(*network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The proxy rules of a client, most specific CIDR first.
+#[derive(Debug, Clone, Default)]
+pub struct SocksProxyRules {
+    rules: Vec<(Cidr, SocksProxyConfig)>,
+}
+
+impl SocksProxyRules {
+    /// Parses the JSON map of CIDR to proxy config. An empty string yields no rules.
+    pub fn parse(json: &str) -> Result<SocksProxyRules> {
+        if json.trim().is_empty() {
+            return Ok(SocksProxyRules::default());
+        }
+        let configs: HashMap<String, SocksProxyConfig> = serde_json::from_str(json)
+            .map_err(|err| SocksProxyError(format!("invalid proxy config: {err}")))?;
+        let mut rules = Vec::with_capacity(configs.len());
+        for (cidr, config) in configs {
+            if config.addr.trim().is_empty() {
+                return Err(SocksProxyError(format!("empty proxy address for {cidr}")));
+            }
+            rules.push((Cidr::parse(&cidr)?, config));
+        }
+        rules.sort_by_key(|(cidr, _)| std::cmp::Reverse(cidr.prefix_len()));
+        Ok(SocksProxyRules { rules })
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Finds the proxy used to reach `addr`, given as `host:port`.
+    pub fn find(&self, addr: &str) -> Option<&SocksProxyConfig> {
+        if self.rules.is_empty() {
+            return None;
+        }
+        let (host, _) = split_host_port(addr).ok()?;
+        self.rules
+            .iter()
+            .find(|(cidr, _)| cidr.contains(host))
+            .map(|(_, config)| config)
+    }
+}
+
+/// Opens a connection to `target`, given as `host:port`, through the SOCKS5 proxy.
+///
+/// Host names are resolved by the proxy.
+pub async fn connect(proxy: &SocksProxyConfig, target: &str) -> Result<TcpStream> {
+    let (host, port) = split_host_port(target)?;
+    let mut stream = TcpStream::connect(proxy.addr.as_str()).await?;
+    handshake(&mut stream, proxy, host, port).await?;
+    Ok(stream)
+}
+
+async fn handshake(
+    stream: &mut TcpStream,
+    proxy: &SocksProxyConfig,
+    host: &str,
+    port: u16,
+) -> Result<()> {
+    let credentials = proxy.credentials();
+    let greeting: &[u8] = if credentials.is_some() {
+        &[SOCKS_VERSION, 2, METHOD_NO_AUTH, METHOD_USERNAME_PASSWORD]
+    } else {
+        &[SOCKS_VERSION, 1, METHOD_NO_AUTH]
+    };
+    stream.write_all(greeting).await?;
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply).await?;
+    if reply[0] != SOCKS_VERSION {
+        return Err(SocksProxyError(format!(
+            "unsupported proxy version: {}",
+            reply[0]
+        )));
+    }
+    match (reply[1], credentials) {
+        (METHOD_NO_AUTH, _) => {}
+        (METHOD_USERNAME_PASSWORD, Some((username, password))) => {
+            authenticate(stream, username, password).await?
+        }
+        (METHOD_NOT_ACCEPTABLE, _) => {
+            return Err(SocksProxyError(
+                "no acceptable authentication method".to_string(),
+            ))
+        }
+        (method, _) => {
+            return Err(SocksProxyError(format!(
+                "unsupported authentication method: {method}"
+            )))
+        }
+    }
+
+    let mut request = vec![SOCKS_VERSION, CMD_CONNECT, 0x00];
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            request.push(ATYP_IPV4);
+            request.extend_from_slice(&ip.octets());
+        }
+        Ok(IpAddr::V6(ip)) => {
+            request.push(ATYP_IPV6);
+            request.extend_from_slice(&ip.octets());
+        }
+        Err(_) => {
+            let len = u8::try_from(host.len())
+                .map_err(|_| SocksProxyError(format!("host name too long: {host}")))?;
+            request.push(ATYP_DOMAIN);
+            request.push(len);
+            request.extend_from_slice(host.as_bytes());
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await?;
+
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[1] != REPLY_SUCCEEDED {
+        return Err(SocksProxyError(format!(
+            "proxy failed to connect {host}:{port}, reply code: {}",
+            reply[1]
+        )));
+    }
+    // Skip the bound address which the client does not need.
+    let bound_len = match reply[3] {
+        ATYP_IPV4 => 4,
+        ATYP_IPV6 => 16,
+        ATYP_DOMAIN => stream.read_u8().await? as usize,
+        atyp => {
+            return Err(SocksProxyError(format!(
+                "unsupported bound address type: {atyp}"
+            )))
+        }
+    };
+    let mut bound = vec![0u8; bound_len + 2];
+    stream.read_exact(&mut bound).await?;
+    Ok(())
+}
+
+/// Username/password authentication as specified by RFC 1929.
+async fn authenticate(stream: &mut TcpStream, username: &str, password: &str) -> Result<()> {
+    let username_len = u8::try_from(username.len())
+        .map_err(|_| SocksProxyError("proxy username too long".to_string()))?;
+    let password_len = u8::try_from(password.len())
+        .map_err(|_| SocksProxyError("proxy password too long".to_string()))?;
+    let mut request = Vec::with_capacity(3 + username.len() + password.len());
+    request.push(AUTH_VERSION);
+    request.push(username_len);
+    request.extend_from_slice(username.as_bytes());
+    request.push(password_len);
+    request.extend_from_slice(password.as_bytes());
+    stream.write_all(&request).await?;
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply).await?;
+    if reply[1] != 0x00 {
+        return Err(SocksProxyError("proxy authentication failed".to_string()));
+    }
+    Ok(())
+}
+
+fn split_host_port(addr: &str) -> Result<(&str, u16)> {
+    let invalid = || SocksProxyError(format!("invalid address: {addr}"));
+    let (host, port) = addr.rsplit_once(':').ok_or_else(invalid)?;
+    let host = host
+        .strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+        .unwrap_or(host);
+    Ok((host, port.parse::<u16>().map_err(|_| invalid())?))
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    #[test]
+    fn find_prefers_most_specific_rule() {
+        let rules = SocksProxyRules::parse(
+            r#"{
+                "0.0.0.0/0": {"addr": "127.0.0.1:1080"},
+                "10.0.0.0/8": {"addr": "127.0.0.1:1081"},
+                "10.1.2.3": {"addr": "127.0.0.1:1082", "username": "u", "password": "p"}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(rules.find("10.1.2.3:9876").unwrap().addr, "127.0.0.1:1082");
+        assert_eq!(rules.find("10.9.9.9:9876").unwrap().addr, "127.0.0.1:1081");
+        assert_eq!(
+            rules.find("192.168.0.1:9876").unwrap().addr,
+            "127.0.0.1:1080"
+        );
+        assert_eq!(rules.find("broker-a:10911").unwrap().addr, "127.0.0.1:1080");
+    }
+
+    #[test]
+    fn find_without_catch_all_rule() {
+        let rules = SocksProxyRules::parse(
+            r#"{"fd00::/8": {"addr": "127.0.0.1:1080"}, "172.16.0.0/12": {"addr": "127.0.0.1:1081"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            rules.find("[fd00::1]:10911").unwrap().addr,
+            "127.0.0.1:1080"
+        );
+        assert_eq!(
+            rules.find("172.20.0.1:10911").unwrap().addr,
+            "127.0.0.1:1081"
+        );
+        assert!(rules.find("172.32.0.1:10911").is_none());
+        assert!(rules.find("broker-a:10911").is_none());
+    }
+
+    #[test]
+    fn parse_rejects_invalid_config() {
+        assert!(SocksProxyRules::parse("{}").unwrap().is_empty());
+        assert!(SocksProxyRules::parse("").unwrap().is_empty());
+        assert!(SocksProxyRules::parse("not json").is_err());
+        assert!(SocksProxyRules::parse(r#"{"10.0.0.0/33": {"addr": "a:1"}}"#).is_err());
+        assert!(SocksProxyRules::parse(r#"{"10.0.0.0/8": {"addr": ""}}"#).is_err());
+    }
+
+    /// Serves a single SOCKS5 CONNECT request and returns the requested target.
+    async fn serve_once(listener: TcpListener, credentials: Option<(&str, &str)>) -> Vec<u8> {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut header = [0u8; 2];
+        stream.read_exact(&mut header).await.unwrap();
+        let mut methods = vec![0u8; header[1] as usize];
+        stream.read_exact(&mut methods).await.unwrap();
+        match credentials {
+            Some((username, password)) => {
+                assert!(methods.contains(&METHOD_USERNAME_PASSWORD));
+                stream
+                    .write_all(&[SOCKS_VERSION, METHOD_USERNAME_PASSWORD])
+                    .await
+                    .unwrap();
+                let mut buf = [0u8; 2];
+                stream.read_exact(&mut buf).await.unwrap();
+                let mut user = vec![0u8; buf[1] as usize];
+                stream.read_exact(&mut user).await.unwrap();
+                let mut pass = vec![0u8; stream.read_u8().await.unwrap() as usize];
+                stream.read_exact(&mut pass).await.unwrap();
+                let ok = user == username.as_bytes() && pass == password.as_bytes();
+                stream
+                    .write_all(&[AUTH_VERSION, if ok { 0 } else { 1 }])
+                    .await
+                    .unwrap();
+                if !ok {
+                    return vec![];
+                }
+            }
+            None => stream
+                .write_all(&[SOCKS_VERSION, METHOD_NO_AUTH])
+                .await
+                .unwrap(),
+        }
+        let mut request = [0u8; 4];
+        stream.read_exact(&mut request).await.unwrap();
+        let mut target = vec![request[3]];
+        let len = match request[3] {
+            ATYP_IPV4 => 4,
+            ATYP_IPV6 => 16,
+            _ => {
+                let len = stream.read_u8().await.unwrap();
+                target.push(len);
+                len as usize
+            }
+        };
+        let mut rest = vec![0u8; len + 2];
+        stream.read_exact(&mut rest).await.unwrap();
+        target.extend_from_slice(&rest);
+        stream
+            .write_all(&[
+                SOCKS_VERSION,
+                REPLY_SUCCEEDED,
+                0,
+                ATYP_IPV4,
+                127,
+                0,
+                0,
+                1,
+                0,
+                80,
+            ])
+            .await
+            .unwrap();
+        stream.write_all(b"ping").await.unwrap();
+        target
+    }
+
+    #[tokio::test]
+    async fn connect_without_auth() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy = SocksProxyConfig {
+            addr: listener.local_addr().unwrap().to_string(),
+            ..Default::default()
+        };
+        let server = tokio::spawn(serve_once(listener, None));
+        let mut stream = connect(&proxy, "10.0.0.1:10911").await.unwrap();
+        let mut buf = [0u8; 4];
+        stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"ping");
+        let target = server.await.unwrap();
+        assert_eq!(target, vec![ATYP_IPV4, 10, 0, 0, 1, 0x2A, 0x9F]);
+    }
+
+    #[tokio::test]
+    async fn connect_with_username_password() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy = SocksProxyConfig {
+            addr: listener.local_addr().unwrap().to_string(),
+            username: Some("user".to_string()),
+            password: Some("secret".to_string()),
+        };
+        let server = tokio::spawn(serve_once(listener, Some(("user", "secret"))));
+        connect(&proxy, "broker-a:9876").await.unwrap();
+        let mut expected = vec![ATYP_DOMAIN, 8];
+        expected.extend_from_slice(b"broker-a");
+        expected.extend_from_slice(&9876u16.to_be_bytes());
+        assert_eq!(server.await.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn connect_with_wrong_password() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy = SocksProxyConfig {
+            addr: listener.local_addr().unwrap().to_string(),
+            username: Some("user".to_string()),
+            password: Some("wrong".to_string()),
+        };
+        let server = tokio::spawn(serve_once(listener, Some(("user", "secret"))));
+        let result = connect(&proxy, "10.0.0.1:10911").await;
+        assert!(matches!(result, Err(SocksProxyError(_))));
+        server.await.unwrap();
+    }
+}
@@ -0,0 +1,386 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+//! TLS support of the remoting layer.
+//!
+//! The server side detects the TLS handshake on every accepted socket, so that plaintext and TLS
+//! clients can be served by the same listener while the server runs in [`TlsMode::Permissive`].
+
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rocketmq_common::common::server::tls_config::TlsConfig;
+use rocketmq_common::common::server::tls_config::TlsMode;
+use rustls::client::danger::HandshakeSignatureValid;
+use rustls::client::danger::ServerCertVerified;
+use rustls::client::danger::ServerCertVerifier;
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::CertificateDer;
+use rustls::pki_types::PrivateKeyDer;
+use rustls::pki_types::ServerName;
+use rustls::pki_types::UnixTime;
+use rustls::server::WebPkiClientVerifier;
+use rustls::DigitallySignedStruct;
+use rustls::RootCertStore;
+use rustls::SignatureScheme;
+use tokio::net::TcpStream;
+use tokio_rustls::TlsAcceptor;
+use tokio_rustls::TlsConnector;
+
+use crate::connection::ConnectionStream;
+use crate::remoting_error::RemotingError::TlsError;
+use crate::Result;
+
+/// The first byte of a TLS record carrying a handshake message.
+const TLS_HANDSHAKE_RECORD_TYPE: u8 = 0x16;
+
+/// Max time to wait for the TLS handshake of a new connection.
+pub const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Builds the acceptor used by the server listener from the server side of `tls_config`.
+pub fn build_server_acceptor(tls_config: &TlsConfig) -> Result<TlsAcceptor> {
+    let cert_path = required_path(tls_config.server_cert_path.as_deref(), "server cert")?;
+    let key_path = required_path(tls_config.server_key_path.as_deref(), "server key")?;
+    let provider = crypto_provider();
+    let builder = rustls::ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|err| TlsError(err.to_string()))?;
+    let builder = if tls_config.server_need_client_auth {
+        let trust_cert_path = required_path(
+            tls_config.server_trust_cert_path.as_deref(),
+            "server trust cert",
+        )?;
+        let verifier = WebPkiClientVerifier::builder_with_provider(
+            Arc::new(load_root_store(trust_cert_path)?),
+            provider,
+        )
+        .build()
+        .map_err(|err| TlsError(err.to_string()))?;
+        builder.with_client_cert_verifier(verifier)
+    } else {
+        builder.with_no_client_auth()
+    };
+    let server_config = builder
+        .with_single_cert(load_certs(cert_path)?, load_private_key(key_path)?)
+        .map_err(|err| TlsError(err.to_string()))?;
+    Ok(TlsAcceptor::from(Arc::new(server_config)))
+}
+
+/// Builds the connector used by the outgoing connections from the client side of `tls_config`.
+pub fn build_client_connector(tls_config: &TlsConfig) -> Result<TlsConnector> {
+    let provider = crypto_provider();
+    let builder = rustls::ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|err| TlsError(err.to_string()))?;
+    let builder = if tls_config.test_mode_enable {
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(InsecureServerCertVerifier(provider)))
+    } else {
+        let trust_cert_path = required_path(
+            tls_config.client_trust_cert_path.as_deref(),
+            "client trust cert",
+        )?;
+        builder.with_root_certificates(load_root_store(trust_cert_path)?)
+    };
+    let client_config = match (
+        tls_config.client_cert_path.as_deref(),
+        tls_config.client_key_path.as_deref(),
+    ) {
+        (Some(cert_path), Some(key_path)) => builder
+            .with_client_auth_cert(load_certs(cert_path)?, load_private_key(key_path)?)
+            .map_err(|err| TlsError(err.to_string()))?,
+        _ => builder.with_no_client_auth(),
+    };
+    Ok(TlsConnector::from(Arc::new(client_config)))
+}
+
+/// Wraps an accepted socket according to `mode`, a TLS handshake is performed if the client
+/// starts one. Plaintext clients are rejected in [`TlsMode::Enforcing`].
+pub async fn accept(
+    acceptor: &TlsAcceptor,
+    mode: TlsMode,
+    stream: TcpStream,
+) -> Result<ConnectionStream> {
+    if mode == TlsMode::Disabled {
+        return Ok(ConnectionStream::from(stream));
+    }
+    let mut first_byte = [0u8; 1];
+    let read = tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, stream.peek(&mut first_byte))
+        .await
+        .map_err(|_| TlsError("waiting for the first bytes timed out".to_string()))??;
+    if read == 0 || first_byte[0] != TLS_HANDSHAKE_RECORD_TYPE {
+        if mode == TlsMode::Enforcing {
+            return Err(TlsError(
+                "plaintext connection rejected in enforcing mode".to_string(),
+            ));
+        }
+        return Ok(ConnectionStream::from(stream));
+    }
+    let stream = tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream))
+        .await
+        .map_err(|_| TlsError("handshake timed out".to_string()))??;
+    Ok(ConnectionStream::from(tokio_rustls::TlsStream::from(
+        stream,
+    )))
+}
+
+/// Performs the client side TLS handshake on `stream`, `addr` is the `host:port` the stream is
+/// connected to and its host is used as the server name.
+pub async fn connect(
+    connector: &TlsConnector,
+    addr: &str,
+    stream: TcpStream,
+) -> Result<ConnectionStream> {
+    let server_name = server_name(addr)?;
+    let stream = tokio::time::timeout(
+        TLS_HANDSHAKE_TIMEOUT,
+        connector.connect(server_name, stream),
+    )
+    .await
+    .map_err(|_| TlsError(format!("handshake with {} timed out", addr)))??;
+    Ok(ConnectionStream::from(tokio_rustls::TlsStream::from(
+        stream,
+    )))
+}
+
+fn server_name(addr: &str) -> Result<ServerName<'static>> {
+    let host = match addr.rsplit_once(':') {
+        Some((host, port)) if port.parse::<u16>().is_ok() => host,
+        _ => addr,
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    ServerName::try_from(host.to_string())
+        .map_err(|err| TlsError(format!("invalid server name {}: {}", host, err)))
+}
+
+#[inline]
+fn crypto_provider() -> Arc<CryptoProvider> {
+    Arc::new(rustls::crypto::ring::default_provider())
+}
+
+fn required_path<'a>(path: Option<&'a str>, name: &str) -> Result<&'a str> {
+    match path {
+        Some(path) if !path.is_empty() => Ok(path),
+        _ => Err(TlsError(format!("the {} path is not configured", name))),
+    }
+}
+
+fn load_certs(path: &str) -> Result<Vec<CertificateDer<'static>>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let certs = rustls_pemfile::certs(&mut reader)
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|err| TlsError(format!("failed to read certificates {}: {}", path, err)))?;
+    if certs.is_empty() {
+        return Err(TlsError(format!("no certificate found in {}", path)));
+    }
+    Ok(certs)
+}
+
+fn load_private_key(path: &str) -> Result<PrivateKeyDer<'static>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    rustls_pemfile::private_key(&mut reader)
+        .map_err(|err| TlsError(format!("failed to read private key {}: {}", path, err)))?
+        .ok_or_else(|| TlsError(format!("no private key found in {}", path)))
+}
+
+fn load_root_store(path: &str) -> Result<RootCertStore> {
+    let mut root_store = RootCertStore::empty();
+    for cert in load_certs(path)? {
+        root_store
+            .add(cert)
+            .map_err(|err| TlsError(format!("invalid trust certificate {}: {}", path, err)))?;
+    }
+    Ok(root_store)
+}
+
+/// Accepts any server certificate, used when `test_mode_enable` is set.
+#[derive(Debug)]
+struct InsecureServerCertVerifier(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for InsecureServerCertVerifier {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> std::result::Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    fn write_self_signed_cert(dir: &Path) -> TlsConfig {
+        let certified_key = rcgen::generate_simple_self_signed(vec!["localhost".to_string()])
+            .expect("generate certificate");
+        let cert_path = dir.join("server.pem");
+        let key_path = dir.join("server.key");
+        std::fs::write(&cert_path, certified_key.cert.pem()).unwrap();
+        std::fs::write(&key_path, certified_key.key_pair.serialize_pem()).unwrap();
+        let cert_path = cert_path.to_string_lossy().to_string();
+        TlsConfig {
+            server_mode: TlsMode::Permissive,
+            server_need_client_auth: false,
+            server_cert_path: Some(cert_path.clone()),
+            server_key_path: Some(key_path.to_string_lossy().to_string()),
+            server_trust_cert_path: None,
+            client_enable: true,
+            client_cert_path: None,
+            client_key_path: None,
+            client_trust_cert_path: Some(cert_path),
+            test_mode_enable: false,
+        }
+    }
+
+    async fn accept_one(
+        listener: &TcpListener,
+        acceptor: &TlsAcceptor,
+        mode: TlsMode,
+    ) -> Result<Vec<u8>> {
+        let (socket, _) = listener.accept().await?;
+        let mut stream = accept(acceptor, mode, socket).await?;
+        let mut buf = vec![0u8; 4];
+        stream.read_exact(&mut buf).await?;
+        Ok(buf)
+    }
+
+    #[test]
+    fn server_name_strips_port() {
+        assert_eq!(
+            server_name("localhost:10911").unwrap(),
+            ServerName::try_from("localhost").unwrap()
+        );
+        assert_eq!(
+            server_name("127.0.0.1:9876").unwrap(),
+            ServerName::try_from("127.0.0.1").unwrap()
+        );
+        assert_eq!(
+            server_name("[::1]:9876").unwrap(),
+            ServerName::try_from("::1").unwrap()
+        );
+    }
+
+    #[test]
+    fn client_connector_requires_trust_cert_out_of_test_mode() {
+        let mut tls_config = TlsConfig {
+            client_trust_cert_path: None,
+            test_mode_enable: false,
+            ..TlsConfig::default()
+        };
+        assert!(build_client_connector(&tls_config).is_err());
+        tls_config.test_mode_enable = true;
+        assert!(build_client_connector(&tls_config).is_ok());
+    }
+
+    #[tokio::test]
+    async fn permissive_mode_accepts_plaintext_and_tls() {
+        let dir = tempfile::tempdir().unwrap();
+        let tls_config = write_self_signed_cert(dir.path());
+        let acceptor = build_server_acceptor(&tls_config).unwrap();
+        let connector = build_client_connector(&tls_config).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let plaintext = tokio::spawn(async move {
+            let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+            stream.write_all(&[0, 0, 0, 1]).await.unwrap();
+            stream
+        });
+        let received = accept_one(&listener, &acceptor, TlsMode::Permissive)
+            .await
+            .unwrap();
+        assert_eq!(received, vec![0, 0, 0, 1]);
+        drop(plaintext.await.unwrap());
+
+        let tls = tokio::spawn(async move {
+            let addr = format!("localhost:{}", port);
+            let stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+            let mut stream = connect(&connector, &addr, stream).await.unwrap();
+            assert!(stream.is_tls());
+            stream.write_all(&[0, 0, 0, 2]).await.unwrap();
+            stream.flush().await.unwrap();
+            stream
+        });
+        let received = accept_one(&listener, &acceptor, TlsMode::Permissive)
+            .await
+            .unwrap();
+        assert_eq!(received, vec![0, 0, 0, 2]);
+        drop(tls.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn enforcing_mode_rejects_plaintext() {
+        let dir = tempfile::tempdir().unwrap();
+        let tls_config = write_self_signed_cert(dir.path());
+        let acceptor = build_server_acceptor(&tls_config).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let plaintext = tokio::spawn(async move {
+            let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+            stream.write_all(&[0, 0, 0, 1]).await.unwrap();
+            stream
+        });
+        let result = accept_one(&listener, &acceptor, TlsMode::Enforcing).await;
+        assert!(matches!(result, Err(TlsError(_))));
+        drop(plaintext.await.unwrap());
+    }
+}
@@ -0,0 +1,294 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+//! Transports of the remoting framework.
+//!
+//! A connection runs on any [`TransportStream`] and a server accepts connections from any
+//! [`TransportListener`]. TCP, optionally upgraded to TLS, is always served. Besides TCP, a
+//! server can additionally listen on a Unix domain socket and a client can dial one, addressed
+//! as `unix:///path/to/socket`. This keeps co-located clients, such as sidecars next to a
+//! broker, off the TCP stack.
+//!
+//! Only TCP connections can be upgraded to TLS, the other transports are plaintext. A server
+//! enforcing TLS refuses to listen on a Unix domain socket and a client using TLS refuses to
+//! dial one.
+//!
+//! Unix domain socket peers have no IP address. Each such connection is given a distinct
+//! address from the IPv6 discard prefix `100::/64` instead, so that everything keyed by the peer
+//! address keeps the channels apart while no IPv4 ACL white list entry can match them.
+
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+#[cfg(unix)]
+use std::path::Path;
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::pin::Pin;
+#[cfg(unix)]
+use std::sync::atomic::AtomicU64;
+#[cfg(unix)]
+use std::sync::atomic::Ordering;
+
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::net::TcpListener;
+#[cfg(unix)]
+use tokio::net::UnixListener;
+#[cfg(unix)]
+use tokio::net::UnixStream;
+
+use crate::connection::ConnectionStream;
+
+/// Scheme of the Unix domain socket addresses.
+pub const UNIX_SOCKET_SCHEME: &str = "unix://";
+
+/// A byte stream a connection can run on.
+pub trait TransportStream: AsyncRead + AsyncWrite + Send + Unpin + 'static {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin + 'static> TransportStream for T {}
+
+/// An accepted or dialed stream with the local and remote addresses of its channel.
+pub type TransportConnection = (ConnectionStream, SocketAddr, SocketAddr);
+
+pub type AcceptFuture<'a> =
+    Pin<Box<dyn Future<Output = io::Result<TransportConnection>> + Send + 'a>>;
+
+/// Accepts the connections of a transport a server listens on in addition to TCP.
+pub trait TransportListener: Send + Sync {
+    /// Accepts the next connection.
+    fn accept(&self) -> AcceptFuture<'_>;
+}
+
+/// Local address of the channels on a Unix domain socket.
+#[cfg(unix)]
+const UNIX_LOCAL_ADDRESS: SocketAddr = SocketAddr::new(
+    std::net::IpAddr::V6(std::net::Ipv6Addr::new(0x100, 0, 0, 0, 0, 0, 0, 0)),
+    0,
+);
+
+#[cfg(unix)]
+static NEXT_UNIX_PEER: AtomicU64 = AtomicU64::new(1);
+
+/// Returns the socket path of a `unix:///path` address, `None` for any other address.
+pub fn unix_socket_path(addr: &str) -> Option<&str> {
+    addr.strip_prefix(UNIX_SOCKET_SCHEME)
+        .filter(|path| !path.is_empty())
+}
+
+/// Allocates the address standing in for a Unix domain socket peer, taken from the IPv6 discard
+/// prefix `100::/64` and unique until 2^64 connections were made.
+#[cfg(unix)]
+fn unix_peer_address() -> SocketAddr {
+    let [a, b, c, d, e, f, g, h] = NEXT_UNIX_PEER.fetch_add(1, Ordering::Relaxed).to_be_bytes();
+    let ip = std::net::Ipv6Addr::new(
+        0x100,
+        0,
+        0,
+        0,
+        u16::from_be_bytes([a, b]),
+        u16::from_be_bytes([c, d]),
+        u16::from_be_bytes([e, f]),
+        u16::from_be_bytes([g, h]),
+    );
+    SocketAddr::new(ip.into(), 0)
+}
+
+/// Dials the Unix domain socket at `path`.
+pub async fn connect_unix(path: &str) -> io::Result<TransportConnection> {
+    #[cfg(unix)]
+    {
+        let stream: Box<dyn TransportStream> = Box::new(UnixStream::connect(path).await?);
+        Ok((stream.into(), UNIX_LOCAL_ADDRESS, unix_peer_address()))
+    }
+    #[cfg(not(unix))]
+    {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "Unix domain socket {} is not supported on this platform",
+                path
+            ),
+        ))
+    }
+}
+
+/// The sockets a remoting server accepts connections on: a TCP listener and the listeners of
+/// the additional transports.
+pub struct Listener {
+    tcp: TcpListener,
+    transports: Vec<Box<dyn TransportListener>>,
+}
+
+/// A bound Unix domain socket, the socket file is removed once the listener is dropped.
+#[cfg(unix)]
+struct UnixSocketListener {
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+#[cfg(unix)]
+impl TransportListener for UnixSocketListener {
+    fn accept(&self) -> AcceptFuture<'_> {
+        Box::pin(async move {
+            let (stream, _) = self.listener.accept().await?;
+            let stream: Box<dyn TransportStream> = Box::new(stream);
+            Ok((stream.into(), UNIX_LOCAL_ADDRESS, unix_peer_address()))
+        })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for UnixSocketListener {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl Listener {
+    /// Additionally accepts the connections of `transport`.
+    pub fn with_transport(mut self, transport: Box<dyn TransportListener>) -> Self {
+        self.transports.push(transport);
+        self
+    }
+
+    /// Additionally listens on the Unix domain socket at `path`. A socket file left behind by a
+    /// previous run is replaced.
+    pub fn with_unix_socket(self, path: &str) -> io::Result<Self> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::FileTypeExt;
+
+            let path = Path::new(path);
+            if std::fs::symlink_metadata(path)
+                .is_ok_and(|metadata| metadata.file_type().is_socket())
+            {
+                tracing::warn!("Remove the stale Unix domain socket {}", path.display());
+                std::fs::remove_file(path)?;
+            }
+            let listener = UnixListener::bind(path)?;
+            Ok(self.with_transport(Box::new(UnixSocketListener {
+                listener,
+                path: path.to_path_buf(),
+            })))
+        }
+        #[cfg(not(unix))]
+        {
+            drop(self);
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!(
+                    "Unix domain socket {} is not supported on this platform",
+                    path
+                ),
+            ))
+        }
+    }
+
+    /// The local address of the TCP listener.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.tcp.local_addr()
+    }
+
+    /// Accepts the next connection on any of the sockets.
+    pub async fn accept(&self) -> io::Result<TransportConnection> {
+        if self.transports.is_empty() {
+            let (stream, remote_addr) = self.tcp.accept().await?;
+            return Self::accepted_tcp(stream, remote_addr);
+        }
+        let transports =
+            futures::future::select_all(self.transports.iter().map(|transport| transport.accept()));
+        tokio::select! {
+            accepted = self.tcp.accept() => {
+                let (stream, remote_addr) = accepted?;
+                Self::accepted_tcp(stream, remote_addr)
+            }
+            (accepted, _, _) = transports => accepted,
+        }
+    }
+
+    fn accepted_tcp(
+        stream: tokio::net::TcpStream,
+        remote_addr: SocketAddr,
+    ) -> io::Result<TransportConnection> {
+        stream.set_nodelay(true)?;
+        let local_addr = stream.local_addr()?;
+        Ok((stream.into(), local_addr, remote_addr))
+    }
+}
+
+impl From<TcpListener> for Listener {
+    fn from(tcp: TcpListener) -> Self {
+        Listener {
+            tcp,
+            transports: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_unix_socket_path() {
+        assert_eq!(
+            unix_socket_path("unix:///var/run/rocketmq.sock"),
+            Some("/var/run/rocketmq.sock")
+        );
+        assert_eq!(unix_socket_path("unix://"), None);
+        assert_eq!(unix_socket_path("127.0.0.1:10911"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_peer_addresses_are_distinct_and_not_ipv4() {
+        let first = unix_peer_address();
+        let second = unix_peer_address();
+        assert_ne!(first, second);
+        assert!(first.is_ipv6());
+        assert!(first.ip().to_string().starts_with("100::"));
+    }
+
+    #[tokio::test]
+    async fn accepts_connections_of_additional_transports() {
+        struct OneShotTransport(tokio::sync::Mutex<Option<tokio::io::DuplexStream>>);
+
+        impl TransportListener for OneShotTransport {
+            fn accept(&self) -> AcceptFuture<'_> {
+                Box::pin(async move {
+                    match self.0.lock().await.take() {
+                        Some(stream) => {
+                            let stream: Box<dyn TransportStream> = Box::new(stream);
+                            let addr = SocketAddr::from(([10, 0, 0, 1], 1));
+                            Ok((stream.into(), addr, addr))
+                        }
+                        None => std::future::pending().await,
+                    }
+                })
+            }
+        }
+
+        let (_client, server) = tokio::io::duplex(64);
+        let listener = Listener::from(TcpListener::bind("127.0.0.1:0").await.unwrap())
+            .with_transport(Box::new(OneShotTransport(tokio::sync::Mutex::new(Some(
+                server,
+            )))));
+        let (stream, _, remote_addr) = listener.accept().await.unwrap();
+        assert!(matches!(stream, ConnectionStream::Transport(_)));
+        assert_eq!(remote_addr, SocketAddr::from(([10, 0, 0, 1], 1)));
+    }
+}
@@ -57,6 +57,10 @@ impl ConsumeStats {
         self.offset_table.clone()
     }
 
+    pub fn get_offset_table_mut(&mut self) -> &mut HashMap<MessageQueue, OffsetWrapper> {
+        &mut self.offset_table
+    }
+
     pub fn set_offset_table(&mut self, offset_table: HashMap<MessageQueue, OffsetWrapper>) {
         self.offset_table = offset_table;
     }
@@ -69,3 +73,23 @@ impl ConsumeStats {
         self.consume_tps = consume_tps;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consume_stats_offset_table_mut_updates_in_place() {
+        let mut stats = ConsumeStats::new();
+        let mut offset_wrapper = OffsetWrapper::new();
+        offset_wrapper.set_broker_offset(100);
+        offset_wrapper.set_consumer_offset(40);
+        stats.get_offset_table_mut().insert(
+            MessageQueue::from_parts("topic", "broker-a", 0),
+            offset_wrapper,
+        );
+
+        assert_eq!(stats.get_offset_table().len(), 1);
+        assert_eq!(stats.compute_total_diff(), 60);
+    }
+}
@@ -34,6 +34,7 @@ pub mod cm_result;
 pub mod connection;
 pub mod consume_message_directly_result;
 pub mod consume_queue_data;
+pub mod consume_status;
 pub mod group_list;
 pub mod ha_client_runtime_info;
 pub mod ha_connection_runtime_info;
@@ -45,8 +46,10 @@ pub mod producer_connection;
 pub mod query_assignment_request_body;
 pub mod query_assignment_response_body;
 pub mod query_consume_queue_response_body;
+pub mod query_message_by_condition_request_body;
 pub mod queue_time_span;
 pub mod request;
+pub mod reset_offset_body;
 pub mod response;
 pub mod set_message_request_mode_request_body;
 pub mod topic;
@@ -14,5 +14,94 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
-#[derive(Clone)]
-pub struct ConsumerRunningInfo {}
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::message::message_queue::MessageQueue;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json_any_key::*;
+
+use crate::protocol::body::consume_status::ConsumeStatus;
+use crate::protocol::body::pop_process_queue_info::PopProcessQueueInfo;
+use crate::protocol::body::process_queue_info::ProcessQueueInfo;
+use crate::protocol::heartbeat::subscription_data::SubscriptionData;
+
+/// Runtime snapshot of a consumer group inside one client, returned for
+/// `GET_CONSUMER_RUNNING_INFO`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsumerRunningInfo {
+    #[serde(default)]
+    pub properties: HashMap<CheetahString, CheetahString>,
+    #[serde(default)]
+    pub subscription_set: HashSet<SubscriptionData>,
+    #[serde(default, with = "any_key_map")]
+    pub mq_table: HashMap<MessageQueue, ProcessQueueInfo>,
+    #[serde(default, with = "any_key_map")]
+    pub mq_pop_table: HashMap<MessageQueue, PopProcessQueueInfo>,
+    #[serde(default)]
+    pub status_table: HashMap<CheetahString, ConsumeStatus>,
+    #[serde(default)]
+    pub user_consumer_info: HashMap<CheetahString, CheetahString>,
+    pub jstack: Option<CheetahString>,
+}
+
+impl ConsumerRunningInfo {
+    pub const PROP_NAMESERVER_ADDR: &'static str = "PROP_NAMESERVER_ADDR";
+    pub const PROP_THREADPOOL_CORE_SIZE: &'static str = "PROP_THREADPOOL_CORE_SIZE";
+    pub const PROP_CONSUME_ORDERLY: &'static str = "PROP_CONSUMEORDERLY";
+    pub const PROP_CONSUME_TYPE: &'static str = "PROP_CONSUME_TYPE";
+    pub const PROP_CLIENT_VERSION: &'static str = "PROP_CLIENT_VERSION";
+    pub const PROP_CONSUMER_START_TIMESTAMP: &'static str = "PROP_CONSUMER_START_TIMESTAMP";
+
+    pub fn property(&self, key: &str) -> Option<&CheetahString> {
+        self.properties.get(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::RemotingDeserializable;
+    use crate::protocol::RemotingSerializable;
+
+    #[test]
+    fn consumer_running_info_round_trip() {
+        let mut info = ConsumerRunningInfo::default();
+        info.properties.insert(
+            ConsumerRunningInfo::PROP_CONSUME_TYPE.into(),
+            "CONSUME_PASSIVELY".into(),
+        );
+        info.mq_table.insert(
+            MessageQueue::from_parts("test_topic", "broker-a", 0),
+            ProcessQueueInfo {
+                commit_offset: 10,
+                cached_msg_count: 2,
+                ..Default::default()
+            },
+        );
+        let encoded = info.encode().unwrap();
+        let decoded = ConsumerRunningInfo::decode(&encoded).unwrap();
+        assert_eq!(
+            decoded
+                .property(ConsumerRunningInfo::PROP_CONSUME_TYPE)
+                .map(|value| value.as_str()),
+            Some("CONSUME_PASSIVELY")
+        );
+        let queue_info = decoded
+            .mq_table
+            .get(&MessageQueue::from_parts("test_topic", "broker-a", 0))
+            .unwrap();
+        assert_eq!(queue_info.commit_offset, 10);
+        assert_eq!(queue_info.cached_msg_count, 2);
+    }
+
+    #[test]
+    fn consumer_running_info_decodes_missing_tables() {
+        let decoded = ConsumerRunningInfo::decode(br#"{"properties":{}}"#).unwrap();
+        assert!(decoded.mq_table.is_empty());
+        assert!(decoded.jstack.is_none());
+    }
+}
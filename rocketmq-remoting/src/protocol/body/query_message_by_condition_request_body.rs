@@ -0,0 +1,95 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Body of a [`RequestCode::QueryMessageByCondition`] request.
+///
+/// The unique key is looked up together with `keys`, `tags` narrows the matched messages down
+/// and an empty list matches every tag. `cursor` is the `nextCursor` of the previous page, or a
+/// value not greater than zero for the first page.
+///
+/// [`RequestCode::QueryMessageByCondition`]: crate::code::request_code::RequestCode::QueryMessageByCondition
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryMessageByConditionRequestBody {
+    pub topic: CheetahString,
+    #[serde(default)]
+    pub keys: Vec<CheetahString>,
+    pub unique_key: Option<CheetahString>,
+    #[serde(default)]
+    pub tags: Vec<CheetahString>,
+    pub begin_timestamp: i64,
+    pub end_timestamp: i64,
+    pub max_num: i32,
+    #[serde(default)]
+    pub cursor: i64,
+}
+
+impl QueryMessageByConditionRequestBody {
+    /// All the keys to look up in the index, including the unique key.
+    pub fn index_keys(&self) -> Vec<CheetahString> {
+        let mut keys = self.keys.clone();
+        if let Some(unique_key) = &self.unique_key {
+            if !keys.contains(unique_key) {
+                keys.push(unique_key.clone());
+            }
+        }
+        keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::RemotingDeserializable;
+    use crate::protocol::RemotingSerializable;
+
+    #[test]
+    fn query_message_by_condition_request_body_round_trip() {
+        let body = QueryMessageByConditionRequestBody {
+            topic: CheetahString::from("test_topic"),
+            keys: vec![CheetahString::from("k1")],
+            unique_key: Some(CheetahString::from("uniq")),
+            tags: vec![CheetahString::from("TagA")],
+            begin_timestamp: 1,
+            end_timestamp: 2,
+            max_num: 32,
+            cursor: 1024,
+        };
+        let decoded =
+            QueryMessageByConditionRequestBody::decode(body.encode().unwrap().as_slice()).unwrap();
+        assert_eq!(decoded.topic, body.topic);
+        assert_eq!(decoded.unique_key, body.unique_key);
+        assert_eq!(decoded.cursor, 1024);
+        assert_eq!(
+            decoded.index_keys(),
+            vec![CheetahString::from("k1"), CheetahString::from("uniq")]
+        );
+    }
+
+    #[test]
+    fn query_message_by_condition_request_body_defaults_optional_fields() {
+        let json = r#"{"topic":"t","beginTimestamp":0,"endTimestamp":10,"maxNum":5}"#;
+        let decoded = QueryMessageByConditionRequestBody::decode(json.as_bytes()).unwrap();
+        assert!(decoded.keys.is_empty());
+        assert!(decoded.tags.is_empty());
+        assert_eq!(decoded.cursor, 0);
+        assert!(decoded.index_keys().is_empty());
+    }
+}
@@ -20,15 +20,19 @@ pub mod change_invisible_time_request_header;
 pub mod change_invisible_time_response_header;
 pub mod check_transaction_state_request_header;
 pub mod client_request_header;
+pub mod clone_group_offset_request_header;
 pub mod consume_message_directly_result_request_header;
 pub mod consumer_send_msg_back_request_header;
+pub mod create_access_config_request_header;
 pub mod create_topic_request_header;
+pub mod delete_access_config_request_header;
 pub mod delete_subscription_group_request_header;
 pub mod delete_topic_request_header;
 pub mod elect_master_response_header;
 pub mod end_transaction_request_header;
 pub mod extra_info_util;
 pub mod get_all_topic_config_response_header;
+pub mod get_broker_acl_config_response_header;
 pub mod get_consume_stats_request_header;
 pub mod get_consumer_connection_list_request_header;
 pub mod get_consumer_listby_group_request_header;
@@ -40,6 +44,7 @@ pub mod get_max_offset_response_header;
 pub mod get_meta_data_response_header;
 pub mod get_min_offset_request_header;
 pub mod get_min_offset_response_header;
+pub mod get_subscription_group_config_request_header;
 pub mod get_topic_config_request_header;
 pub mod get_topic_stats_info_request_header;
 pub mod get_topic_stats_request_header;
@@ -56,6 +61,7 @@ pub mod pull_message_response_header;
 pub mod query_consume_time_span_request_header;
 pub mod query_consumer_offset_request_header;
 pub mod query_consumer_offset_response_header;
+pub mod query_message_by_condition_response_header;
 pub mod query_message_request_header;
 pub mod query_message_response_header;
 pub mod query_subscription_by_consumer_request_header;
@@ -67,5 +73,6 @@ pub mod search_offset_response_header;
 pub mod unlock_batch_mq_request_header;
 pub mod unregister_client_request_header;
 pub mod update_consumer_offset_header;
+pub mod update_global_white_addrs_config_request_header;
 pub mod view_message_request_header;
 pub mod view_message_response_header;
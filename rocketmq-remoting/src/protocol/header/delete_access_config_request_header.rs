@@ -0,0 +1,44 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_macros::RequestHeaderCodec;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteAccessConfigRequestHeader {
+    #[required]
+    pub access_key: CheetahString,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::command_custom_header::CommandCustomHeader;
+    use crate::protocol::command_custom_header::FromMap;
+
+    #[test]
+    fn delete_access_config_request_header_round_trips_through_map() {
+        let header = DeleteAccessConfigRequestHeader {
+            access_key: CheetahString::from("rocketmq"),
+        };
+        let map = header.to_map().unwrap();
+        let decoded = <DeleteAccessConfigRequestHeader as FromMap>::from(&map).unwrap();
+        assert_eq!(decoded.access_key, CheetahString::from("rocketmq"));
+    }
+}
@@ -0,0 +1,64 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_macros::RequestHeaderCodec;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// The ACL version of a broker, `all_acl_file_version` is the JSON map of ACL file path to its
+/// data version.
+#[derive(Clone, Debug, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBrokerAclConfigResponseHeader {
+    #[required]
+    pub version: CheetahString,
+
+    pub all_acl_file_version: Option<CheetahString>,
+
+    #[required]
+    pub broker_name: CheetahString,
+
+    #[required]
+    pub broker_addr: CheetahString,
+
+    #[required]
+    pub cluster_name: CheetahString,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::command_custom_header::CommandCustomHeader;
+    use crate::protocol::command_custom_header::FromMap;
+
+    #[test]
+    fn get_broker_acl_config_response_header_round_trips_through_map() {
+        let header = GetBrokerAclConfigResponseHeader {
+            version: CheetahString::from("{}"),
+            all_acl_file_version: Some(CheetahString::from("{}")),
+            broker_name: CheetahString::from("broker-a"),
+            broker_addr: CheetahString::from("127.0.0.1:10911"),
+            cluster_name: CheetahString::from("DefaultCluster"),
+        };
+        let map = header.to_map().unwrap();
+        let decoded = <GetBrokerAclConfigResponseHeader as FromMap>::from(&map).unwrap();
+        assert_eq!(decoded.broker_name, header.broker_name);
+        assert_eq!(decoded.broker_addr, header.broker_addr);
+        assert_eq!(decoded.cluster_name, header.cluster_name);
+        assert_eq!(decoded.all_acl_file_version, header.all_acl_file_version);
+    }
+}
@@ -0,0 +1,56 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_macros::RequestHeaderCodec;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::rpc::rpc_request_header::RpcRequestHeader;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSubscriptionGroupConfigRequestHeader {
+    #[required]
+    pub group: CheetahString,
+
+    #[serde(flatten)]
+    pub rpc_request_header: Option<RpcRequestHeader>,
+}
+
+#[cfg(test)]
+mod tests {
+    use cheetah_string::CheetahString;
+
+    use super::*;
+
+    #[test]
+    fn get_subscription_group_config_request_header_serializes_correctly() {
+        let header = GetSubscriptionGroupConfigRequestHeader {
+            group: CheetahString::from_static_str("test_group"),
+            rpc_request_header: None,
+        };
+        let serialized = serde_json::to_string(&header).unwrap();
+        assert_eq!(serialized, r#"{"group":"test_group"}"#);
+    }
+
+    #[test]
+    fn get_subscription_group_config_request_header_deserializes_correctly() {
+        let data = r#"{"group":"test_group"}"#;
+        let header: GetSubscriptionGroupConfigRequestHeader = serde_json::from_str(data).unwrap();
+        assert_eq!(header.group, CheetahString::from_static_str("test_group"));
+    }
+}
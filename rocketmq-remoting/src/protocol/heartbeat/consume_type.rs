@@ -41,17 +41,23 @@ impl ConsumeType {
     }
 }
 
+impl ConsumeType {
+    /// The wire name of the consume type, as used by the Java client.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConsumeType::ConsumeActively => "CONSUME_ACTIVELY",
+            ConsumeType::ConsumePassively => "CONSUME_PASSIVELY",
+            ConsumeType::ConsumePop => "CONSUME_POP",
+        }
+    }
+}
+
 impl Serialize for ConsumeType {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let value = match self {
-            ConsumeType::ConsumeActively => "CONSUME_ACTIVELY",
-            ConsumeType::ConsumePassively => "CONSUME_PASSIVELY",
-            ConsumeType::ConsumePop => "CONSUME_POP",
-        };
-        serializer.serialize_str(value)
+        serializer.serialize_str(self.name())
     }
 }
 
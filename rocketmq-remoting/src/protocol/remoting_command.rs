@@ -30,6 +30,7 @@ use bytes::Bytes;
 use bytes::BytesMut;
 use cheetah_string::CheetahString;
 use lazy_static::lazy_static;
+use rocketmq_common::common::compression::compression_type::CompressionType;
 use rocketmq_common::common::mq_version::RocketMqVersion;
 use rocketmq_common::utils::serde_json_utils::SerdeJsonUtils;
 use rocketmq_rust::ArcMut;
@@ -49,6 +50,8 @@ use crate::rocketmq_serializable::RocketMQSerializable;
 pub const SERIALIZE_TYPE_PROPERTY: &str = "rocketmq.serialize.type";
 pub const SERIALIZE_TYPE_ENV: &str = "ROCKETMQ_SERIALIZE_TYPE";
 pub const REMOTING_VERSION_KEY: &str = "rocketmq.remoting.version";
+/// Ext field naming the compression type of a compressed body.
+pub const BODY_COMPRESS_TYPE: &str = "bodyCompressType";
 
 lazy_static! {
     static ref requestId: Arc<AtomicI32> = Arc::new(AtomicI32::new(0));
@@ -101,6 +104,8 @@ pub struct RemotingCommand {
     /// Non-zero indicates a response command, while 0 indicates a request command.
     /// The second bit indicates whether it is a one-way request.
     /// Non-zero indicates a one-way request.
+    /// The third bit indicates whether the body is compressed, see `BODY_COMPRESS_TYPE`.
+    /// The fourth bit advertises that the sender accepts compressed bodies.
     flag: i32,
     remark: Option<CheetahString>,
 
@@ -175,6 +180,8 @@ impl Default for RemotingCommand {
 impl RemotingCommand {
     pub(crate) const RPC_ONEWAY: i32 = 1;
     pub(crate) const RPC_TYPE: i32 = 0;
+    pub(crate) const RPC_COMPRESSED_BODY: i32 = 2;
+    pub(crate) const RPC_ACCEPT_COMPRESSED_BODY: i32 = 3;
 }
 
 impl RemotingCommand {
@@ -523,6 +530,7 @@ impl RemotingCommand {
             if total_size - 4 > header_length {
                 cmd.set_body_mut_ref(cmd_data.split_to(total_size - 4 - header_length).freeze());
             }
+            cmd.decompress_body()?;
         }
         Ok(cmd)
     }
@@ -658,6 +666,77 @@ impl RemotingCommand {
         (self.flag & bits) == bits
     }
 
+    #[inline]
+    pub fn is_body_compressed(&self) -> bool {
+        let bits = 1 << Self::RPC_COMPRESSED_BODY;
+        (self.flag & bits) == bits
+    }
+
+    /// Whether the sender of this command accepts compressed bodies.
+    #[inline]
+    pub fn accepts_compressed_body(&self) -> bool {
+        let bits = 1 << Self::RPC_ACCEPT_COMPRESSED_BODY;
+        (self.flag & bits) == bits
+    }
+
+    pub fn mark_accept_compressed_body_ref(&mut self) {
+        let mark = 1 << Self::RPC_ACCEPT_COMPRESSED_BODY;
+        self.flag |= mark;
+    }
+
+    /// Compresses the body when it is at least `threshold` bytes and compression makes it
+    /// smaller, returns whether the body was compressed.
+    pub fn compress_body(&mut self, threshold: usize, compression_type: CompressionType) -> bool {
+        if self.is_body_compressed() {
+            return false;
+        }
+        let compressed = match self.body.as_ref() {
+            Some(body) if body.len() >= threshold => {
+                let compressed = compression_type.compression(body);
+                if compressed.len() >= body.len() {
+                    return false;
+                }
+                compressed
+            }
+            _ => return false,
+        };
+        self.body = Some(compressed);
+        self.flag |= 1 << Self::RPC_COMPRESSED_BODY;
+        self.add_ext_field(BODY_COMPRESS_TYPE, compression_type.name());
+        true
+    }
+
+    /// Restores a body compressed by [`RemotingCommand::compress_body`], so that processors
+    /// never see a compressed body.
+    pub fn decompress_body(&mut self) -> crate::Result<()> {
+        if !self.is_body_compressed() {
+            return Ok(());
+        }
+        let compress_type = self
+            .ext_fields
+            .as_mut()
+            .and_then(|ext_fields| ext_fields.remove(BODY_COMPRESS_TYPE))
+            .unwrap_or_default();
+        let compression_type =
+            CompressionType::try_of(compress_type.as_str()).ok_or_else(|| {
+                RemotingError::RemotingCommandDecoderError(format!(
+                    "Unsupported body compress type: {}",
+                    compress_type
+                ))
+            })?;
+        if let Some(body) = self.body.as_ref() {
+            let body = compression_type.try_decompression(body).map_err(|error| {
+                RemotingError::RemotingCommandDecoderError(format!(
+                    "Failed to decompress body: {}",
+                    error
+                ))
+            })?;
+            self.body = Some(body);
+        }
+        self.flag &= !(1 << Self::RPC_COMPRESSED_BODY);
+        Ok(())
+    }
+
     pub fn get_type(&self) -> RemotingCommandType {
         if self.is_response_type() {
             RemotingCommandType::RESPONSE
@@ -676,9 +755,9 @@ impl RemotingCommand {
         key: impl Into<CheetahString>,
         value: impl Into<CheetahString>,
     ) -> &mut Self {
-        if let Some(ref mut ext) = self.ext_fields {
-            ext.insert(key.into(), value.into());
-        }
+        self.ext_fields
+            .get_or_insert_with(HashMap::new)
+            .insert(key.into(), value.into());
         self
     }
 
@@ -838,4 +917,60 @@ mod tests {
         println!("i={}", RemotingCommand::default().opaque);
         println!("i={}", RemotingCommand::default().opaque);
     }
+
+    fn encode_and_decode(mut command: RemotingCommand) -> RemotingCommand {
+        let mut buf = BytesMut::new();
+        command.fast_header_encode(&mut buf);
+        if let Some(body) = command.take_body() {
+            buf.put(body);
+        }
+        RemotingCommand::decode(&mut buf).unwrap().unwrap()
+    }
+
+    #[test]
+    fn compressed_body_is_decompressed_on_decode() {
+        let body = Bytes::from("topic-config ".repeat(1024));
+        for compression_type in [
+            CompressionType::LZ4,
+            CompressionType::Zstd,
+            CompressionType::Zlib,
+        ] {
+            let mut command = RemotingCommand::new_request(1, body.clone())
+                .set_serialize_type(SerializeType::JSON);
+            assert!(command.compress_body(1024, compression_type));
+            assert!(command.is_body_compressed());
+            assert!(command.body().as_ref().unwrap().len() < body.len());
+
+            let decoded = encode_and_decode(command);
+            assert!(!decoded.is_body_compressed());
+            assert_eq!(decoded.body().as_ref().unwrap(), &body);
+            assert!(!decoded
+                .ext_fields()
+                .unwrap()
+                .contains_key(BODY_COMPRESS_TYPE));
+        }
+    }
+
+    #[test]
+    fn small_body_is_not_compressed() {
+        let mut command = RemotingCommand::new_request(1, Bytes::from("small body"))
+            .set_serialize_type(SerializeType::JSON);
+        assert!(!command.compress_body(1024, CompressionType::LZ4));
+        assert!(!command.is_body_compressed());
+
+        let decoded = encode_and_decode(command);
+        assert_eq!(decoded.body().as_ref().unwrap().as_ref(), b"small body");
+    }
+
+    #[test]
+    fn corrupted_compressed_body_fails_decode() {
+        let mut command = RemotingCommand::new_request(1, Bytes::from("not compressed"))
+            .set_serialize_type(SerializeType::JSON)
+            .set_flag(1 << RemotingCommand::RPC_COMPRESSED_BODY);
+        command.add_ext_field(BODY_COMPRESS_TYPE, CompressionType::Zlib.name());
+        let mut buf = BytesMut::new();
+        command.fast_header_encode(&mut buf);
+        buf.put(command.take_body().unwrap());
+        assert!(RemotingCommand::decode(&mut buf).is_err());
+    }
 }
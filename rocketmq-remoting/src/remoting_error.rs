@@ -80,6 +80,12 @@ pub enum RemotingError {
 
     #[error("{0}")]
     IllegalArgument(String),
+
+    #[error("TLS error: {0}")]
+    TlsError(String),
+
+    #[error("SOCKS proxy error: {0}")]
+    SocksProxyError(String),
 }
 
 #[cfg(test)]
@@ -222,4 +228,10 @@ mod tests {
             "Channel recv Request failed: recv failed"
         );
     }
+
+    #[test]
+    fn tls_error_displays_correctly() {
+        let error = RemotingError::TlsError("handshake failed".to_string());
+        assert_eq!(format!("{}", error), "TLS error: handshake failed");
+    }
 }
@@ -18,6 +18,7 @@
 use crate::remoting::RemotingService;
 
 pub mod server;
+pub mod server_metrics;
 
 pub trait RemotingServer: RemotingService {
     /*fn register_processor(
@@ -20,26 +20,39 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
 
+use parking_lot::Mutex;
+use rocketmq_common::common::mq_version::RocketMqVersion;
 use rocketmq_common::common::server::config::ServerConfig;
+use rocketmq_common::common::server::tls_config::TlsMode;
+use rocketmq_common::TimeUtils::get_current_millis;
 use rocketmq_rust::wait_for_signal;
 use rocketmq_rust::ArcMut;
 use tokio::net::TcpListener;
-use tokio::net::TcpStream;
 use tokio::sync::broadcast;
 use tokio::sync::mpsc;
+use tokio::sync::watch;
+use tokio::sync::OwnedSemaphorePermit;
 use tokio::sync::Semaphore;
 use tokio::time;
+use tokio_rustls::TlsAcceptor;
 use tracing::error;
 use tracing::info;
 use tracing::warn;
 
-use crate::base::response_future::ResponseFuture;
+use crate::base::response_future::fail_response_table;
+use crate::base::response_future::ResponseTable;
 use crate::code::response_code::ResponseCode;
+use crate::connection::BodyCompression;
 use crate::connection::Connection;
+use crate::connection::ConnectionStream;
 use crate::net::channel::Channel;
+use crate::net::tls;
+use crate::net::transport::Listener;
 use crate::protocol::remoting_command::RemotingCommand;
+use crate::protocol::LanguageCode;
 use crate::protocol::RemotingCommandType;
 use crate::remoting_error::RemotingError;
+use crate::remoting_server::server_metrics::RemotingServerMetrics;
 use crate::runtime::connection_handler_context::ConnectionHandlerContext;
 use crate::runtime::connection_handler_context::ConnectionHandlerContextWrapper;
 use crate::runtime::processor::RequestProcessor;
@@ -49,6 +62,15 @@ use crate::Result;
 /// Default limit the max number of connections.
 const DEFAULT_MAX_CONNECTIONS: usize = 1000;
 
+/// Remark of the response to a request rejected by flow control.
+const OVERLOAD_REMARK: &str = "[OVERLOAD]system busy, start flow control for a while";
+
+/// Remark of the GoAway responses sent while the server shuts down gracefully.
+const GO_AWAY_REMARK: &str = "please go away";
+
+/// Opaque of the GoAway announced to a channel without a request to answer.
+const GO_AWAY_OPAQUE: i32 = -1;
+
 /// Shorthand for the transmit half of the message channel.
 type Tx = mpsc::UnboundedSender<RemotingCommand>;
 
@@ -63,7 +85,115 @@ pub struct ConnectionHandler<RP> {
     _shutdown_complete: mpsc::Sender<()>,
     conn_disconnect_notify: Option<broadcast::Sender<SocketAddr>>,
     rpc_hooks: Arc<Vec<Box<dyn RPCHook>>>,
-    response_table: ArcMut<HashMap<i32, ResponseFuture>>,
+    response_table: ResponseTable,
+    flow_control: ServerFlowControl,
+    /// Turns `true` once the server shuts down gracefully.
+    go_away: watch::Receiver<bool>,
+}
+
+/// The limits a server enforces on all of its connections, so that a single client can not
+/// exhaust the memory of the server.
+#[derive(Clone)]
+pub struct ServerFlowControl {
+    /// Permits of the oneway requests being processed.
+    oneway_permits: Arc<Semaphore>,
+    /// Permits of the request-response requests being processed.
+    async_permits: Arc<Semaphore>,
+    permit_timeout: Duration,
+    /// Channels without any read or write for this long are closed, `None` keeps them open.
+    max_idle_time: Option<Duration>,
+    write_buffer_low_water_mark: usize,
+    write_buffer_high_water_mark: usize,
+    /// Time to wait for the clients to drain after GoAway is announced on shutdown, `None` shuts
+    /// down at once.
+    graceful_shutdown_wait_time: Option<Duration>,
+    /// Compression of the response bodies sent to clients accepting them.
+    body_compression: Option<BodyCompression>,
+    metrics: Arc<RemotingServerMetrics>,
+}
+
+impl ServerFlowControl {
+    pub fn new(config: &ServerConfig, metrics: Arc<RemotingServerMetrics>) -> Self {
+        Self {
+            oneway_permits: Arc::new(Semaphore::new(config.server_oneway_semaphore_value)),
+            async_permits: Arc::new(Semaphore::new(config.server_async_semaphore_value)),
+            permit_timeout: Duration::from_millis(config.server_permit_timeout_millis),
+            max_idle_time: (config.server_channel_max_idle_time_seconds > 0)
+                .then(|| Duration::from_secs(config.server_channel_max_idle_time_seconds)),
+            write_buffer_low_water_mark: config.write_buffer_low_water_mark,
+            write_buffer_high_water_mark: config.write_buffer_high_water_mark,
+            graceful_shutdown_wait_time: config
+                .enable_shutdown_gracefully
+                .then(|| Duration::from_secs(config.shutdown_wait_time_seconds)),
+            body_compression: BodyCompression::new(
+                config.body_compress_threshold,
+                &config.body_compress_type,
+            ),
+            metrics,
+        }
+    }
+
+    /// Waits for a permit to process a request, `None` if no permit is released in time.
+    async fn acquire(&self, oneway: bool) -> Option<InFlightRequest> {
+        let permits = if oneway {
+            &self.oneway_permits
+        } else {
+            &self.async_permits
+        };
+        let permit = time::timeout(self.permit_timeout, permits.clone().acquire_owned())
+            .await
+            .ok()?
+            .ok()?;
+        self.metrics.inc_in_flight_requests(oneway);
+        Some(InFlightRequest {
+            _permit: permit,
+            oneway,
+            metrics: self.metrics.clone(),
+        })
+    }
+
+    /// Time left before a channel last active at the given timestamps turns idle.
+    fn remaining_idle_time(
+        &self,
+        last_read_timestamp: u64,
+        last_write_timestamp: u64,
+    ) -> Option<Duration> {
+        let max_idle_time = self.max_idle_time?;
+        let last_active = last_read_timestamp.max(last_write_timestamp);
+        let idle_time = Duration::from_millis(get_current_millis().saturating_sub(last_active));
+        Some(max_idle_time.saturating_sub(idle_time))
+    }
+}
+
+impl Default for ServerFlowControl {
+    fn default() -> Self {
+        ServerFlowControl::new(&ServerConfig::default(), Default::default())
+    }
+}
+
+/// A request holding a processing permit, the permit is released on drop.
+struct InFlightRequest {
+    _permit: OwnedSemaphorePermit,
+    oneway: bool,
+    metrics: Arc<RemotingServerMetrics>,
+}
+
+impl Drop for InFlightRequest {
+    fn drop(&mut self) {
+        self.metrics.dec_in_flight_requests(self.oneway);
+    }
+}
+
+/// Whether the client sending `request` handles GoAway, older Java clients treat it as an error.
+fn supports_go_away(request: &RemotingCommand) -> bool {
+    request.language() == LanguageCode::RUST || request.version() > i32::from(RocketMqVersion::V531)
+}
+
+async fn sleep_or_pending(duration: Option<Duration>) {
+    match duration {
+        Some(duration) => time::sleep(duration).await,
+        None => std::future::pending().await,
+    }
 }
 
 impl<RP> Drop for ConnectionHandler<RP> {
@@ -109,7 +239,31 @@ impl<RP> ConnectionHandler<RP> {
 
 impl<RP: RequestProcessor + Sync + 'static> ConnectionHandler<RP> {
     async fn handle(&mut self) -> Result<()> {
+        let write_buffer = self.channel.connection_ref().write_buffer();
+        let mut last_read_timestamp = get_current_millis();
+        let mut go_away_announced = false;
+        let mut peer_supports_go_away = true;
         while !self.shutdown.is_shutdown {
+            // Stop reading while the peer does not drain what was already written to it.
+            if !write_buffer.is_writable() {
+                self.flow_control.metrics.inc_read_paused_times();
+                warn!(
+                    "channel[{}] is not writable, {} bytes pending, pause reading",
+                    self.channel.remote_address(),
+                    write_buffer.pending_bytes()
+                );
+                tokio::select! {
+                    _ = write_buffer.wait_writable() => {}
+                    _ = self.shutdown.recv() => {
+                        self.channel.connection_mut().ok = false;
+                        return Ok(());
+                    }
+                }
+            }
+
+            let remaining_idle_time = self
+                .flow_control
+                .remaining_idle_time(last_read_timestamp, write_buffer.last_write_timestamp());
             //Get the next frame from the connection.
             let frame = tokio::select! {
                 res = self.connection_handler_context.channel.connection.receive_command() => res,
@@ -118,7 +272,42 @@ impl<RP: RequestProcessor + Sync + 'static> ConnectionHandler<RP> {
                     self.channel.connection_mut().ok = false;
                     return Ok(());
                 }
+                _ = sleep_or_pending(remaining_idle_time) => {
+                    let remaining_idle_time = self
+                        .flow_control
+                        .remaining_idle_time(last_read_timestamp, write_buffer.last_write_timestamp());
+                    if remaining_idle_time == Some(Duration::ZERO) {
+                        warn!(
+                            "channel[{}] is idle for {:?}, close it",
+                            self.channel.remote_address(),
+                            self.flow_control.max_idle_time.unwrap_or_default()
+                        );
+                        self.flow_control.metrics.inc_idle_closed_connections();
+                        self.channel.connection_mut().ok = false;
+                        return Ok(());
+                    }
+                    continue;
+                }
+                res = self.go_away.changed(), if !go_away_announced => {
+                    go_away_announced = true;
+                    if res.is_ok() && peer_supports_go_away {
+                        // let idle clients reconnect before the server goes down
+                        let announcement = RemotingCommand::create_response_command_with_code_remark(
+                            ResponseCode::GoAway,
+                            GO_AWAY_REMARK,
+                        )
+                        .set_opaque(GO_AWAY_OPAQUE);
+                        if let Err(RemotingError::Io(err)) =
+                            self.channel.connection_mut().send_command(announcement).await
+                        {
+                            error!("announce go away failed: {}", err);
+                            return Ok(());
+                        }
+                    }
+                    continue;
+                }
             };
+            last_read_timestamp = get_current_millis();
 
             let mut cmd = match frame {
                 Some(frame) => frame?,
@@ -129,7 +318,7 @@ impl<RP: RequestProcessor + Sync + 'static> ConnectionHandler<RP> {
             };
             //handle response
             if cmd.get_type() == RemotingCommandType::RESPONSE {
-                let future_response = self.response_table.remove(&cmd.opaque());
+                let future_response = self.response_table.lock().remove(&cmd.opaque());
                 if let Some(future_response) = future_response {
                     let _ = future_response.tx.send(Ok(cmd));
                 } else {
@@ -143,6 +332,19 @@ impl<RP: RequestProcessor + Sync + 'static> ConnectionHandler<RP> {
             }
             let opaque = cmd.opaque();
             let oneway_rpc = cmd.is_oneway_rpc();
+            peer_supports_go_away = supports_go_away(&cmd);
+            // The request is not processed, so the client can safely retry it on a new channel.
+            // Oneway requests can not be answered and are still processed.
+            if !oneway_rpc && peer_supports_go_away && *self.go_away.borrow() {
+                let exception = Some(RemotingError::AbortProcessError(
+                    ResponseCode::GoAway as i32,
+                    GO_AWAY_REMARK.to_string(),
+                ));
+                match self.handle_error(oneway_rpc, opaque, exception).await {
+                    HandleErrorResult::ReturnMethod => return Ok(()),
+                    HandleErrorResult::Continue | HandleErrorResult::GoHead => continue,
+                }
+            }
             //before handle request hooks
             let exception = match self.do_before_rpc_hooks(&self.channel, Some(&mut cmd)) {
                 Ok(_) => None,
@@ -155,6 +357,26 @@ impl<RP: RequestProcessor + Sync + 'static> ConnectionHandler<RP> {
                 HandleErrorResult::GoHead => {}
             }
 
+            // held until the response is written
+            let _in_flight = match self.flow_control.acquire(oneway_rpc).await {
+                Some(in_flight) => in_flight,
+                None => {
+                    self.flow_control.metrics.inc_rejected_requests();
+                    warn!(
+                        "too many requests in flight, reject request from {}",
+                        self.channel.remote_address()
+                    );
+                    let exception = Some(RemotingError::AbortProcessError(
+                        ResponseCode::SystemBusy as i32,
+                        OVERLOAD_REMARK.to_string(),
+                    ));
+                    match self.handle_error(oneway_rpc, opaque, exception).await {
+                        HandleErrorResult::ReturnMethod => return Ok(()),
+                        HandleErrorResult::Continue | HandleErrorResult::GoHead => continue,
+                    }
+                }
+            };
+
             let mut response = {
                 let channel = self.channel.clone();
                 let ctx = self.connection_handler_context.clone();
@@ -168,10 +390,9 @@ impl<RP: RequestProcessor + Sync + 'static> ConnectionHandler<RP> {
                 }
             };
 
-            let exception = match self.do_before_rpc_hooks(&self.channel, response.as_mut()) {
-                Ok(_) => None,
-                Err(error) => Some(error),
-            };
+            let exception = self
+                .do_after_rpc_hooks(&self.channel, response.as_mut())
+                .err();
 
             match self.handle_error(oneway_rpc, opaque, exception).await {
                 HandleErrorResult::Continue => continue,
@@ -266,10 +487,10 @@ enum HandleErrorResult {
 }
 
 /// Server listener state. Created in the `run` call. It includes a `run` method
-/// which performs the TCP listening and initialization of per-connection state.
+/// which performs the listening and initialization of per-connection state.
 struct ConnectionListener<RP> {
-    /// The TCP listener supplied by the `run` caller.
-    listener: TcpListener,
+    /// The listener supplied by the `run` caller.
+    listener: Listener,
 
     /// Limit the max number of connections.
     ///
@@ -290,6 +511,14 @@ struct ConnectionListener<RP> {
     request_processor: RP,
 
     rpc_hooks: Arc<Vec<Box<dyn RPCHook>>>,
+
+    /// The TLS mode of the listener and the acceptor used for TLS connections, `None` when TLS is
+    /// disabled.
+    tls_acceptor: Option<(TlsMode, TlsAcceptor)>,
+
+    flow_control: ServerFlowControl,
+
+    go_away: watch::Receiver<bool>,
 }
 
 impl<RP: RequestProcessor + Sync + 'static + Clone> ConnectionListener<RP> {
@@ -306,34 +535,75 @@ impl<RP: RequestProcessor + Sync + 'static + Clone> ConnectionListener<RP> {
             // Accept a new socket. This will attempt to perform error handling.
             // The `accept` method internally attempts to recover errors, so an
             // error here is non-recoverable.
-            let (socket, remote_addr) = self.accept().await?;
+            let (stream, local_addr, remote_addr) = self.accept().await?;
             info!("Accepted connection, client ip:{}", remote_addr);
-            socket.set_nodelay(true).expect("set nodelay failed");
-
-            let response_table = ArcMut::new(HashMap::with_capacity(128));
-            let channel = Channel::new(
-                socket.local_addr()?,
-                remote_addr,
-                Connection::new(socket),
-                response_table.clone(),
-            );
-            //create per connection handler state
-            let mut handler = ConnectionHandler {
-                request_processor: self.request_processor.clone(),
-                //connection: Connection::new(socket, remote_addr),
-                connection_handler_context: ArcMut::new(ConnectionHandlerContextWrapper {
-                    // connection: Connection::new(socket),
-                    channel: channel.clone(),
-                }),
-                channel,
-                shutdown: Shutdown::new(self.notify_shutdown.subscribe()),
-                _shutdown_complete: self.shutdown_complete_tx.clone(),
-                conn_disconnect_notify: self.conn_disconnect_notify.clone(),
-                rpc_hooks: self.rpc_hooks.clone(),
-                response_table,
-            };
+
+            let request_processor = self.request_processor.clone();
+            let shutdown = Shutdown::new(self.notify_shutdown.subscribe());
+            let shutdown_complete = self.shutdown_complete_tx.clone();
+            let conn_disconnect_notify = self.conn_disconnect_notify.clone();
+            let rpc_hooks = self.rpc_hooks.clone();
+            let tls_acceptor = self.tls_acceptor.clone();
+            let flow_control = self.flow_control.clone();
+            let go_away = self.go_away.clone();
 
             tokio::spawn(async move {
+                // the handshake is done in the connection task so that a slow client does not
+                // block the listener
+                let stream = match (tls_acceptor, stream) {
+                    (Some((tls_mode, acceptor)), ConnectionStream::Tcp(socket)) => {
+                        match tls::accept(&acceptor, tls_mode, socket).await {
+                            Ok(stream) => stream,
+                            Err(err) => {
+                                warn!(
+                                    "The client[IP={}] is closed, TLS mode: {:?}, cause: {}",
+                                    remote_addr, tls_mode, err
+                                );
+                                drop(permit);
+                                return;
+                            }
+                        }
+                    }
+                    // only TCP connections can be upgraded, the server refuses to listen on
+                    // other transports when enforcing TLS
+                    (Some((TlsMode::Enforcing, _)), _) => {
+                        warn!(
+                            "The client[IP={}] is closed, only TCP can be used with TLS enforced",
+                            remote_addr
+                        );
+                        drop(permit);
+                        return;
+                    }
+                    (_, stream) => stream,
+                };
+
+                let response_table = Arc::new(Mutex::new(HashMap::with_capacity(128)));
+                let connection = Connection::new(stream)
+                    .with_write_buffer_water_mark(
+                        flow_control.write_buffer_low_water_mark,
+                        flow_control.write_buffer_high_water_mark,
+                    )
+                    .with_body_compression(flow_control.body_compression);
+                let channel =
+                    Channel::new(local_addr, remote_addr, connection, response_table.clone());
+                let metrics = flow_control.metrics.clone();
+                metrics.inc_connection();
+                //create per connection handler state
+                let mut handler = ConnectionHandler {
+                    request_processor,
+                    connection_handler_context: ArcMut::new(ConnectionHandlerContextWrapper {
+                        channel: channel.clone(),
+                    }),
+                    channel,
+                    shutdown,
+                    _shutdown_complete: shutdown_complete,
+                    conn_disconnect_notify,
+                    rpc_hooks,
+                    response_table,
+                    flow_control,
+                    go_away,
+                };
+
                 if let Err(err) = handler.handle().await {
                     error!(cause = ?err, "connection error");
                 }
@@ -341,16 +611,16 @@ impl<RP: RequestProcessor + Sync + 'static + Clone> ConnectionListener<RP> {
                     "The client[IP={}] disconnected from the remoting_server.",
                     remote_addr
                 );
-                /*  if let Some(ref sender) = handler.conn_disconnect_notify {
-                    let _ = sender.send(remote_addr);
-                }*/
+                // the requests sent to the client are never answered now
+                fail_response_table(&mut handler.response_table.lock(), &remote_addr.to_string());
                 drop(permit);
                 drop(handler);
+                metrics.dec_connection();
             });
         }
     }
 
-    async fn accept(&mut self) -> anyhow::Result<(TcpStream, SocketAddr)> {
+    async fn accept(&mut self) -> anyhow::Result<(ConnectionStream, SocketAddr, SocketAddr)> {
         let mut backoff = 1;
 
         // Try to accept a few times
@@ -358,7 +628,7 @@ impl<RP: RequestProcessor + Sync + 'static + Clone> ConnectionListener<RP> {
             // Perform the accept operation. If a socket is successfully
             // accepted, return it. Otherwise, save the error.
             match self.listener.accept().await {
-                Ok((socket, remote_addr)) => return Ok((socket, remote_addr)),
+                Ok(accepted) => return Ok(accepted),
                 Err(err) => {
                     if backoff > 64 {
                         // Accept has failed too many times. Return the error.
@@ -378,6 +648,8 @@ impl<RP: RequestProcessor + Sync + 'static + Clone> ConnectionListener<RP> {
 
 pub struct RocketMQServer<RP> {
     config: Arc<ServerConfig>,
+    rpc_hooks: Vec<Box<dyn RPCHook>>,
+    metrics: Arc<RemotingServerMetrics>,
     _phantom_data: std::marker::PhantomData<RP>,
 }
 
@@ -385,69 +657,153 @@ impl<RP> RocketMQServer<RP> {
     pub fn new(config: Arc<ServerConfig>) -> Self {
         Self {
             config,
+            rpc_hooks: vec![],
+            metrics: Default::default(),
             _phantom_data: std::marker::PhantomData,
         }
     }
+
+    /// The counters of the server, they stay valid after the server is started.
+    pub fn metrics(&self) -> Arc<RemotingServerMetrics> {
+        self.metrics.clone()
+    }
+
+    /// Registers a hook run around every request the server processes, hooks run in
+    /// registration order.
+    pub fn register_rpc_hook(&mut self, hook: Box<dyn RPCHook>) {
+        self.rpc_hooks.push(hook);
+    }
 }
 
 impl<RP: RequestProcessor + Sync + 'static + Clone> RocketMQServer<RP> {
-    pub async fn run(&self, request_processor: RP) {
-        let listener = TcpListener::bind(&format!(
-            "{}:{}",
-            self.config.bind_address, self.config.listen_port
-        ))
-        .await
-        .unwrap();
-        info!(
-            "Bind local address: {}",
-            format!("{}:{}", self.config.bind_address, self.config.listen_port)
-        );
+    /// Serves until a shutdown signal is received, fails if the listener cannot be bound or the
+    /// TLS context cannot be created from the config.
+    pub async fn run(self, request_processor: RP) -> crate::Result<()> {
+        let bind_address = format!("{}:{}", self.config.bind_address, self.config.listen_port);
+        let listener = TcpListener::bind(&bind_address).await.map_err(|err| {
+            error!(
+                "Failed to bind the remoting server to {}: {}",
+                bind_address, err
+            );
+            err
+        })?;
+        info!("Bind local address: {}", bind_address);
+        let tls_config = &self.config.tls_config;
+        let listener = match &self.config.unix_socket_path {
+            // Unix domain socket connections cannot be encrypted
+            Some(path) if tls_config.server_mode == TlsMode::Enforcing => {
+                error!(
+                    "Refuse to listen on the Unix domain socket {} in TLS enforcing mode",
+                    path
+                );
+                return Err(RemotingError::TlsError(format!(
+                    "Unix domain socket {} cannot be used in TLS enforcing mode",
+                    path
+                )));
+            }
+            Some(path) => {
+                let listener = Listener::from(listener)
+                    .with_unix_socket(path)
+                    .map_err(|err| {
+                        error!(
+                            "Failed to bind the Unix domain socket {} of the remoting server: {}",
+                            path, err
+                        );
+                        err
+                    })?;
+                info!("Bind Unix domain socket: {}", path);
+                listener
+            }
+            None => Listener::from(listener),
+        };
+        let tls_acceptor = match tls_config.server_mode {
+            TlsMode::Disabled => None,
+            tls_mode => {
+                let acceptor = tls::build_server_acceptor(tls_config).map_err(|err| {
+                    error!(
+                        "Failed to create TLS context for the remoting server: {}",
+                        err
+                    );
+                    err
+                })?;
+                info!("TLS enabled for the remoting server, mode: {:?}", tls_mode);
+                Some((tls_mode, acceptor))
+            }
+        };
+        let flow_control = ServerFlowControl::new(&self.config, self.metrics);
         let (notify_conn_disconnect, _) = broadcast::channel::<SocketAddr>(100);
         run(
             listener,
             wait_for_signal(),
             request_processor,
             Some(notify_conn_disconnect),
-            vec![],
+            self.rpc_hooks,
+            tls_acceptor,
+            flow_control,
         )
         .await;
+        Ok(())
     }
 }
 
 pub async fn run<RP: RequestProcessor + Sync + 'static + Clone>(
-    listener: TcpListener,
+    listener: impl Into<Listener>,
     shutdown: impl Future,
     request_processor: RP,
     conn_disconnect_notify: Option<broadcast::Sender<SocketAddr>>,
     rpc_hooks: Vec<Box<dyn RPCHook>>,
+    tls_acceptor: Option<(TlsMode, TlsAcceptor)>,
+    flow_control: ServerFlowControl,
 ) {
     let (notify_shutdown, _) = broadcast::channel(1);
     let (shutdown_complete_tx, mut shutdown_complete_rx) = mpsc::channel(1);
+    let (notify_go_away, go_away) = watch::channel(false);
+    let graceful_shutdown_wait_time = flow_control.graceful_shutdown_wait_time;
+    let metrics = flow_control.metrics.clone();
     // Initialize the connection listener state
     let mut listener = ConnectionListener {
-        listener,
+        listener: listener.into(),
         notify_shutdown,
         shutdown_complete_tx,
         conn_disconnect_notify,
         limit_connections: Arc::new(Semaphore::new(DEFAULT_MAX_CONNECTIONS)),
         request_processor,
         rpc_hooks: Arc::new(rpc_hooks),
+        tls_acceptor,
+        flow_control,
+        go_away,
     };
 
-    tokio::select! {
-        res = listener.run() => {
-            // If an error is received here, accepting connections from the TCP
-            // listener failed multiple times and the remoting_server is giving up and
-            // shutting down.
-            //
-            // Errors encountered when handling individual connections do not
-            // bubble up to this point.
-            if let Err(err) = res {
-                error!(cause = %err, "failed to accept");
+    // the accept loop keeps running while the clients drain
+    {
+        let accept = listener.run();
+        tokio::pin!(accept);
+        tokio::select! {
+            res = &mut accept => {
+                // If an error is received here, accepting connections from the TCP
+                // listener failed multiple times and the remoting_server is giving up and
+                // shutting down.
+                //
+                // Errors encountered when handling individual connections do not
+                // bubble up to this point.
+                if let Err(err) = res {
+                    error!(cause = %err, "failed to accept");
+                }
+            }
+            _ = shutdown => {
+                if let Some(wait_time) = graceful_shutdown_wait_time {
+                    info!(
+                        "Shutdown gracefully, announce go away and wait at most {:?} for the clients to drain",
+                        wait_time
+                    );
+                    let _ = notify_go_away.send(true);
+                    tokio::select! {
+                        _ = &mut accept => {}
+                        _ = wait_for_drained(&metrics, wait_time) => {}
+                    }
+                }
+                info!("Shutdown now.....");
             }
-        }
-        _ = shutdown => {
-            info!("Shutdown now.....");
         }
     }
 
@@ -462,6 +818,16 @@ pub async fn run<RP: RequestProcessor + Sync + 'static + Clone>(
     let _ = shutdown_complete_rx.recv().await;
 }
 
+/// Waits until all the connections are closed by the clients, at most `wait_time`.
+async fn wait_for_drained(metrics: &RemotingServerMetrics, wait_time: Duration) {
+    let _ = time::timeout(wait_time, async {
+        while metrics.active_connections() > 0 {
+            time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await;
+}
+
 #[derive(Debug)]
 pub(crate) struct Shutdown {
     /// `true` if the shutdown signal has been received
@@ -500,3 +866,232 @@ impl Shutdown {
         self.is_shutdown = true;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rocketmq_common::common::server::tls_config::TlsConfig;
+    use tokio::net::TcpStream;
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    #[derive(Clone)]
+    struct SlowProcessor;
+
+    impl RequestProcessor for SlowProcessor {
+        async fn process_request(
+            &mut self,
+            _channel: Channel,
+            _ctx: ConnectionHandlerContext,
+            _request: RemotingCommand,
+        ) -> Result<Option<RemotingCommand>> {
+            time::sleep(Duration::from_millis(300)).await;
+            Ok(Some(RemotingCommand::create_response_command()))
+        }
+    }
+
+    async fn start_server(
+        config: ServerConfig,
+    ) -> (SocketAddr, Arc<RemotingServerMetrics>, oneshot::Sender<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let metrics = Arc::new(RemotingServerMetrics::default());
+        let flow_control = ServerFlowControl::new(&config, metrics.clone());
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        tokio::spawn(run(
+            listener,
+            shutdown_rx,
+            SlowProcessor,
+            None,
+            vec![],
+            None,
+            flow_control,
+        ));
+        (addr, metrics, shutdown_tx)
+    }
+
+    async fn connect(addr: SocketAddr) -> Connection {
+        Connection::new(TcpStream::connect(addr).await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn rejects_requests_over_async_permits() {
+        let (addr, metrics, _shutdown) = start_server(ServerConfig {
+            server_async_semaphore_value: 1,
+            server_permit_timeout_millis: 50,
+            ..Default::default()
+        })
+        .await;
+        let mut first = connect(addr).await;
+        let mut second = connect(addr).await;
+        first
+            .send_command(RemotingCommand::create_remoting_command(10).set_opaque(1))
+            .await
+            .unwrap();
+        time::sleep(Duration::from_millis(50)).await;
+        second
+            .send_command(RemotingCommand::create_remoting_command(10).set_opaque(2))
+            .await
+            .unwrap();
+
+        let rejected = second.receive_command().await.unwrap().unwrap();
+        assert_eq!(rejected.code(), ResponseCode::SystemBusy as i32);
+        assert_eq!(rejected.remark().unwrap().as_str(), OVERLOAD_REMARK);
+        let accepted = first.receive_command().await.unwrap().unwrap();
+        assert_eq!(accepted.code(), ResponseCode::Success as i32);
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.rejected_requests, 1);
+        assert_eq!(snapshot.total_requests, 1);
+        assert_eq!(snapshot.active_connections, 2);
+    }
+
+    #[tokio::test]
+    async fn announces_go_away_on_graceful_shutdown() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let flow_control = ServerFlowControl::new(
+            &ServerConfig {
+                enable_shutdown_gracefully: true,
+                shutdown_wait_time_seconds: 10,
+                ..Default::default()
+            },
+            Default::default(),
+        );
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let server = tokio::spawn(run(
+            listener,
+            shutdown_rx,
+            SlowProcessor,
+            None,
+            vec![],
+            None,
+            flow_control,
+        ));
+        let mut connection = connect(addr).await;
+        time::sleep(Duration::from_millis(50)).await;
+        shutdown_tx.send(()).unwrap();
+
+        let announcement = connection.receive_command().await.unwrap().unwrap();
+        assert_eq!(announcement.code(), ResponseCode::GoAway as i32);
+        assert_eq!(announcement.opaque(), GO_AWAY_OPAQUE);
+        connection
+            .send_command(RemotingCommand::create_remoting_command(10).set_opaque(7))
+            .await
+            .unwrap();
+        let response = connection.receive_command().await.unwrap().unwrap();
+        assert_eq!(response.code(), ResponseCode::GoAway as i32);
+        assert_eq!(response.opaque(), 7);
+
+        // the server stops once the client closed its channel, before the wait time is over
+        connection.close().await.unwrap();
+        time::timeout(Duration::from_secs(3), server)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn closes_idle_channels() {
+        let (addr, metrics, _shutdown) = start_server(ServerConfig {
+            server_channel_max_idle_time_seconds: 1,
+            ..Default::default()
+        })
+        .await;
+        let mut connection = connect(addr).await;
+        let closed = time::timeout(Duration::from_secs(3), connection.receive_command())
+            .await
+            .unwrap();
+        assert!(closed.is_none());
+        time::sleep(Duration::from_millis(50)).await;
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.idle_closed_connections, 1);
+        assert_eq!(snapshot.active_connections, 0);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn serves_unix_domain_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("remoting.sock");
+        let path = path.to_str().unwrap();
+        let listener = Listener::from(TcpListener::bind("127.0.0.1:0").await.unwrap())
+            .with_unix_socket(path)
+            .unwrap();
+        let metrics = Arc::new(RemotingServerMetrics::default());
+        let flow_control = ServerFlowControl::new(&ServerConfig::default(), metrics.clone());
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let server = tokio::spawn(run(
+            listener,
+            shutdown_rx,
+            SlowProcessor,
+            None,
+            vec![],
+            None,
+            flow_control,
+        ));
+
+        let (stream, _, _) = crate::net::transport::connect_unix(path).await.unwrap();
+        let mut connection = Connection::new(stream);
+        connection
+            .send_command(RemotingCommand::create_remoting_command(10).set_opaque(3))
+            .await
+            .unwrap();
+        let response = connection.receive_command().await.unwrap().unwrap();
+        assert_eq!(response.code(), ResponseCode::Success as i32);
+        assert_eq!(response.opaque(), 3);
+        assert_eq!(metrics.snapshot().active_connections, 1);
+
+        // the socket file is removed once the server is stopped
+        connection.close().await.unwrap();
+        shutdown_tx.send(()).unwrap();
+        time::timeout(Duration::from_secs(3), server)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!std::path::Path::new(path).exists());
+    }
+
+    #[tokio::test]
+    async fn run_fails_on_invalid_tls_config() {
+        let config = ServerConfig {
+            listen_port: 0,
+            bind_address: "127.0.0.1".to_string(),
+            tls_config: TlsConfig {
+                server_mode: TlsMode::Enforcing,
+                server_cert_path: None,
+                server_key_path: None,
+                ..TlsConfig::default()
+            },
+            ..ServerConfig::default()
+        };
+        let server = RocketMQServer::<SlowProcessor>::new(Arc::new(config));
+        let result = time::timeout(Duration::from_secs(3), server.run(SlowProcessor))
+            .await
+            .unwrap();
+        assert!(matches!(result, Err(RemotingError::TlsError(_))));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn run_refuses_unix_socket_in_tls_enforcing_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("enforcing.sock");
+        let config = ServerConfig {
+            listen_port: 0,
+            bind_address: "127.0.0.1".to_string(),
+            tls_config: TlsConfig {
+                server_mode: TlsMode::Enforcing,
+                ..TlsConfig::default()
+            },
+            unix_socket_path: Some(path.to_string_lossy().to_string()),
+            ..ServerConfig::default()
+        };
+        let server = RocketMQServer::<SlowProcessor>::new(Arc::new(config));
+        let result = time::timeout(Duration::from_secs(3), server.run(SlowProcessor))
+            .await
+            .unwrap();
+        assert!(matches!(result, Err(RemotingError::TlsError(_))));
+        assert!(!path.exists());
+    }
+}
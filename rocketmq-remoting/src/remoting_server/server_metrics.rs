@@ -0,0 +1,146 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Counters of a remoting server, shared by all of its connections.
+#[derive(Debug, Default)]
+pub struct RemotingServerMetrics {
+    active_connections: AtomicI64,
+    total_connections: AtomicU64,
+    idle_closed_connections: AtomicU64,
+    in_flight_oneway_requests: AtomicI64,
+    in_flight_async_requests: AtomicI64,
+    total_requests: AtomicU64,
+    rejected_requests: AtomicU64,
+    read_paused_times: AtomicU64,
+}
+
+/// A point in time copy of [`RemotingServerMetrics`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemotingServerMetricsSnapshot {
+    pub active_connections: i64,
+    pub total_connections: u64,
+    pub idle_closed_connections: u64,
+    pub in_flight_oneway_requests: i64,
+    pub in_flight_async_requests: i64,
+    pub total_requests: u64,
+    pub rejected_requests: u64,
+    pub read_paused_times: u64,
+}
+
+impl RemotingServerMetrics {
+    #[inline]
+    pub fn inc_connection(&self) {
+        self.active_connections.fetch_add(1, Ordering::Relaxed);
+        self.total_connections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn dec_connection(&self) {
+        self.active_connections.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn active_connections(&self) -> i64 {
+        self.active_connections.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn inc_idle_closed_connections(&self) {
+        self.idle_closed_connections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn inc_in_flight_requests(&self, oneway: bool) {
+        self.total_requests.fetch_add(1, Ordering::Relaxed);
+        self.in_flight_requests(oneway)
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn dec_in_flight_requests(&self, oneway: bool) {
+        self.in_flight_requests(oneway)
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn inc_rejected_requests(&self) {
+        self.rejected_requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn inc_read_paused_times(&self) {
+        self.read_paused_times.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> RemotingServerMetricsSnapshot {
+        RemotingServerMetricsSnapshot {
+            active_connections: self.active_connections.load(Ordering::Relaxed),
+            total_connections: self.total_connections.load(Ordering::Relaxed),
+            idle_closed_connections: self.idle_closed_connections.load(Ordering::Relaxed),
+            in_flight_oneway_requests: self.in_flight_oneway_requests.load(Ordering::Relaxed),
+            in_flight_async_requests: self.in_flight_async_requests.load(Ordering::Relaxed),
+            total_requests: self.total_requests.load(Ordering::Relaxed),
+            rejected_requests: self.rejected_requests.load(Ordering::Relaxed),
+            read_paused_times: self.read_paused_times.load(Ordering::Relaxed),
+        }
+    }
+
+    #[inline]
+    fn in_flight_requests(&self, oneway: bool) -> &AtomicI64 {
+        if oneway {
+            &self.in_flight_oneway_requests
+        } else {
+            &self.in_flight_async_requests
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_reflects_counters() {
+        let metrics = RemotingServerMetrics::default();
+        metrics.inc_connection();
+        metrics.inc_connection();
+        metrics.dec_connection();
+        metrics.inc_in_flight_requests(true);
+        metrics.inc_in_flight_requests(false);
+        metrics.dec_in_flight_requests(false);
+        metrics.inc_rejected_requests();
+        metrics.inc_idle_closed_connections();
+        metrics.inc_read_paused_times();
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.active_connections, 1);
+        assert_eq!(snapshot.total_connections, 2);
+        assert_eq!(snapshot.in_flight_oneway_requests, 1);
+        assert_eq!(snapshot.in_flight_async_requests, 0);
+        assert_eq!(snapshot.total_requests, 2);
+        assert_eq!(snapshot.rejected_requests, 1);
+        assert_eq!(snapshot.idle_closed_connections, 1);
+        assert_eq!(snapshot.read_paused_times, 1);
+    }
+}
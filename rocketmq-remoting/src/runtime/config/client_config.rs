@@ -16,6 +16,7 @@
  */
 
 use lazy_static::lazy_static;
+use rocketmq_common::common::server::tls_config::TlsConfig;
 
 use crate::runtime::config::net_system_config::NetSystemConfig;
 
@@ -37,7 +38,8 @@ pub struct TokioClientConfig {
     pub client_socket_rcv_buf_size: i32,
     pub client_pooled_byte_buf_allocator_enable: bool,
     pub client_close_socket_if_timeout: bool,
-    //pub use_tls: bool,
+    pub use_tls: bool,
+    pub tls_config: TlsConfig,
     pub socks_proxy_config: String,
     pub write_buffer_high_water_mark: i32,
     pub write_buffer_low_water_mark: i32,
@@ -46,10 +48,16 @@ pub struct TokioClientConfig {
     pub max_reconnect_interval_time_seconds: i64,
     pub enable_reconnect_for_go_away: bool,
     pub enable_transparent_retry: bool,
+    /// Request bodies at least this large are compressed for servers accepting compressed
+    /// bodies, `0` disables remoting body compression.
+    pub body_compress_threshold: usize,
+    /// One of `LZ4`, `ZSTD` or `ZLIB`.
+    pub body_compress_type: String,
 }
 
 impl Default for TokioClientConfig {
     fn default() -> Self {
+        let tls_config = TlsConfig::default();
         TokioClientConfig {
             client_worker_threads: NET_SYSTEM_CONFIG.client_worker_size,
             client_callback_executor_threads: num_cpus::get(),
@@ -62,7 +70,8 @@ impl Default for TokioClientConfig {
             client_socket_rcv_buf_size: NET_SYSTEM_CONFIG.socket_rcvbuf_size,
             client_pooled_byte_buf_allocator_enable: false,
             client_close_socket_if_timeout: NET_SYSTEM_CONFIG.client_close_socket_if_timeout,
-            //use_tls: TlsSystemConfig::is_tls_enabled(),
+            use_tls: tls_config.client_enable,
+            tls_config,
             socks_proxy_config: "{}".to_string(),
             write_buffer_high_water_mark: NET_SYSTEM_CONFIG.write_buffer_high_water_mark_value,
             write_buffer_low_water_mark: NET_SYSTEM_CONFIG.write_buffer_low_water_mark,
@@ -71,6 +80,8 @@ impl Default for TokioClientConfig {
             max_reconnect_interval_time_seconds: 60,
             enable_reconnect_for_go_away: true,
             enable_transparent_retry: true,
+            body_compress_threshold: 4 * 1024,
+            body_compress_type: "LZ4".to_string(),
         }
     }
 }
@@ -29,6 +29,7 @@ pub mod message_result;
 pub mod message_status_enum;
 pub mod message_store;
 pub mod put_message_context;
+pub mod query_message_condition;
 pub mod query_message_result;
 pub mod select_result;
 pub mod store_checkpoint;
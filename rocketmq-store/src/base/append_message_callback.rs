@@ -298,7 +298,13 @@ impl AppendMessageCallback for DefaultAppendMessageCallback {
                     .to_be_bytes(),
             );
             if enabled_append_prop_crc {
-                let _check_size = msg_len - self.crc32_reserved_length;
+                // 18 CRC32
+                let check_size = (msg_len - self.crc32_reserved_length) as usize;
+                let crc = crc32(&messages_byte_buffer[msg_pos..msg_pos + check_size]);
+                create_crc32(
+                    &mut messages_byte_buffer[msg_pos + check_size..msg_pos + msg_len as usize],
+                    crc,
+                );
             }
             put_message_context.get_phy_pos_mut()[index] = phy_pos;
             msg_num += 1;
@@ -0,0 +1,71 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+
+/// Condition of a paged message lookup over the index files.
+///
+/// A message matches when it belongs to `topic`, carries one of `keys` (either as a message key
+/// or as its unique key), has been stored within `[begin_timestamp, end_timestamp]` and, when
+/// `tags` is not empty, is tagged with one of them.
+#[derive(Debug, Clone, Default)]
+pub struct QueryMessageCondition {
+    pub topic: CheetahString,
+    pub keys: Vec<CheetahString>,
+    pub tags: Vec<CheetahString>,
+    pub begin_timestamp: i64,
+    pub end_timestamp: i64,
+    pub max_num: i32,
+    /// Only messages stored before this commit log offset are returned, a value not greater
+    /// than zero starts from the newest message.
+    pub cursor: i64,
+}
+
+impl QueryMessageCondition {
+    pub fn is_tag_matched(&self, tag: Option<&CheetahString>) -> bool {
+        if self.tags.is_empty() || self.tags.iter().any(|t| t == "*") {
+            return true;
+        }
+        tag.is_some_and(|tag| self.tags.contains(tag))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tag_filter_matches_any_tag_when_empty_or_wildcard() {
+        let mut condition = QueryMessageCondition::default();
+        assert!(condition.is_tag_matched(None));
+        condition.tags = vec![CheetahString::from_static_str("*")];
+        assert!(condition.is_tag_matched(Some(&CheetahString::from_static_str("TagA"))));
+    }
+
+    #[test]
+    fn tag_filter_matches_listed_tags_only() {
+        let condition = QueryMessageCondition {
+            tags: vec![
+                CheetahString::from_static_str("TagA"),
+                CheetahString::from_static_str("TagB"),
+            ],
+            ..Default::default()
+        };
+        assert!(condition.is_tag_matched(Some(&CheetahString::from_static_str("TagB"))));
+        assert!(!condition.is_tag_matched(Some(&CheetahString::from_static_str("TagC"))));
+        assert!(!condition.is_tag_matched(None));
+    }
+}
@@ -25,6 +25,9 @@ pub struct QueryMessageResult {
     pub index_last_update_timestamp: i64,
     pub index_last_update_phyoffset: i64,
     pub buffer_total_size: i32,
+    /// Cursor to continue a paged query from, not greater than zero when there are no more
+    /// messages.
+    pub next_cursor: i64,
 }
 
 impl QueryMessageResult {
@@ -35,8 +38,7 @@ impl QueryMessageResult {
 
         let mut bytes_mut = BytesMut::with_capacity(self.buffer_total_size as usize);
         for msg in self.message_maped_list.iter() {
-            let data = &msg.mapped_file.as_ref().unwrap().get_mapped_file()
-                [msg.start_offset as usize..(msg.start_offset + msg.size as u64) as usize];
+            let data = msg.get_buffer();
             bytes_mut.extend_from_slice(data);
         }
         Some(bytes_mut.freeze())
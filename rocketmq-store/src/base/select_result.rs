@@ -51,11 +51,14 @@ impl Default for SelectMappedBufferResult {
 }
 
 impl SelectMappedBufferResult {
-    /// Returns the buffer.
+    /// Returns the buffer, the data read from the tiered storage is not backed by a mapped file.
     pub fn get_buffer(&self) -> &[u8] {
-        self.mapped_file.as_ref().unwrap().get_mapped_file()
-            [self.start_offset as usize..(self.start_offset + self.size as u64) as usize]
-            .as_ref()
+        match (&self.mapped_file, &self.bytes) {
+            (None, Some(bytes)) => bytes.as_ref(),
+            _ => self.mapped_file.as_ref().unwrap().get_mapped_file()
+                [self.start_offset as usize..(self.start_offset + self.size as u64) as usize]
+                .as_ref(),
+        }
     }
 
     pub fn get_buffer_slice_mut(&self) -> &mut [u8] {
@@ -65,9 +68,12 @@ impl SelectMappedBufferResult {
     }
 
     pub fn get_bytes(&self) -> Option<Bytes> {
-        if self.size <= 0 || self.mapped_file.is_none() {
+        if self.size <= 0 {
             return None;
         }
+        if self.mapped_file.is_none() {
+            return self.bytes.clone();
+        }
         Some(BytesMut::from(self.get_buffer()).freeze())
     }
 
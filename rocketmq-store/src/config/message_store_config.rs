@@ -206,6 +206,14 @@ pub struct MessageStoreConfig {
     pub enable_rocksdb_log: bool,
     pub topic_queue_lock_num: usize,
     pub max_filter_message_size: i32,
+    pub rebuild_logic_files_on_startup: bool,
+    pub tiered_storage_enable: bool,
+    /// Directory of the local directory tiered storage backend, used unless another backend is
+    /// given to the store, `tiered` under the store root directory by default.
+    pub tiered_storage_path: Option<CheetahString>,
+    /// Hours a segment is kept in the tiered storage after being uploaded.
+    pub tiered_file_reserved_time: usize,
+    pub tiered_upload_interval: usize,
 }
 
 impl Default for MessageStoreConfig {
@@ -262,8 +270,8 @@ impl Default for MessageStoreConfig {
             flush_interval_commit_log: 500,
             commit_interval_commit_log: 200,
             max_recovery_commit_log_files: 0,
-            disk_space_warning_level_ratio: 0,
-            disk_space_clean_forcibly_ratio: 0,
+            disk_space_warning_level_ratio: 90,
+            disk_space_clean_forcibly_ratio: 85,
             use_reentrant_lock_when_put_message: false,
             flush_commit_log_timed: true,
             flush_interval_consume_queue: 1000,
@@ -274,8 +282,8 @@ impl Default for MessageStoreConfig {
             redelete_hanged_file_interval: 1000 * 120,
             delete_when: "04".to_string(),
             disk_max_used_space_ratio: 75,
-            file_reserved_time: 0,
-            delete_file_batch_max: 0,
+            file_reserved_time: 72,
+            delete_file_batch_max: 10,
             put_msg_index_hight_water: 0,
             max_message_size: 1024 * 1024 * 4,
             check_crc_on_recover: false,
@@ -309,14 +317,14 @@ impl Default for MessageStoreConfig {
             slave_timeout: 0,
             message_delay_level: "".to_string(),
             flush_delay_offset_interval: 0,
-            clean_file_forcibly_enable: false,
+            clean_file_forcibly_enable: true,
             warm_mapped_file_enable: false,
             offset_check_in_slave: false,
             debug_lock_enable: false,
             duplication_enable: false,
             disk_fall_recorded: false,
             os_page_cache_busy_timeout_mills: 1000,
-            default_query_max_num: 0,
+            default_query_max_num: 32,
             transient_store_pool_enable: false,
             transient_store_pool_size: 0,
             fast_fail_if_no_buffer_in_store_pool: false,
@@ -370,8 +378,8 @@ impl Default for MessageStoreConfig {
             ha_max_time_slave_not_catchup: 0,
             sync_master_flush_offset_when_startup: false,
             max_checksum_range: 0,
-            replicas_per_disk_partition: 0,
-            logical_disk_space_clean_forcibly_threshold: 0.0,
+            replicas_per_disk_partition: 1,
+            logical_disk_space_clean_forcibly_threshold: 0.8,
             max_slave_resend_length: 0,
             sync_from_last_file: false,
             async_learner: false,
@@ -392,6 +400,11 @@ impl Default for MessageStoreConfig {
             enable_rocksdb_log: false,
             topic_queue_lock_num: 32,
             max_filter_message_size: 16000,
+            rebuild_logic_files_on_startup: false,
+            tiered_storage_enable: false,
+            tiered_storage_path: None,
+            tiered_file_reserved_time: 14 * 24,
+            tiered_upload_interval: 10 * 1000,
         }
     }
 }
@@ -407,6 +420,16 @@ impl MessageStoreConfig {
         self.store_path_commit_log.clone().unwrap().to_string()
     }
 
+    pub fn get_tiered_storage_path(&self) -> String {
+        match &self.tiered_storage_path {
+            Some(path) => path.to_string(),
+            None => PathBuf::from(self.store_path_root_dir.to_string())
+                .join("tiered")
+                .to_string_lossy()
+                .to_string(),
+        }
+    }
+
     pub fn is_enable_rocksdb_store(&self) -> bool {
         self.store_type == StoreType::RocksDB
     }
@@ -1102,6 +1125,29 @@ impl MessageStoreConfig {
             "maxFilterMessageSize".into(),
             self.max_filter_message_size.to_string(),
         );
+        properties.insert(
+            "rebuildLogicFilesOnStartup".into(),
+            self.rebuild_logic_files_on_startup.to_string(),
+        );
+        properties.insert(
+            "tieredStorageEnable".into(),
+            self.tiered_storage_enable.to_string(),
+        );
+        properties.insert(
+            "tieredStoragePath".into(),
+            self.tiered_storage_path
+                .clone()
+                .unwrap_or_default()
+                .to_string(),
+        );
+        properties.insert(
+            "tieredFileReservedTime".into(),
+            self.tiered_file_reserved_time.to_string(),
+        );
+        properties.insert(
+            "tieredUploadInterval".into(),
+            self.tiered_upload_interval.to_string(),
+        );
         properties
             .into_iter()
             .map(|(k, v)| (k.into(), v.into()))
@@ -21,10 +21,15 @@ use std::path::PathBuf;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
+use bytes::Buf;
 use cheetah_string::CheetahString;
 use parking_lot::RwLock;
+use rocketmq_common::TimeUtils::get_current_millis;
 use rocketmq_common::UtilAll::offset_to_file_name;
+use tracing::error;
 use tracing::info;
 use tracing::warn;
 
@@ -108,7 +113,19 @@ impl MappedFileQueue {
 
     #[inline]
     pub fn check_self(&self) {
-        println!("mapped_file_queue check self unimplemented")
+        let mapped_files = self.mapped_files.read();
+        for files in mapped_files.windows(2) {
+            if files[1].get_file_from_offset() - files[0].get_file_from_offset()
+                != self.mapped_file_size
+            {
+                error!(
+                    "[BUG]The mappedFile queue's data is damaged, the adjacent mappedFile's \
+                     offset don't match. pre file {}, cur file {}",
+                    files[0].get_file_name(),
+                    files[1].get_file_name()
+                );
+            }
+        }
     }
 
     #[inline]
@@ -280,6 +297,7 @@ impl MappedFileQueue {
                 }
             }
         }
+        self.delete_expired_file(will_remove_files);
     }
 
     #[inline]
@@ -305,15 +323,126 @@ impl MappedFileQueue {
     }
 
     #[inline]
-    pub(crate) fn delete_expired_file(&mut self, files: Vec<Arc<DefaultMappedFile>>) {
-        let mut files = files;
-        let read_guard = self.mapped_files.read();
+    pub(crate) fn delete_expired_file(&self, files: Vec<Arc<DefaultMappedFile>>) {
         if !files.is_empty() {
-            files.retain(|mf| read_guard.contains(mf));
             self.mapped_files.write().retain(|mf| !files.contains(mf));
         }
     }
 
+    /// Deletes the files last modified more than `expired_time` ms ago, or all but the last file
+    /// when `clean_immediately` is set. Files are deleted from the head of the queue only, the
+    /// last file is always kept as it is still being written. Files ending after
+    /// `deletable_end_offset` are kept as well.
+    pub fn delete_expired_file_by_time(
+        &self,
+        expired_time: u64,
+        delete_files_interval: u64,
+        interval_forcibly: u64,
+        clean_immediately: bool,
+        delete_file_batch_max: usize,
+        deletable_end_offset: i64,
+    ) -> i32 {
+        let mapped_files = self.mapped_files.read().clone();
+        if mapped_files.is_empty() {
+            return 0;
+        }
+        self.check_self();
+        let last_index = mapped_files.len() - 1;
+        let mut files = Vec::new();
+        for (index, mapped_file) in mapped_files[..last_index].iter().enumerate() {
+            if (mapped_file.get_file_from_offset() + mapped_file.get_file_size()) as i64
+                > deletable_end_offset
+            {
+                break;
+            }
+            let live_max_timestamp = mapped_file.get_last_modified_timestamp() + expired_time;
+            if get_current_millis() < live_max_timestamp && !clean_immediately {
+                // avoid deleting files in the middle
+                break;
+            }
+            if !mapped_file.destroy(interval_forcibly) {
+                break;
+            }
+            files.push(mapped_file.clone());
+            if files.len() >= delete_file_batch_max {
+                break;
+            }
+            if delete_files_interval > 0 && index + 1 < last_index {
+                thread::sleep(Duration::from_millis(delete_files_interval));
+            }
+        }
+        let delete_count = files.len() as i32;
+        self.delete_expired_file(files);
+        delete_count
+    }
+
+    /// Deletes the files of a logic queue whose last unit points before the physical `offset`,
+    /// the last file is always kept.
+    pub fn delete_expired_file_by_offset(&self, offset: i64, unit_size: i32) -> i32 {
+        let mapped_files = self.mapped_files.read().clone();
+        if mapped_files.is_empty() {
+            return 0;
+        }
+        let last_index = mapped_files.len() - 1;
+        let mut files = Vec::new();
+        for mapped_file in &mapped_files[..last_index] {
+            let destroy = if mapped_file.is_available() {
+                match mapped_file.get_bytes((self.mapped_file_size as i32 - unit_size) as usize, 8)
+                {
+                    Some(mut bytes) => {
+                        let max_offset_in_logic_queue = bytes.get_i64();
+                        if max_offset_in_logic_queue < offset {
+                            info!(
+                                "physic min offset {}, logics in current mappedFile max offset \
+                                 {}, delete it",
+                                offset, max_offset_in_logic_queue
+                            );
+                        }
+                        max_offset_in_logic_queue < offset
+                    }
+                    None => break,
+                }
+            } else {
+                // the file was shut down but failed to be deleted before
+                true
+            };
+            if !destroy || !mapped_file.destroy(1000 * 60) {
+                break;
+            }
+            files.push(mapped_file.clone());
+        }
+        let delete_count = files.len() as i32;
+        self.delete_expired_file(files);
+        delete_count
+    }
+
+    /// Retries to delete the first file if it was shut down but not deleted, which happens when
+    /// it was still referenced at deletion time.
+    pub fn retry_delete_first_file(&self, interval_forcibly: u64) -> bool {
+        if let Some(mapped_file) = self.get_first_mapped_file() {
+            if !mapped_file.is_available() {
+                warn!(
+                    "the mappedFile was destroyed once, but still alive, {}",
+                    mapped_file.get_file_name()
+                );
+                if mapped_file.destroy(interval_forcibly) {
+                    info!(
+                        "the mappedFile re delete OK, {}",
+                        mapped_file.get_file_name()
+                    );
+                    self.delete_expired_file(vec![mapped_file]);
+                } else {
+                    warn!(
+                        "the mappedFile re delete failed, {}",
+                        mapped_file.get_file_name()
+                    );
+                }
+                return true;
+            }
+        }
+        false
+    }
+
     #[inline]
     pub fn destroy(&mut self) {
         for mapped_file in self.mapped_files.read().iter() {
@@ -503,4 +632,50 @@ mod tests {
         assert!(queue.load());
         assert_eq!(queue.mapped_files.read().len(), 1);
     }
+
+    #[test]
+    fn test_delete_expired_file_by_time_keeps_last_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        for offset in [0, 1024, 2048] {
+            let file_path = temp_dir.path().join(format!("{:020}", offset));
+            fs::write(&file_path, vec![0u8; 1024]).unwrap();
+        }
+
+        let mut queue = MappedFileQueue {
+            store_path: temp_dir.path().to_string_lossy().into_owned(),
+            mapped_file_size: 1024,
+            ..MappedFileQueue::default()
+        };
+        assert!(queue.load());
+        assert_eq!(queue.get_mapped_files_size(), 3);
+
+        // files are fresh, nothing expires unless cleaning immediately
+        assert_eq!(
+            queue.delete_expired_file_by_time(72 * 60 * 60 * 1000, 0, 1000, false, 10, i64::MAX),
+            0
+        );
+        // files not allowed to be deleted are kept
+        assert_eq!(
+            queue.delete_expired_file_by_time(72 * 60 * 60 * 1000, 0, 1000, true, 10, 1023),
+            0
+        );
+        assert_eq!(
+            queue.delete_expired_file_by_time(72 * 60 * 60 * 1000, 0, 1000, true, 1, i64::MAX),
+            1
+        );
+        assert_eq!(
+            queue.delete_expired_file_by_time(72 * 60 * 60 * 1000, 0, 1000, true, 10, i64::MAX),
+            1
+        );
+        assert_eq!(queue.get_mapped_files_size(), 1);
+        assert_eq!(
+            queue
+                .get_first_mapped_file()
+                .unwrap()
+                .get_file_from_offset(),
+            2048
+        );
+        assert!(!temp_dir.path().join(format!("{:020}", 0)).exists());
+        assert!(!temp_dir.path().join(format!("{:020}", 1024)).exists());
+    }
 }
@@ -18,7 +18,8 @@
 use std::sync::Arc;
 
 use bytes::Buf;
-use bytes::Bytes;
+use bytes::BufMut;
+use bytes::BytesMut;
 use cheetah_string::CheetahString;
 use rocketmq_common::common::hasher::string_hasher::JavaStringHasher;
 use tracing::info;
@@ -76,6 +77,9 @@ impl IndexFile {
             CheetahString::from_slice(file_name),
             file_total_size as u64,
         ));
+        // index file is written randomly by slot and unit position, treat it as a full file so
+        // that the whole mapped area is readable and flushable
+        mapped_file.set_wrote_position(file_total_size as i32);
 
         let index_header = IndexHeader::new(mapped_file.clone());
         let index_file = IndexFile {
@@ -149,11 +153,10 @@ impl IndexFile {
             let slot_pos = key_hash as usize % self.hash_slot_num;
             let abs_slot_pos = INDEX_HEADER_SIZE + slot_pos * HASH_SLOT_SIZE;
 
-            let mut buffer = self
-                .mapped_file
-                .get_data(abs_slot_pos, abs_slot_pos + 4)
-                .unwrap();
-            let mut slot_value = buffer.get_i32();
+            let mut slot_value = match self.mapped_file.get_bytes(abs_slot_pos, HASH_SLOT_SIZE) {
+                Some(mut buffer) => buffer.get_i32(),
+                None => INVALID_INDEX,
+            };
             if slot_value <= INVALID_INDEX || slot_value > self.index_header.get_index_count() {
                 slot_value = INVALID_INDEX;
             }
@@ -172,30 +175,15 @@ impl IndexFile {
                 + self.hash_slot_num * HASH_SLOT_SIZE
                 + self.index_header.get_index_count() as usize * INDEX_SIZE;
 
-            self.mapped_file.append_message_offset_length(
-                &Bytes::copy_from_slice(&key_hash.to_be_bytes()),
-                abs_index_pos,
-                4,
-            );
-            self.mapped_file.append_message_offset_length(
-                &Bytes::copy_from_slice(&phy_offset.to_be_bytes()),
-                abs_index_pos,
-                8,
-            );
-            self.mapped_file.append_message_offset_length(
-                &Bytes::copy_from_slice(&(time_diff as i32).to_be_bytes()),
-                abs_index_pos,
-                4,
-            );
-            self.mapped_file.append_message_offset_length(
-                &Bytes::copy_from_slice(&slot_value.to_be_bytes()),
-                abs_index_pos,
-                4,
-            );
-            self.mapped_file.append_message_offset_length(
-                &Bytes::copy_from_slice(&self.index_header.get_index_count().to_be_bytes()),
+            let mut index_unit = BytesMut::with_capacity(INDEX_SIZE);
+            index_unit.put_i32(key_hash);
+            index_unit.put_i64(phy_offset);
+            index_unit.put_i32(time_diff as i32);
+            index_unit.put_i32(slot_value);
+            self.mapped_file.put_slice(&index_unit, abs_index_pos);
+            self.mapped_file.put_slice(
+                &self.index_header.get_index_count().to_be_bytes(),
                 abs_slot_pos,
-                4,
             );
 
             if self.index_header.get_index_count() <= 1 {
@@ -242,6 +230,11 @@ impl IndexFile {
         self.index_header.get_end_timestamp()
     }
 
+    #[inline]
+    pub fn get_begin_phy_offset(&self) -> i64 {
+        self.index_header.get_begin_phy_offset()
+    }
+
     #[inline]
     pub fn get_end_phy_offset(&self) -> i64 {
         self.index_header.get_end_phy_offset()
@@ -256,6 +249,8 @@ impl IndexFile {
             || end >= begin_timestamp && end <= end_timestamp
     }
 
+    /// Collects the physical offsets indexed under `key` whose store time falls in
+    /// `[begin, end]` and which are lower than `max_phy_offset`, newest first.
     #[inline]
     pub fn select_phy_offset(
         &self,
@@ -264,20 +259,32 @@ impl IndexFile {
         max_num: usize,
         begin: i64,
         end: i64,
+        max_phy_offset: i64,
     ) {
         if !self.mapped_file.hold() {
             return;
         }
+        self.select_phy_offset_inner(phy_offsets, key, max_num, begin, end, max_phy_offset);
+        self.mapped_file.release();
+    }
 
+    fn select_phy_offset_inner(
+        &self,
+        phy_offsets: &mut Vec<i64>,
+        key: &str,
+        max_num: usize,
+        begin: i64,
+        end: i64,
+        max_phy_offset: i64,
+    ) {
         let key_hash = self.index_key_hash_method(key);
         let slot_pos = key_hash as usize % self.hash_slot_num;
         let abs_slot_pos = INDEX_HEADER_SIZE + slot_pos * HASH_SLOT_SIZE;
 
-        let mut buffer = self
-            .mapped_file
-            .get_data(abs_slot_pos, abs_slot_pos + 4)
-            .unwrap();
-        let slot_value = buffer.get_i32();
+        let slot_value = match self.mapped_file.get_bytes(abs_slot_pos, HASH_SLOT_SIZE) {
+            Some(mut buffer) => buffer.get_i32(),
+            None => return,
+        };
         if slot_value <= INVALID_INDEX
             || slot_value > self.index_header.get_index_count()
             || self.index_header.get_index_count() <= 1
@@ -291,23 +298,24 @@ impl IndexFile {
                 + self.hash_slot_num * HASH_SLOT_SIZE
                 + next_index_to_read as usize * INDEX_SIZE;
 
-            let key_hash_read = buffer.slice(abs_index_pos..abs_index_pos + 4).get_i32();
-            let phy_offset_read = buffer
-                .slice(abs_index_pos + 4..abs_index_pos + 12)
-                .get_i64();
-            let time_diff = buffer
-                .slice(abs_index_pos + 12..abs_index_pos + 16)
-                .get_i32();
-            let prev_index_read = buffer
-                .slice(abs_index_pos + 16..abs_index_pos + 20)
-                .get_i32();
+            let mut buffer = match self.mapped_file.get_bytes(abs_index_pos, INDEX_SIZE) {
+                Some(buffer) => buffer,
+                None => break,
+            };
+            let key_hash_read = buffer.get_i32();
+            let phy_offset_read = buffer.get_i64();
+            let time_diff = buffer.get_i32();
+            let prev_index_read = buffer.get_i32();
 
             if time_diff < 0 {
                 break;
             }
 
             let time_read = self.index_header.get_begin_timestamp() + time_diff as i64 * 1000;
-            if key_hash == key_hash_read && (time_read >= begin && time_read <= end) {
+            if key_hash == key_hash_read
+                && (time_read >= begin && time_read <= end)
+                && phy_offset_read < max_phy_offset
+            {
                 phy_offsets.push(phy_offset_read);
             }
 
@@ -108,22 +108,67 @@ impl IndexService {
         }
     }
 
+    /// Deletes the index files whose entries all point before the physical `offset`, the last
+    /// index file is always kept.
     #[inline]
-    pub fn delete_expired_file(&self, offset: u64) {
-        let mut index_file_list_lock = self.index_file_list.write();
-        if index_file_list_lock.is_empty() {
-            return;
-        }
-        let mut files = Vec::new();
-        for index_file in index_file_list_lock.iter() {
-            if (index_file.get_end_phy_offset() as u64) < offset {
-                files.push(index_file.clone());
-            } else {
+    pub fn delete_expired_file(&self, offset: i64) {
+        let files = {
+            let index_file_list = self.index_file_list.read();
+            match index_file_list.first() {
+                Some(first) if first.get_end_phy_offset() < offset => index_file_list
+                    [..index_file_list.len() - 1]
+                    .iter()
+                    .take_while(|index_file| index_file.get_end_phy_offset() < offset)
+                    .cloned()
+                    .collect::<Vec<_>>(),
+                _ => return,
+            }
+        };
+        let mut index_file_list = self.index_file_list.write();
+        for index_file in files {
+            if !index_file.destroy(3000) {
+                error!(
+                    "deleteExpiredFile remove failed, file {}",
+                    index_file.get_file_name()
+                );
                 break;
             }
+            index_file_list.retain(|file| file != &index_file);
         }
-        if !files.is_empty() {
-            index_file_list_lock.retain(|index_file| !files.contains(index_file));
+    }
+
+    /// Destroys the index files holding entries at or beyond `phy_offset`, they may point to
+    /// messages that no longer exist after the commit log has been truncated.
+    ///
+    /// Returns the physical offset the index has to be rebuilt from, which is lower than
+    /// `phy_offset` when a destroyed file also held entries before it.
+    #[inline]
+    pub fn truncate_dirty(&self, phy_offset: i64) -> i64 {
+        let mut rebuild_from_offset = phy_offset;
+        let mut index_file_list_lock = self.index_file_list.write();
+        index_file_list_lock.retain(|index_file| {
+            if index_file.get_end_phy_offset() < phy_offset {
+                return true;
+            }
+            warn!(
+                "index file {} contains offset {} beyond truncate offset {}, destroy it",
+                index_file.get_file_name(),
+                index_file.get_end_phy_offset(),
+                phy_offset
+            );
+            rebuild_from_offset = rebuild_from_offset.min(index_file.get_begin_phy_offset());
+            index_file.destroy(0);
+            false
+        });
+        rebuild_from_offset
+    }
+
+    /// Flushes all index files, including the one still being written.
+    #[inline]
+    pub fn flush_all(&self) {
+        let index_file_list = self.index_file_list.read().clone();
+        for index_file in index_file_list {
+            self.flush(Some(index_file));
         }
     }
 
@@ -144,43 +189,54 @@ impl IndexService {
         max_num: i32,
         begin: i64,
         end: i64,
+    ) -> QueryOffsetResult {
+        self.query_offset_before(topic, key, max_num, begin, end, i64::MAX)
+    }
+
+    /// Queries the physical offsets of the messages indexed under `key`, newest first, skipping
+    /// the ones at or after `max_phy_offset` so that callers can page through the results.
+    pub fn query_offset_before(
+        &self,
+        topic: &str,
+        key: &str,
+        max_num: i32,
+        begin: i64,
+        end: i64,
+        max_phy_offset: i64,
     ) -> QueryOffsetResult {
         let mut phy_offsets = Vec::new();
         let mut index_last_update_timestamp = 0;
         let mut index_last_update_phyoffset = 0;
         let max_num = max_num.min(self.message_store_config.max_msgs_num_batch as i32);
+        let index_key = build_key(topic, key);
 
         let index_file_list = self.index_file_list.read();
-        let mut index = 0;
-        if !index_file_list.is_empty() {
-            for f in index_file_list.iter() {
-                let last_file = index == index_file_list.len() - 1;
-                if last_file {
-                    // Assuming IndexFile has methods to get end timestamp and phy offset
-                    index_last_update_timestamp = f.get_end_timestamp();
-                    index_last_update_phyoffset = f.get_end_phy_offset();
-                }
-
-                // Assuming IndexFile has a method to check if the file's timestamps match the query
-                if f.is_time_matched(begin, end) {
-                    // Assuming IndexFile has a method to select physical offsets based on the query
-                    f.select_phy_offset(
-                        &mut phy_offsets,
-                        build_key(topic, key).as_str(),
-                        max_num as usize,
-                        begin,
-                        end,
-                    );
-                }
+        if let Some(last_file) = index_file_list.last() {
+            index_last_update_timestamp = last_file.get_end_timestamp();
+            index_last_update_phyoffset = last_file.get_end_phy_offset();
+        }
+        // walk from the newest file to the oldest one
+        for f in index_file_list.iter().rev() {
+            if f.get_begin_phy_offset() >= max_phy_offset {
+                continue;
+            }
+            if f.is_time_matched(begin, end) {
+                f.select_phy_offset(
+                    &mut phy_offsets,
+                    index_key.as_str(),
+                    max_num as usize,
+                    begin,
+                    end,
+                    max_phy_offset,
+                );
+            }
 
-                if f.get_begin_timestamp() < begin {
-                    break;
-                }
+            if f.get_begin_timestamp() < begin {
+                break;
+            }
 
-                if phy_offsets.len() as i32 >= max_num {
-                    break;
-                }
-                index += 1;
+            if phy_offsets.len() as i32 >= max_num {
+                break;
             }
         }
         QueryOffsetResult::new(
@@ -211,19 +267,21 @@ impl IndexService {
                     _ => (),
                 }
 
-                let mut index_file_new = None;
+                let mut index_file = index_file_inner;
                 if let Some(ref uniq_key) = dispatch_request.uniq_key {
-                    index_file_new = self.put_key(
-                        index_file_inner,
+                    match self.put_key(
+                        index_file,
                         dispatch_request,
                         build_key(topic, uniq_key.as_str()).as_str(),
-                    );
-                    if index_file_new.is_none() {
-                        error!(
-                            "putKey error commitlog {} uniqkey {}",
-                            dispatch_request.commit_log_offset, uniq_key
-                        );
-                        return;
+                    ) {
+                        Some(file) => index_file = file,
+                        None => {
+                            error!(
+                                "putKey error commitlog {} uniqkey {}",
+                                dispatch_request.commit_log_offset, uniq_key
+                            );
+                            return;
+                        }
                     }
                 }
 
@@ -231,18 +289,19 @@ impl IndexService {
                     let keyset = keys.split(MessageConst::KEY_SEPARATOR);
                     for key in keyset {
                         if !key.is_empty() {
-                            let index_file = self.put_key(
-                                index_file_new.take().unwrap(),
+                            match self.put_key(
+                                index_file,
                                 dispatch_request,
                                 build_key(topic, key).as_str(),
-                            );
-                            if index_file.is_none() {
-                                error!(
-                                    "putKey error commitlog {} uniqkey {}",
-                                    dispatch_request.commit_log_offset,
-                                    dispatch_request.uniq_key.as_ref().unwrap()
-                                );
-                                return;
+                            ) {
+                                Some(file) => index_file = file,
+                                None => {
+                                    error!(
+                                        "putKey error commitlog {} key {}",
+                                        dispatch_request.commit_log_offset, key
+                                    );
+                                    return;
+                                }
                             }
                         }
                     }
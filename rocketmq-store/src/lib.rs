@@ -23,16 +23,17 @@ pub mod config;
 pub mod consume_queue;
 pub mod filter;
 pub mod hook;
-mod index;
+pub mod index;
 mod kv;
 pub mod log_file;
 pub(crate) mod message_encoder;
 pub mod message_store;
 pub mod pop;
-mod queue;
+pub mod queue;
 pub(crate) mod services;
 pub mod stats;
 pub mod store;
 pub mod store_path_config_helper;
+pub mod tiered;
 pub mod timer;
 pub mod utils;
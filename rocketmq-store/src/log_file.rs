@@ -28,6 +28,7 @@ use rocketmq_common::TimeUtils::get_current_millis;
 use crate::base::dispatch_request::DispatchRequest;
 use crate::base::get_message_result::GetMessageResult;
 use crate::base::message_result::PutMessageResult;
+use crate::base::query_message_condition::QueryMessageCondition;
 use crate::base::query_message_result::QueryMessageResult;
 use crate::base::select_result::SelectMappedBufferResult;
 use crate::config::message_store_config::MessageStoreConfig;
@@ -40,6 +41,7 @@ use crate::timer::timer_message_store::TimerMessageStore;
 
 pub(crate) mod cold_data_check_service;
 pub mod commit_log;
+pub mod commit_log_verifier;
 pub mod flush_manager_impl;
 pub mod mapped_file;
 
@@ -135,6 +137,10 @@ pub trait RocketMQMessageStore: Sync + 'static {
     /// `true` if the operation was successful; `false` otherwise.
     fn truncate_files(&mut self, offset_to_truncate: i64) -> bool;
 
+    /// Force the expired commit log files to be deleted on the next rounds of cleaning, whether
+    /// or not the configured delete time has come.
+    fn execute_delete_files_manually(&self);
+
     /// Check if the OS page cache is busy.
     ///
     /// # Returns
@@ -350,6 +356,20 @@ pub trait RocketMQMessageStore: Sync + 'static {
         end_timestamp: i64,
     ) -> Option<QueryMessageResult>;
 
+    /// Query one page of messages by keys, tags and store time range, newest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `condition` - The lookup condition, including the cursor of the page.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the matched messages and the cursor of the next page.
+    async fn query_message_by_condition(
+        &self,
+        condition: &QueryMessageCondition,
+    ) -> Option<QueryMessageResult>;
+
     /// Select one message by offset asynchronously.
     ///
     /// # Arguments
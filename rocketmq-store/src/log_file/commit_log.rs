@@ -42,6 +42,7 @@ use rocketmq_common::utils::queue_type_utils::QueueTypeUtils;
 use rocketmq_common::utils::time_utils;
 use rocketmq_common::CRC32Utils::crc32;
 use rocketmq_common::MessageDecoder::string_to_message_properties;
+use rocketmq_common::MessageDecoder::BORN_TIMESTAMP_POSITION;
 use rocketmq_common::MessageDecoder::MESSAGE_MAGIC_CODE_POSITION;
 use rocketmq_common::MessageDecoder::MESSAGE_MAGIC_CODE_V2;
 use rocketmq_common::MessageDecoder::SYSFLAG_POSITION;
@@ -77,6 +78,7 @@ use crate::message_store::default_message_store::CommitLogDispatcherDefault;
 use crate::message_store::default_message_store::DefaultMessageStore;
 use crate::queue::local_file_consume_queue_store::ConsumeQueueStore;
 use crate::queue::ConsumeQueueStoreTrait;
+use crate::tiered::tiered_file_store::TieredFileStore;
 
 // Message's MAGIC CODE daa320a7
 pub const MESSAGE_MAGIC_CODE: i32 = -626843481;
@@ -200,6 +202,7 @@ pub struct CommitLog {
     //flush_manager: Arc<parking_lot::Mutex<DefaultFlushManager>>,
     begin_time_in_lock: Arc<AtomicU64>,
     cold_data_check_service: Arc<ColdDataCheckService>,
+    tiered_file_store: Option<Arc<TieredFileStore>>,
 }
 
 impl CommitLog {
@@ -241,8 +244,20 @@ impl CommitLog {
             ))),
             begin_time_in_lock: Arc::new(AtomicU64::new(0)),
             cold_data_check_service: Arc::new(Default::default()),
+            tiered_file_store: None,
         }
     }
+
+    /// Sets the tiered storage serving the data which is no longer on the local disk, must be
+    /// called before the commit log is cloned into the store services.
+    pub fn set_tiered_file_store(&mut self, tiered_file_store: Arc<TieredFileStore>) {
+        self.tiered_file_store = Some(tiered_file_store);
+    }
+
+    #[inline]
+    pub fn get_tiered_file_store(&self) -> Option<&Arc<TieredFileStore>> {
+        self.tiered_file_store.as_ref()
+    }
 }
 
 #[allow(unused_variables)]
@@ -271,6 +286,20 @@ impl CommitLog {
     pub fn destroy(&mut self) {}
 
     pub fn get_message(&self, offset: i64, size: i32) -> Option<SelectMappedBufferResult> {
+        if let Some(tiered_file_store) = &self.tiered_file_store {
+            let local_min_offset = self.get_min_offset();
+            if local_min_offset < 0 || offset < local_min_offset {
+                return tiered_file_store
+                    .read_commit_log(offset, size)
+                    .map(|bytes| SelectMappedBufferResult {
+                        start_offset: 0,
+                        size,
+                        bytes: Some(bytes),
+                        mapped_file: None,
+                        is_in_cache: false,
+                    });
+            }
+        }
         let mapped_file_size = self.message_store_config.mapped_file_size_commit_log;
         let mapped_file = self
             .mapped_file_queue
@@ -1099,6 +1128,95 @@ impl CommitLog {
         self.mapped_file_queue.get_max_offset()
     }
 
+    /// Reads the store timestamp of the message at `offset`, -1 if it is not in the commit log.
+    pub fn pickup_store_timestamp(&self, offset: i64, size: i32) -> i64 {
+        if offset < self.get_min_offset() || offset + size as i64 > self.get_max_offset() {
+            return -1;
+        }
+        let Some(bytes) = self
+            .get_message(offset, size)
+            .and_then(|result| result.get_bytes())
+        else {
+            return -1;
+        };
+        if bytes.len() < BORN_TIMESTAMP_POSITION + 8 {
+            return -1;
+        }
+        let sys_flag = (&bytes[SYSFLAG_POSITION..]).get_i32();
+        let born_host_length = if sys_flag & MessageSysFlag::BORNHOST_V6_FLAG == 0 {
+            8
+        } else {
+            20
+        };
+        // The position constant already points past the born timestamp, at the born host.
+        let store_timestamp_position = BORN_TIMESTAMP_POSITION + born_host_length;
+        if bytes.len() < store_timestamp_position + 8 {
+            return -1;
+        }
+        (&bytes[store_timestamp_position..]).get_i64()
+    }
+
+    /// Truncates the commit log to `phy_offset`.
+    ///
+    /// Files beyond the offset are deleted and the tail of the file containing it is zeroed, so
+    /// the dropped messages are not picked up again by the next recovery.
+    pub fn truncate_dirty_files(&mut self, phy_offset: i64) {
+        if let Some(mapped_file) = self
+            .mapped_file_queue
+            .find_mapped_file_by_offset(phy_offset, false)
+        {
+            let pos = (phy_offset - mapped_file.get_file_from_offset() as i64) as usize;
+            mapped_file.get_mapped_file_mut()[pos..].fill(0);
+            if let Err(e) = mapped_file.get_mapped_file().flush() {
+                error!(
+                    "flush truncated commit log file {} error: {:?}",
+                    mapped_file.get_file_name(),
+                    e
+                );
+            }
+        }
+        self.mapped_file_queue.truncate_dirty_files(phy_offset);
+        self.mapped_file_queue.set_flushed_where(phy_offset);
+        self.mapped_file_queue.set_committed_where(phy_offset);
+        self.set_confirm_offset(phy_offset);
+    }
+
+    pub fn delete_expired_file(
+        &self,
+        expired_time: u64,
+        delete_files_interval: u64,
+        interval_forcibly: u64,
+        clean_immediately: bool,
+        delete_file_batch_max: usize,
+    ) -> i32 {
+        // with tiered storage, only the files already uploaded can be deleted locally
+        let deletable_end_offset = match (
+            &self.tiered_file_store,
+            self.mapped_file_queue.get_first_mapped_file(),
+        ) {
+            (Some(tiered_file_store), Some(first_mapped_file)) => tiered_file_store
+                .get_uploaded_end_offset(first_mapped_file.get_file_from_offset() as i64),
+            _ => i64::MAX,
+        };
+        self.mapped_file_queue.delete_expired_file_by_time(
+            expired_time,
+            delete_files_interval,
+            interval_forcibly,
+            clean_immediately,
+            delete_file_batch_max,
+            deletable_end_offset,
+        )
+    }
+
+    pub fn retry_delete_first_file(&self, interval_forcibly: u64) -> bool {
+        self.mapped_file_queue
+            .retry_delete_first_file(interval_forcibly)
+    }
+
+    pub fn get_mapped_file_queue(&self) -> &MappedFileQueue {
+        &self.mapped_file_queue
+    }
+
     pub fn get_min_offset(&self) -> i64 {
         match self.mapped_file_queue.get_first_mapped_file() {
             None => -1,
@@ -1112,6 +1230,23 @@ impl CommitLog {
         }
     }
 
+    /// The min physical offset messages can still be read from, including the tiered storage.
+    pub fn get_min_readable_offset(&self) -> i64 {
+        let local_min_offset = self.get_min_offset();
+        match self
+            .tiered_file_store
+            .as_ref()
+            .and_then(|tiered_file_store| tiered_file_store.get_min_offset())
+        {
+            Some(tiered_min_offset)
+                if local_min_offset < 0 || tiered_min_offset < local_min_offset =>
+            {
+                tiered_min_offset
+            }
+            _ => local_min_offset,
+        }
+    }
+
     pub fn roll_next_file(&self, offset: i64) -> i64 {
         let mapped_file_size = self.message_store_config.mapped_file_size_commit_log as i64;
         offset + mapped_file_size - (offset % mapped_file_size)
@@ -1177,6 +1312,8 @@ pub fn check_message_and_return_size(
     read_body: bool,
     message_store_config: &Arc<MessageStoreConfig>,
 ) -> DispatchRequest {
+    // keep a view of the whole message, the property crc covers everything before it
+    let msg_bytes = bytes.clone();
     let total_size = bytes.get_i32();
     let magic_code = bytes.get_i32();
     if magic_code == MESSAGE_MAGIC_CODE || magic_code == MESSAGE_MAGIC_CODE_V2 {
@@ -1247,7 +1384,7 @@ pub fn check_message_and_return_size(
     let properties_length = bytes.get_i16();
     let (tags_code, keys, uniq_key, properties_map) = if properties_length > 0 {
         let properties = bytes.copy_to_bytes(properties_length as usize);
-        let properties_content = String::from_utf8_lossy(topic_bytes.as_ref()).to_string();
+        let properties_content = String::from_utf8_lossy(properties.as_ref()).to_string();
         //need to optimize
         let properties_map =
             string_to_message_properties(Some(&CheetahString::from_string(properties_content)));
@@ -1292,9 +1429,38 @@ pub fn check_message_and_return_size(
         (0, CheetahString::new(), None, HashMap::new())
     };
 
-    if check_crc && !message_store_config.force_verify_prop_crc {
-        let _expected_crc = -1i32;
-        if !properties_map.is_empty() {}
+    if check_crc && message_store_config.force_verify_prop_crc {
+        let expected_crc = properties_map
+            .get(MessageConst::PROPERTY_CRC32)
+            .and_then(|crc| parse_property_crc(crc.as_str()));
+        match expected_crc {
+            Some(expected_crc) if expected_crc > 0 => {
+                let crc = compute_property_crc(&msg_bytes, total_size);
+                if crc != Some(expected_crc) {
+                    warn!(
+                        "CommitLog#checkAndDispatchMessage: failed to check message CRC, expected \
+                         CRC={}, actual CRC={:?}",
+                        expected_crc, crc
+                    );
+                    return DispatchRequest {
+                        msg_size: -1,
+                        success: false,
+                        ..Default::default()
+                    };
+                }
+            }
+            _ => {
+                warn!(
+                    "CommitLog#checkAndDispatchMessage: failed to check message CRC, not found \
+                     CRC in properties"
+                );
+                return DispatchRequest {
+                    msg_size: -1,
+                    success: false,
+                    ..Default::default()
+                };
+            }
+        }
     }
 
     let read_length = MessageExtEncoder::cal_msg_length(
@@ -1337,6 +1503,28 @@ pub fn check_message_and_return_size(
     dispatch_request
 }
 
+/// Parses the CRC32 value appended to the message properties.
+///
+/// The digits are written least significant first (see `MessageDecoder::create_crc32`), so
+/// they are read back in reverse order. Returns `None` if the value is not a valid number.
+pub fn parse_property_crc(crc32_str: &str) -> Option<u32> {
+    let mut expected_crc = 0u32;
+    for c in crc32_str.chars().rev() {
+        let num = c.to_digit(10)?;
+        expected_crc = expected_crc.checked_mul(10)?.checked_add(num)?;
+    }
+    Some(expected_crc)
+}
+
+/// Computes the CRC32 of a stored message, excluding the reserved CRC32 property at its tail.
+pub fn compute_property_crc(msg_bytes: &[u8], total_size: i32) -> Option<u32> {
+    let check_size = total_size - CRC32_RESERVED_LEN;
+    if check_size <= 0 || check_size as usize > msg_bytes.len() {
+        return None;
+    }
+    Some(crc32(&msg_bytes[..check_size as usize]))
+}
+
 fn set_batch_size_if_needed(
     properties_map: &HashMap<CheetahString, CheetahString>,
     dispatch_request: &mut DispatchRequest,
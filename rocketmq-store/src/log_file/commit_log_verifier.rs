@@ -0,0 +1,456 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fs;
+use std::fs::File;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use cheetah_string::CheetahString;
+use memmap2::Mmap;
+use rocketmq_common::common::broker::broker_config::BrokerConfig;
+use rocketmq_common::common::config::TopicConfig;
+use rocketmq_common::common::message::MessageConst;
+use rocketmq_common::common::message::MessageVersion;
+use rocketmq_common::common::sys_flag::message_sys_flag::MessageSysFlag;
+use rocketmq_common::CRC32Utils::crc32;
+use rocketmq_common::MessageDecoder::str_to_message_properties;
+use tracing::info;
+use tracing::warn;
+
+use crate::config::message_store_config::MessageStoreConfig;
+use crate::log_file::commit_log::compute_property_crc;
+use crate::log_file::commit_log::parse_property_crc;
+use crate::log_file::commit_log::BLANK_MAGIC_CODE;
+use crate::log_file::commit_log::MESSAGE_MAGIC_CODE;
+use crate::log_file::MessageStore;
+use crate::message_encoder::message_ext_encoder::MessageExtEncoder;
+use crate::message_store::default_message_store::DefaultMessageStore;
+
+// TOTALSIZE + MAGICCODE
+const MESSAGE_HEAD_LENGTH: usize = 4 + 4;
+
+/// The reason why a commit log entry is considered corrupted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorruptionKind {
+    IllegalMagicCode,
+    IllegalTotalSize,
+    MessageLengthMismatch,
+    BodyCrcMismatch,
+    PropertyCrcMismatch,
+    PropertyCrcMissing,
+}
+
+impl Display for CorruptionKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CorruptionKind::IllegalMagicCode => write!(f, "ILLEGAL_MAGIC_CODE"),
+            CorruptionKind::IllegalTotalSize => write!(f, "ILLEGAL_TOTAL_SIZE"),
+            CorruptionKind::MessageLengthMismatch => write!(f, "MESSAGE_LENGTH_MISMATCH"),
+            CorruptionKind::BodyCrcMismatch => write!(f, "BODY_CRC_MISMATCH"),
+            CorruptionKind::PropertyCrcMismatch => write!(f, "PROPERTY_CRC_MISMATCH"),
+            CorruptionKind::PropertyCrcMissing => write!(f, "PROPERTY_CRC_MISSING"),
+        }
+    }
+}
+
+/// Verify result of a single commit log file.
+#[derive(Debug, Clone)]
+pub struct CommitLogFileVerifyResult {
+    pub file_name: String,
+    pub file_from_offset: i64,
+    pub message_count: u64,
+    /// Physical offset right after the last valid message of the file.
+    pub last_good_offset: i64,
+    pub first_corrupted_offset: Option<i64>,
+    pub corruption: Option<CorruptionKind>,
+}
+
+impl CommitLogFileVerifyResult {
+    #[inline]
+    pub fn is_corrupted(&self) -> bool {
+        self.first_corrupted_offset.is_some()
+    }
+}
+
+impl Display for CommitLogFileVerifyResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (self.first_corrupted_offset, self.corruption) {
+            (Some(offset), Some(kind)) => write!(
+                f,
+                "{} messages={} lastGoodOffset={} firstCorruptedOffset={} reason={}",
+                self.file_name, self.message_count, self.last_good_offset, offset, kind
+            ),
+            _ => write!(
+                f,
+                "{} messages={} lastGoodOffset={} OK",
+                self.file_name, self.message_count, self.last_good_offset
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CommitLogVerifyReport {
+    pub files: Vec<CommitLogFileVerifyResult>,
+}
+
+impl CommitLogVerifyReport {
+    #[inline]
+    pub fn is_clean(&self) -> bool {
+        self.files.iter().all(|file| !file.is_corrupted())
+    }
+
+    /// The smallest corrupted physical offset over all files, which is the offset the commit
+    /// log has to be truncated to.
+    #[inline]
+    pub fn first_corrupted_offset(&self) -> Option<i64> {
+        self.files
+            .iter()
+            .filter_map(|file| file.first_corrupted_offset)
+            .min()
+    }
+}
+
+/// Walks the commit log files of a store directory and validates the body CRC and the
+/// appended property CRC of every message.
+///
+/// It only reads the files, so it can run against the store of a stopped broker.
+pub struct CommitLogVerifier {
+    message_store_config: Arc<MessageStoreConfig>,
+}
+
+impl CommitLogVerifier {
+    pub fn new(message_store_config: Arc<MessageStoreConfig>) -> Self {
+        Self {
+            message_store_config,
+        }
+    }
+
+    pub fn verify(&self) -> CommitLogVerifyReport {
+        let mut report = CommitLogVerifyReport::default();
+        let store_path = self.message_store_config.get_store_path_commit_log();
+        let mut files: Vec<PathBuf> = match fs::read_dir(&store_path) {
+            Ok(ls) => ls
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file())
+                .collect(),
+            Err(e) => {
+                warn!("read commit log dir {} error: {}", store_path, e);
+                return report;
+            }
+        };
+        files.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+        for file in files {
+            match self.verify_file(&file) {
+                Ok(result) => {
+                    info!("verify commit log file {}", result);
+                    report.files.push(result);
+                }
+                Err(e) => warn!("verify commit log file {} error: {}", file.display(), e),
+            }
+        }
+        report
+    }
+
+    pub fn verify_file(&self, path: &Path) -> std::io::Result<CommitLogFileVerifyResult> {
+        let file_from_offset = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.parse::<i64>().ok())
+            .unwrap_or_default();
+        let file = File::open(path)?;
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(self.verify_bytes(path.to_string_lossy().to_string(), file_from_offset, &mmap))
+    }
+
+    pub fn verify_bytes(
+        &self,
+        file_name: String,
+        file_from_offset: i64,
+        data: &[u8],
+    ) -> CommitLogFileVerifyResult {
+        let mut pos = 0usize;
+        let mut message_count = 0u64;
+        let mut corruption = None;
+        while pos + MESSAGE_HEAD_LENGTH <= data.len() {
+            let total_size = read_i32(data, pos);
+            let magic_code = read_i32(data, pos + 4);
+            if total_size == 0 && magic_code == 0 {
+                // end of the written data
+                break;
+            }
+            if magic_code == BLANK_MAGIC_CODE {
+                // end of file
+                break;
+            }
+            match self.verify_message(&data[pos..]) {
+                Ok(size) => {
+                    message_count += 1;
+                    pos += size;
+                }
+                Err(kind) => {
+                    corruption = Some(kind);
+                    break;
+                }
+            }
+        }
+        let last_good_offset = file_from_offset + pos as i64;
+        if let Some(kind) = corruption {
+            warn!(
+                "commit log file {} is corrupted at offset {}, reason: {}",
+                file_name, last_good_offset, kind
+            );
+        }
+        CommitLogFileVerifyResult {
+            file_name,
+            file_from_offset,
+            message_count,
+            last_good_offset,
+            first_corrupted_offset: corruption.map(|_| last_good_offset),
+            corruption,
+        }
+    }
+
+    /// Verifies the message at the start of `data` and returns its total size.
+    fn verify_message(&self, data: &[u8]) -> Result<usize, CorruptionKind> {
+        let total_size = read_i32(data, 0);
+        let magic_code = read_i32(data, 4);
+        let message_version = match MessageVersion::value_of_magic_code(magic_code) {
+            Ok(version) if magic_code == MESSAGE_MAGIC_CODE || version.is_v2() => version,
+            _ => return Err(CorruptionKind::IllegalMagicCode),
+        };
+        if total_size <= 0 || total_size as usize > data.len() {
+            return Err(CorruptionKind::IllegalTotalSize);
+        }
+        let msg = &data[..total_size as usize];
+        let body_crc = read_i32(msg, 8) as u32;
+        let sys_flag = read_i32(msg, 4 + 4 + 4 + 4 + 4 + 8 + 8);
+        let born_host_length = if sys_flag & MessageSysFlag::BORNHOST_V6_FLAG == 0 {
+            8
+        } else {
+            20
+        };
+        let store_host_length = if sys_flag & MessageSysFlag::STOREHOSTADDRESS_V6_FLAG == 0 {
+            8
+        } else {
+            20
+        };
+        let body_len_pos =
+            4 + 4 + 4 + 4 + 4 + 8 + 8 + 4 + 8 + born_host_length + 8 + store_host_length + 4 + 8;
+        let body_len = checked_read_i32(msg, body_len_pos)?;
+        if body_len < 0 {
+            return Err(CorruptionKind::MessageLengthMismatch);
+        }
+        let body_pos = body_len_pos + 4;
+        let topic_len_pos = body_pos + body_len as usize;
+        let topic_len_size = message_version.get_topic_length_size();
+        if topic_len_pos + topic_len_size > msg.len() {
+            return Err(CorruptionKind::MessageLengthMismatch);
+        }
+        let topic_len = message_version.get_topic_length_at_index(msg, topic_len_pos);
+        let properties_len_pos = topic_len_pos + topic_len_size + topic_len;
+        if properties_len_pos + 2 > msg.len() {
+            return Err(CorruptionKind::MessageLengthMismatch);
+        }
+        let properties_len =
+            i16::from_be_bytes([msg[properties_len_pos], msg[properties_len_pos + 1]]);
+        let read_length = MessageExtEncoder::cal_msg_length(
+            message_version,
+            sys_flag,
+            body_len,
+            topic_len as i32,
+            properties_len as i32,
+        );
+        if read_length != total_size {
+            return Err(CorruptionKind::MessageLengthMismatch);
+        }
+
+        if body_len > 0 && crc32(&msg[body_pos..topic_len_pos]) != body_crc {
+            return Err(CorruptionKind::BodyCrcMismatch);
+        }
+
+        let properties_pos = properties_len_pos + 2;
+        let properties = String::from_utf8_lossy(
+            &msg[properties_pos..properties_pos + properties_len.max(0) as usize],
+        );
+        let properties_map = str_to_message_properties(Some(properties.as_ref()));
+        match properties_map
+            .get(MessageConst::PROPERTY_CRC32)
+            .map(|crc| parse_property_crc(crc.as_str()))
+        {
+            Some(Some(expected_crc)) => {
+                if compute_property_crc(msg, total_size) != Some(expected_crc) {
+                    return Err(CorruptionKind::PropertyCrcMismatch);
+                }
+            }
+            Some(None) => return Err(CorruptionKind::PropertyCrcMismatch),
+            None => {
+                if self.message_store_config.force_verify_prop_crc {
+                    return Err(CorruptionKind::PropertyCrcMissing);
+                }
+            }
+        }
+        Ok(total_size as usize)
+    }
+
+    /// Truncates the commit log, consume queues and index files to the first corrupted offset
+    /// found in `report`.
+    ///
+    /// Every message after that offset is dropped, including the ones in later files. Returns
+    /// `false` if there is nothing to repair or the store could not be loaded.
+    pub fn repair(
+        &self,
+        broker_config: Arc<BrokerConfig>,
+        topic_config_table: Arc<parking_lot::Mutex<HashMap<CheetahString, TopicConfig>>>,
+        report: &CommitLogVerifyReport,
+    ) -> bool {
+        let truncate_offset = match report.first_corrupted_offset() {
+            None => {
+                info!("commit log is clean, nothing to repair");
+                return false;
+            }
+            Some(offset) => offset,
+        };
+        let mut message_store = DefaultMessageStore::new(
+            self.message_store_config.clone(),
+            broker_config,
+            topic_config_table,
+            None,
+            false,
+        );
+        if !message_store.load_offline() {
+            warn!("load message store failed, can not repair commit log");
+            return false;
+        }
+        info!("repair commit log, truncate to offset {}", truncate_offset);
+        message_store.truncate_files(truncate_offset)
+    }
+}
+
+#[inline]
+fn read_i32(data: &[u8], pos: usize) -> i32 {
+    i32::from_be_bytes(data[pos..pos + 4].try_into().unwrap())
+}
+
+#[inline]
+fn checked_read_i32(data: &[u8], pos: usize) -> Result<i32, CorruptionKind> {
+    if pos + 4 > data.len() {
+        return Err(CorruptionKind::MessageLengthMismatch);
+    }
+    Ok(read_i32(data, pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+    use rocketmq_common::common::message::message_ext_broker_inner::MessageExtBrokerInner;
+    use rocketmq_common::common::message::MessageTrait;
+    use rocketmq_common::MessageDecoder::create_crc32;
+
+    use super::*;
+    use crate::log_file::commit_log::CRC32_RESERVED_LEN;
+
+    fn encode_message(config: &Arc<MessageStoreConfig>, body: &'static [u8]) -> BytesMut {
+        let mut msg = MessageExtBrokerInner::default();
+        msg.set_topic(CheetahString::from_static_str("verify_topic"));
+        msg.set_body(bytes::Bytes::from_static(body));
+        msg.message_ext_inner.body_crc = crc32(body);
+        msg.properties_string = CheetahString::from_static_str("KEYS\u{1}k1\u{2}");
+        let mut encoder = MessageExtEncoder::new(config.clone());
+        assert!(encoder.encode(&msg).is_none());
+        let mut buffer = encoder.byte_buf().split();
+        let msg_len = buffer.len();
+        let check_size = msg_len - CRC32_RESERVED_LEN as usize;
+        let crc = crc32(&buffer[..check_size]);
+        create_crc32(&mut buffer[check_size..msg_len], crc);
+        buffer
+    }
+
+    fn config() -> Arc<MessageStoreConfig> {
+        Arc::new(MessageStoreConfig {
+            enabled_append_prop_crc: true,
+            ..MessageStoreConfig::default()
+        })
+    }
+
+    #[test]
+    fn verify_bytes_accepts_valid_messages() {
+        let config = config();
+        let mut data = encode_message(&config, b"hello");
+        data.extend_from_slice(&encode_message(&config, b"world"));
+        let size = data.len() as i64;
+        data.resize(data.len() + 128, 0);
+
+        let result = CommitLogVerifier::new(config).verify_bytes("0".to_string(), 0, &data);
+        assert_eq!(result.message_count, 2);
+        assert_eq!(result.last_good_offset, size);
+        assert!(!result.is_corrupted());
+    }
+
+    #[test]
+    fn verify_bytes_reports_body_crc_mismatch() {
+        let config = config();
+        let first = encode_message(&config, b"hello");
+        let mut second = encode_message(&config, b"world");
+        // CRC32 + properties + properties length + topic + topic length
+        let body_end = second.len() - (CRC32_RESERVED_LEN as usize + 8 + 2 + 12 + 1);
+        second[body_end - 1] ^= 0xFF;
+        let mut data = first.clone();
+        data.extend_from_slice(&second);
+
+        let result = CommitLogVerifier::new(config).verify_bytes("1024".to_string(), 1024, &data);
+        assert_eq!(result.message_count, 1);
+        assert_eq!(
+            result.first_corrupted_offset,
+            Some(1024 + first.len() as i64)
+        );
+        assert_eq!(result.corruption, Some(CorruptionKind::BodyCrcMismatch));
+    }
+
+    #[test]
+    fn verify_bytes_reports_property_crc_mismatch() {
+        let config = config();
+        let mut data = encode_message(&config, b"hello");
+        // flip a byte of the queue offset, covered by the property crc only
+        data[20] ^= 0x01;
+
+        let result = CommitLogVerifier::new(config).verify_bytes("0".to_string(), 0, &data);
+        assert_eq!(result.first_corrupted_offset, Some(0));
+        assert_eq!(result.corruption, Some(CorruptionKind::PropertyCrcMismatch));
+    }
+
+    #[test]
+    fn verify_bytes_reports_illegal_magic_code() {
+        let config = config();
+        let mut data = encode_message(&config, b"hello");
+        data[4..8].copy_from_slice(&1i32.to_be_bytes());
+
+        let result = CommitLogVerifier::new(config).verify_bytes("0".to_string(), 0, &data);
+        assert_eq!(result.corruption, Some(CorruptionKind::IllegalMagicCode));
+    }
+
+    #[test]
+    fn parse_property_crc_reads_reversed_digits() {
+        assert_eq!(parse_property_crc("4321000000"), Some(1234));
+        assert_eq!(parse_property_crc("12a"), None);
+    }
+}
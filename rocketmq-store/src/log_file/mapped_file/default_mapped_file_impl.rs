@@ -26,6 +26,7 @@ use std::sync::atomic::AtomicI32;
 use std::sync::atomic::AtomicI64;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
+use std::time::UNIX_EPOCH;
 
 use bytes::Bytes;
 use bytes::BytesMut;
@@ -548,12 +549,10 @@ impl MappedFile for DefaultMappedFile {
     fn get_last_modified_timestamp(&self) -> u64 {
         self.file
             .metadata()
-            .unwrap()
-            .modified()
-            .unwrap()
-            .elapsed()
-            .unwrap()
-            .as_millis() as u64
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |duration| duration.as_millis() as u64)
     }
 
     #[inline]
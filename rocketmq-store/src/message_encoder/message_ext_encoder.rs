@@ -371,7 +371,8 @@ impl MessageExtEncoder {
                 .put_u8(MessageDecoder::PROPERTY_SEPARATOR as u8);
         }
         // 18 CRC32
-        self.byte_buf.advance(self.crc32_reserved_length as usize);
+        self.byte_buf
+            .put_bytes(0, self.crc32_reserved_length as usize);
         None
     }
 
@@ -518,7 +519,8 @@ impl MessageExtEncoder {
                 self.byte_buf.put(batch_prop_data);
             }
             // 18 CRC32
-            self.byte_buf.advance(self.crc32_reserved_length as usize);
+            self.byte_buf
+                .put_bytes(0, self.crc32_reserved_length as usize);
         }
         put_message_context.set_batch_size(batch_size);
         put_message_context.set_phy_pos(vec![0; batch_size as usize]);
@@ -21,7 +21,9 @@ use std::error::Error;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicI32;
 use std::sync::atomic::AtomicI64;
+use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::thread;
@@ -31,9 +33,11 @@ use std::time::Instant;
 use bytes::Buf;
 use cheetah_string::CheetahString;
 use rocketmq_common::common::attribute::cleanup_policy::CleanupPolicy;
+use rocketmq_common::common::attribute::cq_type::CQType;
 use rocketmq_common::common::broker::broker_role::BrokerRole;
 use rocketmq_common::common::message::message_batch::MessageExtBatch;
 use rocketmq_common::common::message::message_ext::MessageExt;
+use rocketmq_common::common::message::MessageTrait;
 use rocketmq_common::common::mix_all::is_lmq;
 use rocketmq_common::common::mix_all::is_sys_consumer_group_for_no_cold_read_limit;
 use rocketmq_common::common::mix_all::MULTI_DISPATCH_QUEUE_SPLITTER;
@@ -69,6 +73,7 @@ use crate::base::message_arriving_listener::MessageArrivingListener;
 use crate::base::message_result::PutMessageResult;
 use crate::base::message_status_enum::GetMessageStatus;
 use crate::base::message_status_enum::PutMessageStatus;
+use crate::base::query_message_condition::QueryMessageCondition;
 use crate::base::query_message_result::QueryMessageResult;
 use crate::base::select_result::SelectMappedBufferResult;
 use crate::base::store_checkpoint::StoreCheckpoint;
@@ -97,6 +102,9 @@ use crate::store::running_flags::RunningFlags;
 use crate::store_path_config_helper::get_abort_file;
 use crate::store_path_config_helper::get_store_checkpoint;
 use crate::store_path_config_helper::get_store_path_consume_queue;
+use crate::tiered::local_directory_backend::LocalDirectoryBackend;
+use crate::tiered::tiered_file_store::TieredFileStore;
+use crate::tiered::tiered_storage_backend::TieredStorageBackend;
 use crate::timer::timer_message_store::TimerMessageStore;
 use crate::utils::store_util::TOTAL_PHYSICAL_MEMORY_SIZE;
 
@@ -124,6 +132,7 @@ pub struct DefaultMessageStore {
     clean_commit_log_service: Arc<CleanCommitLogService>,
     correct_logic_offset_service: Arc<CorrectLogicOffsetService>,
     clean_consume_queue_service: Arc<CleanConsumeQueueService>,
+    tiered_store_service: Option<Arc<TieredStoreService>>,
     broker_stats_manager: Option<Arc<BrokerStatsManager>>,
     message_arriving_listener:
         Option<Arc<Box<dyn MessageArrivingListener + Sync + Send + 'static>>>,
@@ -142,6 +151,27 @@ impl DefaultMessageStore {
         topic_config_table: Arc<parking_lot::Mutex<HashMap<CheetahString, TopicConfig>>>,
         broker_stats_manager: Option<Arc<BrokerStatsManager>>,
         notify_message_arrive_in_batch: bool,
+    ) -> Self {
+        Self::new_with_tiered_storage_backend(
+            message_store_config,
+            broker_config,
+            topic_config_table,
+            broker_stats_manager,
+            notify_message_arrive_in_batch,
+            None,
+        )
+    }
+
+    /// Creates the store with the backend the sealed commit log segments are uploaded to when
+    /// tiered storage is enabled, the local directory backend at `tiered_storage_path` is used
+    /// if `tiered_storage_backend` is `None`.
+    pub fn new_with_tiered_storage_backend(
+        message_store_config: Arc<MessageStoreConfig>,
+        broker_config: Arc<BrokerConfig>,
+        topic_config_table: Arc<parking_lot::Mutex<HashMap<CheetahString, TopicConfig>>>,
+        broker_stats_manager: Option<Arc<BrokerStatsManager>>,
+        notify_message_arrive_in_batch: bool,
+        tiered_storage_backend: Option<Arc<dyn TieredStorageBackend>>,
     ) -> Self {
         let running_flags = Arc::new(RunningFlags::new());
         let store_checkpoint = Arc::new(
@@ -169,7 +199,7 @@ impl DefaultMessageStore {
             dispatcher_vec: Arc::new(vec![Box::new(build_consume_queue), Box::new(build_index)]),
         };
 
-        let commit_log = CommitLog::new(
+        let mut commit_log = CommitLog::new(
             message_store_config.clone(),
             broker_config.clone(),
             &dispatcher,
@@ -177,11 +207,42 @@ impl DefaultMessageStore {
             topic_config_table.clone(),
             consume_queue_store.clone(),
         );
+        if message_store_config.tiered_storage_enable {
+            let backend = tiered_storage_backend.unwrap_or_else(|| {
+                Arc::new(LocalDirectoryBackend::new(
+                    message_store_config.get_tiered_storage_path(),
+                ))
+            });
+            commit_log.set_tiered_file_store(Arc::new(TieredFileStore::new(backend)));
+        }
 
         ensure_dir_ok(message_store_config.store_path_root_dir.as_str());
         ensure_dir_ok(Self::get_store_path_physic(&message_store_config).as_str());
         ensure_dir_ok(Self::get_store_path_logic(&message_store_config).as_str());
 
+        let clean_commit_log_service = Arc::new(CleanCommitLogService::new(
+            message_store_config.clone(),
+            commit_log.clone(),
+            running_flags.clone(),
+        ));
+        let clean_consume_queue_service = Arc::new(CleanConsumeQueueService::new(
+            message_store_config.clone(),
+            commit_log.clone(),
+            consume_queue_store.clone(),
+            index_service.clone(),
+        ));
+        let correct_logic_offset_service = Arc::new(CorrectLogicOffsetService::new(
+            commit_log.clone(),
+            consume_queue_store.clone(),
+        ));
+        let tiered_store_service = commit_log.get_tiered_file_store().map(|tiered_file_store| {
+            Arc::new(TieredStoreService::new(
+                message_store_config.clone(),
+                commit_log.clone(),
+                tiered_file_store.clone(),
+            ))
+        });
+
         let identity = broker_config.broker_identity.clone();
         let transient_store_pool = TransientStorePool::new(
             message_store_config.transient_store_pool_size,
@@ -211,9 +272,10 @@ impl DefaultMessageStore {
                 message_store_config,
                 inner: None,
             },
-            clean_commit_log_service: Arc::new(CleanCommitLogService {}),
-            correct_logic_offset_service: Arc::new(CorrectLogicOffsetService {}),
-            clean_consume_queue_service: Arc::new(CleanConsumeQueueService {}),
+            clean_commit_log_service,
+            correct_logic_offset_service,
+            clean_consume_queue_service,
+            tiered_store_service,
             broker_stats_manager,
             message_arriving_listener: None,
             notify_message_arrive_in_batch,
@@ -336,7 +398,7 @@ impl DefaultMessageStore {
     }
 
     pub fn recover_topic_queue_table(&mut self) {
-        let min_phy_offset = self.commit_log.get_min_offset();
+        let min_phy_offset = self.commit_log.get_min_readable_offset();
         self.consume_queue_store
             .recover_offset_table(min_phy_offset);
     }
@@ -390,6 +452,144 @@ impl DefaultMessageStore {
 
     pub fn truncate_dirty_logic_files(&mut self, phy_offset: i64) {
         self.consume_queue_store.truncate_dirty(phy_offset);
+        self.index_service.truncate_dirty(phy_offset);
+    }
+
+    /// Loads commit log, consume queue and index files without recovering them or starting any
+    /// service, so the store directory of a stopped broker can be inspected or repaired.
+    pub fn load_offline(&mut self) -> bool {
+        let mut result = self.commit_log.load();
+        result &= self.consume_queue_store.load();
+        if result {
+            self.consume_queue_store.recover();
+            result = self.index_service.load(true);
+        }
+        result
+    }
+
+    /// Regenerates consume queues, consume queue extensions and index files from the commit log
+    /// range `[from_offset, to_offset)`, the range is clamped to the offsets the commit log still
+    /// holds.
+    ///
+    /// Logic files are truncated at `from_offset` before being rebuilt, so entries beyond
+    /// `to_offset` are dropped too, pass `i64::MAX` to rebuild up to the end of the commit log.
+    /// Returns the number of messages dispatched.
+    pub fn rebuild_logic_files(&mut self, from_offset: i64, to_offset: i64) -> u64 {
+        let min_phy_offset = self.commit_log.get_min_offset();
+        let from_offset = from_offset.max(min_phy_offset);
+        let to_offset = to_offset.min(self.commit_log.get_max_offset());
+        if from_offset >= to_offset {
+            info!(
+                "no need to rebuild logic files, commit log range [{}, {}) is empty",
+                from_offset, to_offset
+            );
+            return 0;
+        }
+        let begin = Instant::now();
+        info!(
+            "rebuild consume queue and index from commit log range [{}, {})",
+            from_offset, to_offset
+        );
+        self.consume_queue_store.truncate_dirty(from_offset);
+        let index_from_offset = self
+            .index_service
+            .truncate_dirty(from_offset)
+            .max(min_phy_offset);
+
+        // destroyed index files may also have held entries before `from_offset`, only the index
+        // is rebuilt for them as the consume queues still hold those entries
+        if self.message_store_config.message_index_enable && index_from_offset < from_offset {
+            let index_service = self.index_service.clone();
+            self.replay_commit_log(index_from_offset, from_offset, |dispatch_request| {
+                index_service.build_index(dispatch_request)
+            });
+        }
+        let dispatcher = self.dispatcher.clone();
+        let dispatched = self.replay_commit_log(from_offset, to_offset, |dispatch_request| {
+            dispatcher.dispatch(dispatch_request)
+        });
+
+        self.recover_topic_queue_table();
+        for consume_queue_table in self
+            .consume_queue_store
+            .get_consume_queue_table()
+            .lock()
+            .values()
+        {
+            for consume_queue in consume_queue_table.values() {
+                while !consume_queue.flush(0) {}
+            }
+        }
+        self.index_service.flush_all();
+        info!(
+            "rebuild logic files end, dispatched {} messages, cost {} ms",
+            dispatched,
+            begin.elapsed().as_millis()
+        );
+        dispatched
+    }
+
+    /// Reads the messages of the commit log range `[from_offset, to_offset)` and hands them to
+    /// `dispatch`, stops at the end of the written data or at the first illegal message.
+    fn replay_commit_log(
+        &self,
+        from_offset: i64,
+        to_offset: i64,
+        mut dispatch: impl FnMut(&DispatchRequest),
+    ) -> u64 {
+        let mapped_file_size = self.message_store_config.mapped_file_size_commit_log as i64;
+        let mut offset = from_offset;
+        let mut dispatched = 0;
+        while offset < to_offset {
+            let mapped_file = match self
+                .commit_log
+                .get_mapped_file_queue()
+                .find_mapped_file_by_offset(offset, false)
+            {
+                Some(mapped_file) => mapped_file,
+                None => {
+                    warn!(
+                        "commit log file of offset {} not found, stop replaying",
+                        offset
+                    );
+                    break;
+                }
+            };
+            let pos = (offset % mapped_file_size) as usize;
+            let size = match mapped_file.get_bytes(pos, 4) {
+                Some(mut bytes) => bytes.get_i32(),
+                None => 0,
+            };
+            if size <= 0 {
+                break;
+            }
+            let mut bytes = match mapped_file.get_data(pos, size as usize) {
+                Some(bytes) => bytes,
+                None => break,
+            };
+            let dispatch_request = commit_log::check_message_and_return_size(
+                &mut bytes,
+                false,
+                false,
+                false,
+                &self.message_store_config,
+            );
+            if !dispatch_request.success {
+                warn!(
+                    "illegal message found at commit log offset {}, stop replaying",
+                    offset
+                );
+                break;
+            }
+            if dispatch_request.msg_size == 0 {
+                offset = self.commit_log.roll_next_file(offset);
+                continue;
+            }
+            dispatch(&dispatch_request);
+            dispatched += 1;
+            offset += dispatch_request.msg_size as i64;
+        }
+        dispatched
     }
 
     pub fn consume_queue_store_mut(&mut self) -> &mut ConsumeQueueStore {
@@ -411,23 +611,24 @@ impl DefaultMessageStore {
         // clean files  Periodically
         let clean_commit_log_service_arc = self.clean_commit_log_service.clone();
         let clean_resource_interval = self.message_store_config.clean_resource_interval as u64;
+        let shutdown = self.shutdown.clone();
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(Duration::from_millis(1000 * 60));
-            interval.tick().await;
+            tokio::time::sleep(Duration::from_secs(60)).await;
             let mut interval =
                 tokio::time::interval(Duration::from_millis(clean_resource_interval));
-            loop {
-                clean_commit_log_service_arc.run();
+            while !shutdown.load(Ordering::Acquire) {
+                let service = clean_commit_log_service_arc.clone();
+                let _ = tokio::task::spawn_blocking(move || service.run()).await;
                 interval.tick().await;
             }
         });
 
         let message_store = self.message_store_arc.clone().unwrap();
+        let shutdown = self.shutdown.clone();
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(Duration::from_secs(60));
-            interval.tick().await;
+            tokio::time::sleep(Duration::from_secs(60)).await;
             let mut interval = tokio::time::interval(Duration::from_secs(10 * 60));
-            loop {
+            while !shutdown.load(Ordering::Acquire) {
                 message_store.check_self();
                 interval.tick().await;
             }
@@ -435,26 +636,47 @@ impl DefaultMessageStore {
 
         // store check point flush
         let store_checkpoint_arc = self.store_checkpoint.clone().unwrap();
+        let shutdown = self.shutdown.clone();
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(Duration::from_secs(1));
             interval.tick().await;
             let mut interval = tokio::time::interval(Duration::from_secs(1));
-            loop {
+            while !shutdown.load(Ordering::Acquire) {
                 let _ = store_checkpoint_arc.flush();
                 interval.tick().await;
             }
         });
 
+        if let Some(tiered_store_service) = self.tiered_store_service.clone() {
+            let tiered_upload_interval = self.message_store_config.tiered_upload_interval as u64;
+            let shutdown = self.shutdown.clone();
+            tokio::spawn(async move {
+                let mut interval =
+                    tokio::time::interval(Duration::from_millis(tiered_upload_interval));
+                interval.tick().await;
+                while !shutdown.load(Ordering::Acquire) {
+                    let service = tiered_store_service.clone();
+                    let _ = tokio::task::spawn_blocking(move || service.run()).await;
+                    interval.tick().await;
+                }
+            });
+        }
+
         let correct_logic_offset_service_arc = self.correct_logic_offset_service.clone();
         let clean_consume_queue_service_arc = self.clean_consume_queue_service.clone();
+        let shutdown = self.shutdown.clone();
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(Duration::from_millis(1000 * 60));
-            interval.tick().await;
+            tokio::time::sleep(Duration::from_secs(60)).await;
             let mut interval =
                 tokio::time::interval(Duration::from_millis(clean_resource_interval));
-            loop {
-                correct_logic_offset_service_arc.run();
-                clean_consume_queue_service_arc.run();
+            while !shutdown.load(Ordering::Acquire) {
+                let correct_logic_offset_service = correct_logic_offset_service_arc.clone();
+                let clean_consume_queue_service = clean_consume_queue_service_arc.clone();
+                let _ = tokio::task::spawn_blocking(move || {
+                    correct_logic_offset_service.run();
+                    clean_consume_queue_service.run();
+                })
+                .await;
                 interval.tick().await;
             }
         });
@@ -493,6 +715,28 @@ impl DefaultMessageStore {
     }
 }
 
+/// Checks the decoded message against the condition, the index only keeps the hash of a key so
+/// that colliding keys and topics have to be filtered out here.
+fn is_message_matched(msg: &MessageExt, condition: &QueryMessageCondition) -> bool {
+    if msg.get_topic() != &condition.topic {
+        return false;
+    }
+    if !condition.is_tag_matched(msg.get_tags().as_ref()) {
+        return false;
+    }
+    let uniq_key = msg.get_property(&CheetahString::from_static_str(
+        MessageConst::PROPERTY_UNIQ_CLIENT_MESSAGE_ID_KEYIDX,
+    ));
+    let keys = msg.get_keys();
+    condition.keys.iter().any(|key| {
+        uniq_key.as_ref() == Some(key)
+            || keys.as_ref().is_some_and(|keys| {
+                keys.split(MessageConst::KEY_SEPARATOR)
+                    .any(|msg_key| msg_key == key.as_str())
+            })
+    })
+}
+
 fn estimate_in_mem_by_commit_offset(
     offset_py: i64,
     max_offset_py: i64,
@@ -558,6 +802,12 @@ impl MessageStore for DefaultMessageStore {
             },
             self.message_store_config.store_path_root_dir
         );
+        // segments in the tiered storage extend the commit log, load them before recovering
+        if let Some(tiered_file_store) = self.commit_log.get_tiered_file_store() {
+            if !tiered_file_store.load() {
+                return false;
+            }
+        }
         //load Commit log-- init commit mapped file queue
         let mut result = self.commit_log.load();
         if !result {
@@ -585,7 +835,11 @@ impl MessageStore for DefaultMessageStore {
             info!(
                 "message store recover end, and the max phy offset = {}",
                 self.get_max_phy_offset()
-            )
+            );
+
+            if self.message_store_config.rebuild_logic_files_on_startup {
+                self.rebuild_logic_files(0, i64::MAX);
+            }
         }
 
         let max_offset = self.get_max_phy_offset();
@@ -613,7 +867,7 @@ impl MessageStore for DefaultMessageStore {
 
         self.commit_log.start();
 
-        //self.add_schedule_task();
+        self.add_schedule_task();
 
         Ok(())
     }
@@ -723,8 +977,50 @@ impl MessageStore for DefaultMessageStore {
         result
     }
 
+    fn execute_delete_files_manually(&self) {
+        self.clean_commit_log_service
+            .execute_delete_files_manually();
+    }
+
     fn truncate_files(&mut self, offset_to_truncate: i64) -> bool {
-        unimplemented!()
+        let max_phy_offset = self.get_max_phy_offset();
+        if offset_to_truncate >= max_phy_offset {
+            info!(
+                "no need to truncate files, truncate offset is {}, max physical offset is {}",
+                offset_to_truncate, max_phy_offset
+            );
+            return false;
+        }
+        let reput_running = self.reput_message_service.is_started();
+        if reput_running {
+            self.reput_message_service.shutdown();
+        }
+        // truncate consume queue and index
+        self.truncate_dirty_logic_files(offset_to_truncate);
+        // truncate commitLog
+        self.commit_log.truncate_dirty_files(offset_to_truncate);
+        self.recover_topic_queue_table();
+
+        if reput_running {
+            let reput_from_offset = self
+                .reput_message_service
+                .reput_from_offset()
+                .min(offset_to_truncate);
+            self.reput_message_service
+                .set_reput_from_offset(reput_from_offset);
+            self.reput_message_service.start(
+                Arc::new(self.commit_log.clone()),
+                self.message_store_config.clone(),
+                self.dispatcher.clone(),
+                self.notify_message_arrive_in_batch,
+                self.message_store_arc.clone().unwrap(),
+            );
+        }
+        info!(
+            "truncate files to {}, old max physical offset is {}",
+            offset_to_truncate, max_phy_offset
+        );
+        true
     }
 
     fn is_os_page_cache_busy(&self) -> bool {
@@ -1187,12 +1483,13 @@ impl MessageStore for DefaultMessageStore {
             let phy_offsets = query_offset_result.get_phy_offsets();
             for m in 0..phy_offsets.len() {
                 let offset = *phy_offsets.get(m).unwrap();
-                let msg = self.look_message_by_offset(offset);
+                let Some(msg) = self.look_message_by_offset(offset) else {
+                    continue;
+                };
                 if m == 0 {
-                    last_query_msg_time = msg.as_ref().unwrap().store_timestamp;
+                    last_query_msg_time = msg.store_timestamp;
                 }
-                let result = self.commit_log.get_data_with_option(offset, false);
-                if let Some(sbr) = result {
+                if let Some(sbr) = self.commit_log.get_message(offset, msg.store_size) {
                     query_message_result.add_message(sbr);
                 }
             }
@@ -1207,6 +1504,73 @@ impl MessageStore for DefaultMessageStore {
         Some(query_message_result)
     }
 
+    async fn query_message_by_condition(
+        &self,
+        condition: &QueryMessageCondition,
+    ) -> Option<QueryMessageResult> {
+        let max_num = condition
+            .max_num
+            .clamp(1, self.message_store_config.max_msgs_num_batch as i32);
+        let mut query_message_result = QueryMessageResult::default();
+        let mut cursor = if condition.cursor > 0 {
+            condition.cursor
+        } else {
+            i64::MAX
+        };
+        let mut matched = 0;
+        loop {
+            let mut phy_offsets = Vec::new();
+            // offsets not lower than the boundary are complete for every key, the ones below it
+            // may still be missing entries of a key whose page is full
+            let mut boundary = i64::MIN;
+            for key in &condition.keys {
+                let query_offset_result = self.index_service.query_offset_before(
+                    &condition.topic,
+                    key,
+                    max_num,
+                    condition.begin_timestamp,
+                    condition.end_timestamp,
+                    cursor,
+                );
+                query_message_result.index_last_update_timestamp =
+                    query_offset_result.get_index_last_update_timestamp();
+                query_message_result.index_last_update_phyoffset =
+                    query_offset_result.get_index_last_update_phyoffset();
+                let key_offsets = query_offset_result.get_phy_offsets();
+                if key_offsets.len() as i32 >= max_num {
+                    if let Some(min) = key_offsets.iter().min() {
+                        boundary = boundary.max(*min);
+                    }
+                }
+                phy_offsets.extend_from_slice(key_offsets);
+            }
+            phy_offsets.sort_unstable_by(|a, b| b.cmp(a));
+            phy_offsets.dedup();
+
+            for offset in phy_offsets.into_iter().filter(|offset| *offset >= boundary) {
+                let Some(msg) = self.look_message_by_offset(offset) else {
+                    continue;
+                };
+                if !is_message_matched(&msg, condition) {
+                    continue;
+                }
+                if let Some(sbr) = self.commit_log.get_message(offset, msg.store_size) {
+                    query_message_result.add_message(sbr);
+                    matched += 1;
+                }
+                if matched >= max_num {
+                    query_message_result.next_cursor = offset;
+                    return Some(query_message_result);
+                }
+            }
+            if boundary == i64::MIN {
+                break;
+            }
+            cursor = boundary;
+        }
+        Some(query_message_result)
+    }
+
     async fn select_one_message_by_offset(
         &self,
         commit_log_offset: i64,
@@ -1259,18 +1623,12 @@ impl MessageStore for DefaultMessageStore {
         queue_id: i32,
         consume_queue_offset: i64,
     ) -> i64 {
-        let consume_queue = self.find_consume_queue(topic, queue_id);
-        if let Some(consume_queue) = consume_queue {
-            if let Some((_, store_time)) =
-                consume_queue.get_cq_unit_and_store_time(consume_queue_offset)
-            {
-                store_time
-            } else {
-                -1
-            }
-        } else {
-            -1
-        }
+        self.find_consume_queue(topic, queue_id)
+            .and_then(|consume_queue| consume_queue.get(consume_queue_offset))
+            .map_or(-1, |cq_unit| {
+                self.commit_log
+                    .pickup_store_timestamp(cq_unit.pos, cq_unit.size)
+            })
     }
     fn get_runtime_info(&self) -> HashMap<String, String> {
         self.store_stats_service.get_runtime_info()
@@ -1396,6 +1754,16 @@ impl ReputMessageService {
         self.reput_from_offset = Some(Arc::new(AtomicI64::new(reput_from_offset)));
     }
 
+    pub fn reput_from_offset(&self) -> i64 {
+        self.reput_from_offset
+            .as_ref()
+            .map_or(0, |offset| offset.load(Ordering::Acquire))
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.tx.is_some()
+    }
+
     pub fn start(
         &mut self,
         commit_log: Arc<CommitLog>,
@@ -1646,26 +2014,827 @@ impl ReputMessageServiceInner {
     }
 }
 
-struct CleanCommitLogService {}
+const MAX_MANUAL_DELETE_FILE_TIMES: i32 = 20;
+
+/// Returns the used ratio, between 0 and 1, of the disk partition holding a path.
+type DiskSpaceUsedRatio = Box<dyn Fn(&str) -> f64 + Send + Sync>;
+
+/// Deletes commit log files that exceed the reserved time, and deletes them forcibly when the
+/// disk usage goes over the configured water marks.
+struct CleanCommitLogService {
+    message_store_config: Arc<MessageStoreConfig>,
+    commit_log: CommitLog,
+    running_flags: Arc<RunningFlags>,
+    last_redelete_timestamp: AtomicU64,
+    manual_delete_file_several_times: AtomicI32,
+    clean_immediately: AtomicBool,
+    disk_space_used_ratio: DiskSpaceUsedRatio,
+}
 
 impl CleanCommitLogService {
+    fn new(
+        message_store_config: Arc<MessageStoreConfig>,
+        commit_log: CommitLog,
+        running_flags: Arc<RunningFlags>,
+    ) -> Self {
+        Self {
+            message_store_config,
+            commit_log,
+            running_flags,
+            last_redelete_timestamp: AtomicU64::new(0),
+            manual_delete_file_several_times: AtomicI32::new(0),
+            clean_immediately: AtomicBool::new(false),
+            disk_space_used_ratio: Box::new(util_all::get_disk_partition_space_used_percent),
+        }
+    }
+
+    fn execute_delete_files_manually(&self) {
+        self.manual_delete_file_several_times
+            .store(MAX_MANUAL_DELETE_FILE_TIMES, Ordering::SeqCst);
+        info!("executeDeleteFilesManually was invoked");
+    }
+
     fn run(&self) {
-        info!("clean commit log service run unimplemented!")
+        self.delete_expired_files();
+        self.redelete_hanged_file();
+    }
+
+    fn delete_expired_files(&self) {
+        let config = &self.message_store_config;
+        let is_time_up = util_all::is_it_time_to_do(config.delete_when.as_str());
+        let is_usage_exceeds_threshold = self.is_space_to_delete();
+        let is_manual_delete = self.manual_delete_file_several_times.load(Ordering::SeqCst) > 0;
+        if !is_time_up && !is_usage_exceeds_threshold && !is_manual_delete {
+            return;
+        }
+        if is_manual_delete {
+            self.manual_delete_file_several_times
+                .fetch_sub(1, Ordering::SeqCst);
+        }
+
+        let clean_at_once =
+            config.clean_file_forcibly_enable && self.clean_immediately.load(Ordering::SeqCst);
+        info!(
+            "begin to delete before {} hours file. timeUp: {} spaceFull: {} \
+             manualDeleteFileSeveralTimes: {} cleanAtOnce: {} deleteFileBatchMax: {}",
+            config.file_reserved_time,
+            is_time_up,
+            is_usage_exceeds_threshold,
+            self.manual_delete_file_several_times.load(Ordering::SeqCst),
+            clean_at_once,
+            config.delete_file_batch_max
+        );
+
+        let file_reserved_time = config.file_reserved_time as u64 * 60 * 60 * 1000;
+        let delete_count = self.commit_log.delete_expired_file(
+            file_reserved_time,
+            config.delete_commit_log_files_interval as u64,
+            config.destroy_mapped_file_interval_forcibly as u64,
+            clean_at_once,
+            config.delete_file_batch_max,
+        );
+        if delete_count == 0 && is_usage_exceeds_threshold {
+            warn!("disk space will be full soon, but delete file failed.");
+        }
+    }
+
+    fn redelete_hanged_file(&self) {
+        let interval = self.message_store_config.redelete_hanged_file_interval as u64;
+        let current_timestamp = get_current_millis();
+        if current_timestamp.saturating_sub(self.last_redelete_timestamp.load(Ordering::SeqCst))
+            > interval
+        {
+            self.last_redelete_timestamp
+                .store(current_timestamp, Ordering::SeqCst);
+            if self.commit_log.retry_delete_first_file(
+                self.message_store_config
+                    .destroy_mapped_file_interval_forcibly as u64,
+            ) {
+                info!("redelete hanged commit log file OK");
+            }
+        }
+    }
+
+    /// Checks the usage of the commit log and consume queue partitions, updating the disk full
+    /// flags accordingly. Returns `true` when space should be reclaimed.
+    fn is_space_to_delete(&self) -> bool {
+        self.clean_immediately.store(false, Ordering::SeqCst);
+        let config = &self.message_store_config;
+        let warning_ratio = config.disk_space_warning_level_ratio as f64 / 100.0;
+        let clean_forcibly_ratio = config.disk_space_clean_forcibly_ratio as f64 / 100.0;
+        let disk_max_used_space_ratio =
+            config.disk_max_used_space_ratio.clamp(10, 95) as f64 / 100.0;
+
+        let physic_ratio = (self.disk_space_used_ratio)(
+            DefaultMessageStore::get_store_path_physic(config).as_str(),
+        );
+        if physic_ratio > warning_ratio {
+            if self.running_flags.get_and_make_disk_full() {
+                error!(
+                    "physic disk maybe full soon {}, so mark disk full",
+                    physic_ratio
+                );
+            }
+            self.clean_immediately.store(true, Ordering::SeqCst);
+            return true;
+        } else if physic_ratio > clean_forcibly_ratio {
+            self.clean_immediately.store(true, Ordering::SeqCst);
+            return true;
+        } else if !self.running_flags.get_and_make_disk_ok() {
+            info!("physic disk space OK {}, so mark disk ok", physic_ratio);
+        }
+
+        let logics_ratio = (self.disk_space_used_ratio)(
+            DefaultMessageStore::get_store_path_logic(config).as_str(),
+        );
+        if logics_ratio > warning_ratio {
+            if self.running_flags.get_and_make_logic_disk_full() {
+                error!(
+                    "logics disk maybe full soon {}, so mark disk full",
+                    logics_ratio
+                );
+            }
+            self.clean_immediately.store(true, Ordering::SeqCst);
+            return true;
+        } else if logics_ratio > clean_forcibly_ratio {
+            self.clean_immediately.store(true, Ordering::SeqCst);
+            return true;
+        } else if !self.running_flags.get_and_make_logic_disk_ok() {
+            info!("logics disk space OK {}, so mark disk ok", logics_ratio);
+        }
+
+        if physic_ratio < 0.0 || physic_ratio > disk_max_used_space_ratio {
+            info!(
+                "commitLog disk maybe full soon, so reclaim space, {}",
+                physic_ratio
+            );
+            return true;
+        }
+        if logics_ratio < 0.0 || logics_ratio > disk_max_used_space_ratio {
+            info!(
+                "consumeQueue disk maybe full soon, so reclaim space, {}",
+                logics_ratio
+            );
+            return true;
+        }
+        false
     }
 }
 
-struct CleanConsumeQueueService {}
+/// Deletes consume queue and index files that only reference commit log data which has already
+/// been deleted.
+struct CleanConsumeQueueService {
+    message_store_config: Arc<MessageStoreConfig>,
+    commit_log: CommitLog,
+    consume_queue_store: ConsumeQueueStore,
+    index_service: IndexService,
+    last_physical_min_offset: AtomicI64,
+}
 
 impl CleanConsumeQueueService {
+    fn new(
+        message_store_config: Arc<MessageStoreConfig>,
+        commit_log: CommitLog,
+        consume_queue_store: ConsumeQueueStore,
+        index_service: IndexService,
+    ) -> Self {
+        Self {
+            message_store_config,
+            commit_log,
+            consume_queue_store,
+            index_service,
+            last_physical_min_offset: AtomicI64::new(0),
+        }
+    }
+
     fn run(&self) {
-        println!("clean consume queue service run unimplemented!")
+        self.delete_expired_files();
+    }
+
+    fn delete_expired_files(&self) {
+        let delete_logics_files_interval = self
+            .message_store_config
+            .delete_consume_queue_files_interval as u64;
+        let min_offset = self.commit_log.get_min_readable_offset();
+        if min_offset <= self.last_physical_min_offset.load(Ordering::SeqCst) {
+            return;
+        }
+        self.last_physical_min_offset
+            .store(min_offset, Ordering::SeqCst);
+
+        // snapshot the table so that the lock is not held while sleeping between deletions
+        let consume_queue_table = self
+            .consume_queue_store
+            .get_consume_queue_table()
+            .lock()
+            .clone();
+        for consume_queue in consume_queue_table
+            .values()
+            .flat_map(|table| table.values())
+        {
+            let delete_count = self
+                .consume_queue_store
+                .delete_expired_file(consume_queue.as_ref().as_ref(), min_offset);
+            if delete_count > 0 && delete_logics_files_interval > 0 {
+                thread::sleep(Duration::from_millis(delete_logics_files_interval));
+            }
+        }
+        self.index_service.delete_expired_file(min_offset);
     }
 }
 
-struct CorrectLogicOffsetService {}
+/// Keeps the min logic offset of the consume queues in line with the min commit log offset.
+struct CorrectLogicOffsetService {
+    commit_log: CommitLog,
+    consume_queue_store: ConsumeQueueStore,
+}
 
 impl CorrectLogicOffsetService {
+    fn new(commit_log: CommitLog, consume_queue_store: ConsumeQueueStore) -> Self {
+        Self {
+            commit_log,
+            consume_queue_store,
+        }
+    }
+
+    fn run(&self) {
+        let min_phy_offset = self.commit_log.get_min_readable_offset();
+        let consume_queue_table = self
+            .consume_queue_store
+            .get_consume_queue_table()
+            .lock()
+            .clone();
+        for consume_queue in consume_queue_table
+            .values()
+            .flat_map(|table| table.values())
+        {
+            // the min offset of a simple consume queue is corrected when its files are deleted
+            if consume_queue.get_cq_type() == CQType::SimpleCQ {
+                continue;
+            }
+            self.consume_queue_store
+                .correct_min_offset(consume_queue.as_ref().as_ref(), min_phy_offset);
+        }
+    }
+}
+
+/// Uploads the sealed commit log and consume queue segments to the tiered storage, and deletes
+/// the segments kept there for longer than the reserved time once their local files are gone.
+struct TieredStoreService {
+    message_store_config: Arc<MessageStoreConfig>,
+    commit_log: CommitLog,
+    tiered_file_store: Arc<TieredFileStore>,
+}
+
+impl TieredStoreService {
+    fn new(
+        message_store_config: Arc<MessageStoreConfig>,
+        commit_log: CommitLog,
+        tiered_file_store: Arc<TieredFileStore>,
+    ) -> Self {
+        Self {
+            message_store_config,
+            commit_log,
+            tiered_file_store,
+        }
+    }
+
     fn run(&self) {
-        println!("correct logic offset service run unimplemented!")
+        self.upload_commit_log();
+        self.upload_consume_queue();
+        let reserved_time =
+            self.message_store_config.tiered_file_reserved_time as u64 * 60 * 60 * 1000;
+        self.tiered_file_store
+            .delete_expired_segments(reserved_time, self.commit_log.get_min_offset());
+        self.tiered_file_store
+            .delete_expired_consume_queue_segments(reserved_time, &self.consume_queue_root());
+    }
+
+    fn consume_queue_root(&self) -> PathBuf {
+        PathBuf::from(DefaultMessageStore::get_store_path_logic(
+            &self.message_store_config,
+        ))
+    }
+
+    fn upload_commit_log(&self) {
+        let mapped_files = self
+            .commit_log
+            .get_mapped_file_queue()
+            .get_mapped_files()
+            .read()
+            .clone();
+        // the last file is still being written, all the others are sealed
+        let Some((_, sealed_files)) = mapped_files.split_last() else {
+            return;
+        };
+        for mapped_file in sealed_files {
+            let file_from_offset = mapped_file.get_file_from_offset() as i64;
+            if self
+                .tiered_file_store
+                .is_commit_log_segment_uploaded(file_from_offset)
+            {
+                continue;
+            }
+            let path = PathBuf::from(mapped_file.get_file_name().as_str());
+            if !self
+                .tiered_file_store
+                .upload_commit_log_segment(file_from_offset, &path)
+            {
+                // keep the uploaded segments contiguous
+                break;
+            }
+        }
+    }
+
+    fn upload_consume_queue(&self) {
+        let root = self.consume_queue_root();
+        let Ok(topic_dirs) = fs::read_dir(&root) else {
+            return;
+        };
+        for topic_dir in topic_dirs.flatten() {
+            let Ok(queue_dirs) = fs::read_dir(topic_dir.path()) else {
+                continue;
+            };
+            for queue_dir in queue_dirs.flatten() {
+                let Ok(files) = fs::read_dir(queue_dir.path()) else {
+                    continue;
+                };
+                let mut files = files
+                    .flatten()
+                    .map(|file| file.path())
+                    .filter(|path| path.is_file())
+                    .collect::<Vec<_>>();
+                files.sort();
+                // the last file is still being written, all the others are sealed
+                files.pop();
+                for file in files {
+                    let Ok(relative_path) = file.strip_prefix(&root) else {
+                        continue;
+                    };
+                    let relative_path = relative_path
+                        .components()
+                        .map(|component| component.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    if !self
+                        .tiered_file_store
+                        .is_consume_queue_segment_uploaded(&relative_path)
+                    {
+                        self.tiered_file_store
+                            .upload_consume_queue_segment(&relative_path, &file);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BufMut;
+    use bytes::BytesMut;
+    use rocketmq_common::common::message::MessageTrait;
+
+    use super::*;
+    use crate::message_encoder::message_ext_encoder::MessageExtEncoder;
+
+    const MAPPED_FILE_SIZE: usize = 64 * 1024;
+    const STORE_TIMESTAMP: i64 = 1_700_000_000_000;
+
+    fn write_commit_log(config: &Arc<MessageStoreConfig>, topic: &CheetahString) -> Vec<i64> {
+        let mut data = BytesMut::new();
+        let mut offsets = Vec::new();
+        for queue_offset in 0..3 {
+            let mut msg = MessageExtBrokerInner::default();
+            msg.set_topic(topic.clone());
+            msg.set_body(bytes::Bytes::from_static(b"rebuild"));
+            msg.properties_string = CheetahString::from_static_str("KEYS\u{1}k1\u{2}");
+            msg.message_ext_inner.queue_offset = queue_offset;
+            msg.message_ext_inner.store_timestamp = STORE_TIMESTAMP + queue_offset;
+            let mut encoder = MessageExtEncoder::new(config.clone());
+            assert!(encoder.encode(&msg).is_none());
+            let mut buffer = encoder.byte_buf().split();
+            // physical offset is filled in when the message is appended to the commit log
+            let phy_offset = data.len() as i64;
+            (&mut buffer[28..36]).put_i64(phy_offset);
+            offsets.push(phy_offset);
+            data.extend_from_slice(&buffer);
+        }
+        data.resize(MAPPED_FILE_SIZE, 0);
+        let commit_log_dir = PathBuf::from(config.get_store_path_commit_log());
+        fs::create_dir_all(&commit_log_dir).unwrap();
+        fs::write(commit_log_dir.join("00000000000000000000"), &data).unwrap();
+        offsets
+    }
+
+    #[tokio::test]
+    async fn rebuild_logic_files_from_commit_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Arc::new(MessageStoreConfig {
+            store_path_root_dir: CheetahString::from(dir.path().to_string_lossy().to_string()),
+            mapped_file_size_commit_log: MAPPED_FILE_SIZE,
+            ..MessageStoreConfig::default()
+        });
+        let topic = CheetahString::from_static_str("rebuild_topic");
+        let offsets = write_commit_log(&config, &topic);
+
+        let mut message_store = DefaultMessageStore::new(
+            config,
+            Arc::new(BrokerConfig::default()),
+            Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            None,
+            false,
+        );
+        assert!(message_store.load_offline());
+        assert_eq!(message_store.rebuild_logic_files(0, i64::MAX), 3);
+        assert_eq!(message_store.get_max_offset_in_queue(&topic, 0), 3);
+
+        // entries before the rebuild range are kept, the rest is dispatched again
+        assert_eq!(message_store.rebuild_logic_files(offsets[1], i64::MAX), 2);
+        assert_eq!(message_store.get_max_offset_in_queue(&topic, 0), 3);
+
+        // entries beyond the rebuild range are dropped
+        assert_eq!(message_store.rebuild_logic_files(offsets[1], offsets[2]), 1);
+        assert_eq!(message_store.get_max_offset_in_queue(&topic, 0), 2);
+    }
+
+    #[tokio::test]
+    async fn get_message_store_timestamp_reads_commit_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Arc::new(MessageStoreConfig {
+            store_path_root_dir: CheetahString::from(dir.path().to_string_lossy().to_string()),
+            mapped_file_size_commit_log: MAPPED_FILE_SIZE,
+            ..MessageStoreConfig::default()
+        });
+        let topic = CheetahString::from_static_str("timestamp_topic");
+        write_commit_log(&config, &topic);
+
+        let mut message_store = DefaultMessageStore::new(
+            config,
+            Arc::new(BrokerConfig::default()),
+            Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            None,
+            false,
+        );
+        assert!(message_store.load_offline());
+        assert_eq!(message_store.rebuild_logic_files(0, i64::MAX), 3);
+
+        assert_eq!(
+            message_store.get_message_store_timestamp(&topic, 0, 2),
+            STORE_TIMESTAMP + 2
+        );
+        assert_eq!(message_store.get_message_store_timestamp(&topic, 0, 3), -1);
+        assert_eq!(message_store.get_message_store_timestamp(&topic, 1, 0), -1);
+    }
+
+    #[tokio::test]
+    async fn consume_queue_get_and_pickup_store_timestamp() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Arc::new(MessageStoreConfig {
+            store_path_root_dir: CheetahString::from(dir.path().to_string_lossy().to_string()),
+            mapped_file_size_commit_log: MAPPED_FILE_SIZE,
+            ..MessageStoreConfig::default()
+        });
+        let topic = CheetahString::from_static_str("cq_get_topic");
+        let offsets = write_commit_log(&config, &topic);
+
+        let mut message_store = DefaultMessageStore::new(
+            config,
+            Arc::new(BrokerConfig::default()),
+            Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            None,
+            false,
+        );
+        assert!(message_store.load_offline());
+        assert_eq!(message_store.rebuild_logic_files(0, i64::MAX), 3);
+
+        let consume_queue = message_store.find_consume_queue(&topic, 0).unwrap();
+        let cq_unit = consume_queue.get(1).unwrap();
+        assert_eq!(cq_unit.queue_offset, 1);
+        assert_eq!(cq_unit.pos, offsets[1]);
+        assert!(consume_queue.get(3).is_none());
+
+        assert_eq!(
+            message_store
+                .commit_log
+                .pickup_store_timestamp(cq_unit.pos, cq_unit.size),
+            STORE_TIMESTAMP + 1
+        );
+        // offsets outside the commit log are rejected
+        assert_eq!(
+            message_store
+                .commit_log
+                .pickup_store_timestamp(MAPPED_FILE_SIZE as i64, cq_unit.size),
+            -1
+        );
+    }
+
+    #[tokio::test]
+    async fn query_message_by_condition_pages_through_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Arc::new(MessageStoreConfig {
+            store_path_root_dir: CheetahString::from(dir.path().to_string_lossy().to_string()),
+            mapped_file_size_commit_log: MAPPED_FILE_SIZE,
+            ..MessageStoreConfig::default()
+        });
+        let topic = CheetahString::from_static_str("query_topic");
+        let offsets = write_commit_log(&config, &topic);
+
+        let mut message_store = DefaultMessageStore::new(
+            config,
+            Arc::new(BrokerConfig::default()),
+            Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            None,
+            false,
+        );
+        assert!(message_store.load_offline());
+        assert_eq!(message_store.rebuild_logic_files(0, i64::MAX), 3);
+
+        let mut condition = QueryMessageCondition {
+            topic: topic.clone(),
+            keys: vec![CheetahString::from_static_str("k1")],
+            begin_timestamp: 0,
+            end_timestamp: i64::MAX,
+            max_num: 2,
+            ..Default::default()
+        };
+        let first_page = message_store
+            .query_message_by_condition(&condition)
+            .await
+            .unwrap();
+        assert_eq!(first_page.message_maped_list.len(), 2);
+        assert_eq!(first_page.next_cursor, offsets[1]);
+
+        condition.cursor = first_page.next_cursor;
+        let second_page = message_store
+            .query_message_by_condition(&condition)
+            .await
+            .unwrap();
+        assert_eq!(second_page.message_maped_list.len(), 1);
+        assert!(second_page.next_cursor <= 0);
+
+        condition.cursor = 0;
+        condition.tags = vec![CheetahString::from_static_str("TagA")];
+        let filtered = message_store
+            .query_message_by_condition(&condition)
+            .await
+            .unwrap();
+        assert!(filtered.message_maped_list.is_empty());
+
+        condition.tags.clear();
+        condition.keys = vec![CheetahString::from_static_str("k2")];
+        let not_found = message_store
+            .query_message_by_condition(&condition)
+            .await
+            .unwrap();
+        assert!(not_found.message_maped_list.is_empty());
+    }
+
+    #[tokio::test]
+    async fn query_message_by_key_is_limited_by_max_num() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Arc::new(MessageStoreConfig {
+            store_path_root_dir: CheetahString::from(dir.path().to_string_lossy().to_string()),
+            mapped_file_size_commit_log: MAPPED_FILE_SIZE,
+            ..MessageStoreConfig::default()
+        });
+        let topic = CheetahString::from_static_str("query_key_topic");
+        let key = CheetahString::from_static_str("k1");
+        write_commit_log(&config, &topic);
+
+        let mut message_store = DefaultMessageStore::new(
+            config.clone(),
+            Arc::new(BrokerConfig::default()),
+            Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            None,
+            false,
+        );
+        assert!(message_store.load_offline());
+        assert_eq!(message_store.rebuild_logic_files(0, i64::MAX), 3);
+
+        // unique key lookups use the default limit, which has to find something
+        assert_eq!(config.default_query_max_num, 32);
+        let all = message_store
+            .query_message(
+                &topic,
+                &key,
+                config.default_query_max_num as i32,
+                0,
+                i64::MAX,
+            )
+            .await
+            .unwrap();
+        assert_eq!(all.message_maped_list.len(), 3);
+
+        let limited = message_store
+            .query_message(&topic, &key, 2, 0, i64::MAX)
+            .await
+            .unwrap();
+        assert_eq!(limited.message_maped_list.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn get_message_from_tiered_storage_after_local_file_is_deleted() {
+        let dir = tempfile::tempdir().unwrap();
+        let tier = tempfile::tempdir().unwrap();
+        let config = Arc::new(MessageStoreConfig {
+            store_path_root_dir: CheetahString::from(dir.path().to_string_lossy().to_string()),
+            mapped_file_size_commit_log: MAPPED_FILE_SIZE,
+            tiered_storage_enable: true,
+            ..MessageStoreConfig::default()
+        });
+        let topic = CheetahString::from_static_str("tiered_topic");
+        let group = CheetahString::from_static_str("tiered_group");
+        write_commit_log(&config, &topic);
+        // the file being written, which seals the first one
+        let commit_log_dir = PathBuf::from(config.get_store_path_commit_log());
+        let first_file = commit_log_dir.join("00000000000000000000");
+        fs::write(
+            commit_log_dir.join(format!("{:020}", MAPPED_FILE_SIZE)),
+            vec![0; MAPPED_FILE_SIZE],
+        )
+        .unwrap();
+
+        let mut message_store = DefaultMessageStore::new_with_tiered_storage_backend(
+            config,
+            Arc::new(BrokerConfig::default()),
+            Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            None,
+            false,
+            Some(Arc::new(LocalDirectoryBackend::new(tier.path()))),
+        );
+        assert!(message_store.load_offline());
+        assert_eq!(message_store.rebuild_logic_files(0, i64::MAX), 3);
+
+        message_store.tiered_store_service.as_ref().unwrap().run();
+        assert!(tier.path().join("commitlog/00000000000000000000").exists());
+        assert_eq!(
+            message_store
+                .commit_log
+                .delete_expired_file(0, 0, 0, true, 10),
+            1
+        );
+        assert!(!first_file.exists());
+        assert_eq!(
+            message_store.commit_log.get_min_offset(),
+            MAPPED_FILE_SIZE as i64
+        );
+
+        let result = message_store
+            .get_message(&group, &topic, 0, 0, 32, None)
+            .await
+            .unwrap();
+        assert_eq!(result.status(), Some(GetMessageStatus::Found));
+        assert_eq!(result.message_count(), 3);
+        let message = MessageDecoder::decode(
+            &mut result.message_mapped_list()[1].get_bytes().unwrap(),
+            true,
+            true,
+            false,
+            false,
+            false,
+        )
+        .unwrap();
+        assert_eq!(message.queue_offset, 1);
+    }
+
+    /// A clean commit log service of `message_store` seeing the disk used at the ratio stored in
+    /// the returned cell, in percent.
+    fn clean_commit_log_service(
+        message_store: &DefaultMessageStore,
+    ) -> (CleanCommitLogService, Arc<AtomicU64>) {
+        let used_percent = Arc::new(AtomicU64::new(0));
+        let ratio = used_percent.clone();
+        let service = CleanCommitLogService {
+            disk_space_used_ratio: Box::new(move |_| ratio.load(Ordering::SeqCst) as f64 / 100.0),
+            ..CleanCommitLogService::new(
+                message_store.message_store_config.clone(),
+                message_store.commit_log.clone(),
+                message_store.running_flags.clone(),
+            )
+        };
+        (service, used_percent)
+    }
+
+    #[tokio::test]
+    async fn clean_commit_log_deletes_forcibly_when_disk_usage_passes_threshold() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Arc::new(MessageStoreConfig {
+            store_path_root_dir: CheetahString::from(dir.path().to_string_lossy().to_string()),
+            mapped_file_size_commit_log: MAPPED_FILE_SIZE,
+            // never time to delete, only disk pressure triggers it
+            delete_when: "99".to_string(),
+            ..MessageStoreConfig::default()
+        });
+        write_commit_log(&config, &CheetahString::from_static_str("clean_topic"));
+        let commit_log_dir = PathBuf::from(config.get_store_path_commit_log());
+        let first_file = commit_log_dir.join("00000000000000000000");
+        fs::write(
+            commit_log_dir.join(format!("{:020}", MAPPED_FILE_SIZE)),
+            vec![0; MAPPED_FILE_SIZE],
+        )
+        .unwrap();
+
+        let mut message_store = DefaultMessageStore::new(
+            config.clone(),
+            Arc::new(BrokerConfig::default()),
+            Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            None,
+            false,
+        );
+        assert!(message_store.load_offline());
+        let (service, used_percent) = clean_commit_log_service(&message_store);
+
+        // the files are within their reserved time and the disk has room
+        used_percent.store(50, Ordering::SeqCst);
+        assert!(!service.is_space_to_delete());
+        service.delete_expired_files();
+        assert!(first_file.exists());
+
+        // past the forcible clean ratio, the files are deleted before their reserved time
+        used_percent.store(
+            config.disk_space_clean_forcibly_ratio as u64 + 1,
+            Ordering::SeqCst,
+        );
+        service.delete_expired_files();
+        assert!(!first_file.exists());
+        assert_eq!(
+            message_store.commit_log.get_min_offset(),
+            MAPPED_FILE_SIZE as i64
+        );
+        assert!(message_store.get_running_flags().is_writeable());
+    }
+
+    #[tokio::test]
+    async fn disk_full_flag_follows_disk_usage() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Arc::new(MessageStoreConfig {
+            store_path_root_dir: CheetahString::from(dir.path().to_string_lossy().to_string()),
+            ..MessageStoreConfig::default()
+        });
+        let message_store = DefaultMessageStore::new(
+            config.clone(),
+            Arc::new(BrokerConfig::default()),
+            Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            None,
+            false,
+        );
+        let (service, used_percent) = clean_commit_log_service(&message_store);
+        assert!(message_store.get_running_flags().is_writeable());
+
+        // over the warning level the store refuses writes
+        used_percent.store(
+            config.disk_space_warning_level_ratio as u64 + 1,
+            Ordering::SeqCst,
+        );
+        assert!(service.is_space_to_delete());
+        assert!(!message_store.get_running_flags().is_writeable());
+        assert!(service.clean_immediately.load(Ordering::SeqCst));
+
+        // once space is reclaimed writes are accepted again
+        used_percent.store(50, Ordering::SeqCst);
+        assert!(!service.is_space_to_delete());
+        assert!(message_store.get_running_flags().is_writeable());
+        assert!(!service.clean_immediately.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn tiered_store_service_uploads_sealed_consume_queue_segments() {
+        let dir = tempfile::tempdir().unwrap();
+        let tier = tempfile::tempdir().unwrap();
+        let config = Arc::new(MessageStoreConfig {
+            store_path_root_dir: CheetahString::from(dir.path().to_string_lossy().to_string()),
+            tiered_storage_enable: true,
+            tiered_file_reserved_time: 0,
+            ..MessageStoreConfig::default()
+        });
+        let queue_dir = PathBuf::from(DefaultMessageStore::get_store_path_logic(&config))
+            .join("tiered_topic")
+            .join("0");
+        fs::create_dir_all(&queue_dir).unwrap();
+        let sealed_file = queue_dir.join("00000000000000000000");
+        fs::write(&sealed_file, b"sealed").unwrap();
+        fs::write(queue_dir.join("00000000000000006000"), b"written").unwrap();
+
+        let message_store = DefaultMessageStore::new_with_tiered_storage_backend(
+            config,
+            Arc::new(BrokerConfig::default()),
+            Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            None,
+            false,
+            Some(Arc::new(LocalDirectoryBackend::new(tier.path()))),
+        );
+        let service = message_store.tiered_store_service.as_ref().unwrap();
+        let tiered_queue_dir = tier.path().join("consumequeue/tiered_topic/0");
+
+        service.run();
+        // the file being written is not uploaded, the uploaded one is kept while it is local
+        assert!(tiered_queue_dir.join("00000000000000000000").exists());
+        assert!(!tiered_queue_dir.join("00000000000000006000").exists());
+
+        fs::remove_file(&sealed_file).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        service.run();
+        assert!(!tiered_queue_dir.join("00000000000000000000").exists());
     }
 }
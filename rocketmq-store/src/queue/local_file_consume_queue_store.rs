@@ -168,7 +168,7 @@ impl ConsumeQueueStoreTrait for ConsumeQueueStore {
 
     #[inline]
     fn flush(&self, consume_queue: &dyn ConsumeQueueTrait, flush_least_pages: i32) -> bool {
-        todo!()
+        consume_queue.flush(flush_least_pages)
     }
 
     #[inline]
@@ -178,7 +178,11 @@ impl ConsumeQueueStoreTrait for ConsumeQueueStore {
 
     #[inline]
     fn check_self(&self) {
-        println!("ConsumeQueueStore::check_self unimplemented");
+        for consume_queue_table in self.inner.consume_queue_table.lock().values() {
+            for consume_queue in consume_queue_table.values() {
+                consume_queue.check_self();
+            }
+        }
     }
 
     #[inline]
@@ -187,17 +191,17 @@ impl ConsumeQueueStoreTrait for ConsumeQueueStore {
         consume_queue: &dyn ConsumeQueueTrait,
         min_commit_log_pos: i64,
     ) -> i32 {
-        todo!()
+        consume_queue.delete_expired_file(min_commit_log_pos)
     }
 
     #[inline]
     fn is_first_file_available(&self, consume_queue: &dyn ConsumeQueueTrait) -> bool {
-        todo!()
+        consume_queue.is_first_file_available()
     }
 
     #[inline]
     fn is_first_file_exist(&self, consume_queue: &dyn ConsumeQueueTrait) -> bool {
-        todo!()
+        consume_queue.is_first_file_exist()
     }
 
     #[inline]
@@ -425,7 +429,8 @@ impl ConsumeQueueStoreTrait for ConsumeQueueStore {
 
     #[inline]
     fn get_max_offset_in_queue(&self, topic: &CheetahString, queue_id: i32) -> i64 {
-        todo!()
+        let queue = self.find_or_create_consume_queue(topic, queue_id);
+        queue.get_max_offset_in_queue()
     }
 
     #[inline]
@@ -458,12 +458,12 @@ impl FileQueueLifeCycle for ConsumeQueue {
 
     #[inline]
     fn check_self(&self) {
-        todo!()
+        self.mapped_file_queue.check_self();
     }
 
     #[inline]
     fn flush(&self, flush_least_pages: i32) -> bool {
-        todo!()
+        self.mapped_file_queue.flush(flush_least_pages)
     }
 
     #[inline]
@@ -483,7 +483,11 @@ impl FileQueueLifeCycle for ConsumeQueue {
 
     #[inline]
     fn delete_expired_file(&self, min_commit_log_pos: i64) -> i32 {
-        todo!()
+        let count = self
+            .mapped_file_queue
+            .delete_expired_file_by_offset(min_commit_log_pos, CQ_STORE_UNIT_SIZE);
+        self.correct_min_offset(min_commit_log_pos);
+        count
     }
 
     #[inline]
@@ -493,12 +497,14 @@ impl FileQueueLifeCycle for ConsumeQueue {
 
     #[inline]
     fn is_first_file_available(&self) -> bool {
-        todo!()
+        self.mapped_file_queue
+            .get_first_mapped_file()
+            .is_some_and(|mapped_file| mapped_file.is_available())
     }
 
     #[inline]
     fn is_first_file_exist(&self) -> bool {
-        todo!()
+        self.mapped_file_queue.get_first_mapped_file().is_some()
     }
 }
 
@@ -533,10 +539,8 @@ impl ConsumeQueueTrait for ConsumeQueue {
 
     #[inline]
     fn get(&self, index: i64) -> Option<CqUnit> {
-        match self.iterate_from(index) {
-            None => None,
-            Some(value) => None,
-        }
+        self.iterate_from(index)
+            .and_then(|mut iterator| iterator.next())
     }
 
     #[inline]
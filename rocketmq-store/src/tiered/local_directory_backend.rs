@@ -0,0 +1,167 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+
+use bytes::Bytes;
+
+use crate::tiered::tiered_storage_backend::TieredObject;
+use crate::tiered::tiered_storage_backend::TieredStorageBackend;
+
+const TEMP_FILE_SUFFIX: &str = ".tmp";
+
+/// Tiered storage backend keeping the objects as files under a local directory, mostly useful
+/// for testing or for a cheap mounted volume.
+pub struct LocalDirectoryBackend {
+    root: PathBuf,
+}
+
+impl LocalDirectoryBackend {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    #[inline]
+    fn object_path(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+
+    fn collect_objects(
+        &self,
+        dir: &Path,
+        prefix: &str,
+        objects: &mut Vec<TieredObject>,
+    ) -> io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let metadata = entry.metadata()?;
+            if metadata.is_dir() {
+                self.collect_objects(&path, prefix, objects)?;
+                continue;
+            }
+            let Ok(relative) = path.strip_prefix(&self.root) else {
+                continue;
+            };
+            let key = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            if !key.starts_with(prefix) || key.ends_with(TEMP_FILE_SUFFIX) {
+                continue;
+            }
+            let last_modified = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |duration| duration.as_millis() as u64);
+            objects.push(TieredObject {
+                key,
+                size: metadata.len(),
+                last_modified,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl TieredStorageBackend for LocalDirectoryBackend {
+    fn upload(&self, key: &str, source: &Path) -> io::Result<()> {
+        let target = self.object_path(key);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // copy aside first so that a half written object is never visible under its key
+        let mut temp = target.clone().into_os_string();
+        temp.push(TEMP_FILE_SUFFIX);
+        fs::copy(source, &temp)?;
+        fs::rename(&temp, &target)
+    }
+
+    fn read(&self, key: &str, position: u64, length: usize) -> io::Result<Bytes> {
+        let mut file = File::open(self.object_path(key))?;
+        file.seek(SeekFrom::Start(position))?;
+        let mut buffer = vec![0u8; length];
+        file.read_exact(&mut buffer)?;
+        Ok(Bytes::from(buffer))
+    }
+
+    fn list(&self, prefix: &str) -> io::Result<Vec<TieredObject>> {
+        let mut objects = Vec::new();
+        if self.root.exists() {
+            self.collect_objects(&self.root, prefix, &mut objects)?;
+        }
+        objects.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(objects)
+    }
+
+    fn delete(&self, key: &str) -> io::Result<()> {
+        match fs::remove_file(self.object_path(key)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upload_read_list_and_delete() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let source = source_dir.path().join("00000000000000000000");
+        fs::write(&source, b"0123456789").unwrap();
+
+        let root = tempfile::tempdir().unwrap();
+        let backend = LocalDirectoryBackend::new(root.path());
+        backend
+            .upload("commitlog/00000000000000000000", &source)
+            .unwrap();
+        backend
+            .upload("consumequeue/topic/0/00000000000000000000", &source)
+            .unwrap();
+
+        assert_eq!(
+            backend
+                .read("commitlog/00000000000000000000", 2, 4)
+                .unwrap()
+                .as_ref(),
+            b"2345"
+        );
+        let objects = backend.list("commitlog/").unwrap();
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0].key, "commitlog/00000000000000000000");
+        assert_eq!(objects[0].size, 10);
+        assert_eq!(backend.list("").unwrap().len(), 2);
+
+        backend.delete("commitlog/00000000000000000000").unwrap();
+        backend.delete("commitlog/00000000000000000000").unwrap();
+        assert!(backend.list("commitlog/").unwrap().is_empty());
+        assert!(backend
+            .read("commitlog/00000000000000000000", 0, 1)
+            .is_err());
+    }
+}
@@ -0,0 +1,407 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use parking_lot::Mutex;
+use parking_lot::RwLock;
+use rocketmq_common::TimeUtils::get_current_millis;
+use tracing::error;
+use tracing::info;
+use tracing::warn;
+
+use crate::tiered::tiered_storage_backend::TieredObject;
+use crate::tiered::tiered_storage_backend::TieredStorageBackend;
+
+pub const COMMIT_LOG_PREFIX: &str = "commitlog/";
+pub const CONSUME_QUEUE_PREFIX: &str = "consumequeue/";
+
+/// Keeps track of the segments uploaded to a [`TieredStorageBackend`] and serves the commit log
+/// data that is no longer on the local disk.
+pub struct TieredFileStore {
+    backend: Arc<dyn TieredStorageBackend>,
+    /// Uploaded commit log segments by the physical offset they start at.
+    commit_log_segments: RwLock<BTreeMap<i64, TieredObject>>,
+    /// Uploaded consume queue segments by key.
+    consume_queue_segments: Mutex<HashMap<String, TieredObject>>,
+}
+
+impl TieredFileStore {
+    pub fn new(backend: Arc<dyn TieredStorageBackend>) -> Self {
+        Self {
+            backend,
+            commit_log_segments: RwLock::new(BTreeMap::new()),
+            consume_queue_segments: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Loads the segments already in the backend.
+    pub fn load(&self) -> bool {
+        let commit_log_objects = match self.backend.list(COMMIT_LOG_PREFIX) {
+            Ok(objects) => objects,
+            Err(err) => {
+                error!("list tiered commit log segments failed, {}", err);
+                return false;
+            }
+        };
+        let consume_queue_objects = match self.backend.list(CONSUME_QUEUE_PREFIX) {
+            Ok(objects) => objects,
+            Err(err) => {
+                error!("list tiered consume queue segments failed, {}", err);
+                return false;
+            }
+        };
+
+        let mut commit_log_segments = self.commit_log_segments.write();
+        commit_log_segments.clear();
+        for object in commit_log_objects {
+            match object.key[COMMIT_LOG_PREFIX.len()..].parse::<i64>() {
+                Ok(offset) => {
+                    commit_log_segments.insert(offset, object);
+                }
+                Err(_) => warn!("unknown tiered commit log segment {}", object.key),
+            }
+        }
+        let mut consume_queue_segments = self.consume_queue_segments.lock();
+        consume_queue_segments.clear();
+        for object in consume_queue_objects {
+            consume_queue_segments.insert(object.key.clone(), object);
+        }
+        info!(
+            "load tiered storage OK, commit log segments: {}, consume queue segments: {}",
+            commit_log_segments.len(),
+            consume_queue_segments.len()
+        );
+        true
+    }
+
+    /// The physical offset of the oldest message in the tiered storage.
+    pub fn get_min_offset(&self) -> Option<i64> {
+        self.commit_log_segments
+            .read()
+            .first_key_value()
+            .map(|(offset, _)| *offset)
+    }
+
+    /// The physical offset right after the newest segment in the tiered storage.
+    pub fn get_max_offset(&self) -> Option<i64> {
+        self.commit_log_segments
+            .read()
+            .last_key_value()
+            .map(|(offset, object)| offset + object.size as i64)
+    }
+
+    #[inline]
+    pub fn is_commit_log_segment_uploaded(&self, file_from_offset: i64) -> bool {
+        self.commit_log_segments
+            .read()
+            .contains_key(&file_from_offset)
+    }
+
+    /// Whether the consume queue segment at `relative_path` is uploaded, see
+    /// [`TieredFileStore::upload_consume_queue_segment`].
+    #[inline]
+    pub fn is_consume_queue_segment_uploaded(&self, relative_path: &str) -> bool {
+        self.consume_queue_segments
+            .lock()
+            .contains_key(&format!("{}{}", CONSUME_QUEUE_PREFIX, relative_path))
+    }
+
+    /// Returns the end of the uploaded segments following `offset` without any gap, the local
+    /// commit log files before it can be safely deleted.
+    pub fn get_uploaded_end_offset(&self, offset: i64) -> i64 {
+        let mut end_offset = offset;
+        for (file_from_offset, object) in self.commit_log_segments.read().range(offset..) {
+            if *file_from_offset != end_offset {
+                break;
+            }
+            end_offset += object.size as i64;
+        }
+        end_offset
+    }
+
+    pub fn upload_commit_log_segment(&self, file_from_offset: i64, source: &Path) -> bool {
+        let key = format!("{}{:020}", COMMIT_LOG_PREFIX, file_from_offset);
+        match self.upload(key, source) {
+            Some(object) => {
+                self.commit_log_segments
+                    .write()
+                    .insert(file_from_offset, object);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Uploads a consume queue segment, `relative_path` is its path under the consume queue
+    /// store directory, i.e. `topic/queue_id/file_name`.
+    pub fn upload_consume_queue_segment(&self, relative_path: &str, source: &Path) -> bool {
+        let key = format!("{}{}", CONSUME_QUEUE_PREFIX, relative_path);
+        match self.upload(key, source) {
+            Some(object) => {
+                self.consume_queue_segments
+                    .lock()
+                    .insert(object.key.clone(), object);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn upload(&self, key: String, source: &Path) -> Option<TieredObject> {
+        let size = match source.metadata() {
+            Ok(metadata) => metadata.len(),
+            Err(err) => {
+                error!("upload {} failed, {}", source.display(), err);
+                return None;
+            }
+        };
+        if let Err(err) = self.backend.upload(&key, source) {
+            error!("upload {} to {} failed, {}", source.display(), key, err);
+            return None;
+        }
+        info!("upload {} to tiered storage OK, size: {}", key, size);
+        Some(TieredObject {
+            key,
+            size,
+            last_modified: get_current_millis(),
+        })
+    }
+
+    /// Reads a message of `size` bytes at the physical `offset` from the tiered storage.
+    pub fn read_commit_log(&self, offset: i64, size: i32) -> Option<Bytes> {
+        if offset < 0 || size <= 0 {
+            return None;
+        }
+        let (file_from_offset, key) = {
+            let segments = self.commit_log_segments.read();
+            let (file_from_offset, object) = segments.range(..=offset).next_back()?;
+            if offset + size as i64 > file_from_offset + object.size as i64 {
+                return None;
+            }
+            (*file_from_offset, object.key.clone())
+        };
+        match self
+            .backend
+            .read(&key, (offset - file_from_offset) as u64, size as usize)
+        {
+            Ok(bytes) => Some(bytes),
+            Err(err) => {
+                error!(
+                    "read tiered commit log failed, offset: {}, size: {}, {}",
+                    offset, size, err
+                );
+                None
+            }
+        }
+    }
+
+    /// Deletes the commit log segments uploaded more than `reserved_time` milliseconds ago,
+    /// returns the number of deleted segments.
+    ///
+    /// A segment is only deleted once its local file is gone, i.e. it ends at or before
+    /// `local_min_offset`, otherwise the still sealed local file would be uploaded again. A
+    /// negative `local_min_offset` means there is no local file left.
+    pub fn delete_expired_segments(&self, reserved_time: u64, local_min_offset: i64) -> usize {
+        let now = get_current_millis();
+        let mut deleted = 0;
+        loop {
+            // segments are deleted from the oldest one, without leaving holes
+            let Some((file_from_offset, object)) = self
+                .commit_log_segments
+                .read()
+                .first_key_value()
+                .map(|(offset, object)| (*offset, object.clone()))
+            else {
+                break;
+            };
+            if object.last_modified + reserved_time >= now {
+                break;
+            }
+            if local_min_offset >= 0 && file_from_offset + object.size as i64 > local_min_offset {
+                break;
+            }
+            if let Err(err) = self.backend.delete(&object.key) {
+                error!("delete tiered segment {} failed, {}", object.key, err);
+                break;
+            }
+            info!("delete expired tiered segment {}", object.key);
+            self.commit_log_segments.write().remove(&file_from_offset);
+            deleted += 1;
+        }
+        deleted
+    }
+
+    /// Deletes the consume queue segments uploaded more than `reserved_time` milliseconds ago,
+    /// returns the number of deleted segments.
+    ///
+    /// Like commit log segments, a consume queue segment is only deleted once its local file
+    /// under `local_root`, the consume queue store directory, is gone.
+    pub fn delete_expired_consume_queue_segments(
+        &self,
+        reserved_time: u64,
+        local_root: &Path,
+    ) -> usize {
+        let now = get_current_millis();
+        let expired = self
+            .consume_queue_segments
+            .lock()
+            .iter()
+            .filter(|(_, object)| object.last_modified + reserved_time < now)
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        let mut deleted = 0;
+        for key in expired {
+            if local_root.join(&key[CONSUME_QUEUE_PREFIX.len()..]).exists() {
+                continue;
+            }
+            if let Err(err) = self.backend.delete(&key) {
+                error!("delete tiered segment {} failed, {}", key, err);
+                continue;
+            }
+            info!("delete expired tiered segment {}", key);
+            self.consume_queue_segments.lock().remove(&key);
+            deleted += 1;
+        }
+        deleted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::tiered::local_directory_backend::LocalDirectoryBackend;
+
+    #[test]
+    fn upload_and_read_commit_log_segments() {
+        let local = tempfile::tempdir().unwrap();
+        let first = local.path().join("00000000000000000000");
+        let second = local.path().join("00000000000000000008");
+        fs::write(&first, b"aaaabbbb").unwrap();
+        fs::write(&second, b"ccccdddd").unwrap();
+
+        let tier = tempfile::tempdir().unwrap();
+        let store = TieredFileStore::new(Arc::new(LocalDirectoryBackend::new(tier.path())));
+        assert!(store.load());
+        assert_eq!(store.get_min_offset(), None);
+        assert_eq!(store.get_uploaded_end_offset(0), 0);
+
+        assert!(store.upload_commit_log_segment(0, &first));
+        assert!(store.upload_commit_log_segment(8, &second));
+        assert_eq!(store.get_min_offset(), Some(0));
+        assert_eq!(store.get_max_offset(), Some(16));
+        assert_eq!(store.get_uploaded_end_offset(0), 16);
+        assert_eq!(store.read_commit_log(4, 4).unwrap().as_ref(), b"bbbb");
+        assert_eq!(store.read_commit_log(12, 4).unwrap().as_ref(), b"dddd");
+        // a message never spans two segments
+        assert!(store.read_commit_log(6, 4).is_none());
+        assert!(store.read_commit_log(16, 4).is_none());
+
+        // segments are found again after a restart
+        let reloaded = TieredFileStore::new(Arc::new(LocalDirectoryBackend::new(tier.path())));
+        assert!(reloaded.load());
+        assert_eq!(reloaded.get_max_offset(), Some(16));
+        assert!(reloaded.is_commit_log_segment_uploaded(8));
+    }
+
+    #[test]
+    fn delete_expired_segments_after_local_files_are_gone() {
+        let local = tempfile::tempdir().unwrap();
+        let first = local.path().join("00000000000000000000");
+        let second = local.path().join("00000000000000000004");
+        fs::write(&first, b"aaaa").unwrap();
+        fs::write(&second, b"bbbb").unwrap();
+
+        let tier = tempfile::tempdir().unwrap();
+        let store = TieredFileStore::new(Arc::new(LocalDirectoryBackend::new(tier.path())));
+        assert!(store.upload_commit_log_segment(0, &first));
+        assert!(store.upload_commit_log_segment(4, &second));
+
+        assert_eq!(store.delete_expired_segments(60 * 60 * 1000, -1), 0);
+        assert_eq!(store.get_min_offset(), Some(0));
+
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        // both local files still exist, nothing is deleted
+        assert_eq!(store.delete_expired_segments(0, 0), 0);
+        // only the first local file is gone
+        assert_eq!(store.delete_expired_segments(0, 4), 1);
+        assert_eq!(store.get_min_offset(), Some(4));
+        assert!(!store.is_commit_log_segment_uploaded(0));
+
+        // no local file is left
+        assert_eq!(store.delete_expired_segments(0, -1), 1);
+        assert_eq!(store.get_min_offset(), None);
+        assert!(
+            TieredStorageBackend::list(&LocalDirectoryBackend::new(tier.path()), "")
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn delete_expired_consume_queue_segments_after_local_files_are_gone() {
+        let local = tempfile::tempdir().unwrap();
+        let queue_dir = local.path().join("topic").join("0");
+        fs::create_dir_all(&queue_dir).unwrap();
+        let file = queue_dir.join("00000000000000000000");
+        fs::write(&file, b"aaaa").unwrap();
+
+        let tier = tempfile::tempdir().unwrap();
+        let store = TieredFileStore::new(Arc::new(LocalDirectoryBackend::new(tier.path())));
+        assert!(!store.is_consume_queue_segment_uploaded("topic/0/00000000000000000000"));
+        assert!(store.upload_consume_queue_segment("topic/0/00000000000000000000", &file));
+        assert!(store.is_consume_queue_segment_uploaded("topic/0/00000000000000000000"));
+        assert!(tier
+            .path()
+            .join("consumequeue/topic/0/00000000000000000000")
+            .exists());
+
+        // segments are found again after a restart
+        let reloaded = TieredFileStore::new(Arc::new(LocalDirectoryBackend::new(tier.path())));
+        assert!(reloaded.load());
+        assert!(reloaded.is_consume_queue_segment_uploaded("topic/0/00000000000000000000"));
+
+        assert_eq!(
+            store.delete_expired_consume_queue_segments(60 * 60 * 1000, local.path()),
+            0
+        );
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        // the local file still exists, nothing is deleted
+        assert_eq!(
+            store.delete_expired_consume_queue_segments(0, local.path()),
+            0
+        );
+        assert!(store.is_consume_queue_segment_uploaded("topic/0/00000000000000000000"));
+
+        fs::remove_file(&file).unwrap();
+        assert_eq!(
+            store.delete_expired_consume_queue_segments(0, local.path()),
+            1
+        );
+        assert!(!store.is_consume_queue_segment_uploaded("topic/0/00000000000000000000"));
+        assert!(
+            TieredStorageBackend::list(&LocalDirectoryBackend::new(tier.path()), "")
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
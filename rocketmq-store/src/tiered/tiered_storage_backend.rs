@@ -0,0 +1,46 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::io;
+use std::path::Path;
+
+use bytes::Bytes;
+
+/// An object kept in the tiered storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TieredObject {
+    pub key: String,
+    pub size: u64,
+    /// Milliseconds since the epoch when the object was stored.
+    pub last_modified: u64,
+}
+
+/// Backend of the tiered storage, where the sealed segments are uploaded to.
+///
+/// Keys are `/` separated relative paths, such as `commitlog/00000000000000000000`.
+pub trait TieredStorageBackend: Send + Sync {
+    /// Uploads the local file at `source` under `key`, replacing any existing object.
+    fn upload(&self, key: &str, source: &Path) -> io::Result<()>;
+
+    /// Reads `length` bytes of the object `key` starting at `position`.
+    fn read(&self, key: &str, position: u64, length: usize) -> io::Result<Bytes>;
+
+    /// Lists the objects whose key starts with `prefix`, ordered by key.
+    fn list(&self, prefix: &str) -> io::Result<Vec<TieredObject>>;
+
+    /// Deletes the object `key`, deleting a missing object is not an error.
+    fn delete(&self, key: &str) -> io::Result<()>;
+}
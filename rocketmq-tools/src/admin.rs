@@ -15,7 +15,7 @@
  * limitations under the License.
  */
 
-mod api;
+pub mod api;
 pub mod common;
 pub mod default_mq_admin_ext;
 pub mod default_mq_admin_ext_impl;
@@ -15,3 +15,5 @@
  * limitations under the License.
  */
 mod broker_operator_result;
+pub mod message_track;
+pub mod track_type;
@@ -0,0 +1,70 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+
+use crate::admin::api::track_type::TrackType;
+
+/// Whether a consumer group subscribed to the topic of a message has consumed it.
+#[derive(Debug, Clone, Default)]
+pub struct MessageTrack {
+    pub consumer_group: CheetahString,
+    pub track_type: TrackType,
+    pub exception_desc: Option<CheetahString>,
+}
+
+impl MessageTrack {
+    pub fn new(consumer_group: CheetahString) -> Self {
+        MessageTrack {
+            consumer_group,
+            track_type: TrackType::Unknown,
+            exception_desc: None,
+        }
+    }
+}
+
+impl std::fmt::Display for MessageTrack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "MessageTrack [consumerGroup={}, trackType={}, exceptionDesc={}]",
+            self.consumer_group,
+            self.track_type,
+            self.exception_desc.as_deref().unwrap_or("null")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_track_display_format() {
+        let mut track = MessageTrack::new(CheetahString::from("group"));
+        assert_eq!(
+            track.to_string(),
+            "MessageTrack [consumerGroup=group, trackType=UNKNOWN, exceptionDesc=null]"
+        );
+        track.track_type = TrackType::NotOnline;
+        track.exception_desc = Some(CheetahString::from("CODE:206 DESC:offline"));
+        assert_eq!(
+            track.to_string(),
+            "MessageTrack [consumerGroup=group, trackType=NOT_ONLINE, exceptionDesc=CODE:206 \
+             DESC:offline]"
+        );
+    }
+}
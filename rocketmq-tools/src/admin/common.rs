@@ -14,5 +14,6 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
-pub(crate) mod admin_tool_result;
-pub(crate) mod admin_tools_result_code_enum;
+pub mod admin_tool_result;
+pub mod admin_tools_result_code_enum;
+pub mod query_message_page;
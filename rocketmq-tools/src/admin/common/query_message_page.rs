@@ -0,0 +1,33 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use rocketmq_common::common::message::message_ext::MessageExt;
+
+/// One page of messages returned by an index lookup on a broker.
+#[derive(Debug, Clone, Default)]
+pub struct QueryMessagePage {
+    pub messages: Vec<MessageExt>,
+    pub index_last_update_timestamp: i64,
+    /// Cursor to request the next page with, not greater than zero when this is the last page.
+    pub next_cursor: i64,
+}
+
+impl QueryMessagePage {
+    #[inline]
+    pub fn has_more(&self) -> bool {
+        self.next_cursor > 0
+    }
+}
@@ -17,13 +17,18 @@
 #![allow(dead_code)]
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::sync::Arc;
 
 use cheetah_string::CheetahString;
 use rocketmq_client_rust::base::client_config::ClientConfig;
+use rocketmq_client_rust::consumer::pull_result::PullResult;
+use rocketmq_client_rust::trace::trace_view::TraceView;
 use rocketmq_common::common::base::plain_access_config::PlainAccessConfig;
 use rocketmq_common::common::config::TopicConfig;
 use rocketmq_common::common::message::message_enum::MessageRequestMode;
+use rocketmq_common::common::message::message_ext::MessageExt;
 use rocketmq_common::common::message::message_queue::MessageQueue;
+use rocketmq_common::common::topic::TopicValidator;
 use rocketmq_remoting::protocol::admin::consume_stats::ConsumeStats;
 use rocketmq_remoting::protocol::admin::topic_stats_table::TopicStatsTable;
 use rocketmq_remoting::protocol::body::broker_body::cluster_info::ClusterInfo;
@@ -33,18 +38,25 @@ use rocketmq_remoting::protocol::body::consumer_running_info::ConsumerRunningInf
 use rocketmq_remoting::protocol::body::group_list::GroupList;
 use rocketmq_remoting::protocol::body::kv_table::KVTable;
 use rocketmq_remoting::protocol::body::producer_connection::ProducerConnection;
+use rocketmq_remoting::protocol::body::query_message_by_condition_request_body::QueryMessageByConditionRequestBody;
 use rocketmq_remoting::protocol::body::topic::topic_list::TopicList;
 use rocketmq_remoting::protocol::body::topic_info_wrapper::TopicConfigSerializeWrapper;
 use rocketmq_remoting::protocol::heartbeat::subscription_data::SubscriptionData;
 use rocketmq_remoting::protocol::route::topic_route_data::TopicRouteData;
 use rocketmq_remoting::protocol::static_topic::topic_queue_mapping_detail::TopicQueueMappingDetail;
 use rocketmq_remoting::protocol::subscription::subscription_group_config::SubscriptionGroupConfig;
+use rocketmq_remoting::runtime::RPCHook;
 use rocketmq_rust::ArcMut;
 
+use crate::admin::api::message_track::MessageTrack;
 use crate::admin::common::admin_tool_result::AdminToolResult;
+use crate::admin::common::query_message_page::QueryMessagePage;
 use crate::admin::default_mq_admin_ext_impl::DefaultMQAdminExtImpl;
 use crate::admin::mq_admin_ext_async::MQAdminExt;
 
+const ADMIN_EXT_GROUP: &str = "admin_ext_group";
+const DEFAULT_TIMEOUT_MILLIS: u64 = 5000;
+
 pub struct DefaultMQAdminExt {
     client_config: ArcMut<ClientConfig>,
     admin_ext_group: CheetahString,
@@ -55,7 +67,67 @@ pub struct DefaultMQAdminExt {
 
 impl DefaultMQAdminExt {
     pub fn new() -> Self {
-        unimplemented!("DefaultMQAdminExt::new")
+        Self::with_admin_ext_group(None, ADMIN_EXT_GROUP, DEFAULT_TIMEOUT_MILLIS)
+    }
+
+    pub fn with_rpc_hook(rpc_hook: Arc<Box<dyn RPCHook>>, timeout_millis: u64) -> Self {
+        Self::with_admin_ext_group(Some(rpc_hook), ADMIN_EXT_GROUP, timeout_millis)
+    }
+
+    pub fn with_admin_ext_group(
+        rpc_hook: Option<Arc<Box<dyn RPCHook>>>,
+        admin_ext_group: impl Into<CheetahString>,
+        timeout_millis: u64,
+    ) -> Self {
+        let client_config = ArcMut::new(ClientConfig::new());
+        let admin_ext_group = admin_ext_group.into();
+        let default_mqadmin_ext_impl = DefaultMQAdminExtImpl::new(
+            rpc_hook,
+            timeout_millis,
+            client_config.clone(),
+            admin_ext_group.clone(),
+        );
+        Self {
+            client_config,
+            admin_ext_group,
+            create_topic_key: CheetahString::from_static_str(
+                TopicValidator::AUTO_CREATE_TOPIC_KEY_TOPIC,
+            ),
+            timeout_millis,
+            default_mqadmin_ext_impl,
+        }
+    }
+
+    /// The client config shared with the admin implementation, changes take effect on the
+    /// next `start`.
+    pub fn client_config_mut(&mut self) -> &mut ClientConfig {
+        &mut self.client_config
+    }
+
+    pub fn set_namesrv_addr(&mut self, namesrv_addr: impl Into<CheetahString>) {
+        self.client_config.namesrv_addr = Some(namesrv_addr.into());
+    }
+
+    pub fn set_instance_name(&mut self, instance_name: impl Into<CheetahString>) {
+        self.client_config.instance_name = instance_name.into();
+    }
+
+    pub fn set_timeout_millis(&mut self, timeout_millis: u64) {
+        self.timeout_millis = timeout_millis;
+        self.default_mqadmin_ext_impl
+            .set_timeout_millis(timeout_millis);
+    }
+
+    pub fn admin_ext_group(&self) -> &CheetahString {
+        &self.admin_ext_group
+    }
+
+    pub fn create_topic_key(&self) -> &CheetahString {
+        &self.create_topic_key
+    }
+
+    pub fn timeout_millis(&self) -> u64 {
+        self.timeout_millis
     }
 }
 
@@ -65,16 +137,14 @@ impl Default for DefaultMQAdminExt {
     }
 }
 
-#[allow(unused_variables)]
-#[allow(unused_mut)]
 #[cfg(feature = "async")]
 impl MQAdminExt for DefaultMQAdminExt {
-    async fn start(&self) -> crate::Result<()> {
-        todo!()
+    async fn start(&mut self) -> crate::Result<()> {
+        self.default_mqadmin_ext_impl.start().await
     }
 
-    async fn shutdown(&self) {
-        todo!()
+    async fn shutdown(&mut self) {
+        self.default_mqadmin_ext_impl.shutdown().await
     }
 
     async fn add_broker_to_container(
@@ -82,7 +152,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         broker_container_addr: CheetahString,
         broker_config: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .add_broker_to_container(broker_container_addr, broker_config)
+            .await
     }
 
     async fn remove_broker_from_container(
@@ -92,7 +164,14 @@ impl MQAdminExt for DefaultMQAdminExt {
         broker_name: CheetahString,
         broker_id: u64,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .remove_broker_from_container(
+                broker_container_addr,
+                cluster_name,
+                broker_name,
+                broker_id,
+            )
+            .await
     }
 
     async fn update_broker_config(
@@ -100,14 +179,18 @@ impl MQAdminExt for DefaultMQAdminExt {
         broker_addr: CheetahString,
         properties: HashMap<CheetahString, CheetahString>,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .update_broker_config(broker_addr, properties)
+            .await
     }
 
     async fn get_broker_config(
         &self,
         broker_addr: CheetahString,
     ) -> crate::Result<HashMap<CheetahString, CheetahString>> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .get_broker_config(broker_addr)
+            .await
     }
 
     async fn create_and_update_topic_config(
@@ -115,7 +198,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         addr: CheetahString,
         config: TopicConfig,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .create_and_update_topic_config(addr, config)
+            .await
     }
 
     async fn create_and_update_topic_config_list(
@@ -123,7 +208,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         addr: CheetahString,
         topic_config_list: Vec<TopicConfig>,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .create_and_update_topic_config_list(addr, topic_config_list)
+            .await
     }
 
     async fn create_and_update_plain_access_config(
@@ -131,7 +218,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         addr: CheetahString,
         config: PlainAccessConfig,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .create_and_update_plain_access_config(addr, config)
+            .await
     }
 
     async fn delete_plain_access_config(
@@ -139,7 +228,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         addr: CheetahString,
         access_key: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .delete_plain_access_config(addr, access_key)
+            .await
     }
 
     async fn update_global_white_addr_config(
@@ -148,14 +239,18 @@ impl MQAdminExt for DefaultMQAdminExt {
         global_white_addrs: CheetahString,
         acl_file_full_path: Option<CheetahString>,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .update_global_white_addr_config(addr, global_white_addrs, acl_file_full_path)
+            .await
     }
 
     async fn examine_broker_cluster_acl_version_info(
         &self,
         addr: CheetahString,
     ) -> crate::Result<CheetahString> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .examine_broker_cluster_acl_version_info(addr)
+            .await
     }
 
     async fn create_and_update_subscription_group_config(
@@ -163,7 +258,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         addr: CheetahString,
         config: SubscriptionGroupConfig,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .create_and_update_subscription_group_config(addr, config)
+            .await
     }
 
     async fn create_and_update_subscription_group_config_list(
@@ -171,7 +268,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         broker_addr: CheetahString,
         configs: Vec<SubscriptionGroupConfig>,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .create_and_update_subscription_group_config_list(broker_addr, configs)
+            .await
     }
 
     async fn examine_subscription_group_config(
@@ -179,7 +278,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         addr: CheetahString,
         group: CheetahString,
     ) -> crate::Result<SubscriptionGroupConfig> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .examine_subscription_group_config(addr, group)
+            .await
     }
 
     async fn examine_topic_stats(
@@ -187,32 +288,40 @@ impl MQAdminExt for DefaultMQAdminExt {
         topic: CheetahString,
         broker_addr: Option<CheetahString>,
     ) -> crate::Result<TopicStatsTable> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .examine_topic_stats(topic, broker_addr)
+            .await
     }
 
     async fn examine_topic_stats_concurrent(
         &self,
         topic: CheetahString,
     ) -> AdminToolResult<TopicStatsTable> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .examine_topic_stats_concurrent(topic)
+            .await
     }
 
     async fn fetch_all_topic_list(&self) -> crate::Result<TopicList> {
-        todo!()
+        self.default_mqadmin_ext_impl.fetch_all_topic_list().await
     }
 
     async fn fetch_topics_by_cluster(
         &self,
         cluster_name: CheetahString,
     ) -> crate::Result<TopicList> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .fetch_topics_by_cluster(cluster_name)
+            .await
     }
 
     async fn fetch_broker_runtime_stats(
         &self,
         broker_addr: CheetahString,
     ) -> crate::Result<KVTable> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .fetch_broker_runtime_stats(broker_addr)
+            .await
     }
 
     async fn examine_consume_stats(
@@ -223,18 +332,30 @@ impl MQAdminExt for DefaultMQAdminExt {
         broker_addr: Option<CheetahString>,
         timeout_millis: Option<u64>,
     ) -> crate::Result<ConsumeStats> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .examine_consume_stats(
+                consumer_group,
+                topic,
+                cluster_name,
+                broker_addr,
+                timeout_millis,
+            )
+            .await
     }
 
     async fn examine_broker_cluster_info(&self) -> crate::Result<ClusterInfo> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .examine_broker_cluster_info()
+            .await
     }
 
     async fn examine_topic_route_info(
         &self,
         topic: CheetahString,
     ) -> crate::Result<TopicRouteData> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .examine_topic_route_info(topic)
+            .await
     }
 
     async fn examine_consumer_connection_info(
@@ -242,7 +363,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         consumer_group: CheetahString,
         broker_addr: Option<CheetahString>,
     ) -> crate::Result<ConsumerConnection> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .examine_consumer_connection_info(consumer_group, broker_addr)
+            .await
     }
 
     async fn examine_producer_connection_info(
@@ -250,11 +373,15 @@ impl MQAdminExt for DefaultMQAdminExt {
         producer_group: CheetahString,
         topic: CheetahString,
     ) -> crate::Result<ProducerConnection> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .examine_producer_connection_info(producer_group, topic)
+            .await
     }
 
     async fn get_name_server_address_list(&self) -> Vec<CheetahString> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .get_name_server_address_list()
+            .await
     }
 
     async fn wipe_write_perm_of_broker(
@@ -262,7 +389,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         namesrv_addr: CheetahString,
         broker_name: CheetahString,
     ) -> crate::Result<i32> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .wipe_write_perm_of_broker(namesrv_addr, broker_name)
+            .await
     }
 
     async fn add_write_perm_of_broker(
@@ -270,7 +399,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         namesrv_addr: CheetahString,
         broker_name: CheetahString,
     ) -> crate::Result<i32> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .add_write_perm_of_broker(namesrv_addr, broker_name)
+            .await
     }
 
     async fn put_kv_config(
@@ -279,7 +410,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         key: CheetahString,
         value: CheetahString,
     ) {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .put_kv_config(namespace, key, value)
+            .await
     }
 
     async fn get_kv_config(
@@ -287,11 +420,15 @@ impl MQAdminExt for DefaultMQAdminExt {
         namespace: CheetahString,
         key: CheetahString,
     ) -> crate::Result<CheetahString> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .get_kv_config(namespace, key)
+            .await
     }
 
     async fn get_kv_list_by_namespace(&self, namespace: CheetahString) -> crate::Result<KVTable> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .get_kv_list_by_namespace(namespace)
+            .await
     }
 
     async fn delete_topic(
@@ -299,7 +436,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         topic_name: CheetahString,
         cluster_name: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .delete_topic(topic_name, cluster_name)
+            .await
     }
 
     async fn delete_topic_in_broker(
@@ -307,7 +446,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         addrs: HashSet<CheetahString>,
         topic: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .delete_topic_in_broker(addrs, topic)
+            .await
     }
 
     async fn delete_topic_in_name_server(
@@ -316,7 +457,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         cluster_name: Option<CheetahString>,
         topic: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .delete_topic_in_name_server(addrs, cluster_name, topic)
+            .await
     }
 
     async fn delete_subscription_group(
@@ -325,7 +468,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         group_name: CheetahString,
         remove_offset: Option<bool>,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .delete_subscription_group(addr, group_name, remove_offset)
+            .await
     }
 
     async fn create_and_update_kv_config(
@@ -334,7 +479,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         key: CheetahString,
         value: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .create_and_update_kv_config(namespace, key, value)
+            .await
     }
 
     async fn delete_kv_config(
@@ -342,7 +489,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         namespace: CheetahString,
         key: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .delete_kv_config(namespace, key)
+            .await
     }
 
     async fn reset_offset_by_timestamp(
@@ -353,7 +502,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         timestamp: u64,
         is_force: bool,
     ) -> crate::Result<HashMap<MessageQueue, u64>> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .reset_offset_by_timestamp(cluster_name, topic, group, timestamp, is_force)
+            .await
     }
 
     async fn reset_offset_new(
@@ -362,7 +513,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         topic: CheetahString,
         timestamp: u64,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .reset_offset_new(consumer_group, topic, timestamp)
+            .await
     }
 
     async fn get_consume_status(
@@ -371,7 +524,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         group: CheetahString,
         client_addr: CheetahString,
     ) -> crate::Result<HashMap<CheetahString, HashMap<MessageQueue, u64>>> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .get_consume_status(topic, group, client_addr)
+            .await
     }
 
     async fn create_or_update_order_conf(
@@ -380,22 +535,30 @@ impl MQAdminExt for DefaultMQAdminExt {
         value: CheetahString,
         is_cluster: bool,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .create_or_update_order_conf(key, value, is_cluster)
+            .await
     }
 
     async fn query_topic_consume_by_who(&self, topic: CheetahString) -> crate::Result<GroupList> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .query_topic_consume_by_who(topic)
+            .await
     }
 
     async fn query_topics_by_consumer(&self, group: CheetahString) -> crate::Result<TopicList> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .query_topics_by_consumer(group)
+            .await
     }
 
     async fn query_topics_by_consumer_concurrent(
         &self,
         group: CheetahString,
     ) -> AdminToolResult<TopicList> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .query_topics_by_consumer_concurrent(group)
+            .await
     }
 
     async fn query_subscription(
@@ -403,7 +566,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         group: CheetahString,
         topic: CheetahString,
     ) -> crate::Result<SubscriptionData> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .query_subscription(group, topic)
+            .await
     }
 
     async fn clean_expired_consumer_queue(
@@ -411,7 +576,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         cluster: Option<CheetahString>,
         addr: Option<CheetahString>,
     ) -> crate::Result<bool> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .clean_expired_consumer_queue(cluster, addr)
+            .await
     }
 
     async fn delete_expired_commit_log(
@@ -419,7 +586,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         cluster: Option<CheetahString>,
         addr: Option<CheetahString>,
     ) -> crate::Result<bool> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .delete_expired_commit_log(cluster, addr)
+            .await
     }
 
     async fn clean_unused_topic(
@@ -427,7 +596,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         cluster: Option<CheetahString>,
         addr: Option<CheetahString>,
     ) -> crate::Result<bool> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .clean_unused_topic(cluster, addr)
+            .await
     }
 
     async fn get_consumer_running_info(
@@ -437,7 +608,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         jstack: bool,
         metrics: Option<bool>,
     ) -> crate::Result<ConsumerRunningInfo> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .get_consumer_running_info(consumer_group, client_id, jstack, metrics)
+            .await
     }
 
     async fn consume_message_directly(
@@ -447,7 +620,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         topic: CheetahString,
         msg_id: CheetahString,
     ) -> crate::Result<ConsumeMessageDirectlyResult> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .consume_message_directly(consumer_group, client_id, topic, msg_id)
+            .await
     }
 
     async fn consume_message_directly_ext(
@@ -458,7 +633,68 @@ impl MQAdminExt for DefaultMQAdminExt {
         topic: CheetahString,
         msg_id: CheetahString,
     ) -> crate::Result<ConsumeMessageDirectlyResult> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .consume_message_directly_ext(cluster_name, consumer_group, client_id, topic, msg_id)
+            .await
+    }
+
+    async fn view_message(
+        &self,
+        topic: CheetahString,
+        msg_id: CheetahString,
+    ) -> crate::Result<MessageExt> {
+        self.default_mqadmin_ext_impl
+            .view_message(topic, msg_id)
+            .await
+    }
+
+    async fn query_message_by_key(
+        &self,
+        topic: CheetahString,
+        key: CheetahString,
+        max_num: i32,
+        begin: i64,
+        end: i64,
+    ) -> crate::Result<Vec<MessageExt>> {
+        self.default_mqadmin_ext_impl
+            .query_message_by_key(topic, key, max_num, begin, end)
+            .await
+    }
+
+    async fn pull_message_by_offset(
+        &self,
+        mq: MessageQueue,
+        offset: i64,
+        max_nums: i32,
+    ) -> crate::Result<PullResult> {
+        self.default_mqadmin_ext_impl
+            .pull_message_by_offset(mq, offset, max_nums)
+            .await
+    }
+
+    async fn message_track_detail(&self, msg: MessageExt) -> crate::Result<Vec<MessageTrack>> {
+        self.default_mqadmin_ext_impl
+            .message_track_detail(msg)
+            .await
+    }
+
+    async fn message_track_detail_concurrent(
+        &self,
+        msg: MessageExt,
+    ) -> AdminToolResult<Vec<MessageTrack>> {
+        self.default_mqadmin_ext_impl
+            .message_track_detail_concurrent(msg)
+            .await
+    }
+
+    async fn query_message_trace_by_id(
+        &self,
+        trace_topic: CheetahString,
+        msg_id: CheetahString,
+    ) -> crate::Result<Vec<TraceView>> {
+        self.default_mqadmin_ext_impl
+            .query_message_trace_by_id(trace_topic, msg_id)
+            .await
     }
 
     async fn clone_group_offset(
@@ -468,15 +704,19 @@ impl MQAdminExt for DefaultMQAdminExt {
         topic: CheetahString,
         is_offline: bool,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .clone_group_offset(src_group, dest_group, topic, is_offline)
+            .await
     }
 
     async fn get_cluster_list(&self, topic: String) -> crate::Result<HashSet<CheetahString>> {
-        todo!()
+        self.default_mqadmin_ext_impl.get_cluster_list(topic).await
     }
 
     async fn get_topic_cluster_list(&self, topic: String) -> crate::Result<HashSet<CheetahString>> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .get_topic_cluster_list(topic)
+            .await
     }
 
     async fn get_all_topic_config(
@@ -484,7 +724,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         broker_addr: CheetahString,
         timeout_millis: u64,
     ) -> crate::Result<TopicConfigSerializeWrapper> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .get_all_topic_config(broker_addr, timeout_millis)
+            .await
     }
 
     async fn get_user_topic_config(
@@ -493,7 +735,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         special_topic: bool,
         timeout_millis: u64,
     ) -> crate::Result<TopicConfigSerializeWrapper> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .get_user_topic_config(broker_addr, special_topic, timeout_millis)
+            .await
     }
 
     async fn update_consume_offset(
@@ -503,7 +747,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         mq: MessageQueue,
         offset: u64,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .update_consume_offset(broker_addr, consume_group, mq, offset)
+            .await
     }
 
     async fn update_name_server_config(
@@ -511,14 +757,18 @@ impl MQAdminExt for DefaultMQAdminExt {
         properties: HashMap<CheetahString, CheetahString>,
         name_servers: Vec<CheetahString>,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .update_name_server_config(properties, name_servers)
+            .await
     }
 
     async fn get_name_server_config(
         &self,
         name_servers: Vec<CheetahString>,
     ) -> crate::Result<HashMap<CheetahString, HashMap<CheetahString, CheetahString>>> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .get_name_server_config(name_servers)
+            .await
     }
 
     async fn resume_check_half_message(
@@ -526,7 +776,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         topic: CheetahString,
         msg_id: CheetahString,
     ) -> crate::Result<bool> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .resume_check_half_message(topic, msg_id)
+            .await
     }
 
     async fn set_message_request_mode(
@@ -558,7 +810,15 @@ impl MQAdminExt for DefaultMQAdminExt {
         queue_id: i32,
         reset_offset: u64,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .reset_offset_by_queue_id(
+                broker_addr,
+                consumer_group,
+                topic_name,
+                queue_id,
+                reset_offset,
+            )
+            .await
     }
 
     async fn examine_topic_config(
@@ -566,7 +826,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         addr: CheetahString,
         topic: CheetahString,
     ) -> crate::Result<TopicConfig> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .examine_topic_config(addr, topic)
+            .await
     }
 
     async fn create_static_topic(
@@ -577,7 +839,19 @@ impl MQAdminExt for DefaultMQAdminExt {
         mapping_detail: TopicQueueMappingDetail,
         force: bool,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .create_static_topic(addr, default_topic, topic_config, mapping_detail, force)
+            .await
+    }
+
+    async fn query_message(
+        &self,
+        broker_addr: CheetahString,
+        request_body: QueryMessageByConditionRequestBody,
+    ) -> crate::Result<QueryMessagePage> {
+        self.default_mqadmin_ext_impl
+            .query_message(broker_addr, request_body)
+            .await
     }
 
     async fn reset_master_flush_offset(
@@ -585,14 +859,18 @@ impl MQAdminExt for DefaultMQAdminExt {
         broker_addr: CheetahString,
         master_flush_offset: u64,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .reset_master_flush_offset(broker_addr, master_flush_offset)
+            .await
     }
 
     async fn get_controller_config(
         &self,
         controller_servers: Vec<CheetahString>,
     ) -> crate::Result<HashMap<CheetahString, HashMap<CheetahString, CheetahString>>> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .get_controller_config(controller_servers)
+            .await
     }
 
     async fn update_controller_config(
@@ -600,7 +878,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         properties: HashMap<CheetahString, CheetahString>,
         controllers: Vec<CheetahString>,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .update_controller_config(properties, controllers)
+            .await
     }
 
     async fn clean_controller_broker_data(
@@ -611,7 +891,15 @@ impl MQAdminExt for DefaultMQAdminExt {
         broker_controller_ids_to_clean: Option<CheetahString>,
         is_clean_living_broker: bool,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .clean_controller_broker_data(
+                controller_addr,
+                cluster_name,
+                broker_name,
+                broker_controller_ids_to_clean,
+                is_clean_living_broker,
+            )
+            .await
     }
 
     async fn update_cold_data_flow_ctr_group_config(
@@ -619,7 +907,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         broker_addr: CheetahString,
         properties: HashMap<CheetahString, CheetahString>,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .update_cold_data_flow_ctr_group_config(broker_addr, properties)
+            .await
     }
 
     async fn remove_cold_data_flow_ctr_group_config(
@@ -627,14 +917,18 @@ impl MQAdminExt for DefaultMQAdminExt {
         broker_addr: CheetahString,
         consumer_group: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .remove_cold_data_flow_ctr_group_config(broker_addr, consumer_group)
+            .await
     }
 
     async fn get_cold_data_flow_ctr_info(
         &self,
         broker_addr: CheetahString,
     ) -> crate::Result<CheetahString> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .get_cold_data_flow_ctr_info(broker_addr)
+            .await
     }
 
     async fn set_commit_log_read_ahead_mode(
@@ -642,7 +936,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         broker_addr: CheetahString,
         mode: CheetahString,
     ) -> crate::Result<CheetahString> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .set_commit_log_read_ahead_mode(broker_addr, mode)
+            .await
     }
 
     async fn create_user(
@@ -652,7 +948,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         password: CheetahString,
         user_type: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .create_user(broker_addr, username, password, user_type)
+            .await
     }
 
     async fn update_user(
@@ -663,7 +961,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         user_type: CheetahString,
         user_status: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .update_user(broker_addr, username, password, user_type, user_status)
+            .await
     }
 
     async fn delete_user(
@@ -671,7 +971,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         broker_addr: CheetahString,
         username: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .delete_user(broker_addr, username)
+            .await
     }
 
     async fn create_acl(
@@ -683,7 +985,16 @@ impl MQAdminExt for DefaultMQAdminExt {
         source_ips: Vec<CheetahString>,
         decision: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .create_acl(
+                broker_addr,
+                subject,
+                resources,
+                actions,
+                source_ips,
+                decision,
+            )
+            .await
     }
 
     async fn update_acl(
@@ -695,7 +1006,16 @@ impl MQAdminExt for DefaultMQAdminExt {
         source_ips: Vec<CheetahString>,
         decision: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .update_acl(
+                broker_addr,
+                subject,
+                resources,
+                actions,
+                source_ips,
+                decision,
+            )
+            .await
     }
 
     async fn delete_acl(
@@ -704,6 +1024,8 @@ impl MQAdminExt for DefaultMQAdminExt {
         subject: CheetahString,
         resource: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .delete_acl(broker_addr, subject, resource)
+            .await
     }
 }
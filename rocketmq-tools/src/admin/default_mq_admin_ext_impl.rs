@@ -22,13 +22,29 @@ use std::sync::Arc;
 
 use cheetah_string::CheetahString;
 use lazy_static::lazy_static;
+use rocketmq_client_rust::base::client_config::ClientConfig;
+use rocketmq_client_rust::client_error::ClientErr;
+use rocketmq_client_rust::client_error::MQClientError;
+use rocketmq_client_rust::consumer::pull_result::PullResult;
 use rocketmq_client_rust::factory::mq_client_instance::MQClientInstance;
+use rocketmq_client_rust::trace::trace_type::TraceType;
+use rocketmq_client_rust::trace::trace_view::TraceView;
+use rocketmq_client_rust::MQClientAPIImpl;
+use rocketmq_client_rust::MQClientManager;
 use rocketmq_common::common::base::plain_access_config::PlainAccessConfig;
 use rocketmq_common::common::base::service_state::ServiceState;
 use rocketmq_common::common::config::TopicConfig;
+use rocketmq_common::common::message::message_client_id_setter::MessageClientIDSetter;
 use rocketmq_common::common::message::message_enum::MessageRequestMode;
+use rocketmq_common::common::message::message_ext::MessageExt;
 use rocketmq_common::common::message::message_queue::MessageQueue;
+use rocketmq_common::common::message::MessageConst;
+use rocketmq_common::common::message::MessageTrait;
 use rocketmq_common::common::mix_all;
+use rocketmq_common::common::topic::TopicValidator;
+use rocketmq_common::utils::serde_json_utils::SerdeJsonUtils;
+use rocketmq_common::MessageDecoder;
+use rocketmq_remoting::code::response_code::ResponseCode;
 use rocketmq_remoting::protocol::admin::consume_stats::ConsumeStats;
 use rocketmq_remoting::protocol::admin::topic_stats_table::TopicStatsTable;
 use rocketmq_remoting::protocol::body::broker_body::cluster_info::ClusterInfo;
@@ -38,17 +54,29 @@ use rocketmq_remoting::protocol::body::consumer_running_info::ConsumerRunningInf
 use rocketmq_remoting::protocol::body::group_list::GroupList;
 use rocketmq_remoting::protocol::body::kv_table::KVTable;
 use rocketmq_remoting::protocol::body::producer_connection::ProducerConnection;
+use rocketmq_remoting::protocol::body::query_message_by_condition_request_body::QueryMessageByConditionRequestBody;
 use rocketmq_remoting::protocol::body::topic::topic_list::TopicList;
 use rocketmq_remoting::protocol::body::topic_info_wrapper::TopicConfigSerializeWrapper;
+use rocketmq_remoting::protocol::header::query_message_request_header::QueryMessageRequestHeader;
+use rocketmq_remoting::protocol::header::update_consumer_offset_header::UpdateConsumerOffsetRequestHeader;
+use rocketmq_remoting::protocol::heartbeat::consume_type::ConsumeType;
+use rocketmq_remoting::protocol::heartbeat::message_model::MessageModel;
 use rocketmq_remoting::protocol::heartbeat::subscription_data::SubscriptionData;
 use rocketmq_remoting::protocol::route::topic_route_data::TopicRouteData;
 use rocketmq_remoting::protocol::static_topic::topic_queue_mapping_detail::TopicQueueMappingDetail;
 use rocketmq_remoting::protocol::subscription::subscription_group_config::SubscriptionGroupConfig;
 use rocketmq_remoting::runtime::RPCHook;
 use rocketmq_rust::ArcMut;
+use tracing::info;
+use tracing::warn;
 
+use crate::admin::api::message_track::MessageTrack;
+use crate::admin::api::track_type::TrackType;
 use crate::admin::common::admin_tool_result::AdminToolResult;
+use crate::admin::common::admin_tools_result_code_enum::AdminToolsResultCodeEnum;
+use crate::admin::common::query_message_page::QueryMessagePage;
 use crate::admin::mq_admin_ext_async::MQAdminExt;
+use crate::tools_error::ToolsError;
 
 lazy_static! {
     static ref SYSTEM_GROUP_SET: HashSet<CheetahString> = {
@@ -79,18 +107,315 @@ pub struct DefaultMQAdminExtImpl {
     rpc_hook: Option<Arc<Box<dyn RPCHook>>>,
     timeout_millis: u64,
     kv_namespace_to_delete_list: Vec<CheetahString>,
+    client_config: ArcMut<ClientConfig>,
+    admin_ext_group: CheetahString,
+}
+
+impl DefaultMQAdminExtImpl {
+    pub fn new(
+        rpc_hook: Option<Arc<Box<dyn RPCHook>>>,
+        timeout_millis: u64,
+        client_config: ArcMut<ClientConfig>,
+        admin_ext_group: CheetahString,
+    ) -> Self {
+        Self {
+            service_state: ServiceState::CreateJust,
+            client_instance: None,
+            rpc_hook,
+            timeout_millis,
+            kv_namespace_to_delete_list: Vec::new(),
+            client_config,
+            admin_ext_group,
+        }
+    }
+
+    pub fn set_timeout_millis(&mut self, timeout_millis: u64) {
+        self.timeout_millis = timeout_millis;
+    }
+
+    fn mq_client_api_impl(&self) -> Option<ArcMut<MQClientAPIImpl>> {
+        self.client_instance
+            .as_ref()
+            .map(|client_instance| client_instance.get_mq_client_api_impl())
+    }
+
+    async fn topic_route(&self, topic: &CheetahString) -> crate::Result<TopicRouteData> {
+        match self
+            .mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .get_topic_route_info_from_name_server(topic, self.timeout_millis)
+            .await?
+        {
+            Some(route) => Ok(route),
+            None => Err(MQClientError::MQClientErr(ClientErr::new_with_code(
+                ResponseCode::TopicNotExist as i32,
+                format!(
+                    "No topic route info in name server for the topic: {}",
+                    topic
+                ),
+            ))
+            .into()),
+        }
+    }
+
+    /// Resolves the master brokers an operation targets, either the given broker or every
+    /// master of the given cluster.
+    async fn target_master_addrs(
+        &self,
+        cluster: Option<CheetahString>,
+        addr: Option<CheetahString>,
+    ) -> crate::Result<Vec<CheetahString>> {
+        if let Some(addr) = addr {
+            return Ok(vec![addr]);
+        }
+        let cluster_info = self.examine_broker_cluster_info().await?;
+        let addrs = match cluster {
+            Some(cluster) => cluster_broker_addrs(&cluster_info, &cluster, true),
+            None => cluster_info
+                .cluster_addr_table
+                .iter()
+                .flat_map(|table| table.keys())
+                .flat_map(|cluster| cluster_broker_addrs(&cluster_info, cluster, true))
+                .collect(),
+        };
+        Ok(addrs.into_iter().collect())
+    }
+
+    /// Queries the index of every broker serving the route. The index is a hash index, so
+    /// hits are filtered down to messages whose keys, or unique id, really match.
+    #[allow(clippy::too_many_arguments)]
+    async fn query_message_in_route(
+        &self,
+        route: &TopicRouteData,
+        topic: &CheetahString,
+        key: &CheetahString,
+        max_num: i32,
+        begin: i64,
+        end: i64,
+        unique_key: bool,
+    ) -> crate::Result<Vec<MessageExt>> {
+        let mut mq_client_api = self.mq_client_api_impl().ok_or_else(not_started)?;
+        let mut messages = Vec::new();
+        let mut failures = Vec::new();
+        let broker_addrs = select_broker_addrs(route);
+        for broker_addr in &broker_addrs {
+            let request_header = QueryMessageRequestHeader {
+                topic: topic.clone(),
+                key: key.clone(),
+                max_num,
+                begin_timestamp: begin,
+                end_timestamp: end,
+                topic_request_header: None,
+            };
+            match mq_client_api
+                .query_message(broker_addr, request_header, unique_key, self.timeout_millis)
+                .await
+            {
+                Ok(found) => messages.extend(
+                    found
+                        .into_iter()
+                        .filter(|message| message_matches_key(message, key, unique_key)),
+                ),
+                Err(e) => {
+                    warn!("query message from broker {} failed: {}", broker_addr, e);
+                    failures.push(e);
+                }
+            }
+        }
+        // Only fail when every broker failed, a partial answer is still worth showing.
+        if !broker_addrs.is_empty() && failures.len() == broker_addrs.len() {
+            return Err(failures.remove(0).into());
+        }
+        Ok(messages)
+    }
+}
+
+fn message_matches_key(message: &MessageExt, key: &CheetahString, unique_key: bool) -> bool {
+    if unique_key {
+        return MessageClientIDSetter::get_uniq_id(message).as_ref() == Some(key)
+            || message.msg_id() == key;
+    }
+    message.get_keys().is_some_and(|keys| {
+        keys.split(MessageConst::KEY_SEPARATOR)
+            .any(|message_key| message_key == key.as_str())
+    })
+}
+
+/// Whether the consume offset of the queue holding `msg` moved past it. The queue is only
+/// trusted when its master broker is the one that stored the message.
+fn is_consumed(msg: &MessageExt, consume_stats: &ConsumeStats, cluster_info: &ClusterInfo) -> bool {
+    let store_host = msg.store_host().to_string();
+    consume_stats
+        .get_offset_table()
+        .iter()
+        .any(|(mq, offset_wrapper)| {
+            mq.get_topic() == msg.get_topic().as_str()
+                && mq.get_queue_id() == msg.queue_id()
+                && cluster_info
+                    .broker_addr_table
+                    .as_ref()
+                    .and_then(|broker_addr_table| broker_addr_table.get(mq.get_broker_name()))
+                    .and_then(|broker_data| broker_data.broker_addrs().get(&mix_all::MASTER_ID))
+                    .is_some_and(|addr| addr.as_str() == store_host)
+                && offset_wrapper.get_consumer_offset() > msg.queue_offset()
+        })
+}
+
+/// Whether a subscription of the consumer accepts the tag of `msg`, a consumer that does not
+/// subscribe to its topic any more is not blamed for filtering it.
+fn is_subscribed(msg: &MessageExt, consumer_connection: &ConsumerConnection) -> bool {
+    let subscription_table = consumer_connection.get_subscription_table();
+    let Some(subscription_data) = subscription_table.get(msg.get_topic()) else {
+        return true;
+    };
+    let tags_set = &subscription_data.tags_set;
+    tags_set.is_empty()
+        || tags_set.contains(SubscriptionData::SUB_ALL)
+        || msg.get_tags().is_some_and(|tags| tags_set.contains(&tags))
+}
+
+/// Consume records of older clients only carry the request id, their group and time are taken
+/// from the SubBefore record of the same request.
+fn complete_consume_views(views: &mut [TraceView]) {
+    let before_views: HashMap<CheetahString, (CheetahString, i64)> = views
+        .iter()
+        .filter(|view| view.trace_type == Some(TraceType::SubBefore))
+        .map(|view| {
+            (
+                view.request_id.clone(),
+                (view.group_name.clone(), view.time_stamp),
+            )
+        })
+        .collect();
+    for view in views.iter_mut() {
+        if view.trace_type != Some(TraceType::SubAfter) || !view.group_name.is_empty() {
+            continue;
+        }
+        if let Some((group_name, time_stamp)) = before_views.get(&view.request_id) {
+            view.group_name = group_name.clone();
+            view.time_stamp = time_stamp + view.cost_time;
+        }
+    }
+}
+
+fn error_response_code(error: &ToolsError) -> Option<i32> {
+    match error {
+        ToolsError::MQClientError(MQClientError::MQClientErr(err)) => Some(err.response_code()),
+        ToolsError::MQClientError(MQClientError::MQClientBrokerError(err)) => {
+            Some(err.response_code())
+        }
+        _ => None,
+    }
+}
+
+/// Picks one address of every broker serving the route, the master when it is online.
+fn select_broker_addrs(route: &TopicRouteData) -> Vec<CheetahString> {
+    route
+        .broker_datas
+        .iter()
+        .filter_map(|broker_data| broker_data.select_broker_addr())
+        .collect()
+}
+
+/// Collects the addresses of the brokers in a cluster, only masters when `master_only` is set.
+fn cluster_broker_addrs(
+    cluster_info: &ClusterInfo,
+    cluster_name: &CheetahString,
+    master_only: bool,
+) -> HashSet<CheetahString> {
+    let (Some(cluster_addr_table), Some(broker_addr_table)) = (
+        cluster_info.cluster_addr_table.as_ref(),
+        cluster_info.broker_addr_table.as_ref(),
+    ) else {
+        return HashSet::new();
+    };
+    cluster_addr_table
+        .get(cluster_name)
+        .into_iter()
+        .flatten()
+        .filter_map(|broker_name| broker_addr_table.get(broker_name))
+        .flat_map(|broker_data| broker_data.broker_addrs().iter())
+        .filter(|(broker_id, _)| !master_only || **broker_id == mix_all::MASTER_ID)
+        .map(|(_, addr)| addr.clone())
+        .collect()
+}
+
+fn is_user_topic(topic: &str, special_topic: bool) -> bool {
+    if TopicValidator::is_system_topic(topic) {
+        return false;
+    }
+    special_topic
+        || !(topic.starts_with(mix_all::RETRY_GROUP_TOPIC_PREFIX)
+            || topic.starts_with(mix_all::DLQ_GROUP_TOPIC_PREFIX))
+}
+
+fn not_started() -> ToolsError {
+    ToolsError::NotStarted
+}
+
+fn admin_tool_failure<T>(error: ToolsError) -> AdminToolResult<T> {
+    let code = match &error {
+        ToolsError::MQClientError(MQClientError::MQClientBrokerError(_)) => {
+            AdminToolsResultCodeEnum::MQBrokerError
+        }
+        ToolsError::MQClientError(MQClientError::RemotingError(_)) => {
+            AdminToolsResultCodeEnum::RemotingError
+        }
+        ToolsError::MQClientError(MQClientError::MQClientErr(err))
+            if err.response_code() == ResponseCode::TopicNotExist as i32 =>
+        {
+            AdminToolsResultCodeEnum::TopicRouteInfoNotExist
+        }
+        _ => AdminToolsResultCodeEnum::MQClientError,
+    };
+    AdminToolResult::failure(code, error.to_string())
 }
 
 #[allow(unused_variables)]
 #[allow(unused_mut)]
 #[cfg(feature = "async")]
 impl MQAdminExt for DefaultMQAdminExtImpl {
-    async fn start(&self) -> crate::Result<()> {
-        todo!()
+    async fn start(&mut self) -> crate::Result<()> {
+        match self.service_state {
+            ServiceState::CreateJust => {
+                self.service_state = ServiceState::StartFailed;
+                self.client_config.change_instance_name_to_pid();
+                let mut client_instance = MQClientManager::get_instance()
+                    .get_or_create_mq_client_instance(
+                        (*self.client_config).clone(),
+                        self.rpc_hook.clone(),
+                    );
+                let this = client_instance.clone();
+                Box::pin(client_instance.start(this)).await?;
+                self.client_instance = Some(client_instance);
+                self.service_state = ServiceState::Running;
+                info!("the adminExt [{}] start OK", self.admin_ext_group);
+                Ok(())
+            }
+            ServiceState::Running | ServiceState::ShutdownAlready | ServiceState::StartFailed => {
+                Err(MQClientError::MQClientErr(ClientErr::new(format!(
+                    "The AdminExt service state not OK, maybe started once, {:?}",
+                    self.service_state
+                )))
+                .into())
+            }
+        }
     }
 
-    async fn shutdown(&self) {
-        todo!()
+    async fn shutdown(&mut self) {
+        if self.service_state != ServiceState::Running {
+            return;
+        }
+        if let Some(mut client_instance) = self.client_instance.take() {
+            client_instance.shutdown().await;
+            MQClientManager::get_instance()
+                .remove_client_factory(&CheetahString::from_string(
+                    self.client_config.build_mq_client_id(),
+                ))
+                .await;
+        }
+        info!("the adminExt [{}] shutdown OK", self.admin_ext_group);
+        self.service_state = ServiceState::ShutdownAlready;
     }
 
     async fn add_broker_to_container(
@@ -98,7 +423,7 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         broker_container_addr: CheetahString,
         broker_config: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation("add_broker_to_container"))
     }
 
     async fn remove_broker_from_container(
@@ -108,7 +433,9 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         broker_name: CheetahString,
         broker_id: u64,
     ) -> crate::Result<()> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation(
+            "remove_broker_from_container",
+        ))
     }
 
     async fn update_broker_config(
@@ -116,14 +443,22 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         broker_addr: CheetahString,
         properties: HashMap<CheetahString, CheetahString>,
     ) -> crate::Result<()> {
-        todo!()
+        self.mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .update_broker_config(&broker_addr, &properties, self.timeout_millis)
+            .await?;
+        Ok(())
     }
 
     async fn get_broker_config(
         &self,
         broker_addr: CheetahString,
     ) -> crate::Result<HashMap<CheetahString, CheetahString>> {
-        todo!()
+        Ok(self
+            .mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .get_broker_config(&broker_addr, self.timeout_millis)
+            .await?)
     }
 
     async fn create_and_update_topic_config(
@@ -131,7 +466,16 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         addr: CheetahString,
         config: TopicConfig,
     ) -> crate::Result<()> {
-        todo!()
+        self.mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .create_topic(
+                &addr,
+                &CheetahString::from_static_str(TopicValidator::AUTO_CREATE_TOPIC_KEY_TOPIC),
+                &config,
+                self.timeout_millis,
+            )
+            .await?;
+        Ok(())
     }
 
     async fn create_and_update_topic_config_list(
@@ -139,7 +483,11 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         addr: CheetahString,
         topic_config_list: Vec<TopicConfig>,
     ) -> crate::Result<()> {
-        todo!()
+        self.mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .create_topic_list(&addr, topic_config_list, self.timeout_millis)
+            .await?;
+        Ok(())
     }
 
     async fn create_and_update_plain_access_config(
@@ -147,7 +495,11 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         addr: CheetahString,
         config: PlainAccessConfig,
     ) -> crate::Result<()> {
-        todo!()
+        self.mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .create_plain_access_config(&addr, &config, self.timeout_millis)
+            .await?;
+        Ok(())
     }
 
     async fn delete_plain_access_config(
@@ -155,7 +507,11 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         addr: CheetahString,
         access_key: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        self.mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .delete_access_config(&addr, access_key, self.timeout_millis)
+            .await?;
+        Ok(())
     }
 
     async fn update_global_white_addr_config(
@@ -164,14 +520,30 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         global_white_addrs: CheetahString,
         acl_file_full_path: Option<CheetahString>,
     ) -> crate::Result<()> {
-        todo!()
+        self.mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .update_global_white_addrs_config(
+                &addr,
+                global_white_addrs,
+                acl_file_full_path,
+                self.timeout_millis,
+            )
+            .await?;
+        Ok(())
     }
 
     async fn examine_broker_cluster_acl_version_info(
         &self,
         addr: CheetahString,
     ) -> crate::Result<CheetahString> {
-        todo!()
+        let cluster_acl_version_info = self
+            .mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .get_broker_cluster_acl_info(&addr, self.timeout_millis)
+            .await?;
+        Ok(CheetahString::from_string(SerdeJsonUtils::to_json(
+            &cluster_acl_version_info,
+        )?))
     }
 
     async fn create_and_update_subscription_group_config(
@@ -179,7 +551,11 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         addr: CheetahString,
         config: SubscriptionGroupConfig,
     ) -> crate::Result<()> {
-        todo!()
+        self.mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .create_subscription_group(&addr, &config, self.timeout_millis)
+            .await?;
+        Ok(())
     }
 
     async fn create_and_update_subscription_group_config_list(
@@ -187,7 +563,13 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         broker_addr: CheetahString,
         configs: Vec<SubscriptionGroupConfig>,
     ) -> crate::Result<()> {
-        todo!()
+        let mut mq_client_api = self.mq_client_api_impl().ok_or_else(not_started)?;
+        for config in configs {
+            mq_client_api
+                .create_subscription_group(&broker_addr, &config, self.timeout_millis)
+                .await?;
+        }
+        Ok(())
     }
 
     async fn examine_subscription_group_config(
@@ -195,7 +577,11 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         addr: CheetahString,
         group: CheetahString,
     ) -> crate::Result<SubscriptionGroupConfig> {
-        todo!()
+        Ok(self
+            .mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .get_subscription_group_config(&addr, &group, self.timeout_millis)
+            .await?)
     }
 
     async fn examine_topic_stats(
@@ -203,32 +589,61 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         topic: CheetahString,
         broker_addr: Option<CheetahString>,
     ) -> crate::Result<TopicStatsTable> {
-        todo!()
+        let broker_addrs = match broker_addr {
+            Some(broker_addr) => vec![broker_addr],
+            None => select_broker_addrs(&self.topic_route(&topic).await?),
+        };
+        let mut mq_client_api = self.mq_client_api_impl().ok_or_else(not_started)?;
+        let mut offset_table = HashMap::new();
+        for broker_addr in broker_addrs {
+            let topic_stats_table = mq_client_api
+                .get_topic_stats_info(&broker_addr, &topic, self.timeout_millis)
+                .await?;
+            offset_table.extend(topic_stats_table.get_offset_table());
+        }
+        let mut topic_stats_table = TopicStatsTable::new();
+        topic_stats_table.set_offset_table(offset_table);
+        Ok(topic_stats_table)
     }
 
     async fn examine_topic_stats_concurrent(
         &self,
         topic: CheetahString,
     ) -> AdminToolResult<TopicStatsTable> {
-        todo!()
+        match self.examine_topic_stats(topic, None).await {
+            Ok(topic_stats_table) => AdminToolResult::success(topic_stats_table),
+            Err(e) => admin_tool_failure(e),
+        }
     }
 
     async fn fetch_all_topic_list(&self) -> crate::Result<TopicList> {
-        todo!()
+        Ok(self
+            .mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .get_topic_list_from_name_server(self.timeout_millis)
+            .await?)
     }
 
     async fn fetch_topics_by_cluster(
         &self,
         cluster_name: CheetahString,
     ) -> crate::Result<TopicList> {
-        todo!()
+        Ok(self
+            .mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .get_topics_by_cluster(&cluster_name, self.timeout_millis)
+            .await?)
     }
 
     async fn fetch_broker_runtime_stats(
         &self,
         broker_addr: CheetahString,
     ) -> crate::Result<KVTable> {
-        todo!()
+        Ok(self
+            .mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .get_broker_runtime_info(&broker_addr, self.timeout_millis)
+            .await?)
     }
 
     async fn examine_consume_stats(
@@ -239,18 +654,63 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         broker_addr: Option<CheetahString>,
         timeout_millis: Option<u64>,
     ) -> crate::Result<ConsumeStats> {
-        todo!()
+        let timeout_millis = timeout_millis.unwrap_or(self.timeout_millis);
+        let broker_addrs = match broker_addr {
+            Some(broker_addr) => vec![broker_addr],
+            None => {
+                let retry_topic =
+                    CheetahString::from_string(mix_all::get_retry_topic(&consumer_group));
+                let route = match (self.topic_route(&retry_topic).await, topic.as_ref()) {
+                    (Ok(route), _) => route,
+                    (Err(_), Some(topic)) => self.topic_route(topic).await?,
+                    (Err(e), None) => return Err(e),
+                };
+                route
+                    .broker_datas
+                    .iter()
+                    .filter(|broker_data| {
+                        cluster_name.as_ref().map_or(true, |cluster_name| {
+                            broker_data.cluster() == cluster_name.as_str()
+                        })
+                    })
+                    .filter_map(|broker_data| broker_data.select_broker_addr())
+                    .collect()
+            }
+        };
+        let mut mq_client_api = self.mq_client_api_impl().ok_or_else(not_started)?;
+        let mut offset_table = HashMap::new();
+        let mut consume_tps = 0.0;
+        for broker_addr in broker_addrs {
+            let consume_stats = mq_client_api
+                .get_consume_stats(
+                    &broker_addr,
+                    &consumer_group,
+                    topic.as_ref(),
+                    timeout_millis,
+                )
+                .await?;
+            offset_table.extend(consume_stats.get_offset_table());
+            consume_tps += consume_stats.get_consume_tps();
+        }
+        let mut consume_stats = ConsumeStats::new();
+        consume_stats.set_offset_table(offset_table);
+        consume_stats.set_consume_tps(consume_tps);
+        Ok(consume_stats)
     }
 
     async fn examine_broker_cluster_info(&self) -> crate::Result<ClusterInfo> {
-        todo!()
+        Ok(self
+            .mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .get_broker_cluster_info(self.timeout_millis)
+            .await?)
     }
 
     async fn examine_topic_route_info(
         &self,
         topic: CheetahString,
     ) -> crate::Result<TopicRouteData> {
-        todo!()
+        self.topic_route(&topic).await
     }
 
     async fn examine_consumer_connection_info(
@@ -258,7 +718,38 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         consumer_group: CheetahString,
         broker_addr: Option<CheetahString>,
     ) -> crate::Result<ConsumerConnection> {
-        todo!()
+        let broker_addrs = match broker_addr {
+            Some(broker_addr) => vec![broker_addr],
+            None => {
+                let retry_topic =
+                    CheetahString::from_string(mix_all::get_retry_topic(&consumer_group));
+                select_broker_addrs(&self.topic_route(&retry_topic).await?)
+            }
+        };
+        let mut mq_client_api = self.mq_client_api_impl().ok_or_else(not_started)?;
+        for broker_addr in broker_addrs {
+            match mq_client_api
+                .get_consumer_connection_list(&broker_addr, &consumer_group, self.timeout_millis)
+                .await
+            {
+                Ok(consumer_connection) if !consumer_connection.get_connection_set().is_empty() => {
+                    return Ok(consumer_connection);
+                }
+                Ok(_) => {}
+                Err(e) => warn!(
+                    "get consumer connection list of {} from {} failed: {}",
+                    consumer_group, broker_addr, e
+                ),
+            }
+        }
+        Err(MQClientError::MQClientErr(ClientErr::new_with_code(
+            ResponseCode::ConsumerNotOnline as i32,
+            format!(
+                "Not found the consumer group connection, {}",
+                consumer_group
+            ),
+        ))
+        .into())
     }
 
     async fn examine_producer_connection_info(
@@ -266,11 +757,15 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         producer_group: CheetahString,
         topic: CheetahString,
     ) -> crate::Result<ProducerConnection> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation(
+            "examine_producer_connection_info",
+        ))
     }
 
     async fn get_name_server_address_list(&self) -> Vec<CheetahString> {
-        todo!()
+        self.mq_client_api_impl()
+            .map(|mq_client_api| mq_client_api.get_name_server_address_list().to_vec())
+            .unwrap_or_default()
     }
 
     async fn wipe_write_perm_of_broker(
@@ -278,7 +773,11 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         namesrv_addr: CheetahString,
         broker_name: CheetahString,
     ) -> crate::Result<i32> {
-        todo!()
+        Ok(self
+            .mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .wipe_write_perm_of_broker(&namesrv_addr, &broker_name, self.timeout_millis)
+            .await?)
     }
 
     async fn add_write_perm_of_broker(
@@ -286,7 +785,11 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         namesrv_addr: CheetahString,
         broker_name: CheetahString,
     ) -> crate::Result<i32> {
-        todo!()
+        Ok(self
+            .mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .add_write_perm_of_broker(&namesrv_addr, &broker_name, self.timeout_millis)
+            .await?)
     }
 
     async fn put_kv_config(
@@ -295,7 +798,12 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         key: CheetahString,
         value: CheetahString,
     ) {
-        todo!()
+        if let Err(e) = self
+            .create_and_update_kv_config(namespace, key, value)
+            .await
+        {
+            warn!("put kv config failed: {}", e);
+        }
     }
 
     async fn get_kv_config(
@@ -303,11 +811,19 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         namespace: CheetahString,
         key: CheetahString,
     ) -> crate::Result<CheetahString> {
-        todo!()
+        Ok(self
+            .mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .get_kv_config_value(&namespace, &key, self.timeout_millis)
+            .await?)
     }
 
     async fn get_kv_list_by_namespace(&self, namespace: CheetahString) -> crate::Result<KVTable> {
-        todo!()
+        Ok(self
+            .mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .get_kv_list_by_namespace(&namespace, self.timeout_millis)
+            .await?)
     }
 
     async fn delete_topic(
@@ -315,7 +831,12 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         topic_name: CheetahString,
         cluster_name: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        let cluster_info = self.examine_broker_cluster_info().await?;
+        let broker_addrs = cluster_broker_addrs(&cluster_info, &cluster_name, false);
+        self.delete_topic_in_broker(broker_addrs, topic_name.clone())
+            .await?;
+        self.delete_topic_in_name_server(HashSet::new(), Some(cluster_name), topic_name)
+            .await
     }
 
     async fn delete_topic_in_broker(
@@ -323,7 +844,13 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         addrs: HashSet<CheetahString>,
         topic: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        let mut mq_client_api = self.mq_client_api_impl().ok_or_else(not_started)?;
+        for addr in addrs {
+            mq_client_api
+                .delete_topic_in_broker(&addr, &topic, self.timeout_millis)
+                .await?;
+        }
+        Ok(())
     }
 
     async fn delete_topic_in_name_server(
@@ -332,7 +859,23 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         cluster_name: Option<CheetahString>,
         topic: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        let mut mq_client_api = self.mq_client_api_impl().ok_or_else(not_started)?;
+        let addrs = if addrs.is_empty() {
+            mq_client_api.get_name_server_address_list().to_vec()
+        } else {
+            addrs.into_iter().collect()
+        };
+        for addr in addrs {
+            mq_client_api
+                .delete_topic_in_name_server(
+                    &addr,
+                    cluster_name.clone(),
+                    &topic,
+                    self.timeout_millis,
+                )
+                .await?;
+        }
+        Ok(())
     }
 
     async fn delete_subscription_group(
@@ -341,7 +884,16 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         group_name: CheetahString,
         remove_offset: Option<bool>,
     ) -> crate::Result<()> {
-        todo!()
+        self.mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .delete_subscription_group(
+                &addr,
+                &group_name,
+                remove_offset.unwrap_or(false),
+                self.timeout_millis,
+            )
+            .await?;
+        Ok(())
     }
 
     async fn create_and_update_kv_config(
@@ -350,7 +902,11 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         key: CheetahString,
         value: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        self.mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .put_kv_config_value(&namespace, &key, &value, self.timeout_millis)
+            .await?;
+        Ok(())
     }
 
     async fn delete_kv_config(
@@ -358,7 +914,11 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         namespace: CheetahString,
         key: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        self.mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .delete_kv_config_value(&namespace, &key, self.timeout_millis)
+            .await?;
+        Ok(())
     }
 
     async fn reset_offset_by_timestamp(
@@ -369,7 +929,36 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         timestamp: u64,
         is_force: bool,
     ) -> crate::Result<HashMap<MessageQueue, u64>> {
-        todo!()
+        let route = self.topic_route(&topic).await?;
+        let mut mq_client_api = self.mq_client_api_impl().ok_or_else(not_started)?;
+        let mut all_offset_table = HashMap::new();
+        for broker_data in route.broker_datas.iter().filter(|broker_data| {
+            cluster_name.as_ref().map_or(true, |cluster_name| {
+                broker_data.cluster() == cluster_name.as_str()
+            })
+        }) {
+            let Some(broker_addr) = broker_data.select_broker_addr() else {
+                continue;
+            };
+            let offset_table = mq_client_api
+                .invoke_broker_to_reset_offset(
+                    &broker_addr,
+                    &topic,
+                    &group,
+                    timestamp as i64,
+                    is_force,
+                    -1,
+                    None,
+                    self.timeout_millis,
+                )
+                .await?;
+            all_offset_table.extend(
+                offset_table
+                    .into_iter()
+                    .map(|(mq, offset)| (mq, offset.max(0) as u64)),
+            );
+        }
+        Ok(all_offset_table)
     }
 
     async fn reset_offset_new(
@@ -378,7 +967,7 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         topic: CheetahString,
         timestamp: u64,
     ) -> crate::Result<()> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation("reset_offset_new"))
     }
 
     async fn get_consume_status(
@@ -387,7 +976,7 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         group: CheetahString,
         client_addr: CheetahString,
     ) -> crate::Result<HashMap<CheetahString, HashMap<MessageQueue, u64>>> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation("get_consume_status"))
     }
 
     async fn create_or_update_order_conf(
@@ -396,22 +985,50 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         value: CheetahString,
         is_cluster: bool,
     ) -> crate::Result<()> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation(
+            "create_or_update_order_conf",
+        ))
     }
 
     async fn query_topic_consume_by_who(&self, topic: CheetahString) -> crate::Result<GroupList> {
-        todo!()
+        let route = self.topic_route(&topic).await?;
+        let mut mq_client_api = self.mq_client_api_impl().ok_or_else(not_started)?;
+        let mut group_list = GroupList::default();
+        for broker_addr in select_broker_addrs(&route) {
+            let groups = mq_client_api
+                .query_topic_consume_by_who(&broker_addr, &topic, self.timeout_millis)
+                .await?;
+            group_list.group_list.extend(groups.group_list);
+        }
+        Ok(group_list)
     }
 
     async fn query_topics_by_consumer(&self, group: CheetahString) -> crate::Result<TopicList> {
-        todo!()
+        let retry_topic = CheetahString::from_string(mix_all::get_retry_topic(&group));
+        let route = self.topic_route(&retry_topic).await?;
+        let mut mq_client_api = self.mq_client_api_impl().ok_or_else(not_started)?;
+        let mut topic_list = TopicList::default();
+        for broker_addr in select_broker_addrs(&route) {
+            let topics = mq_client_api
+                .query_topics_by_consumer(&broker_addr, &group, self.timeout_millis)
+                .await?;
+            for topic in topics.topic_list {
+                if !topic_list.topic_list.contains(&topic) {
+                    topic_list.topic_list.push(topic);
+                }
+            }
+        }
+        Ok(topic_list)
     }
 
     async fn query_topics_by_consumer_concurrent(
         &self,
         group: CheetahString,
     ) -> AdminToolResult<TopicList> {
-        todo!()
+        match self.query_topics_by_consumer(group).await {
+            Ok(topic_list) => AdminToolResult::success(topic_list),
+            Err(e) => admin_tool_failure(e),
+        }
     }
 
     async fn query_subscription(
@@ -419,7 +1036,7 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         group: CheetahString,
         topic: CheetahString,
     ) -> crate::Result<SubscriptionData> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation("query_subscription"))
     }
 
     async fn clean_expired_consumer_queue(
@@ -427,7 +1044,15 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         cluster: Option<CheetahString>,
         addr: Option<CheetahString>,
     ) -> crate::Result<bool> {
-        todo!()
+        let broker_addrs = self.target_master_addrs(cluster, addr).await?;
+        let mut mq_client_api = self.mq_client_api_impl().ok_or_else(not_started)?;
+        let mut result = !broker_addrs.is_empty();
+        for broker_addr in broker_addrs {
+            result &= mq_client_api
+                .clean_expired_consume_queue(&broker_addr, self.timeout_millis)
+                .await?;
+        }
+        Ok(result)
     }
 
     async fn delete_expired_commit_log(
@@ -435,7 +1060,15 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         cluster: Option<CheetahString>,
         addr: Option<CheetahString>,
     ) -> crate::Result<bool> {
-        todo!()
+        let broker_addrs = self.target_master_addrs(cluster, addr).await?;
+        let mut mq_client_api = self.mq_client_api_impl().ok_or_else(not_started)?;
+        let mut result = !broker_addrs.is_empty();
+        for broker_addr in broker_addrs {
+            result &= mq_client_api
+                .delete_expired_commit_log(&broker_addr, self.timeout_millis)
+                .await?;
+        }
+        Ok(result)
     }
 
     async fn clean_unused_topic(
@@ -443,7 +1076,15 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         cluster: Option<CheetahString>,
         addr: Option<CheetahString>,
     ) -> crate::Result<bool> {
-        todo!()
+        let broker_addrs = self.target_master_addrs(cluster, addr).await?;
+        let mut mq_client_api = self.mq_client_api_impl().ok_or_else(not_started)?;
+        let mut result = !broker_addrs.is_empty();
+        for broker_addr in broker_addrs {
+            result &= mq_client_api
+                .clean_unused_topic(&broker_addr, self.timeout_millis)
+                .await?;
+        }
+        Ok(result)
     }
 
     async fn get_consumer_running_info(
@@ -453,7 +1094,28 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         jstack: bool,
         metrics: Option<bool>,
     ) -> crate::Result<ConsumerRunningInfo> {
-        todo!()
+        let retry_topic = CheetahString::from_string(mix_all::get_retry_topic(&consumer_group));
+        let route = self.topic_route(&retry_topic).await?;
+        let broker_addr = select_broker_addrs(&route)
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                MQClientError::MQClientErr(ClientErr::new_with_code(
+                    ResponseCode::ConsumerNotOnline as i32,
+                    format!("no broker found for consumer group {}", consumer_group),
+                ))
+            })?;
+        Ok(self
+            .mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .get_consumer_running_info(
+                &broker_addr,
+                &consumer_group,
+                &client_id,
+                jstack,
+                self.timeout_millis,
+            )
+            .await?)
     }
 
     async fn consume_message_directly(
@@ -463,7 +1125,22 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         topic: CheetahString,
         msg_id: CheetahString,
     ) -> crate::Result<ConsumeMessageDirectlyResult> {
-        todo!()
+        // The broker looks the message up by its offset message id, so resolve unique client
+        // ids first. The store host of the message is the broker holding it.
+        let message = self.view_message(topic.clone(), msg_id).await?;
+        let broker_addr = CheetahString::from_string(message.store_host().to_string());
+        Ok(self
+            .mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .consume_message_directly(
+                &broker_addr,
+                &consumer_group,
+                &client_id,
+                &topic,
+                message.msg_id(),
+                self.timeout_millis,
+            )
+            .await?)
     }
 
     async fn consume_message_directly_ext(
@@ -474,7 +1151,187 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         topic: CheetahString,
         msg_id: CheetahString,
     ) -> crate::Result<ConsumeMessageDirectlyResult> {
-        todo!()
+        self.consume_message_directly(consumer_group, client_id, topic, msg_id)
+            .await
+    }
+
+    async fn view_message(
+        &self,
+        topic: CheetahString,
+        msg_id: CheetahString,
+    ) -> crate::Result<MessageExt> {
+        let route = self.topic_route(&topic).await?;
+        // A unique client id may decode as an offset id too, so only trust the decoded store
+        // host when it is a broker serving the topic.
+        if let Some(message_id) = MessageDecoder::try_decode_message_id(msg_id.as_str()) {
+            let store_host = message_id.address.to_string();
+            let is_route_broker = route
+                .broker_datas
+                .iter()
+                .flat_map(|broker_data| broker_data.broker_addrs().values())
+                .any(|addr| addr.as_str() == store_host);
+            if is_route_broker {
+                return Ok(self
+                    .mq_client_api_impl()
+                    .ok_or_else(not_started)?
+                    .view_message(
+                        &CheetahString::from_string(store_host),
+                        &topic,
+                        message_id.offset,
+                        self.timeout_millis,
+                    )
+                    .await?);
+            }
+        }
+
+        let messages = self
+            .query_message_in_route(&route, &topic, &msg_id, 32, 0, i64::MAX, true)
+            .await?;
+        messages.into_iter().next().ok_or_else(|| {
+            MQClientError::MQClientErr(ClientErr::new_with_code(
+                ResponseCode::QueryNotFound as i32,
+                format!(
+                    "query message by id finished, but no message. topic={}, msgId={}",
+                    topic, msg_id
+                ),
+            ))
+            .into()
+        })
+    }
+
+    async fn query_message_by_key(
+        &self,
+        topic: CheetahString,
+        key: CheetahString,
+        max_num: i32,
+        begin: i64,
+        end: i64,
+    ) -> crate::Result<Vec<MessageExt>> {
+        let route = self.topic_route(&topic).await?;
+        self.query_message_in_route(&route, &topic, &key, max_num, begin, end, false)
+            .await
+    }
+
+    async fn pull_message_by_offset(
+        &self,
+        mq: MessageQueue,
+        offset: i64,
+        max_nums: i32,
+    ) -> crate::Result<PullResult> {
+        let route = self.topic_route(mq.get_topic_cs()).await?;
+        let broker_addr = route
+            .broker_datas
+            .iter()
+            .find(|broker_data| broker_data.broker_name() == mq.get_broker_name())
+            .and_then(|broker_data| broker_data.select_broker_addr())
+            .ok_or_else(|| {
+                ToolsError::from(MQClientError::MQClientErr(ClientErr::new(format!(
+                    "The broker[{}] not exist",
+                    mq.get_broker_name()
+                ))))
+            })?;
+        Ok(self
+            .mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .pull_message_from_offset(
+                &broker_addr,
+                &CheetahString::from_static_str(mix_all::TOOLS_CONSUMER_GROUP),
+                &mq,
+                offset,
+                max_nums,
+                self.timeout_millis,
+            )
+            .await?)
+    }
+
+    async fn message_track_detail(&self, msg: MessageExt) -> crate::Result<Vec<MessageTrack>> {
+        let group_list = self
+            .query_topic_consume_by_who(msg.get_topic().clone())
+            .await?;
+        let cluster_info = self.examine_broker_cluster_info().await?;
+        let mut tracks = Vec::with_capacity(group_list.group_list.len());
+        for group in group_list.group_list {
+            let mut track = MessageTrack::new(group.clone());
+            let consumer_connection = match self
+                .examine_consumer_connection_info(group.clone(), None)
+                .await
+            {
+                Ok(consumer_connection) => consumer_connection,
+                Err(e) => {
+                    if error_response_code(&e) == Some(ResponseCode::ConsumerNotOnline as i32) {
+                        track.track_type = TrackType::NotOnline;
+                    }
+                    track.exception_desc = Some(CheetahString::from_string(e.to_string()));
+                    tracks.push(track);
+                    continue;
+                }
+            };
+            if consumer_connection.get_consume_type() == ConsumeType::ConsumeActively {
+                track.track_type = TrackType::Pull;
+            } else if consumer_connection.get_message_model() == MessageModel::Broadcasting {
+                // Broadcasting consumers keep their offsets locally, the broker cannot tell.
+                track.track_type = TrackType::ConsumeBroadcasting;
+            } else {
+                match self
+                    .examine_consume_stats(
+                        group.clone(),
+                        Some(msg.get_topic().clone()),
+                        None,
+                        None,
+                        None,
+                    )
+                    .await
+                {
+                    Ok(consume_stats) if is_consumed(&msg, &consume_stats, &cluster_info) => {
+                        track.track_type = if is_subscribed(&msg, &consumer_connection) {
+                            TrackType::Consumed
+                        } else {
+                            TrackType::ConsumedButFiltered
+                        };
+                    }
+                    Ok(_) => track.track_type = TrackType::NotConsumeYet,
+                    Err(e) => {
+                        if error_response_code(&e) == Some(ResponseCode::ConsumerNotOnline as i32) {
+                            track.track_type = TrackType::NotOnline;
+                        }
+                        track.exception_desc = Some(CheetahString::from_string(e.to_string()));
+                    }
+                }
+            }
+            tracks.push(track);
+        }
+        Ok(tracks)
+    }
+
+    async fn message_track_detail_concurrent(
+        &self,
+        msg: MessageExt,
+    ) -> AdminToolResult<Vec<MessageTrack>> {
+        match self.message_track_detail(msg).await {
+            Ok(tracks) => AdminToolResult::success(tracks),
+            Err(e) => admin_tool_failure(e),
+        }
+    }
+
+    async fn query_message_trace_by_id(
+        &self,
+        trace_topic: CheetahString,
+        msg_id: CheetahString,
+    ) -> crate::Result<Vec<TraceView>> {
+        let route = self.topic_route(&trace_topic).await?;
+        // Trace messages are indexed by the unique ids and keys of the messages they trace.
+        let messages = self
+            .query_message_in_route(&route, &trace_topic, &msg_id, 64, 0, i64::MAX, false)
+            .await?;
+        let mut seen = HashSet::new();
+        let mut views: Vec<TraceView> = messages
+            .iter()
+            .filter(|message| seen.insert(message.msg_id().clone()))
+            .flat_map(|message| TraceView::decode_from_trace_trans_data(&msg_id, message))
+            .collect();
+        complete_consume_views(&mut views);
+        views.sort_by_key(|view| view.time_stamp);
+        Ok(views)
     }
 
     async fn clone_group_offset(
@@ -484,15 +1341,35 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         topic: CheetahString,
         is_offline: bool,
     ) -> crate::Result<()> {
-        todo!()
+        let retry_topic = CheetahString::from_string(mix_all::get_retry_topic(&src_group));
+        let route = self.topic_route(&retry_topic).await?;
+        let mut mq_client_api = self.mq_client_api_impl().ok_or_else(not_started)?;
+        for broker_addr in select_broker_addrs(&route) {
+            mq_client_api
+                .clone_group_offset(
+                    &broker_addr,
+                    &src_group,
+                    &dest_group,
+                    Some(&topic),
+                    is_offline,
+                    self.timeout_millis,
+                )
+                .await?;
+        }
+        Ok(())
     }
 
     async fn get_cluster_list(&self, topic: String) -> crate::Result<HashSet<CheetahString>> {
-        todo!()
+        let route = self.topic_route(&CheetahString::from_string(topic)).await?;
+        Ok(route
+            .broker_datas
+            .iter()
+            .map(|broker_data| CheetahString::from_slice(broker_data.cluster()))
+            .collect())
     }
 
     async fn get_topic_cluster_list(&self, topic: String) -> crate::Result<HashSet<CheetahString>> {
-        todo!()
+        self.get_cluster_list(topic).await
     }
 
     async fn get_all_topic_config(
@@ -500,7 +1377,11 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         broker_addr: CheetahString,
         timeout_millis: u64,
     ) -> crate::Result<TopicConfigSerializeWrapper> {
-        todo!()
+        Ok(self
+            .mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .get_all_topic_config(&broker_addr, timeout_millis)
+            .await?)
     }
 
     async fn get_user_topic_config(
@@ -509,7 +1390,20 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         special_topic: bool,
         timeout_millis: u64,
     ) -> crate::Result<TopicConfigSerializeWrapper> {
-        todo!()
+        let topic_config_wrapper = self
+            .get_all_topic_config(broker_addr, timeout_millis)
+            .await?;
+        let topic_config_table = topic_config_wrapper.topic_config_table().map(|table| {
+            table
+                .iter()
+                .filter(|(topic, _)| is_user_topic(topic, special_topic))
+                .map(|(topic, config)| (topic.clone(), config.clone()))
+                .collect()
+        });
+        Ok(TopicConfigSerializeWrapper::new(
+            topic_config_table,
+            topic_config_wrapper.data_version().cloned(),
+        ))
     }
 
     async fn update_consume_offset(
@@ -519,7 +1413,18 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         mq: MessageQueue,
         offset: u64,
     ) -> crate::Result<()> {
-        todo!()
+        let request_header = UpdateConsumerOffsetRequestHeader {
+            consumer_group: consume_group,
+            topic: CheetahString::from_slice(mq.get_topic()),
+            queue_id: mq.get_queue_id(),
+            commit_offset: offset as i64,
+            topic_request_header: None,
+        };
+        self.mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .update_consumer_offset(&broker_addr, request_header, self.timeout_millis)
+            .await?;
+        Ok(())
     }
 
     async fn update_name_server_config(
@@ -527,14 +1432,38 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         properties: HashMap<CheetahString, CheetahString>,
         name_servers: Vec<CheetahString>,
     ) -> crate::Result<()> {
-        todo!()
+        let mut mq_client_api = self.mq_client_api_impl().ok_or_else(not_started)?;
+        let name_servers = if name_servers.is_empty() {
+            mq_client_api.get_name_server_address_list().to_vec()
+        } else {
+            name_servers
+        };
+        for name_server in name_servers {
+            mq_client_api
+                .update_name_server_config(&name_server, &properties, self.timeout_millis)
+                .await?;
+        }
+        Ok(())
     }
 
     async fn get_name_server_config(
         &self,
         name_servers: Vec<CheetahString>,
     ) -> crate::Result<HashMap<CheetahString, HashMap<CheetahString, CheetahString>>> {
-        todo!()
+        let mut mq_client_api = self.mq_client_api_impl().ok_or_else(not_started)?;
+        let name_servers = if name_servers.is_empty() {
+            mq_client_api.get_name_server_address_list().to_vec()
+        } else {
+            name_servers
+        };
+        let mut configs = HashMap::with_capacity(name_servers.len());
+        for name_server in name_servers {
+            let config = mq_client_api
+                .get_name_server_config(&name_server, self.timeout_millis)
+                .await?;
+            configs.insert(name_server, config);
+        }
+        Ok(configs)
     }
 
     async fn resume_check_half_message(
@@ -542,7 +1471,9 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         topic: CheetahString,
         msg_id: CheetahString,
     ) -> crate::Result<bool> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation(
+            "resume_check_half_message",
+        ))
     }
 
     async fn set_message_request_mode(
@@ -554,12 +1485,8 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         pop_work_group_size: i32,
         timeout_millis: u64,
     ) -> crate::Result<()> {
-        let mut mq_client_api = self
-            .client_instance
-            .as_ref()
-            .unwrap()
-            .get_mq_client_api_impl();
-        match mq_client_api
+        self.mq_client_api_impl()
+            .ok_or_else(not_started)?
             .set_message_request_mode(
                 &broker_addr,
                 &topic,
@@ -568,11 +1495,8 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
                 pop_work_group_size,
                 timeout_millis,
             )
-            .await
-        {
-            Ok(_) => Ok(()),
-            Err(e) => Err(crate::tools_error::ToolsError::MQClientError(e)),
-        }
+            .await?;
+        Ok(())
     }
 
     async fn reset_offset_by_queue_id(
@@ -583,7 +1507,20 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         queue_id: i32,
         reset_offset: u64,
     ) -> crate::Result<()> {
-        todo!()
+        self.mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .invoke_broker_to_reset_offset(
+                &broker_addr,
+                &topic_name,
+                &consumer_group,
+                0,
+                false,
+                queue_id,
+                Some(reset_offset as i64),
+                self.timeout_millis,
+            )
+            .await?;
+        Ok(())
     }
 
     async fn examine_topic_config(
@@ -591,7 +1528,11 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         addr: CheetahString,
         topic: CheetahString,
     ) -> crate::Result<TopicConfig> {
-        todo!()
+        Ok(self
+            .mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .get_topic_config(&addr, &topic, self.timeout_millis)
+            .await?)
     }
 
     async fn create_static_topic(
@@ -602,7 +1543,24 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         mapping_detail: TopicQueueMappingDetail,
         force: bool,
     ) -> crate::Result<()> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation("create_static_topic"))
+    }
+
+    async fn query_message(
+        &self,
+        broker_addr: CheetahString,
+        request_body: QueryMessageByConditionRequestBody,
+    ) -> crate::Result<QueryMessagePage> {
+        let (response_header, messages) = self
+            .mq_client_api_impl()
+            .ok_or_else(not_started)?
+            .query_message_by_condition(&broker_addr, &request_body, self.timeout_millis)
+            .await?;
+        Ok(QueryMessagePage {
+            messages,
+            index_last_update_timestamp: response_header.index_last_update_timestamp,
+            next_cursor: response_header.next_cursor,
+        })
     }
 
     async fn reset_master_flush_offset(
@@ -610,14 +1568,16 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         broker_addr: CheetahString,
         master_flush_offset: u64,
     ) -> crate::Result<()> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation(
+            "reset_master_flush_offset",
+        ))
     }
 
     async fn get_controller_config(
         &self,
         controller_servers: Vec<CheetahString>,
     ) -> crate::Result<HashMap<CheetahString, HashMap<CheetahString, CheetahString>>> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation("get_controller_config"))
     }
 
     async fn update_controller_config(
@@ -625,7 +1585,7 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         properties: HashMap<CheetahString, CheetahString>,
         controllers: Vec<CheetahString>,
     ) -> crate::Result<()> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation("update_controller_config"))
     }
 
     async fn clean_controller_broker_data(
@@ -636,7 +1596,9 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         broker_controller_ids_to_clean: Option<CheetahString>,
         is_clean_living_broker: bool,
     ) -> crate::Result<()> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation(
+            "clean_controller_broker_data",
+        ))
     }
 
     async fn update_cold_data_flow_ctr_group_config(
@@ -644,7 +1606,9 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         broker_addr: CheetahString,
         properties: HashMap<CheetahString, CheetahString>,
     ) -> crate::Result<()> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation(
+            "update_cold_data_flow_ctr_group_config",
+        ))
     }
 
     async fn remove_cold_data_flow_ctr_group_config(
@@ -652,14 +1616,18 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         broker_addr: CheetahString,
         consumer_group: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation(
+            "remove_cold_data_flow_ctr_group_config",
+        ))
     }
 
     async fn get_cold_data_flow_ctr_info(
         &self,
         broker_addr: CheetahString,
     ) -> crate::Result<CheetahString> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation(
+            "get_cold_data_flow_ctr_info",
+        ))
     }
 
     async fn set_commit_log_read_ahead_mode(
@@ -667,7 +1635,9 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         broker_addr: CheetahString,
         mode: CheetahString,
     ) -> crate::Result<CheetahString> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation(
+            "set_commit_log_read_ahead_mode",
+        ))
     }
 
     async fn create_user(
@@ -677,7 +1647,7 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         password: CheetahString,
         user_type: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation("create_user"))
     }
 
     async fn update_user(
@@ -688,7 +1658,7 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         user_type: CheetahString,
         user_status: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation("update_user"))
     }
 
     async fn delete_user(
@@ -696,7 +1666,7 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         broker_addr: CheetahString,
         username: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation("delete_user"))
     }
 
     async fn create_acl(
@@ -708,7 +1678,7 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         source_ips: Vec<CheetahString>,
         decision: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation("create_acl"))
     }
 
     async fn update_acl(
@@ -720,7 +1690,7 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         source_ips: Vec<CheetahString>,
         decision: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation("update_acl"))
     }
 
     async fn delete_acl(
@@ -729,6 +1699,203 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         subject: CheetahString,
         resource: CheetahString,
     ) -> crate::Result<()> {
-        todo!()
+        Err(ToolsError::UnsupportedOperation("delete_acl"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocketmq_remoting::protocol::admin::offset_wrapper::OffsetWrapper;
+    use rocketmq_remoting::protocol::route::route_data_view::BrokerData;
+
+    use super::*;
+
+    fn admin_ext_impl() -> DefaultMQAdminExtImpl {
+        DefaultMQAdminExtImpl::new(
+            None,
+            3000,
+            ArcMut::new(ClientConfig::new()),
+            CheetahString::from_static_str("admin_ext_group"),
+        )
+    }
+
+    #[test]
+    fn collects_cluster_broker_addrs() {
+        let broker_data = BrokerData::new(
+            CheetahString::from_static_str("DefaultCluster"),
+            CheetahString::from_static_str("broker-a"),
+            HashMap::from([
+                (0, CheetahString::from_static_str("127.0.0.1:10911")),
+                (1, CheetahString::from_static_str("127.0.0.1:10921")),
+            ]),
+            None,
+        );
+        let cluster_info = ClusterInfo::new(
+            Some(HashMap::from([(
+                CheetahString::from_static_str("broker-a"),
+                broker_data,
+            )])),
+            Some(HashMap::from([(
+                CheetahString::from_static_str("DefaultCluster"),
+                HashSet::from([CheetahString::from_static_str("broker-a")]),
+            )])),
+        );
+        let cluster = CheetahString::from_static_str("DefaultCluster");
+
+        assert_eq!(
+            cluster_broker_addrs(&cluster_info, &cluster, true),
+            HashSet::from([CheetahString::from_static_str("127.0.0.1:10911")])
+        );
+        assert_eq!(
+            cluster_broker_addrs(&cluster_info, &cluster, false).len(),
+            2
+        );
+        assert!(cluster_broker_addrs(
+            &cluster_info,
+            &CheetahString::from_static_str("OtherCluster"),
+            false
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn filters_user_topics() {
+        assert!(is_user_topic("TopicTest", false));
+        assert!(!is_user_topic(
+            TopicValidator::AUTO_CREATE_TOPIC_KEY_TOPIC,
+            true
+        ));
+        assert!(!is_user_topic("%RETRY%group", false));
+        assert!(is_user_topic("%RETRY%group", true));
+        assert!(!is_user_topic("%DLQ%group", false));
+    }
+
+    #[test]
+    fn matches_message_keys_and_unique_id() {
+        let mut message = MessageExt::default();
+        message.set_keys(CheetahString::from_static_str("order-1 order-2"));
+        message.put_property(
+            CheetahString::from_static_str(MessageConst::PROPERTY_UNIQ_CLIENT_MESSAGE_ID_KEYIDX),
+            CheetahString::from_static_str("7F00000100002A9F0000000000000001"),
+        );
+
+        let key = CheetahString::from_static_str("order-2");
+        assert!(message_matches_key(&message, &key, false));
+        let key = CheetahString::from_static_str("order");
+        assert!(!message_matches_key(&message, &key, false));
+        let key = CheetahString::from_static_str("7F00000100002A9F0000000000000001");
+        assert!(message_matches_key(&message, &key, true));
+        assert!(!message_matches_key(&message, &key, false));
+    }
+
+    #[test]
+    fn tracks_consumed_messages_by_consume_offset() {
+        let broker_data = BrokerData::new(
+            CheetahString::from_static_str("DefaultCluster"),
+            CheetahString::from_static_str("broker-a"),
+            HashMap::from([(0, CheetahString::from_static_str("127.0.0.1:10911"))]),
+            None,
+        );
+        let cluster_info = ClusterInfo::new(
+            Some(HashMap::from([(
+                CheetahString::from_static_str("broker-a"),
+                broker_data,
+            )])),
+            None,
+        );
+        let mut message = MessageExt::default();
+        message.set_topic(CheetahString::from_static_str("TopicTest"));
+        message.set_queue_id(1);
+        message.set_queue_offset(10);
+        message.set_store_host("127.0.0.1:10911".parse().unwrap());
+
+        let consume_stats = |consumer_offset: i64| {
+            let mut offset_wrapper = OffsetWrapper::new();
+            offset_wrapper.set_consumer_offset(consumer_offset);
+            let mut consume_stats = ConsumeStats::new();
+            consume_stats.set_offset_table(HashMap::from([(
+                MessageQueue::from_parts("TopicTest", "broker-a", 1),
+                offset_wrapper,
+            )]));
+            consume_stats
+        };
+        assert!(is_consumed(&message, &consume_stats(11), &cluster_info));
+        assert!(!is_consumed(&message, &consume_stats(10), &cluster_info));
+
+        message.set_store_host("127.0.0.2:10911".parse().unwrap());
+        assert!(!is_consumed(&message, &consume_stats(11), &cluster_info));
+    }
+
+    #[test]
+    fn completes_consume_views_of_older_clients() {
+        let before = TraceView {
+            trace_type: Some(TraceType::SubBefore),
+            request_id: CheetahString::from_static_str("request-1"),
+            group_name: CheetahString::from_static_str("consumer_group"),
+            time_stamp: 100,
+            ..Default::default()
+        };
+        let after = TraceView {
+            trace_type: Some(TraceType::SubAfter),
+            request_id: CheetahString::from_static_str("request-1"),
+            cost_time: 7,
+            ..Default::default()
+        };
+        let orphan = TraceView {
+            trace_type: Some(TraceType::SubAfter),
+            request_id: CheetahString::from_static_str("request-2"),
+            ..Default::default()
+        };
+        let mut views = vec![before, after, orphan];
+
+        complete_consume_views(&mut views);
+        assert_eq!(views[1].group_name, "consumer_group");
+        assert_eq!(views[1].time_stamp, 107);
+        assert!(views[2].group_name.is_empty());
+    }
+
+    #[tokio::test]
+    async fn operations_fail_before_start() {
+        let admin = admin_ext_impl();
+        assert!(matches!(
+            admin.examine_broker_cluster_info().await,
+            Err(ToolsError::NotStarted)
+        ));
+        assert!(matches!(
+            admin
+                .examine_broker_cluster_acl_version_info(CheetahString::from_static_str(
+                    "127.0.0.1:10911"
+                ))
+                .await,
+            Err(ToolsError::NotStarted)
+        ));
+        assert!(admin
+            .examine_topic_route_info(CheetahString::from_static_str("TopicTest"))
+            .await
+            .is_err());
+        assert!(admin.get_name_server_address_list().await.is_empty());
+
+        let result = admin
+            .examine_topic_stats_concurrent(CheetahString::from_static_str("TopicTest"))
+            .await;
+        assert!(!result.is_success());
+        assert_eq!(
+            result.get_code(),
+            AdminToolsResultCodeEnum::MQClientError.get_code()
+        );
+    }
+
+    #[tokio::test]
+    async fn unsupported_operations_return_errors() {
+        let admin = admin_ext_impl();
+        let result = admin
+            .reset_master_flush_offset(CheetahString::from_static_str("127.0.0.1:10911"), 0)
+            .await;
+        assert!(matches!(
+            result,
+            Err(ToolsError::UnsupportedOperation(
+                "reset_master_flush_offset"
+            ))
+        ));
     }
 }
@@ -32,6 +32,7 @@ use rocketmq_remoting::protocol::body::consumer_running_info::ConsumerRunningInf
 use rocketmq_remoting::protocol::body::group_list::GroupList;
 use rocketmq_remoting::protocol::body::kv_table::KVTable;
 use rocketmq_remoting::protocol::body::producer_connection::ProducerConnection;
+use rocketmq_remoting::protocol::body::query_message_by_condition_request_body::QueryMessageByConditionRequestBody;
 use rocketmq_remoting::protocol::body::topic::topic_list::TopicList;
 use rocketmq_remoting::protocol::body::topic_info_wrapper::TopicConfigSerializeWrapper;
 use rocketmq_remoting::protocol::heartbeat::subscription_data::SubscriptionData;
@@ -40,13 +41,14 @@ use rocketmq_remoting::protocol::static_topic::topic_queue_mapping_detail::Topic
 use rocketmq_remoting::protocol::subscription::subscription_group_config::SubscriptionGroupConfig;
 
 use crate::admin::common::admin_tool_result::AdminToolResult;
+use crate::admin::common::query_message_page::QueryMessagePage;
 use crate::Result;
 
 #[cfg(feature = "sync")]
 #[allow(dead_code)]
 pub trait MQAdminExt {
-    fn start(&self) -> Result<()>;
-    fn shutdown(&self);
+    fn start(&mut self) -> Result<()>;
+    fn shutdown(&mut self);
     fn add_broker_to_container(
         &self,
         broker_container_addr: CheetahString,
@@ -479,6 +481,14 @@ pub trait MQAdminExt {
         force: bool,
     ) -> Result<()>;
 
+    /// Queries one page of messages from the index of a broker by keys, unique key, tags and
+    /// store time range. The cursor in `request_body` is the `next_cursor` of the previous page.
+    fn query_message(
+        &self,
+        broker_addr: CheetahString,
+        request_body: QueryMessageByConditionRequestBody,
+    ) -> Result<QueryMessagePage>;
+
     /*fn update_and_get_group_read_forbidden(
         &self,
         broker_addr: CheetahString,
@@ -487,13 +497,6 @@ pub trait MQAdminExt {
         readable: Option<bool>,
     ) -> Result<GroupForbidden>;
 
-    fn query_message(
-        &self,
-        cluster_name: CheetahString,
-        topic: CheetahString,
-        msg_id: CheetahString,
-    ) -> Result<MessageExt>;
-
     fn get_broker_ha_status(&self, broker_addr: CheetahString) -> Result<HARuntimeInfo>;
 
     fn get_in_sync_state_data(
@@ -18,9 +18,12 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 
 use cheetah_string::CheetahString;
+use rocketmq_client_rust::consumer::pull_result::PullResult;
+use rocketmq_client_rust::trace::trace_view::TraceView;
 use rocketmq_common::common::base::plain_access_config::PlainAccessConfig;
 use rocketmq_common::common::config::TopicConfig;
 use rocketmq_common::common::message::message_enum::MessageRequestMode;
+use rocketmq_common::common::message::message_ext::MessageExt;
 use rocketmq_common::common::message::message_queue::MessageQueue;
 use rocketmq_remoting::protocol::admin::consume_stats::ConsumeStats;
 use rocketmq_remoting::protocol::admin::topic_stats_table::TopicStatsTable;
@@ -31,6 +34,7 @@ use rocketmq_remoting::protocol::body::consumer_running_info::ConsumerRunningInf
 use rocketmq_remoting::protocol::body::group_list::GroupList;
 use rocketmq_remoting::protocol::body::kv_table::KVTable;
 use rocketmq_remoting::protocol::body::producer_connection::ProducerConnection;
+use rocketmq_remoting::protocol::body::query_message_by_condition_request_body::QueryMessageByConditionRequestBody;
 use rocketmq_remoting::protocol::body::topic::topic_list::TopicList;
 use rocketmq_remoting::protocol::body::topic_info_wrapper::TopicConfigSerializeWrapper;
 use rocketmq_remoting::protocol::heartbeat::subscription_data::SubscriptionData;
@@ -38,15 +42,17 @@ use rocketmq_remoting::protocol::route::topic_route_data::TopicRouteData;
 use rocketmq_remoting::protocol::static_topic::topic_queue_mapping_detail::TopicQueueMappingDetail;
 use rocketmq_remoting::protocol::subscription::subscription_group_config::SubscriptionGroupConfig;
 
+use crate::admin::api::message_track::MessageTrack;
 use crate::admin::common::admin_tool_result::AdminToolResult;
+use crate::admin::common::query_message_page::QueryMessagePage;
 use crate::Result;
 
 #[cfg(feature = "async")]
 #[allow(dead_code)]
 #[trait_variant::make(MQAdminExt: Send)]
 pub trait MQAdminExtLocal: Sync {
-    async fn start(&self) -> Result<()>;
-    async fn shutdown(&self);
+    async fn start(&mut self) -> Result<()>;
+    async fn shutdown(&mut self);
     async fn add_broker_to_container(
         &self,
         broker_container_addr: CheetahString,
@@ -364,15 +370,46 @@ pub trait MQAdminExtLocal: Sync {
         msg_id: CheetahString,
     ) -> Result<ConsumeMessageDirectlyResult>;
 
-    /*async fn message_track_detail(
+    /// Looks a message up by its offset message id or by its unique client message id.
+    async fn view_message(&self, topic: CheetahString, msg_id: CheetahString)
+        -> Result<MessageExt>;
+
+    /// Queries the messages of a topic whose keys contain `key` and which were stored between
+    /// `begin` and `end`, in milliseconds, at most `max_num` from each broker.
+    async fn query_message_by_key(
         &self,
-        msg: MessageExt,
-    ) -> Result<Vec<MessageTrack>>;
+        topic: CheetahString,
+        key: CheetahString,
+        max_num: i32,
+        begin: i64,
+        end: i64,
+    ) -> Result<Vec<MessageExt>>;
+
+    /// Pulls at most `max_nums` messages of a queue starting at `offset` without committing
+    /// any consume offset.
+    async fn pull_message_by_offset(
+        &self,
+        mq: MessageQueue,
+        offset: i64,
+        max_nums: i32,
+    ) -> Result<PullResult>;
+
+    /// Reports, for every consumer group of the topic of `msg`, whether it consumed the
+    /// message.
+    async fn message_track_detail(&self, msg: MessageExt) -> Result<Vec<MessageTrack>>;
 
     async fn message_track_detail_concurrent(
         &self,
         msg: MessageExt,
-    ) -> AdminToolResult<Vec<MessageTrack>>;*/
+    ) -> AdminToolResult<Vec<MessageTrack>>;
+
+    /// Queries the trace records of the message whose unique id is `msg_id` from
+    /// `trace_topic`, ordered by time.
+    async fn query_message_trace_by_id(
+        &self,
+        trace_topic: CheetahString,
+        msg_id: CheetahString,
+    ) -> Result<Vec<TraceView>>;
 
     async fn clone_group_offset(
         &self,
@@ -494,6 +531,14 @@ pub trait MQAdminExtLocal: Sync {
         force: bool,
     ) -> Result<()>;
 
+    /// Queries one page of messages from the index of a broker by keys, unique key, tags and
+    /// store time range. The cursor in `request_body` is the `next_cursor` of the previous page.
+    async fn query_message(
+        &self,
+        broker_addr: CheetahString,
+        request_body: QueryMessageByConditionRequestBody,
+    ) -> Result<QueryMessagePage>;
+
     /*async fn update_and_get_group_read_forbidden(
         &self,
         broker_addr: CheetahString,
@@ -502,13 +547,6 @@ pub trait MQAdminExtLocal: Sync {
         readable: Option<bool>,
     ) -> Result<GroupForbidden>;
 
-    async fn query_message(
-        &self,
-        cluster_name: CheetahString,
-        topic: CheetahString,
-        msg_id: CheetahString,
-    ) -> Result<MessageExt>;
-
     async fn get_broker_ha_status(&self, broker_addr: CheetahString) -> Result<HARuntimeInfo>;
 
     async fn get_in_sync_state_data(
@@ -32,4 +32,10 @@ pub enum ToolsError {
     UnsupportedEncodingError,
     #[error("Operation interrupted.")]
     InterruptedError,
+    #[error("The admin operation {0} is not supported yet.")]
+    UnsupportedOperation(&'static str),
+    #[error("The admin client is not started.")]
+    NotStarted,
+    #[error("Common error occurred. {0}")]
+    CommonError(#[from] rocketmq_common::error::Error),
 }
@@ -21,4 +21,12 @@ use strum::Display;
 #[derive(Debug, Clone, PartialEq, Serialize, Display, Deserialize)]
 pub enum Action {
     Quit,
+    Refresh,
+    NextTab,
+    PreviousTab,
+    SelectTab(usize),
+    SelectNext,
+    SelectPrevious,
+    FocusSearch,
+    ResetOffset,
 }
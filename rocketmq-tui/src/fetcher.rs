@@ -0,0 +1,765 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::constant::PermName;
+use rocketmq_common::common::message::message_client_id_setter::MessageClientIDSetter;
+use rocketmq_common::common::message::message_ext::MessageExt;
+use rocketmq_common::common::message::MessageTrait;
+use rocketmq_common::common::mix_all;
+use rocketmq_common::UtilAll::bytes_to_string;
+use rocketmq_common::UtilAll::time_millis_to_human_string2;
+use rocketmq_remoting::protocol::admin::consume_stats::ConsumeStats;
+use rocketmq_remoting::protocol::admin::topic_stats_table::TopicStatsTable;
+use rocketmq_remoting::protocol::route::topic_route_data::TopicRouteData;
+use rocketmq_tools::admin::default_mq_admin_ext::DefaultMQAdminExt;
+use rocketmq_tools::admin::mq_admin_ext_async::MQAdminExt;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Upper bound of the messages returned by a key search.
+const MAX_SEARCH_MESSAGES: i32 = 64;
+
+/// A query the dashboard sends to the admin client, answered by a [`Response`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Request {
+    Cluster,
+    Topics,
+    TopicDetail(String),
+    GroupDetail(String),
+    SearchMessage {
+        topic: String,
+        text: String,
+    },
+    ResetOffset {
+        group: String,
+        topic: String,
+        timestamp: u64,
+    },
+}
+
+/// The kind of a request, at most one request of every kind is in flight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum RequestKind {
+    Cluster,
+    Topics,
+    TopicDetail,
+    GroupDetail,
+    SearchMessage,
+    ResetOffset,
+}
+
+impl Request {
+    pub fn kind(&self) -> RequestKind {
+        match self {
+            Request::Cluster => RequestKind::Cluster,
+            Request::Topics => RequestKind::Topics,
+            Request::TopicDetail(_) => RequestKind::TopicDetail,
+            Request::GroupDetail(_) => RequestKind::GroupDetail,
+            Request::SearchMessage { .. } => RequestKind::SearchMessage,
+            Request::ResetOffset { .. } => RequestKind::ResetOffset,
+        }
+    }
+}
+
+pub(crate) type FetchResult<T> = Result<T, String>;
+
+pub(crate) enum Response {
+    Cluster(FetchResult<Vec<BrokerNode>>),
+    Topics(FetchResult<Vec<String>>),
+    TopicDetail(String, FetchResult<TopicDetail>),
+    GroupDetail(String, FetchResult<GroupDetail>),
+    SearchMessage(FetchResult<Vec<MessageEntry>>),
+    ResetOffset(FetchResult<String>),
+}
+
+impl Response {
+    pub fn kind(&self) -> RequestKind {
+        match self {
+            Response::Cluster(_) => RequestKind::Cluster,
+            Response::Topics(_) => RequestKind::Topics,
+            Response::TopicDetail(..) => RequestKind::TopicDetail,
+            Response::GroupDetail(..) => RequestKind::GroupDetail,
+            Response::SearchMessage(_) => RequestKind::SearchMessage,
+            Response::ResetOffset(_) => RequestKind::ResetOffset,
+        }
+    }
+}
+
+pub(crate) struct BrokerNode {
+    pub cluster: String,
+    pub broker_name: String,
+    pub broker_id: u64,
+    pub addr: String,
+    pub version: String,
+    pub put_tps: String,
+    pub get_tps: String,
+    /// The runtime info reported by the broker, empty if it did not answer.
+    pub runtime_info: BTreeMap<String, String>,
+}
+
+pub(crate) struct RouteRow {
+    pub broker_name: String,
+    pub master_addr: String,
+    pub read_queue_nums: u32,
+    pub write_queue_nums: u32,
+    pub perm: String,
+}
+
+pub(crate) struct QueueOffsetRow {
+    pub broker_name: String,
+    pub queue_id: i32,
+    pub min_offset: i64,
+    pub max_offset: i64,
+    pub last_update: String,
+}
+
+pub(crate) struct TopicDetail {
+    pub routes: Vec<RouteRow>,
+    pub queues: Vec<QueueOffsetRow>,
+    /// Groups consuming the topic, it stays empty if the brokers could not tell.
+    pub consumer_groups: Vec<String>,
+}
+
+impl TopicDetail {
+    /// Sum of the max offsets of every queue, the TPS is derived from its growth.
+    pub fn max_offset_sum(&self) -> i64 {
+        self.queues.iter().map(|queue| queue.max_offset).sum()
+    }
+
+    pub fn message_count(&self) -> i64 {
+        self.queues
+            .iter()
+            .map(|queue| queue.max_offset - queue.min_offset)
+            .sum()
+    }
+}
+
+pub(crate) struct LagRow {
+    pub topic: String,
+    pub broker_name: String,
+    pub queue_id: i32,
+    pub broker_offset: i64,
+    pub consumer_offset: i64,
+    pub lag: i64,
+    pub last_timestamp: String,
+}
+
+pub(crate) struct ClientRow {
+    pub client_id: String,
+    pub client_addr: String,
+    pub language: String,
+    pub version: i32,
+}
+
+pub(crate) struct GroupDetail {
+    pub consume_tps: f64,
+    pub diff_total: i64,
+    pub queues: Vec<LagRow>,
+    /// Online clients, the consume type and model are only known while clients are online.
+    pub clients: Vec<ClientRow>,
+    pub consume_type: String,
+    pub message_model: String,
+}
+
+impl GroupDetail {
+    /// Topics of the group's queues except its retry topic, sorted and deduplicated.
+    pub fn topics(&self) -> Vec<String> {
+        let mut topics: Vec<_> = self
+            .queues
+            .iter()
+            .filter(|row| !row.topic.starts_with(mix_all::RETRY_GROUP_TOPIC_PREFIX))
+            .map(|row| row.topic.clone())
+            .collect();
+        topics.sort();
+        topics.dedup();
+        topics
+    }
+}
+
+pub(crate) struct MessageEntry {
+    pub topic: String,
+    pub offset_msg_id: String,
+    pub unique_msg_id: String,
+    pub store_host: String,
+    pub queue_id: i32,
+    pub queue_offset: i64,
+    pub tags: String,
+    pub keys: String,
+    pub born_timestamp: String,
+    pub store_timestamp: String,
+    pub reconsume_times: i32,
+    pub properties: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl MessageEntry {
+    fn new(message: &MessageExt) -> Self {
+        let body = message
+            .get_body()
+            .map(|body| body.as_ref())
+            .unwrap_or_default();
+        let body = match std::str::from_utf8(body) {
+            Ok(text) => text.to_string(),
+            Err(_) => bytes_to_string(body),
+        };
+        let mut properties: Vec<_> = message
+            .properties()
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        properties.sort();
+        MessageEntry {
+            topic: message.topic().to_string(),
+            offset_msg_id: message.msg_id().to_string(),
+            unique_msg_id: MessageClientIDSetter::get_uniq_id(message)
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+            store_host: message.store_host().to_string(),
+            queue_id: message.queue_id(),
+            queue_offset: message.queue_offset(),
+            tags: message
+                .get_tags()
+                .map(|tags| tags.to_string())
+                .unwrap_or_default(),
+            keys: message
+                .get_keys()
+                .map(|keys| keys.to_string())
+                .unwrap_or_default(),
+            born_timestamp: format_timestamp(message.born_timestamp()),
+            store_timestamp: format_timestamp(message.store_timestamp()),
+            reconsume_times: message.reconsume_times(),
+            properties,
+            body,
+        }
+    }
+}
+
+/// Runs the dashboard requests in the background and sends the responses back to the app.
+pub(crate) struct Fetcher {
+    admin: Arc<DefaultMQAdminExt>,
+    responses: UnboundedSender<Response>,
+}
+
+impl Fetcher {
+    pub fn new(admin: Arc<DefaultMQAdminExt>, responses: UnboundedSender<Response>) -> Self {
+        Self { admin, responses }
+    }
+
+    pub fn fetch(&self, request: Request) {
+        let admin = self.admin.clone();
+        let responses = self.responses.clone();
+        tokio::spawn(async move {
+            let response = execute(&admin, request).await;
+            let _ = responses.send(response);
+        });
+    }
+}
+
+/// Creates and starts the admin client used by the dashboard.
+pub(crate) async fn start_admin(namesrv: Option<&str>) -> anyhow::Result<DefaultMQAdminExt> {
+    let mut admin = DefaultMQAdminExt::new();
+    if let Some(namesrv) = namesrv {
+        admin.set_namesrv_addr(namesrv);
+    }
+    admin.set_instance_name(format!("rocketmq-tui-{}", std::process::id()));
+    MQAdminExt::start(&mut admin).await?;
+    Ok(admin)
+}
+
+async fn execute(admin: &DefaultMQAdminExt, request: Request) -> Response {
+    match request {
+        Request::Cluster => Response::Cluster(fetch_brokers(admin).await),
+        Request::Topics => Response::Topics(
+            admin
+                .fetch_all_topic_list()
+                .await
+                .map(|topic_list| {
+                    let mut topics: Vec<_> = topic_list
+                        .topic_list
+                        .into_iter()
+                        .map(|topic| topic.to_string())
+                        .collect();
+                    topics.sort();
+                    topics
+                })
+                .map_err(|e| e.to_string()),
+        ),
+        Request::TopicDetail(topic) => {
+            let detail = fetch_topic_detail(admin, &topic).await;
+            Response::TopicDetail(topic, detail)
+        }
+        Request::GroupDetail(group) => {
+            let detail = fetch_group_detail(admin, &group).await;
+            Response::GroupDetail(group, detail)
+        }
+        Request::SearchMessage { topic, text } => {
+            Response::SearchMessage(search_message(admin, &topic, &text).await)
+        }
+        Request::ResetOffset {
+            group,
+            topic,
+            timestamp,
+        } => Response::ResetOffset(
+            admin
+                .reset_offset_by_timestamp(
+                    None,
+                    topic.as_str().into(),
+                    group.as_str().into(),
+                    timestamp,
+                    true,
+                )
+                .await
+                .map(|offset_table| {
+                    let target = match timestamp {
+                        0 => "the earliest offset".to_string(),
+                        timestamp => format_timestamp(timestamp as i64),
+                    };
+                    format!(
+                        "Reset {} queues of group {} on topic {} to {}",
+                        offset_table.len(),
+                        group,
+                        topic,
+                        target
+                    )
+                })
+                .map_err(|e| e.to_string()),
+        ),
+    }
+}
+
+/// Walks the cluster info, the load columns stay empty for brokers that did not answer.
+async fn fetch_brokers(admin: &DefaultMQAdminExt) -> FetchResult<Vec<BrokerNode>> {
+    let cluster_info = admin
+        .examine_broker_cluster_info()
+        .await
+        .map_err(|e| e.to_string())?;
+    let cluster_addr_table: BTreeMap<_, _> = cluster_info
+        .cluster_addr_table
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    let broker_addr_table = cluster_info.broker_addr_table.unwrap_or_default();
+
+    let mut brokers = Vec::new();
+    for (cluster, broker_names) in cluster_addr_table {
+        let mut broker_names: Vec<_> = broker_names.into_iter().collect();
+        broker_names.sort();
+        for broker_name in broker_names {
+            let Some(broker_data) = broker_addr_table.get(&broker_name) else {
+                continue;
+            };
+            let broker_addrs: BTreeMap<_, _> = broker_data.broker_addrs().iter().collect();
+            for (broker_id, addr) in broker_addrs {
+                let runtime_info: BTreeMap<_, _> = admin
+                    .fetch_broker_runtime_stats(addr.clone())
+                    .await
+                    .map(|kv_table| kv_table.table)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect();
+                brokers.push(BrokerNode {
+                    cluster: cluster.to_string(),
+                    broker_name: broker_name.to_string(),
+                    broker_id: *broker_id,
+                    addr: addr.to_string(),
+                    version: runtime_info
+                        .get("brokerVersionDesc")
+                        .cloned()
+                        .unwrap_or_default(),
+                    put_tps: format_tps(runtime_info.get("putTps")),
+                    // Java brokers spell the key without the second `r`.
+                    get_tps: format_tps(
+                        runtime_info
+                            .get("getTransferredTps")
+                            .or_else(|| runtime_info.get("getTransferedTps")),
+                    ),
+                    runtime_info,
+                });
+            }
+        }
+    }
+    Ok(brokers)
+}
+
+async fn fetch_topic_detail(admin: &DefaultMQAdminExt, topic: &str) -> FetchResult<TopicDetail> {
+    let topic = CheetahString::from(topic);
+    let route = admin
+        .examine_topic_route_info(topic.clone())
+        .await
+        .map_err(|e| e.to_string())?;
+    let routes = route_rows(&route);
+
+    let topic_stats = admin
+        .examine_topic_stats(topic.clone(), None)
+        .await
+        .map_err(|e| e.to_string())?;
+    let queues = queue_offset_rows(&topic_stats);
+
+    let mut consumer_groups: Vec<_> = admin
+        .query_topic_consume_by_who(topic)
+        .await
+        .map(|group_list| {
+            group_list
+                .group_list
+                .into_iter()
+                .map(|group| group.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    consumer_groups.sort();
+    consumer_groups.dedup();
+
+    Ok(TopicDetail {
+        routes,
+        queues,
+        consumer_groups,
+    })
+}
+
+async fn fetch_group_detail(admin: &DefaultMQAdminExt, group: &str) -> FetchResult<GroupDetail> {
+    let consume_stats = admin
+        .examine_consume_stats(group.into(), None, None, None, None)
+        .await
+        .map_err(|e| e.to_string())?;
+    let queues = lag_rows(&consume_stats);
+
+    // A group without online clients has no connection info, it is not an error here.
+    let connection = admin
+        .examine_consumer_connection_info(group.into(), None)
+        .await
+        .ok();
+    let mut clients: Vec<_> = connection
+        .as_ref()
+        .map(|connection| {
+            connection
+                .get_connection_set()
+                .into_iter()
+                .map(|client| ClientRow {
+                    client_id: client.get_client_id().to_string(),
+                    client_addr: client.get_client_addr().to_string(),
+                    language: format!("{:?}", client.get_language()),
+                    version: client.get_version(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    clients.sort_by(|a, b| a.client_id.cmp(&b.client_id));
+
+    Ok(GroupDetail {
+        consume_tps: consume_stats.get_consume_tps(),
+        diff_total: consume_stats.compute_total_diff(),
+        queues,
+        clients,
+        consume_type: connection
+            .as_ref()
+            .map(|connection| connection.get_consume_type().name().to_string())
+            .unwrap_or_default(),
+        message_model: connection
+            .as_ref()
+            .map(|connection| connection.get_message_model().to_string())
+            .unwrap_or_default(),
+    })
+}
+
+/// One row per broker serving the topic, with the address of its master if it is known.
+fn route_rows(route: &TopicRouteData) -> Vec<RouteRow> {
+    let mut routes: Vec<_> = route
+        .queue_datas
+        .iter()
+        .map(|queue_data| RouteRow {
+            broker_name: queue_data.broker_name.to_string(),
+            master_addr: route
+                .broker_datas
+                .iter()
+                .find(|broker_data| broker_data.broker_name() == &queue_data.broker_name)
+                .and_then(|broker_data| broker_data.broker_addrs().get(&mix_all::MASTER_ID))
+                .map(|addr| addr.to_string())
+                .unwrap_or_default(),
+            read_queue_nums: queue_data.read_queue_nums,
+            write_queue_nums: queue_data.write_queue_nums,
+            perm: PermName::perm2string(queue_data.perm),
+        })
+        .collect();
+    routes.sort_by(|a, b| a.broker_name.cmp(&b.broker_name));
+    routes
+}
+
+fn queue_offset_rows(topic_stats: &TopicStatsTable) -> Vec<QueueOffsetRow> {
+    let mut queues: Vec<_> = topic_stats
+        .get_offset_table()
+        .into_iter()
+        .map(|(mq, offset)| QueueOffsetRow {
+            broker_name: mq.get_broker_name().to_string(),
+            queue_id: mq.get_queue_id(),
+            min_offset: offset.get_min_offset(),
+            max_offset: offset.get_max_offset(),
+            last_update: format_timestamp(offset.get_last_update_timestamp()),
+        })
+        .collect();
+    queues.sort_by(|a, b| (&a.broker_name, a.queue_id).cmp(&(&b.broker_name, b.queue_id)));
+    queues
+}
+
+fn lag_rows(consume_stats: &ConsumeStats) -> Vec<LagRow> {
+    let mut queues: Vec<_> = consume_stats
+        .get_offset_table()
+        .into_iter()
+        .map(|(mq, offset)| LagRow {
+            topic: mq.get_topic().to_string(),
+            broker_name: mq.get_broker_name().to_string(),
+            queue_id: mq.get_queue_id(),
+            broker_offset: offset.get_broker_offset(),
+            consumer_offset: offset.get_consumer_offset(),
+            lag: offset.get_broker_offset() - offset.get_consumer_offset(),
+            last_timestamp: format_timestamp(offset.get_last_timestamp()),
+        })
+        .collect();
+    queues.sort_by(|a, b| {
+        (&a.topic, &a.broker_name, a.queue_id).cmp(&(&b.topic, &b.broker_name, b.queue_id))
+    });
+    queues
+}
+
+/// Looks `text` up as a message id first and falls back to a key search.
+async fn search_message(
+    admin: &DefaultMQAdminExt,
+    topic: &str,
+    text: &str,
+) -> FetchResult<Vec<MessageEntry>> {
+    if let Ok(message) = admin.view_message(topic.into(), text.into()).await {
+        return Ok(vec![MessageEntry::new(&message)]);
+    }
+    let mut messages = admin
+        .query_message_by_key(topic.into(), text.into(), MAX_SEARCH_MESSAGES, 0, i64::MAX)
+        .await
+        .map_err(|e| e.to_string())?;
+    messages.sort_by_key(|message| message.store_timestamp());
+    Ok(messages.iter().map(MessageEntry::new).collect())
+}
+
+/// Runtime TPS values hold the averages of the last 10s, 1m and 10m, the first one is shown.
+fn format_tps(value: Option<&String>) -> String {
+    value
+        .and_then(|value| value.split_whitespace().next())
+        .and_then(|tps| tps.parse::<f64>().ok())
+        .map(|tps| format!("{:.2}", tps))
+        .unwrap_or_default()
+}
+
+pub(crate) fn format_timestamp(timestamp: i64) -> String {
+    if timestamp > 0 {
+        time_millis_to_human_string2(timestamp)
+    } else {
+        String::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use bytes::Bytes;
+    use rocketmq_common::common::message::message_queue::MessageQueue;
+    use rocketmq_common::common::message::MessageConst;
+    use rocketmq_remoting::protocol::admin::offset_wrapper::OffsetWrapper;
+    use rocketmq_remoting::protocol::admin::topic_offset::TopicOffset;
+    use rocketmq_remoting::protocol::route::route_data_view::BrokerData;
+    use rocketmq_remoting::protocol::route::route_data_view::QueueData;
+
+    use super::*;
+
+    fn topic_offset(min_offset: i64, max_offset: i64) -> TopicOffset {
+        let mut offset = TopicOffset::new();
+        offset.set_min_offset(min_offset);
+        offset.set_max_offset(max_offset);
+        offset
+    }
+
+    fn offset_wrapper(broker_offset: i64, consumer_offset: i64) -> OffsetWrapper {
+        let mut offset = OffsetWrapper::new();
+        offset.set_broker_offset(broker_offset);
+        offset.set_consumer_offset(consumer_offset);
+        offset
+    }
+
+    fn lag_row(topic: &str) -> LagRow {
+        LagRow {
+            topic: topic.to_string(),
+            broker_name: "broker-a".to_string(),
+            queue_id: 0,
+            broker_offset: 0,
+            consumer_offset: 0,
+            lag: 0,
+            last_timestamp: String::new(),
+        }
+    }
+
+    #[test]
+    fn route_rows_are_sorted_and_point_at_the_master() {
+        let route = TopicRouteData {
+            queue_datas: vec![
+                QueueData::new("broker-b".into(), 4, 4, 6, 0),
+                QueueData::new("broker-a".into(), 8, 8, 4, 0),
+            ],
+            broker_datas: vec![BrokerData::new(
+                "DefaultCluster".into(),
+                "broker-a".into(),
+                HashMap::from([
+                    (mix_all::MASTER_ID, "127.0.0.1:10911".into()),
+                    (1, "127.0.0.1:10921".into()),
+                ]),
+                None,
+            )],
+            ..Default::default()
+        };
+        let rows = route_rows(&route);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].broker_name, "broker-a");
+        assert_eq!(rows[0].master_addr, "127.0.0.1:10911");
+        assert_eq!(rows[0].read_queue_nums, 8);
+        assert_eq!(rows[0].perm, PermName::perm2string(4));
+        // a broker without broker data has no known master
+        assert_eq!(rows[1].broker_name, "broker-b");
+        assert_eq!(rows[1].master_addr, "");
+    }
+
+    #[test]
+    fn queue_offset_rows_are_sorted_and_summed() {
+        let mut topic_stats = TopicStatsTable::new();
+        topic_stats.set_offset_table(HashMap::from([
+            (
+                MessageQueue::from_parts("TopicTest", "broker-b", 0),
+                topic_offset(0, 10),
+            ),
+            (
+                MessageQueue::from_parts("TopicTest", "broker-a", 1),
+                topic_offset(5, 20),
+            ),
+            (
+                MessageQueue::from_parts("TopicTest", "broker-a", 0),
+                topic_offset(2, 7),
+            ),
+        ]));
+        let queues = queue_offset_rows(&topic_stats);
+        let order: Vec<_> = queues
+            .iter()
+            .map(|queue| (queue.broker_name.as_str(), queue.queue_id))
+            .collect();
+        assert_eq!(order, [("broker-a", 0), ("broker-a", 1), ("broker-b", 0)]);
+        assert_eq!(queues[0].last_update, "");
+
+        let detail = TopicDetail {
+            routes: vec![],
+            queues,
+            consumer_groups: vec![],
+        };
+        assert_eq!(detail.max_offset_sum(), 37);
+        assert_eq!(detail.message_count(), 30);
+    }
+
+    #[test]
+    fn lag_rows_compute_the_lag_of_every_queue() {
+        let mut consume_stats = ConsumeStats::new();
+        consume_stats.set_offset_table(HashMap::from([
+            (
+                MessageQueue::from_parts("TopicB", "broker-a", 0),
+                offset_wrapper(100, 40),
+            ),
+            (
+                MessageQueue::from_parts("TopicA", "broker-a", 0),
+                offset_wrapper(10, 10),
+            ),
+        ]));
+        let rows = lag_rows(&consume_stats);
+        assert_eq!(rows[0].topic, "TopicA");
+        assert_eq!(rows[0].lag, 0);
+        assert_eq!(rows[1].topic, "TopicB");
+        assert_eq!(rows[1].lag, 60);
+    }
+
+    #[test]
+    fn group_topics_skip_the_retry_topic() {
+        let detail = GroupDetail {
+            consume_tps: 0.0,
+            diff_total: 0,
+            queues: vec![
+                lag_row("TopicB"),
+                lag_row("%RETRY%group"),
+                lag_row("TopicA"),
+                lag_row("TopicB"),
+            ],
+            clients: vec![],
+            consume_type: String::new(),
+            message_model: String::new(),
+        };
+        assert_eq!(detail.topics(), ["TopicA", "TopicB"]);
+    }
+
+    #[test]
+    fn message_entries_render_bodies_and_sort_properties() {
+        let mut message = MessageExt::default();
+        message.set_topic("TopicTest".into());
+        message.set_body(Bytes::from_static(&[0xff, 0x01]));
+        message.put_property("b".into(), "2".into());
+        message.put_property("a".into(), "1".into());
+        message.put_property(
+            MessageConst::PROPERTY_UNIQ_CLIENT_MESSAGE_ID_KEYIDX.into(),
+            "7F0000010D6C18B4AAC2764F2D6C0000".into(),
+        );
+        let entry = MessageEntry::new(&message);
+        assert_eq!(entry.topic, "TopicTest");
+        assert_eq!(entry.body, "FF01");
+        assert_eq!(entry.unique_msg_id, "7F0000010D6C18B4AAC2764F2D6C0000");
+        let keys: Vec<_> = entry
+            .properties
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect();
+        assert_eq!(keys, ["UNIQ_KEY", "a", "b"]);
+        assert_eq!(entry.born_timestamp, "");
+
+        message.set_body(Bytes::from_static(b"hello"));
+        assert_eq!(MessageEntry::new(&message).body, "hello");
+    }
+
+    #[test]
+    fn formats_the_latest_tps_average() {
+        assert_eq!(format_tps(Some(&"3.14159 2.0 1.0".to_string())), "3.14");
+        assert_eq!(format_tps(Some(&"n/a".to_string())), "");
+        assert_eq!(format_tps(None), "");
+    }
+
+    #[test]
+    fn responses_answer_requests_of_the_same_kind() {
+        let request = Request::SearchMessage {
+            topic: "TopicTest".to_string(),
+            text: "key".to_string(),
+        };
+        assert_eq!(request.kind(), RequestKind::SearchMessage);
+        assert_eq!(
+            Response::SearchMessage(Ok(vec![])).kind(),
+            RequestKind::SearchMessage
+        );
+        assert_eq!(
+            Request::GroupDetail("group".to_string()).kind(),
+            Response::GroupDetail("group".to_string(), Err(String::new())).kind()
+        );
+    }
+}
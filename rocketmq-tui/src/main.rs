@@ -19,17 +19,62 @@
 #![allow(unused_variables)]
 
 mod action;
+mod fetcher;
 mod rocketmq_tui_app;
 mod ui;
 
+use std::sync::Arc;
+use std::time::Duration;
+
+use clap::Parser;
 use rocketmq_rust::rocketmq;
+use rocketmq_tools::admin::mq_admin_ext_async::MQAdminExt;
 
+use crate::fetcher::Fetcher;
 use crate::rocketmq_tui_app::RocketmqTuiApp;
 
+#[derive(Parser, Debug)]
+#[command(name = "rocketmq-tui", version, about = "RocketMQ terminal dashboard")]
+struct TuiArgs {
+    #[arg(
+        short = 'n',
+        long,
+        value_name = "NAMESRV",
+        help = "name server address list, eg: '192.168.0.1:9876;192.168.0.2:9876', defaults to \
+                the NAMESRV_ADDR environment variable"
+    )]
+    namesrv: Option<String>,
+
+    #[arg(
+        short = 'r',
+        long,
+        value_name = "SECONDS",
+        default_value_t = 5,
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "seconds between two refreshes of the current tab"
+    )]
+    refresh_interval: u64,
+}
+
 #[rocketmq::main]
 async fn main() -> anyhow::Result<()> {
+    let args = TuiArgs::parse();
+    // Connect before taking over the terminal so that startup errors stay readable.
+    let admin = Arc::new(fetcher::start_admin(args.namesrv.as_deref()).await?);
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let app = RocketmqTuiApp::new(
+        Fetcher::new(admin.clone(), tx),
+        rx,
+        Duration::from_secs(args.refresh_interval),
+    );
+
     let terminal = ratatui::try_init()?;
-    let result = RocketmqTuiApp::default().run(terminal).await;
+    let result = app.run(terminal).await;
     ratatui::try_restore()?;
+
+    // Requests still in flight hold the admin client, shutting it down is best effort.
+    if let Ok(mut admin) = Arc::try_unwrap(admin) {
+        MQAdminExt::shutdown(&mut admin).await;
+    }
     result
 }
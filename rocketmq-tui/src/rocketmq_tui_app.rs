@@ -14,6 +14,7 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::collections::HashSet;
 use std::time::Duration;
 
 use ratatui::crossterm::event::Event;
@@ -21,26 +22,100 @@ use ratatui::crossterm::event::EventStream;
 use ratatui::crossterm::event::KeyCode;
 use ratatui::crossterm::event::KeyEventKind;
 use ratatui::layout::Constraint;
-use ratatui::layout::Direction;
 use ratatui::layout::Layout;
-use ratatui::widgets::Block;
+use ratatui::style::Color;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::Tabs;
 use ratatui::DefaultTerminal;
 use ratatui::Frame;
+use rocketmq_common::TimeUtils::get_current_millis;
+use tokio::sync::mpsc::UnboundedReceiver;
 use tokio_stream::StreamExt;
 
+use crate::action::Action;
+use crate::fetcher::format_timestamp;
+use crate::fetcher::Fetcher;
+use crate::fetcher::Request;
+use crate::fetcher::RequestKind;
+use crate::fetcher::Response;
+use crate::ui::bordered_block;
+use crate::ui::cluster_view::ClusterView;
+use crate::ui::group_view::GroupView;
+use crate::ui::header_style;
+use crate::ui::message_view::MessageView;
+use crate::ui::reset_offset_popup::PopupOutcome;
+use crate::ui::reset_offset_popup::ResetOffsetPopup;
 use crate::ui::search_input_widget::SearchInputWidget;
+use crate::ui::topic_view::TopicView;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tab {
+    Cluster,
+    Topics,
+    Groups,
+    Messages,
+}
+
+impl Tab {
+    const ALL: [Tab; 4] = [Tab::Cluster, Tab::Topics, Tab::Groups, Tab::Messages];
+
+    fn title(self) -> &'static str {
+        match self {
+            Tab::Cluster => "1 Cluster",
+            Tab::Topics => "2 Topics",
+            Tab::Groups => "3 Consumer Groups",
+            Tab::Messages => "4 Messages",
+        }
+    }
+
+    fn index(self) -> usize {
+        Tab::ALL.iter().position(|tab| *tab == self).unwrap_or(0)
+    }
+}
 
-#[derive(Default)]
 pub struct RocketmqTuiApp {
     should_quit: bool,
     search_input: SearchInputWidget,
+    fetcher: Fetcher,
+    responses: UnboundedReceiver<Response>,
+    refresh_interval: Duration,
+    /// Requests in flight, a request is not sent again until it is answered.
+    loading: HashSet<RequestKind>,
+    tab: Tab,
+    /// The status line and whether it reports an error.
+    status: Option<(String, bool)>,
+    last_refresh: Option<u64>,
+    cluster_view: ClusterView,
+    topic_view: TopicView,
+    group_view: GroupView,
+    message_view: MessageView,
+    popup: Option<ResetOffsetPopup>,
 }
 
 impl RocketmqTuiApp {
-    pub fn new() -> Self {
+    pub fn new(
+        fetcher: Fetcher,
+        responses: UnboundedReceiver<Response>,
+        refresh_interval: Duration,
+    ) -> Self {
         Self {
             should_quit: false,
             search_input: Default::default(),
+            fetcher,
+            responses,
+            refresh_interval,
+            loading: HashSet::new(),
+            tab: Tab::Cluster,
+            status: None,
+            last_refresh: None,
+            cluster_view: Default::default(),
+            topic_view: Default::default(),
+            group_view: Default::default(),
+            message_view: Default::default(),
+            popup: None,
         }
     }
 
@@ -59,98 +134,309 @@ impl RocketmqTuiApp {
     pub async fn run(mut self, mut terminal: DefaultTerminal) -> anyhow::Result<()> {
         let period = Duration::from_secs_f32(1.0 / Self::FRAMES_PER_SECOND);
         let mut interval = tokio::time::interval(period);
+        let mut refresh = tokio::time::interval(self.refresh_interval);
         let mut events = EventStream::new();
         while !self.should_quit {
             tokio::select! {
                 _ = interval.tick() => { terminal.draw(|frame| self.draw(frame))?; },
                 Some(Ok(event)) = events.next() => self.handle_event(&event),
+                _ = refresh.tick() => self.refresh(),
+                Some(response) = self.responses.recv() => self.handle_response(response),
             }
         }
         Ok(())
     }
 
     fn handle_event(&mut self, event: &Event) {
-        if let Event::Key(key) = event {
-            if key.kind == KeyEventKind::Press {
-                match key.code {
-                    KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
-                    _ => {}
+        let Event::Key(key) = event else {
+            return;
+        };
+        if key.kind != KeyEventKind::Press {
+            return;
+        }
+        if let Some(popup) = self.popup.as_mut() {
+            match popup.handle_key_event(key.code) {
+                PopupOutcome::Pending => {}
+                PopupOutcome::Cancelled => self.popup = None,
+                PopupOutcome::Confirmed(request) => {
+                    self.popup = None;
+                    self.set_status("Resetting consumer offset...", false);
+                    self.request(request);
                 }
             }
+            return;
         }
+        if self.search_input.is_focused() {
+            self.handle_search_key(key.code);
+            return;
+        }
+        let action = match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => Action::Quit,
+            KeyCode::Tab => Action::NextTab,
+            KeyCode::BackTab => Action::PreviousTab,
+            KeyCode::Char(c @ '1'..='4') => Action::SelectTab(c as usize - '1' as usize),
+            KeyCode::Down | KeyCode::Char('j') => Action::SelectNext,
+            KeyCode::Up | KeyCode::Char('k') => Action::SelectPrevious,
+            KeyCode::Char('r') => Action::Refresh,
+            KeyCode::Char('s') | KeyCode::Char('S') => Action::FocusSearch,
+            KeyCode::Char('o') if self.tab == Tab::Groups => Action::ResetOffset,
+            _ => return,
+        };
+        self.handle_action(action);
     }
 
-    fn draw(&self, frame: &mut Frame) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(
-                [
-                    Constraint::Percentage(5),
-                    Constraint::Percentage(92),
-                    Constraint::Percentage(3),
-                ]
-                .as_ref(),
-            )
-            .split(frame.area());
-
-        let search = chunks[0];
-        let middle = chunks[1];
-        let progress_bar = chunks[2];
-
-        let middle_chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
-            .split(middle);
-
-        let command_tree = middle_chunks[0];
-        let middle_right = middle_chunks[1];
-
-        let middle_right_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(
-                [
-                    Constraint::Percentage(50),
-                    Constraint::Percentage(15),
-                    Constraint::Percentage(35),
-                ]
-                .as_ref(),
-            )
-            .split(middle_right);
-
-        let command_detail = middle_right_chunks[0];
-        let command_args = middle_right_chunks[1];
-        let execute_command_result = middle_right_chunks[2];
-
-        frame.render_widget(&self.search_input, search);
-        frame.render_widget(
-            Block::default()
-                .borders(ratatui::widgets::Borders::ALL)
-                .title("Command Tree"),
-            command_tree,
-        );
-        frame.render_widget(
-            Block::default()
-                .borders(ratatui::widgets::Borders::ALL)
-                .title("Command Detail"),
-            command_detail,
-        );
-        frame.render_widget(
-            Block::default()
-                .borders(ratatui::widgets::Borders::ALL)
-                .title("Command Args"),
-            command_args,
-        );
-        frame.render_widget(
-            Block::default()
-                .borders(ratatui::widgets::Borders::ALL)
-                .title("Execute Command Result"),
-            execute_command_result,
-        );
+    fn handle_action(&mut self, action: Action) {
+        match action {
+            Action::Quit => self.quit(),
+            Action::Refresh => self.refresh(),
+            Action::NextTab => {
+                self.select_tab((self.tab.index() + 1) % Tab::ALL.len());
+            }
+            Action::PreviousTab => {
+                self.select_tab((self.tab.index() + Tab::ALL.len() - 1) % Tab::ALL.len());
+            }
+            Action::SelectTab(index) => self.select_tab(index),
+            Action::SelectNext | Action::SelectPrevious => {
+                let forward = action == Action::SelectNext;
+                match (self.tab, forward) {
+                    (Tab::Cluster, true) => self.cluster_view.select_next(),
+                    (Tab::Cluster, false) => self.cluster_view.select_previous(),
+                    (Tab::Topics, true) => self.topic_view.select_next(),
+                    (Tab::Topics, false) => self.topic_view.select_previous(),
+                    (Tab::Groups, true) => self.group_view.select_next(),
+                    (Tab::Groups, false) => self.group_view.select_previous(),
+                    (Tab::Messages, true) => self.message_view.select_next(),
+                    (Tab::Messages, false) => self.message_view.select_previous(),
+                }
+                self.fetch_selected_detail();
+            }
+            Action::FocusSearch => self.search_input.set_focus(true),
+            Action::ResetOffset => match self.group_view.selected() {
+                Some(group) => {
+                    let topics = self
+                        .group_view
+                        .selected_detail()
+                        .map(|detail| detail.topics())
+                        .unwrap_or_default();
+                    self.popup = Some(ResetOffsetPopup::new(group.to_string(), topics));
+                }
+                None => self.set_status("No consumer group selected", true),
+            },
+        }
+    }
+
+    fn handle_search_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Esc => self.search_input.set_focus(false),
+            KeyCode::Enter => {
+                self.search_input.set_focus(false);
+                if self.tab == Tab::Messages {
+                    self.search_message();
+                }
+            }
+            key => {
+                self.search_input.handle_key_event(key);
+                let filter = self.search_input.get_input().to_string();
+                match self.tab {
+                    Tab::Topics => {
+                        self.topic_view.set_filter(&filter);
+                        self.fetch_selected_detail();
+                    }
+                    Tab::Groups => {
+                        self.group_view.set_filter(&filter);
+                        self.fetch_selected_detail();
+                    }
+                    Tab::Cluster | Tab::Messages => {}
+                }
+            }
+        }
+    }
+
+    /// Searches the messages of `<topic> <message id or key>` typed into the search input.
+    fn search_message(&mut self) {
+        let input = self.search_input.get_input().trim().to_string();
+        let Some((topic, text)) = input
+            .split_once(char::is_whitespace)
+            .map(|(topic, text)| (topic.to_string(), text.trim().to_string()))
+        else {
+            self.set_status(
+                "Type '<topic> <message id or key>' to search messages",
+                true,
+            );
+            return;
+        };
+        self.message_view.set_query(input);
+        self.request(Request::SearchMessage { topic, text });
+    }
+
+    fn select_tab(&mut self, index: usize) {
+        let Some(tab) = Tab::ALL.get(index).copied() else {
+            return;
+        };
+        if tab == self.tab {
+            return;
+        }
+        self.tab = tab;
+        self.search_input.set_focus(false);
+        let input = match tab {
+            Tab::Topics => self.topic_view.filter().to_string(),
+            Tab::Groups => self.group_view.filter().to_string(),
+            Tab::Cluster | Tab::Messages => String::new(),
+        };
+        self.search_input.set_input(input);
+        self.refresh();
+    }
+
+    /// Fetches everything the current tab shows.
+    fn refresh(&mut self) {
+        match self.tab {
+            Tab::Cluster => self.request(Request::Cluster),
+            Tab::Topics | Tab::Groups => {
+                self.request(Request::Topics);
+                self.fetch_selected_detail();
+            }
+            Tab::Messages => return,
+        }
+        self.last_refresh = Some(get_current_millis());
+    }
+
+    fn fetch_selected_detail(&mut self) {
+        let request = match self.tab {
+            Tab::Topics => self
+                .topic_view
+                .selected()
+                .map(|topic| Request::TopicDetail(topic.to_string())),
+            Tab::Groups => self
+                .group_view
+                .selected()
+                .map(|group| Request::GroupDetail(group.to_string())),
+            Tab::Cluster | Tab::Messages => None,
+        };
+        if let Some(request) = request {
+            self.request(request);
+        }
+    }
+
+    fn request(&mut self, request: Request) {
+        if self.loading.insert(request.kind()) {
+            self.fetcher.fetch(request);
+        }
+    }
+
+    fn handle_response(&mut self, response: Response) {
+        self.loading.remove(&response.kind());
+        match response {
+            Response::Cluster(result) => match result {
+                Ok(brokers) => {
+                    self.cluster_view.set_brokers(brokers);
+                    self.clear_error();
+                }
+                Err(error) => self.set_status(&error, true),
+            },
+            Response::Topics(result) => match result {
+                Ok(topics) => {
+                    self.group_view.set_topics(&topics);
+                    self.topic_view.set_topics(topics);
+                    self.clear_error();
+                    // The selection may have appeared with the first topic list.
+                    self.fetch_selected_detail();
+                }
+                Err(error) => self.set_status(&error, true),
+            },
+            Response::TopicDetail(topic, result) => {
+                let selected = self.topic_view.selected() == Some(topic.as_str());
+                self.topic_view.set_detail(topic, result);
+                if !selected && self.tab == Tab::Topics {
+                    self.fetch_selected_detail();
+                }
+            }
+            Response::GroupDetail(group, result) => {
+                let selected = self.group_view.selected() == Some(group.as_str());
+                self.group_view.set_detail(group, result);
+                if !selected && self.tab == Tab::Groups {
+                    self.fetch_selected_detail();
+                }
+            }
+            Response::SearchMessage(result) => {
+                if let Err(error) = &result {
+                    self.set_status(error, true);
+                }
+                self.message_view.set_messages(result);
+            }
+            Response::ResetOffset(result) => match result {
+                Ok(message) => {
+                    self.set_status(&message, false);
+                    self.fetch_selected_detail();
+                }
+                Err(error) => self.set_status(&error, true),
+            },
+        }
+    }
+
+    fn set_status(&mut self, message: &str, is_error: bool) {
+        self.status = Some((message.to_string(), is_error));
+    }
+
+    /// Drops an error status once a later request succeeded, other messages stay visible.
+    fn clear_error(&mut self) {
+        if self.status.as_ref().is_some_and(|(_, is_error)| *is_error) {
+            self.status = None;
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [header_area, body_area, status_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Fill(1),
+            Constraint::Length(3),
+        ])
+        .areas(frame.area());
+        let [tabs_area, search_area] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(header_area);
+
+        let tabs = Tabs::new(Tab::ALL.iter().map(|tab| tab.title()))
+            .select(self.tab.index())
+            .highlight_style(header_style())
+            .block(bordered_block("RocketMQ Dashboard"));
+        frame.render_widget(tabs, tabs_area);
+        frame.render_widget(&self.search_input, search_area);
+
+        match self.tab {
+            Tab::Cluster => self.cluster_view.render(frame, body_area),
+            Tab::Topics => self.topic_view.render(frame, body_area),
+            Tab::Groups => self.group_view.render(frame, body_area),
+            Tab::Messages => self.message_view.render(frame, body_area),
+        }
+
+        let mut spans = Vec::new();
+        if !self.loading.is_empty() {
+            spans.push(Span::styled("[loading] ", Style::default().fg(Color::Cyan)));
+        }
+        match &self.status {
+            Some((message, true)) => spans.push(Span::styled(
+                message.as_str(),
+                Style::default().fg(Color::Red),
+            )),
+            Some((message, false)) => spans.push(Span::styled(
+                message.as_str(),
+                Style::default().fg(Color::Green),
+            )),
+            None => spans.push(Span::styled(
+                "q quit  Tab/1-4 switch tab  ↑/↓ select  r refresh  s search  o reset offset",
+                Style::default().fg(Color::DarkGray),
+            )),
+        }
+        let title = self.last_refresh.map_or("Status".to_string(), |time| {
+            format!("Status (refreshed at {})", format_timestamp(time as i64))
+        });
         frame.render_widget(
-            Block::default()
-                .borders(ratatui::widgets::Borders::ALL)
-                .title("Progress Bar"),
-            progress_bar,
+            Paragraph::new(Line::from(spans)).block(bordered_block(&title)),
+            status_area,
         );
+
+        if let Some(popup) = &self.popup {
+            popup.render(frame, body_area);
+        }
     }
 }
@@ -14,4 +14,76 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+pub(crate) mod cluster_view;
+pub(crate) mod group_view;
+pub(crate) mod message_view;
+pub(crate) mod reset_offset_popup;
 pub(crate) mod search_input_widget;
+pub(crate) mod topic_view;
+
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+
+/// Moves a selection over `len` rows by one, wrapping around at both ends.
+pub(crate) fn step_selection(selected: Option<usize>, len: usize, forward: bool) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    Some(match selected {
+        None => 0,
+        Some(index) if forward => (index + 1) % len,
+        Some(index) => (index + len - 1) % len,
+    })
+}
+
+/// Keeps a selection inside `len` rows, selecting the first row if nothing was selected.
+pub(crate) fn clamp_selection(selected: Option<usize>, len: usize) -> Option<usize> {
+    if len == 0 {
+        None
+    } else {
+        Some(selected.unwrap_or(0).min(len - 1))
+    }
+}
+
+pub(crate) fn bordered_block(title: &str) -> Block<'_> {
+    Block::default().borders(Borders::ALL).title(title)
+}
+
+pub(crate) fn header_style() -> Style {
+    Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD)
+}
+
+pub(crate) fn highlight_style() -> Style {
+    Style::default()
+        .fg(Color::Black)
+        .bg(Color::Cyan)
+        .add_modifier(Modifier::BOLD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_selection_wraps_around() {
+        assert_eq!(step_selection(None, 3, true), Some(0));
+        assert_eq!(step_selection(None, 3, false), Some(0));
+        assert_eq!(step_selection(Some(0), 3, true), Some(1));
+        assert_eq!(step_selection(Some(2), 3, true), Some(0));
+        assert_eq!(step_selection(Some(0), 3, false), Some(2));
+        assert_eq!(step_selection(Some(1), 0, true), None);
+    }
+
+    #[test]
+    fn clamp_selection_keeps_the_selection_in_range() {
+        assert_eq!(clamp_selection(None, 3), Some(0));
+        assert_eq!(clamp_selection(Some(1), 3), Some(1));
+        assert_eq!(clamp_selection(Some(5), 3), Some(2));
+        assert_eq!(clamp_selection(Some(1), 0), None);
+    }
+}
@@ -0,0 +1,179 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use ratatui::layout::Constraint;
+use ratatui::layout::Layout;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::List;
+use ratatui::widgets::ListItem;
+use ratatui::widgets::ListState;
+use ratatui::widgets::Row;
+use ratatui::widgets::Table;
+use ratatui::widgets::TableState;
+use ratatui::Frame;
+
+use crate::fetcher::BrokerNode;
+use crate::ui::bordered_block;
+use crate::ui::clamp_selection;
+use crate::ui::header_style;
+use crate::ui::highlight_style;
+use crate::ui::step_selection;
+
+/// The cluster tab: a cluster/broker tree, the load of every broker and the runtime info of the
+/// selected one.
+#[derive(Default)]
+pub(crate) struct ClusterView {
+    brokers: Vec<BrokerNode>,
+    state: TableState,
+}
+
+impl ClusterView {
+    pub fn set_brokers(&mut self, brokers: Vec<BrokerNode>) {
+        self.brokers = brokers;
+        self.state
+            .select(clamp_selection(self.state.selected(), self.brokers.len()));
+    }
+
+    pub fn select_next(&mut self) {
+        self.state.select(step_selection(
+            self.state.selected(),
+            self.brokers.len(),
+            true,
+        ));
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select(step_selection(
+            self.state.selected(),
+            self.brokers.len(),
+            false,
+        ));
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let [tree_area, right_area] =
+            Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)])
+                .areas(area);
+        let [brokers_area, runtime_area] =
+            Layout::vertical([Constraint::Percentage(45), Constraint::Percentage(55)])
+                .areas(right_area);
+
+        self.render_tree(frame, tree_area);
+
+        let rows = self.brokers.iter().map(|broker| {
+            Row::new(vec![
+                broker.cluster.clone(),
+                broker.broker_name.clone(),
+                broker.broker_id.to_string(),
+                broker.addr.clone(),
+                broker.version.clone(),
+                broker.put_tps.clone(),
+                broker.get_tps.clone(),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(2),
+                Constraint::Fill(2),
+                Constraint::Length(4),
+                Constraint::Fill(3),
+                Constraint::Fill(2),
+                Constraint::Length(10),
+                Constraint::Length(10),
+            ],
+        )
+        .header(
+            Row::new(vec![
+                "Cluster", "Broker", "Id", "Address", "Version", "Put TPS", "Get TPS",
+            ])
+            .style(header_style()),
+        )
+        .block(bordered_block("Brokers"))
+        .row_highlight_style(highlight_style());
+        frame.render_stateful_widget(table, brokers_area, &mut self.state);
+
+        let selected = self
+            .state
+            .selected()
+            .and_then(|index| self.brokers.get(index));
+        let title = selected.map_or("Runtime Info".to_string(), |broker| {
+            format!("Runtime Info of {}", broker.addr)
+        });
+        let rows = selected
+            .map(|broker| {
+                broker
+                    .runtime_info
+                    .iter()
+                    .map(|(key, value)| Row::new(vec![key.clone(), value.clone()]))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let table = Table::new(
+            rows,
+            [Constraint::Percentage(40), Constraint::Percentage(60)],
+        )
+        .header(Row::new(vec!["Key", "Value"]).style(header_style()))
+        .block(bordered_block(&title));
+        frame.render_widget(table, runtime_area);
+    }
+
+    fn render_tree(&self, frame: &mut Frame, area: Rect) {
+        let selected = self.state.selected();
+        let mut items = Vec::new();
+        let mut selected_item = None;
+        for (index, broker) in self.brokers.iter().enumerate() {
+            let previous = index.checked_sub(1).map(|index| &self.brokers[index]);
+            if previous.map_or(true, |previous| previous.cluster != broker.cluster) {
+                items.push(ListItem::new(Line::from(Span::styled(
+                    format!("▾ {}", broker.cluster),
+                    header_style(),
+                ))));
+            }
+            if previous.map_or(true, |previous| {
+                previous.cluster != broker.cluster || previous.broker_name != broker.broker_name
+            }) {
+                items.push(ListItem::new(format!("  ▾ {}", broker.broker_name)));
+            }
+            if selected == Some(index) {
+                selected_item = Some(items.len());
+            }
+            let role = if broker.broker_id == 0 {
+                "MASTER"
+            } else {
+                "SLAVE"
+            };
+            let style = if broker.runtime_info.is_empty() {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default().fg(Color::Green)
+            };
+            items.push(ListItem::new(Line::from(vec![
+                Span::raw(format!("      {} ", broker.addr)),
+                Span::styled(format!("[{}:{}]", role, broker.broker_id), style),
+            ])));
+        }
+        let mut state = ListState::default().with_selected(selected_item);
+        let list = List::new(items)
+            .block(bordered_block("Clusters"))
+            .highlight_style(highlight_style());
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+}
@@ -0,0 +1,323 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use ratatui::layout::Constraint;
+use ratatui::layout::Layout;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::List;
+use ratatui::widgets::ListItem;
+use ratatui::widgets::ListState;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::Row;
+use ratatui::widgets::Table;
+use ratatui::Frame;
+use rocketmq_common::common::mix_all;
+
+use crate::fetcher::GroupDetail;
+use crate::ui::bordered_block;
+use crate::ui::clamp_selection;
+use crate::ui::header_style;
+use crate::ui::highlight_style;
+use crate::ui::step_selection;
+
+/// The consumer groups tab: a filterable group list and the online clients and per queue lag of
+/// the selected group.
+#[derive(Default)]
+pub(crate) struct GroupView {
+    groups: Vec<String>,
+    filter: String,
+    state: ListState,
+    detail: Option<(String, GroupDetail)>,
+    error: Option<String>,
+}
+
+impl GroupView {
+    /// Derives the groups from the retry topics, every consumer group subscribes its retry topic.
+    pub fn set_topics(&mut self, topics: &[String]) {
+        let selected = self.selected().map(str::to_string);
+        let mut groups: Vec<_> = topics
+            .iter()
+            .filter_map(|topic| topic.strip_prefix(mix_all::RETRY_GROUP_TOPIC_PREFIX))
+            .map(str::to_string)
+            .collect();
+        groups.sort();
+        groups.dedup();
+        self.groups = groups;
+        self.reselect(selected.as_deref());
+    }
+
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    pub fn set_filter(&mut self, filter: &str) {
+        if self.filter == filter {
+            return;
+        }
+        let selected = self.selected().map(str::to_string);
+        self.filter = filter.to_string();
+        self.reselect(selected.as_deref());
+    }
+
+    pub fn selected(&self) -> Option<&str> {
+        let visible = self.visible();
+        self.state
+            .selected()
+            .and_then(|index| visible.get(index).copied())
+    }
+
+    /// The detail of the selected group, if it has been fetched.
+    pub fn selected_detail(&self) -> Option<&GroupDetail> {
+        let selected = self.selected()?;
+        self.detail
+            .as_ref()
+            .filter(|(group, _)| group == selected)
+            .map(|(_, detail)| detail)
+    }
+
+    pub fn select_next(&mut self) {
+        let len = self.visible().len();
+        self.state
+            .select(step_selection(self.state.selected(), len, true));
+    }
+
+    pub fn select_previous(&mut self) {
+        let len = self.visible().len();
+        self.state
+            .select(step_selection(self.state.selected(), len, false));
+    }
+
+    pub fn set_detail(&mut self, group: String, detail: Result<GroupDetail, String>) {
+        match detail {
+            Ok(detail) => {
+                self.detail = Some((group, detail));
+                self.error = None;
+            }
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    fn visible(&self) -> Vec<&str> {
+        let filter = self.filter.to_lowercase();
+        self.groups
+            .iter()
+            .filter(|group| group.to_lowercase().contains(&filter))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Selects `group` again after the list changed, or keeps the selection in range.
+    fn reselect(&mut self, group: Option<&str>) {
+        let visible = self.visible();
+        let index = group
+            .and_then(|group| visible.iter().position(|name| *name == group))
+            .or(self.state.selected());
+        let index = clamp_selection(index, visible.len());
+        self.state.select(index);
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let [list_area, detail_area] =
+            Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)])
+                .areas(area);
+
+        let visible = self.visible();
+        let title = format!("Consumer Groups ({}/{})", visible.len(), self.groups.len());
+        let items: Vec<_> = visible
+            .iter()
+            .map(|group| ListItem::new(group.to_string()))
+            .collect();
+        let list = List::new(items)
+            .block(bordered_block(&title))
+            .highlight_style(highlight_style());
+        frame.render_stateful_widget(list, list_area, &mut self.state);
+
+        self.render_detail(frame, detail_area);
+    }
+
+    fn render_detail(&self, frame: &mut Frame, area: Rect) {
+        let [summary_area, clients_area, lag_area] = Layout::vertical([
+            Constraint::Length(5),
+            Constraint::Percentage(30),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+
+        let selected = self.selected();
+        let detail = self.selected_detail();
+
+        let mut summary = Vec::new();
+        if let Some(error) = &self.error {
+            summary.push(Line::from(Span::styled(
+                error.as_str(),
+                Style::default().fg(Color::Red),
+            )));
+        }
+        match detail {
+            Some(detail) => {
+                summary.push(Line::from(format!(
+                    "Consume TPS: {:.1}    Diff Total: {}",
+                    detail.consume_tps, detail.diff_total
+                )));
+                let state = if detail.clients.is_empty() {
+                    Span::styled("OFFLINE", Style::default().fg(Color::Red))
+                } else {
+                    Span::styled("ONLINE", Style::default().fg(Color::Green))
+                };
+                summary.push(Line::from(vec![
+                    state,
+                    Span::raw(format!(
+                        "    Consume Type: {}    Message Model: {}",
+                        detail.consume_type, detail.message_model
+                    )),
+                ]));
+            }
+            None if selected.is_some() => summary.push(Line::from("Loading...")),
+            None => summary.push(Line::from("No group selected")),
+        }
+        let title = selected.map_or("Consumer Group".to_string(), |group| {
+            format!("{} [Press o to reset offset]", group)
+        });
+        frame.render_widget(
+            Paragraph::new(summary).block(bordered_block(&title)),
+            summary_area,
+        );
+
+        let clients = detail.map_or(Vec::new(), |detail| {
+            detail
+                .clients
+                .iter()
+                .map(|client| {
+                    Row::new(vec![
+                        client.client_id.clone(),
+                        client.client_addr.clone(),
+                        client.language.clone(),
+                        client.version.to_string(),
+                    ])
+                })
+                .collect()
+        });
+        let table = Table::new(
+            clients,
+            [
+                Constraint::Fill(3),
+                Constraint::Fill(2),
+                Constraint::Length(10),
+                Constraint::Length(8),
+            ],
+        )
+        .header(Row::new(vec!["Client Id", "Address", "Language", "Version"]).style(header_style()))
+        .block(bordered_block("Online Clients"));
+        frame.render_widget(table, clients_area);
+
+        let queues = detail.map_or(Vec::new(), |detail| {
+            detail
+                .queues
+                .iter()
+                .map(|queue| {
+                    let lag_style = if queue.lag > 0 {
+                        Style::default().fg(Color::Yellow)
+                    } else {
+                        Style::default()
+                    };
+                    Row::new(vec![
+                        Line::from(queue.topic.as_str()),
+                        Line::from(queue.broker_name.as_str()),
+                        Line::from(queue.queue_id.to_string()),
+                        Line::from(queue.broker_offset.to_string()),
+                        Line::from(queue.consumer_offset.to_string()),
+                        Line::from(Span::styled(queue.lag.to_string(), lag_style)),
+                        Line::from(queue.last_timestamp.as_str()),
+                    ])
+                })
+                .collect()
+        });
+        let table = Table::new(
+            queues,
+            [
+                Constraint::Fill(3),
+                Constraint::Fill(2),
+                Constraint::Length(6),
+                Constraint::Length(14),
+                Constraint::Length(16),
+                Constraint::Length(10),
+                Constraint::Fill(2),
+            ],
+        )
+        .header(
+            Row::new(vec![
+                "Topic",
+                "Broker",
+                "Queue",
+                "Broker Offset",
+                "Consumer Offset",
+                "Lag",
+                "Last Consumed",
+            ])
+            .style(header_style()),
+        )
+        .block(bordered_block("Queue Lag"));
+        frame.render_widget(table, lag_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detail() -> GroupDetail {
+        GroupDetail {
+            consume_tps: 0.0,
+            diff_total: 0,
+            queues: vec![],
+            clients: vec![],
+            consume_type: String::new(),
+            message_model: String::new(),
+        }
+    }
+
+    #[test]
+    fn derives_groups_from_retry_topics() {
+        let mut view = GroupView::default();
+        let topics =
+            ["TopicA", "%RETRY%group_b", "%DLQ%group_b", "%RETRY%group_a"].map(str::to_string);
+        view.set_topics(&topics);
+        assert_eq!(view.visible(), ["group_a", "group_b"]);
+        assert_eq!(view.selected(), Some("group_a"));
+
+        view.select_previous();
+        assert_eq!(view.selected(), Some("group_b"));
+        view.set_filter("B");
+        assert_eq!(view.selected(), Some("group_b"));
+        view.set_filter("");
+        assert_eq!(view.selected(), Some("group_b"));
+    }
+
+    #[test]
+    fn only_returns_the_detail_of_the_selected_group() {
+        let mut view = GroupView::default();
+        view.set_topics(&["%RETRY%group_a".to_string(), "%RETRY%group_b".to_string()]);
+        view.set_detail("group_b".to_string(), Ok(detail()));
+        assert!(view.selected_detail().is_none());
+
+        view.select_next();
+        assert!(view.selected_detail().is_some());
+    }
+}
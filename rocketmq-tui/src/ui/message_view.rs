@@ -0,0 +1,224 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use ratatui::layout::Constraint;
+use ratatui::layout::Layout;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::List;
+use ratatui::widgets::ListItem;
+use ratatui::widgets::ListState;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::Wrap;
+use ratatui::Frame;
+
+use crate::fetcher::MessageEntry;
+use crate::ui::bordered_block;
+use crate::ui::header_style;
+use crate::ui::highlight_style;
+use crate::ui::step_selection;
+
+/// The messages tab: the result of a search by message id or key and the selected message.
+#[derive(Default)]
+pub(crate) struct MessageView {
+    query: Option<String>,
+    messages: Vec<MessageEntry>,
+    state: ListState,
+    error: Option<String>,
+}
+
+impl MessageView {
+    pub fn set_query(&mut self, query: String) {
+        self.query = Some(query);
+        self.messages.clear();
+        self.state.select(None);
+        self.error = None;
+    }
+
+    pub fn set_messages(&mut self, messages: Result<Vec<MessageEntry>, String>) {
+        match messages {
+            Ok(messages) => {
+                self.state
+                    .select(if messages.is_empty() { None } else { Some(0) });
+                self.messages = messages;
+                self.error = None;
+            }
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        self.state.select(step_selection(
+            self.state.selected(),
+            self.messages.len(),
+            true,
+        ));
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select(step_selection(
+            self.state.selected(),
+            self.messages.len(),
+            false,
+        ));
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let [list_area, detail_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(area);
+
+        let title = match &self.query {
+            Some(query) => format!("Results of '{}' ({})", query, self.messages.len()),
+            None => "Results".to_string(),
+        };
+        let items: Vec<_> = self
+            .messages
+            .iter()
+            .map(|message| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{} ", message.store_timestamp),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::raw(message.unique_msg_id.as_str()),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(bordered_block(&title))
+            .highlight_style(highlight_style());
+        frame.render_stateful_widget(list, list_area, &mut self.state);
+
+        let lines = match (&self.error, self.selected()) {
+            (Some(error), _) => vec![Line::from(Span::styled(
+                error.as_str(),
+                Style::default().fg(Color::Red),
+            ))],
+            (None, Some(message)) => message_lines(message),
+            (None, None) if self.query.is_some() && self.messages.is_empty() => {
+                vec![Line::from("No message found")]
+            }
+            (None, None) => vec![Line::from(
+                "Press s/S and type '<topic> <message id or key>', then press Enter",
+            )],
+        };
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(bordered_block("Message"))
+                .wrap(Wrap { trim: false }),
+            detail_area,
+        );
+    }
+
+    fn selected(&self) -> Option<&MessageEntry> {
+        self.state
+            .selected()
+            .and_then(|index| self.messages.get(index))
+    }
+}
+
+fn message_lines(message: &MessageEntry) -> Vec<Line<'_>> {
+    let field = |name: &'static str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{:<16}", name), header_style()),
+            Span::raw(value),
+        ])
+    };
+    let mut lines = vec![
+        field("Topic", message.topic.clone()),
+        field("Msg Id", message.unique_msg_id.clone()),
+        field("Offset Msg Id", message.offset_msg_id.clone()),
+        field("Store Host", message.store_host.clone()),
+        field("Queue Id", message.queue_id.to_string()),
+        field("Queue Offset", message.queue_offset.to_string()),
+        field("Tags", message.tags.clone()),
+        field("Keys", message.keys.clone()),
+        field("Born Time", message.born_timestamp.clone()),
+        field("Store Time", message.store_timestamp.clone()),
+        field("Reconsume Times", message.reconsume_times.to_string()),
+        Line::from(""),
+        Line::from(Span::styled("Properties", header_style())),
+    ];
+    lines.extend(
+        message
+            .properties
+            .iter()
+            .map(|(key, value)| Line::from(format!("  {}={}", key, value))),
+    );
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("Body", header_style())));
+    lines.extend(message.body.lines().map(Line::from));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(count: usize) -> Vec<MessageEntry> {
+        (0..count)
+            .map(|index| MessageEntry {
+                topic: "TopicTest".to_string(),
+                offset_msg_id: String::new(),
+                unique_msg_id: String::new(),
+                store_host: String::new(),
+                queue_id: 0,
+                queue_offset: index as i64,
+                tags: String::new(),
+                keys: String::new(),
+                born_timestamp: String::new(),
+                store_timestamp: String::new(),
+                reconsume_times: 0,
+                properties: vec![],
+                body: String::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn selects_the_first_result_and_wraps_around() {
+        let mut view = MessageView::default();
+        view.set_query("TopicTest key".to_string());
+        view.set_messages(Ok(messages(2)));
+        assert_eq!(view.state.selected(), Some(0));
+        view.select_next();
+        assert_eq!(view.selected().map(|message| message.queue_offset), Some(1));
+        view.select_next();
+        assert_eq!(view.state.selected(), Some(0));
+        view.select_previous();
+        assert_eq!(view.state.selected(), Some(1));
+    }
+
+    #[test]
+    fn a_new_query_clears_the_previous_results() {
+        let mut view = MessageView::default();
+        view.set_query("TopicTest key".to_string());
+        view.set_messages(Ok(messages(1)));
+        view.set_query("TopicTest other".to_string());
+        assert!(view.messages.is_empty());
+        assert_eq!(view.state.selected(), None);
+
+        view.set_messages(Err("not found".to_string()));
+        assert_eq!(view.error.as_deref(), Some("not found"));
+        view.set_messages(Ok(vec![]));
+        assert_eq!(view.state.selected(), None);
+        assert_eq!(view.error, None);
+    }
+}
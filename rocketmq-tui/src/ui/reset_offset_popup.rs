@@ -0,0 +1,244 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use crossterm::event::KeyCode;
+use ratatui::layout::Constraint;
+use ratatui::layout::Flex;
+use ratatui::layout::Layout;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+use rocketmq_common::TimeUtils::get_current_millis;
+
+use crate::fetcher::Request;
+use crate::ui::bordered_block;
+use crate::ui::header_style;
+use crate::ui::highlight_style;
+
+const HOUR_MILLIS: u64 = 60 * 60 * 1000;
+
+/// Where the consumer offset is moved to.
+#[derive(Debug, Clone, Copy)]
+enum ResetTarget {
+    Latest,
+    Earliest,
+    HourAgo,
+    DayAgo,
+}
+
+impl ResetTarget {
+    const ALL: [ResetTarget; 4] = [
+        ResetTarget::Latest,
+        ResetTarget::Earliest,
+        ResetTarget::HourAgo,
+        ResetTarget::DayAgo,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            ResetTarget::Latest => "Latest (skip all messages)",
+            ResetTarget::Earliest => "Earliest (consume all messages again)",
+            ResetTarget::HourAgo => "1 hour ago",
+            ResetTarget::DayAgo => "1 day ago",
+        }
+    }
+
+    fn timestamp(self) -> u64 {
+        let now = get_current_millis();
+        match self {
+            ResetTarget::Latest => now,
+            ResetTarget::Earliest => 0,
+            ResetTarget::HourAgo => now.saturating_sub(HOUR_MILLIS),
+            ResetTarget::DayAgo => now.saturating_sub(24 * HOUR_MILLIS),
+        }
+    }
+}
+
+/// What the popup wants the app to do after a key press.
+pub(crate) enum PopupOutcome {
+    Pending,
+    Cancelled,
+    Confirmed(Request),
+}
+
+/// Asks for the topic and the target of a consumer offset reset and a confirmation.
+pub(crate) struct ResetOffsetPopup {
+    group: String,
+    topics: Vec<String>,
+    topic_index: usize,
+    target_index: usize,
+}
+
+impl ResetOffsetPopup {
+    pub fn new(group: String, topics: Vec<String>) -> Self {
+        Self {
+            group,
+            topics,
+            topic_index: 0,
+            target_index: 0,
+        }
+    }
+
+    pub fn handle_key_event(&mut self, key: KeyCode) -> PopupOutcome {
+        let targets = ResetTarget::ALL.len();
+        match key {
+            KeyCode::Left | KeyCode::Char('h') if !self.topics.is_empty() => {
+                self.topic_index = (self.topic_index + self.topics.len() - 1) % self.topics.len();
+            }
+            KeyCode::Right | KeyCode::Char('l') if !self.topics.is_empty() => {
+                self.topic_index = (self.topic_index + 1) % self.topics.len();
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.target_index = (self.target_index + targets - 1) % targets;
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.target_index = (self.target_index + 1) % targets;
+            }
+            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                return match self.topics.get(self.topic_index) {
+                    Some(topic) => PopupOutcome::Confirmed(Request::ResetOffset {
+                        group: self.group.clone(),
+                        topic: topic.clone(),
+                        timestamp: ResetTarget::ALL[self.target_index].timestamp(),
+                    }),
+                    None => PopupOutcome::Cancelled,
+                };
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                return PopupOutcome::Cancelled;
+            }
+            _ => {}
+        }
+        PopupOutcome::Pending
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let height = ResetTarget::ALL.len() as u16 + 8;
+        let [area] = Layout::vertical([Constraint::Length(height)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::horizontal([Constraint::Percentage(60)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        let topic = self
+            .topics
+            .get(self.topic_index)
+            .map_or("<no subscribed topic>", String::as_str);
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("Group: ", header_style()),
+                Span::raw(self.group.as_str()),
+            ]),
+            Line::from(vec![
+                Span::styled("Topic: ", header_style()),
+                Span::raw(format!("◀ {} ▶", topic)),
+            ]),
+            Line::from(""),
+        ];
+        lines.extend(ResetTarget::ALL.iter().enumerate().map(|(index, target)| {
+            if index == self.target_index {
+                Line::from(Span::styled(
+                    format!("> {}", target.label()),
+                    highlight_style(),
+                ))
+            } else {
+                Line::from(format!("  {}", target.label()))
+            }
+        }));
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "←/→ topic  ↑/↓ target  y/Enter confirm  n/Esc cancel",
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                bordered_block("Reset Consumer Offset")
+                    .border_style(Style::default().fg(Color::Red)),
+            ),
+            area,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn popup(topics: &[&str]) -> ResetOffsetPopup {
+        ResetOffsetPopup::new(
+            "group".to_string(),
+            topics.iter().map(|topic| topic.to_string()).collect(),
+        )
+    }
+
+    fn confirm(popup: &mut ResetOffsetPopup) -> Option<Request> {
+        match popup.handle_key_event(KeyCode::Enter) {
+            PopupOutcome::Confirmed(request) => Some(request),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn confirms_the_chosen_topic_and_target() {
+        let mut popup = popup(&["TopicA", "TopicB"]);
+        assert!(matches!(
+            popup.handle_key_event(KeyCode::Left),
+            PopupOutcome::Pending
+        ));
+        popup.handle_key_event(KeyCode::Down);
+        assert_eq!(
+            confirm(&mut popup),
+            Some(Request::ResetOffset {
+                group: "group".to_string(),
+                topic: "TopicB".to_string(),
+                timestamp: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn moves_the_target_back_in_time() {
+        let mut popup = popup(&["TopicA"]);
+        popup.handle_key_event(KeyCode::Up);
+        let Some(Request::ResetOffset { timestamp, .. }) = confirm(&mut popup) else {
+            panic!("the reset was not confirmed");
+        };
+        let day_ago = get_current_millis() - 24 * HOUR_MILLIS;
+        assert!(timestamp <= day_ago && timestamp + 60_000 > day_ago);
+    }
+
+    #[test]
+    fn cancels_without_a_topic_or_on_request() {
+        let mut popup_without_topics = popup(&[]);
+        popup_without_topics.handle_key_event(KeyCode::Right);
+        assert!(matches!(
+            popup_without_topics.handle_key_event(KeyCode::Enter),
+            PopupOutcome::Cancelled
+        ));
+        assert!(matches!(
+            popup(&["TopicA"]).handle_key_event(KeyCode::Esc),
+            PopupOutcome::Cancelled
+        ));
+    }
+}
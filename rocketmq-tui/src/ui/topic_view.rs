@@ -0,0 +1,367 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::time::Instant;
+
+use ratatui::layout::Constraint;
+use ratatui::layout::Layout;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::List;
+use ratatui::widgets::ListItem;
+use ratatui::widgets::ListState;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::Row;
+use ratatui::widgets::Table;
+use ratatui::Frame;
+use rocketmq_common::common::mix_all;
+use rocketmq_common::common::topic::TopicValidator;
+
+use crate::fetcher::TopicDetail;
+use crate::ui::bordered_block;
+use crate::ui::clamp_selection;
+use crate::ui::header_style;
+use crate::ui::highlight_style;
+use crate::ui::step_selection;
+
+/// The topics tab: a filterable topic list and the routes, queue offsets and TPS of the selected
+/// topic.
+#[derive(Default)]
+pub(crate) struct TopicView {
+    topics: Vec<String>,
+    filter: String,
+    state: ListState,
+    detail: Option<(String, TopicDetail)>,
+    error: Option<String>,
+    tps: Option<f64>,
+    /// The last max offset sum of a topic and when it was fetched.
+    sample: Option<(String, i64, Instant)>,
+}
+
+impl TopicView {
+    pub fn set_topics(&mut self, mut topics: Vec<String>) {
+        let selected = self.selected().map(str::to_string);
+        topics.sort();
+        self.topics = topics;
+        self.reselect(selected.as_deref());
+    }
+
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    pub fn set_filter(&mut self, filter: &str) {
+        if self.filter == filter {
+            return;
+        }
+        let selected = self.selected().map(str::to_string);
+        self.filter = filter.to_string();
+        self.reselect(selected.as_deref());
+    }
+
+    pub fn selected(&self) -> Option<&str> {
+        let visible = self.visible();
+        self.state
+            .selected()
+            .and_then(|index| visible.get(index).copied())
+    }
+
+    pub fn select_next(&mut self) {
+        let len = self.visible().len();
+        self.state
+            .select(step_selection(self.state.selected(), len, true));
+    }
+
+    pub fn select_previous(&mut self) {
+        let len = self.visible().len();
+        self.state
+            .select(step_selection(self.state.selected(), len, false));
+    }
+
+    pub fn set_detail(&mut self, topic: String, detail: Result<TopicDetail, String>) {
+        match detail {
+            Ok(detail) => {
+                let now = Instant::now();
+                let max_offset_sum = detail.max_offset_sum();
+                self.tps = match &self.sample {
+                    Some((sampled, previous, at)) if *sampled == topic => {
+                        let elapsed = now.duration_since(*at).as_secs_f64();
+                        (elapsed > 0.0)
+                            .then(|| (max_offset_sum - previous).max(0) as f64 / elapsed)
+                            .or(self.tps)
+                    }
+                    _ => None,
+                };
+                self.sample = Some((topic.clone(), max_offset_sum, now));
+                self.detail = Some((topic, detail));
+                self.error = None;
+            }
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    fn visible(&self) -> Vec<&str> {
+        let filter = self.filter.to_lowercase();
+        self.topics
+            .iter()
+            .filter(|topic| topic.to_lowercase().contains(&filter))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Selects `topic` again after the list changed, or keeps the selection in range.
+    fn reselect(&mut self, topic: Option<&str>) {
+        let visible = self.visible();
+        let index = topic
+            .and_then(|topic| visible.iter().position(|name| *name == topic))
+            .or(self.state.selected());
+        let index = clamp_selection(index, visible.len());
+        self.state.select(index);
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let [list_area, detail_area] =
+            Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)])
+                .areas(area);
+
+        let visible = self.visible();
+        let title = format!("Topics ({}/{})", visible.len(), self.topics.len());
+        let items: Vec<_> = visible
+            .iter()
+            .map(|topic| {
+                let (kind, color) = topic_kind(topic);
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{:<7}", kind), Style::default().fg(color)),
+                    Span::raw(topic.to_string()),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(bordered_block(&title))
+            .highlight_style(highlight_style());
+        frame.render_stateful_widget(list, list_area, &mut self.state);
+
+        self.render_detail(frame, detail_area);
+    }
+
+    fn render_detail(&self, frame: &mut Frame, area: Rect) {
+        let [summary_area, routes_area, queues_area] = Layout::vertical([
+            Constraint::Length(5),
+            Constraint::Percentage(35),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+
+        let selected = self.selected();
+        let detail = self
+            .detail
+            .as_ref()
+            .filter(|(topic, _)| Some(topic.as_str()) == selected)
+            .map(|(_, detail)| detail);
+
+        let mut summary = Vec::new();
+        if let Some(error) = &self.error {
+            summary.push(Line::from(Span::styled(
+                error.as_str(),
+                Style::default().fg(Color::Red),
+            )));
+        }
+        match detail {
+            Some(detail) => {
+                let tps = self
+                    .tps
+                    .map_or("-".to_string(), |tps| format!("{:.1}", tps));
+                summary.push(Line::from(format!(
+                    "Put TPS: {}    Messages: {}    Queues: {}",
+                    tps,
+                    detail.message_count(),
+                    detail.queues.len()
+                )));
+                let groups = if detail.consumer_groups.is_empty() {
+                    "-".to_string()
+                } else {
+                    detail.consumer_groups.join(", ")
+                };
+                summary.push(Line::from(format!("Consumer groups: {}", groups)));
+            }
+            None if selected.is_some() => summary.push(Line::from("Loading...")),
+            None => summary.push(Line::from("No topic selected")),
+        }
+        let title = selected.unwrap_or("Topic");
+        frame.render_widget(
+            Paragraph::new(summary).block(bordered_block(title)),
+            summary_area,
+        );
+
+        let routes = detail.map_or(Vec::new(), |detail| {
+            detail
+                .routes
+                .iter()
+                .map(|route| {
+                    Row::new(vec![
+                        route.broker_name.clone(),
+                        route.master_addr.clone(),
+                        route.read_queue_nums.to_string(),
+                        route.write_queue_nums.to_string(),
+                        route.perm.clone(),
+                    ])
+                })
+                .collect()
+        });
+        let table = Table::new(
+            routes,
+            [
+                Constraint::Fill(2),
+                Constraint::Fill(3),
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Length(6),
+            ],
+        )
+        .header(Row::new(vec!["Broker", "Master", "Read", "Write", "Perm"]).style(header_style()))
+        .block(bordered_block("Routes"));
+        frame.render_widget(table, routes_area);
+
+        let queues = detail.map_or(Vec::new(), |detail| {
+            detail
+                .queues
+                .iter()
+                .map(|queue| {
+                    Row::new(vec![
+                        queue.broker_name.clone(),
+                        queue.queue_id.to_string(),
+                        queue.min_offset.to_string(),
+                        queue.max_offset.to_string(),
+                        queue.last_update.clone(),
+                    ])
+                })
+                .collect()
+        });
+        let table = Table::new(
+            queues,
+            [
+                Constraint::Fill(2),
+                Constraint::Length(6),
+                Constraint::Length(12),
+                Constraint::Length(12),
+                Constraint::Fill(3),
+            ],
+        )
+        .header(
+            Row::new(vec![
+                "Broker",
+                "Queue",
+                "Min Offset",
+                "Max Offset",
+                "Last Update",
+            ])
+            .style(header_style()),
+        )
+        .block(bordered_block("Queue Offsets"));
+        frame.render_widget(table, queues_area);
+    }
+}
+
+fn topic_kind(topic: &str) -> (&'static str, Color) {
+    if topic.starts_with(mix_all::RETRY_GROUP_TOPIC_PREFIX) {
+        ("RETRY", Color::Yellow)
+    } else if topic.starts_with(mix_all::DLQ_GROUP_TOPIC_PREFIX) {
+        ("DLQ", Color::Red)
+    } else if TopicValidator::is_system_topic(topic) {
+        ("SYSTEM", Color::Magenta)
+    } else {
+        ("NORMAL", Color::Green)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetcher::QueueOffsetRow;
+
+    fn topics(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn detail(max_offset: i64) -> TopicDetail {
+        TopicDetail {
+            routes: vec![],
+            queues: vec![QueueOffsetRow {
+                broker_name: "broker-a".to_string(),
+                queue_id: 0,
+                min_offset: 0,
+                max_offset,
+                last_update: String::new(),
+            }],
+            consumer_groups: vec![],
+        }
+    }
+
+    #[test]
+    fn keeps_the_selected_topic_when_the_list_changes() {
+        let mut view = TopicView::default();
+        view.set_topics(topics(&["TopicB", "TopicA"]));
+        assert_eq!(view.selected(), Some("TopicA"));
+        view.select_next();
+        assert_eq!(view.selected(), Some("TopicB"));
+
+        view.set_topics(topics(&["TopicC", "TopicB", "TopicA", "Other"]));
+        assert_eq!(view.selected(), Some("TopicB"));
+
+        // the selection stays in range when the selected topic is gone
+        view.set_topics(topics(&["TopicA"]));
+        assert_eq!(view.selected(), Some("TopicA"));
+        view.set_topics(vec![]);
+        assert_eq!(view.selected(), None);
+    }
+
+    #[test]
+    fn filters_topics_case_insensitively() {
+        let mut view = TopicView::default();
+        view.set_topics(topics(&["OrderTopic", "PayTopic", "orders"]));
+        view.set_filter("ORDER");
+        assert_eq!(view.visible(), ["OrderTopic", "orders"]);
+        view.select_previous();
+        assert_eq!(view.selected(), Some("orders"));
+
+        view.set_filter("pay");
+        assert_eq!(view.selected(), Some("PayTopic"));
+        view.set_filter("missing");
+        assert_eq!(view.selected(), None);
+        view.select_next();
+        assert_eq!(view.selected(), None);
+    }
+
+    #[test]
+    fn derives_tps_from_two_samples_of_the_same_topic() {
+        let mut view = TopicView::default();
+        view.set_detail("TopicA".to_string(), Ok(detail(100)));
+        assert_eq!(view.tps, None);
+        view.set_detail("TopicA".to_string(), Ok(detail(200)));
+        assert!(view.tps.is_some_and(|tps| tps > 0.0));
+
+        // a sample of another topic starts over
+        view.set_detail("TopicB".to_string(), Ok(detail(300)));
+        assert_eq!(view.tps, None);
+
+        view.set_detail("TopicB".to_string(), Err("timeout".to_string()));
+        assert_eq!(view.error.as_deref(), Some("timeout"));
+        assert!(view.detail.is_some());
+    }
+}